@@ -0,0 +1,396 @@
+//! Math of concentrated liquidity pools, in which liquidity is provided within a price range.
+//!
+//! Prices are expressed as the amount of the quote asset per unit of the base asset, and are
+//! discretized into ticks such that `price(tick) = 1.0001^tick`. Square roots of prices are
+//! represented as unsigned Q64.64 fixed point numbers.
+//!
+//! From https://uniswap.org/whitepaper-v3.pdf
+
+use frame_support::ensure;
+use sp_core::{U256, U512};
+use sp_runtime::{ArithmeticError, DispatchError, FixedPointNumber, FixedU128, PerThing};
+
+/// Lowest tick a position can be bounded by.
+pub const MIN_TICK: i32 = -443_636;
+/// Highest tick a position can be bounded by.
+pub const MAX_TICK: i32 = 443_636;
+/// Number of fractional bits of the Q64.64 square root prices.
+pub const RESOLUTION: u8 = 64;
+
+/// `1 / sqrt(1.0001)^(2^i)` as Q128.128 numbers, for `i` in `0..19`.
+const TICK_RATIOS: [u128; 19] = [
+	0xfffcb933bd6fad37aa2d162d1a594001,
+	0xfff97272373d413259a46990580e2139,
+	0xfff2e50f5f656932ef12357cf3c7fdcb,
+	0xffe5caca7e10e4e61c3624eaa0941ccf,
+	0xffcb9843d60f6159c9db58835c926643,
+	0xff973b41fa98c081472e6896dfb254bf,
+	0xff2ea16466c96a3843ec78b326b52860,
+	0xfe5dee046a99a2a811c461f1969c3052,
+	0xfcbe86c7900a88aedcffc83b479aa3a3,
+	0xf987a7253ac413176f2b074cf7815e53,
+	0xf3392b0822b70005940c7a398e4b70f2,
+	0xe7159475a2c29b7443b29c7fa6e889d8,
+	0xd097f3bdfd2022b8845ad8f792aa5825,
+	0xa9f746462d870fdf8a65dc1f90e061e4,
+	0x70d869a156d2a1b890bb3df62baf32f6,
+	0x31be135f97d08fd981231505542fcfa5,
+	0x09aa508b5b7a84e1c677de54f3e99bc8,
+	0x005d6af8dedb81196699c329225ee604,
+	0x00002216e584f5fa1ea926041bedfe97,
+];
+
+pub type ConcentratedLiquidityResult<T> = Result<T, ConcentratedLiquidityError>;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ConcentratedLiquidityError {
+	ArithmeticError(ArithmeticError),
+	/// The tick is outside of `MIN_TICK..=MAX_TICK`.
+	TickOutOfBounds,
+	/// The square root price is outside of the range covered by the ticks.
+	SqrtPriceOutOfBounds,
+	/// Not enough liquidity to move the price by the requested amount.
+	NotEnoughLiquidity,
+}
+
+impl From<ArithmeticError> for ConcentratedLiquidityError {
+	fn from(error: ArithmeticError) -> Self {
+		ConcentratedLiquidityError::ArithmeticError(error)
+	}
+}
+
+impl From<ConcentratedLiquidityError> for DispatchError {
+	fn from(error: ConcentratedLiquidityError) -> Self {
+		match error {
+			ConcentratedLiquidityError::ArithmeticError(error) => DispatchError::from(error),
+			ConcentratedLiquidityError::TickOutOfBounds =>
+				DispatchError::from("Tick must be between `MIN_TICK` and `MAX_TICK`!"),
+			ConcentratedLiquidityError::SqrtPriceOutOfBounds => DispatchError::from(
+				"Price must be between the prices of `MIN_TICK` and `MAX_TICK`!",
+			),
+			ConcentratedLiquidityError::NotEnoughLiquidity =>
+				DispatchError::from("Not enough liquidity to move the price that far!"),
+		}
+	}
+}
+
+/// Result of a swap within a single tick range.
+#[derive(Debug, Eq, PartialEq)]
+pub struct SwapStep {
+	/// Square root price after the step.
+	pub sqrt_price: u128,
+	/// Amount of the input asset taken by the step, excluding the fee.
+	pub amount_in: u128,
+	/// Amount of the output asset given by the step.
+	pub amount_out: u128,
+	/// Fee charged on the input asset.
+	pub fee: u128,
+}
+
+/// Computes `a * b / c` without intermediate overflow.
+fn mul_div(a: U256, b: U256, c: U256, round_up: bool) -> Result<U256, ArithmeticError> {
+	ensure!(!c.is_zero(), ArithmeticError::DivisionByZero);
+	let (quotient, remainder) = a.full_mul(b).div_mod(U512::from(c));
+	let quotient = if round_up && !remainder.is_zero() {
+		quotient.checked_add(U512::one()).ok_or(ArithmeticError::Overflow)?
+	} else {
+		quotient
+	};
+	U256::try_from(quotient).map_err(|_| ArithmeticError::Overflow)
+}
+
+fn to_u128(a: U256) -> Result<u128, ArithmeticError> {
+	ensure!(a <= U256::from(u128::MAX), ArithmeticError::Overflow);
+	Ok(a.low_u128())
+}
+
+/// `1` as a Q64.64 number.
+fn q64() -> U256 {
+	U256::one() << RESOLUTION
+}
+
+/// Square root price at the lower bound of `tick`, as a Q64.64 number.
+pub fn sqrt_price_at_tick(tick: i32) -> ConcentratedLiquidityResult<u128> {
+	ensure!((MIN_TICK..=MAX_TICK).contains(&tick), ConcentratedLiquidityError::TickOutOfBounds);
+	let abs_tick = tick.unsigned_abs();
+
+	// 1 / sqrt(1.0001)^|tick|, as a Q128.128 number
+	let ratio = TICK_RATIOS.iter().enumerate().fold(U256::one() << 128, |ratio, (bit, factor)| {
+		if abs_tick & (1 << bit) != 0 {
+			(ratio * U256::from(*factor)) >> 128
+		} else {
+			ratio
+		}
+	});
+	let ratio = if tick > 0 { U256::MAX / ratio } else { ratio };
+
+	// round up to make sure that `tick_at_sqrt_price(sqrt_price_at_tick(tick)) == tick`
+	let sqrt_price =
+		(ratio >> RESOLUTION) + if (ratio % q64()).is_zero() { U256::zero() } else { U256::one() };
+	Ok(to_u128(sqrt_price)?)
+}
+
+/// Greatest tick whose square root price is lower than or equal to `sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> ConcentratedLiquidityResult<i32> {
+	ensure!(
+		sqrt_price >= sqrt_price_at_tick(MIN_TICK)? && sqrt_price <= sqrt_price_at_tick(MAX_TICK)?,
+		ConcentratedLiquidityError::SqrtPriceOutOfBounds
+	);
+
+	let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+	while low < high {
+		// bias upwards, so that the search always progresses
+		let middle = low + (high - low + 1) / 2;
+		if sqrt_price_at_tick(middle)? <= sqrt_price {
+			low = middle;
+		} else {
+			high = middle - 1;
+		}
+	}
+
+	Ok(low)
+}
+
+/// Price of the base asset, denominated in the quote asset.
+pub fn price_from_sqrt_price(sqrt_price: u128) -> ConcentratedLiquidityResult<FixedU128> {
+	let sqrt_price = U256::from(sqrt_price);
+	let price = mul_div(
+		sqrt_price * sqrt_price,
+		U256::from(FixedU128::DIV),
+		U256::one() << (2 * RESOLUTION),
+		false,
+	)?;
+	Ok(FixedU128::from_inner(to_u128(price)?))
+}
+
+fn ordered(sqrt_price_a: u128, sqrt_price_b: u128) -> (U256, U256) {
+	(U256::from(sqrt_price_a.min(sqrt_price_b)), U256::from(sqrt_price_a.max(sqrt_price_b)))
+}
+
+/// Amount of the base asset backing `liquidity` between two square root prices,
+/// `L * (sqrt(P_b) - sqrt(P_a)) / (sqrt(P_a) * sqrt(P_b))`.
+pub fn base_amount_delta(
+	sqrt_price_a: u128,
+	sqrt_price_b: u128,
+	liquidity: u128,
+	round_up: bool,
+) -> ConcentratedLiquidityResult<u128> {
+	let (lower, upper) = ordered(sqrt_price_a, sqrt_price_b);
+	ensure!(!lower.is_zero(), ConcentratedLiquidityError::SqrtPriceOutOfBounds);
+	let amount = mul_div(U256::from(liquidity) << RESOLUTION, upper - lower, upper, round_up)?;
+	let amount = mul_div(amount, U256::one(), lower, round_up)?;
+	Ok(to_u128(amount)?)
+}
+
+/// Amount of the quote asset backing `liquidity` between two square root prices,
+/// `L * (sqrt(P_b) - sqrt(P_a))`.
+pub fn quote_amount_delta(
+	sqrt_price_a: u128,
+	sqrt_price_b: u128,
+	liquidity: u128,
+	round_up: bool,
+) -> ConcentratedLiquidityResult<u128> {
+	let (lower, upper) = ordered(sqrt_price_a, sqrt_price_b);
+	Ok(to_u128(mul_div(U256::from(liquidity), upper - lower, q64(), round_up)?)?)
+}
+
+/// Square root price after `amount` of the base asset is added to (or removed from) the pool.
+/// Rounds up, in favour of the pool.
+pub fn next_sqrt_price_from_base(
+	sqrt_price: u128,
+	liquidity: u128,
+	amount: u128,
+	add: bool,
+) -> ConcentratedLiquidityResult<u128> {
+	if amount == 0 {
+		return Ok(sqrt_price)
+	}
+	ensure!(liquidity != 0, ConcentratedLiquidityError::NotEnoughLiquidity);
+
+	// L * sqrt(P) / (L +- amount * sqrt(P))
+	let numerator = U256::from(liquidity) << RESOLUTION;
+	let product = U256::from(amount) * U256::from(sqrt_price);
+	let denominator = if add {
+		numerator.checked_add(product).ok_or(ArithmeticError::Overflow)?
+	} else {
+		ensure!(numerator > product, ConcentratedLiquidityError::NotEnoughLiquidity);
+		numerator - product
+	};
+
+	Ok(to_u128(mul_div(numerator, U256::from(sqrt_price), denominator, true)?)?)
+}
+
+/// Square root price after `amount` of the quote asset is added to (or removed from) the pool.
+/// Rounds down, in favour of the pool.
+pub fn next_sqrt_price_from_quote(
+	sqrt_price: u128,
+	liquidity: u128,
+	amount: u128,
+	add: bool,
+) -> ConcentratedLiquidityResult<u128> {
+	if amount == 0 {
+		return Ok(sqrt_price)
+	}
+	ensure!(liquidity != 0, ConcentratedLiquidityError::NotEnoughLiquidity);
+
+	// sqrt(P) +- amount / L
+	let sqrt_price = U256::from(sqrt_price);
+	let shift = mul_div(U256::from(amount), q64(), U256::from(liquidity), !add)?;
+	let next = if add {
+		sqrt_price.checked_add(shift).ok_or(ArithmeticError::Overflow)?
+	} else {
+		ensure!(sqrt_price > shift, ConcentratedLiquidityError::NotEnoughLiquidity);
+		sqrt_price - shift
+	};
+
+	Ok(to_u128(next)?)
+}
+
+/// Maximum liquidity that can be provided between `sqrt_price_lower` and `sqrt_price_upper` with
+/// the given amounts, at the current `sqrt_price`.
+pub fn liquidity_for_amounts(
+	sqrt_price: u128,
+	sqrt_price_lower: u128,
+	sqrt_price_upper: u128,
+	base_amount: u128,
+	quote_amount: u128,
+) -> ConcentratedLiquidityResult<u128> {
+	// L = amount_base * sqrt(P_a) * sqrt(P_b) / (sqrt(P_b) - sqrt(P_a))
+	let liquidity_for_base = |lower: u128, upper: u128| -> ConcentratedLiquidityResult<u128> {
+		let (lower, upper) = ordered(lower, upper);
+		let intermediate = mul_div(lower, upper, q64(), false)?;
+		Ok(to_u128(mul_div(U256::from(base_amount), intermediate, upper - lower, false)?)?)
+	};
+	// L = amount_quote / (sqrt(P_b) - sqrt(P_a))
+	let liquidity_for_quote = |lower: u128, upper: u128| -> ConcentratedLiquidityResult<u128> {
+		let (lower, upper) = ordered(lower, upper);
+		Ok(to_u128(mul_div(U256::from(quote_amount), q64(), upper - lower, false)?)?)
+	};
+
+	ensure!(sqrt_price_lower < sqrt_price_upper, ConcentratedLiquidityError::TickOutOfBounds);
+	if sqrt_price <= sqrt_price_lower {
+		liquidity_for_base(sqrt_price_lower, sqrt_price_upper)
+	} else if sqrt_price < sqrt_price_upper {
+		Ok(liquidity_for_base(sqrt_price, sqrt_price_upper)?
+			.min(liquidity_for_quote(sqrt_price_lower, sqrt_price)?))
+	} else {
+		liquidity_for_quote(sqrt_price_lower, sqrt_price_upper)
+	}
+}
+
+/// Amounts of the base and quote assets backing `liquidity` between `sqrt_price_lower` and
+/// `sqrt_price_upper`, at the current `sqrt_price`.
+pub fn amounts_for_liquidity(
+	sqrt_price: u128,
+	sqrt_price_lower: u128,
+	sqrt_price_upper: u128,
+	liquidity: u128,
+	round_up: bool,
+) -> ConcentratedLiquidityResult<(u128, u128)> {
+	ensure!(sqrt_price_lower < sqrt_price_upper, ConcentratedLiquidityError::TickOutOfBounds);
+	if sqrt_price <= sqrt_price_lower {
+		Ok((base_amount_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?, 0))
+	} else if sqrt_price < sqrt_price_upper {
+		Ok((
+			base_amount_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+			quote_amount_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+		))
+	} else {
+		Ok((0, quote_amount_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?))
+	}
+}
+
+/// Fees earned per unit of liquidity when `liquidity` earns `fee`, as a Q64.64 number.
+pub fn fee_growth(fee: u128, liquidity: u128) -> ConcentratedLiquidityResult<u128> {
+	Ok(to_u128(mul_div(U256::from(fee), q64(), U256::from(liquidity), false)?)?)
+}
+
+/// Fees earned by `liquidity` for a Q64.64 `fee_growth` per unit of liquidity.
+pub fn fees_for_liquidity(liquidity: u128, fee_growth: u128) -> ConcentratedLiquidityResult<u128> {
+	Ok(to_u128(mul_div(U256::from(liquidity), U256::from(fee_growth), q64(), false)?)?)
+}
+
+/// Fee charged on `amount_in` such that `amount_in` is what is left once the fee is taken,
+/// `amount_in * f / (1 - f)`, rounded up.
+fn fee_on_amount_in<T: PerThing>(amount_in: u128, f: T) -> ConcentratedLiquidityResult<u128> {
+	let f_parts: u128 = f.deconstruct().into();
+	let left_from_fee: u128 = f.left_from_one().deconstruct().into();
+	Ok(to_u128(mul_div(
+		U256::from(amount_in),
+		U256::from(f_parts),
+		U256::from(left_from_fee),
+		true,
+	)?)?)
+}
+
+/// Computes a swap within a single tick range, moving the price from `sqrt_price` towards
+/// `sqrt_price_target` with constant `liquidity`. The direction of the swap is given by the
+/// target: a lower price means the base asset is swapped for the quote asset.
+///
+/// With `exact_in`, `amount_remaining` is the amount of the input asset left to swap, fee
+/// included. Otherwise it is the amount of the output asset left to receive.
+///
+/// # Parameters
+/// * `sqrt_price` - Current square root price
+/// * `sqrt_price_target` - Square root price at the end of the tick range
+/// * `liquidity` - Liquidity active within the tick range
+/// * `amount_remaining` - Amount left to swap
+/// * `exact_in` - Whether `amount_remaining` is denominated in the input asset
+/// * `f` - Total swap fee
+pub fn compute_swap_step<T: PerThing>(
+	sqrt_price: u128,
+	sqrt_price_target: u128,
+	liquidity: u128,
+	amount_remaining: u128,
+	exact_in: bool,
+	f: T,
+) -> ConcentratedLiquidityResult<SwapStep> {
+	let base_for_quote = sqrt_price >= sqrt_price_target;
+	let amount_in_between = |from: u128, to: u128, round_up: bool| {
+		if base_for_quote {
+			base_amount_delta(from, to, liquidity, round_up)
+		} else {
+			quote_amount_delta(from, to, liquidity, round_up)
+		}
+	};
+	let amount_out_between = |from: u128, to: u128, round_up: bool| {
+		if base_for_quote {
+			quote_amount_delta(from, to, liquidity, round_up)
+		} else {
+			base_amount_delta(from, to, liquidity, round_up)
+		}
+	};
+
+	let next_sqrt_price = if exact_in {
+		let amount_remaining_less_fee = f.left_from_one().mul_floor(amount_remaining);
+		if amount_remaining_less_fee >= amount_in_between(sqrt_price, sqrt_price_target, true)? {
+			sqrt_price_target
+		} else if base_for_quote {
+			next_sqrt_price_from_base(sqrt_price, liquidity, amount_remaining_less_fee, true)?
+		} else {
+			next_sqrt_price_from_quote(sqrt_price, liquidity, amount_remaining_less_fee, true)?
+		}
+	} else if amount_remaining >= amount_out_between(sqrt_price, sqrt_price_target, false)? {
+		sqrt_price_target
+	} else if base_for_quote {
+		next_sqrt_price_from_quote(sqrt_price, liquidity, amount_remaining, false)?
+	} else {
+		next_sqrt_price_from_base(sqrt_price, liquidity, amount_remaining, false)?
+	};
+
+	let amount_in = amount_in_between(sqrt_price, next_sqrt_price, true)?;
+	let mut amount_out = amount_out_between(sqrt_price, next_sqrt_price, false)?;
+	if !exact_in {
+		// rounding can't make the pool give out more than requested
+		amount_out = amount_out.min(amount_remaining);
+	}
+
+	let fee = if exact_in && next_sqrt_price != sqrt_price_target {
+		// the whole remaining amount is consumed, whatever isn't swapped is taken as fee
+		amount_remaining.checked_sub(amount_in).ok_or(ArithmeticError::Underflow)?
+	} else {
+		fee_on_amount_in(amount_in, f)?
+	};
+
+	Ok(SwapStep { sqrt_price: next_sqrt_price, amount_in, amount_out, fee })
+}
@@ -4,7 +4,8 @@ use rust_decimal::{
 	prelude::{FromPrimitive, ToPrimitive},
 	Decimal, MathematicalOps, RoundingStrategy,
 };
-use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, PerThing};
+use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedU128, PerThing};
+use sp_std::vec::Vec;
 
 /// Compute the amount of the output token given the amount of the input token.
 ///
@@ -242,6 +243,88 @@ pub fn compute_redeemed_for_lp<T: PerThing>(
 	Ok(a_k.safe_to_u128()?)
 }
 
+/// Calculates the balances of a pool at equilibrium with the prices `p_i`, that is the balances
+/// which keep the invariant of the pool and for which its spot prices are `p_i`. Unlike the actual
+/// balances of the pool, they cannot be skewed by trading against it.
+///
+/// With `v_i = b_i * p_i` the value of the balance of token `i`, the value of the pool at
+/// equilibrium is `V = prod((v_i / w_i)^w_i)`, of which the balance of token `i` is `V * w_i /
+/// p_i`.
+///
+/// **NOTE:** Weights must already be normalized.
+///
+/// # Parameters
+/// * `pool_assets` - Balance `b_i`, price `p_i` of one unit of balance and weight `w_i` of each
+///   token
+pub fn compute_equilibrium_balances<T: PerThing>(
+	pool_assets: &[(u128, FixedU128, T)],
+) -> ConstantProductAmmResult<Vec<u128>> {
+	let pool_assets = pool_assets
+		.iter()
+		.map(|(b_i, p_i, w_i)| {
+			let p_i = Decimal::safe_from_fixed_point(p_i.into_inner(), 18)?;
+			let w_i = Decimal::safe_from_per_thing(*w_i)?;
+			let v_i = Decimal::safe_from_u128(*b_i)?.safe_mul(&p_i)?;
+			Ok::<_, ArithmeticError>((p_i, w_i, v_i.safe_div(&w_i)?))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	// factoring out the first token keeps the bases of the powers close to one
+	let (_, _, reference) =
+		pool_assets.first().ok_or(ConstantProductAmmError::InvalidTokensList)?;
+	let value = pool_assets.iter().try_fold(*reference, |value, (_, w_i, v_i_by_w_i)| {
+		let power = v_i_by_w_i
+			.safe_div(reference)?
+			.checked_powd(*w_i)
+			.ok_or(ArithmeticError::Overflow)?;
+		value.safe_mul(&power)
+	})?;
+
+	pool_assets
+		.iter()
+		.map(|(p_i, w_i, _)| {
+			Ok::<_, ConstantProductAmmError>(
+				value.safe_mul(w_i)?.safe_div(p_i)?.round_down().safe_to_u128()?,
+			)
+		})
+		.collect()
+}
+
+/// Calculates `a_k` when redeeming LP tokens for a single asset of the pool.
+///
+/// If `Ok`, returns a `ConstantProductAmmValueFeePair` containing the `a_k` and the `fee`. Only
+/// the part of the withdrawal that is implicitly swapped into token `k`, `(1 - w_k)`, is charged.
+///
+/// # Parameters
+/// * `p_supply` - Existing supply of LP
+/// * `p_redeemed` - Redeemed LP tokens
+/// * `b_k` - balance of token `k`
+/// * `w_k` - weight of token `k`
+/// * `f` - Total swap fee
+pub fn compute_single_asset_redeemed_for_lp<T: PerThing>(
+	p_supply: u128,
+	p_redeemed: u128,
+	b_k: u128,
+	w_k: T,
+	f: T,
+) -> ConstantProductAmmResult<ConstantProductAmmValueFeePair> {
+	let a_k = Decimal::safe_from_u128(compute_redeemed_for_lp(p_supply, p_redeemed, b_k, w_k)?)?;
+
+	let fee = if f.is_zero() {
+		Decimal::ZERO
+	} else {
+		let charged = Decimal::safe_from_per_thing(w_k.left_from_one())?;
+		let f = Decimal::safe_from_per_thing(f)?;
+		a_k.safe_mul(&charged)?.safe_mul(&f)?.round_up()
+	};
+	let a_k_left_from_fee = a_k.safe_sub(&fee)?;
+
+	Ok(ConstantProductAmmValueFeePair {
+		value: a_k_left_from_fee.safe_to_u128()?,
+		fee: fee.safe_to_u128()?,
+	})
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum ConstantProductAmmError {
 	ArithmeticError(ArithmeticError),
@@ -2,8 +2,10 @@ use core::{cmp::Ordering, ops::Sub};
 
 use sp_runtime::{traits::CheckedAdd, PerThing};
 
+pub mod concentrated_liquidity;
 pub mod constant_product;
 pub mod price;
+pub mod stable_swap;
 #[cfg(test)]
 pub mod tests;
 
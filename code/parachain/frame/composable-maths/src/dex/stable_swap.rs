@@ -0,0 +1,367 @@
+use frame_support::ensure;
+use sp_core::U256;
+use sp_runtime::{ArithmeticError, DispatchError, FixedPointNumber, FixedU128, PerThing};
+use sp_std::vec::Vec;
+
+/// Maximum number of Newton iterations to run before giving up on convergence.
+const MAX_ITERATIONS: u8 = 255;
+
+pub type StableSwapResult<T> = Result<T, StableSwapError>;
+
+/// Output value of a StableSwap computation together with the fee charged for it.
+#[derive(Debug, Eq, PartialEq)]
+pub struct StableSwapValueFeePair {
+	pub value: u128,
+	pub fee: u128,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum StableSwapError {
+	ArithmeticError(ArithmeticError),
+	CannotTakeMoreThanAvailable,
+	InvalidTokensList,
+	/// The Newton iteration did not converge within `MAX_ITERATIONS` steps.
+	DidNotConverge,
+	/// The invariant is undefined for an amplification coefficient of `0`.
+	ZeroAmplificationCoefficient,
+}
+
+impl From<ArithmeticError> for StableSwapError {
+	fn from(error: ArithmeticError) -> Self {
+		StableSwapError::ArithmeticError(error)
+	}
+}
+
+impl From<StableSwapError> for DispatchError {
+	fn from(error: StableSwapError) -> Self {
+		match error {
+			StableSwapError::ArithmeticError(error) => DispatchError::from(error),
+			StableSwapError::CannotTakeMoreThanAvailable => DispatchError::from(
+				"`a_out` must be less than `b_o` (can't take out more than what's available)!",
+			),
+			StableSwapError::InvalidTokensList =>
+				DispatchError::from("Must provide at least two non-empty balances!"),
+			StableSwapError::DidNotConverge =>
+				DispatchError::from("StableSwap invariant computation did not converge!"),
+			StableSwapError::ZeroAmplificationCoefficient =>
+				DispatchError::from("Amplification coefficient must be greater than zero!"),
+		}
+	}
+}
+
+fn checked_add(a: U256, b: U256) -> Result<U256, ArithmeticError> {
+	a.checked_add(b).ok_or(ArithmeticError::Overflow)
+}
+
+fn checked_sub(a: U256, b: U256) -> Result<U256, ArithmeticError> {
+	a.checked_sub(b).ok_or(ArithmeticError::Underflow)
+}
+
+fn checked_mul(a: U256, b: U256) -> Result<U256, ArithmeticError> {
+	a.checked_mul(b).ok_or(ArithmeticError::Overflow)
+}
+
+fn checked_div(a: U256, b: U256) -> Result<U256, ArithmeticError> {
+	a.checked_div(b).ok_or(ArithmeticError::DivisionByZero)
+}
+
+fn to_u128(a: U256) -> Result<u128, ArithmeticError> {
+	ensure!(a <= U256::from(u128::MAX), ArithmeticError::Overflow);
+	Ok(a.low_u128())
+}
+
+fn converged(a: U256, b: U256) -> bool {
+	let difference = if a > b { a - b } else { b - a };
+	difference <= U256::one()
+}
+
+/// Computes `A * n^n`, the amplification coefficient as used in the invariant.
+fn compute_ann(amp_coeff: u128, n: U256) -> StableSwapResult<U256> {
+	ensure!(amp_coeff > 0, StableSwapError::ZeroAmplificationCoefficient);
+	let n_pow_n = (1..n.low_u32()).try_fold(n, |acc, _| checked_mul(acc, n))?;
+	Ok(checked_mul(U256::from(amp_coeff), n_pow_n)?)
+}
+
+/// Computes `D^(n+1) / (n^n * prod(balances))`.
+fn compute_d_p(d: U256, balances: &[u128], n: U256) -> Result<U256, ArithmeticError> {
+	balances.iter().try_fold(d, |d_p, balance| {
+		checked_div(checked_mul(d_p, d)?, checked_mul(U256::from(*balance), n)?)
+	})
+}
+
+/// Compute the StableSwap invariant `D` for the given balances.
+///
+/// `D` is the solution of `A * n^n * sum(x_i) + D = A * D * n^n + D^(n+1) / (n^n * prod(x_i))`,
+/// found using Newton's method.
+///
+/// # Parameters
+/// * `balances` - Balances of all of the assets in the pool
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+///
+/// From https://curve.fi/files/stableswap-paper.pdf
+pub fn compute_d(balances: &[u128], amp_coeff: u128) -> StableSwapResult<u128> {
+	ensure!(balances.len() > 1, StableSwapError::InvalidTokensList);
+	let n = U256::from(balances.len());
+	let sum = balances
+		.iter()
+		.try_fold(U256::zero(), |sum, balance| checked_add(sum, U256::from(*balance)))?;
+	if sum.is_zero() {
+		return Ok(0)
+	}
+
+	let ann = compute_ann(amp_coeff, n)?;
+	let mut d = sum;
+	for _ in 0..MAX_ITERATIONS {
+		let d_p = compute_d_p(d, balances, n)?;
+		let d_prev = d;
+		// d = (ann * sum + d_p * n) * d / ((ann - 1) * d + (n + 1) * d_p)
+		let numerator = checked_add(checked_mul(ann, sum)?, checked_mul(d_p, n)?)?;
+		let numerator = checked_mul(numerator, d)?;
+		let denominator = checked_add(
+			checked_mul(checked_sub(ann, U256::one())?, d)?,
+			checked_mul(checked_add(n, U256::one())?, d_p)?,
+		)?;
+		d = checked_div(numerator, denominator)?;
+		if converged(d, d_prev) {
+			return Ok(to_u128(d)?)
+		}
+	}
+
+	Err(StableSwapError::DidNotConverge)
+}
+
+/// Compute the balance of the remaining asset such that the invariant `D` holds, given the
+/// balances of all of the other assets.
+///
+/// # Parameters
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `d` - The invariant to respect
+/// * `other_balances` - Balances of all of the assets in the pool except the one to compute
+pub fn compute_y(amp_coeff: u128, d: u128, other_balances: &[u128]) -> StableSwapResult<u128> {
+	ensure!(!other_balances.is_empty(), StableSwapError::InvalidTokensList);
+	let n = U256::from(other_balances.len() + 1);
+	let ann = compute_ann(amp_coeff, n)?;
+	let d = U256::from(d);
+
+	// c = D^(n+1) / (n^n * prod(other_balances) * ann)
+	// b = sum(other_balances) + D / ann
+	let (sum, c) = other_balances.iter().try_fold(
+		(U256::zero(), d),
+		|(sum, c), balance| -> Result<_, ArithmeticError> {
+			let balance = U256::from(*balance);
+			Ok((
+				checked_add(sum, balance)?,
+				checked_div(checked_mul(c, d)?, checked_mul(balance, n)?)?,
+			))
+		},
+	)?;
+	let c = checked_div(checked_mul(c, d)?, checked_mul(ann, n)?)?;
+	let b = checked_add(sum, checked_div(d, ann)?)?;
+
+	// y = (y^2 + c) / (2 * y + b - D)
+	let mut y = d;
+	for _ in 0..MAX_ITERATIONS {
+		let y_prev = y;
+		let numerator = checked_add(checked_mul(y, y)?, c)?;
+		let denominator = checked_sub(checked_add(checked_mul(y, U256::from(2_u8))?, b)?, d)?;
+		y = checked_div(numerator, denominator)?;
+		if converged(y, y_prev) {
+			return Ok(to_u128(y)?)
+		}
+	}
+
+	Err(StableSwapError::DidNotConverge)
+}
+
+/// Compute the amount of the output token given the amount of the input token.
+///
+/// If `Ok`, returns a `StableSwapValueFeePair` containing the `a_out` and the `fee`.
+/// To get `a_out` without accounting for the fee, set `f = 0`.
+/// Amount out, round down results.
+///
+/// # Parameters
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `b_i` - Balance of the input token
+/// * `b_o` - Balance of the output token
+/// * `a_sent` - Amount of the input token sent by the user
+/// * `f` - Total swap fee
+pub fn compute_out_given_in<T: PerThing>(
+	amp_coeff: u128,
+	b_i: u128,
+	b_o: u128,
+	a_sent: u128,
+	f: T,
+) -> StableSwapResult<StableSwapValueFeePair> {
+	let fee = f.mul_ceil(a_sent);
+	let a_sent_fee_cut = a_sent.checked_sub(fee).ok_or(ArithmeticError::Underflow)?;
+
+	let d = compute_d(&[b_i, b_o], amp_coeff)?;
+	let new_b_i = b_i.checked_add(a_sent_fee_cut).ok_or(ArithmeticError::Overflow)?;
+	let new_b_o = compute_y(amp_coeff, d, &[new_b_i])?;
+
+	// subtract one to account for any rounding errors in the favour of the pool
+	let a_out = b_o.checked_sub(new_b_o).ok_or(ArithmeticError::Underflow)?.saturating_sub(1);
+
+	Ok(StableSwapValueFeePair { value: a_out, fee })
+}
+
+/// Compute the amount of the input token given the amount of the output token.
+///
+/// If `Ok`, returns a `StableSwapValueFeePair` containing the `a_sent` and the `fee`.
+/// To get `a_sent` without accounting for the fee, set `f = 0`.
+/// Amount in, round up results.
+///
+/// # Parameters
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `b_i` - Balance of the input token
+/// * `b_o` - Balance of the output token
+/// * `a_out` - Amount of the output token desired by the user
+/// * `f` - Total swap fee
+pub fn compute_in_given_out<T: PerThing>(
+	amp_coeff: u128,
+	b_i: u128,
+	b_o: u128,
+	a_out: u128,
+	f: T,
+) -> StableSwapResult<StableSwapValueFeePair> {
+	ensure!(a_out < b_o, StableSwapError::CannotTakeMoreThanAvailable);
+
+	let d = compute_d(&[b_i, b_o], amp_coeff)?;
+	let new_b_i = compute_y(amp_coeff, d, &[b_o - a_out])?;
+
+	// add one to account for any rounding errors in the favour of the pool
+	let a_sent_fee_cut = new_b_i
+		.checked_sub(b_i)
+		.ok_or(ArithmeticError::Underflow)?
+		.checked_add(1)
+		.ok_or(ArithmeticError::Overflow)?;
+
+	// a_sent = a_sent_fee_cut / (1 - f)
+	let one: u128 = T::ACCURACY.into();
+	let left_from_fee: u128 = f.left_from_one().deconstruct().into();
+	let a_sent = checked_div(
+		checked_add(
+			checked_mul(U256::from(a_sent_fee_cut), U256::from(one))?,
+			U256::from(left_from_fee.saturating_sub(1)),
+		)?,
+		U256::from(left_from_fee),
+	)?;
+	let a_sent = to_u128(a_sent)?;
+	let fee = a_sent.checked_sub(a_sent_fee_cut).ok_or(ArithmeticError::Underflow)?;
+
+	Ok(StableSwapValueFeePair { value: a_sent, fee })
+}
+
+/// Computes the LP to mint on first deposit.
+///
+/// The LP minted on the first deposit is equal to the invariant `D` of the deposited balances.
+///
+/// # Parameters
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `deposits` - Deposits of all of the assets in the pool
+pub fn compute_first_deposit_lp(amp_coeff: u128, deposits: &[u128]) -> StableSwapResult<u128> {
+	ensure!(deposits.iter().all(|deposit| *deposit != 0), StableSwapError::InvalidTokensList);
+	compute_d(deposits, amp_coeff)
+}
+
+/// Computes the LP to mint on an existing deposit.
+///
+/// Deposits that move the pool away from its current balance ratio are charged an imbalance fee
+/// of `f * n / (4 * (n - 1))` on the deviation from the ideal (balanced) deposit, which stays in
+/// the pool.
+///
+/// If `Ok`, returns a `StableSwapValueFeePair` containing the `lp_to_mint` and the `fee`, the
+/// latter being denominated in LP tokens.
+///
+/// # Parameters
+/// * `p_supply` - Existing supply of LP tokens
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `balances` - Balances of all of the assets in the pool
+/// * `deposits` - Deposits of all of the assets in the pool, in the same order as `balances`
+/// * `f` - Fee
+pub fn compute_deposit_lp<T: PerThing>(
+	p_supply: u128,
+	amp_coeff: u128,
+	balances: &[u128],
+	deposits: &[u128],
+	f: T,
+) -> StableSwapResult<StableSwapValueFeePair> {
+	ensure!(balances.len() == deposits.len(), StableSwapError::InvalidTokensList);
+	let n = U256::from(balances.len());
+
+	let d_0 = compute_d(balances, amp_coeff)?;
+	let new_balances = balances
+		.iter()
+		.zip(deposits)
+		.map(|(balance, deposit)| balance.checked_add(*deposit).ok_or(ArithmeticError::Overflow))
+		.collect::<Result<Vec<_>, _>>()?;
+	let d_1 = compute_d(&new_balances, amp_coeff)?;
+	ensure!(d_1 > d_0, ArithmeticError::Underflow);
+
+	// base_fee = f * n / (4 * (n - 1))
+	let f_parts: u128 = f.deconstruct().into();
+	let one: u128 = T::ACCURACY.into();
+	let fee_numerator = checked_mul(U256::from(f_parts), n)?;
+	let fee_denominator =
+		checked_mul(U256::from(one), checked_mul(U256::from(4_u8), checked_sub(n, U256::one())?)?)?;
+
+	let adjusted_balances = balances
+		.iter()
+		.zip(&new_balances)
+		.map(|(old_balance, new_balance)| -> Result<u128, ArithmeticError> {
+			let ideal_balance = to_u128(checked_div(
+				checked_mul(U256::from(d_1), U256::from(*old_balance))?,
+				U256::from(d_0),
+			)?)?;
+			let difference = ideal_balance.max(*new_balance) - ideal_balance.min(*new_balance);
+			let fee = to_u128(checked_div(
+				checked_mul(U256::from(difference), fee_numerator)?,
+				fee_denominator,
+			)?)?;
+			new_balance.checked_sub(fee).ok_or(ArithmeticError::Underflow)
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	let d_2 = compute_d(&adjusted_balances, amp_coeff)?;
+
+	// lp_to_mint = p_supply * (d_2 - d_0) / d_0
+	let p_supply = U256::from(p_supply);
+	let d_0 = U256::from(d_0);
+	let issued =
+		to_u128(checked_div(checked_mul(p_supply, checked_sub(U256::from(d_2), d_0)?)?, d_0)?)?;
+	let fee = to_u128(checked_div(
+		checked_mul(p_supply, checked_sub(U256::from(d_1), U256::from(d_2))?)?,
+		d_0,
+	)?)?;
+
+	Ok(StableSwapValueFeePair { value: issued, fee })
+}
+
+/// Computes the marginal price of the quote asset, denominated in the base asset.
+///
+/// It lies between `1` and the constant product price `b_base / b_quote`, approaching `1` as
+/// `A -> inf`. Errors for `A = 0`, for which the invariant is undefined.
+///
+/// # Parameters
+/// * `amp_coeff` - Amplification coefficient of the pool (`A`)
+/// * `b_base` - Balance of the base asset
+/// * `b_quote` - Balance of the quote asset
+pub fn compute_spot_price(
+	amp_coeff: u128,
+	b_base: u128,
+	b_quote: u128,
+) -> StableSwapResult<FixedU128> {
+	let n = U256::from(2_u8);
+	let ann = compute_ann(amp_coeff, n)?;
+	let d = compute_d(&[b_base, b_quote], amp_coeff)?;
+	let d_p = compute_d_p(U256::from(d), &[b_base, b_quote], n)?;
+	let (x, y) = (U256::from(b_base), U256::from(b_quote));
+
+	// price = x * (ann * y + d_p) / (y * (ann * x + d_p))
+	let numerator = checked_mul(
+		checked_mul(x, checked_add(checked_mul(ann, y)?, d_p)?)?,
+		U256::from(FixedU128::DIV),
+	)?;
+	let denominator = checked_mul(y, checked_add(checked_mul(ann, x)?, d_p)?)?;
+
+	Ok(FixedU128::from_inner(to_u128(checked_div(numerator, denominator)?)?))
+}
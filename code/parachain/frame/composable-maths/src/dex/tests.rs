@@ -157,6 +157,138 @@ mod constant_product {
 		}
 	}
 
+	/// Tests related to the function `compute_single_asset_redeemed_for_lp`
+	mod compute_single_asset_redeemed_for_lp {
+		use super::*;
+
+		#[test]
+		fn should_equal_compute_redeemed_for_lp_when_fee_is_zero() {
+			let p_supply = 512_000_000_000_000;
+			let p_redeemed = 128_000_000_000_000;
+			let b_k = 2_048_000_000_000_000;
+			let w_k = Permill::from_percent(50);
+
+			let res = compute_single_asset_redeemed_for_lp(
+				p_supply,
+				p_redeemed,
+				b_k,
+				w_k,
+				Permill::zero(),
+			)
+			.expect("Inputs are valid; QED");
+
+			assert_eq!(res, ConstantProductAmmValueFeePair { value: 896_000_000_000_000, fee: 0 });
+		}
+
+		#[test]
+		fn should_only_charge_fee_on_implicitly_swapped_part() {
+			let p_supply = 512_000_000_000_000;
+			let p_redeemed = 128_000_000_000_000;
+			let b_k = 2_048_000_000_000_000;
+			let w_k = Permill::from_percent(50);
+			let f = Permill::from_percent(1);
+
+			let res = compute_single_asset_redeemed_for_lp(p_supply, p_redeemed, b_k, w_k, f)
+				.expect("Inputs are valid; QED");
+
+			assert_eq!(
+				res,
+				ConstantProductAmmValueFeePair {
+					value: 891_520_000_000_000,
+					fee: 4_480_000_000_000
+				}
+			);
+		}
+
+		#[test]
+		fn should_error_when_w_k_is_zero() {
+			let res = compute_single_asset_redeemed_for_lp(
+				256,
+				128,
+				512,
+				Permill::zero(),
+				Permill::zero(),
+			);
+
+			assert_eq!(res, Err(ConstantProductAmmError::from(ArithmeticError::DivisionByZero)));
+		}
+	}
+
+	/// Tests related to the function `compute_equilibrium_balances`
+	mod compute_equilibrium_balances {
+		use super::*;
+		use sp_runtime::{FixedPointNumber, FixedU128};
+
+		#[test]
+		fn should_keep_balances_of_a_pool_at_equilibrium() {
+			let res = compute_equilibrium_balances(&[
+				(
+					100_000_000_000_000,
+					FixedU128::saturating_from_integer(1_u32),
+					Permill::from_percent(50),
+				),
+				(
+					100_000_000_000,
+					FixedU128::saturating_from_integer(1_000_u32),
+					Permill::from_percent(50),
+				),
+			])
+			.expect("Inputs are valid; QED");
+
+			assert!(default_acceptable_computation_error(res[0], 100_000_000_000_000).is_ok());
+			assert!(default_acceptable_computation_error(res[1], 100_000_000_000).is_ok());
+		}
+
+		#[test]
+		fn should_undo_a_skew_of_the_balances() {
+			// same invariant as above, skewed by buying most of the second token
+			let res = compute_equilibrium_balances(&[
+				(
+					400_000_000_000_000,
+					FixedU128::saturating_from_integer(1_u32),
+					Permill::from_percent(50),
+				),
+				(
+					25_000_000_000,
+					FixedU128::saturating_from_integer(1_000_u32),
+					Permill::from_percent(50),
+				),
+			])
+			.expect("Inputs are valid; QED");
+
+			assert!(default_acceptable_computation_error(res[0], 100_000_000_000_000).is_ok());
+			assert!(default_acceptable_computation_error(res[1], 100_000_000_000).is_ok());
+		}
+
+		#[test]
+		fn should_weigh_balances_by_their_weights() {
+			// 80/20 pool worth 1_000_000_000_000_000 at equilibrium
+			let res = compute_equilibrium_balances(&[
+				(
+					800_000_000_000_000,
+					FixedU128::saturating_from_integer(1_u32),
+					Permill::from_percent(80),
+				),
+				(
+					200_000_000_000,
+					FixedU128::saturating_from_integer(1_000_u32),
+					Permill::from_percent(20),
+				),
+			])
+			.expect("Inputs are valid; QED");
+
+			assert!(default_acceptable_computation_error(res[0], 800_000_000_000_000).is_ok());
+			assert!(default_acceptable_computation_error(res[1], 200_000_000_000).is_ok());
+		}
+
+		#[test]
+		fn should_error_without_tokens() {
+			let res = compute_equilibrium_balances::<Permill>(&[]);
+
+			assert_eq!(res, Err(ConstantProductAmmError::InvalidTokensList));
+		}
+	}
+
 	/// Tests related to the function `compute_first_deposit_lp`
 	mod compute_first_deposit_lp {
 		use super::*;
@@ -914,3 +1046,386 @@ mod constant_product {
 		}
 	}
 }
+
+/// Tests related to StableSwap math functions
+mod stable_swap {
+	use crate::dex::stable_swap::*;
+	use proptest::prelude::*;
+	use sp_runtime::{FixedPointNumber, FixedU128, Permill};
+
+	const UNIT: u128 = 1_000_000_000_000;
+
+	/// Tests related to the function `compute_d`
+	mod compute_d {
+		use super::*;
+
+		#[test]
+		fn should_equal_sum_of_balances_when_balanced() {
+			let balance = 1_000_000 * UNIT;
+			for amp_coeff in [1, 10, 100, 1_000] {
+				assert_eq!(compute_d(&[balance, balance], amp_coeff), Ok(2 * balance));
+			}
+		}
+
+		#[test]
+		fn should_be_zero_for_empty_pool() {
+			assert_eq!(compute_d(&[0, 0], 100), Ok(0));
+		}
+
+		#[test]
+		fn should_require_at_least_two_balances() {
+			assert_eq!(compute_d(&[UNIT], 100), Err(StableSwapError::InvalidTokensList));
+		}
+
+		#[test]
+		fn should_be_bounded_by_sum_of_balances() {
+			let d = compute_d(&[1_000 * UNIT, 3_000 * UNIT], 100).expect("no overflow");
+
+			assert!(d < 4_000 * UNIT);
+			assert!(d > 3_900 * UNIT);
+		}
+	}
+
+	/// Tests related to the functions `compute_out_given_in` and `compute_in_given_out`
+	mod swap {
+		use super::*;
+
+		#[test]
+		fn should_have_less_slippage_than_constant_product() {
+			let balance = 1_000_000 * UNIT;
+			let a_sent = 100_000 * UNIT;
+			let constant_product_out = balance - balance * balance / (balance + a_sent);
+
+			let a_out = compute_out_given_in(100, balance, balance, a_sent, Permill::zero())
+				.expect("no overflow")
+				.value;
+
+			assert!(a_out > constant_product_out);
+			assert!(a_out < a_sent);
+		}
+
+		#[test]
+		fn should_charge_fee_on_input() {
+			let balance = 1_000_000 * UNIT;
+			let a_sent = 1_000 * UNIT;
+			let f = Permill::from_rational::<u32>(3, 1000);
+
+			let res = compute_out_given_in(100, balance, balance, a_sent, f).expect("no overflow");
+			let res_no_fee = compute_out_given_in(100, balance, balance, a_sent, Permill::zero())
+				.expect("no overflow");
+
+			assert_eq!(res.fee, 3 * UNIT);
+			assert!(res.value < res_no_fee.value);
+		}
+
+		#[test]
+		fn cannot_take_more_than_available() {
+			assert_eq!(
+				compute_in_given_out(100, UNIT, UNIT, UNIT, Permill::zero()),
+				Err(StableSwapError::CannotTakeMoreThanAvailable)
+			);
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(1_000))]
+
+			#[test]
+			fn in_given_out_should_round_trip_out_given_in(
+				amp_coeff in 1..5_000_u128,
+				b_i in 1_000..1_000_000_000_u128,
+				b_o in 1_000..1_000_000_000_u128,
+				a_out_percent in 1..90_u32,
+				f in 0..10_000_u32,
+			) {
+				let (b_i, b_o) = (b_i * UNIT, b_o * UNIT);
+				let a_out = Permill::from_percent(a_out_percent).mul_floor(b_o);
+				let f = Permill::from_parts(f);
+
+				let a_sent = compute_in_given_out(amp_coeff, b_i, b_o, a_out, f)
+					.expect("no values cause overflow")
+					.value;
+				let a_received = compute_out_given_in(amp_coeff, b_i, b_o, a_sent, f)
+					.expect("no values cause overflow")
+					.value;
+
+				// pool must never pay out more than what was requested for the computed input
+				prop_assert!(a_received + 2 >= a_out);
+			}
+		}
+	}
+
+	/// Tests related to the function `compute_deposit_lp`
+	mod compute_deposit_lp {
+		use super::*;
+
+		#[test]
+		fn balanced_deposit_should_not_be_charged() {
+			let balance = 1_000_000 * UNIT;
+			let p_supply = compute_first_deposit_lp(100, &[balance, balance]).expect("no overflow");
+
+			let res = compute_deposit_lp(
+				p_supply,
+				100,
+				&[balance, balance],
+				&[balance / 10, balance / 10],
+				Permill::from_rational::<u32>(3, 1000),
+			)
+			.expect("no overflow");
+
+			assert_eq!(res, StableSwapValueFeePair { value: p_supply / 10, fee: 0 });
+		}
+
+		#[test]
+		fn imbalanced_deposit_should_be_charged() {
+			let balance = 1_000_000 * UNIT;
+			let p_supply = compute_first_deposit_lp(100, &[balance, balance]).expect("no overflow");
+
+			let res = compute_deposit_lp(
+				p_supply,
+				100,
+				&[balance, balance],
+				&[balance / 5, 0],
+				Permill::from_rational::<u32>(3, 1000),
+			)
+			.expect("no overflow");
+
+			assert!(res.fee > 0);
+			assert!(res.value < p_supply / 10);
+		}
+
+		#[test]
+		fn first_deposit_must_contain_all_assets() {
+			assert_eq!(
+				compute_first_deposit_lp(100, &[UNIT, 0]),
+				Err(StableSwapError::InvalidTokensList)
+			);
+		}
+	}
+
+	/// Tests related to the function `compute_spot_price`
+	mod compute_spot_price {
+		use super::*;
+
+		#[test]
+		fn should_be_one_when_balanced() {
+			let balance = 1_000_000 * UNIT;
+
+			assert_eq!(compute_spot_price(100, balance, balance), Ok(FixedU128::one()));
+		}
+
+		#[test]
+		fn should_be_between_one_and_constant_product_price() {
+			let spot_price =
+				compute_spot_price(100, 2_000_000 * UNIT, 1_000_000 * UNIT).expect("no overflow");
+
+			assert!(spot_price > FixedU128::one());
+			assert!(spot_price < FixedU128::saturating_from_integer(2_u128));
+		}
+
+		#[test]
+		fn should_fail_without_amplification() {
+			assert_eq!(
+				compute_spot_price(0, 2_000_000 * UNIT, 1_000_000 * UNIT),
+				Err(StableSwapError::ZeroAmplificationCoefficient)
+			);
+		}
+	}
+}
+
+mod concentrated_liquidity {
+	use crate::dex::concentrated_liquidity::*;
+	use proptest::prelude::*;
+	use sp_runtime::{FixedPointNumber, FixedU128, Permill};
+
+	const UNIT: u128 = 1_000_000_000_000;
+	/// `1.0001^6932` is roughly `2`.
+	const TICK_OF_PRICE_TWO: i32 = 6_932;
+
+	/// Tests related to the functions `sqrt_price_at_tick` and `tick_at_sqrt_price`
+	mod ticks {
+		use super::*;
+
+		#[test]
+		fn tick_zero_should_be_price_one() {
+			assert_eq!(sqrt_price_at_tick(0), Ok(1 << RESOLUTION));
+			assert_eq!(price_from_sqrt_price(1 << RESOLUTION), Ok(FixedU128::one()));
+		}
+
+		#[test]
+		fn price_should_follow_ticks() {
+			let price = price_from_sqrt_price(
+				sqrt_price_at_tick(TICK_OF_PRICE_TWO).expect("tick is in bounds"),
+			)
+			.expect("no overflow");
+
+			assert!(price > FixedU128::saturating_from_rational(19_999_u128, 10_000_u128));
+			assert!(price < FixedU128::saturating_from_rational(20_001_u128, 10_000_u128));
+		}
+
+		#[test]
+		fn should_error_when_out_of_bounds() {
+			assert_eq!(
+				sqrt_price_at_tick(MAX_TICK + 1),
+				Err(ConcentratedLiquidityError::TickOutOfBounds)
+			);
+			assert_eq!(
+				sqrt_price_at_tick(MIN_TICK - 1),
+				Err(ConcentratedLiquidityError::TickOutOfBounds)
+			);
+			assert_eq!(
+				tick_at_sqrt_price(sqrt_price_at_tick(MIN_TICK).expect("tick is in bounds") - 1),
+				Err(ConcentratedLiquidityError::SqrtPriceOutOfBounds)
+			);
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(1_000))]
+
+			#[test]
+			fn tick_at_sqrt_price_should_round_trip(tick in (MIN_TICK + 1)..=MAX_TICK) {
+				let sqrt_price = sqrt_price_at_tick(tick).expect("tick is in bounds");
+
+				prop_assert_eq!(tick_at_sqrt_price(sqrt_price), Ok(tick));
+				prop_assert_eq!(tick_at_sqrt_price(sqrt_price - 1), Ok(tick - 1));
+			}
+		}
+	}
+
+	/// Tests related to the functions `liquidity_for_amounts` and `amounts_for_liquidity`
+	mod liquidity {
+		use super::*;
+
+		#[test]
+		fn should_take_both_assets_in_range() {
+			let lower = sqrt_price_at_tick(-TICK_OF_PRICE_TWO).expect("tick is in bounds");
+			let upper = sqrt_price_at_tick(TICK_OF_PRICE_TWO).expect("tick is in bounds");
+			let current = sqrt_price_at_tick(0).expect("tick is in bounds");
+
+			let liquidity =
+				liquidity_for_amounts(current, lower, upper, 1_000 * UNIT, 1_000 * UNIT)
+					.expect("no overflow");
+
+			assert_eq!(
+				amounts_for_liquidity(current, lower, upper, liquidity, true),
+				Ok((1_000 * UNIT, 1_000 * UNIT))
+			);
+		}
+
+		#[test]
+		fn should_take_a_single_asset_out_of_range() {
+			let lower = sqrt_price_at_tick(100).expect("tick is in bounds");
+			let upper = sqrt_price_at_tick(200).expect("tick is in bounds");
+
+			let (below, above) =
+				(sqrt_price_at_tick(0).expect("ok"), sqrt_price_at_tick(300).expect("ok"));
+			let liquidity =
+				liquidity_for_amounts(below, lower, upper, UNIT, UNIT).expect("no overflow");
+			let (base, quote) =
+				amounts_for_liquidity(below, lower, upper, liquidity, true).expect("no overflow");
+			assert!(base <= UNIT && base > UNIT - 10);
+			assert_eq!(quote, 0);
+
+			let liquidity =
+				liquidity_for_amounts(above, lower, upper, UNIT, UNIT).expect("no overflow");
+			let (base, quote) =
+				amounts_for_liquidity(above, lower, upper, liquidity, true).expect("no overflow");
+			assert_eq!(base, 0);
+			assert!(quote <= UNIT && quote > UNIT - 10);
+		}
+	}
+
+	/// Tests related to the function `compute_swap_step`
+	mod compute_swap_step {
+		use super::*;
+
+		fn range() -> (u128, u128, u128, u128) {
+			let lower = sqrt_price_at_tick(-TICK_OF_PRICE_TWO).expect("tick is in bounds");
+			let upper = sqrt_price_at_tick(TICK_OF_PRICE_TWO).expect("tick is in bounds");
+			let current = sqrt_price_at_tick(0).expect("tick is in bounds");
+			let liquidity =
+				liquidity_for_amounts(current, lower, upper, 1_000 * UNIT, 1_000 * UNIT)
+					.expect("no overflow");
+			(lower, upper, current, liquidity)
+		}
+
+		#[test]
+		fn exact_in_should_charge_fee_on_input() {
+			let (lower, _, current, liquidity) = range();
+			let f = Permill::from_rational::<u32>(3, 1000);
+
+			let step = compute_swap_step(current, lower, liquidity, 10 * UNIT, true, f)
+				.expect("no overflow");
+
+			assert_eq!(step.fee, 3 * UNIT / 100);
+			assert_eq!(step.amount_in + step.fee, 10 * UNIT);
+			assert!(step.sqrt_price < current && step.sqrt_price > lower);
+			// price is one, minus the slippage
+			assert!(step.amount_out < step.amount_in);
+			assert!(step.amount_out > step.amount_in * 99 / 100);
+		}
+
+		#[test]
+		fn should_stop_at_target() {
+			let (lower, _, current, liquidity) = range();
+
+			let step = compute_swap_step(
+				current,
+				lower,
+				liquidity,
+				1_000_000_000 * UNIT,
+				true,
+				Permill::from_rational::<u32>(3, 1000),
+			)
+			.expect("no overflow");
+
+			assert_eq!(step.sqrt_price, lower);
+			// the whole range's quote reserve is swapped out
+			assert!(step.amount_out > 1_000 * UNIT - 10 && step.amount_out <= 1_000 * UNIT);
+		}
+
+		#[test]
+		fn exact_out_should_not_give_more_than_requested() {
+			let (_, upper, current, liquidity) = range();
+
+			let step = compute_swap_step(
+				current,
+				upper,
+				liquidity,
+				10 * UNIT,
+				false,
+				Permill::from_rational::<u32>(3, 1000),
+			)
+			.expect("no overflow");
+
+			assert_eq!(step.amount_out, 10 * UNIT);
+			assert!(step.sqrt_price > current && step.sqrt_price < upper);
+			assert!(step.amount_in > 10 * UNIT);
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(1_000))]
+
+			#[test]
+			fn exact_in_should_never_take_more_than_remaining(
+				amount in 1..1_000_000_u128,
+				f in 0..10_000_u32,
+				base_for_quote: bool,
+			) {
+				let (lower, upper, current, liquidity) = range();
+				let target = if base_for_quote { lower } else { upper };
+
+				let step = compute_swap_step(
+					current,
+					target,
+					liquidity,
+					amount * UNIT,
+					true,
+					Permill::from_parts(f),
+				)
+				.expect("no values cause overflow");
+
+				prop_assert!(step.amount_in + step.fee <= amount * UNIT);
+			}
+		}
+	}
+}
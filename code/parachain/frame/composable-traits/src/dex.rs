@@ -12,8 +12,9 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 use sp_runtime::{
-	helpers_128bit::multiply_by_rational_with_rounding, traits::Zero, BoundedBTreeMap,
-	DispatchError, Permill, Rational128,
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{AtLeast32Bit, UniqueSaturatedInto, Zero},
+	BoundedBTreeMap, DispatchError, PerThing, Permill, Rational128,
 };
 use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, ops::Mul, vec::Vec};
 
@@ -64,6 +65,9 @@ pub trait Amm {
 
 	fn pool_exists(pool_id: Self::PoolId) -> bool;
 
+	/// Lists at most `limit` of the existing pools, in no particular order.
+	fn pools(limit: u32) -> Vec<Self::PoolId>;
+
 	/// Retrieves the pool assets and their weights.
 	fn assets(pool_id: Self::PoolId) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError>;
 
@@ -299,24 +303,225 @@ pub struct BasicPoolInfo<
 	pub fee_config: FeeConfig,
 }
 
+/// Describes a StableSwap (Curve-style) pool, suited for assets that are expected to trade close
+/// to parity.
+#[derive(
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+	CloneNoBound,
+	Default,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+)]
+#[scale_info(skip_type_params(MaxAssets))]
+pub struct StableSwapPoolInfo<
+	AccountId: Clone + PartialEq + Debug,
+	AssetId: Ord + Clone + Debug,
+	MaxAssets: Get<u32>,
+> {
+	/// Basic pool information. All assets are weighted equally.
+	pub pool_info: BasicPoolInfo<AccountId, AssetId, MaxAssets>,
+	/// Amplification coefficient of the invariant, `A` in the StableSwap whitepaper.
+	/// The higher it is, the flatter the curve around the point of balance.
+	pub amplification_coefficient: u16,
+}
+
+/// State of the sale of a liquidity bootstrapping pool at a given moment.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SaleState {
+	NotStarted,
+	Ongoing,
+	Ended,
+}
+
+/// Sale window of a liquidity bootstrapping pool.
+///
+/// During `[start, end)` the weight of the project asset shifts linearly from `initial_weight` to
+/// `final_weight`. The weight of the other asset is always the complement of it.
+#[derive(
+	Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug,
+)]
+pub struct Sale<Moment> {
+	/// Start of the sale.
+	pub start: Moment,
+	/// End of the sale, after which liquidity can be withdrawn by the owner.
+	pub end: Moment,
+	/// Weight of the project asset at `start`.
+	pub initial_weight: Permill,
+	/// Weight of the project asset at `end`.
+	pub final_weight: Permill,
+}
+
+impl<Moment: AtLeast32Bit + Copy> Sale<Moment> {
+	pub fn duration(&self) -> Moment {
+		self.end.saturating_sub(self.start)
+	}
+
+	pub fn state(&self, now: Moment) -> SaleState {
+		if now < self.start {
+			SaleState::NotStarted
+		} else if now < self.end {
+			SaleState::Ongoing
+		} else {
+			SaleState::Ended
+		}
+	}
+
+	/// Weights of the `(project, other)` assets at `now`.
+	///
+	/// Before the sale starts the initial weights apply, after it ends the final weights.
+	pub fn current_weights(&self, now: Moment) -> (Permill, Permill) {
+		let duration: u128 = self.duration().unique_saturated_into();
+		let elapsed: u128 = now
+			.clamp(self.start, self.end.max(self.start))
+			.saturating_sub(self.start)
+			.unique_saturated_into();
+		let progress = if duration.is_zero() {
+			Permill::one()
+		} else {
+			Permill::from_rational(elapsed, duration)
+		};
+		let initial = self.initial_weight.deconstruct();
+		let final_ = self.final_weight.deconstruct();
+		// `progress <= 1`, so the shift never exceeds the distance between both weights
+		let shift = (Permill::from_parts(initial.abs_diff(final_)) * progress).deconstruct();
+		let weight = if final_ < initial {
+			Permill::from_parts(initial.saturating_sub(shift))
+		} else {
+			Permill::from_parts(initial.saturating_add(shift))
+		};
+		(weight, weight.left_from_one())
+	}
+}
+
+/// Describes a liquidity bootstrapping pool, used for fair price discovery of a newly launched
+/// asset. The pool does not issue LP tokens, only its owner can provide liquidity.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LiquidityBootstrappingPoolInfo<AccountId, AssetId, Moment> {
+	/// Owner of pool
+	pub owner: AccountId,
+	/// `base` is the project asset being sold, `quote` the asset it is sold for.
+	pub pair: CurrencyPair<AssetId>,
+	/// Sale window and weights of the project asset.
+	pub sale: Sale<Moment>,
+	/// Amount of the fee pool charges for the exchange
+	pub fee_config: FeeConfig,
+}
+
+/// Describes a concentrated liquidity pool, in which liquidity is provided within a price range.
+/// Positions are represented as financial NFTs instead of fungible LP tokens.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ConcentratedLiquidityPoolInfo<AccountId, AssetId> {
+	/// Owner of pool
+	pub owner: AccountId,
+	/// Prices are expressed in amount of `quote` per unit of `base`.
+	pub pair: CurrencyPair<AssetId>,
+	/// Only ticks that are a multiple of the spacing can bound a position.
+	pub tick_spacing: u32,
+	/// Financial NFT collection of the pool's positions.
+	pub position_collection: AssetId,
+	/// Amount of the fee pool charges for the exchange
+	pub fee_config: FeeConfig,
+}
+
+#[cfg(test)]
+mod test_sale {
+	use super::*;
+
+	const SALE: Sale<u64> = Sale {
+		start: 100,
+		end: 200,
+		initial_weight: Permill::from_percent(90),
+		final_weight: Permill::from_percent(50),
+	};
+
+	#[test]
+	fn state_follows_the_sale_window() {
+		assert_eq!(SALE.state(99), SaleState::NotStarted);
+		assert_eq!(SALE.state(100), SaleState::Ongoing);
+		assert_eq!(SALE.state(199), SaleState::Ongoing);
+		assert_eq!(SALE.state(200), SaleState::Ended);
+	}
+
+	#[test]
+	fn weights_shift_linearly() {
+		assert_eq!(SALE.current_weights(0), (Permill::from_percent(90), Permill::from_percent(10)));
+		assert_eq!(
+			SALE.current_weights(150),
+			(Permill::from_percent(70), Permill::from_percent(30))
+		);
+		assert_eq!(
+			SALE.current_weights(175),
+			(Permill::from_percent(60), Permill::from_percent(40))
+		);
+		assert_eq!(
+			SALE.current_weights(1_000),
+			(Permill::from_percent(50), Permill::from_percent(50))
+		);
+	}
+
+	#[test]
+	fn weights_can_increase() {
+		let sale = Sale { initial_weight: Permill::from_percent(20), ..SALE };
+		assert_eq!(
+			sale.current_weights(150),
+			(Permill::from_percent(35), Permill::from_percent(65))
+		);
+	}
+}
+
 /// Describes route for DEX.
 /// `Direct` gives vector of pool_id to use as router.
-#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
-pub enum DexRoute<PoolId, MaxHops: Get<u32>> {
+/// `Split` divides the traded amount across several routes, each getting its share.
+#[derive(
+	Encode,
+	Decode,
+	MaxEncodedLen,
+	TypeInfo,
+	CloneNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	RuntimeDebugNoBound,
+)]
+#[scale_info(skip_type_params(MaxHops, MaxSplits))]
+pub enum DexRoute<PoolId: Clone + PartialEq + Debug, MaxHops: Get<u32>, MaxSplits: Get<u32>> {
 	Direct(BoundedVec<PoolId, MaxHops>),
+	Split(BoundedVec<(Permill, BoundedVec<PoolId, MaxHops>), MaxSplits>),
 }
 
-pub trait DexRouter<AssetId, PoolId, Balance, MaxHops> {
+pub trait DexRouter<
+	AssetId,
+	PoolId: Clone + PartialEq + Debug,
+	Balance,
+	MaxHops: Get<u32>,
+	MaxSplits: Get<u32>,
+>
+{
 	/// If route is `None` then delete existing entry for `asset_pair`
 	/// If route is `Some` and no entry exist for `asset_pair` then add new entry
 	/// else update existing entry.
 	fn update_route(
 		asset_pair: CurrencyPair<AssetId>,
-		route: Option<BoundedVec<PoolId, MaxHops>>,
+		route: Option<DexRoute<PoolId, MaxHops, MaxSplits>>,
 	) -> Result<(), DispatchError>;
-	/// If route exist return `Some((Vec<PoolId>, bool))`, else `None`.
+	/// If route exist return `Some((DexRoute, bool))`, else `None`.
 	/// boolean in pair indicates if route needs to be used in reversed direction.
-	fn get_route(asset_pair: CurrencyPair<AssetId>) -> Option<(Vec<PoolId>, bool)>;
+	fn get_route(
+		asset_pair: CurrencyPair<AssetId>,
+	) -> Option<(DexRoute<PoolId, MaxHops, MaxSplits>, bool)>;
+}
+
+/// Route picked by a path finder together with its quote.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct RouteQuote<PoolId, AssetId, Balance> {
+	/// Pools to go through in order, each paired with the asset it pays out.
+	pub hops: Vec<(PoolId, AssetId)>,
+	/// For a swap the expected amount received, for a buy the expected amount paid.
+	pub amount: Balance,
 }
 
 /// Aggregated prices for a given base/quote currency pair in a pool.
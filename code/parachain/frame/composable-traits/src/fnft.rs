@@ -8,7 +8,7 @@ use composable_support::collections::vec::bounded::BiBoundedVec;
 use core::fmt::Debug;
 use frame_support::traits::tokens::nonfungibles::Inspect;
 use scale_info::TypeInfo;
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, DispatchResult};
 use sp_std::vec::Vec;
 
 pub type Key = BiBoundedVec<u8, 1, 64>;
@@ -27,6 +27,31 @@ pub trait FinancialNft<AccountId>: Inspect<AccountId> {
 	fn get_next_nft_id(collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError>;
 }
 
+/// Called on every transfer of a financial NFT, before its ownership changes.
+pub trait FinancialNftTransferHook<AccountId, CollectionId, ItemId> {
+	/// Returning an error prevents the transfer, such as for positions the protocol must keep
+	/// with their owner.
+	fn on_transfer(
+		collection: &CollectionId,
+		instance: &ItemId,
+		from: &AccountId,
+		to: &AccountId,
+	) -> DispatchResult;
+}
+
+impl<AccountId, CollectionId, ItemId> FinancialNftTransferHook<AccountId, CollectionId, ItemId>
+	for ()
+{
+	fn on_transfer(
+		_collection: &CollectionId,
+		_instance: &ItemId,
+		_from: &AccountId,
+		_to: &AccountId,
+	) -> DispatchResult {
+		Ok(())
+	}
+}
+
 /// Trait to be implemented by protocol supporting financial NFTs.
 pub trait FinancialNftProtocol {
 	/// Type for identifying an item.
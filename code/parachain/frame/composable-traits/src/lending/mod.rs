@@ -31,8 +31,19 @@ pub type LendAssetAmountOf<T> = <T as DeFiEngine>::Balance;
 
 pub type BorrowAmountOf<T> = <T as DeFiEngine>::Balance;
 
+/// Restricts the collateral of a market to back only debt in a whitelisted set of assets, up to a
+/// debt ceiling.
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
-pub struct UpdateInput<LiquidationStrategyId, BlockNumber> {
+pub struct IsolationMode<AssetId, Balance> {
+	/// Assets which can be borrowed against the collateral of the market.
+	pub borrowable_assets: Vec<AssetId>,
+	/// Maximum total value, priced by the oracle at borrow time, which can be borrowed against
+	/// the collateral of the market.
+	pub debt_ceiling: Balance,
+}
+
+#[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
+pub struct UpdateInput<LiquidationStrategyId, AssetId, Balance, BlockNumber> {
 	/// Collateral factor of market
 	pub collateral_factor: MoreThanOneFixedU128,
 	/// warn borrower when loan's collateral/debt ratio
@@ -42,15 +53,21 @@ pub struct UpdateInput<LiquidationStrategyId, BlockNumber> {
 	pub liquidators: Vec<LiquidationStrategyId>,
 	/// Count of blocks until throw error PriceIsTooOld
 	pub max_price_age: BlockNumber,
+	/// Maximum total amount of collateral which can be deposited into the market
+	pub supply_cap: Option<Balance>,
+	/// Maximum total amount, excluding interest, which can be borrowed from the market
+	pub borrow_cap: Option<Balance>,
+	/// If set, the collateral of the market is isolated
+	pub isolation: Option<IsolationMode<AssetId, Balance>>,
 }
 
 /// input to create market extrinsic
 ///
 /// Input to [`Lending::create()`].
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
-pub struct CreateInput<LiquidationStrategyId, AssetId, BlockNumber> {
+pub struct CreateInput<LiquidationStrategyId, AssetId, Balance, BlockNumber> {
 	/// the part of market which can be changed
-	pub updatable: UpdateInput<LiquidationStrategyId, BlockNumber>,
+	pub updatable: UpdateInput<LiquidationStrategyId, AssetId, Balance, BlockNumber>,
 	/// collateral currency and borrow currency
 	/// in case of liquidation, collateral is base and borrow is quote
 	pub currency_pair: CurrencyPair<AssetId>,
@@ -59,8 +76,8 @@ pub struct CreateInput<LiquidationStrategyId, AssetId, BlockNumber> {
 	pub interest_rate_model: InterestRateModel,
 }
 
-impl<LiquidationStrategyId, AssetId: Copy, BlockNumber>
-	CreateInput<LiquidationStrategyId, AssetId, BlockNumber>
+impl<LiquidationStrategyId, AssetId: Copy, Balance, BlockNumber>
+	CreateInput<LiquidationStrategyId, AssetId, Balance, BlockNumber>
 {
 	pub fn borrow_asset(&self) -> AssetId {
 		self.currency_pair.quote
@@ -75,7 +92,7 @@ impl<LiquidationStrategyId, AssetId: Copy, BlockNumber>
 }
 
 #[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug)]
-pub struct MarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, BlockNumber> {
+pub struct MarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, Balance, BlockNumber> {
 	/// The owner of this market.
 	pub manager: AccountId,
 	/// The vault containing the borrow asset.
@@ -88,6 +105,53 @@ pub struct MarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, Bloc
 	pub interest_rate_model: InterestRateModel,
 	pub under_collateralized_warn_percent: Percent,
 	pub liquidators: Vec<LiquidationStrategyId>,
+	/// See [`UpdateInput::supply_cap`].
+	pub supply_cap: Option<Balance>,
+	/// See [`UpdateInput::borrow_cap`].
+	pub borrow_cap: Option<Balance>,
+	/// See [`UpdateInput::isolation`].
+	pub isolation: Option<IsolationMode<AssetId, Balance>>,
+	/// See [`CreateInput::reserved_factor`]. The same share of the interest repaid by borrowers
+	/// is kept in the reserves of the market.
+	pub reserved_factor: Perquintill,
+}
+
+/// Solvency of a market, i.e. how much debt was written off by liquidations and how much of the
+/// reserves are available to cover it.
+#[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct MarketSolvency<Balance> {
+	/// Debt, in borrow asset, left uncovered after liquidating the collateral of borrowers.
+	pub bad_debt: Balance,
+	/// Reserve fund of the market, in borrow asset, built from the interest repaid by borrowers.
+	pub reserves: Balance,
+}
+
+/// Position of an account in a market.
+#[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct AccountPosition<Balance> {
+	/// Collateral deposited by the account, in collateral asset.
+	pub collateral: Balance,
+	/// Debt of the account including accrued interest, in borrow asset.
+	pub debt_with_interest: Balance,
+	/// Value the account can still borrow, in normalized currency. See
+	/// [`Lending::get_borrow_limit()`].
+	pub borrow_limit: Balance,
+}
+
+/// Statistics of a market.
+#[derive(Encode, Decode, Default, TypeInfo, RuntimeDebug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+pub struct MarketStats<Balance, Rate> {
+	/// Share of the liquidity of the market which is borrowed.
+	pub utilization: Percent,
+	/// Yearly yield paid by borrowers at the current utilization, compounded as interest accrues.
+	pub borrow_apy: Rate,
+	/// Yearly yield earned by lenders at the current utilization, after the reserve factor.
+	pub supply_apy: Rate,
+	/// See [`MarketSolvency::reserves`].
+	pub total_reserves: Balance,
 }
 
 /// Different ways that a market can be repaid.
@@ -238,14 +302,24 @@ pub trait Lending: DeFiEngine {
 	/// Returned `MarketId` is mapped one to one with (deposit VaultId, collateral VaultId)
 	fn create_market(
 		manager: Self::AccountId,
-		config: CreateInput<Self::LiquidationStrategyId, Self::MayBeAssetId, Self::BlockNumber>,
+		config: CreateInput<
+			Self::LiquidationStrategyId,
+			Self::MayBeAssetId,
+			Self::Balance,
+			Self::BlockNumber,
+		>,
 		keep_alive: bool,
 	) -> Result<(Self::MarketId, Self::VaultId), DispatchError>;
 
 	fn update_market(
 		manager: Self::AccountId,
 		market_id: Self::MarketId,
-		input: UpdateInput<Self::LiquidationStrategyId, Self::BlockNumber>,
+		input: UpdateInput<
+			Self::LiquidationStrategyId,
+			Self::MayBeAssetId,
+			Self::Balance,
+			Self::BlockNumber,
+		>,
 	) -> Result<(), DispatchError>;
 
 	/// [`AccountId`][Self::AccountId] of the market instance
@@ -322,6 +396,20 @@ pub trait Lending: DeFiEngine {
 		keep_alive: bool,
 	) -> Result<BorrowAmountOf<Self>, DispatchError>;
 
+	/// Lends `amount` of the market's borrow asset to `borrower` without collateral, calls
+	/// `callback`, then takes `amount` plus the flash loan fee back from `borrower`. The fee is
+	/// credited to the market's vault.
+	///
+	/// Returns the fee paid. Fails if `callback` fails or `borrower` cannot pay back the loan.
+	///
+	/// NOTE: Must be called in transaction!
+	fn flash_loan(
+		market_id: &Self::MarketId,
+		borrower: &Self::AccountId,
+		amount: BorrowAmountOf<Self>,
+		callback: impl FnOnce() -> DispatchResult,
+	) -> Result<Self::Balance, DispatchError>;
+
 	/// The total amount borrowed from the given market, excluding interest.
 	///
 	/// Can also be though of as the total amount of borrow asset currently lent out by the market.
@@ -1,23 +1,100 @@
 use codec::Encode;
-use sp_runtime::DispatchError;
+use frame_support::weights::Weight;
+use sp_runtime::{DispatchError, DispatchResult};
 use sp_std::vec::*;
 
-use crate::defi::{DeFiEngine, Sell};
+use crate::defi::{CurrencyPair, DeFiEngine, OrderIdLike, Sell};
 
 /// An object from which we can initiate liquidations from.
-/// Does not cares if liquidation was completed or not, neither can reasonably provide that
-/// information. Off-chain can join relevant ids if needed.
+/// Reports how much liquidations fetched to the [`LiquidationCallback`] of the initiator, as they
+/// are filled, completed or expire.
 /// `configuration` - optional list of liquidations strategies
 pub trait Liquidation: DeFiEngine {
-	type OrderId;
+	type OrderId: OrderIdLike;
 	type LiquidationStrategyId;
 
 	/// Initiate a liquidation, this operation should be executed as fast as possible.
+	/// Returns the id of the order placed for the part of `order` which could not be sold
+	/// instantly, if any.
 	fn liquidate(
 		from_to: &Self::AccountId,
 		order: Sell<Self::MayBeAssetId, Self::Balance>,
 		configuration: sp_std::vec::Vec<Self::LiquidationStrategyId>,
-	) -> Result<Self::OrderId, DispatchError>;
+	) -> Result<Option<Self::OrderId>, DispatchError>;
+
+	/// Upper bound of the weight of [`Self::liquidate`], for the initiator to charge for each
+	/// liquidation.
+	fn liquidate_weight() -> Weight;
+}
+
+/// Notified of the outcome of liquidations, so that the protocol which initiated them can
+/// settle the liquidated positions with what they actually fetched.
+///
+/// `order_id` is `None` for the part of a liquidation sold instantly when it is initiated, before
+/// the id of the order placed for the rest of it, if any, is known.
+pub trait LiquidationCallback<AccountId, AssetId, Balance, OrderId> {
+	/// `sold` of the base of `pair` was sold from `from_to` for `received` of its quote, while
+	/// `remaining` of it is left to sell.
+	fn on_fill(
+		from_to: &AccountId,
+		order_id: Option<OrderId>,
+		pair: CurrencyPair<AssetId>,
+		sold: Balance,
+		received: Balance,
+		remaining: Balance,
+	) -> DispatchResult;
+
+	/// The order was sold in full, for `received` of the quote of `pair` in total.
+	fn on_complete(
+		from_to: &AccountId,
+		order_id: OrderId,
+		pair: CurrencyPair<AssetId>,
+		received: Balance,
+	) -> DispatchResult;
+
+	/// The order was removed before being sold in full, for `received` of the quote of `pair` in
+	/// total, and `remaining` of its base was returned to `from_to`.
+	fn on_expire(
+		from_to: &AccountId,
+		order_id: OrderId,
+		pair: CurrencyPair<AssetId>,
+		received: Balance,
+		remaining: Balance,
+	) -> DispatchResult;
+}
+
+impl<AccountId, AssetId, Balance, OrderId> LiquidationCallback<AccountId, AssetId, Balance, OrderId>
+	for ()
+{
+	fn on_fill(
+		_from_to: &AccountId,
+		_order_id: Option<OrderId>,
+		_pair: CurrencyPair<AssetId>,
+		_sold: Balance,
+		_received: Balance,
+		_remaining: Balance,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn on_complete(
+		_from_to: &AccountId,
+		_order_id: OrderId,
+		_pair: CurrencyPair<AssetId>,
+		_received: Balance,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn on_expire(
+		_from_to: &AccountId,
+		_order_id: OrderId,
+		_pair: CurrencyPair<AssetId>,
+		_received: Balance,
+		_remaining: Balance,
+	) -> DispatchResult {
+		Ok(())
+	}
 }
 
 /// generic transaction which can target any pallet and any method in any parachain (local or
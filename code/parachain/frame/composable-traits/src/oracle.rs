@@ -2,7 +2,9 @@ use crate::{
 	currency::LocalAssets,
 	defi::{CurrencyPair, Ratio},
 };
+use codec::FullCodec;
 use frame_support::{dispatch::DispatchError, pallet_prelude::*};
+use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, vec::Vec};
 
 // block timestamped value
 #[derive(Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo, Clone)]
@@ -40,15 +42,16 @@ pub trait Oracle {
 	// type BlockNumber: From<u64>;
 	// type StalePrice: Get<Self::BlockNumber>;
 
-	/// Quote the `amount` of `asset_id` in normalized currency unit cent. Default is USDT Cent.
-	/// Which is 0.01 of USDT. `Result::Err` is returned if `asset_id` not supported or price
-	/// information not available.
+	/// Quote the `amount` of `asset_id` in the smallest unit of the normalized currency. Default is
+	/// USDT. `Result::Err` is returned if `asset_id` not supported or price information not
+	/// available.
 	///
 	/// Returns last price as it known.
 	///
 	/// # Normal assets
 	///
-	/// Assuming we have a price `price` for an unit (not smallest) of `asset_id` in USDT cents.
+	/// Assuming we have a price `price` for an unit (not smallest) of `asset_id` in the smallest
+	/// unit of USDT.
 	/// Let `decimals` be the number of decimals for `asset_id` as given by
 	/// `CurrencyFactory::decimals` The price of an amount `amount` of the smallest possible unit of
 	/// `asset_id` is: `price * amount / 10^decimals`
@@ -57,8 +60,8 @@ pub trait Oracle {
 	/// E.g. for BTC, the price is expressed for 1 BTC, but the amount is in sats:
 	/// 1 BTC = 10^8 sats
 	/// So that:
-	/// `get_price(BTC, 1_00000000) = price(1BTC) * 1_00000000 / 10^8 = $50_000 = 5_000_000 USDT
-	/// cents`
+	/// `get_price(BTC, 1_00000000) = price(1BTC) * 1_00000000 / 10^8 = $50_000 = 50_000 *
+	/// 10^decimals(USDT)`
 	///
 	/// # Diluted assets
 	///
@@ -108,6 +111,11 @@ pub trait Oracle {
 	/// ```
 	fn get_ratio(pair: CurrencyPair<Self::AssetId>) -> Result<Ratio, DispatchError>;
 
+	/// How much of the smallest unit of `quote` one smallest unit of `base` is worth. Unlike
+	/// [`Oracle::get_ratio`], prices are not rounded to integers before dividing them, so that
+	/// prices of less than the smallest unit of the normalized currency keep their precision.
+	fn get_precise_ratio(pair: CurrencyPair<Self::AssetId>) -> Result<Ratio, DispatchError>;
+
 	/// Given `asset_id` and `amount` of price asset.
 	/// Returns what amount of `asset_id` will be required to be same price as `amount` of
 	/// normalized currency
@@ -117,3 +125,59 @@ pub trait Oracle {
 		amount: Self::Balance,
 	) -> Result<Self::Balance, DispatchError>;
 }
+
+/// Pools whose LP tokens can be priced from the prices of the assets they hold.
+pub trait LiquidityPools<AssetId, Balance> {
+	type PoolId: FullCodec + MaxEncodedLen + TypeInfo + Clone + PartialEq + Eq + Debug;
+
+	/// Assets held by the pool.
+	fn pool_assets(pool_id: Self::PoolId) -> Result<Vec<AssetId>, DispatchError>;
+
+	/// Amount of each asset of the pool backing `lp_amount` of its LP token, were the pool at
+	/// equilibrium with `prices`, the price of one unit of balance of each of its assets.
+	///
+	/// Derived from the invariant of the pool rather than from its balances, which trading
+	/// against the pool, e.g. with a flash loan, can skew.
+	fn fair_assets_for_lp_tokens(
+		pool_id: Self::PoolId,
+		lp_amount: Balance,
+		prices: &BTreeMap<AssetId, Ratio>,
+	) -> Result<BTreeMap<AssetId, Balance>, DispatchError>;
+}
+
+/// No pools.
+impl<AssetId, Balance> LiquidityPools<AssetId, Balance> for () {
+	type PoolId = ();
+
+	fn pool_assets(_pool_id: Self::PoolId) -> Result<Vec<AssetId>, DispatchError> {
+		Err(DispatchError::Other("no liquidity pools"))
+	}
+
+	fn fair_assets_for_lp_tokens(
+		_pool_id: Self::PoolId,
+		_lp_amount: Balance,
+		_prices: &BTreeMap<AssetId, Ratio>,
+	) -> Result<BTreeMap<AssetId, Balance>, DispatchError> {
+		Err(DispatchError::Other("no liquidity pools"))
+	}
+}
+
+/// Staking pools whose shares can be priced from the staked asset.
+pub trait StakingShares<AssetId, Balance> {
+	/// Staked asset of the pool issuing `share_asset_id`, and the amount of it backing `amount`
+	/// of shares.
+	fn staked_for_shares(
+		share_asset_id: AssetId,
+		amount: Balance,
+	) -> Result<(AssetId, Balance), DispatchError>;
+}
+
+/// No staking pools.
+impl<AssetId, Balance> StakingShares<AssetId, Balance> for () {
+	fn staked_for_shares(
+		_share_asset_id: AssetId,
+		_amount: Balance,
+	) -> Result<(AssetId, Balance), DispatchError> {
+		Err(DispatchError::Other("no staking pools"))
+	}
+}
@@ -6,7 +6,9 @@ use crate::{
 };
 
 use codec::{Decode, Encode};
-use frame_support::{dispatch::DispatchResult, pallet_prelude::*, BoundedBTreeMap};
+use frame_support::{
+	dispatch::DispatchResult, pallet_prelude::*, traits::LockIdentifier, BoundedBTreeMap,
+};
 use scale_info::TypeInfo;
 use sp_arithmetic::traits::Zero;
 use sp_runtime::{DispatchError, Permill};
@@ -272,6 +274,42 @@ pub trait ProtocolStaking {
 	) -> DispatchResult;
 }
 
+/// Vote weight derived from locked staking positions. The weight of a position decays linearly
+/// with its remaining lock, reaching zero once the lock ends, so that long locks weigh the most.
+///
+/// Voting locks keep the positions of an account with that account while its votes stand, so
+/// that a position cannot be transferred and voted with twice.
+pub trait VotingPower<AccountId, AssetId, Balance> {
+	/// Voting power of `who` from the positions it owns in the pool staking `pool_id`.
+	fn voting_power(who: &AccountId, pool_id: &AssetId) -> Balance;
+
+	/// Voting power of all the positions in the pool staking `pool_id`.
+	fn total_voting_power(pool_id: &AssetId) -> Balance;
+
+	/// Locks the positions `who` owns in the pool staking `pool_id` to its votes under `id`. They
+	/// cannot be transferred nor unstaked until every lock of `who` in the pool is removed.
+	fn set_voting_lock(id: LockIdentifier, who: &AccountId, pool_id: &AssetId);
+
+	/// Removes the lock `id` of `who` in the pool staking `pool_id`, releasing its positions once
+	/// no lock is left.
+	fn remove_voting_lock(id: LockIdentifier, who: &AccountId, pool_id: &AssetId);
+}
+
+/// No voting power.
+impl<AccountId, AssetId, Balance: Zero> VotingPower<AccountId, AssetId, Balance> for () {
+	fn voting_power(_who: &AccountId, _pool_id: &AssetId) -> Balance {
+		Balance::zero()
+	}
+
+	fn total_voting_power(_pool_id: &AssetId) -> Balance {
+		Balance::zero()
+	}
+
+	fn set_voting_lock(_id: LockIdentifier, _who: &AccountId, _pool_id: &AssetId) {}
+
+	fn remove_voting_lock(_id: LockIdentifier, _who: &AccountId, _pool_id: &AssetId) {}
+}
+
 /// Interface for protocol staking.
 pub trait Staking {
 	type AccountId;
@@ -26,6 +26,19 @@ pub const MS_PER_YEAR_NAIVE: DurationSeconds = SECONDS_PER_YEAR_NAIVE * 1000;
 pub enum TimeReleaseFunction {
 	LinearDecrease(LinearDecrease),
 	StairstepExponentialDecrease(StairstepExponentialDecrease),
+	ExponentialDecrease(ExponentialDecrease),
+	PiecewiseLinearDecrease(PiecewiseLinearDecrease),
+}
+
+impl TimeReleaseFunction {
+	pub fn is_valid(&self) -> bool {
+		match self {
+			Self::LinearDecrease(_) => true,
+			Self::StairstepExponentialDecrease(x) => x.step > 0,
+			Self::ExponentialDecrease(x) => x.half_life > 0,
+			Self::PiecewiseLinearDecrease(x) => x.is_valid(),
+		}
+	}
 }
 
 impl Default for TimeReleaseFunction {
@@ -48,3 +61,38 @@ pub struct StairstepExponentialDecrease {
 	// Drop per unit of `step`.
 	pub cut: Permill,
 }
+
+#[derive(Default, Decode, Encode, MaxEncodedLen, Clone, Debug, PartialEq, Eq, TypeInfo)]
+pub struct ExponentialDecrease {
+	/// Seconds it takes to halve the amount, which decreases continuously, not in steps.
+	pub half_life: DurationSeconds,
+}
+
+/// Maximal number of points of a `PiecewiseLinearDecrease`.
+pub const MAX_CURVE_POINTS: u32 = 16;
+
+#[derive(Default, Decode, Encode, MaxEncodedLen, Clone, Debug, PartialEq, Eq, TypeInfo)]
+pub struct CurvePoint {
+	/// Seconds after start
+	pub at: DurationSeconds,
+	/// Share of the initial amount left at `at`
+	pub multiplier: Permill,
+}
+
+/// Starts at the initial amount and goes linearly from point to point, keeping the amount of the
+/// last point after it.
+#[derive(Default, Decode, Encode, MaxEncodedLen, Clone, Debug, PartialEq, Eq, TypeInfo)]
+pub struct PiecewiseLinearDecrease {
+	pub points: BoundedVec<CurvePoint, ConstU32<MAX_CURVE_POINTS>>,
+}
+
+impl PiecewiseLinearDecrease {
+	/// Points must be in time order and must not increase the amount.
+	pub fn is_valid(&self) -> bool {
+		let start = CurvePoint { at: 0, multiplier: Permill::from_percent(100) };
+		!self.points.is_empty() &&
+			core::iter::once(&start).chain(self.points.iter()).zip(self.points.iter()).all(
+				|(previous, next)| previous.at < next.at && previous.multiplier >= next.multiplier,
+			)
+	}
+}
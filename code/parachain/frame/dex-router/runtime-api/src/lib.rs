@@ -0,0 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use composable_support::rpc_helpers::SafeRpcWrapper;
+use composable_traits::dex::RouteQuote;
+
+// DEX Router Runtime API declaration. Implemented for each runtime at
+// `runtime/<runtime-name>/src/lib.rs`.
+sp_api::decl_runtime_apis! {
+	pub trait DexRouterRuntimeApi<PoolId, AssetId, Balance>
+	where
+		PoolId: Codec,
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// Route `swap` takes to exchange `amount` of `in_asset_id` for `out_asset_id`, with the
+		/// amount it is expected to return. `None` if the assets are not connected.
+		fn swap_route(
+			in_asset_id: AssetId,
+			amount: Balance,
+			out_asset_id: AssetId,
+		) -> Option<RouteQuote<SafeRpcWrapper<PoolId>, SafeRpcWrapper<AssetId>, SafeRpcWrapper<Balance>>>;
+	}
+}
@@ -2,10 +2,10 @@ use super::*;
 use crate::{self as pallet_dex_router, Pallet as DexRouter};
 use composable_traits::{
 	defi::CurrencyPair,
-	dex::{Amm, AssetAmount},
+	dex::{Amm, AssetAmount, DexRoute},
 };
 use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
-use frame_support::traits::fungibles::Mutate;
+use frame_support::traits::{fungibles::Mutate, Get};
 use frame_system::RawOrigin;
 use pallet_pablo::PoolInitConfiguration;
 use sp_arithmetic::{PerThing, Permill};
@@ -202,6 +202,64 @@ where
 	(currency_pair, dex_route)
 }
 
+// Creates `count` PICA pools with unrelated assets, which a route search has to explore without
+// being able to use them.
+fn create_unrelated_pools<T>(count: u32)
+where
+	T: pallet_dex_router::Config + pallet_pablo::Config,
+	<T as pallet_pablo::Config>::Balance: From<u128>,
+	<T as pallet_pablo::Config>::AssetId: From<u128>,
+{
+	let unit = 1_000_000_000_000_u128;
+	let owner: <T as frame_system::Config>::AccountId = whitelisted_caller();
+	let pica = 100_u128.into();
+	for i in 0..count as u128 {
+		let asset = (200_u128 + i).into();
+		let config = PoolInitConfiguration::DualAssetConstantProduct {
+			owner: owner.clone(),
+			fee: Permill::zero(),
+			assets_weights: dual_asset_pool_weights::<T>(pica, Permill::from_percent(50), asset),
+		};
+		let pool_id =
+			pallet_pablo::Pallet::<T>::do_create_pool(config, Some((2000_u128 + i).into()))
+				.unwrap();
+		let amount = 1000 * unit;
+		<T as pallet_pablo::Config>::Assets::mint_into(pica, &owner, amount.into())
+			.expect("mint pica failed");
+		<T as pallet_pablo::Config>::Assets::mint_into(asset, &owner, amount.into())
+			.expect("mint asset failed");
+		<pallet_pablo::Pallet<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(pica, amount.into()), (asset, amount.into())]),
+			0_u128.into(),
+			false,
+		)
+		.expect("add_liquidity to unrelated pool failed");
+	}
+}
+
+// Splits a trade equally across `s` copies of `route`, the last one taking the remainder.
+fn split_route<T>(
+	route: Vec<<T as pallet_dex_router::Config>::PoolId>,
+	s: u32,
+) -> DexRoute<
+	<T as pallet_dex_router::Config>::PoolId,
+	<T as pallet_dex_router::Config>::MaxHopsInRoute,
+	<T as pallet_dex_router::Config>::MaxSplitsInRoute,
+>
+where
+	T: pallet_dex_router::Config,
+{
+	let share = Permill::from_rational(1, s);
+	let mut splits = (1..s).map(|_| (share, route.clone().try_into().unwrap())).collect::<Vec<_>>();
+	splits.push((
+		Permill::from_parts(Permill::one().deconstruct() - share.deconstruct() * (s - 1)),
+		route.try_into().unwrap(),
+	));
+	DexRoute::Split(splits.try_into().unwrap())
+}
+
 benchmarks! {
 	impl_benchmark_test_suite!(DexRouter, crate::mock::new_test_ext(), crate::mock::Test);
 
@@ -220,7 +278,7 @@ benchmarks! {
 	update_route {
 		let (currency_pair, dex_route) = create_pools_route::<T>();
 		// let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
-	} : _(RawOrigin::Root, currency_pair, Some(dex_route.try_into().unwrap()))
+	} : _(RawOrigin::Root, currency_pair, Some(DexRoute::Direct(dex_route.try_into().unwrap())))
 
 	swap {
 		let unit = 1_000_000_000_000_u128;
@@ -232,7 +290,7 @@ benchmarks! {
 		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
 		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
 		<T as pallet_pablo::Config>::Assets::mint_into(pica, &owner, pica_amount.into()).expect("Mint pica failed");
-		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(dex_route.try_into().unwrap())).expect("update route failed");
+		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(DexRoute::Direct(dex_route.try_into().unwrap()))).expect("update route failed");
 		// exchange 1000 PICA via route
 	} : _(origin, AssetAmount::new(pica_, (1000_u128 * unit).into()), AssetAmount::new(usdt, 0_u128.into()))
 
@@ -244,12 +302,72 @@ benchmarks! {
 		let origin = RawOrigin::Signed(owner.clone());
 		let usdc : <T as pallet_pablo::Config>::AssetId = 104_u128.into();
 		<T as pallet_pablo::Config>::Assets::mint_into(usdc, &owner, usdc_amount.into()).expect("Mint usdc failed");
-		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(dex_route.try_into().unwrap())).expect("update route failed");
+		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(DexRoute::Direct(dex_route.try_into().unwrap()))).expect("update route failed");
 		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
 		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
 		// buy 100 PICA via route
 	} : _(origin, usdt, AssetAmount::new(pica_, (100_u128 * unit).into()))
 
+	swap_best_route {
+		let p in 0 .. <T as pallet_dex_router::Config>::MaxPoolsInGraph::get().saturating_sub(4);
+		let unit = 1_000_000_000_000_u128;
+		let pica_amount =  2000_u128 * unit;
+		create_pools_route::<T>();
+		create_unrelated_pools::<T>(p);
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let pica : <T as pallet_pablo::Config>::AssetId = 100_u128.into();
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(pica, &owner, pica_amount.into()).expect("Mint pica failed");
+		// exchange 1000 PICA via the best route found
+	} : swap(origin, AssetAmount::new(pica_, (1000_u128 * unit).into()), AssetAmount::new(usdt, 0_u128.into()))
+
+	buy_best_route {
+		let p in 0 .. <T as pallet_dex_router::Config>::MaxPoolsInGraph::get().saturating_sub(4);
+		let unit = 1_000_000_000_000_u128;
+		let usdc_amount =  20_u128 * 100 * unit; // 1 pica = 20 usdc based on liquidity added while pool creation
+		create_pools_route::<T>();
+		create_unrelated_pools::<T>(p);
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let usdc : <T as pallet_pablo::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(usdc, &owner, usdc_amount.into()).expect("Mint usdc failed");
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
+		// buy 100 PICA via the best route found
+	} : buy(origin, usdt, AssetAmount::new(pica_, (100_u128 * unit).into()))
+
+	swap_split {
+		let s in 1 .. <T as pallet_dex_router::Config>::MaxSplitsInRoute::get();
+		let unit = 1_000_000_000_000_u128;
+		let usdt_amount = 100_u128 * unit;
+		let (_, dex_route) = create_pools_route::<T>();
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let usdt : <T as pallet_pablo::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(usdt, &owner, usdt_amount.into()).expect("Mint usdt failed");
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt_ : <T as pallet::Config>::AssetId = 104_u128.into();
+		let route = split_route::<T>(dex_route, s);
+		// exchange 100 USDT for PICA across `s` routes
+	} : _(origin, route, AssetAmount::new(usdt_, usdt_amount.into()), AssetAmount::new(pica_, 0_u128.into()), false)
+
+	buy_split {
+		let s in 1 .. <T as pallet_dex_router::Config>::MaxSplitsInRoute::get();
+		let unit = 1_000_000_000_000_u128;
+		let usdc_amount =  20_u128 * 100 * unit; // 1 pica = 20 usdc based on liquidity added while pool creation
+		let (currency_pair, dex_route) = create_pools_route::<T>();
+		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
+		let origin = RawOrigin::Signed(owner.clone());
+		let usdc : <T as pallet_pablo::Config>::AssetId = 104_u128.into();
+		<T as pallet_pablo::Config>::Assets::mint_into(usdc, &owner, usdc_amount.into()).expect("Mint usdc failed");
+		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(split_route::<T>(dex_route, s))).expect("update route failed");
+		let pica_ : <T as pallet::Config>::AssetId = 100_u128.into();
+		let usdt : <T as pallet::Config>::AssetId = 104_u128.into();
+		// buy 100 PICA across `s` routes
+	} : buy(origin, usdt, AssetAmount::new(pica_, (100_u128 * unit).into()))
+
 	add_liquidity {
 		let unit = 1_000_000_000_000_u128;
 		let owner : <T as frame_system::Config>::AccountId= whitelisted_caller();
@@ -258,7 +376,7 @@ benchmarks! {
 		let usdc_amount = 1000 * unit;
 		let usdt_amount = 1000 * unit;
 		let origin = RawOrigin::Signed(owner);
-		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(dex_route.try_into().unwrap())).expect("update route failed");
+		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(DexRoute::Direct(dex_route.try_into().unwrap()))).expect("update route failed");
 	} : _(origin, BTreeMap::from([(currency_pair.base, usdc_amount.into()), (currency_pair.quote, usdt_amount.into())]), 0_u128.into(), false)
 
 	remove_liquidity {
@@ -269,7 +387,7 @@ benchmarks! {
 		let usdc_amount = 1000 * unit;
 		let usdt_amount = 1000 * unit;
 		let origin = RawOrigin::Signed(owner);
-		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(dex_route.try_into().unwrap())).expect("update route failed");
+		pallet_dex_router::Pallet::<T>::update_route(RawOrigin::Root.into(), currency_pair, Some(DexRoute::Direct(dex_route.try_into().unwrap()))).expect("update route failed");
 		pallet_dex_router::Pallet::<T>::add_liquidity(origin.clone().into(), BTreeMap::from([(currency_pair.base, usdc_amount.into()), (currency_pair.quote, usdt_amount.into())]), 0_u128.into(), false).expect("add_liquidity failed");
 		// remove 1 lp_token
 	} : _(origin, 1_u128.into(), BTreeMap::from([(currency_pair.base, 0.into()), (currency_pair.quote, 0.into())]))
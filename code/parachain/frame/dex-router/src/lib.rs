@@ -2,6 +2,7 @@
 //!
 //! Is used to add route to DEX for given asset_id's pair.
 //! It is required to have permissioned approval of routes.
+//! Pairs without an approved route are routed through the best path found in the pool graph.
 
 #![cfg_attr(not(test), warn(clippy::disallowed_methods, clippy::indexing_slicing))] // allow in tests
 #![warn(clippy::unseparated_literal_suffix, clippy::disallowed_types)]
@@ -29,17 +30,17 @@ pub mod pallet {
 	use codec::{Codec, FullCodec};
 
 	use crate::pool_id_pair;
-	use composable_support::math::safe::SafeArithmetic;
+	use composable_support::math::safe::{SafeAdd, SafeArithmetic, SafeDiv, SafeMul, SafeSub};
 	use composable_traits::{
 		defi::CurrencyPair,
-		dex::{Amm, AssetAmount, DexRoute, DexRouter, SwapResult},
+		dex::{Amm, AssetAmount, DexRoute, DexRouter, RouteQuote, SwapResult},
 	};
 	use core::fmt::Debug;
 	use frame_support::{pallet_prelude::*, transactional, PalletId};
 	use frame_system::{ensure_signed, pallet_prelude::OriginFor};
 	use sp_arithmetic::Permill;
 	use sp_runtime::{
-		traits::{CheckedAdd, One, Zero},
+		traits::{AtLeast32BitUnsigned, CheckedAdd, One, Zero},
 		DispatchResult,
 	};
 	use sp_std::{
@@ -47,6 +48,10 @@ pub mod pallet {
 		vec::Vec,
 	};
 
+	/// Number of times the amount to pay to a pool of a bought route is scaled up before giving
+	/// up on the route.
+	const BUY_QUOTE_ITERATIONS: u32 = 8;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
@@ -67,10 +72,18 @@ pub mod pallet {
 			+ Copy
 			+ Zero
 			+ Ord
+			+ AtLeast32BitUnsigned
 			+ SafeArithmetic;
 		/// The maximum hops in the route.
 		#[pallet::constant]
 		type MaxHopsInRoute: Get<u32> + MaxEncodedLen + TypeInfo;
+		/// The maximum number of pools explored when searching a route for a pair that has none
+		/// configured.
+		#[pallet::constant]
+		type MaxPoolsInGraph: Get<u32>;
+		/// The maximum number of routes a split swap divides its input across.
+		#[pallet::constant]
+		type MaxSplitsInRoute: Get<u32> + MaxEncodedLen + TypeInfo;
 		type PoolId: FullCodec
 			+ MaxEncodedLen
 			+ Default
@@ -99,6 +112,12 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 	}
 
+	pub(crate) type DexRouteOf<T> = DexRoute<
+		<T as Config>::PoolId,
+		<T as Config>::MaxHopsInRoute,
+		<T as Config>::MaxSplitsInRoute,
+	>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(trait Store)]
 	pub struct Pallet<T>(_);
@@ -110,7 +129,7 @@ pub mod pallet {
 		T::AssetId,
 		Blake2_128Concat,
 		T::AssetId,
-		DexRoute<T::PoolId, T::MaxHopsInRoute>,
+		DexRouteOf<T>,
 		OptionQuery,
 	>;
 
@@ -130,6 +149,8 @@ pub mod pallet {
 		LoopSuspectedInRouteUpdate,
 		/// Only dual asset pools supported
 		OnlyDualAssetPoolsSupported,
+		/// Split route shares must be non zero and add up to 100%.
+		InvalidSplitRoute,
 	}
 
 	#[pallet::event]
@@ -138,30 +159,39 @@ pub mod pallet {
 		RouteAdded {
 			x_asset_id: T::AssetId,
 			y_asset_id: T::AssetId,
-			route: Vec<T::PoolId>,
+			route: DexRouteOf<T>,
 		},
 		RouteDeleted {
 			x_asset_id: T::AssetId,
 			y_asset_id: T::AssetId,
-			route: Vec<T::PoolId>,
+			route: DexRouteOf<T>,
 		},
 		RouteUpdated {
 			x_asset_id: T::AssetId,
 			y_asset_id: T::AssetId,
-			old_route: Vec<T::PoolId>,
-			updated_route: Vec<T::PoolId>,
+			old_route: DexRouteOf<T>,
+			updated_route: DexRouteOf<T>,
+		},
+		/// An amount was swapped across the routes of a split route.
+		SplitSwapped {
+			who: T::AccountId,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			out_asset: AssetAmount<T::AssetId, T::Balance>,
+			/// Number of routes the input was divided across.
+			routes: u32,
 		},
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Create, update or remove route.
+		/// A `Split` route divides every trade of the pair across its routes.
 		/// On successful emits one of `RouteAdded`, `RouteUpdated` or `RouteDeleted`.
 		#[pallet::weight(T::WeightInfo::update_route())]
 		pub fn update_route(
 			origin: OriginFor<T>,
 			asset_pair: CurrencyPair<T::AssetId>,
-			route: Option<BoundedVec<T::PoolId, T::MaxHopsInRoute>>,
+			route: Option<DexRouteOf<T>>,
 		) -> DispatchResult {
 			T::UpdateRouteOrigin::ensure_origin(origin)?;
 			<Self as DexRouter<
@@ -169,13 +199,19 @@ pub mod pallet {
 				T::PoolId,
 				T::Balance,
 				T::MaxHopsInRoute,
+				T::MaxSplitsInRoute,
 			>>::update_route(asset_pair, route)?;
 			Ok(())
 		}
 
 		/// Exchange `amount` of quote asset for `asset_pair` via route found in router.
+		/// If no route is configured for the pair, the best route found in the pool graph is used.
 		/// On successful underlying DEX pallets will emit appropriate event
-		#[pallet::weight(T::WeightInfo::swap())]
+		#[pallet::weight(
+			T::WeightInfo::swap()
+				.max(T::WeightInfo::swap_best_route(T::MaxPoolsInGraph::get()))
+				.max(T::WeightInfo::swap_split(T::MaxSplitsInRoute::get()))
+		)]
 		pub fn swap(
 			origin: OriginFor<T>,
 			in_asset: AssetAmount<T::AssetId, T::Balance>,
@@ -193,8 +229,13 @@ pub mod pallet {
 		}
 
 		/// Buy `amount` of quote asset for `asset_pair` via route found in router.
+		/// If no route is configured for the pair, the best route found in the pool graph is used.
 		/// On successful underlying DEX pallets will emit appropriate event.
-		#[pallet::weight(T::WeightInfo::buy())]
+		#[pallet::weight(
+			T::WeightInfo::buy()
+				.max(T::WeightInfo::buy_best_route(T::MaxPoolsInGraph::get()))
+				.max(T::WeightInfo::buy_split(T::MaxSplitsInRoute::get()))
+		)]
 		pub fn buy(
 			origin: OriginFor<T>,
 			in_asset_id: T::AssetId,
@@ -252,9 +293,358 @@ pub mod pallet {
 			<Self as Amm>::remove_liquidity(&who, asset_pair, lp_amount, min_receive)?;
 			Ok(())
 		}
+
+		/// Exchange `in_asset` for at least `min_receive` by dividing it across the routes of
+		/// `route`, each getting its share of the input.
+		/// On successful emits `SplitSwapped` once all routes are swapped.
+		#[pallet::weight(T::WeightInfo::swap_split(T::MaxSplitsInRoute::get()))]
+		pub fn swap_split(
+			origin: OriginFor<T>,
+			route: DexRouteOf<T>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_swap_split(&who, route, in_asset, min_receive, keep_alive)?;
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Route `swap` takes to exchange `in_asset` for `out_asset_id`, along with the amount it
+		/// is expected to return.
+		pub fn swap_route(
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			out_asset_id: T::AssetId,
+		) -> Result<RouteQuote<T::PoolId, T::AssetId, T::Balance>, DispatchError> {
+			match Self::get_route(CurrencyPair::new(out_asset_id, in_asset.asset_id)) {
+				Some((route, reverse)) => {
+					// The hops of a split route are given one route after the other, and its
+					// amount is the sum received across all of them.
+					let mut quote = RouteQuote { hops: Vec::new(), amount: T::Balance::zero() };
+					for (route, amount) in Self::split_amount(route, in_asset.amount)? {
+						if amount.is_zero() {
+							continue
+						}
+						let hops =
+							Self::route_hops(route.into_inner(), reverse, in_asset.asset_id)?;
+						let out_asset = hops.iter().try_fold(
+							AssetAmount::new(in_asset.asset_id, amount),
+							|asset, (pool_id, out_asset_id)| {
+								T::Pablo::spot_price(*pool_id, asset, *out_asset_id, true)
+									.map(|swap_result| swap_result.value)
+							},
+						)?;
+						quote.hops.extend(hops);
+						quote.amount = quote.amount.safe_add(&out_asset.amount)?;
+					}
+					Ok(quote)
+				},
+				None => Self::find_best_route(in_asset, out_asset_id, true),
+			}
+		}
+
+		/// Checks the shares of a split route are non zero and add up to 100%, and returns its
+		/// routes with their shares. A direct route is a single route with the whole share.
+		fn route_splits(
+			route: DexRouteOf<T>,
+		) -> Result<Vec<(Permill, BoundedVec<T::PoolId, T::MaxHopsInRoute>)>, DispatchError> {
+			let splits = match route {
+				DexRoute::Direct(route) => Vec::from([(Permill::one(), route)]),
+				DexRoute::Split(splits) => splits.into_inner(),
+			};
+			let total_share = splits.iter().try_fold(Permill::zero(), |total, (share, _)| {
+				ensure!(!share.is_zero(), Error::<T>::InvalidSplitRoute);
+				total.checked_add(share).ok_or(Error::<T>::InvalidSplitRoute)
+			})?;
+			ensure!(total_share.is_one(), Error::<T>::InvalidSplitRoute);
+			Ok(splits)
+		}
+
+		/// Divides `amount` across the routes of `route` by their shares, the last route taking
+		/// whatever rounding left over.
+		fn split_amount(
+			route: DexRouteOf<T>,
+			amount: T::Balance,
+		) -> Result<Vec<(BoundedVec<T::PoolId, T::MaxHopsInRoute>, T::Balance)>, DispatchError> {
+			let mut remaining = amount;
+			let mut splits = Self::route_splits(route)?.into_iter().peekable();
+			let mut amounts = Vec::new();
+			while let Some((share, route)) = splits.next() {
+				let split_amount = if splits.peek().is_some() {
+					sp_arithmetic::PerThing::mul_floor(share, amount)
+				} else {
+					remaining
+				};
+				remaining = remaining.safe_sub(&split_amount)?;
+				amounts.push((route, split_amount));
+			}
+			Ok(amounts)
+		}
+
+		/// Swaps `in_asset` through `hops`, returning the result of the last pool.
+		fn swap_hops(
+			who: &T::AccountId,
+			hops: Vec<(T::PoolId, T::AssetId)>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			keep_alive: bool,
+		) -> Result<SwapResult<T::AssetId, T::Balance>, DispatchError> {
+			let mut swap_result = SwapResult {
+				value: in_asset,
+				fee: AssetAmount { asset_id: in_asset.asset_id, amount: T::Balance::zero() },
+			};
+			for (pool_id, out_asset_id) in hops {
+				swap_result = T::Pablo::do_swap(
+					who,
+					pool_id,
+					swap_result.value,
+					AssetAmount::new(out_asset_id, T::Balance::zero()),
+					keep_alive,
+				)?;
+			}
+			// TODO (vim): Final fee amount is not correct as the fee need to be incremented with
+			// each swap fee when iterating.
+			Ok(swap_result)
+		}
+
+		/// Buys `out_asset` with `in_asset_id` through `hops`, quoting the route pool by pool
+		/// backwards from the amount wanted.
+		fn buy_hops(
+			who: &T::AccountId,
+			hops: Vec<(T::PoolId, T::AssetId)>,
+			in_asset_id: T::AssetId,
+			out_asset: AssetAmount<T::AssetId, T::Balance>,
+			keep_alive: bool,
+		) -> Result<SwapResult<T::AssetId, T::Balance>, DispatchError> {
+			// Pair each pool with the asset it takes in, to walk the route backwards.
+			let mut pool_in_asset_id = in_asset_id;
+			let pools_in = hops
+				.iter()
+				.map(|(pool_id, out_asset_id)| {
+					let pool_in = (*pool_id, pool_in_asset_id);
+					pool_in_asset_id = *out_asset_id;
+					pool_in
+				})
+				.collect::<Vec<_>>();
+			// Iterate and quote each pool until we reach the `in_asset` amount required
+			let mut in_asset = out_asset;
+			for (pool_id, quote_asset_id) in pools_in.into_iter().rev() {
+				in_asset = AssetAmount::new(
+					quote_asset_id,
+					Self::buy_quote(pool_id, quote_asset_id, in_asset)?,
+				);
+			}
+			Self::swap_hops(who, hops, in_asset, keep_alive)
+		}
+
+		/// Single pool a route configured for `asset_pair` goes through, for the operations only
+		/// supported on such routes.
+		fn single_pool_route(
+			asset_pair: CurrencyPair<T::AssetId>,
+		) -> Result<T::PoolId, DispatchError> {
+			let (route, _reverse) = Self::get_route(asset_pair).ok_or(Error::<T>::NoRouteFound)?;
+			match route {
+				DexRoute::Direct(route) => match route[..] {
+					[pool_id] => Ok(pool_id),
+					_ => Err(Error::<T>::UnsupportedOperation.into()),
+				},
+				DexRoute::Split(_) => Err(Error::<T>::UnsupportedOperation.into()),
+			}
+		}
+
+		/// Expands a configured route into the pools to go through starting from `in_asset_id`,
+		/// each paired with the asset it pays out.
+		fn route_hops(
+			route: Vec<T::PoolId>,
+			reverse: bool,
+			in_asset_id: T::AssetId,
+		) -> Result<Vec<(T::PoolId, T::AssetId)>, DispatchError> {
+			let route: Vec<T::PoolId> =
+				if reverse { route.into_iter().rev().collect() } else { route };
+			let mut asset_id = in_asset_id;
+			route
+				.into_iter()
+				.map(|pool_id| -> Result<_, DispatchError> {
+					let assets = T::Pablo::assets(pool_id)?;
+					ensure!(assets.contains_key(&asset_id), Error::<T>::NoRouteFound);
+					// We only allow dual asset pools in routes, therefore taking the remaining
+					// asset other than `asset_id` gives us the out asset
+					asset_id = assets
+						.keys()
+						.copied()
+						.find(|a| *a != asset_id)
+						.ok_or(Error::<T>::NoRouteFound)?;
+					Ok((pool_id, asset_id))
+				})
+				.collect()
+		}
+
+		/// Amount of `in_asset_id` to swap in `pool_id` for at least `out_asset`.
+		///
+		/// Selling `out_asset` into the pool only estimates the amount, which is then scaled up
+		/// by the shortfall of what it would buy until it buys enough.
+		fn buy_quote(
+			pool_id: T::PoolId,
+			in_asset_id: T::AssetId,
+			out_asset: AssetAmount<T::AssetId, T::Balance>,
+		) -> Result<T::Balance, DispatchError> {
+			let mut amount_in =
+				T::Pablo::spot_price(pool_id, out_asset, in_asset_id, false)?.value.amount;
+			for _ in 0..BUY_QUOTE_ITERATIONS {
+				let bought = T::Pablo::spot_price(
+					pool_id,
+					AssetAmount::new(in_asset_id, amount_in),
+					out_asset.asset_id,
+					true,
+				)?
+				.value
+				.amount;
+				if bought >= out_asset.amount {
+					return Ok(amount_in)
+				}
+				ensure!(!bought.is_zero(), Error::<T>::CanNotRespectMinAmountRequested);
+				amount_in = amount_in
+					.safe_mul(&out_asset.amount)?
+					.safe_div(&bought)?
+					.safe_add(&One::one())?;
+			}
+			Err(Error::<T>::CanNotRespectMinAmountRequested.into())
+		}
+
+		/// Searches the first `MaxPoolsInGraph` pools for the best route of at most
+		/// `MaxHopsInRoute` hops between `start` and `target_asset_id`.
+		///
+		/// With `exact_in`, `start` is the amount put in and the route yielding the most
+		/// `target_asset_id` wins. Otherwise `start` is the amount wanted out, the graph is walked
+		/// backwards selling the amount wanted from each pool into it, and the route estimated to
+		/// require the least `target_asset_id` wins.
+		///
+		/// Each round extends the routes improved in the previous one by a single hop, so the
+		/// number of quotes is bounded by hops times the edges of the graph. A route never goes
+		/// through the same pool or asset twice.
+		pub(crate) fn find_best_route(
+			start: AssetAmount<T::AssetId, T::Balance>,
+			target_asset_id: T::AssetId,
+			exact_in: bool,
+		) -> Result<RouteQuote<T::PoolId, T::AssetId, T::Balance>, DispatchError> {
+			let pools = T::Pablo::pools(T::MaxPoolsInGraph::get())
+				.into_iter()
+				.filter_map(|pool_id| {
+					T::Pablo::assets(pool_id)
+						.ok()
+						.map(|assets| (pool_id, assets.into_keys().collect::<Vec<_>>()))
+				})
+				.collect::<Vec<_>>();
+
+			let mut best = BTreeMap::from([(start.asset_id, (start.amount, Vec::new()))]);
+			let mut frontier = best.clone();
+			for _ in 0..T::MaxHopsInRoute::get() {
+				let mut improved = BTreeMap::<T::AssetId, (T::Balance, Vec<_>)>::new();
+				for (asset_id, (amount, path)) in frontier.iter() {
+					for (pool_id, assets) in pools.iter() {
+						if !assets.contains(asset_id) ||
+							path.iter().any(|(used_pool_id, _)| used_pool_id == pool_id)
+						{
+							continue
+						}
+						for next_asset_id in assets.iter().filter(|next_asset_id| {
+							*next_asset_id != asset_id &&
+								**next_asset_id != start.asset_id &&
+								!path.iter().any(|(_, visited)| visited == *next_asset_id)
+						}) {
+							// Fees only make sense when quoting in the direction of the swap.
+							let quote = match T::Pablo::spot_price(
+								*pool_id,
+								AssetAmount::new(*asset_id, *amount),
+								*next_asset_id,
+								exact_in,
+							) {
+								Ok(swap_result) if !swap_result.value.amount.is_zero() =>
+									swap_result.value.amount,
+								_ => continue,
+							};
+							let is_better = best.get(next_asset_id).map_or(true, |(current, _)| {
+								if exact_in {
+									quote > *current
+								} else {
+									quote < *current
+								}
+							});
+							if is_better {
+								let mut next_path = path.clone();
+								next_path.push((*pool_id, *next_asset_id));
+								best.insert(*next_asset_id, (quote, next_path.clone()));
+								improved.insert(*next_asset_id, (quote, next_path));
+							}
+						}
+					}
+				}
+				if improved.is_empty() {
+					break
+				}
+				frontier = improved;
+			}
+
+			let (amount, path) = best
+				.remove(&target_asset_id)
+				.filter(|(_, path)| !path.is_empty())
+				.ok_or(Error::<T>::NoRouteFound)?;
+			let hops = if exact_in {
+				path
+			} else {
+				// Walked backwards from the asset bought, so each pool pays out the asset it was
+				// reached from.
+				let mut out_asset_id = start.asset_id;
+				let mut hops = path
+					.into_iter()
+					.map(|(pool_id, asset_id)| {
+						let hop = (pool_id, out_asset_id);
+						out_asset_id = asset_id;
+						hop
+					})
+					.collect::<Vec<_>>();
+				hops.reverse();
+				hops
+			};
+			Ok(RouteQuote { hops, amount })
+		}
+
+		#[transactional]
+		pub(crate) fn do_swap_split(
+			who: &T::AccountId,
+			route: DexRouteOf<T>,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			keep_alive: bool,
+		) -> Result<AssetAmount<T::AssetId, T::Balance>, DispatchError> {
+			let currency_pair = CurrencyPair::new(min_receive.asset_id, in_asset.asset_id);
+			let splits = Self::split_amount(route, in_asset.amount)?;
+			let routes = splits.len() as u32;
+			let mut out_amount = T::Balance::zero();
+			for (route, amount) in splits {
+				Self::validate_route(currency_pair, &route)?;
+				let hops = Self::route_hops(route.into_inner(), false, in_asset.asset_id)?;
+				let swap_result = Self::swap_hops(
+					who,
+					hops,
+					AssetAmount::new(in_asset.asset_id, amount),
+					keep_alive,
+				)?;
+				out_amount = out_amount.safe_add(&swap_result.value.amount)?;
+			}
+			ensure!(out_amount >= min_receive.amount, Error::<T>::CanNotRespectMinAmountRequested);
+
+			let out_asset = AssetAmount::new(min_receive.asset_id, out_amount);
+			Self::deposit_event(Event::SplitSwapped {
+				who: who.clone(),
+				in_asset,
+				out_asset,
+				routes,
+			});
+			Ok(out_asset)
+		}
+
 		fn validate_route(
 			asset_pair: CurrencyPair<T::AssetId>,
 			route: &BoundedVec<T::PoolId, T::MaxHopsInRoute>,
@@ -297,9 +687,11 @@ pub mod pallet {
 
 		fn do_update_route(
 			asset_pair: CurrencyPair<T::AssetId>,
-			route: BoundedVec<T::PoolId, T::MaxHopsInRoute>,
+			route: DexRouteOf<T>,
 		) -> Result<(), DispatchError> {
-			Self::validate_route(asset_pair, &route)?;
+			for (_share, split) in Self::route_splits(route.clone())? {
+				Self::validate_route(asset_pair, &split)?;
+			}
 			let existing_route = Self::get_route(asset_pair);
 			let event = match existing_route {
 				Some((old_route, reverse)) => {
@@ -312,16 +704,16 @@ pub mod pallet {
 						x_asset_id: asset_pair.base,
 						y_asset_id: asset_pair.quote,
 						old_route,
-						updated_route: route.to_vec(),
+						updated_route: route.clone(),
 					}
 				},
 				None => Event::RouteAdded {
 					x_asset_id: asset_pair.base,
 					y_asset_id: asset_pair.quote,
-					route: route.to_vec(),
+					route: route.clone(),
 				},
 			};
-			DexRoutes::<T>::insert(asset_pair.base, asset_pair.quote, DexRoute::Direct(route));
+			DexRoutes::<T>::insert(asset_pair.base, asset_pair.quote, route);
 			Self::deposit_event(event);
 			Ok(())
 		}
@@ -345,11 +737,13 @@ pub mod pallet {
 		}
 	}
 
-	impl<T: Config> DexRouter<T::AssetId, T::PoolId, T::Balance, T::MaxHopsInRoute> for Pallet<T> {
+	impl<T: Config>
+		DexRouter<T::AssetId, T::PoolId, T::Balance, T::MaxHopsInRoute, T::MaxSplitsInRoute> for Pallet<T>
+	{
 		#[transactional]
 		fn update_route(
 			asset_pair: CurrencyPair<T::AssetId>,
-			route: Option<BoundedVec<T::PoolId, T::MaxHopsInRoute>>,
+			route: Option<DexRouteOf<T>>,
 		) -> Result<(), DispatchError> {
 			match route {
 				Some(bounded_route) => Self::do_update_route(asset_pair, bounded_route)?,
@@ -360,13 +754,13 @@ pub mod pallet {
 
 		/// Returns pair of route and bool to indicate if route should be used in reverse direction
 		/// with assets swapped.
-		fn get_route(asset_pair: CurrencyPair<T::AssetId>) -> Option<(Vec<T::PoolId>, bool)> {
+		fn get_route(asset_pair: CurrencyPair<T::AssetId>) -> Option<(DexRouteOf<T>, bool)> {
 			DexRoutes::<T>::get(asset_pair.base, asset_pair.quote).map_or_else(
 				|| {
 					DexRoutes::<T>::get(asset_pair.quote, asset_pair.base)
-						.map(|DexRoute::Direct(route)| (route.into_inner(), true))
+						.map(|route| (route, true))
 				},
-				|DexRoute::Direct(route)| Some((route.into_inner(), false)),
+				|route| Some((route, false)),
 			)
 		}
 	}
@@ -382,6 +776,13 @@ pub mod pallet {
 				DexRoutes::<T>::contains_key(pool_id.quote, pool_id.base)
 		}
 
+		fn pools(limit: u32) -> Vec<Self::PoolId> {
+			DexRoutes::<T>::iter_keys()
+				.take(limit as usize)
+				.map(|(base, quote)| CurrencyPair::new(base, quote))
+				.collect()
+		}
+
 		fn assets(
 			pool_id: Self::PoolId,
 		) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
@@ -393,11 +794,8 @@ pub mod pallet {
 		}
 
 		fn lp_token(pool_id: Self::PoolId) -> Result<Self::AssetId, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] => T::Pablo::lp_token(pool_id),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::lp_token(pool_id)
 		}
 
 		fn spot_price(
@@ -406,12 +804,8 @@ pub mod pallet {
 			quote_asset_id: Self::AssetId,
 			calculate_with_fees: bool,
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] =>
-					T::Pablo::spot_price(pool_id, base_asset, quote_asset_id, calculate_with_fees),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::spot_price(pool_id, base_asset, quote_asset_id, calculate_with_fees)
 		}
 
 		fn simulate_add_liquidity(
@@ -419,22 +813,16 @@ pub mod pallet {
 			pool_id: Self::PoolId,
 			amounts: BTreeMap<Self::AssetId, Self::Balance>,
 		) -> Result<Self::Balance, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] => T::Pablo::simulate_add_liquidity(who, pool_id, amounts),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::simulate_add_liquidity(who, pool_id, amounts)
 		}
 
 		fn redeemable_assets_for_lp_tokens(
 			pool_id: Self::PoolId,
 			lp_amount: Self::Balance,
 		) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] => T::Pablo::redeemable_assets_for_lp_tokens(pool_id, lp_amount),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::redeemable_assets_for_lp_tokens(pool_id, lp_amount)
 		}
 
 		fn simulate_remove_liquidity(
@@ -443,12 +831,8 @@ pub mod pallet {
 			lp_amount: Self::Balance,
 			min_amounts: BTreeMap<Self::AssetId, Self::Balance>,
 		) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] =>
-					T::Pablo::simulate_remove_liquidity(who, pool_id, lp_amount, min_amounts),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::simulate_remove_liquidity(who, pool_id, lp_amount, min_amounts)
 		}
 
 		#[transactional]
@@ -460,49 +844,54 @@ pub mod pallet {
 			keep_alive: bool,
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
 			let currency_pair = CurrencyPair::new(min_receive.asset_id, in_asset.asset_id);
-			let (route, reverse) =
-				Self::get_route(currency_pair).ok_or(Error::<T>::NoRouteFound)?;
-			let mut forward_iter;
-			let mut backward_iter;
-			// Iterate forward or backward depending on the reverse flag to find the pools to swap
-			// with.
-			let route_iter: &mut dyn Iterator<Item = &T::PoolId> = if !reverse {
-				forward_iter = route.iter();
-				&mut forward_iter
-			} else {
-				backward_iter = route.iter().rev();
-				&mut backward_iter
-			};
-			// Iterate and swap until we obtain the required asset in the `min_receive.asset_id`
-			let mut in_asset_itr = in_asset;
-			let mut swap_result: SwapResult<T::AssetId, T::Balance> = SwapResult {
-				value: in_asset_itr,
-				fee: AssetAmount { asset_id: in_asset_itr.asset_id, amount: T::Balance::zero() },
-			};
-			for pool_id in route_iter {
-				let assets = T::Pablo::assets(*pool_id)?;
-				// We only allow dual asset pools in routes, therefore taking the remaining asset
-				// other than `in_asset_itr.asset_id` gives us the out_asset_id
-				let out_asset_id = assets
-					.keys()
-					.copied()
-					.find(|a| *a != in_asset_itr.asset_id)
-					.ok_or(Error::<T>::NoRouteFound)?;
-				swap_result = T::Pablo::do_swap(
+			let swap_result = match Self::get_route(currency_pair) {
+				Some((DexRoute::Split(splits), reverse)) => {
+					// Each route of the split swaps its share of the input.
+					let mut received = T::Balance::zero();
+					for (route, amount) in
+						Self::split_amount(DexRoute::Split(splits), in_asset.amount)?
+					{
+						if amount.is_zero() {
+							continue
+						}
+						let hops =
+							Self::route_hops(route.into_inner(), reverse, in_asset.asset_id)?;
+						received = received.safe_add(
+							&Self::swap_hops(
+								who,
+								hops,
+								AssetAmount::new(in_asset.asset_id, amount),
+								keep_alive,
+							)?
+							.value
+							.amount,
+						)?;
+					}
+					SwapResult {
+						value: AssetAmount::new(min_receive.asset_id, received),
+						fee: AssetAmount {
+							asset_id: min_receive.asset_id,
+							amount: T::Balance::zero(),
+						},
+					}
+				},
+				Some((DexRoute::Direct(route), reverse)) => Self::swap_hops(
 					who,
-					*pool_id,
-					in_asset_itr,
-					AssetAmount::new(out_asset_id, T::Balance::zero()),
+					Self::route_hops(route.into_inner(), reverse, in_asset.asset_id)?,
+					in_asset,
 					keep_alive,
-				)?;
-				in_asset_itr = swap_result.value;
-			}
+				)?,
+				None => Self::swap_hops(
+					who,
+					Self::find_best_route(in_asset, min_receive.asset_id, true)?.hops,
+					in_asset,
+					keep_alive,
+				)?,
+			};
 			ensure!(
 				swap_result.value.amount >= min_receive.amount,
 				Error::<T>::CanNotRespectMinAmountRequested
 			);
-			// TODO (vim): Final fee amount is not correct as the fee need to be incremented with
-			// each swap fee when iterating.
 			Ok(swap_result)
 		}
 
@@ -515,64 +904,55 @@ pub mod pallet {
 			keep_alive: bool,
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
 			let currency_pair = CurrencyPair::new(out_asset.asset_id, in_asset_id);
-			let (route, reverse) =
-				Self::get_route(currency_pair).ok_or(Error::<T>::NoRouteFound)?;
-
-			// Iterate and calculate spot price until we reach the `in_asset` amount required
-			let mut forward_iter;
-			let mut backward_iter;
-			let route_iter: &mut dyn Iterator<Item = &T::PoolId> = if !reverse {
-				backward_iter = route.iter().rev();
-				&mut backward_iter
-			} else {
-				forward_iter = route.iter();
-				&mut forward_iter
-			};
-			let mut in_asset_itr = out_asset;
-			let mut in_asset: SwapResult<T::AssetId, T::Balance> = SwapResult {
-				value: in_asset_itr,
-				fee: AssetAmount { asset_id: in_asset_itr.asset_id, amount: T::Balance::zero() },
-			};
-			for pool_id in route_iter {
-				let assets = T::Pablo::assets(*pool_id)?;
-				// We only allow dual asset pools in routes, therefore taking the remaining asset
-				// other than `in_asset_itr.asset_id` gives us the out_asset_id
-				let quote_asset_id = assets
-					.keys()
-					.copied()
-					.find(|a| *a != in_asset_itr.asset_id)
-					.ok_or(Error::<T>::NoRouteFound)?;
-				in_asset = T::Pablo::spot_price(*pool_id, in_asset_itr, quote_asset_id, false)?;
-				in_asset_itr = in_asset.value;
-			}
-
-			// Iterate and swap until we reach the out_asset amount required
-			let route_iter: &mut dyn Iterator<Item = &T::PoolId> = if !reverse {
-				forward_iter = route.iter();
-				&mut forward_iter
-			} else {
-				backward_iter = route.iter().rev();
-				&mut backward_iter
+			let buy_result = match Self::get_route(currency_pair) {
+				Some((route, reverse)) => {
+					// Each route of a split buys its share of the amount wanted.
+					let mut bought = T::Balance::zero();
+					for (route, amount) in Self::split_amount(route, out_asset.amount)? {
+						if amount.is_zero() {
+							continue
+						}
+						let hops = Self::route_hops(route.into_inner(), reverse, in_asset_id)?;
+						bought = bought.safe_add(
+							&Self::buy_hops(
+								who,
+								hops,
+								in_asset_id,
+								AssetAmount::new(out_asset.asset_id, amount),
+								keep_alive,
+							)?
+							.value
+							.amount,
+						)?;
+					}
+					SwapResult {
+						value: AssetAmount::new(out_asset.asset_id, bought),
+						fee: AssetAmount {
+							asset_id: out_asset.asset_id,
+							amount: T::Balance::zero(),
+						},
+					}
+				},
+				None => {
+					// Quotes walking backwards only estimate the amount to pay, so they are used
+					// to size the search in the swap direction, where the route paying out the
+					// most for that amount is the one needing the least.
+					let estimate = Self::find_best_route(out_asset, in_asset_id, false)?;
+					let hops = Self::find_best_route(
+						AssetAmount::new(in_asset_id, estimate.amount),
+						out_asset.asset_id,
+						true,
+					)?
+					.hops;
+					Self::buy_hops(who, hops, in_asset_id, out_asset, keep_alive)?
+				},
 			};
-			let mut out_asset_itr = in_asset;
-			for pool_id in route_iter {
-				let assets = T::Pablo::assets(*pool_id)?;
-				let out_asset_id = assets
-					.keys()
-					.copied()
-					.find(|a| *a != out_asset_itr.value.asset_id)
-					.ok_or(Error::<T>::NoRouteFound)?;
-				out_asset_itr = T::Pablo::do_swap(
-					who,
-					*pool_id,
-					out_asset_itr.value,
-					AssetAmount::new(out_asset_id, T::Balance::zero()),
-					keep_alive,
-				)?;
-			}
-			// TODO (vim): Final fee amount is not correct as the fee need to be incremented with
-			//  each swap fee when iterating.
-			Ok(out_asset_itr)
+			// the route is quoted pool by pool, never pay out less than asked
+			ensure!(
+				buy_result.value.amount >= out_asset.amount,
+				Error::<T>::CanNotRespectMinAmountRequested
+			);
+			Ok(buy_result)
 		}
 
 		#[transactional]
@@ -583,12 +963,8 @@ pub mod pallet {
 			min_mint_amount: Self::Balance,
 			keep_alive: bool,
 		) -> Result<Self::Balance, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] =>
-					T::Pablo::add_liquidity(who, pool_id, assets, min_mint_amount, keep_alive),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::add_liquidity(who, pool_id, assets, min_mint_amount, keep_alive)
 		}
 
 		#[transactional]
@@ -598,11 +974,8 @@ pub mod pallet {
 			lp_amount: Self::Balance,
 			min_receive: BTreeMap<Self::AssetId, Self::Balance>,
 		) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
-			let (route, _reverse) = Self::get_route(pool_id).ok_or(Error::<T>::NoRouteFound)?;
-			match route[..] {
-				[pool_id] => T::Pablo::remove_liquidity(who, pool_id, lp_amount, min_receive),
-				_ => Err(Error::<T>::UnsupportedOperation.into()),
-			}
+			let pool_id = Self::single_pool_route(pool_id)?;
+			T::Pablo::remove_liquidity(who, pool_id, lp_amount, min_receive)
 		}
 	}
 }
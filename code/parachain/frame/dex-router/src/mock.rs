@@ -149,11 +149,14 @@ impl orml_tokens::Config for Test {
 parameter_types! {
 	pub Precision: u128 = 100_u128;
 	pub TestPalletID : PalletId = PalletId(*b"pablo_pa");
-	pub MinSaleDuration: BlockNumber = 3600 / 12;
-	pub MaxSaleDuration: BlockNumber = 30 * 24 * 3600 / 12;
+	pub MinSaleDuration: Moment = 3600 * 1000;
+	pub MaxSaleDuration: Moment = 30 * 24 * 3600 * 1000;
 	pub MaxInitialWeight: Permill = Permill::from_percent(95);
 	pub MinFinalWeight: Permill = Permill::from_percent(5);
+	pub const MaxSwapSteps: u32 = 64;
+	pub const MaxLimitOrdersPerPool: u32 = 16;
 	pub const TWAPInterval: Moment = MILLISECS_PER_BLOCK * TWAP_INTERVAL;
+	pub const MaxAssetsPerPool: u32 = 5;
 }
 
 parameter_types! {
@@ -196,6 +199,8 @@ impl pallet_staking_rewards::Config for Test {
 	type LockId = StakingRewardsLockId;
 	type TreasuryAccount = TreasuryAccountId;
 	type ExistentialDeposits = ExistentialDeposits;
+	type VotingPowerEpoch = frame_support::traits::ConstU64<60>;
+	type MaxVotingLocks = frame_support::traits::ConstU32<8>;
 }
 
 impl pallet_pablo::Config for Test {
@@ -212,12 +217,24 @@ impl pallet_pablo::Config for Test {
 	type EnableTwapOrigin = EnsureRoot<AccountId>;
 	type Time = Timestamp;
 	type TWAPInterval = TWAPInterval;
+	type MaxAssetsPerPool = MaxAssetsPerPool;
+	type MinSaleDuration = MinSaleDuration;
+	type MaxSaleDuration = MaxSaleDuration;
+	type MaxInitialWeight = MaxInitialWeight;
+	type MinFinalWeight = MinFinalWeight;
+	type FinancialNft = MockFnft;
+	type FinancialNftInstanceId = u64;
+	type MaxSwapSteps = MaxSwapSteps;
+	type MaxLimitOrdersPerPool = MaxLimitOrdersPerPool;
 	type WeightInfo = ();
 }
 
 parameter_types! {
 	#[derive(TypeInfo, codec::MaxEncodedLen, codec::Encode)]
 	pub const MaxHopsCount: u32 = 4;
+	#[derive(TypeInfo, codec::MaxEncodedLen, codec::Encode)]
+	pub const MaxSplitsCount: u32 = 4;
+	pub const MaxPoolsInGraph: u32 = 16;
 	// cspell:disable-next
 	pub TestDexRouterPalletID: PalletId = PalletId(*b"dex_rout");
 }
@@ -227,6 +244,8 @@ impl dex_router::Config for Test {
 	type AssetId = AssetId;
 	type Balance = Balance;
 	type MaxHopsInRoute = MaxHopsCount;
+	type MaxPoolsInGraph = MaxPoolsInGraph;
+	type MaxSplitsInRoute = MaxSplitsCount;
 	type PoolId = PoolId;
 	type Pablo = Pablo;
 	type PalletId = TestDexRouterPalletID;
@@ -7,7 +7,7 @@ use composable_tests_helpers::test::{
 };
 use composable_traits::{
 	defi::CurrencyPair,
-	dex::{Amm as AmmTrait, AssetAmount, DexRouter as DexRouterTrait},
+	dex::{Amm as AmmTrait, AssetAmount, DexRoute, DexRouter as DexRouterTrait},
 };
 use frame_support::{
 	assert_noop, assert_ok, bounded_btree_map,
@@ -175,10 +175,16 @@ fn get_route_tests() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.clone().try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.clone().try_into().unwrap()))
 		));
-		assert_eq!(DexRouter::get_route(currency_pair), Some((dex_route.clone(), false)));
-		assert_eq!(DexRouter::get_route(currency_pair.swap()), Some((dex_route, true)));
+		assert_eq!(
+			DexRouter::get_route(currency_pair),
+			Some((DexRoute::Direct(dex_route.clone().try_into().unwrap()), false))
+		);
+		assert_eq!(
+			DexRouter::get_route(currency_pair.swap()),
+			Some((DexRoute::Direct(dex_route.try_into().unwrap()), true))
+		);
 	});
 }
 
@@ -196,7 +202,7 @@ fn update_route_origin_tests() {
 			DexRouter::update_route(
 				RuntimeOrigin::signed(ALICE),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			BadOrigin
 		);
@@ -217,7 +223,7 @@ fn halborn_hal11_route_with_cycle() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			Error::<Test>::LoopSuspectedInRouteUpdate
 		);
@@ -231,7 +237,7 @@ fn halborn_hal11_route_with_cycle() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			Error::<Test>::LoopSuspectedInRouteUpdate,
 		);
@@ -241,7 +247,7 @@ fn halborn_hal11_route_with_cycle() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				CurrencyPair::new(USDC, USDC),
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			Error::<Test>::LoopSuspectedInRouteUpdate,
 		);
@@ -261,18 +267,24 @@ fn update_route_tests() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.clone().try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.clone().try_into().unwrap()))
 		));
-		assert_eq!(DexRouter::get_route(currency_pair), Some((dex_route, false)));
+		assert_eq!(
+			DexRouter::get_route(currency_pair),
+			Some((DexRoute::Direct(dex_route.try_into().unwrap()), false))
+		);
 
 		// update
 		let dex_route = vec![create_dai_eth_pool(), create_usdt_dai_pool()];
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.clone().try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.clone().try_into().unwrap()))
 		));
-		assert_eq!(DexRouter::get_route(currency_pair), Some((dex_route, false)));
+		assert_eq!(
+			DexRouter::get_route(currency_pair),
+			Some((DexRoute::Direct(dex_route.try_into().unwrap()), false))
+		);
 
 		// delete
 		assert_ok!(DexRouter::update_route(RuntimeOrigin::root(), currency_pair, None));
@@ -288,7 +300,7 @@ fn update_route_tests() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			PabloError::<Test>::PoolNotFound,
 		);
@@ -299,7 +311,7 @@ fn update_route_tests() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			Error::<Test>::UnexpectedNodeFoundWhileValidation,
 		);
@@ -310,7 +322,7 @@ fn update_route_tests() {
 			DexRouter::update_route(
 				RuntimeOrigin::root(),
 				currency_pair,
-				Some(dex_route.try_into().unwrap())
+				Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 			),
 			Error::<Test>::UnexpectedNodeFoundWhileValidation,
 		);
@@ -320,7 +332,7 @@ fn update_route_tests() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			CurrencyPair::new(USDT, USDC),
-			Some(dex_route.try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 		));
 	});
 }
@@ -336,7 +348,7 @@ fn exchange_tests() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 		));
 		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 100_000_u128 * unit));
 		// exchange ETH for USDT
@@ -410,7 +422,7 @@ fn buy_test() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 		));
 		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 200_u128 * unit));
 
@@ -434,6 +446,7 @@ fn buy_test() {
 			precision,
 			epsilon
 		));
+		assert!(Tokens::balance(USDT, &CHARLIE) >= 3 * unit);
 		assert_ok!(Tokens::mint_into(USDT, &CHARLIE, 6100_u128 * unit));
 		// buy 1 ETH
 		let dy = <DexRouter as AmmTrait>::do_buy(
@@ -471,7 +484,7 @@ fn unsupported_operation_test() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.try_into().unwrap()))
 		));
 		// EVE adds liquidity to pool via dex-router
 		let eth_amount = 3_u128 * unit;
@@ -513,7 +526,7 @@ fn single_pool_route_test() {
 		assert_ok!(DexRouter::update_route(
 			RuntimeOrigin::root(),
 			currency_pair,
-			Some(dex_route.clone().try_into().unwrap())
+			Some(DexRoute::Direct(dex_route.clone().try_into().unwrap()))
 		));
 		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 3000_u128 * unit));
 		// buy 3000 USDC
@@ -593,3 +606,368 @@ fn single_pool_route_test() {
 		assert_eq!(8999999999999, bob_usdc_amount);
 	});
 }
+
+fn create_balanced_pool(first: AssetId, second: AssetId, amount: Balance, fee: Permill) -> PoolId {
+	create_constant_product_amm_pool(
+		AssetAmountPair {
+			base: AssetAmount { asset_id: first, amount },
+			quote: AssetAmount { asset_id: second, amount },
+		},
+		fee,
+	)
+}
+
+// Pools swapped through by the events emitted so far, in order.
+fn swapped_pools() -> Vec<PoolId> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::Pablo(pallet_pablo::Event::<Test>::Swapped { pool_id, .. }) =>
+				Some(pool_id),
+			_ => None,
+		})
+		.collect()
+}
+
+// A shallow direct ETH/USDT pool without fees next to a deep ETH -> USDC -> USDT route with 1%
+// fees per pool: small trades are best done directly, large ones through the deep route.
+fn create_shallow_and_deep_routes() -> (PoolId, PoolId, PoolId) {
+	let unit = 1_000_000_000_000_u128;
+	let eth_usdt = create_balanced_pool(ETH, USDT, 100 * unit, Permill::zero());
+	let eth_usdc = create_balanced_pool(ETH, USDC, 10_000 * unit, Permill::from_percent(1));
+	let usdc_usdt = create_balanced_pool(USDC, USDT, 10_000 * unit, Permill::from_percent(1));
+	(eth_usdt, eth_usdc, usdc_usdt)
+}
+
+#[test]
+fn swap_route_picks_the_best_path() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+
+		let quote = DexRouter::swap_route(AssetAmount::new(ETH, unit / 100), USDT).unwrap();
+		assert_eq!(quote.hops, vec![(eth_usdt, USDT)]);
+
+		let quote = DexRouter::swap_route(AssetAmount::new(ETH, 50 * unit), USDT).unwrap();
+		assert_eq!(quote.hops, vec![(eth_usdc, USDC), (usdc_usdt, USDT)]);
+
+		assert_noop!(
+			DexRouter::swap_route(AssetAmount::new(ETH, unit), DAI),
+			Error::<Test>::NoRouteFound
+		);
+	});
+}
+
+#[test]
+fn swap_without_configured_route() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (_, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		let quote = DexRouter::swap_route(AssetAmount::new(ETH, 50 * unit), USDT).unwrap();
+
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		assert_noop!(
+			DexRouter::swap(
+				RuntimeOrigin::signed(CHARLIE),
+				AssetAmount::new(ETH, 50 * unit),
+				AssetAmount::new(USDT, quote.amount + 1),
+			),
+			Error::<Test>::CanNotRespectMinAmountRequested
+		);
+		assert_ok!(DexRouter::swap(
+			RuntimeOrigin::signed(CHARLIE),
+			AssetAmount::new(ETH, 50 * unit),
+			AssetAmount::new(USDT, quote.amount),
+		));
+
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), quote.amount);
+		assert_eq!(Tokens::balance(ETH, &CHARLIE), 0);
+		assert_eq!(swapped_pools(), vec![eth_usdc, usdc_usdt]);
+
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, unit));
+		assert_noop!(
+			DexRouter::swap(
+				RuntimeOrigin::signed(CHARLIE),
+				AssetAmount::new(ETH, unit),
+				AssetAmount::new(DAI, 0),
+			),
+			Error::<Test>::NoRouteFound
+		);
+	});
+}
+
+#[test]
+fn buy_without_configured_route() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 100 * unit));
+
+		assert_ok!(DexRouter::buy(
+			RuntimeOrigin::signed(CHARLIE),
+			ETH,
+			AssetAmount::new(USDT, unit / 100)
+		));
+		assert_eq!(swapped_pools(), vec![eth_usdt]);
+
+		assert_ok!(DexRouter::buy(
+			RuntimeOrigin::signed(CHARLIE),
+			ETH,
+			AssetAmount::new(USDT, 50 * unit)
+		));
+		assert_eq!(swapped_pools(), vec![eth_usdt, eth_usdc, usdc_usdt]);
+	});
+}
+
+#[test]
+fn configured_route_takes_precedence() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, ..) = create_shallow_and_deep_routes();
+		assert_ok!(DexRouter::update_route(
+			RuntimeOrigin::root(),
+			CurrencyPair::new(USDT, ETH),
+			Some(DexRoute::Direct(vec![eth_usdt].try_into().unwrap()))
+		));
+
+		let quote = DexRouter::swap_route(AssetAmount::new(ETH, 50 * unit), USDT).unwrap();
+		assert_eq!(quote.hops, vec![(eth_usdt, USDT)]);
+
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		assert_ok!(DexRouter::swap(
+			RuntimeOrigin::signed(CHARLIE),
+			AssetAmount::new(ETH, 50 * unit),
+			AssetAmount::new(USDT, quote.amount),
+		));
+		assert_eq!(swapped_pools(), vec![eth_usdt]);
+	});
+}
+
+#[test]
+fn swap_split_across_routes() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		let direct_out =
+			<Pablo as AmmTrait>::spot_price(eth_usdt, AssetAmount::new(ETH, 10 * unit), USDT, true)
+				.unwrap()
+				.value;
+		let usdc_out =
+			<Pablo as AmmTrait>::spot_price(eth_usdc, AssetAmount::new(ETH, 40 * unit), USDC, true)
+				.unwrap()
+				.value;
+		let deep_out =
+			<Pablo as AmmTrait>::spot_price(usdc_usdt, usdc_out, USDT, true).unwrap().value;
+		let expected = direct_out.amount + deep_out.amount;
+		let route = DexRoute::Split(
+			vec![
+				(Permill::from_percent(20), vec![eth_usdt].try_into().unwrap()),
+				(Permill::from_percent(80), vec![eth_usdc, usdc_usdt].try_into().unwrap()),
+			]
+			.try_into()
+			.unwrap(),
+		);
+
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		assert_noop!(
+			DexRouter::swap_split(
+				RuntimeOrigin::signed(CHARLIE),
+				route.clone(),
+				AssetAmount::new(ETH, 50 * unit),
+				AssetAmount::new(USDT, expected + 1),
+				false,
+			),
+			Error::<Test>::CanNotRespectMinAmountRequested
+		);
+		assert_ok!(DexRouter::swap_split(
+			RuntimeOrigin::signed(CHARLIE),
+			route,
+			AssetAmount::new(ETH, 50 * unit),
+			AssetAmount::new(USDT, expected),
+			false,
+		));
+
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), expected);
+		assert_eq!(Tokens::balance(ETH, &CHARLIE), 0);
+		assert_eq!(swapped_pools(), vec![eth_usdt, eth_usdc, usdc_usdt]);
+		Test::assert_last_event(crate::Event::<Test>::SplitSwapped {
+			who: CHARLIE,
+			in_asset: AssetAmount::new(ETH, 50 * unit),
+			out_asset: AssetAmount::new(USDT, expected),
+			routes: 2,
+		});
+	});
+}
+
+#[test]
+fn configured_split_route_is_used() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		let currency_pair = CurrencyPair::new(USDT, ETH);
+		let route: DexRoute<PoolId, MaxHopsCount, MaxSplitsCount> = DexRoute::Split(
+			vec![
+				(Permill::from_percent(20), vec![eth_usdt].try_into().unwrap()),
+				(Permill::from_percent(80), vec![eth_usdc, usdc_usdt].try_into().unwrap()),
+			]
+			.try_into()
+			.unwrap(),
+		);
+		assert_ok!(DexRouter::update_route(
+			RuntimeOrigin::root(),
+			currency_pair,
+			Some(route.clone())
+		));
+		assert_eq!(DexRouter::get_route(currency_pair), Some((route.clone(), false)));
+		assert_eq!(DexRouter::get_route(currency_pair.swap()), Some((route, true)));
+
+		let direct_out =
+			<Pablo as AmmTrait>::spot_price(eth_usdt, AssetAmount::new(ETH, 10 * unit), USDT, true)
+				.unwrap()
+				.value;
+		let usdc_out =
+			<Pablo as AmmTrait>::spot_price(eth_usdc, AssetAmount::new(ETH, 40 * unit), USDC, true)
+				.unwrap()
+				.value;
+		let deep_out =
+			<Pablo as AmmTrait>::spot_price(usdc_usdt, usdc_out, USDT, true).unwrap().value;
+		let expected = direct_out.amount + deep_out.amount;
+		let quote = DexRouter::swap_route(AssetAmount::new(ETH, 50 * unit), USDT).unwrap();
+		assert_eq!(quote.hops, vec![(eth_usdt, USDT), (eth_usdc, USDC), (usdc_usdt, USDT)]);
+		assert_eq!(quote.amount, expected);
+
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		assert_ok!(DexRouter::swap(
+			RuntimeOrigin::signed(CHARLIE),
+			AssetAmount::new(ETH, 50 * unit),
+			AssetAmount::new(USDT, expected),
+		));
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), expected);
+		assert_eq!(Tokens::balance(ETH, &CHARLIE), 0);
+		assert_eq!(swapped_pools(), vec![eth_usdt, eth_usdc, usdc_usdt]);
+
+		// buying splits the amount wanted across the same routes
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		assert_ok!(DexRouter::buy(
+			RuntimeOrigin::signed(CHARLIE),
+			ETH,
+			AssetAmount::new(USDT, 10 * unit)
+		));
+		assert!(Tokens::balance(USDT, &CHARLIE) >= expected + 10 * unit);
+		assert_eq!(
+			swapped_pools(),
+			vec![eth_usdt, eth_usdc, usdc_usdt, eth_usdt, eth_usdc, usdc_usdt]
+		);
+
+		// single pool operations are not supported on split routes
+		assert_noop!(
+			<DexRouter as AmmTrait>::lp_token(currency_pair),
+			Error::<Test>::UnsupportedOperation
+		);
+	});
+}
+
+#[test]
+fn update_route_rejects_invalid_split_routes() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		let update_route = |splits: Vec<(Permill, Vec<PoolId>)>| {
+			DexRouter::update_route(
+				RuntimeOrigin::root(),
+				CurrencyPair::new(USDT, ETH),
+				Some(DexRoute::Split(
+					splits
+						.into_iter()
+						.map(|(share, route)| (share, route.try_into().unwrap()))
+						.collect::<Vec<_>>()
+						.try_into()
+						.unwrap(),
+				)),
+			)
+		};
+
+		// shares must add up to 100%
+		assert_noop!(
+			update_route(vec![
+				(Permill::from_percent(50), vec![eth_usdt]),
+				(Permill::from_percent(40), vec![eth_usdc, usdc_usdt]),
+			]),
+			Error::<Test>::InvalidSplitRoute
+		);
+		// every route must lead to the requested asset
+		assert_noop!(
+			update_route(vec![
+				(Permill::from_percent(50), vec![eth_usdt]),
+				(Permill::from_percent(50), vec![eth_usdc]),
+			]),
+			Error::<Test>::UnexpectedNodeFoundWhileValidation
+		);
+	});
+}
+
+#[test]
+fn swap_split_rejects_invalid_routes() {
+	new_test_ext().execute_with(|| {
+		next_block::<DexRouter, Test>();
+
+		let unit = 1_000_000_000_000_u128;
+		let (eth_usdt, eth_usdc, usdc_usdt) = create_shallow_and_deep_routes();
+		assert_ok!(Tokens::mint_into(ETH, &CHARLIE, 50 * unit));
+		let swap_split = |splits: Vec<(Permill, Vec<PoolId>)>| {
+			DexRouter::swap_split(
+				RuntimeOrigin::signed(CHARLIE),
+				DexRoute::Split(
+					splits
+						.into_iter()
+						.map(|(share, route)| (share, route.try_into().unwrap()))
+						.collect::<Vec<_>>()
+						.try_into()
+						.unwrap(),
+				),
+				AssetAmount::new(ETH, 50 * unit),
+				AssetAmount::new(USDT, 0),
+				false,
+			)
+		};
+
+		// shares must add up to 100%
+		assert_noop!(
+			swap_split(vec![
+				(Permill::from_percent(50), vec![eth_usdt]),
+				(Permill::from_percent(40), vec![eth_usdc, usdc_usdt]),
+			]),
+			Error::<Test>::InvalidSplitRoute
+		);
+		// every route must get a share
+		assert_noop!(
+			swap_split(vec![
+				(Permill::from_percent(100), vec![eth_usdt]),
+				(Permill::zero(), vec![eth_usdc, usdc_usdt]),
+			]),
+			Error::<Test>::InvalidSplitRoute
+		);
+		assert_noop!(swap_split(vec![]), Error::<Test>::InvalidSplitRoute);
+		// every route must lead to the requested asset
+		assert_noop!(
+			swap_split(vec![
+				(Permill::from_percent(50), vec![eth_usdt]),
+				(Permill::from_percent(50), vec![eth_usdc]),
+			]),
+			Error::<Test>::UnexpectedNodeFoundWhileValidation
+		);
+	});
+}
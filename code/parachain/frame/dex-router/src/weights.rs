@@ -12,6 +12,10 @@ pub trait WeightInfo {
   fn buy() -> Weight;
   fn add_liquidity() -> Weight;
   fn remove_liquidity() -> Weight;
+  fn swap_best_route(p: u32) -> Weight;
+  fn buy_best_route(p: u32) -> Weight;
+  fn swap_split(s: u32) -> Weight;
+  fn buy_split(s: u32) -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -35,4 +39,20 @@ impl WeightInfo for () {
     fn remove_liquidity() -> Weight {
         Weight::from_ref_time(10_000)
     }
+
+    fn swap_best_route(_p: u32) -> Weight {
+        Weight::from_ref_time(10_000)
+    }
+
+    fn buy_best_route(_p: u32) -> Weight {
+        Weight::from_ref_time(10_000)
+    }
+
+    fn swap_split(_s: u32) -> Weight {
+        Weight::from_ref_time(10_000)
+    }
+
+    fn buy_split(_s: u32) -> Weight {
+        Weight::from_ref_time(10_000)
+    }
 }
@@ -86,7 +86,8 @@ benchmarks! {
 		}: _(
 			caller,
 			sell,
-			<_>::default()
+			<_>::default(),
+			None
 		)
 	take {
 		let sell = sell_identity::<T>();
@@ -96,7 +97,7 @@ benchmarks! {
 		mint_native_tokens::<T>(&account_id);
 		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.base, &account_id, amount).unwrap();
 		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.quote, &account_id, amount).unwrap();
-		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default()).unwrap();
+		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default(), None).unwrap();
 		let order_id = OrdersIndex::<T>::get();
 		let take_order = take_identity::<T>();
 		DutchAuction::<T>::take(caller.clone().into(), order_id, take_order.clone()).unwrap();
@@ -112,7 +113,7 @@ benchmarks! {
 		let amount: BalanceOf<T> = 1_000_000_000_000_u64.into();
 		mint_native_tokens::<T>(&account_id);
 		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.base, &account_id, amount).unwrap();
-		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default()).unwrap();
+		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default(), None).unwrap();
 		let order_id = OrdersIndex::<T>::get();
 		}: _(
 			caller,
@@ -145,13 +146,36 @@ benchmarks! {
 		mint_native_tokens::<T>(&account_id);
 		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.base, &account_id, amount).unwrap();
 		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.quote, &account_id, amount).unwrap();
-		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default()).unwrap();
+		DutchAuction::<T>::ask(caller.clone().into(), sell, <_>::default(), None).unwrap();
 		let order_id = OrdersIndex::<T>::get();
 		let take_order = take_identity::<T>();
 		DutchAuction::<T>::take(caller.into(), order_id, take_order).unwrap();
 	} : {
 		<DutchAuction::<T> as Hooks<BlockNumberFor<T>>>::on_finalize(T::BlockNumber::default())
 	}
+	check_floor {
+		let sell = sell_identity::<T>();
+		let account_id: T::AccountId = whitelisted_caller();
+		let caller = RawOrigin::Signed(account_id.clone());
+		let amount: BalanceOf<T> = 1_000_000_000_000_u64.into();
+		mint_native_tokens::<T>(&account_id);
+		<T as pallet::Config>::MultiCurrency::mint_into(sell.pair.base, &account_id, amount).unwrap();
+		let floor = sell.take.limit;
+		DutchAuction::<T>::ask(caller.into(), sell, <_>::default(), Some(floor)).unwrap();
+		let order_id = OrdersIndex::<T>::get();
+		// the price does not move within the benchmark, so the floor is lifted above it instead
+		SellOrders::<T>::mutate(order_id, |sell| {
+			if let Some(sell) = sell {
+				sell.floor = Some(floor.saturating_add(floor));
+			}
+		});
+	} : {
+		let sell = SellOrders::<T>::get(order_id).unwrap();
+		DutchAuction::<T>::check_floor(order_id, sell)
+	}
+	verify {
+		assert!(SellOrders::<T>::get(order_id).is_none());
+	}
 }
 
 impl_benchmark_test_suite!(
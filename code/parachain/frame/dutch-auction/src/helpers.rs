@@ -2,7 +2,8 @@ use crate::{math::*, prelude::*, support::DefiMultiReservableCurrency, types::*}
 pub use crate::{pallet::*, weights::WeightInfo};
 use composable_support::abstractions::utils::increment::Increment;
 use composable_traits::{
-	defi::{DeFiComposableConfig, Sell, SellEngine, Take},
+	defi::{DeFiComposableConfig, LiftedFixedBalance, Sell, SellEngine, Take},
+	liquidation::LiquidationCallback,
 	time::TimeReleaseFunction,
 	xcm::XcmSellInitialResponseTransact,
 };
@@ -11,7 +12,10 @@ use frame_support::{
 	transactional,
 };
 use orml_traits::MultiReservableCurrency;
-use sp_runtime::{traits::AccountIdConversion, DispatchError};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating},
+	DispatchError,
+};
 use sp_std::convert::TryInto;
 use xcm::latest::{prelude::*, MultiAsset, WeightLimit::Unlimited};
 
@@ -21,65 +25,236 @@ impl<T: Config> Pallet<T> {
 		order_id: <T as Config>::OrderId,
 		mut takes: Vec<TakeOf<T>>,
 	) -> Result<(), DispatchError> {
-		<SellOrders<T>>::try_mutate_exists(order_id, |order_item| {
-			if let Some(crate::types::SellOrder {
-				order,
-				context: _,
-				from_to: ref seller,
-				configuration: _,
-				total_amount_received,
-			}) = order_item
-			{
-				let mut amount_received = T::Balance::zero();
-				// users payed N * WEIGHT before, we here pay N * (log N - 1) * Weight. We can
-				// retain pure N by first served principle so, not highest price.
-				takes.sort_by(|a, b| b.take.limit.cmp(&a.take.limit));
-				// calculate real price
-				for take in takes {
-					let quote_amount = take.take.quote_limit_amount()?;
-					// TODO: what to do with orders which nobody ever takes? some kind of dust
-					// orders
-					if order.take.amount == T::Balance::zero() {
-						// bidder was unlucky because order was sol out
-						T::MultiCurrency::unreserve(order.pair.quote, &take.from_to, quote_amount);
-					} else {
-						let take_amount = take.take.amount.min(order.take.amount);
-						order.take.amount -= take_amount;
-						let real_quote_amount = take.take.quote_amount(take_amount)?;
-
-						T::MultiCurrency::exchange_reserved(
-							order.pair.base,
-							seller,
-							take_amount,
-							order.pair.quote,
-							&take.from_to,
-							real_quote_amount,
-						)?;
-						if real_quote_amount < quote_amount {
-							T::MultiCurrency::unreserve(
-								order.pair.quote,
-								&take.from_to,
-								quote_amount - real_quote_amount,
-							);
-						}
-						amount_received += real_quote_amount;
-					}
-				}
+		let mut sell =
+			<SellOrders<T>>::get(order_id).ok_or(Error::<T>::RequestedOrderDoesNotExists)?;
+		let price = Self::current_price(&sell)?;
+		if Self::is_floor_reached(&sell, price) {
+			Self::refund_takes(sell.order.pair.quote, takes);
+			return Self::cancel_floored_order(order_id, sell, price)
+		}
 
-				*total_amount_received += amount_received;
+		let order = &mut sell.order;
+		let seller = &sell.from_to;
+		// users payed N * WEIGHT before, we here pay N * (log N - 1) * Weight. Sort is stable, so
+		// of takes with same limit, the first served wins.
+		takes.sort_by(|a, b| b.take.limit.cmp(&a.take.limit));
+		let clearing_price = Self::clearing_price(price, order.take.amount, &takes);
+		let mut amount_received = T::Balance::zero();
+		let mut amount_sold = T::Balance::zero();
+		for take in takes {
+			let quote_amount = take.take.quote_limit_amount()?;
+			// TODO: what to do with orders which nobody ever takes? some kind of dust
+			// orders
+			if order.take.amount == T::Balance::zero() || take.take.limit < clearing_price {
+				// bidder was unlucky because order was sold out or others bid higher
+				T::MultiCurrency::unreserve(order.pair.quote, &take.from_to, quote_amount);
+			} else {
+				let take_amount = take.take.amount.min(order.take.amount);
+				order.take.amount -= take_amount;
+				let real_quote_amount =
+					Take::new(take_amount, clearing_price).quote_limit_amount()?;
 
-				if order.take.amount == T::Balance::zero() {
-					Self::callback_xcm(order, seller, order_id, *total_amount_received)?;
-					*order_item = None;
-					Self::deposit_event(Event::OrderRemoved { order_id });
+				T::MultiCurrency::exchange_reserved(
+					order.pair.base,
+					seller,
+					take_amount,
+					order.pair.quote,
+					&take.from_to,
+					real_quote_amount,
+				)?;
+				if real_quote_amount < quote_amount {
+					T::MultiCurrency::unreserve(
+						order.pair.quote,
+						&take.from_to,
+						quote_amount - real_quote_amount,
+					);
 				}
+				amount_received += real_quote_amount;
+				amount_sold += take_amount;
+			}
+		}
 
-				if amount_received > T::Balance::zero() {
-					return Ok(())
-				}
+		if amount_sold == T::Balance::zero() {
+			return Ok(())
+		}
+		sell.total_amount_received += amount_received;
+		Self::deposit_event(Event::OrderTaken { order_id, taken: amount_sold });
+		T::LiquidationCallback::on_fill(
+			seller,
+			Some(order_id),
+			order.pair,
+			amount_sold,
+			amount_received,
+			order.take.amount,
+		)?;
+
+		if order.take.amount == T::Balance::zero() {
+			Self::callback_xcm(order, seller, order_id, sell.total_amount_received)?;
+			T::LiquidationCallback::on_complete(
+				seller,
+				order_id,
+				order.pair,
+				sell.total_amount_received,
+			)?;
+			<SellOrders<T>>::remove(order_id);
+			Self::deposit_event(Event::OrderRemoved { order_id });
+		} else {
+			<SellOrders<T>>::insert(order_id, sell);
+		}
+		Ok(())
+	}
+
+	/// Price all takes of the block pay, `takes` sorted from the highest limit.
+	/// It is the auction price, or the lowest limit of the takes buying the order out if they
+	/// compete for more than is sold.
+	fn clearing_price(
+		price: LiftedFixedBalance,
+		amount: T::Balance,
+		takes: &[TakeOf<T>],
+	) -> LiftedFixedBalance {
+		let mut demand = T::Balance::zero();
+		for take in takes.iter().take_while(|take| take.take.limit >= price) {
+			demand = demand.saturating_add(take.take.amount);
+			if demand >= amount {
+				return take.take.limit
 			}
-			Err(Error::<T>::TakeOrderDidNotHappen.into())
-		})
+		}
+		price
+	}
+
+	/// Current auction price of `base` in `quote`.
+	pub fn current_price(sell: &SellOf<T>) -> Result<LiftedFixedBalance, DispatchError> {
+		let passed = T::UnixTime::now().as_secs().saturating_sub(sell.context.added_at);
+		Ok(sell.configuration.price(sell.order.take.limit, passed)?)
+	}
+
+	fn is_floor_reached(sell: &SellOf<T>, price: LiftedFixedBalance) -> bool {
+		sell.floor.map_or(false, |floor| price < floor)
+	}
+
+	fn refund_takes(quote: T::MayBeAssetId, takes: Vec<TakeOf<T>>) {
+		for take in takes {
+			if let Ok(quote_amount) = take.take.quote_limit_amount() {
+				T::MultiCurrency::unreserve(quote, &take.from_to, quote_amount);
+			}
+		}
+	}
+
+	/// Returns the rest of `base` and the deposit to the seller, and takes of the block to the
+	/// takers.
+	pub(crate) fn remove_order(
+		order_id: <T as Config>::OrderId,
+		sell: SellOf<T>,
+	) -> Result<(), DispatchError> {
+		let treasury = &T::PalletId::get().into_account_truncating();
+		T::MultiCurrency::unreserve(sell.order.pair.base, &sell.from_to, sell.order.take.amount);
+		<T::NativeCurrency as NativeTransfer<T::AccountId>>::transfer(
+			treasury,
+			&sell.from_to,
+			sell.context.deposit,
+			false,
+		)?;
+
+		<SellOrders<T>>::remove(order_id);
+		if let Some(takes) = <Takes<T>>::take(order_id) {
+			Self::refund_takes(sell.order.pair.quote, takes);
+		}
+		T::LiquidationCallback::on_expire(
+			&sell.from_to,
+			order_id,
+			sell.order.pair,
+			sell.total_amount_received,
+			sell.order.take.amount,
+		)?;
+		Self::deposit_event(Event::OrderRemoved { order_id });
+		Ok(())
+	}
+
+	#[transactional]
+	fn cancel_floored_order(
+		order_id: <T as Config>::OrderId,
+		sell: SellOf<T>,
+		price: LiftedFixedBalance,
+	) -> Result<(), DispatchError> {
+		Self::deposit_event(Event::OrderFloorReached { order_id, price });
+		Self::remove_order(order_id, sell)
+	}
+
+	/// Cancels `sell` if its auction price went under its floor.
+	pub(crate) fn check_floor(order_id: <T as Config>::OrderId, sell: SellOf<T>) {
+		let price = match Self::current_price(&sell) {
+			Ok(price) if Self::is_floor_reached(&sell, price) => price,
+			_ => return,
+		};
+		if let Err(err) = Self::cancel_floored_order(order_id, sell, price) {
+			log::error!("failed to cancel order {:?} with {:?}", order_id, err);
+		}
+	}
+
+	/// Cancels orders which auction price went under their floor, as many as `remaining_weight`
+	/// allows, continuing from where the previous block stopped.
+	pub(crate) fn cancel_floored_orders(remaining_weight: Weight) -> Weight {
+		let per_order = T::WeightInfo::check_floor();
+		// reading and updating the cursor
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+		let budget =
+			remaining_weight.saturating_sub(weight).ref_time() / per_order.ref_time().max(1);
+		if budget.is_zero() {
+			return Weight::from_ref_time(0)
+		}
+
+		let orders = match FloorCursor::<T>::get() {
+			Some(order_id) => SellOrders::<T>::iter_from(SellOrders::<T>::hashed_key_for(order_id)),
+			None => SellOrders::<T>::iter(),
+		}
+		.take(budget as usize)
+		.collect::<Vec<_>>();
+		match orders.last() {
+			Some((order_id, _)) if orders.len() as u64 == budget => FloorCursor::<T>::put(order_id),
+			// the last order was reached, start over from the first one
+			_ => FloorCursor::<T>::kill(),
+		}
+
+		for (order_id, sell) in orders {
+			weight = weight.saturating_add(per_order);
+			Self::check_floor(order_id, sell);
+		}
+		weight
+	}
+
+	pub(crate) fn do_ask(
+		from_to: &T::AccountId,
+		order: Sell<T::MayBeAssetId, T::Balance>,
+		configuration: TimeReleaseFunction,
+		floor: Option<LiftedFixedBalance>,
+	) -> Result<T::OrderId, DispatchError> {
+		ensure!(order.is_valid(), Error::<T>::OrderParametersIsInvalid,);
+		ensure!(
+			floor.map_or(true, |floor| floor <= order.take.limit),
+			Error::<T>::OrderParametersIsInvalid,
+		);
+		ensure!(configuration.is_valid(), Error::<T>::ConfigurationIsInvalid);
+		let order_id = <OrdersIndex<T>>::increment();
+		let treasury = &T::PalletId::get().into_account_truncating();
+		let deposit = T::PositionExistentialDeposit::get();
+		<T::NativeCurrency as NativeTransfer<T::AccountId>>::transfer(
+			from_to, treasury, deposit, true,
+		)?;
+
+		let now = T::UnixTime::now().as_secs();
+		let order = SellOf::<T> {
+			from_to: from_to.clone(),
+			configuration,
+			order,
+			context: EDContext::<T::Balance> { added_at: now, deposit },
+			total_amount_received: T::Balance::zero(),
+			floor,
+		};
+
+		T::MultiCurrency::reserve(order.order.pair.base, from_to, order.order.take.amount)?;
+		SellOrders::<T>::insert(order_id, order);
+
+		Ok(order_id)
 	}
 
 	pub fn callback_xcm(
@@ -185,27 +360,7 @@ impl<T: Config + DeFiComposableConfig> SellEngine<TimeReleaseFunction> for Palle
 		order: Sell<Self::MayBeAssetId, Self::Balance>,
 		configuration: TimeReleaseFunction,
 	) -> Result<Self::OrderId, DispatchError> {
-		ensure!(order.is_valid(), Error::<T>::OrderParametersIsInvalid,);
-		let order_id = <OrdersIndex<T>>::increment();
-		let treasury = &T::PalletId::get().into_account_truncating();
-		let deposit = T::PositionExistentialDeposit::get();
-		<T::NativeCurrency as NativeTransfer<T::AccountId>>::transfer(
-			from_to, treasury, deposit, true,
-		)?;
-
-		let now = T::UnixTime::now().as_secs();
-		let order = SellOf::<T> {
-			from_to: from_to.clone(),
-			configuration,
-			order,
-			context: EDContext::<Self::Balance> { added_at: now, deposit },
-			total_amount_received: Self::Balance::zero(),
-		};
-
-		T::MultiCurrency::reserve(order.order.pair.base, from_to, order.order.take.amount)?;
-		SellOrders::<T>::insert(order_id, order);
-
-		Ok(order_id)
+		Self::do_ask(from_to, order, configuration, None)
 	}
 
 	fn take(
@@ -216,12 +371,11 @@ impl<T: Config + DeFiComposableConfig> SellEngine<TimeReleaseFunction> for Palle
 		ensure!(take.is_valid(), Error::<T>::TakeParametersIsInvalid,);
 		let order = <SellOrders<T>>::try_get(order_id)
 			.map_err(|_x| Error::<T>::RequestedOrderDoesNotExists)?;
-		ensure!(order.order.take.limit <= take.limit, Error::<T>::TakeLimitDoesNotSatisfyOrder,);
-		let limit = order.order.take.limit;
 		// may consider storing calculation results within single block, so that finalize does
 		// not recalculates
-		let passed = T::UnixTime::now().as_secs() - order.context.added_at;
-		let _limit = order.configuration.price(limit, passed)?;
+		let price = Self::current_price(&order)?;
+		ensure!(!Self::is_floor_reached(&order, price), Error::<T>::OrderPriceBelowFloor);
+		ensure!(price <= take.limit, Error::<T>::TakeLimitDoesNotSatisfyOrder,);
 		let quote_amount = take.quote_limit_amount()?;
 
 		T::MultiCurrency::reserve(order.order.pair.quote, from_to, quote_amount)?;
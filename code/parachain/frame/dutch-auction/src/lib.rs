@@ -7,6 +7,8 @@
 //! Higher takers take first.
 //! Sell(ask) orders stored on chain. Sell takes deposit from seller, returned during take or
 //! liquidation. Takes live only one block.
+//! Sell may have a floor price. When the auction price goes under it, the order is cancelled and
+//! the rest of `base` returned to the seller.
 //!
 //! # Take Sell Order
 //! Allows for best price to win during auction take. as takes are not executed immediately.
@@ -15,6 +17,10 @@
 //! more optimal. So takers appropriate tip to auction, not via transaction tip(not proportional to
 //! price) to parachain. Allows to win bids not by closes to parachain host machine.
 //!
+//! All takes of a block are cleared at the same price, so ordering of takes within block does not
+//! matter. It is the auction price, or the lowest limit of the takes buying the order out when
+//! they ask for more than is sold.
+//!
 //! # Sell Order deposit
 //! Sell takes deposit (as for accounts), to store sells for some time.
 //! We have to store lock deposit value with ask as it can change within time.
@@ -23,6 +29,8 @@
 //! # Price prediction
 //! Dutch action starts with configured price and than and other price value is f(t).
 //! So any external observer can predict what price will be on specified block.
+//! f(t) decreases linearly, in exponential steps, exponentially or linearly between configured
+//! points.
 //!
 //! # DEX
 //! Currently this dutch auction does not tries to sell on external DEX.
@@ -73,6 +81,7 @@ mod benchmarking;
 mod mock;
 
 mod helpers;
+pub mod migration;
 mod prelude;
 mod support;
 mod types;
@@ -96,7 +105,11 @@ pub mod pallet {
 		validation::Validate,
 	};
 	use composable_traits::{
-		defi::{DeFiComposableConfig, DeFiEngine, OrderIdLike, Sell, SellEngine, Take},
+		defi::{
+			DeFiComposableConfig, DeFiEngine, LiftedFixedBalance, OrderIdLike, Sell, SellEngine,
+			Take,
+		},
+		liquidation::LiquidationCallback,
 		time::TimeReleaseFunction,
 		xcm::{ConfigurationId, CumulusMethodId, XcmSellRequest},
 	};
@@ -111,7 +124,7 @@ pub mod pallet {
 		pallet_prelude::{BlockNumberFor, OriginFor},
 	};
 	use orml_traits::{MultiCurrency, MultiReservableCurrency};
-	use sp_runtime::{traits::AccountIdConversion, DispatchError};
+	use sp_runtime::DispatchError;
 	use sp_std::convert::TryInto;
 	use xcm::latest::prelude::*;
 
@@ -157,6 +170,14 @@ pub mod pallet {
 		type AdminOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
 
 		type XcmSender: SendXcm;
+
+		/// Notified of fills, completion and removal of orders.
+		type LiquidationCallback: LiquidationCallback<
+			Self::AccountId,
+			Self::MayBeAssetId,
+			<Self as DeFiComposableConfig>::Balance,
+			Self::OrderId,
+		>;
 	}
 
 	#[pallet::event]
@@ -174,6 +195,11 @@ pub mod pallet {
 		OrderRemoved {
 			order_id: OrderIdOf<T>,
 		},
+		/// raised when auction price went under floor of order, so it is removed
+		OrderFloorReached {
+			order_id: OrderIdOf<T>,
+			price: LiftedFixedBalance,
+		},
 		ConfigurationAdded {
 			configuration_id: ConfigurationId,
 			configuration: TimeReleaseFunction,
@@ -193,10 +219,16 @@ pub mod pallet {
 		XcmCannotDecodeRemoteParametersToLocalRepresentations,
 		XcmCannotFindLocalIdentifiersAsDecodedFromRemote,
 		XcmNotFoundConfigurationById,
+		ConfigurationIsInvalid,
+		OrderPriceBelowFloor,
 	}
 
+	/// The current storage version, see [`crate::migration`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -252,6 +284,10 @@ pub mod pallet {
 	pub type Configurations<T: Config> =
 		StorageMap<_, Twox64Concat, ConfigurationId, TimeReleaseFunction, OptionQuery>;
 
+	/// last order checked for reached floor, the next check starts after it
+	#[pallet::storage]
+	pub type FloorCursor<T: Config> = StorageValue<_, OrderIdOf<T>, OptionQuery>;
+
 	/// one block storage, users payed N * WEIGHT for this Vec, so will not put bound here (neither
 	/// HydraDX does)
 	#[pallet::storage]
@@ -277,25 +313,26 @@ pub mod pallet {
 			configuration: TimeReleaseFunction,
 		) -> DispatchResultWithPostInfo {
 			let _ = T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(configuration.is_valid(), Error::<T>::ConfigurationIsInvalid);
 			Configurations::<T>::insert(configuration_id, configuration.clone());
 			Self::deposit_event(Event::ConfigurationAdded { configuration_id, configuration });
 			Ok(().into())
 		}
 
-		/// sell `order` in auction with `configuration`
+		/// sell `order` in auction with `configuration`, until price goes under `floor`
 		/// some deposit is taken for storing sell order
 		#[pallet::weight(T::WeightInfo::ask())]
 		pub fn ask(
 			origin: OriginFor<T>,
 			order: Sell<T::MayBeAssetId, T::Balance>,
 			configuration: TimeReleaseFunction,
+			floor: Option<LiftedFixedBalance>,
 		) -> DispatchResultWithPostInfo {
 			let who = &(ensure_signed(origin)?);
 
 			let order = SellValid::validate(order)?;
 
-			let order_id =
-				<Self as SellEngine<TimeReleaseFunction>>::ask(who, order, configuration)?;
+			let order_id = Self::do_ask(who, order, configuration, floor)?;
 
 			Self::deposit_event(Event::OrderAdded {
 				order_id,
@@ -327,17 +364,7 @@ pub mod pallet {
 			// we can later allow liquidate old orders(or orders with some block liquidation
 			// timeout set) using kind of account per order is possible, but may risk to
 			// pollute account system
-			let treasury = &T::PalletId::get().into_account_truncating();
-			T::MultiCurrency::unreserve(order.order.pair.base, &who, order.order.take.amount);
-			<T::NativeCurrency as NativeTransfer<T::AccountId>>::transfer(
-				treasury,
-				&order.from_to,
-				order.context.deposit,
-				false,
-			)?;
-
-			<SellOrders<T>>::remove(order_id);
-			Self::deposit_event(Event::OrderRemoved { order_id });
+			Self::remove_order(order_id, order)?;
 
 			Ok(Pays::No.into())
 		}
@@ -399,5 +426,9 @@ pub mod pallet {
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
 			T::WeightInfo::known_overhead_for_on_finalize()
 		}
+
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::cancel_floored_orders(remaining_weight)
+		}
 	}
 }
@@ -5,11 +5,14 @@
 use composable_support::math::safe::{SafeDiv, SafeMul};
 use composable_traits::{
 	defi::LiftedFixedBalance,
-	time::{DurationSeconds, LinearDecrease, StairstepExponentialDecrease, TimeReleaseFunction},
+	time::{
+		DurationSeconds, ExponentialDecrease, LinearDecrease, PiecewiseLinearDecrease,
+		StairstepExponentialDecrease, TimeReleaseFunction,
+	},
 };
 use sp_runtime::{
-	traits::{Saturating, Zero},
-	ArithmeticError, FixedPointNumber,
+	traits::{One, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, PerThing, Permill,
 };
 
 pub trait AuctionTimeCurveModel {
@@ -31,6 +34,10 @@ impl AuctionTimeCurveModel for TimeReleaseFunction {
 			TimeReleaseFunction::LinearDecrease(x) => x.price(initial_price, duration_since_start),
 			TimeReleaseFunction::StairstepExponentialDecrease(x) =>
 				x.price(initial_price, duration_since_start),
+			TimeReleaseFunction::ExponentialDecrease(x) =>
+				x.price(initial_price, duration_since_start),
+			TimeReleaseFunction::PiecewiseLinearDecrease(x) =>
+				x.price(initial_price, duration_since_start),
 		}
 	}
 }
@@ -77,12 +84,78 @@ impl AuctionTimeCurveModel for StairstepExponentialDecrease {
 	}
 }
 
+/// returns: initial_price / 2 ^ (duration_since_start / half_life)
+/// whole halvings are exact, the fraction of a halving left is `1 / e ^ (fraction * ln 2)`
+impl AuctionTimeCurveModel for ExponentialDecrease {
+	fn price(
+		&self,
+		initial_price: LiftedFixedBalance,
+		duration_since_start: DurationSeconds,
+	) -> Result<LiftedFixedBalance, ArithmeticError> {
+		let halvings = duration_since_start.safe_div(&self.half_life)?;
+		if halvings >= u128::BITS.into() {
+			return Ok(LiftedFixedBalance::zero())
+		}
+		let halved = LiftedFixedBalance::from_inner(initial_price.into_inner() >> halvings);
+		let exponent = LiftedFixedBalance::from_inner(LN_2).safe_mul(
+			&LiftedFixedBalance::saturating_from_rational(
+				duration_since_start % self.half_life,
+				self.half_life,
+			),
+		)?;
+		// exponent is less than 1, so the series converges in few terms
+		let mut exp = LiftedFixedBalance::one();
+		let mut term = LiftedFixedBalance::one();
+		for k in 1_u128.. {
+			term = term
+				.safe_mul(&exponent)?
+				.safe_div(&LiftedFixedBalance::saturating_from_integer(k))?;
+			if term.is_zero() {
+				break
+			}
+			exp = exp.saturating_add(term);
+		}
+		halved.safe_div(&exp)
+	}
+}
+
+/// ln 2 with the 18 decimals of `LiftedFixedBalance`
+const LN_2: u128 = 693_147_180_559_945_309;
+
+/// returns: initial_price * multiplier, with multiplier interpolated between the points around
+/// duration_since_start
+impl AuctionTimeCurveModel for PiecewiseLinearDecrease {
+	fn price(
+		&self,
+		initial_price: LiftedFixedBalance,
+		duration_since_start: DurationSeconds,
+	) -> Result<LiftedFixedBalance, ArithmeticError> {
+		let mut from = (0, Permill::from_percent(100).deconstruct());
+		for point in self.points.iter() {
+			let to = (point.at, point.multiplier.deconstruct());
+			if duration_since_start < to.0 {
+				let elapsed = u128::from(duration_since_start - from.0);
+				let drop = u128::from(from.1.saturating_sub(to.1))
+					.safe_mul(&elapsed)?
+					.safe_div(&u128::from(to.0 - from.0))?;
+				let parts = u128::from(from.1).saturating_sub(drop) as u32;
+				return initial_price.safe_mul(&Permill::from_parts(parts).into())
+			}
+			from = to;
+		}
+		initial_price.safe_mul(&Permill::from_parts(from.1).into())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 
 	use composable_traits::{
 		defi::LiftedFixedBalance,
-		time::{DurationSeconds, LinearDecrease, StairstepExponentialDecrease, ONE_HOUR},
+		time::{
+			CurvePoint, DurationSeconds, ExponentialDecrease, LinearDecrease,
+			PiecewiseLinearDecrease, StairstepExponentialDecrease, ONE_HOUR,
+		},
 	};
 
 	use sp_arithmetic::assert_eq_error_rate;
@@ -137,6 +210,45 @@ mod tests {
 		}
 	}
 
+	#[test]
+	pub fn test_smooth_exp_decrease() {
+		let calc = ExponentialDecrease { half_life: 10 };
+		let initial_price = 4000.0;
+
+		for time in 0..=50 {
+			let price: f64 = calc
+				.price(LiftedFixedBalance::from_float(initial_price), time)
+				.unwrap()
+				.to_float();
+			let expected_price = initial_price * 0.5_f64.powf(time as f64 / 10.0);
+			assert_eq_error_rate!(price, expected_price, 0.000_001);
+		}
+		assert_eq!(
+			calc.price(LiftedFixedBalance::from_float(initial_price), 10 * 128).unwrap(),
+			LiftedFixedBalance::zero()
+		);
+	}
+
+	#[test]
+	pub fn test_piecewise_linear_decrease() {
+		let calc = PiecewiseLinearDecrease {
+			points: vec![
+				CurvePoint { at: 100, multiplier: Permill::from_percent(50) },
+				CurvePoint { at: 300, multiplier: Permill::from_percent(40) },
+			]
+			.try_into()
+			.unwrap(),
+		};
+		let initial_price = LiftedFixedBalance::saturating_from_integer(1000);
+		let price = |time| calc.price(initial_price, time).unwrap();
+		assert_eq!(price(0), initial_price);
+		assert_eq!(price(50), 750.into());
+		assert_eq!(price(100), 500.into());
+		assert_eq!(price(200), 450.into());
+		assert_eq!(price(300), 400.into());
+		assert_eq!(price(ONE_HOUR), 400.into());
+	}
+
 	use proptest::{prop_assert, strategy::Strategy, test_runner::TestRunner};
 
 	#[test]
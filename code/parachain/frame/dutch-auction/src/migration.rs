@@ -0,0 +1,60 @@
+//! Storage migrations of the dutch auction pallet.
+
+use crate::{prelude::*, types::*, *};
+use composable_traits::{
+	defi::{DeFiComposableConfig, Sell},
+	time::TimeReleaseFunction,
+};
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::marker::PhantomData;
+
+pub mod v1 {
+	use super::*;
+
+	/// [`SellOrder`] before the floor.
+	#[derive(Encode, Decode)]
+	struct OldSellOrder<AssetId, Balance, AccountId, Context, Configuration> {
+		from_to: AccountId,
+		order: Sell<AssetId, Balance>,
+		configuration: Configuration,
+		context: Context,
+		total_amount_received: Balance,
+	}
+
+	type OldSellOf<T> = OldSellOrder<
+		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as DeFiComposableConfig>::Balance,
+		<T as frame_system::Config>::AccountId,
+		EDContext<<T as DeFiComposableConfig>::Balance>,
+		TimeReleaseFunction,
+	>;
+
+	/// Adds an empty floor to existing sell orders, so they keep running until taken or
+	/// liquidated, and drops the floor cursor stored as a raw key.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut orders = 0_u64;
+			SellOrders::<T>::translate::<OldSellOf<T>, _>(|_, old| {
+				orders += 1;
+				Some(SellOrder {
+					from_to: old.from_to,
+					order: old.order,
+					configuration: old.configuration,
+					context: old.context,
+					total_amount_received: old.total_amount_received,
+					floor: None,
+				})
+			});
+			FloorCursor::<T>::kill();
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(orders.saturating_add(1), orders.saturating_add(2))
+		}
+	}
+}
@@ -143,6 +143,8 @@ pub static ALICE: Public =
 	Public(hex!("0000000000000000000000000000000000000000000000000000000000000000"));
 pub static BOB: Public =
 	Public(hex!("0000000000000000000000000000000000000000000000000000000000000001"));
+pub static CHARLIE: Public =
+	Public(hex!("0000000000000000000000000000000000000000000000000000000000000002"));
 
 ord_parameter_types! {
 	pub const RootAccount: AccountId = ALICE;
@@ -191,6 +193,7 @@ impl pallet_dutch_auction::Config for Runtime {
 	type NativeCurrency = Balances;
 	type AdminOrigin = EnsureOneOf<EnsureRoot<AccountId>, EnsureSignedBy<RootAccount, AccountId>>;
 	type XcmSender = XcmFake;
+	type LiquidationCallback = ();
 	type XcmOrigin = RuntimeOrigin;
 }
 
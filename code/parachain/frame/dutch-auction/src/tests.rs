@@ -3,7 +3,7 @@ use crate::{self as pallet_dutch_auction, weights::*};
 use crate::mock::{currency::*, runtime::*};
 use composable_traits::{
 	defi::{LiftedFixedBalance, Sell, Take},
-	time::{LinearDecrease, TimeReleaseFunction},
+	time::{CurvePoint, LinearDecrease, PiecewiseLinearDecrease, TimeReleaseFunction},
 	xcm::XcmSellRequest,
 };
 use frame_support::{
@@ -13,10 +13,11 @@ use frame_support::{
 		fungibles::{Inspect, Mutate},
 		Hooks,
 	},
+	weights::Weight,
 };
 use orml_traits::MultiReservableCurrency;
 use proptest::prop_assert;
-use sp_runtime::{traits::AccountIdConversion, FixedPointNumber};
+use sp_runtime::{traits::AccountIdConversion, FixedPointNumber, Permill};
 
 fn fixed(n: u128) -> LiftedFixedBalance {
 	LiftedFixedBalance::saturating_from_integer(n)
@@ -84,7 +85,7 @@ fn setup_sell() {
 		let gas = Assets::balance(PICA, &ALICE);
 		let treasury =
 			Assets::balance(PICA, &DutchAuctionPalletId::get().into_account_truncating());
-		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration).unwrap();
+		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration, None).unwrap();
 		let treasury_added =
 			Assets::balance(PICA, &DutchAuctionPalletId::get().into_account_truncating()) -
 				treasury;
@@ -118,7 +119,7 @@ fn with_immediate_exact_buy() {
 		let take_amount = 1000_u128;
 		let sell = Sell::new(BTC, USDT, sell_amount, fixed(take_amount));
 		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 42 });
-		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration).unwrap();
+		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration, None).unwrap();
 		let order_id = crate::OrdersIndex::<Runtime>::get();
 		let result =
 			DutchAuction::take(RuntimeOrigin::signed(buyer), order_id, Take::new(1, fixed(999)));
@@ -151,7 +152,7 @@ fn with_two_takes_higher_than_limit_and_not_enough_for_all() {
 		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 42 });
 
 		let sell = Sell::new(BTC, USDT, sell_amount, fixed(take_amount));
-		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration).unwrap();
+		DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration, None).unwrap();
 		let order_id = crate::OrdersIndex::<Runtime>::get();
 		assert_ok!(DutchAuction::take(
 			RuntimeOrigin::signed(buyer),
@@ -179,7 +180,7 @@ fn liquidation() {
 			let seller = AccountId::from_raw(ALICE.0);
 			let sell = Sell::new(BTC, USDT, 1, fixed(1000));
 			let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 42 });
-			DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration).unwrap();
+			DutchAuction::ask(RuntimeOrigin::signed(seller), sell, configuration, None).unwrap();
 			let order_id = crate::OrdersIndex::<Runtime>::get();
 			let balance_before = <Balances as fungible::Inspect<_>>::balance(&ALICE);
 			DutchAuction::liquidate(RuntimeOrigin::signed(seller), order_id).unwrap();
@@ -195,3 +196,207 @@ fn liquidation() {
 		})
 		.unwrap();
 }
+
+#[test]
+fn takes_of_block_clear_at_same_price() {
+	new_test_externalities().execute_with(|| {
+		Tokens::mint_into(BTC, &ALICE, 2).unwrap();
+		Tokens::mint_into(USDT, &BOB, 10_000).unwrap();
+		Tokens::mint_into(USDT, &CHARLIE, 10_000).unwrap();
+		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 42 });
+		let sell = Sell::new(BTC, USDT, 2, fixed(1000));
+		DutchAuction::ask(RuntimeOrigin::signed(ALICE), sell, configuration, None).unwrap();
+		let order_id = crate::OrdersIndex::<Runtime>::get();
+
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(BOB),
+			order_id,
+			Take::new(1, fixed(1050))
+		));
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(BOB),
+			order_id,
+			Take::new(1, fixed(1200))
+		));
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(CHARLIE),
+			order_id,
+			Take::new(1, fixed(1100))
+		));
+		DutchAuction::on_finalize(42);
+
+		// lowest limit buying the order out
+		assert_eq!(Tokens::balance(USDT, &ALICE), 2 * 1100);
+		assert_eq!(Tokens::balance(USDT, &BOB), 10_000 - 1100);
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), 10_000 - 1100);
+		assert_eq!(Assets::reserved_balance(USDT, &BOB), 0);
+		assert_eq!(Tokens::balance(BTC, &BOB), 1);
+		assert_eq!(Tokens::balance(BTC, &CHARLIE), 1);
+		assert!(crate::SellOrders::<Runtime>::get(order_id).is_none());
+	});
+}
+
+#[test]
+fn takes_not_buying_order_out_pay_auction_price() {
+	new_test_externalities().execute_with(|| {
+		Tokens::mint_into(BTC, &ALICE, 3).unwrap();
+		Tokens::mint_into(USDT, &BOB, 10_000).unwrap();
+		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 100 });
+		let sell = Sell::new(BTC, USDT, 3, fixed(1000));
+		DutchAuction::ask(RuntimeOrigin::signed(ALICE), sell, configuration, None).unwrap();
+		let order_id = crate::OrdersIndex::<Runtime>::get();
+		Timestamp::set_timestamp(Timestamp::now() + 10_000);
+
+		assert_noop!(
+			DutchAuction::take(RuntimeOrigin::signed(BOB), order_id, Take::new(1, fixed(800))),
+			crate::Error::<Runtime>::TakeLimitDoesNotSatisfyOrder,
+		);
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(BOB),
+			order_id,
+			Take::new(1, fixed(1200))
+		));
+		DutchAuction::on_finalize(42);
+
+		assert_eq!(Tokens::balance(USDT, &ALICE), 900);
+		assert_eq!(Tokens::balance(USDT, &BOB), 10_000 - 900);
+		assert_eq!(crate::SellOrders::<Runtime>::get(order_id).unwrap().order.take.amount, 2);
+	});
+}
+
+#[test]
+fn order_under_floor_is_cancelled() {
+	new_test_externalities().execute_with(|| {
+		Tokens::mint_into(BTC, &ALICE, 1).unwrap();
+		Tokens::mint_into(USDT, &BOB, 10_000).unwrap();
+		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 100 });
+		let sell = Sell::new(BTC, USDT, 1, fixed(1000));
+		assert_noop!(
+			DutchAuction::ask(
+				RuntimeOrigin::signed(ALICE),
+				sell.clone(),
+				configuration.clone(),
+				Some(fixed(1001))
+			),
+			crate::Error::<Runtime>::OrderParametersIsInvalid,
+		);
+		DutchAuction::ask(RuntimeOrigin::signed(ALICE), sell, configuration, Some(fixed(500)))
+			.unwrap();
+		let order_id = crate::OrdersIndex::<Runtime>::get();
+
+		DutchAuction::on_idle(42, Weight::MAX);
+		assert!(crate::SellOrders::<Runtime>::get(order_id).is_some());
+
+		Timestamp::set_timestamp(Timestamp::now() + 60_000);
+		assert_noop!(
+			DutchAuction::take(RuntimeOrigin::signed(BOB), order_id, Take::new(1, fixed(1000))),
+			crate::Error::<Runtime>::OrderPriceBelowFloor,
+		);
+		DutchAuction::on_idle(42, Weight::MAX);
+
+		assert!(crate::SellOrders::<Runtime>::get(order_id).is_none());
+		assert_eq!(Assets::reserved_balance(BTC, &ALICE), 0);
+		assert_eq!(Tokens::balance(BTC, &ALICE), 1);
+		System::assert_has_event(RuntimeEvent::DutchAuction(crate::Event::OrderFloorReached {
+			order_id,
+			price: fixed(400),
+		}));
+	});
+}
+
+#[test]
+fn takes_under_clearing_price_are_refunded() {
+	new_test_externalities().execute_with(|| {
+		Tokens::mint_into(BTC, &ALICE, 1).unwrap();
+		Tokens::mint_into(USDT, &BOB, 10_000).unwrap();
+		Tokens::mint_into(USDT, &CHARLIE, 10_000).unwrap();
+		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 42 });
+		let sell = Sell::new(BTC, USDT, 1, fixed(1000));
+		DutchAuction::ask(RuntimeOrigin::signed(ALICE), sell, configuration, None).unwrap();
+		let order_id = crate::OrdersIndex::<Runtime>::get();
+
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(CHARLIE),
+			order_id,
+			Take::new(1, fixed(1100))
+		));
+		assert_ok!(DutchAuction::take(
+			RuntimeOrigin::signed(BOB),
+			order_id,
+			Take::new(2, fixed(1200))
+		));
+		assert_eq!(Assets::reserved_balance(USDT, &CHARLIE), 1100);
+		DutchAuction::on_finalize(42);
+
+		// the higher take buys the order out and gets back the quote of the unit not sold
+		assert_eq!(Tokens::balance(USDT, &ALICE), 1200);
+		assert_eq!(Tokens::balance(USDT, &BOB), 10_000 - 1200);
+		assert_eq!(Assets::reserved_balance(USDT, &BOB), 0);
+		assert_eq!(Tokens::balance(BTC, &BOB), 1);
+		// the take under the clearing price is refunded in full
+		assert_eq!(Tokens::balance(USDT, &CHARLIE), 10_000);
+		assert_eq!(Assets::reserved_balance(USDT, &CHARLIE), 0);
+		assert_eq!(Tokens::balance(BTC, &CHARLIE), 0);
+	});
+}
+
+#[test]
+fn floored_orders_are_cancelled_across_blocks() {
+	new_test_externalities().execute_with(|| {
+		Tokens::mint_into(BTC, &ALICE, 3).unwrap();
+		let configuration = TimeReleaseFunction::LinearDecrease(LinearDecrease { total: 100 });
+		let orders = (0..3)
+			.map(|_| {
+				let sell = Sell::new(BTC, USDT, 1, fixed(1000));
+				DutchAuction::ask(
+					RuntimeOrigin::signed(ALICE),
+					sell,
+					configuration.clone(),
+					Some(fixed(500)),
+				)
+				.unwrap();
+				crate::OrdersIndex::<Runtime>::get()
+			})
+			.collect::<Vec<_>>();
+		let live = || {
+			orders
+				.iter()
+				.filter(|order_id| crate::SellOrders::<Runtime>::contains_key(order_id))
+				.count()
+		};
+		Timestamp::set_timestamp(Timestamp::now() + 60_000);
+
+		// room for two orders per block
+		let check_floor =
+			<Runtime as pallet_dutch_auction::Config>::WeightInfo::check_floor().ref_time();
+		let remaining_weight = Weight::from_ref_time(2 * check_floor);
+		DutchAuction::on_idle(42, remaining_weight);
+		assert_eq!(live(), 1);
+		assert!(crate::FloorCursor::<Runtime>::get().is_some());
+
+		// next block resumes after the cursor, even though its order is gone
+		DutchAuction::on_idle(43, remaining_weight);
+		assert_eq!(live(), 0);
+		assert!(crate::FloorCursor::<Runtime>::get().is_none());
+		assert_eq!(Assets::reserved_balance(BTC, &ALICE), 0);
+		assert_eq!(Tokens::balance(BTC, &ALICE), 3);
+	});
+}
+
+#[test]
+fn invalid_curve_is_rejected() {
+	new_test_externalities().execute_with(|| {
+		let configuration = TimeReleaseFunction::PiecewiseLinearDecrease(PiecewiseLinearDecrease {
+			points: vec![
+				CurvePoint { at: 100, multiplier: Permill::from_percent(50) },
+				CurvePoint { at: 200, multiplier: Permill::from_percent(60) },
+			]
+			.try_into()
+			.unwrap(),
+		});
+		assert_noop!(
+			DutchAuction::add_configuration(RuntimeOrigin::root(), 1, configuration),
+			crate::Error::<Runtime>::ConfigurationIsInvalid,
+		);
+	});
+}
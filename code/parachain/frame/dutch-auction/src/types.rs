@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 use composable_traits::{
-	defi::{Sell, Take},
+	defi::{LiftedFixedBalance, Sell, Take},
 	time::Timestamp,
 };
 
@@ -15,6 +15,8 @@ pub struct SellOrder<AssetId, Balance, AccountId, Context, Configuration> {
 	pub context: Context,
 	/// amount of `quote` received up to now
 	pub total_amount_received: Balance,
+	/// price under which the order is cancelled and the rest of `base` returned, if any
+	pub floor: Option<LiftedFixedBalance>,
 }
 
 /// existential deposit context with date of creation
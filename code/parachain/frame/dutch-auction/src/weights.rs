@@ -10,6 +10,7 @@ pub trait WeightInfo {
 	fn liquidate() -> Weight;
 	fn xcm_sell() -> Weight;
 	fn known_overhead_for_on_finalize() -> Weight;
+	fn check_floor() -> Weight;
 }
 
 /// Weight functions for `dutch_auction`.
@@ -69,4 +70,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(7_u64))
 			.saturating_add(T::DbWeight::get().writes(6_u64))
 	}
+	// Storage: DutchAuction SellOrders (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: DutchAuction Takes (r:1 w:1)
+	fn check_floor() -> Weight {
+		Weight::from_ref_time(38_412_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
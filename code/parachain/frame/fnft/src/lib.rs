@@ -51,7 +51,7 @@ pub mod pallet {
 	use composable_traits::{
 		account_proxy::AccountProxy,
 		currency::AssetIdLike,
-		fnft::{FinancialNft, FnftAccountProxyTypeSelector},
+		fnft::{FinancialNft, FinancialNftTransferHook, FnftAccountProxyTypeSelector},
 	};
 	use core::fmt::Debug;
 	use frame_support::{
@@ -145,6 +145,13 @@ pub mod pallet {
 
 		type ProxyTypeSelector: FnftAccountProxyTypeSelector<Self::ProxyType>;
 
+		/// Called on every transfer, which fails if it does.
+		type OnTransfer: FinancialNftTransferHook<
+			Self::AccountId,
+			Self::FinancialNftCollectionId,
+			Self::FinancialNftInstanceId,
+		>;
+
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
 
@@ -221,9 +228,7 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			let fnfts = OwnerInstances::<T>::get(&who).ok_or(Error::<T>::MustBeOwner)?;
 			ensure!(fnfts.contains(&(collection, instance)), Error::<T>::MustBeOwner);
-			let _ =
-				<Self as Transfer<AccountIdOf<T>>>::transfer(&collection, &instance, &destination);
-			Ok(())
+			<Self as Transfer<AccountIdOf<T>>>::transfer(&collection, &instance, &destination)
 		}
 	}
 
@@ -324,6 +329,8 @@ pub mod pallet {
 		) -> DispatchResult {
 			Instance::<T>::try_mutate(collection, instance, |entry| match entry {
 				Some((owner, _)) => {
+					T::OnTransfer::on_transfer(collection, instance, owner, destination)?;
+
 					OwnerInstances::<T>::mutate(owner.clone(), |x| match x {
 						Some(owner_instances) => {
 							let was_previously_owned =
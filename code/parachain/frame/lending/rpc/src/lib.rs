@@ -1,6 +1,9 @@
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::defi::Rate;
+use composable_traits::{
+	defi::Rate,
+	lending::{AccountPosition, MarketSolvency, MarketStats},
+};
 use core::{fmt::Display, str::FromStr};
 use jsonrpsee::{
 	core::{Error as RpcError, RpcResult},
@@ -14,9 +17,11 @@ use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use sp_std::sync::Arc;
 
 #[rpc(client, server)]
-pub trait LendingApi<BlockHash, MarketId>
+pub trait LendingApi<BlockHash, AccountId, MarketId, Balance>
 where
+	AccountId: FromStr + Display,
 	MarketId: FromStr + Display,
+	Balance: FromStr + Display,
 {
 	#[method(name = "lending_currentInterestRate")]
 	fn current_interest_rate(
@@ -24,6 +29,35 @@ where
 		market_id: SafeRpcWrapper<MarketId>,
 		at: Option<BlockHash>,
 	) -> RpcResult<SafeRpcWrapper<Rate>>;
+
+	#[method(name = "lending_marketSolvency")]
+	fn market_solvency(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<MarketSolvency<SafeRpcWrapper<Balance>>>;
+
+	#[method(name = "lending_accountPosition")]
+	fn account_position(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: SafeRpcWrapper<AccountId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<AccountPosition<SafeRpcWrapper<Balance>>>>;
+
+	#[method(name = "lending_marketStats")]
+	fn market_stats(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<MarketStats<SafeRpcWrapper<Balance>, SafeRpcWrapper<Rate>>>>;
+
+	#[method(name = "lending_accountsToWarn")]
+	fn accounts_to_warn(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<SafeRpcWrapper<AccountId>>>;
 }
 
 pub struct Lending<C, Block> {
@@ -37,15 +71,18 @@ impl<C, M> Lending<C, M> {
 	}
 }
 
-impl<C, Block, MarketId> LendingApiServer<<Block as BlockT>::Hash, MarketId>
-	for Lending<C, (Block, MarketId)>
+impl<C, Block, AccountId, MarketId, Balance>
+	LendingApiServer<<Block as BlockT>::Hash, AccountId, MarketId, Balance>
+	for Lending<C, (Block, AccountId, MarketId, Balance)>
 where
 	Block: BlockT,
+	AccountId: Send + Sync + 'static + Codec + FromStr + Display,
 	MarketId: Send + Sync + 'static + Codec + FromStr + Display,
+	Balance: Send + Sync + 'static + Codec + FromStr + Display,
 	C: Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block>,
-	C::Api: LendingRuntimeApi<Block, MarketId>,
+	C::Api: LendingRuntimeApi<Block, AccountId, MarketId, Balance>,
 {
 	fn current_interest_rate(
 		&self,
@@ -66,4 +103,84 @@ where
 			)))
 		})
 	}
+
+	fn market_solvency(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<MarketSolvency<SafeRpcWrapper<Balance>>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.market_solvency(&at, market_id.0);
+		runtime_api_result.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9876,
+				"Something wrong",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+	fn account_position(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		account: SafeRpcWrapper<AccountId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<AccountPosition<SafeRpcWrapper<Balance>>>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.account_position(&at, market_id.0, account);
+		runtime_api_result.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9876,
+				"Something wrong",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+
+	fn market_stats(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<MarketStats<SafeRpcWrapper<Balance>, SafeRpcWrapper<Rate>>>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.market_stats(&at, market_id.0);
+		runtime_api_result.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9876,
+				"Something wrong",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+
+	fn accounts_to_warn(
+		&self,
+		market_id: SafeRpcWrapper<MarketId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<SafeRpcWrapper<AccountId>>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		// calling ../../runtime-api
+		let runtime_api_result = api.accounts_to_warn(&at, market_id.0);
+		runtime_api_result.map_err(|e| {
+			RpcError::Call(CallError::Custom(ErrorObject::owned(
+				9876,
+				"Something wrong",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
 }
@@ -2,16 +2,42 @@
 
 use codec::Codec;
 use composable_support::rpc_helpers::SafeRpcWrapper;
-use composable_traits::defi::Rate;
+use composable_traits::{
+	defi::Rate,
+	lending::{AccountPosition, MarketSolvency, MarketStats},
+};
+use sp_std::vec::Vec;
 
 // Lending Runtime API declaration. Implemented for each runtime at
 // `runtime/<runtime-name>/src/lib.rs`.
 sp_api::decl_runtime_apis! {
-	pub trait LendingRuntimeApi<MarketId>
+	pub trait LendingRuntimeApi<AccountId, MarketId, Balance>
 	where
+		AccountId: Codec,
 		MarketId: Codec,
+		Balance: Codec,
 	{
 		/// Retrieve the current interest rate for the given `market_id`.
 		fn current_interest_rate(market_id: MarketId) -> SafeRpcWrapper<Rate>;
+
+		/// Retrieve the bad debt and the reserves of the given `market_id`.
+		fn market_solvency(market_id: MarketId) -> MarketSolvency<SafeRpcWrapper<Balance>>;
+
+		/// Retrieve the collateral, debt with interest and borrow limit of `account` in the given
+		/// `market_id`.
+		fn account_position(
+			market_id: MarketId,
+			account: SafeRpcWrapper<AccountId>,
+		) -> Option<AccountPosition<SafeRpcWrapper<Balance>>>;
+
+		/// Retrieve the utilization, supply and borrow APY and total reserves of the given
+		/// `market_id`.
+		fn market_stats(
+			market_id: MarketId,
+		) -> Option<MarketStats<SafeRpcWrapper<Balance>, SafeRpcWrapper<Rate>>>;
+
+		/// Retrieve the accounts of the given `market_id` whose collateral is below the
+		/// `under_collateralized_warn_percent` of the market.
+		fn accounts_to_warn(market_id: MarketId) -> Vec<SafeRpcWrapper<AccountId>>;
 	}
 }
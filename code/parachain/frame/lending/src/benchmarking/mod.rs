@@ -12,7 +12,11 @@ use composable_traits::{
 };
 use frame_benchmarking::{benchmarks, whitelisted_caller};
 use frame_support::{
-	traits::{fungible, fungibles::Mutate, Get},
+	traits::{
+		fungible,
+		fungibles::{Mutate, MutateHold},
+		Get,
+	},
 	BoundedVec,
 };
 use frame_system::RawOrigin;
@@ -32,6 +36,7 @@ fn create_market_from_raw_origin<T: Config>(
 	input: CreateInput<
 		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as DeFiComposableConfig>::Balance,
 		<T as frame_system::Config>::BlockNumber,
 	>,
 ) -> MarketId {
@@ -62,6 +67,7 @@ pub struct LendingBenchmarkingSetup<T: Config> {
 	input: CreateInput<
 		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as DeFiComposableConfig>::Balance,
 		<T as frame_system::Config>::BlockNumber,
 	>,
 }
@@ -361,5 +367,80 @@ benchmarks! {
 		Lending::<T>::handle_must_liquidate(&market_config, &caller).unwrap()
 	}
 
+	flash_loan {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+		let account = &Lending::<T>::account_id(&market_id);
+
+		<T as Config>::MultiCurrency::mint_into(pair.base, account, bank).unwrap();
+		<T as Config>::MultiCurrency::mint_into(pair.quote, account, bank).unwrap();
+		// enough to pay the fee
+		<T as Config>::MultiCurrency::mint_into(pair.base, &caller, amount).unwrap();
+		<T as Config>::MultiCurrency::mint_into(pair.quote, &caller, amount).unwrap();
+		let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+	}: _(origin, market_id, amount, Box::new(call))
+
+	enter_portfolio {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+
+		Lending::<T>::deposit_collateral(origin.clone().into(), market_id, amount, false).unwrap();
+	}: _(origin, market_id)
+
+	exit_portfolio {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+
+		Lending::<T>::deposit_collateral(origin.clone().into(), market_id, amount, false).unwrap();
+		Lending::<T>::enter_portfolio(origin.clone().into(), market_id).unwrap();
+	}: _(origin, market_id)
+
+	cover_bad_debt {
+		let LendingBenchmarkingSetup {
+			caller,
+			origin,
+			bank,
+			pair,
+			input,
+		} = lending_benchmarking_setup::<T>();
+
+		let amount: BalanceOf<T> = 1_000_000_u64.into();
+
+		let market_id = create_market_from_raw_origin::<T>(origin.clone(), input);
+		let account = &Lending::<T>::account_id(&market_id);
+
+		<T as Config>::MultiCurrency::mint_into(pair.quote, account, amount).unwrap();
+		<T as Config>::MultiCurrency::hold(pair.quote, account, amount).unwrap();
+		MarketReserves::<T>::insert(market_id, amount);
+		BadDebt::<T>::insert(market_id, amount);
+	}: _(origin, market_id, amount)
+
 	impl_benchmark_test_suite!(Lending, crate::mocks::general::new_test_ext(), crate::mocks::general::Runtime);
 }
@@ -92,6 +92,7 @@ pub(crate) fn create_market_config<T: Config>(
 ) -> CreateInput<
 	<T as Config>::LiquidationStrategyId,
 	<T as DeFiComposableConfig>::MayBeAssetId,
+	<T as DeFiComposableConfig>::Balance,
 	<T as frame_system::Config>::BlockNumber,
 > {
 	CreateInput {
@@ -100,6 +101,9 @@ pub(crate) fn create_market_config<T: Config>(
 			under_collateralized_warn_percent: Percent::from_percent(10),
 			liquidators: Default::default(),
 			max_price_age,
+			supply_cap: None,
+			borrow_cap: None,
+			isolation: None,
 		},
 		reserved_factor: Perquintill::from_percent(10),
 		currency_pair: CurrencyPair::new(collateral_asset, borrow_asset),
@@ -0,0 +1,91 @@
+use crate::{types::MarketId, *};
+use composable_support::math::safe::{SafeAdd, SafeSub};
+use composable_traits::{
+	defi::DeFiComposableConfig,
+	lending::{Lending, MarketSolvency},
+	vault::Vault,
+};
+use frame_support::{ensure, traits::fungibles::MutateHold};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchError, PerThing,
+};
+
+impl<T: Config> Pallet<T> {
+	/// Bad debt of the market and the reserves available to cover it.
+	pub fn market_solvency(market_id: &MarketId) -> MarketSolvency<T::Balance> {
+		MarketSolvency {
+			bad_debt: BadDebt::<T>::get(market_id),
+			reserves: MarketReserves::<T>::get(market_id),
+		}
+	}
+
+	/// Keep the `reserved_factor` share of `interest` repaid to the market in its reserves.
+	///
+	/// Reserves are held on the market account, so they are neither lent nor deposited back to
+	/// the borrow vault.
+	pub(crate) fn collect_reserves(
+		market_id: &MarketId,
+		borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
+		interest: T::Balance,
+	) -> Result<(), DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		let reserves = market.reserved_factor.mul_floor(interest);
+		if !reserves.is_zero() {
+			<T as Config>::MultiCurrency::hold(
+				borrow_asset,
+				&Self::account_id(market_id),
+				reserves,
+			)?;
+			MarketReserves::<T>::try_mutate(market_id, |total| -> Result<(), DispatchError> {
+				*total = total.safe_add(&reserves)?;
+				Ok(())
+			})?;
+		}
+		Ok(())
+	}
+
+	/// Record the part of `debt` which is not covered by `collateral_value`, both in borrow
+	/// asset. Returns the recorded bad debt.
+	pub(crate) fn record_bad_debt(
+		market_id: &MarketId,
+		debt: T::Balance,
+		collateral_value: T::Balance,
+	) -> Result<T::Balance, DispatchError> {
+		let shortfall = debt.saturating_sub(collateral_value);
+		if !shortfall.is_zero() {
+			BadDebt::<T>::try_mutate(market_id, |bad_debt| -> Result<(), DispatchError> {
+				*bad_debt = bad_debt.safe_add(&shortfall)?;
+				Ok(())
+			})?;
+		}
+		Ok(shortfall)
+	}
+
+	/// Cover `amount` of the bad debt of the market from its reserves. Released reserves are
+	/// deposited back to the borrow vault with the next block, restoring the value of its LP
+	/// tokens.
+	pub(crate) fn do_cover_bad_debt(
+		manager: &T::AccountId,
+		market_id: &MarketId,
+		amount: T::Balance,
+	) -> Result<(), DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		ensure!(manager == &market.manager, Error::<T>::Unauthorized);
+		let bad_debt = BadDebt::<T>::get(market_id);
+		ensure!(amount <= bad_debt, Error::<T>::CannotCoverMoreThanBadDebt);
+		let reserves = MarketReserves::<T>::get(market_id);
+		ensure!(amount <= reserves, Error::<T>::NotEnoughReserves);
+
+		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
+		<T as Config>::MultiCurrency::release(
+			borrow_asset,
+			&Self::account_id(market_id),
+			amount,
+			false,
+		)?;
+		BadDebt::<T>::insert(market_id, bad_debt.safe_sub(&amount)?);
+		MarketReserves::<T>::insert(market_id, reserves.safe_sub(&amount)?);
+		Ok(())
+	}
+}
@@ -16,7 +16,10 @@ use frame_support::{
 	},
 	weights::WeightToFee,
 };
-use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointNumber, Percent};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, DispatchError, FixedPointNumber, Percent,
+};
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
@@ -35,6 +38,7 @@ impl<T: Config> Pallet<T> {
 		let market_account = Self::account_id(market_id);
 
 		Self::can_borrow(market_id, borrowing_account, amount_to_borrow, market, &market_account)?;
+		Self::charge_isolated_debt(market_id, borrowing_account, borrow_asset, amount_to_borrow)?;
 
 		let new_account_interest_index = {
 			let market_index =
@@ -104,13 +108,22 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn create_borrower_data(
 		market_id: &<Self as Lending>::MarketId,
 		account: &<Self as DeFiEngine>::AccountId,
+	) -> Result<BorrowerData, DispatchError> {
+		let collateral_balance = Self::collateral_of_account(market_id, account)?;
+		Self::create_borrower_data_with_collateral(market_id, account, collateral_balance)
+	}
+
+	/// Same as [`create_borrower_data`](Self::create_borrower_data), but values
+	/// `collateral_balance` as the collateral of the account instead of what it deposited.
+	pub(crate) fn create_borrower_data_with_collateral(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+		collateral_balance: CollateralLpAmountOf<Self>,
 	) -> Result<BorrowerData, DispatchError> {
 		let (_, market) = Self::get_market(market_id)?;
 
-		let collateral_balance_value = Self::get_price(
-			market.collateral_asset,
-			Self::collateral_of_account(market_id, account)?,
-		)?;
+		let collateral_balance_value =
+			Self::get_price(market.collateral_asset, collateral_balance)?;
 
 		let account_total_debt_with_interest =
 			Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
@@ -131,6 +144,7 @@ impl<T: Config> Pallet<T> {
 
 		Ok(borrower)
 	}
+
 	/// Some of these checks remain to provide better errors. See [this clickup task](task) for
 	/// more information.
 	///
@@ -149,6 +163,14 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
+		if let Some(borrow_cap) = market.borrow_cap {
+			let total_borrowed = Self::total_borrowed_from_market_excluding_interest(market_id)?;
+			ensure!(
+				total_borrowed.safe_add(&amount_to_borrow)? <= borrow_cap,
+				Error::<T>::BorrowCapReached
+			);
+		}
+
 		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
 		let borrow_limit = Self::get_borrow_limit(market_id, debt_owner)?;
 		let borrow_amount_value = Self::get_price(borrow_asset, amount_to_borrow)?;
@@ -193,13 +215,23 @@ impl<T: Config> Pallet<T> {
 	) -> Result<T::Balance, DispatchError> {
 		let (_, market) = Self::get_market(market_id)?;
 		let borrow_asset_id = T::Vault::asset_id(&market.borrow_asset_vault)?;
-		Ok(<T as Config>::MultiCurrency::balance(borrow_asset_id, &Self::account_id(market_id)))
+		// reserves are held on the market account but cannot be borrowed
+		Ok(<T as Config>::MultiCurrency::balance(borrow_asset_id, &Self::account_id(market_id))
+			.saturating_sub(MarketReserves::<T>::get(market_id)))
 	}
 
 	pub(crate) fn do_get_borrow_limit(
 		market_id: &MarketId,
 		account: &T::AccountId,
 	) -> Result<T::Balance, DispatchError> {
+		if Self::is_in_portfolio(market_id, account) {
+			let balance = Self::portfolio_health(account)?
+				.get_borrow_limit()
+				.checked_mul_int(1_u64)
+				.ok_or(ArithmeticError::Overflow)?;
+			return Ok(balance.into())
+		}
+
 		let collateral_balance = AccountCollateral::<T>::get(market_id, account)
 			// REVIEW: I don't think this should default to zero, only to check against zero
 			// afterwards.
@@ -9,7 +9,11 @@ use composable_traits::{
 	vault::Vault,
 };
 use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
-use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointNumber};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, DispatchError, FixedPointNumber,
+};
+use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
 	pub(crate) fn do_deposit_collateral(
@@ -22,12 +26,28 @@ impl<T: Config> Pallet<T> {
 		let (_, market) = Self::get_market(market_id)?;
 		let market_account = Self::account_id(market_id);
 
+		TotalCollateral::<T>::try_mutate(market_id, |total_collateral| {
+			let new_total_collateral = total_collateral.safe_add(&amount)?;
+			if let Some(supply_cap) = market.supply_cap {
+				ensure!(new_total_collateral <= supply_cap, Error::<T>::SupplyCapReached);
+			}
+			*total_collateral = new_total_collateral;
+			Result::<(), DispatchError>::Ok(())
+		})?;
+
 		AccountCollateral::<T>::try_mutate(market_id, account, |collateral_balance| {
 			let new_collateral_balance =
 				collateral_balance.unwrap_or_default().safe_add(&amount)?;
 			collateral_balance.replace(new_collateral_balance);
 			Result::<(), DispatchError>::Ok(())
 		})?;
+		// collateral of an isolated market only backs the debt it whitelists
+		let backed_markets = if Self::is_in_portfolio(market_id, account) {
+			PortfolioMarkets::<T>::get(account).into_inner()
+		} else {
+			Vec::from([*market_id])
+		};
+		Self::charge_isolated_backing(account, &backed_markets)?;
 
 		<T as Config>::MultiCurrency::transfer(
 			market.collateral_asset,
@@ -54,29 +74,43 @@ impl<T: Config> Pallet<T> {
 
 		ensure!(amount <= collateral_balance, Error::<T>::NotEnoughCollateralToWithdraw);
 
-		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
-		let borrower_balance_with_interest =
-			Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
-
-		let borrow_balance_value = Self::get_price(borrow_asset, borrower_balance_with_interest)?;
-
-		let collateral_balance_after_withdrawal_value =
-			Self::get_price(market.collateral_asset, collateral_balance.safe_sub(&amount)?)?;
-
-		let borrower_after_withdrawal = BorrowerData::new(
-			collateral_balance_after_withdrawal_value,
-			borrow_balance_value,
-			market
-				.collateral_factor
-				.try_into_validated()
-				.map_err(|_| ArithmeticError::Overflow)?, // TODO: Use a proper error message?
-			market.under_collateralized_warn_percent,
-		);
-
-		ensure!(
-			!borrower_after_withdrawal.should_liquidate()?,
-			Error::<T>::WouldGoUnderCollateralized
-		);
+		if Self::is_in_portfolio(market_id, account) {
+			let portfolio_after_withdrawal = Self::portfolio_health_with_collateral(
+				account,
+				market_id,
+				collateral_balance.safe_sub(&amount)?,
+			)?;
+
+			ensure!(
+				!portfolio_after_withdrawal.should_liquidate(),
+				Error::<T>::WouldGoUnderCollateralized
+			);
+		} else {
+			let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
+			let borrower_balance_with_interest =
+				Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
+
+			let borrow_balance_value =
+				Self::get_price(borrow_asset, borrower_balance_with_interest)?;
+
+			let collateral_balance_after_withdrawal_value =
+				Self::get_price(market.collateral_asset, collateral_balance.safe_sub(&amount)?)?;
+
+			let borrower_after_withdrawal = BorrowerData::new(
+				collateral_balance_after_withdrawal_value,
+				borrow_balance_value,
+				market
+					.collateral_factor
+					.try_into_validated()
+					.map_err(|_| ArithmeticError::Overflow)?, // TODO: Use a proper error message?
+				market.under_collateralized_warn_percent,
+			);
+
+			ensure!(
+				!borrower_after_withdrawal.should_liquidate()?,
+				Error::<T>::WouldGoUnderCollateralized
+			);
+		}
 
 		let market_account = Self::account_id(market_id);
 		AccountCollateral::<T>::try_mutate(market_id, account, |collateral_balance| {
@@ -88,6 +122,9 @@ impl<T: Config> Pallet<T> {
 
 			Result::<(), DispatchError>::Ok(())
 		})?;
+		TotalCollateral::<T>::mutate(market_id, |total_collateral| {
+			*total_collateral = total_collateral.saturating_sub(amount)
+		});
 		<T as Config>::MultiCurrency::transfer(
 			market.collateral_asset,
 			&market_account,
@@ -0,0 +1,54 @@
+use crate::{types::MarketId, *};
+use composable_support::math::safe::SafeAdd;
+use composable_traits::{
+	lending::{BorrowAmountOf, Lending},
+	vault::{StrategicVault, Vault},
+};
+use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
+use sp_runtime::{traits::Zero, DispatchError, PerThing};
+
+impl<T: Config> Pallet<T> {
+	/// Lends `amount` out of the market account, which holds the liquidity the market's vault
+	/// allocated to it, for the duration of `callback`.
+	pub(crate) fn do_flash_loan(
+		market_id: &MarketId,
+		borrower: &T::AccountId,
+		amount: BorrowAmountOf<Self>,
+		callback: impl FnOnce() -> DispatchResult,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(!amount.is_zero(), Error::<T>::CannotFlashLoanZero);
+		let (_, market) = Self::get_market(market_id)?;
+		let market_account = Self::account_id(market_id);
+		Self::ensure_can_borrow_from_vault(&market.borrow_asset_vault, &market_account)?;
+
+		let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
+		// rounded up, so that no loan is free
+		let fee = T::FlashLoanFee::get().mul_ceil(amount);
+
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			&market_account,
+			borrower,
+			amount,
+			false,
+		)?;
+		callback()?;
+		<T as Config>::MultiCurrency::transfer(
+			borrow_asset,
+			borrower,
+			&market_account,
+			amount.safe_add(&fee)?,
+			false,
+		)
+		.map_err(|_| Error::<T>::FlashLoanNotRepaid)?;
+
+		if !fee.is_zero() {
+			<T::Vault as StrategicVault>::deposit(
+				&market.borrow_asset_vault,
+				&market_account,
+				fee,
+			)?;
+		}
+		Ok(fee)
+	}
+}
@@ -0,0 +1,155 @@
+use crate::*;
+use composable_support::math::safe::SafeAdd;
+use composable_traits::{
+	defi::{DeFiComposableConfig, DeFiEngine},
+	lending::{IsolationMode, Lending},
+	vault::Vault,
+};
+use frame_support::pallet_prelude::*;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchError, PerThing, Perquintill,
+};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+	/// Isolated markets whose collateral backs the borrow of the account in `market_id`. That is
+	/// the market itself, or, if the market is part of the portfolio of the account, the isolated
+	/// markets of the portfolio in which the account has collateral.
+	fn isolated_markets_backing(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+	) -> Result<Vec<(MarketId, IsolationMode<T::MayBeAssetId, T::Balance>)>, DispatchError> {
+		let markets: Vec<MarketId> = if Self::is_in_portfolio(market_id, account) {
+			PortfolioMarkets::<T>::get(account)
+				.into_iter()
+				.filter(|market_id| AccountCollateral::<T>::contains_key(market_id, account))
+				.collect()
+		} else {
+			Vec::from([*market_id])
+		};
+		markets
+			.into_iter()
+			.filter_map(|market_id| match Self::get_market(&market_id) {
+				Ok((_, market)) => market.isolation.map(|isolation| Ok((market_id, isolation))),
+				Err(error) => Some(Err(error)),
+			})
+			.collect()
+	}
+
+	/// Charges the value of a new borrow to the debt ceiling of every isolated market backing it.
+	///
+	/// NOTE: Must be called in transaction!
+	pub(crate) fn charge_isolated_debt(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+		borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
+		amount: T::Balance,
+	) -> Result<(), DispatchError> {
+		let isolated_markets = Self::isolated_markets_backing(market_id, account)?;
+		Self::charge_isolated_markets(market_id, account, borrow_asset, amount, isolated_markets)
+	}
+
+	/// Charges the debt of the account in each of `markets` to the debt ceiling of the isolated
+	/// markets which back it but were not charged for it yet. To be called whenever the
+	/// collateral of the account or its portfolio changes, as isolated collateral may then start
+	/// backing an existing borrow.
+	///
+	/// NOTE: Must be called in transaction!
+	pub(crate) fn charge_isolated_backing(
+		account: &<Self as DeFiEngine>::AccountId,
+		markets: &[<Self as Lending>::MarketId],
+	) -> Result<(), DispatchError> {
+		for market_id in markets {
+			let debt = Self::total_debt_with_interest(market_id, account)?.unwrap_or_zero();
+			if debt.is_zero() {
+				continue
+			}
+			let charged = IsolatedBorrows::<T>::get(market_id, account);
+			let isolated_markets: Vec<_> = Self::isolated_markets_backing(market_id, account)?
+				.into_iter()
+				.filter(|(isolated_market_id, _)| {
+					!charged.iter().any(|(id, _)| id == isolated_market_id)
+				})
+				.collect();
+			if isolated_markets.is_empty() {
+				continue
+			}
+			let (_, market) = Self::get_market(market_id)?;
+			let borrow_asset = T::Vault::asset_id(&market.borrow_asset_vault)?;
+			Self::charge_isolated_markets(
+				market_id,
+				account,
+				borrow_asset,
+				debt,
+				isolated_markets,
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Charges the value of `amount` of `borrow_asset` borrowed by the account in `market_id` to
+	/// the debt ceiling of each of `isolated_markets`.
+	fn charge_isolated_markets(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+		borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
+		amount: T::Balance,
+		isolated_markets: Vec<(MarketId, IsolationMode<T::MayBeAssetId, T::Balance>)>,
+	) -> Result<(), DispatchError> {
+		if isolated_markets.is_empty() {
+			return Ok(())
+		}
+
+		let value = Self::get_price(borrow_asset, amount)?;
+		for (isolated_market_id, isolation) in isolated_markets {
+			ensure!(
+				isolation.borrowable_assets.contains(&borrow_asset),
+				Error::<T>::AssetNotBorrowableAgainstIsolatedCollateral
+			);
+			IsolatedDebt::<T>::try_mutate(isolated_market_id, |debt| {
+				let new_debt = debt.safe_add(&value)?;
+				ensure!(new_debt <= isolation.debt_ceiling, Error::<T>::DebtCeilingReached);
+				*debt = new_debt;
+				Result::<(), DispatchError>::Ok(())
+			})?;
+			IsolatedBorrows::<T>::try_mutate(market_id, account, |borrows| {
+				match borrows.iter_mut().find(|(id, _)| *id == isolated_market_id) {
+					Some((_, charged)) => *charged = charged.safe_add(&value)?,
+					None => borrows
+						.try_push((isolated_market_id, value))
+						.map_err(|_| Error::<T>::TooManyPortfolioMarkets)?,
+				}
+				Result::<(), DispatchError>::Ok(())
+			})?;
+		}
+		Ok(())
+	}
+
+	/// Gives back `share` of what the borrow of the account in `market_id` was charged to the
+	/// debt ceilings of isolated markets.
+	pub(crate) fn release_isolated_debt(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+		share: Perquintill,
+	) {
+		let borrows = if share == Perquintill::one() {
+			IsolatedBorrows::<T>::take(market_id, account)
+		} else {
+			IsolatedBorrows::<T>::mutate(market_id, account, |borrows| {
+				let released = borrows.clone();
+				for (_, charged) in borrows.iter_mut() {
+					*charged = charged.saturating_sub(share.mul_floor(*charged));
+				}
+				released
+			})
+		};
+		for (isolated_market_id, charged) in borrows {
+			// debt charged before the ceiling was lowered or removed may not add up, hence
+			// saturating
+			IsolatedDebt::<T>::mutate(isolated_market_id, |debt| {
+				*debt = debt.saturating_sub(share.mul_floor(charged))
+			});
+		}
+	}
+}
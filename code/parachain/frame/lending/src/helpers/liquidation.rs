@@ -1,26 +1,37 @@
 use crate::*;
 use composable_traits::{
-	defi::{CurrencyPair, DeFiComposableConfig, DeFiEngine, Sell},
-	lending::Lending,
-	liquidation::Liquidation,
+	defi::{CurrencyPair, DeFiEngine, Sell},
+	lending::{Lending, MarketSolvency, TotalDebtWithInterest},
+	liquidation::{Liquidation, LiquidationCallback},
 	oracle::Oracle,
 	vault::Vault,
 };
 use frame_support::{
 	pallet_prelude::*,
 	storage::{with_transaction, TransactionOutcome},
-	traits::fungible::Transfer as NativeTransfer,
+	traits::{fungible::Transfer as NativeTransfer, fungibles::Transfer},
+};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchError, DispatchResult, PerThing, Perquintill,
 };
-use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
 	/// Whether or not an account should be liquidated. See [`BorrowerData::should_liquidate()`]
 	/// for more information.
+	///
+	/// If the market is part of the portfolio of the account, the whole portfolio is checked
+	/// instead. See [`AccountHealth::should_liquidate()`].
+	///
+	/// [`AccountHealth::should_liquidate()`]: crate::models::account_health::AccountHealth::should_liquidate
 	pub fn should_liquidate(
 		market_id: &<Self as Lending>::MarketId,
 		account: &<Self as DeFiEngine>::AccountId,
 	) -> Result<bool, DispatchError> {
+		if Self::is_in_portfolio(market_id, account) {
+			return Ok(Self::portfolio_health(account)?.should_liquidate())
+		}
 		let borrower = Self::create_borrower_data(market_id, account)?;
 		let should_liquidate = borrower.should_liquidate()?;
 		Ok(should_liquidate)
@@ -30,67 +41,209 @@ impl<T: Config> Pallet<T> {
 		market_id: &<Self as Lending>::MarketId,
 		account: &<Self as DeFiEngine>::AccountId,
 	) -> Result<bool, DispatchError> {
+		if Self::is_in_portfolio(market_id, account) {
+			return Ok(Self::portfolio_health(account)?.should_warn())
+		}
 		let borrower = Self::create_borrower_data(market_id, account)?;
 		let should_warn = borrower.should_warn()?;
 		Ok(should_warn)
 	}
 
-	/// Initiate liquidation of individual position for particular borrower within mentioned
-	/// market. Returns 'Ok(())' in the case of successful initiation, 'Err(DispatchError)' in
-	/// the opposite case.
+	/// Initiate liquidation of the position of a borrower within mentioned market, or of its
+	/// whole portfolio if the market is part of it. Returns the bad debt left in each market the
+	/// borrower owed to, along with the number of positions sold in the case of successful
+	/// initiation, 'Err(DispatchError)' in the opposite case. Bad debt is only known once the
+	/// liquidations are settled, unless no collateral is left for the debt.
+	///
+	/// Collateral is sold into the borrow asset of each debt in turn, drawing on the markets in
+	/// order until the oracle value of the collateral sold covers the debt. The last market drawn
+	/// on is sold in full, the excess proceeds being released to the borrower, while the
+	/// collateral of the markets not drawn on is left to the borrower.
+	///
+	/// Each debt is then settled with the proceeds of the liquidation, reported to its
+	/// [`LiquidationCallback`]. The last sale for a debt carries the part of it the oracle value
+	/// of the collateral does not cover, so that proceeds above that value repay it before any
+	/// excess is released. Bad debt is recorded for the part of the debt the proceeds do not
+	/// cover.
 	/// - `liquidator` : Liquidator's account id.
-	/// - `market_pair` : Index and configuration of the market from which tokens were borrowed.
+	/// - `market_id` : Index of the market from which tokens were borrowed.
 	/// - `account` : Borrower's account id whose debt are going to be liquidated.
 	fn liquidate_position(
 		liquidator: &<Self as DeFiEngine>::AccountId,
-		market_pair: &(&<Self as Lending>::MarketId, MarketConfigOf<T>),
-		borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
+		market_id: &<Self as Lending>::MarketId,
 		account: &<Self as DeFiEngine>::AccountId,
-	) -> Result<(), DispatchError> {
-		let (market_id, market) = market_pair;
+	) -> Result<
+		(Vec<(<Self as Lending>::MarketId, <Self as DeFiEngine>::Balance)>, u32),
+		DispatchError,
+	> {
 		ensure!(
 			Self::should_liquidate(market_id, account)?,
 			DispatchError::Other("Tried liquidate position which is not supposed to be liquidated")
 		);
 
-		let collateral_to_liquidate = Self::collateral_of_account(market_id, account)?;
+		let markets = if Self::is_in_portfolio(market_id, account) {
+			PortfolioMarkets::<T>::get(account).into_inner()
+		} else {
+			Vec::from([*market_id])
+		};
+		let mut collaterals = Vec::new();
+		let mut debts = Vec::new();
+		for id in markets.iter() {
+			let (_, market) = Self::get_market(id)?;
+			let collateral = AccountCollateral::<T>::get(id, account).unwrap_or_default();
+			if !collateral.is_zero() {
+				collaterals.push((*id, market.clone(), collateral));
+			}
+			if let TotalDebtWithInterest::Amount(debt) =
+				Self::total_debt_with_interest(id, account)?
+			{
+				debts.push((*id, T::Vault::asset_id(&market.borrow_asset_vault)?, debt));
+			}
+		}
 
-		let source_target_account = Self::account_id(market_id);
+		// Collateral sold for each debt: the index of its market in `collaterals`, the amount
+		// sold, and the part of the debt it repays.
+		let mut sales = Vec::new();
+		let mut bad_debts = Vec::new();
+		let mut drawn = 0;
+		for (debt_market_id, borrow_asset, debt) in debts {
+			let first_sale = sales.len();
+			let mut uncovered = debt;
+			while !uncovered.is_zero() {
+				let (_, market, remaining) = match collaterals.get_mut(drawn) {
+					Some(collateral) => collateral,
+					None => break,
+				};
+				let unit_price =
+					T::Oracle::get_ratio(CurrencyPair::new(market.collateral_asset, borrow_asset))?;
+				let value =
+					Sell::new(market.collateral_asset, borrow_asset, *remaining, unit_price)
+						.take
+						.quote_limit_amount()?;
+				let (amount, covered) = if value > uncovered {
+					(Perquintill::from_rational(uncovered, value).mul_ceil(*remaining), uncovered)
+				} else {
+					(*remaining, value)
+				};
+				*remaining = remaining.saturating_sub(amount);
+				uncovered = uncovered.saturating_sub(covered);
+				sales.push((drawn, amount, debt_market_id, borrow_asset, covered));
+				if remaining.is_zero() {
+					drawn += 1;
+				}
+			}
+			let bad_debt = match sales.get_mut(first_sale..).and_then(|sales| sales.last_mut()) {
+				// settled along with the last sale
+				Some((.., covered)) => {
+					*covered = covered.saturating_add(uncovered);
+					Zero::zero()
+				},
+				None => Self::record_bad_debt(&debt_market_id, uncovered, Zero::zero())?,
+			};
+			bad_debts.push((debt_market_id, bad_debt));
+		}
+		// the last market drawn on is sold in full
+		if let (Some((index, amount, ..)), Some((_, _, remaining))) =
+			(sales.last_mut(), collaterals.get(drawn))
+		{
+			if *index == drawn {
+				*amount = amount.saturating_add(*remaining);
+			}
+		}
 
-		let unit_price =
-			T::Oracle::get_ratio(CurrencyPair::new(market.collateral_asset, borrow_asset))?;
+		let positions = sales.len() as u32;
+		for (index, amount, debt_market_id, borrow_asset, covered) in sales {
+			let (collateral_market_id, market, _) =
+				collaterals.get(index).ok_or(Error::<T>::AccountCollateralAbsent)?;
+			AccountCollateral::<T>::mutate_exists(collateral_market_id, account, |collateral| {
+				*collateral = collateral
+					.map(|collateral| collateral.saturating_sub(amount))
+					.filter(|collateral| !collateral.is_zero());
+			});
+			TotalCollateral::<T>::mutate(collateral_market_id, |total_collateral| {
+				*total_collateral = total_collateral.saturating_sub(amount)
+			});
+			let unit_price =
+				T::Oracle::get_ratio(CurrencyPair::new(market.collateral_asset, borrow_asset))?;
+			let sell = Sell::new(market.collateral_asset, borrow_asset, amount, unit_price);
+			InitiatedLiquidation::<T>::put(LiquidatedPosition {
+				market_id: debt_market_id,
+				collateral_market_id: *collateral_market_id,
+				borrower: account.clone(),
+				debt: covered,
+			});
+			let order_id = T::Liquidation::liquidate(
+				&Self::account_id(collateral_market_id),
+				sell,
+				market.liquidators.clone(),
+			)?;
+			if let Some(position) = InitiatedLiquidation::<T>::take() {
+				match order_id {
+					Some(order_id) => LiquidatedPositions::<T>::insert(order_id, position),
+					// sold instantly in full
+					None => {
+						let (settled_bad_debt, _) = Self::settle_liquidation(
+							&position,
+							market.collateral_asset,
+							Zero::zero(),
+						)?;
+						if let Some((_, bad_debt)) =
+							bad_debts.iter_mut().find(|(id, _)| *id == debt_market_id)
+						{
+							*bad_debt = bad_debt.saturating_add(settled_bad_debt);
+						}
+						Self::deposit_event(Event::<T>::LiquidationSettled {
+							market_id: position.market_id,
+							account: position.borrower,
+							collateral_released: Zero::zero(),
+						});
+					},
+				}
+			}
+		}
 
-		let sell =
-			Sell::new(market.collateral_asset, borrow_asset, collateral_to_liquidate, unit_price);
-		T::Liquidation::liquidate(&source_target_account, sell, market.liquidators.clone())?;
-		if let Some(deposit) = BorrowRent::<T>::get(market_id, account) {
-			let market_account = Self::account_id(market_id);
-			<T as Config>::NativeCurrency::transfer(&market_account, liquidator, deposit, false)?;
+		for (debt_market_id, _) in bad_debts.iter() {
+			if let Some(deposit) = BorrowRent::<T>::take(debt_market_id, account) {
+				<T as Config>::NativeCurrency::transfer(
+					&Self::account_id(debt_market_id),
+					liquidator,
+					deposit,
+					false,
+				)?;
+			}
 		}
-		Ok(())
+		Ok((bad_debts, positions))
 	}
 
 	/// Liquidates debt for each borrower in the vector within mentioned market.
-	/// Returns a vector of borrowers' account ids whose debts were liquidated.
+	/// Returns the account ids of the borrowers whose debts were liquidated, along with the bad
+	/// debt each of them left in each market they owed to and the number of positions sold.
 	/// - `liquidator` : Liquidator's account id.
 	/// - `market_id` : Market index from which `borrowers` has taken borrow.
 	/// - `borrowers` : Vector of borrowers whose debts are going to be liquidated.
+	#[allow(clippy::type_complexity)]
 	pub fn do_liquidate(
 		liquidator: &<Self as DeFiEngine>::AccountId,
 		market_id: &<Self as Lending>::MarketId,
 		borrowers: BoundedVec<<Self as DeFiEngine>::AccountId, T::MaxLiquidationBatchSize>,
-	) -> Result<Vec<<Self as DeFiEngine>::AccountId>, DispatchError> {
+	) -> Result<
+		Vec<(
+			<Self as DeFiEngine>::AccountId,
+			Vec<(<Self as Lending>::MarketId, <Self as DeFiEngine>::Balance)>,
+			u32,
+		)>,
+		DispatchError,
+	> {
 		// Vector of borrowers whose positions are involved in the liquidation process.
-		let mut subjected_borrowers: Vec<<Self as DeFiEngine>::AccountId> = Vec::new();
-		let market_pair = Self::get_market(market_id)?;
-		let borrow_asset = T::Vault::asset_id(&market_pair.1.borrow_asset_vault)?;
+		let mut subjected_borrowers = Vec::new();
+		Self::get_market(market_id)?;
 		for account in borrowers.iter() {
 			// Wrap liquidate position request in a storage transaction.
 			// So, in the case of any error state's changes will not be committed
 			let storage_transaction_succeeded =
 				with_transaction(|| {
 					let liquidation_response_result =
-						Self::liquidate_position(liquidator, &market_pair, borrow_asset, account);
+						Self::liquidate_position(liquidator, market_id, account);
 					if let Err(error) = liquidation_response_result {
 						log::warn!("Creation of liquidation request for position {:?} {:?} was failed: {:?}",
 						market_id,
@@ -98,18 +251,218 @@ impl<T: Config> Pallet<T> {
 						error );
 						return TransactionOutcome::Rollback(liquidation_response_result)
 					}
-					TransactionOutcome::Commit(Ok(()))
+					TransactionOutcome::Commit(liquidation_response_result)
 				});
 
 			// If storage transaction succeeded,
 			// push borrower to the output vector,
 			// remove debt records from storages.
-			if storage_transaction_succeeded.is_ok() {
-				subjected_borrowers.push(account.clone());
-				BorrowTimestamp::<T>::remove(market_id, account);
-				DebtIndex::<T>::remove(market_id, account);
+			if let Ok((bad_debts, positions)) = storage_transaction_succeeded {
+				for (debt_market_id, _) in bad_debts.iter() {
+					BorrowTimestamp::<T>::remove(debt_market_id, account);
+					DebtIndex::<T>::remove(debt_market_id, account);
+					Self::release_isolated_debt(debt_market_id, account, Perquintill::one());
+				}
+				subjected_borrowers.push((account.clone(), bad_debts, positions));
 			}
 		}
 		Ok(subjected_borrowers)
 	}
+
+	/// Weight of liquidating borrowers selling `positions` positions, each of them initiating a
+	/// liquidation.
+	pub(crate) fn liquidate_weight(positions: u32) -> Weight {
+		<T as Config>::WeightInfo::liquidate(positions)
+			.saturating_add(T::Liquidation::liquidate_weight().saturating_mul(positions.into()))
+	}
+
+	/// Liquidates the `borrowers` within mentioned market, reporting the bad debt they left.
+	/// Returns the borrowers liquidated along with the number of positions sold.
+	pub(crate) fn liquidate_borrowers(
+		liquidator: &<Self as DeFiEngine>::AccountId,
+		market_id: &<Self as Lending>::MarketId,
+		borrowers: BoundedVec<<Self as DeFiEngine>::AccountId, T::MaxLiquidationBatchSize>,
+	) -> Result<(Vec<<Self as DeFiEngine>::AccountId>, u32), DispatchError> {
+		let liquidated = Self::do_liquidate(liquidator, market_id, borrowers)?;
+		// a portfolio leaves bad debt in every market it owed to
+		let mut markets_with_bad_debt = Vec::new();
+		for (account, bad_debts, _) in liquidated.iter() {
+			for (debt_market_id, amount) in bad_debts.iter().filter(|(_, amount)| !amount.is_zero())
+			{
+				Self::deposit_event(Event::<T>::BadDebtRecorded {
+					market_id: *debt_market_id,
+					account: account.clone(),
+					amount: *amount,
+				});
+				if !markets_with_bad_debt.contains(debt_market_id) {
+					markets_with_bad_debt.push(*debt_market_id);
+				}
+			}
+		}
+		for market_id in markets_with_bad_debt {
+			let MarketSolvency { bad_debt, reserves } = Self::market_solvency(&market_id);
+			Self::deposit_event(Event::<T>::MarketSolvencyUpdated {
+				market_id,
+				bad_debt,
+				reserves,
+			});
+		}
+		let positions =
+			liquidated.iter().fold(0_u32, |total, (_, _, sold)| total.saturating_add(*sold));
+		let subjected_borrowers: Vec<_> =
+			liquidated.into_iter().map(|(account, ..)| account).collect();
+		// if at least one borrower was affected then liquidation been initiated
+		if !subjected_borrowers.is_empty() {
+			Self::deposit_event(Event::LiquidationInitiated {
+				market_id: *market_id,
+				borrowers: subjected_borrowers.clone(),
+			});
+		}
+		Ok((subjected_borrowers, positions))
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Position sold by the order `order_id`, or of which the liquidation is being initiated.
+	fn liquidated_position(order_id: Option<OrderIdOf<T>>) -> Option<LiquidatedPositionOf<T>> {
+		match order_id {
+			Some(order_id) => LiquidatedPositions::<T>::get(order_id),
+			None => InitiatedLiquidation::<T>::get(),
+		}
+	}
+
+	/// Settle the position once its liquidation is over. If the proceeds covered its debt, the
+	/// `remaining` collateral is released to the borrower, otherwise it is kept by the market
+	/// holding it and the debt left is recorded as bad debt. Returns the bad debt recorded and the
+	/// collateral released.
+	fn settle_liquidation(
+		position: &LiquidatedPositionOf<T>,
+		collateral_asset: T::MayBeAssetId,
+		remaining: T::Balance,
+	) -> Result<(T::Balance, T::Balance), DispatchError> {
+		if !position.debt.is_zero() {
+			let bad_debt = Self::record_bad_debt(&position.market_id, position.debt, Zero::zero())?;
+			return Ok((bad_debt, Zero::zero()))
+		}
+		if !remaining.is_zero() {
+			<T as Config>::MultiCurrency::transfer(
+				collateral_asset,
+				&Self::account_id(&position.collateral_market_id),
+				&position.borrower,
+				remaining,
+				false,
+			)?;
+		}
+		Ok((Zero::zero(), remaining))
+	}
+
+	fn deposit_settlement_events(
+		position: &LiquidatedPositionOf<T>,
+		bad_debt: T::Balance,
+		collateral_released: T::Balance,
+	) {
+		let market_id = position.market_id;
+		if !bad_debt.is_zero() {
+			Self::deposit_event(Event::<T>::BadDebtRecorded {
+				market_id,
+				account: position.borrower.clone(),
+				amount: bad_debt,
+			});
+			let MarketSolvency { bad_debt, reserves } = Self::market_solvency(&market_id);
+			Self::deposit_event(Event::<T>::MarketSolvencyUpdated {
+				market_id,
+				bad_debt,
+				reserves,
+			});
+		}
+		Self::deposit_event(Event::<T>::LiquidationSettled {
+			market_id,
+			account: position.borrower.clone(),
+			collateral_released,
+		});
+	}
+}
+
+impl<T: Config> LiquidationCallback<T::AccountId, T::MayBeAssetId, T::Balance, OrderIdOf<T>>
+	for Pallet<T>
+{
+	/// Proceeds cover the debt of the position and are paid to the market it is owed to, the
+	/// excess is released to the borrower.
+	fn on_fill(
+		from_to: &T::AccountId,
+		order_id: Option<OrderIdOf<T>>,
+		pair: CurrencyPair<T::MayBeAssetId>,
+		sold: T::Balance,
+		received: T::Balance,
+		remaining: T::Balance,
+	) -> DispatchResult {
+		let mut position = match Self::liquidated_position(order_id) {
+			Some(position) if *from_to == Self::account_id(&position.collateral_market_id) =>
+				position,
+			_ => return Ok(()),
+		};
+		let covered = received.min(position.debt);
+		position.debt = position.debt.saturating_sub(covered);
+		if position.market_id != position.collateral_market_id && !covered.is_zero() {
+			<T as Config>::MultiCurrency::transfer(
+				pair.quote,
+				from_to,
+				&Self::account_id(&position.market_id),
+				covered,
+				false,
+			)?;
+		}
+		let released = received.saturating_sub(covered);
+		if !released.is_zero() {
+			<T as Config>::MultiCurrency::transfer(
+				pair.quote,
+				from_to,
+				&position.borrower,
+				released,
+				false,
+			)?;
+		}
+		Self::deposit_event(Event::<T>::LiquidationFilled {
+			market_id: position.market_id,
+			account: position.borrower.clone(),
+			collateral_sold: sold,
+			proceeds: received,
+			released,
+			remaining,
+		});
+		match order_id {
+			Some(order_id) => LiquidatedPositions::<T>::insert(order_id, position),
+			None => InitiatedLiquidation::<T>::put(position),
+		}
+		Ok(())
+	}
+
+	fn on_complete(
+		from_to: &T::AccountId,
+		order_id: OrderIdOf<T>,
+		pair: CurrencyPair<T::MayBeAssetId>,
+		received: T::Balance,
+	) -> DispatchResult {
+		Self::on_expire(from_to, order_id, pair, received, Zero::zero())
+	}
+
+	/// The collateral left unsold is released to the borrower if the debt was covered.
+	fn on_expire(
+		from_to: &T::AccountId,
+		order_id: OrderIdOf<T>,
+		pair: CurrencyPair<T::MayBeAssetId>,
+		_received: T::Balance,
+		remaining: T::Balance,
+	) -> DispatchResult {
+		let position = match LiquidatedPositions::<T>::get(order_id) {
+			Some(position) if *from_to == Self::account_id(&position.collateral_market_id) =>
+				position,
+			_ => return Ok(()),
+		};
+		LiquidatedPositions::<T>::remove(order_id);
+		let (bad_debt, collateral_released) =
+			Self::settle_liquidation(&position, pair.base, remaining)?;
+		Self::deposit_settlement_events(&position, bad_debt, collateral_released);
+		Ok(())
+	}
 }
@@ -7,7 +7,7 @@ use crate::{
 use composable_support::validation::Validated;
 use composable_traits::{
 	currency::CurrencyFactory,
-	lending::{Lending, MarketConfig, UpdateInput},
+	lending::{Lending, MarketConfig},
 	vault::{Deposit, Vault, VaultConfig},
 };
 use frame_support::{pallet_prelude::*, traits::fungibles::Transfer};
@@ -85,6 +85,10 @@ impl<T: Config> Pallet<T> {
 					.updatable
 					.under_collateralized_warn_percent,
 				liquidators: config_input.updatable.liquidators,
+				supply_cap: config_input.updatable.supply_cap,
+				borrow_cap: config_input.updatable.borrow_cap,
+				isolation: config_input.updatable.isolation,
+				reserved_factor: config_input.reserved_factor,
 			};
 			let debt_token_id = T::CurrencyFactory::reserve_lp_token_id()?;
 
@@ -99,10 +103,7 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn do_update_market(
 		manager: T::AccountId,
 		market_id: MarketId,
-		input: Validated<
-			UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
-			UpdateInputValid,
-		>,
+		input: Validated<UpdateInputOf<T>, UpdateInputValid>,
 	) -> Result<(), DispatchError> {
 		let input = input.value();
 		Markets::<T>::mutate(market_id, |market| {
@@ -116,6 +117,9 @@ impl<T: Config> Pallet<T> {
 				market.collateral_factor = input.collateral_factor;
 				market.under_collateralized_warn_percent = input.under_collateralized_warn_percent;
 				market.liquidators = input.liquidators.clone();
+				market.supply_cap = input.supply_cap;
+				market.borrow_cap = input.borrow_cap;
+				market.isolation = input.isolation.clone();
 				Ok(())
 			} else {
 				Err(Error::<T>::MarketDoesNotExist)
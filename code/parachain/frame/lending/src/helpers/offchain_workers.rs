@@ -2,7 +2,7 @@ pub use crate::types::{MarketId, MarketIdInner};
 use crate::*;
 use frame_support::pallet_prelude::*;
 use frame_system::offchain::{SendSignedTransaction, Signer};
-use sp_std::vec;
+use sp_std::{collections::btree_set::BTreeSet, vec, vec::Vec};
 
 impl<T: Config> Pallet<T> {
 	pub(crate) fn do_offchain_worker(_block_number: T::BlockNumber) {
@@ -11,6 +11,8 @@ impl<T: Config> Pallet<T> {
 			log::warn!("No signer");
 			return
 		}
+		// portfolios are liquidated as a whole, so only once per run
+		let mut liquidated_portfolios = BTreeSet::new();
 		for (market_id, account, _) in DebtIndex::<T>::iter() {
 			//Check that it should liquidate before liquidations
 			let should_be_liquidated = match Self::should_liquidate(&market_id, &account) {
@@ -29,28 +31,44 @@ impl<T: Config> Pallet<T> {
 			if !should_be_liquidated {
 				continue
 			}
-			let results = signer.send_signed_transaction(|_account| Call::liquidate {
-				market_id,
-				// Unwrapped since we push only one borrower in the vector
-				borrowers: BoundedVec::<_, T::MaxLiquidationBatchSize>::try_from(vec![
-					account.clone()
-				])
-				.expect("This function never panics"),
-			});
+			// the debt of a portfolio may be backed by collateral in other markets, which are
+			// the ones to liquidate
+			let in_portfolio = Self::is_in_portfolio(&market_id, &account);
+			if in_portfolio && !liquidated_portfolios.insert(account.clone()) {
+				continue
+			}
+			let markets_to_liquidate: Vec<MarketId> = if in_portfolio {
+				PortfolioMarkets::<T>::get(&account)
+					.into_iter()
+					.filter(|market_id| AccountCollateral::<T>::contains_key(market_id, &account))
+					.collect()
+			} else {
+				vec![market_id]
+			};
+			for market_id in markets_to_liquidate {
+				let results = signer.send_signed_transaction(|_account| Call::liquidate {
+					market_id,
+					// Unwrapped since we push only one borrower in the vector
+					borrowers: BoundedVec::<_, T::MaxLiquidationBatchSize>::try_from(vec![
+						account.clone()
+					])
+					.expect("This function never panics"),
+				});
 
-			for (_acc, res) in &results {
-				match res {
-					Ok(()) => log::info!(
-						"Liquidation succeed, market_id: {:?}, account: {:?}",
-						market_id,
-						account
-					),
-					Err(e) => log::error!(
-						"Liquidation failed, market_id: {:?}, account: {:?}, error: {:?}",
-						market_id,
-						account,
-						e
-					),
+				for (_acc, res) in &results {
+					match res {
+						Ok(()) => log::info!(
+							"Liquidation succeed, market_id: {:?}, account: {:?}",
+							market_id,
+							account
+						),
+						Err(e) => log::error!(
+							"Liquidation failed, market_id: {:?}, account: {:?}, error: {:?}",
+							market_id,
+							account,
+							e
+						),
+					}
 				}
 			}
 		}
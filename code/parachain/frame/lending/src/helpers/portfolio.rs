@@ -0,0 +1,100 @@
+use crate::{models::account_health::AccountHealth, *};
+use composable_traits::{defi::DeFiEngine, lending::Lending};
+use frame_support::pallet_prelude::*;
+use sp_runtime::DispatchError;
+
+impl<T: Config> Pallet<T> {
+	/// Whether the market is part of the portfolio of the account.
+	pub(crate) fn is_in_portfolio(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+	) -> bool {
+		PortfolioMarkets::<T>::get(account).contains(market_id)
+	}
+
+	/// The health of the account across all the markets of its portfolio. See [`AccountHealth`]
+	/// for more information.
+	pub fn portfolio_health(
+		account: &<Self as DeFiEngine>::AccountId,
+	) -> Result<AccountHealth, DispatchError> {
+		Self::compute_portfolio_health(account, &PortfolioMarkets::<T>::get(account), |_| None)
+	}
+
+	/// Same as [`portfolio_health`](Self::portfolio_health), but values `collateral_balance` as
+	/// the collateral of the account in `market_id`. Used to check a withdrawal before it happens.
+	pub(crate) fn portfolio_health_with_collateral(
+		account: &<Self as DeFiEngine>::AccountId,
+		market_id: &<Self as Lending>::MarketId,
+		collateral_balance: T::Balance,
+	) -> Result<AccountHealth, DispatchError> {
+		Self::compute_portfolio_health(account, &PortfolioMarkets::<T>::get(account), |id| {
+			(id == market_id).then_some(collateral_balance)
+		})
+	}
+
+	pub(crate) fn do_enter_portfolio(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+	) -> Result<(), DispatchError> {
+		Self::get_market(market_id)?;
+
+		let markets = PortfolioMarkets::<T>::try_mutate(account, |markets| {
+			ensure!(!markets.contains(market_id), Error::<T>::MarketAlreadyInPortfolio);
+			markets.try_push(*market_id).map_err(|_| Error::<T>::TooManyPortfolioMarkets)?;
+			Result::<_, DispatchError>::Ok(markets.clone())
+		})?;
+
+		// an unhealthy market may be rescued by the portfolio, but must not sink it
+		ensure!(
+			!Self::compute_portfolio_health(account, &markets, |_| None)?.should_liquidate(),
+			Error::<T>::WouldGoUnderCollateralized
+		);
+		// isolated collateral of the portfolio now backs the debt of all its markets
+		Self::charge_isolated_backing(account, &markets)
+	}
+
+	pub(crate) fn do_exit_portfolio(
+		market_id: &<Self as Lending>::MarketId,
+		account: &<Self as DeFiEngine>::AccountId,
+	) -> Result<(), DispatchError> {
+		let markets = PortfolioMarkets::<T>::try_mutate(account, |markets| {
+			let position = markets
+				.iter()
+				.position(|id| id == market_id)
+				.ok_or(Error::<T>::MarketNotInPortfolio)?;
+			markets.remove(position);
+			Result::<_, DispatchError>::Ok(markets.clone())
+		})?;
+
+		// both the market and the rest of the portfolio must stand on their own
+		ensure!(
+			!Self::compute_portfolio_health(account, &[*market_id], |_| None)?.should_liquidate(),
+			Error::<T>::WouldGoUnderCollateralized
+		);
+		ensure!(
+			!Self::compute_portfolio_health(account, &markets, |_| None)?.should_liquidate(),
+			Error::<T>::WouldGoUnderCollateralized
+		);
+		// the debt of the market is now backed by its own collateral only
+		Self::charge_isolated_backing(account, &[*market_id])
+	}
+
+	/// Sums up the positions of the account in `markets`. Collateral of a market defaults to the
+	/// deposit of the account unless `collateral_override` returns a balance for it.
+	fn compute_portfolio_health(
+		account: &<Self as DeFiEngine>::AccountId,
+		markets: &[<Self as Lending>::MarketId],
+		collateral_override: impl Fn(&<Self as Lending>::MarketId) -> Option<T::Balance>,
+	) -> Result<AccountHealth, DispatchError> {
+		let mut health = AccountHealth::default();
+		for market_id in markets {
+			let collateral_balance = collateral_override(market_id)
+				.or_else(|| AccountCollateral::<T>::get(market_id, account))
+				.unwrap_or_default();
+			let borrower =
+				Self::create_borrower_data_with_collateral(market_id, account, collateral_balance)?;
+			health.add(&borrower)?;
+		}
+		Ok(health)
+	}
+}
@@ -13,7 +13,10 @@ use frame_support::{
 		fungibles::{Inspect, Mutate, MutateHold, Transfer},
 	},
 };
-use sp_runtime::{traits::Zero, ArithmeticError, DispatchError, FixedPointNumber, FixedU128};
+use sp_runtime::{
+	traits::Zero, ArithmeticError, DispatchError, FixedPointNumber, FixedU128, PerThing,
+	Perquintill,
+};
 
 impl<T: Config> Pallet<T> {
 	/// NOTE: Must be called in transaction!
@@ -68,6 +71,7 @@ impl<T: Config> Pallet<T> {
 					beneficiary_interest_on_market,
 					keep_alive,
 				)?;
+				Self::collect_reserves(market_id, borrow_asset, beneficiary_interest_on_market)?;
 
 				// release and burn debt token from beneficiary and transfer borrow asset to
 				// market, paid by `from`
@@ -108,6 +112,11 @@ impl<T: Config> Pallet<T> {
 				)
 				.ok_or(ArithmeticError::Overflow)?;
 
+				let interest_repaid: BorrowAmountOf<Self> = interest_percentage
+					.checked_mul_int::<u128>(partial_repay_amount.into())
+					.ok_or(ArithmeticError::Overflow)?
+					.into();
+
 				// pay interest, from -> market
 				// burn interest (debt token) from market
 				Self::pay_interest(
@@ -115,12 +124,10 @@ impl<T: Config> Pallet<T> {
 					debt_asset,
 					from,
 					&market_account,
-					interest_percentage
-						.checked_mul_int::<u128>(partial_repay_amount.into())
-						.ok_or(ArithmeticError::Overflow)?
-						.into(),
+					interest_repaid,
 					keep_alive,
 				)?;
+				Self::collect_reserves(market_id, borrow_asset, interest_repaid)?;
 
 				// release and burn debt token from beneficiary and transfer borrow asset to
 				// market, paid by `from`
@@ -143,6 +150,12 @@ impl<T: Config> Pallet<T> {
 			},
 		};
 
+		Self::release_isolated_debt(
+			market_id,
+			beneficiary,
+			Perquintill::from_rational(repaid_amount, beneficiary_total_debt_with_interest),
+		);
+
 		// if the borrow is completely repaid, remove the borrow information
 		if repaid_amount == beneficiary_total_debt_with_interest {
 			// borrow no longer exists as it has been repaid in entirety, remove the
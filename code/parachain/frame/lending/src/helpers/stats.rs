@@ -0,0 +1,87 @@
+use crate::{types::MarketId, *};
+use composable_support::math::safe::{SafeDiv, SafeSub};
+use composable_traits::{
+	defi::Rate,
+	lending::{math::InterestRate, AccountPosition, Lending, MarketStats, TotalDebtWithInterest},
+	time::SECONDS_PER_YEAR_NAIVE,
+};
+use sp_runtime::{
+	traits::{One, Saturating, Zero},
+	DispatchError, FixedPointNumber, PerThing, Percent,
+};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+	/// Collateral, debt with interest and borrow limit of `account` in the market.
+	pub fn account_position(
+		market_id: &MarketId,
+		account: &T::AccountId,
+	) -> Result<AccountPosition<T::Balance>, DispatchError> {
+		let debt_with_interest = match Self::total_debt_with_interest(market_id, account)? {
+			TotalDebtWithInterest::Amount(amount) => amount,
+			TotalDebtWithInterest::NoDebt => Zero::zero(),
+		};
+		Ok(AccountPosition {
+			collateral: AccountCollateral::<T>::get(market_id, account).unwrap_or_else(Zero::zero),
+			debt_with_interest,
+			borrow_limit: Self::get_borrow_limit(market_id, account)?,
+		})
+	}
+
+	/// Current yearly borrow rate of the market, given its utilization.
+	pub fn borrow_rate(market_id: &MarketId) -> Result<Rate, DispatchError> {
+		let (_, mut market) = Self::get_market(market_id)?;
+		let utilization = Self::utilization(market_id)?;
+		market
+			.interest_rate_model
+			.get_borrow_rate(utilization)
+			.ok_or(Error::<T>::CannotCalculateBorrowRate)
+			.map_err(Into::into)
+	}
+
+	/// Utilization, yields and reserves of the market.
+	pub fn market_stats(
+		market_id: &MarketId,
+	) -> Result<MarketStats<T::Balance, Rate>, DispatchError> {
+		let (_, market) = Self::get_market(market_id)?;
+		let utilization = Self::utilization(market_id)?;
+		let borrow_rate = Self::borrow_rate(market_id)?;
+		// lenders earn the interest paid on the borrowed share of the liquidity, minus reserves
+		let supply_rate = borrow_rate
+			.saturating_mul(utilization.into())
+			.saturating_mul(market.reserved_factor.left_from_one().into());
+		Ok(MarketStats {
+			utilization,
+			borrow_apy: Self::compound_yearly(borrow_rate)?,
+			supply_apy: Self::compound_yearly(supply_rate)?,
+			total_reserves: MarketReserves::<T>::get(market_id),
+		})
+	}
+
+	/// Accounts with debt in the market whose collateral ratio is below the collateral factor
+	/// increased by `under_collateralized_warn_percent`, including those which can be
+	/// liquidated.
+	pub fn accounts_to_warn(market_id: &MarketId) -> Vec<T::AccountId> {
+		DebtIndex::<T>::iter_key_prefix(market_id)
+			.filter(|account| Self::soon_under_collateralized(market_id, account).unwrap_or(false))
+			.collect()
+	}
+
+	fn utilization(market_id: &MarketId) -> Result<Percent, DispatchError> {
+		Self::calculate_utilization_ratio(
+			Self::total_available_to_be_borrowed(market_id)?,
+			Self::total_borrowed_from_market_excluding_interest(market_id)?,
+		)
+	}
+
+	/// Yearly yield of `rate`, compounded every second. Interest accrues with every block, which
+	/// is close enough to continuous compounding.
+	fn compound_yearly(rate: Rate) -> Result<Rate, DispatchError> {
+		let rate_per_second =
+			rate.safe_div(&Rate::saturating_from_integer(SECONDS_PER_YEAR_NAIVE))?;
+		Ok(Rate::one()
+			.saturating_add(rate_per_second)
+			.saturating_pow(SECONDS_PER_YEAR_NAIVE as usize)
+			.safe_sub(&Rate::one())?)
+	}
+}
@@ -43,6 +43,7 @@ pub use crate::weights::WeightInfo;
 
 pub mod crypto;
 mod helpers;
+pub mod migration;
 mod models;
 mod types;
 
@@ -65,14 +66,14 @@ pub mod pallet {
 	// ----------------------------------------------------------------------------------------------------
 
 	pub(crate) use crate::types::MarketAssets;
-	pub use crate::types::{MarketId, MarketIdInner};
+	pub use crate::types::{LiquidatedPosition, MarketId, MarketIdInner};
 	use crate::weights::WeightInfo;
 	use composable_traits::{
 		currency::CurrencyFactory,
 		defi::{DeFiComposableConfig, *},
 		lending::{
 			BorrowAmountOf, CollateralLpAmountOf, CreateInput, LendAssetAmountOf, Lending,
-			MarketConfig, RepayStrategy, TotalDebtWithInterest, UpdateInput,
+			MarketConfig, MarketSolvency, RepayStrategy, TotalDebtWithInterest, UpdateInput,
 		},
 		liquidation::Liquidation,
 		oracle::Oracle,
@@ -84,6 +85,7 @@ pub mod pallet {
 	use codec::Codec;
 	use composable_support::validation::TryIntoValidated;
 	use frame_support::{
+		dispatch::GetDispatchInfo,
 		pallet_prelude::*,
 		traits::{
 			fungible::{Inspect as NativeInspect, Transfer as NativeTransfer},
@@ -100,17 +102,21 @@ pub mod pallet {
 	};
 	use sp_core::crypto::KeyTypeId;
 	use sp_runtime::{
-		traits::{AccountIdConversion, Get},
-		DispatchError, KeyTypeId as CryptoKeyTypeId, Percent,
+		traits::{AccountIdConversion, Dispatchable, Get, Zero},
+		DispatchError, KeyTypeId as CryptoKeyTypeId, Percent, Permill,
 	};
-	use sp_std::{fmt::Debug, vec::Vec};
+	use sp_std::{boxed::Box, fmt::Debug, vec::Vec};
 
 	// ----------------------------------------------------------------------------------------------------
 	//                                   @Declaration Of The Pallet Type
 	// ----------------------------------------------------------------------------------------------------
 
+	/// The current storage version, see [`crate::migration`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -230,6 +236,21 @@ pub mod pallet {
 		/// Convert a weight value into a deductible fee based on the currency type.
 		type WeightToFee: WeightToFeePolynomial<Balance = Self::Balance>
 			+ WeightToFee<Balance = Self::Balance>;
+
+		/// The call dispatched while a flash loan is out.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>;
+
+		/// Fee charged on flash loans, as a share of the amount borrowed. It is credited to the
+		/// vault of the market.
+		#[pallet::constant]
+		type FlashLoanFee: Get<Permill>;
+
+		/// The maximum amount of markets an account can have in its portfolio.
+		#[pallet::constant]
+		type MaxPortfolioMarkets: Get<u32>;
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -242,12 +263,28 @@ pub mod pallet {
 		<T as DeFiComposableConfig>::MayBeAssetId,
 		<T as frame_system::Config>::AccountId,
 		<T as Config>::LiquidationStrategyId,
+		<T as DeFiComposableConfig>::Balance,
 		<T as frame_system::Config>::BlockNumber,
 	>;
+	/// Id of the orders selling the collateral of liquidated positions.
+	pub type OrderIdOf<T> = <<T as Config>::Liquidation as Liquidation>::OrderId;
+	/// A convenience wrapper around [`LiquidatedPosition`].
+	pub type LiquidatedPositionOf<T> = LiquidatedPosition<
+		<T as frame_system::Config>::AccountId,
+		<T as DeFiComposableConfig>::Balance,
+	>;
 	/// A convenience wrapper around [`CreateInput`].
 	pub type CreateInputOf<T> = CreateInput<
 		<T as Config>::LiquidationStrategyId,
 		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as DeFiComposableConfig>::Balance,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+	/// A convenience wrapper around [`UpdateInput`].
+	pub type UpdateInputOf<T> = UpdateInput<
+		<T as Config>::LiquidationStrategyId,
+		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as DeFiComposableConfig>::Balance,
 		<T as frame_system::Config>::BlockNumber,
 	>;
 
@@ -351,6 +388,84 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Total collateral deposited into each market, checked against its supply cap. Tracked
+	/// apart from the balance of the market account, which anyone can send collateral to.
+	///
+	/// MarketId -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No collateral is the same as zero collateral.
+	pub type TotalCollateral<T: Config> =
+		StorageMap<_, Twox64Concat, MarketId, T::Balance, ValueQuery>;
+
+	/// Markets an account opted into portfolio margin for. Collateral and debt of these markets
+	/// are accounted together when checking the health of the account.
+	///
+	/// Account -> [MarketId]
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // An empty portfolio is the same as no portfolio.
+	pub type PortfolioMarkets<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<MarketId, T::MaxPortfolioMarkets>,
+		ValueQuery,
+	>;
+
+	/// Value of the debt backed by the collateral of each isolated market, priced when borrowed.
+	///
+	/// MarketId -> Value
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No debt is the same as zero debt.
+	pub type IsolatedDebt<T: Config> =
+		StorageMap<_, Twox64Concat, MarketId, T::Balance, ValueQuery>;
+
+	/// Share of [`IsolatedDebt`] charged for the borrow of an account in a market, per isolated
+	/// market backing it. Released when the borrow is repaid or liquidated.
+	///
+	/// (Market, Account) -> [(Isolated market, Value)]
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No debt is the same as zero debt.
+	pub type IsolatedBorrows<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		MarketId,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<(MarketId, T::Balance), T::MaxPortfolioMarkets>,
+		ValueQuery,
+	>;
+
+	/// Debt of each market, in borrow asset, which liquidations did not cover and which was not
+	/// yet covered from the reserves of the market.
+	///
+	/// MarketId -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No bad debt is the same as zero bad debt.
+	pub type BadDebt<T: Config> = StorageMap<_, Twox64Concat, MarketId, T::Balance, ValueQuery>;
+
+	/// Reserve fund of each market, in borrow asset, held on the market account. Built from the
+	/// `reserved_factor` share of the interest repaid by borrowers.
+	///
+	/// MarketId -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No reserves is the same as zero reserves.
+	pub type MarketReserves<T: Config> =
+		StorageMap<_, Twox64Concat, MarketId, T::Balance, ValueQuery>;
+
+	/// Positions sent to liquidation, by the id of the order selling their collateral, until the
+	/// order is completed or expires.
+	///
+	/// OrderId -> LiquidatedPosition
+	#[pallet::storage]
+	pub type LiquidatedPositions<T: Config> =
+		StorageMap<_, Twox64Concat, OrderIdOf<T>, LiquidatedPositionOf<T>, OptionQuery>;
+
+	/// Position of which the liquidation is being initiated, settled with the part of its
+	/// collateral sold instantly. Only set while the liquidation is initiated.
+	#[pallet::storage]
+	pub type InitiatedLiquidation<T: Config> =
+		StorageValue<_, LiquidatedPositionOf<T>, OptionQuery>;
+
 	/// The timestamp of the previous block or defaults to timestamp at genesis.
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)] // LastBlockTimestamp is set on genesis (see below) so it will always be set.
@@ -372,7 +487,12 @@ pub mod pallet {
 		},
 		MarketUpdated {
 			market_id: MarketId,
-			input: UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
+			input: UpdateInput<
+				T::LiquidationStrategyId,
+				T::MayBeAssetId,
+				T::Balance,
+				<T as frame_system::Config>::BlockNumber,
+			>,
 		},
 		/// Event emitted when asset is deposited by lender.
 		AssetDeposited { sender: T::AccountId, market_id: MarketId, amount: T::Balance },
@@ -395,6 +515,41 @@ pub mod pallet {
 		LiquidationInitiated { market_id: MarketId, borrowers: Vec<T::AccountId> },
 		/// Event emitted to warn that loan may go under collateralize soon.
 		MayGoUnderCollateralizedSoon { market_id: MarketId, account: T::AccountId },
+		/// Event emitted when a flash loan is repaid along with its fee.
+		FlashLoaned {
+			sender: T::AccountId,
+			market_id: MarketId,
+			amount: T::Balance,
+			fee: T::Balance,
+		},
+		/// Event emitted when an account adds a market to its portfolio.
+		PortfolioMarketEntered { account: T::AccountId, market_id: MarketId },
+		/// Event emitted when an account removes a market from its portfolio.
+		PortfolioMarketExited { account: T::AccountId, market_id: MarketId },
+		/// Event emitted when the collateral of a liquidated account is not worth its debt.
+		BadDebtRecorded { market_id: MarketId, account: T::AccountId, amount: T::Balance },
+		/// Event emitted when bad debt is covered from the reserves of a market.
+		BadDebtCovered { market_id: MarketId, amount: T::Balance },
+		/// Event emitted with the solvency of a market when its bad debt is recorded or covered.
+		MarketSolvencyUpdated { market_id: MarketId, bad_debt: T::Balance, reserves: T::Balance },
+		/// Event emitted when a part of the collateral sent to liquidation is sold, with the part
+		/// of the proceeds in excess of the debt released to the borrower and the amount of
+		/// collateral left to sell.
+		LiquidationFilled {
+			market_id: MarketId,
+			account: T::AccountId,
+			collateral_sold: T::Balance,
+			proceeds: T::Balance,
+			released: T::Balance,
+			remaining: T::Balance,
+		},
+		/// Event emitted when the liquidation of a position is over, with the collateral left
+		/// unsold released to the borrower.
+		LiquidationSettled {
+			market_id: MarketId,
+			account: T::AccountId,
+			collateral_released: T::Balance,
+		},
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -453,6 +608,29 @@ pub mod pallet {
 		// If Vault is unbalanced we can not borrow from it, since
 		// we do not know how many asset one needs to balance the value.
 		CannotBorrowFromMarketWithUnbalancedVault,
+		/// Flash loans must lend a non-zero amount.
+		CannotFlashLoanZero,
+		/// The borrower did not pay back the flash loan and its fee.
+		FlashLoanNotRepaid,
+		/// The market is already part of the portfolio of the account.
+		MarketAlreadyInPortfolio,
+		/// The market is not part of the portfolio of the account.
+		MarketNotInPortfolio,
+		/// The portfolio of the account already has `T::MaxPortfolioMarkets` markets.
+		TooManyPortfolioMarkets,
+		/// Depositing the collateral would exceed the supply cap of the market.
+		SupplyCapReached,
+		/// Borrowing the amount would exceed the borrow cap of the market.
+		BorrowCapReached,
+		/// The borrow asset is not whitelisted by an isolated market backing the borrow.
+		AssetNotBorrowableAgainstIsolatedCollateral,
+		/// Borrowing the amount would exceed the debt ceiling of an isolated market backing the
+		/// borrow.
+		DebtCeilingReached,
+		/// Cannot cover more than the bad debt of the market.
+		CannotCoverMoreThanBadDebt,
+		/// The reserves of the market are not enough to cover the requested amount.
+		NotEnoughReserves,
 	}
 
 	// ----------------------------------------------------------------------------------------------------
@@ -543,7 +721,12 @@ pub mod pallet {
 		fn update_market(
 			manager: Self::AccountId,
 			market_id: Self::MarketId,
-			input: UpdateInput<Self::LiquidationStrategyId, Self::BlockNumber>,
+			input: UpdateInput<
+				Self::LiquidationStrategyId,
+				Self::MayBeAssetId,
+				Self::Balance,
+				Self::BlockNumber,
+			>,
 		) -> Result<(), DispatchError> {
 			Self::do_update_market(manager, market_id, input.clone().try_into_validated()?)?;
 			Self::deposit_event(Event::<T>::MarketUpdated { market_id, input });
@@ -660,6 +843,23 @@ pub mod pallet {
 			Ok(amount)
 		}
 
+		/// NOTE: Must be called in transaction!
+		fn flash_loan(
+			market_id: &Self::MarketId,
+			borrower: &Self::AccountId,
+			amount: BorrowAmountOf<Self>,
+			callback: impl FnOnce() -> DispatchResult,
+		) -> Result<Self::Balance, DispatchError> {
+			let fee = Self::do_flash_loan(market_id, borrower, amount, callback)?;
+			Self::deposit_event(Event::<T>::FlashLoaned {
+				sender: borrower.clone(),
+				market_id: *market_id,
+				amount,
+				fee,
+			});
+			Ok(fee)
+		}
+
 		fn total_borrowed_from_market_excluding_interest(
 			market_id: &Self::MarketId,
 		) -> Result<Self::Balance, DispatchError> {
@@ -723,15 +923,8 @@ pub mod pallet {
 			market_id: &<Self as Lending>::MarketId,
 			borrowers: BoundedVec<<Self as DeFiEngine>::AccountId, Self::MaxLiquidationBatchSize>,
 		) -> Result<Vec<<Self as DeFiEngine>::AccountId>, DispatchError> {
-			let subjected_borrowers = Self::do_liquidate(liquidator, market_id, borrowers)?;
-			// if at least one borrower was affected then liquidation been initiated
-			if !subjected_borrowers.is_empty() {
-				Self::deposit_event(Event::LiquidationInitiated {
-					market_id: *market_id,
-					borrowers: subjected_borrowers.clone(),
-				});
-			}
-			Ok(subjected_borrowers)
+			Self::liquidate_borrowers(liquidator, market_id, borrowers)
+				.map(|(subjected_borrowers, _)| subjected_borrowers)
 		}
 	}
 
@@ -776,7 +969,7 @@ pub mod pallet {
 		pub fn update_market(
 			origin: OriginFor<T>,
 			market_id: MarketId,
-			input: UpdateInput<T::LiquidationStrategyId, <T as frame_system::Config>::BlockNumber>,
+			input: UpdateInputOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			<Self as Lending>::update_market(who, market_id, input)?;
@@ -893,7 +1086,13 @@ pub mod pallet {
 		/// - `origin` : Sender of this extrinsic.
 		/// - `market_id` : Market index from which `borrower` has taken borrow.
 		/// - `borrowers` : Vector of borrowers accounts' ids.
-		#[pallet::weight(<T as Config>::WeightInfo::liquidate(borrowers.len() as u32))]
+		///
+		/// A borrower liquidated as a portfolio may sell the collateral of each of its markets
+		/// into the debt of each other, the weight of the positions actually sold is refunded.
+		#[pallet::weight(Pallet::<T>::liquidate_weight(
+			(borrowers.len() as u32)
+				.saturating_mul(T::MaxPortfolioMarkets::get().saturating_mul(2))
+		))]
 		#[transactional]
 		pub fn liquidate(
 			origin: OriginFor<T>,
@@ -901,7 +1100,90 @@ pub mod pallet {
 			borrowers: BoundedVec<T::AccountId, T::MaxLiquidationBatchSize>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin.clone())?;
-			<Self as Lending>::liquidate(&sender, &market_id, borrowers)?;
+			let batch_size = borrowers.len() as u32;
+			let (_, positions) = Self::liquidate_borrowers(&sender, &market_id, borrowers)?;
+			Ok(Some(Self::liquidate_weight(positions.max(batch_size))).into())
+		}
+
+		/// Borrow without collateral, dispatch a call and pay the loan back, plus
+		/// `T::FlashLoanFee`, all in the same transaction. The fee is credited to the market's
+		/// vault.
+		/// - `origin` : Sender of this extrinsic. (Also the borrower and the origin of `call`.)
+		/// - `market_id` : Market index from which the borrow asset is lent.
+		/// - `amount` : Amount to borrow.
+		/// - `call` : Call using the loan, e.g. a CosmWasm contract execution.
+		#[pallet::weight({
+			let call_weight = call.get_dispatch_info().weight;
+			<T as Config>::WeightInfo::flash_loan().saturating_add(call_weight)
+		})]
+		#[transactional]
+		pub fn flash_loan(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+			amount: T::Balance,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin.clone())?;
+			<Self as Lending>::flash_loan(&market_id, &sender, amount, || {
+				(*call).dispatch(origin).map(|_| ()).map_err(|error| error.error)
+			})?;
+			Ok(().into())
+		}
+
+		/// Add a market to the portfolio of the sender. Collateral and debt of all the markets of
+		/// the portfolio are accounted together when borrowing, withdrawing collateral and
+		/// checking for liquidation, so collateral in one market backs debt in the others.
+		/// - `origin` : Sender of this extrinsic.
+		/// - `market_id` : Market index to add to the portfolio.
+		#[pallet::weight(<T as Config>::WeightInfo::enter_portfolio())]
+		#[transactional]
+		pub fn enter_portfolio(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::do_enter_portfolio(&market_id, &sender)?;
+			Self::deposit_event(Event::<T>::PortfolioMarketEntered { account: sender, market_id });
+			Ok(().into())
+		}
+
+		/// Remove a market from the portfolio of the sender. Fails if either the market or the
+		/// rest of the portfolio would go under collateralized on its own.
+		/// - `origin` : Sender of this extrinsic.
+		/// - `market_id` : Market index to remove from the portfolio.
+		#[pallet::weight(<T as Config>::WeightInfo::exit_portfolio())]
+		#[transactional]
+		pub fn exit_portfolio(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::do_exit_portfolio(&market_id, &sender)?;
+			Self::deposit_event(Event::<T>::PortfolioMarketExited { account: sender, market_id });
+			Ok(().into())
+		}
+
+		/// Cover bad debt of a market from its reserves. Only the manager of the market can
+		/// call this.
+		/// - `origin` : Sender of this extrinsic, the manager of the market.
+		/// - `market_id` : Market index whose bad debt is covered.
+		/// - `amount` : Amount of bad debt to cover, in borrow asset.
+		#[pallet::weight(<T as Config>::WeightInfo::cover_bad_debt())]
+		#[transactional]
+		pub fn cover_bad_debt(
+			origin: OriginFor<T>,
+			market_id: MarketId,
+			amount: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			Self::do_cover_bad_debt(&sender, &market_id, amount)?;
+			Self::deposit_event(Event::<T>::BadDebtCovered { market_id, amount });
+			let MarketSolvency { bad_debt, reserves } = Self::market_solvency(&market_id);
+			Self::deposit_event(Event::<T>::MarketSolvencyUpdated {
+				market_id,
+				bad_debt,
+				reserves,
+			});
 			Ok(().into())
 		}
 	}
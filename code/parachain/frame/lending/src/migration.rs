@@ -0,0 +1,82 @@
+//! Storage migrations of the lending pallet.
+
+use crate::*;
+use composable_traits::{
+	defi::{DeFiComposableConfig, MoreThanOneFixedU128},
+	lending::{math::InterestRateModel, MarketConfig},
+};
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use sp_runtime::{traits::Saturating, Percent, Perquintill};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub mod v1 {
+	use super::*;
+
+	/// [`MarketConfig`] before supply caps, borrow caps, isolation mode and reserves.
+	#[derive(Encode, Decode)]
+	struct OldMarketConfig<VaultId, AssetId, AccountId, LiquidationStrategyId, BlockNumber> {
+		manager: AccountId,
+		borrow_asset_vault: VaultId,
+		collateral_asset: AssetId,
+		max_price_age: BlockNumber,
+		collateral_factor: MoreThanOneFixedU128,
+		interest_rate_model: InterestRateModel,
+		under_collateralized_warn_percent: Percent,
+		liquidators: Vec<LiquidationStrategyId>,
+	}
+
+	type OldMarketConfigOf<T> = OldMarketConfig<
+		<T as Config>::VaultId,
+		<T as DeFiComposableConfig>::MayBeAssetId,
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::LiquidationStrategyId,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// Adds the supply cap, borrow cap, isolation mode and reserved factor to the configuration
+	/// of existing markets, none of them being capped or isolated. Markets keep none of the
+	/// interest repaid in reserves, as before, until their manager sets one. Also sums up the
+	/// collateral deposited into each market into [`TotalCollateral`].
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut markets = 0_u64;
+			Markets::<T>::translate::<OldMarketConfigOf<T>, _>(|_, old| {
+				markets += 1;
+				Some(MarketConfig {
+					manager: old.manager,
+					borrow_asset_vault: old.borrow_asset_vault,
+					collateral_asset: old.collateral_asset,
+					max_price_age: old.max_price_age,
+					collateral_factor: old.collateral_factor,
+					interest_rate_model: old.interest_rate_model,
+					under_collateralized_warn_percent: old.under_collateralized_warn_percent,
+					liquidators: old.liquidators,
+					supply_cap: None,
+					borrow_cap: None,
+					isolation: None,
+					reserved_factor: Perquintill::zero(),
+				})
+			});
+
+			let mut collaterals = 0_u64;
+			for (market_id, _, collateral) in AccountCollateral::<T>::iter() {
+				collaterals += 1;
+				TotalCollateral::<T>::mutate(market_id, |total_collateral| {
+					*total_collateral = total_collateral.saturating_add(collateral)
+				});
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(
+				markets.saturating_add(collaterals.saturating_mul(2)).saturating_add(1),
+				markets.saturating_add(collaterals).saturating_add(1),
+			)
+		}
+	}
+}
@@ -0,0 +1,105 @@
+use crate::currency::CurrencyId;
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount, SwapResult},
+};
+use sp_runtime::{DispatchError, Permill};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData};
+
+/// No pools, liquidations are only auctioned.
+pub struct NoDex<AccountId>(PhantomData<AccountId>);
+
+impl<AccountId> Amm for NoDex<AccountId> {
+	type AssetId = CurrencyId;
+	type Balance = u128;
+	type AccountId = AccountId;
+	type PoolId = CurrencyPair<CurrencyId>;
+
+	fn pool_exists(_pool_id: Self::PoolId) -> bool {
+		false
+	}
+
+	fn pools(_limit: u32) -> Vec<Self::PoolId> {
+		Vec::new()
+	}
+
+	fn assets(_pool_id: Self::PoolId) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn lp_token(_pool_id: Self::PoolId) -> Result<Self::AssetId, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn redeemable_assets_for_lp_tokens(
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn simulate_add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn simulate_remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn spot_price(
+		_pool_id: Self::PoolId,
+		_base_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		_quote_asset_id: Self::AssetId,
+		_calculate_with_fees: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn do_buy(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_in_asset_id: Self::AssetId,
+		_out_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_assets: BTreeMap<Self::AssetId, Self::Balance>,
+		_min_mint_amount: Self::Balance,
+		_keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_receive: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+
+	fn do_swap(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_in_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		_min_receive: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		Err(DispatchError::Other("no pools"))
+	}
+}
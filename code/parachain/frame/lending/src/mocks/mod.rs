@@ -1,3 +1,4 @@
 pub mod authority_id_wrapper;
+pub mod dex;
 pub mod general;
 pub mod offchain;
@@ -1,5 +1,6 @@
 use self::currency::CurrencyId;
 pub use self::currency::*;
+use super::dex::NoDex;
 use crate::{self as pallet_lending, *};
 use composable_support::math::safe::SafeAdd;
 use composable_traits::{
@@ -25,7 +26,7 @@ use sp_runtime::{
 		BlakeTwo256, ConvertInto, Extrinsic as ExtrinsicT, Header as HeaderTrait, IdentifyAccount,
 		IdentityLookup,
 	},
-	DispatchError, Perbill,
+	DispatchError, Perbill, Permill, Perquintill,
 };
 use xcm::latest::SendXcm;
 
@@ -179,6 +180,11 @@ parameter_types! {
 	pub const MinimumWithdrawal: Balance = 0;
 	pub const VaultPalletId: PalletId = PalletId(*b"cubic___");
   pub const TombstoneDuration: u64 = 42;
+	pub const RebalanceThreshold: Perquintill = Perquintill::from_percent(10);
+	pub const BlocksPerYear: u64 = 365 * 24 * 60 * 60 * 1000 / MILLISECS_PER_BLOCK;
+	pub const MaxManagementFee: Perquintill = Perquintill::from_percent(5);
+	pub const MaxPerformanceFee: Perquintill = Perquintill::from_percent(30);
+	pub const FeeNoticePeriod: u64 = 42;
 }
 
 impl pallet_vault::Config for Runtime {
@@ -195,6 +201,12 @@ impl pallet_vault::Config for Runtime {
 	type CreationDeposit = CreationDeposit;
 	type ExistentialDeposit = ExistentialDeposit;
 	type RentPerBlock = RentPerBlock;
+	type RebalanceThreshold = RebalanceThreshold;
+	type BlocksPerYear = BlocksPerYear;
+	type MaxManagementFee = MaxManagementFee;
+	type MaxPerformanceFee = MaxPerformanceFee;
+	type FeeNoticePeriod = FeeNoticePeriod;
+	type MaxRedemptionsPerCall = ConstU32<16>;
 	type NativeCurrency = Balances;
 	type VaultId = VaultId;
 	type TombstoneDuration = TombstoneDuration;
@@ -271,6 +283,7 @@ parameter_types! {
 	// cspell:disable-next
 	pub const OraclePalletId: PalletId = PalletId(*b"plt_orac");
 	pub const MsPerBlock: u64 = MILLISECS_PER_BLOCK;
+	pub const NormalizedAssetId: CurrencyId = NORMALIZED::ID;
 }
 
 pub struct Decimals;
@@ -292,11 +305,17 @@ impl pallet_oracle::Config for Runtime {
 	type AddOracle = EnsureSignedBy<RootAccount, AccountId>;
 	type MaxAnswerBound = MinU32;
 	type MaxAssetsCount = MinU32;
+	type MaxSigners = MinU32;
 	type MaxHistory = MinU32;
 	type MaxPrePrices = MinU32;
 	type WeightInfo = ();
 	type LocalAssets = Decimals;
+	type NormalizedAssetId = NormalizedAssetId;
+	type LiquidityPools = ();
+	type StakingShares = ();
 	type TreasuryAccount = RootAccount;
+	type SlashLadder = ();
+	type SlashGracePeriod = ();
 	type TwapWindow = TwapWindow;
 	type Balance = Balance;
 	type RewardOrigin = EnsureRoot<AccountId>;
@@ -364,6 +383,7 @@ impl pallet_dutch_auction::Config for Runtime {
 	type XcmOrigin = XcmFake;
 	type AdminOrigin = EnsureRoot<Self::AccountId>;
 	type XcmSender = XcmFake;
+	type LiquidationCallback = Lending;
 }
 
 impl pallet_liquidations::Config for Runtime {
@@ -372,11 +392,14 @@ impl pallet_liquidations::Config for Runtime {
 	type DutchAuction = DutchAuction;
 	type LiquidationStrategyId = LiquidationStrategyId;
 	type OrderId = OrderId;
+	type Dex = NoDex<AccountId>;
+	type LiquidationCallback = Lending;
 	type PalletId = LiquidationsPalletId;
 	type WeightInfo = pallet_liquidations::weights::SubstrateWeight<Self>;
 	type CanModifyStrategies = EnsureRoot<Self::AccountId>;
 	type XcmSender = XcmFake;
 	type MaxLiquidationStrategiesAmount = ConstU32<3>;
+	type InstantSaleSearchSteps = ConstU32<16>;
 }
 
 pub type Extrinsic = TestExtrinsic;
@@ -413,6 +436,8 @@ parameter_types! {
 	pub LendingPalletId: PalletId = PalletId(*b"liquidat");
 	pub OracleMarketCreationStake: Balance = NORMALIZED::ONE;
 	pub const MaxLiquidationBatchSize: u32 = 5;
+	pub const FlashLoanFee: Permill = Permill::from_percent(1);
+	pub const MaxPortfolioMarkets: u32 = 8;
 }
 
 parameter_types! {
@@ -451,6 +476,9 @@ impl pallet_lending::Config for Runtime {
 	type OracleMarketCreationStake = OracleMarketCreationStake;
 	type MaxLiquidationBatchSize = MaxLiquidationBatchSize;
 	type WeightToFee = WeightToFee;
+	type RuntimeCall = RuntimeCall;
+	type FlashLoanFee = FlashLoanFee;
+	type MaxPortfolioMarkets = MaxPortfolioMarkets;
 }
 
 /// Convenience function to set the price of an asset in [`pallet_oracle::Prices`].
@@ -0,0 +1,73 @@
+use crate::models::borrower_data::BorrowerData;
+use composable_support::math::safe::{SafeAdd, SafeDiv};
+use composable_traits::lending::CollateralRatio;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, FixedU128,
+};
+
+/// Health of an account across all the markets of its portfolio.
+///
+/// Each market contributes the value of the account's collateral, discounted by the
+/// `collateral_factor` of the market, and the value of the account's debt. So collateral in one
+/// market backs debt in any other market of the portfolio.
+#[derive(Debug, Default)]
+pub struct AccountHealth {
+	/// Total value which can be borrowed against the collateral of the portfolio, i.e. the sum of
+	/// `collateral value / collateral_factor` over the markets.
+	pub borrow_capacity: FixedU128,
+	/// Same as [`borrow_capacity`], but discounted by the
+	/// [`minimum_safe_collateral_factor`] of each market instead.
+	///
+	/// [`borrow_capacity`]: AccountHealth::borrow_capacity
+	/// [`minimum_safe_collateral_factor`]: BorrowerData::minimum_safe_collateral_factor
+	pub safe_borrow_capacity: FixedU128,
+	/// Total value of the debt of the portfolio, interest included.
+	pub borrow_balance_total_value: FixedU128,
+}
+
+impl AccountHealth {
+	/// Adds the position of the account in one market to the portfolio.
+	pub fn add(&mut self, borrower: &BorrowerData) -> Result<(), ArithmeticError> {
+		self.borrow_capacity =
+			self.borrow_capacity.safe_add(&borrower.max_borrow_for_collateral()?)?;
+		self.safe_borrow_capacity = self.safe_borrow_capacity.safe_add(
+			&borrower
+				.collateral_balance_total_value
+				.safe_div(&borrower.minimum_safe_collateral_factor()?)?,
+		)?;
+		self.borrow_balance_total_value =
+			self.borrow_balance_total_value.safe_add(&borrower.borrow_balance_total_value)?;
+		Ok(())
+	}
+
+	/// The maximum value the account can still borrow in any market of the portfolio.
+	///
+	/// NOTE: Returns `zero` if the account is under-collateralized.
+	pub fn get_borrow_limit(&self) -> FixedU128 {
+		self.borrow_capacity.saturating_sub(self.borrow_balance_total_value)
+	}
+
+	/// The health factor of the account, i.e. the ratio of the borrow capacity to the debt. The
+	/// account is liquidated once it goes below one.
+	pub fn health_factor(&self) -> Result<CollateralRatio<FixedU128>, ArithmeticError> {
+		if self.borrow_balance_total_value.is_zero() {
+			Ok(CollateralRatio::NoBorrowValue)
+		} else {
+			let ratio = self.borrow_capacity.safe_div(&self.borrow_balance_total_value)?;
+			Ok(CollateralRatio::Ratio(ratio))
+		}
+	}
+
+	/// Determines whether the portfolio should be liquidated. Equivalent to
+	/// [`BorrowerData::should_liquidate`] with the markets summed up.
+	pub fn should_liquidate(&self) -> bool {
+		self.borrow_balance_total_value > self.borrow_capacity
+	}
+
+	/// Check if the portfolio is about to go under-collateralized. Equivalent to
+	/// [`BorrowerData::should_warn`] with the markets summed up.
+	pub fn should_warn(&self) -> bool {
+		self.borrow_balance_total_value > self.safe_borrow_capacity
+	}
+}
@@ -72,7 +72,7 @@ impl BorrowerData {
 	/// Returns the amount of collateral asset available in the market for the borrower, i.e. the
 	/// amount not being held as collateral.
 	#[inline(always)]
-	fn max_borrow_for_collateral(&self) -> Result<FixedU128, ArithmeticError> {
+	pub fn max_borrow_for_collateral(&self) -> Result<FixedU128, ArithmeticError> {
 		self.collateral_balance_total_value.safe_div(&self.collateral_factor)
 	}
 
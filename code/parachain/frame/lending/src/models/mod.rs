@@ -1,3 +1,4 @@
 //! Various models used internally throughout the crate.
 
+pub(crate) mod account_health;
 pub(crate) mod borrower_data;
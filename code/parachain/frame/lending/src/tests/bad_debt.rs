@@ -0,0 +1,217 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, create_market_for_liquidation_test, process_and_progress_blocks},
+	BadDebt, LiquidatedPositions, MarketId, MarketReserves,
+};
+use composable_traits::{
+	defi::Take,
+	lending::{MarketSolvency, TotalDebtWithInterest},
+};
+use frame_support::traits::{
+	fungibles::{InspectHold, MutateHold},
+	Hooks,
+};
+use sp_runtime::PerThing;
+
+/// Creates a market with a funded vault and 1 BTC of collateral deposited by BOB.
+fn create_funded_market() -> MarketId {
+	let (market_id, vault_id) = create_market_for_liquidation_test::<Runtime>(*ALICE);
+	let vault_value = USDT::units(100_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), market_id, BTC::ID);
+	market_id
+}
+
+fn total_debt_of(market_id: &MarketId, account: &AccountId) -> Balance {
+	match Lending::total_debt_with_interest(market_id, account).unwrap() {
+		TotalDebtWithInterest::Amount(amount) => amount,
+		TotalDebtWithInterest::NoDebt => 0,
+	}
+}
+
+#[test]
+fn repaid_interest_builds_reserves() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		let principal = USDT::units(20_000);
+		borrow::<Runtime>(*BOB, market_id, principal);
+		process_and_progress_blocks::<Lending, Runtime>(1_000);
+
+		let interest = total_debt_of(&market_id, &BOB) - principal;
+		assert!(interest > 0);
+		// enough to pay the interest
+		assert_ok!(Tokens::mint_into(USDT::ID, &BOB, interest));
+		assert_ok!(Lending::repay_borrow(
+			RuntimeOrigin::signed(*BOB),
+			market_id,
+			*BOB,
+			RepayStrategy::TotalDebt,
+			false,
+		));
+
+		let reserves = DEFAULT_MARKET_VAULT_RESERVE.mul_floor(interest);
+		assert_eq!(MarketReserves::<Runtime>::get(market_id), reserves);
+		assert_eq!(Tokens::balance_on_hold(USDT::ID, &Lending::account_id(&market_id)), reserves);
+	})
+}
+
+#[test]
+fn liquidation_records_debt_left_uncovered_once_settled() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+		process_and_progress_blocks::<Lending, Runtime>(1);
+
+		// 1 BTC of collateral is now worth less than the debt
+		set_price(BTC::ID, NORMALIZED::units(15_000));
+		let debt = total_debt_of(&market_id, &BOB);
+		assert_ok!(Lending::liquidate(
+			RuntimeOrigin::signed(*ALICE),
+			market_id,
+			TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+		));
+		// the auction may fetch more than the oracle value of the collateral
+		assert_eq!(BadDebt::<Runtime>::get(market_id), 0);
+		let (order_id, position) =
+			LiquidatedPositions::<Runtime>::iter().next().expect("auctioned");
+		assert_eq!(position.debt, debt);
+
+		let bob_balance = Tokens::balance(USDT::ID, &BOB);
+		let proceeds = take_auction(order_id, BTC::units(1));
+		let bad_debt = debt - proceeds;
+		assert_eq!(Tokens::balance(USDT::ID, &BOB), bob_balance);
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::BadDebtRecorded {
+			market_id,
+			account: *BOB,
+			amount: bad_debt,
+		}));
+		assert_eq!(Lending::market_solvency(&market_id), MarketSolvency { bad_debt, reserves: 0 });
+	})
+}
+
+#[test]
+fn liquidation_covered_by_collateral_records_no_bad_debt() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+		process_and_progress_blocks::<Lending, Runtime>(1);
+
+		set_price(BTC::ID, NORMALIZED::units(38_000));
+		assert_ok!(Lending::liquidate(
+			RuntimeOrigin::signed(*ALICE),
+			market_id,
+			TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+		));
+		assert_eq!(BadDebt::<Runtime>::get(market_id), 0);
+	})
+}
+
+#[test]
+fn manager_covers_bad_debt_from_reserves() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		let market_account = Lending::account_id(&market_id);
+		let reserves = USDT::units(100);
+		assert_ok!(Tokens::mint_into(USDT::ID, &market_account, reserves));
+		assert_ok!(Tokens::hold(USDT::ID, &market_account, reserves));
+		MarketReserves::<Runtime>::insert(market_id, reserves);
+		BadDebt::<Runtime>::insert(market_id, USDT::units(150));
+
+		assert_noop!(
+			Lending::cover_bad_debt(RuntimeOrigin::signed(*BOB), market_id, USDT::units(50)),
+			Error::<Runtime>::Unauthorized
+		);
+		assert_noop!(
+			Lending::cover_bad_debt(RuntimeOrigin::signed(*ALICE), market_id, USDT::units(200)),
+			Error::<Runtime>::CannotCoverMoreThanBadDebt
+		);
+		assert_noop!(
+			Lending::cover_bad_debt(RuntimeOrigin::signed(*ALICE), market_id, USDT::units(120)),
+			Error::<Runtime>::NotEnoughReserves
+		);
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::cover_bad_debt(RuntimeOrigin::signed(*ALICE), market_id, USDT::units(80)),
+			RuntimeEvent::Lending(crate::Event::MarketSolvencyUpdated {
+				market_id,
+				bad_debt: USDT::units(70),
+				reserves: USDT::units(20),
+			}),
+		);
+		assert_eq!(Tokens::balance_on_hold(USDT::ID, &market_account), USDT::units(20));
+	})
+}
+
+/// Liquidates BOB, borrowing 20 000 USDT against 1 BTC worth 38 000 USDT, and returns the id of
+/// the auction selling the collateral with the debt of BOB.
+fn liquidate_covered_position(market_id: MarketId) -> (OrderId, Balance) {
+	borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	set_price(BTC::ID, NORMALIZED::units(38_000));
+	let debt = total_debt_of(&market_id, &BOB);
+	assert_ok!(Lending::liquidate(
+		RuntimeOrigin::signed(*ALICE),
+		market_id,
+		TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+	));
+	let (order_id, position) = LiquidatedPositions::<Runtime>::iter().next().expect("auctioned");
+	assert_eq!((position.market_id, position.borrower, position.debt), (market_id, *BOB, debt));
+	(order_id, debt)
+}
+
+/// CHARLIE takes `amount` of BTC of the auction at its limit price. Returns the USDT paid.
+fn take_auction(order_id: OrderId, amount: Balance) -> Balance {
+	let order = pallet_dutch_auction::SellOrders::<Runtime>::get(order_id).expect("auctioned");
+	let take = Take::new(amount, order.order.take.limit);
+	let paid = take.quote_limit_amount().unwrap();
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, paid));
+	assert_ok!(DutchAuction::take(RuntimeOrigin::signed(*CHARLIE), order_id, take));
+	DutchAuction::on_finalize(System::block_number());
+	paid
+}
+
+#[test]
+fn auction_proceeds_settle_debt_and_excess_is_released() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		let (order_id, debt) = liquidate_covered_position(market_id);
+		let bob_balance = Tokens::balance(USDT::ID, &BOB);
+
+		let proceeds = take_auction(order_id, BTC::units(1));
+
+		assert_eq!(Tokens::balance(USDT::ID, &BOB), bob_balance + proceeds - debt);
+		assert!(!LiquidatedPositions::<Runtime>::contains_key(order_id));
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::LiquidationSettled {
+			market_id,
+			account: *BOB,
+			collateral_released: 0,
+		}));
+		assert_eq!(BadDebt::<Runtime>::get(market_id), 0);
+	})
+}
+
+#[test]
+fn expired_auction_records_uncovered_debt() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		let (order_id, debt) = liquidate_covered_position(market_id);
+
+		let proceeds = take_auction(order_id, BTC::units(1) / 2);
+		assert_eq!(LiquidatedPositions::<Runtime>::get(order_id).unwrap().debt, debt - proceeds);
+
+		// removed by the market before it is sold in full
+		assert_ok!(DutchAuction::liquidate(
+			RuntimeOrigin::signed(Lending::account_id(&market_id)),
+			order_id
+		));
+
+		assert_eq!(BadDebt::<Runtime>::get(market_id), debt - proceeds);
+		System::assert_has_event(RuntimeEvent::Lending(crate::Event::BadDebtRecorded {
+			market_id,
+			account: *BOB,
+			amount: debt - proceeds,
+		}));
+	})
+}
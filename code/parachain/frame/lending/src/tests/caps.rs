@@ -0,0 +1,179 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, process_and_progress_blocks},
+	MarketId, UpdateInputOf,
+};
+use composable_traits::lending::IsolationMode;
+
+/// Creates a market with a funded vault and applies `update` to its configuration.
+fn create_capped_market(update: impl FnOnce(&mut UpdateInputOf<Runtime>)) -> MarketId {
+	let (market_id, vault_id) = create_simple_market();
+	let vault_value = USDT::units(100_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+
+	let market = crate::Markets::<Runtime>::get(market_id).unwrap();
+	let mut input = UpdateInput {
+		collateral_factor: market.collateral_factor,
+		under_collateralized_warn_percent: market.under_collateralized_warn_percent,
+		liquidators: market.liquidators,
+		max_price_age: market.max_price_age,
+		supply_cap: market.supply_cap,
+		borrow_cap: market.borrow_cap,
+		isolation: market.isolation,
+	};
+	update(&mut input);
+	assert_ok!(Lending::update_market(RuntimeOrigin::signed(*ALICE), market_id, input));
+	market_id
+}
+
+#[test]
+fn supply_cap_limits_deposited_collateral() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_capped_market(|input| input.supply_cap = Some(BTC::units(2)));
+
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(2), market_id, BTC::ID);
+		assert_ok!(Tokens::mint_into(BTC::ID, &CHARLIE, BTC::ONE));
+		assert_noop!(
+			Lending::deposit_collateral(
+				RuntimeOrigin::signed(*CHARLIE),
+				market_id,
+				BTC::ONE,
+				false
+			),
+			Error::<Runtime>::SupplyCapReached
+		);
+	});
+}
+
+#[test]
+fn collateral_sent_to_the_market_does_not_count_towards_the_supply_cap() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_capped_market(|input| input.supply_cap = Some(BTC::units(2)));
+
+		assert_ok!(Tokens::mint_into(BTC::ID, &Lending::account_id(&market_id), BTC::units(5)));
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(2), market_id, BTC::ID);
+		assert_eq!(crate::TotalCollateral::<Runtime>::get(market_id), BTC::units(2));
+
+		assert_ok!(Lending::withdraw_collateral(RuntimeOrigin::signed(*BOB), market_id, BTC::ONE));
+		assert_eq!(crate::TotalCollateral::<Runtime>::get(market_id), BTC::ONE);
+	});
+}
+
+#[test]
+fn borrow_cap_limits_total_borrowed() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_capped_market(|input| input.borrow_cap = Some(USDT::units(10_000)));
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::ONE, market_id, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*CHARLIE, BTC::ONE, market_id, BTC::ID);
+
+		borrow::<Runtime>(*BOB, market_id, USDT::units(10_000));
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*CHARLIE), market_id, USDT::ONE),
+			Error::<Runtime>::BorrowCapReached
+		);
+	});
+}
+
+#[test]
+fn isolated_collateral_only_backs_whitelisted_assets() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_capped_market(|input| {
+			input.isolation = Some(IsolationMode {
+				borrowable_assets: vec![BTC::ID],
+				debt_ceiling: NORMALIZED::units(1_000_000),
+			})
+		});
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::ONE, market_id, BTC::ID);
+
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), market_id, USDT::units(10_000)),
+			Error::<Runtime>::AssetNotBorrowableAgainstIsolatedCollateral
+		);
+	});
+}
+
+#[test]
+fn isolated_collateral_debt_is_capped_by_the_ceiling() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_capped_market(|input| {
+			input.isolation = Some(IsolationMode {
+				borrowable_assets: vec![USDT::ID],
+				debt_ceiling: NORMALIZED::units(15_000),
+			})
+		});
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::ONE, market_id, BTC::ID);
+		mint_and_deposit_collateral::<Runtime>(*CHARLIE, BTC::ONE, market_id, BTC::ID);
+
+		borrow::<Runtime>(*BOB, market_id, USDT::units(10_000));
+		assert_eq!(crate::IsolatedDebt::<Runtime>::get(market_id), NORMALIZED::units(10_000));
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*CHARLIE), market_id, USDT::units(10_000)),
+			Error::<Runtime>::DebtCeilingReached
+		);
+
+		// repaying gives the debt ceiling back
+		process_and_progress_blocks::<Lending, Runtime>(1);
+		assert_ok!(Tokens::mint_into(USDT::ID, &BOB, USDT::units(1)));
+		assert_ok!(Lending::repay_borrow(
+			RuntimeOrigin::signed(*BOB),
+			market_id,
+			*BOB,
+			RepayStrategy::TotalDebt,
+			false,
+		));
+		assert_eq!(crate::IsolatedDebt::<Runtime>::get(market_id), 0);
+		assert!(!crate::IsolatedBorrows::<Runtime>::contains_key(market_id, *BOB));
+		borrow::<Runtime>(*CHARLIE, market_id, USDT::units(10_000));
+	});
+}
+
+/// Creates a market whose collateral is isolated and only backs BTC borrows, and an unrestricted
+/// market in which BOB borrows 10 000 USDT.
+fn create_isolated_market_and_borrow() -> (MarketId, MarketId) {
+	let isolated_market = create_capped_market(|input| {
+		input.isolation = Some(IsolationMode {
+			borrowable_assets: vec![BTC::ID],
+			debt_ceiling: NORMALIZED::units(1_000_000),
+		})
+	});
+	let debt_market = create_capped_market(|_| {});
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::ONE, debt_market, BTC::ID);
+	borrow::<Runtime>(*BOB, debt_market, USDT::units(10_000));
+	(isolated_market, debt_market)
+}
+
+#[test]
+fn isolated_collateral_cannot_join_a_portfolio_with_other_debt() {
+	new_test_ext().execute_with(|| {
+		let (isolated_market, debt_market) = create_isolated_market_and_borrow();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::ONE, isolated_market, BTC::ID);
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), isolated_market));
+
+		assert_noop!(
+			Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), debt_market),
+			Error::<Runtime>::AssetNotBorrowableAgainstIsolatedCollateral
+		);
+	});
+}
+
+#[test]
+fn isolated_collateral_cannot_be_deposited_into_a_portfolio_with_other_debt() {
+	new_test_ext().execute_with(|| {
+		let (isolated_market, debt_market) = create_isolated_market_and_borrow();
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), isolated_market));
+		assert_ok!(Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), debt_market));
+
+		assert_ok!(Tokens::mint_into(BTC::ID, &BOB, BTC::ONE));
+		assert_noop!(
+			Lending::deposit_collateral(
+				RuntimeOrigin::signed(*BOB),
+				isolated_market,
+				BTC::ONE,
+				false
+			),
+			Error::<Runtime>::AssetNotBorrowableAgainstIsolatedCollateral
+		);
+	});
+}
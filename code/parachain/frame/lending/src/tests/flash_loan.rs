@@ -0,0 +1,92 @@
+use super::prelude::*;
+use crate::tests::process_and_progress_blocks;
+use composable_traits::vault::Vault as VaultTrait;
+use frame_support::traits::Get;
+use sp_runtime::PerThing;
+
+/// Creates a market and fills its account with borrow asset from the vault.
+fn create_funded_market() -> (crate::MarketId, VaultId) {
+	let (market_id, vault_id) = create_simple_market();
+	let vault_deposit = USDT::units(1_000_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_deposit));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_deposit));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	(market_id, vault_id)
+}
+
+fn remark() -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }))
+}
+
+#[test]
+fn flash_loan_is_repaid_with_fee() {
+	new_test_ext().execute_with(|| {
+		let (market_id, vault_id) = create_funded_market();
+		let market_account = Lending::account_id(&market_id);
+		let vault_account = <Vault as VaultTrait>::account_id(&vault_id);
+		let market_cash = Tokens::balance(USDT::ID, &market_account);
+		let vault_cash = Tokens::balance(USDT::ID, &vault_account);
+
+		let amount = USDT::units(100_000);
+		let fee = FlashLoanFee::get().mul_ceil(amount);
+		assert_ok!(Tokens::mint_into(USDT::ID, &ALICE, fee));
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::flash_loan(RuntimeOrigin::signed(*ALICE), market_id, amount, remark()),
+			RuntimeEvent::Lending(crate::Event::FlashLoaned {
+				sender: *ALICE,
+				market_id,
+				amount,
+				fee,
+			}),
+		);
+
+		assert_eq!(Tokens::balance(USDT::ID, &ALICE), 0);
+		assert_eq!(Tokens::balance(USDT::ID, &market_account), market_cash);
+		assert_eq!(Tokens::balance(USDT::ID, &vault_account), vault_cash + fee);
+	});
+}
+
+#[test]
+fn flash_loan_must_be_repaid() {
+	new_test_ext().execute_with(|| {
+		let (market_id, _) = create_funded_market();
+		let amount = USDT::units(100_000);
+		assert_ok!(Tokens::mint_into(USDT::ID, &ALICE, FlashLoanFee::get().mul_ceil(amount)));
+
+		// the borrowed funds are sent away, so nothing is left to pay back the loan with
+		let transfer = Box::new(RuntimeCall::Tokens(orml_tokens::Call::transfer {
+			dest: *BOB,
+			currency_id: USDT::ID,
+			amount,
+		}));
+		assert_noop!(
+			Lending::flash_loan(RuntimeOrigin::signed(*ALICE), market_id, amount, transfer),
+			Error::<Runtime>::FlashLoanNotRepaid
+		);
+
+		// the fee is owed as well
+		assert_noop!(
+			Lending::flash_loan(RuntimeOrigin::signed(*BOB), market_id, amount, remark()),
+			Error::<Runtime>::FlashLoanNotRepaid
+		);
+	});
+}
+
+#[test]
+fn flash_loan_fails_with_the_dispatched_call() {
+	new_test_ext().execute_with(|| {
+		let (market_id, _) = create_funded_market();
+		let amount = USDT::units(100_000);
+
+		let nested = Box::new(RuntimeCall::Lending(crate::Call::flash_loan {
+			market_id,
+			amount: 0,
+			call: remark(),
+		}));
+		assert_noop!(
+			Lending::flash_loan(RuntimeOrigin::signed(*ALICE), market_id, amount, nested),
+			Error::<Runtime>::CannotFlashLoanZero
+		);
+	});
+}
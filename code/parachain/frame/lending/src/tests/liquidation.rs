@@ -306,6 +306,9 @@ fn market_owner_cannot_retroactively_liquidate() {
 			under_collateralized_warn_percent: Percent::from_float(1.1),
 			liquidators: vec![],
 			max_price_age: DEFAULT_MAX_PRICE_AGE,
+			supply_cap: None,
+			borrow_cap: None,
+			isolation: None,
 		};
 		// ALICE is the creator of the market.
 		assert_noop!(
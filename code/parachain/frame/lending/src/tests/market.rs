@@ -23,6 +23,9 @@ fn can_update_market() {
 			under_collateralized_warn_percent: market.under_collateralized_warn_percent,
 			liquidators: market.liquidators.clone(),
 			max_price_age: market.max_price_age,
+			supply_cap: market.supply_cap,
+			borrow_cap: market.borrow_cap,
+			isolation: market.isolation.clone(),
 		};
 		let updated = Lending::update_market(origin, market_id, update_input.clone());
 		// check if the market was successfully updated
@@ -38,6 +41,9 @@ fn can_update_market() {
 			under_collateralized_warn_percent: market.under_collateralized_warn_percent,
 			liquidators: market.liquidators,
 			max_price_age: market.max_price_age,
+			supply_cap: market.supply_cap,
+			borrow_cap: market.borrow_cap,
+			isolation: market.isolation,
 		};
 		assert_err!(
 			update_input.try_into_validated::<UpdateInputValid>(),
@@ -20,13 +20,18 @@ use frame_system::Config as FrameSystemConfig;
 use pallet_timestamp::Config as PalletTimestampConfig;
 use sp_runtime::traits::One;
 
+pub mod bad_debt;
 pub mod borrow;
+pub mod caps;
+pub mod flash_loan;
 pub mod interest;
 pub mod liquidation;
 pub mod market;
 pub mod offchain;
+pub mod portfolio;
 pub mod prelude;
 pub mod repay;
+pub mod stats;
 pub mod vault;
 
 pub const DEFAULT_MARKET_VAULT_RESERVE: Perquintill = Perquintill::from_percent(10);
@@ -53,13 +58,16 @@ impl ConfigBound for Runtime {}
 /// Creates a "default" [`CreateInput`], with the specified [`CurrencyPair`].
 fn default_create_input<AssetId, BlockNumber: sp_runtime::traits::Bounded>(
 	currency_pair: CurrencyPair<AssetId>,
-) -> CreateInput<u32, AssetId, BlockNumber> {
+) -> CreateInput<u32, AssetId, Balance, BlockNumber> {
 	CreateInput {
 		updatable: UpdateInput {
 			collateral_factor: default_collateral_factor(),
 			under_collateralized_warn_percent: default_under_collateralized_warn_percent(),
 			liquidators: vec![],
 			max_price_age: BlockNumber::max_value(),
+			supply_cap: None,
+			borrow_cap: None,
+			isolation: None,
 		},
 		interest_rate_model: InterestRateModel::default(),
 		reserved_factor: DEFAULT_MARKET_VAULT_RESERVE,
@@ -156,6 +164,9 @@ where
 			under_collateralized_warn_percent: default_under_collateralized_warn_percent(),
 			liquidators: vec![],
 			max_price_age: DEFAULT_MAX_PRICE_AGE,
+			supply_cap: None,
+			borrow_cap: None,
+			isolation: None,
 		},
 		interest_rate_model: InterestRateModel::default(),
 		reserved_factor,
@@ -0,0 +1,176 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, process_and_progress_blocks},
+	AccountCollateral, BadDebt, LiquidatedPositions,
+};
+use composable_traits::{
+	defi::Take,
+	lending::{CollateralRatio, TotalDebtWithInterest},
+};
+use frame_support::traits::Hooks;
+
+/// Creates two USDT/BTC markets with funded vaults. BOB deposits 1 BTC of collateral, worth
+/// `50_000` USDT, into the first one only.
+fn create_portfolio_markets() -> (crate::MarketId, crate::MarketId) {
+	let (collateral_market, collateral_vault) = create_simple_market();
+	let (debt_market, debt_vault) = create_simple_market();
+	for vault_id in [collateral_vault, debt_vault] {
+		let vault_value = USDT::units(100_000_000);
+		assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+		assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	}
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), collateral_market, BTC::ID);
+	(collateral_market, debt_market)
+}
+
+fn enter_portfolio(market_id: crate::MarketId) {
+	assert_extrinsic_event::<Runtime>(
+		Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), market_id),
+		RuntimeEvent::Lending(crate::Event::PortfolioMarketEntered { account: *BOB, market_id }),
+	);
+}
+
+#[test]
+fn portfolio_collateral_backs_debt_in_other_markets() {
+	new_test_ext().execute_with(|| {
+		let (collateral_market, debt_market) = create_portfolio_markets();
+
+		assert_noop!(
+			Lending::borrow(RuntimeOrigin::signed(*BOB), debt_market, USDT::units(20_000)),
+			Error::<Runtime>::NotEnoughCollateralToBorrow
+		);
+
+		enter_portfolio(collateral_market);
+		enter_portfolio(debt_market);
+		// 1 BTC at collateral factor 2 backs up to 25_000 USDT, in any market of the portfolio
+		assert_eq!(
+			Lending::get_borrow_limit(&debt_market, &BOB),
+			Lending::get_borrow_limit(&collateral_market, &BOB)
+		);
+		borrow::<Runtime>(*BOB, debt_market, USDT::units(20_000));
+
+		let health = Lending::portfolio_health(&BOB).unwrap();
+		assert!(!health.should_liquidate());
+		assert!(
+			matches!(health.health_factor(), Ok(CollateralRatio::Ratio(ratio)) if ratio > FixedU128::saturating_from_integer(1))
+		);
+	});
+}
+
+#[test]
+fn cannot_withdraw_collateral_backing_portfolio_debt() {
+	new_test_ext().execute_with(|| {
+		let (collateral_market, debt_market) = create_portfolio_markets();
+		enter_portfolio(collateral_market);
+		enter_portfolio(debt_market);
+		borrow::<Runtime>(*BOB, debt_market, USDT::units(20_000));
+
+		assert_noop!(
+			Lending::withdraw_collateral(
+				RuntimeOrigin::signed(*BOB),
+				collateral_market,
+				BTC::units(1)
+			),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+		// 0.9 BTC still back 22_500 USDT
+		assert_ok!(Lending::withdraw_collateral(
+			RuntimeOrigin::signed(*BOB),
+			collateral_market,
+			BTC::units(1) / 10
+		));
+	});
+}
+
+#[test]
+fn cannot_exit_portfolio_with_unbacked_debt() {
+	new_test_ext().execute_with(|| {
+		let (collateral_market, debt_market) = create_portfolio_markets();
+		assert_noop!(
+			Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), collateral_market),
+			Error::<Runtime>::MarketNotInPortfolio
+		);
+		enter_portfolio(collateral_market);
+		assert_noop!(
+			Lending::enter_portfolio(RuntimeOrigin::signed(*BOB), collateral_market),
+			Error::<Runtime>::MarketAlreadyInPortfolio
+		);
+		enter_portfolio(debt_market);
+		borrow::<Runtime>(*BOB, debt_market, USDT::units(20_000));
+
+		assert_noop!(
+			Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), debt_market),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+		assert_noop!(
+			Lending::exit_portfolio(RuntimeOrigin::signed(*BOB), collateral_market),
+			Error::<Runtime>::WouldGoUnderCollateralized
+		);
+	});
+}
+
+#[test]
+fn portfolio_liquidation_repays_debt_from_other_markets() {
+	new_test_ext().execute_with(|| {
+		let (collateral_market, debt_market) = create_portfolio_markets();
+		let (spare_market, _) = create_simple_market();
+		mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1) / 10, spare_market, BTC::ID);
+		enter_portfolio(collateral_market);
+		enter_portfolio(debt_market);
+		enter_portfolio(spare_market);
+		borrow::<Runtime>(*BOB, debt_market, USDT::units(20_000));
+
+		// 1.1 BTC now back only 18_700 USDT
+		set_price(BTC::ID, NORMALIZED::units(34_000));
+		assert!(Lending::should_liquidate(&collateral_market, &BOB).unwrap());
+		let debt = match Lending::total_debt_with_interest(&debt_market, &BOB).unwrap() {
+			TotalDebtWithInterest::Amount(amount) => amount,
+			TotalDebtWithInterest::NoDebt => 0,
+		};
+
+		assert_extrinsic_event::<Runtime>(
+			Lending::liquidate(
+				RuntimeOrigin::signed(*ALICE),
+				collateral_market,
+				TestBoundedVec::try_from(vec![*BOB]).unwrap(),
+			),
+			RuntimeEvent::Lending(crate::Event::LiquidationInitiated {
+				market_id: collateral_market,
+				borrowers: vec![*BOB],
+			}),
+		);
+		assert_eq!(
+			Lending::total_debt_with_interest(&debt_market, &BOB).unwrap(),
+			TotalDebtWithInterest::NoDebt
+		);
+		// the first market covers the debt and is sold, the other one is left to BOB
+		assert_eq!(AccountCollateral::<Runtime>::get(collateral_market, *BOB), None);
+		assert_eq!(AccountCollateral::<Runtime>::get(spare_market, *BOB), Some(BTC::units(1) / 10));
+		let (order_id, position) =
+			LiquidatedPositions::<Runtime>::iter().next().expect("auctioned");
+		assert_eq!(
+			(position.market_id, position.collateral_market_id, position.debt),
+			(debt_market, collateral_market, debt)
+		);
+
+		// proceeds repay the debt market, the excess is released to BOB
+		let debt_market_balance = Tokens::balance(USDT::ID, &Lending::account_id(&debt_market));
+		let bob_balance = Tokens::balance(USDT::ID, &BOB);
+		let order = pallet_dutch_auction::SellOrders::<Runtime>::get(order_id).expect("auctioned");
+		assert_eq!(order.order.take.amount, BTC::units(1));
+		let take = Take::new(BTC::units(1), order.order.take.limit);
+		let paid = take.quote_limit_amount().unwrap();
+		assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, paid));
+		assert_ok!(DutchAuction::take(RuntimeOrigin::signed(*CHARLIE), order_id, take));
+		DutchAuction::on_finalize(System::block_number());
+
+		assert_eq!(
+			Tokens::balance(USDT::ID, &Lending::account_id(&debt_market)),
+			debt_market_balance + debt
+		);
+		assert_eq!(Tokens::balance(USDT::ID, &BOB), bob_balance + paid - debt);
+		assert!(!LiquidatedPositions::<Runtime>::contains_key(order_id));
+		assert_eq!(BadDebt::<Runtime>::get(debt_market), 0);
+	});
+}
@@ -0,0 +1,72 @@
+use super::prelude::*;
+use crate::{
+	tests::{borrow, create_market_for_liquidation_test, process_and_progress_blocks},
+	MarketId,
+};
+use composable_traits::lending::AccountPosition;
+use sp_runtime::traits::Zero;
+
+/// Creates a market with a funded vault and 1 BTC of collateral deposited by BOB.
+fn create_funded_market() -> MarketId {
+	let (market_id, vault_id) = create_market_for_liquidation_test::<Runtime>(*ALICE);
+	let vault_value = USDT::units(100_000);
+	assert_ok!(Tokens::mint_into(USDT::ID, &CHARLIE, vault_value));
+	assert_ok!(Vault::deposit(RuntimeOrigin::signed(*CHARLIE), vault_id, vault_value));
+	process_and_progress_blocks::<Lending, Runtime>(1);
+	mint_and_deposit_collateral::<Runtime>(*BOB, BTC::units(1), market_id, BTC::ID);
+	market_id
+}
+
+#[test]
+fn account_position_reports_collateral_debt_and_limit() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		assert_eq!(
+			Lending::account_position(&market_id, &BOB),
+			Ok(AccountPosition {
+				collateral: BTC::units(1),
+				debt_with_interest: 0,
+				borrow_limit: Lending::get_borrow_limit(&market_id, &BOB).unwrap(),
+			})
+		);
+
+		borrow::<Runtime>(*BOB, market_id, USDT::units(10_000));
+		process_and_progress_blocks::<Lending, Runtime>(100);
+
+		let position = Lending::account_position(&market_id, &BOB).unwrap();
+		assert!(position.debt_with_interest > USDT::units(10_000));
+		assert_eq!(position.borrow_limit, Lending::get_borrow_limit(&market_id, &BOB).unwrap());
+	})
+}
+
+#[test]
+fn market_stats_follow_utilization() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		let idle = Lending::market_stats(&market_id).unwrap();
+		assert_eq!(idle.utilization, Percent::zero());
+		assert_eq!(idle.supply_apy, FixedU128::zero());
+
+		borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+		let busy = Lending::market_stats(&market_id).unwrap();
+		assert!(busy.utilization > Percent::zero());
+		assert!(busy.borrow_apy > idle.borrow_apy);
+		assert!(busy.supply_apy > FixedU128::zero());
+		assert!(busy.supply_apy < busy.borrow_apy);
+		// compounding yields more than the plain yearly rate
+		assert!(busy.borrow_apy > Lending::borrow_rate(&market_id).unwrap());
+	})
+}
+
+#[test]
+fn accounts_to_warn_lists_borrowers_close_to_liquidation() {
+	new_test_ext().execute_with(|| {
+		let market_id = create_funded_market();
+		borrow::<Runtime>(*BOB, market_id, USDT::units(20_000));
+		assert_eq!(Lending::accounts_to_warn(&market_id), vec![]);
+
+		// 1 BTC now backs 20_000 USDT with a ratio just above the collateral factor of 2
+		set_price(BTC::ID, NORMALIZED::units(41_000));
+		assert_eq!(Lending::accounts_to_warn(&market_id), vec![*BOB]);
+	})
+}
@@ -77,6 +77,20 @@ impl Display for MarketId {
 	}
 }
 
+/// A position sent to liquidation, settled with the proceeds of the sale of its collateral.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
+pub struct LiquidatedPosition<AccountId, Balance> {
+	/// Market the debt is owed to, which the proceeds are paid to.
+	pub market_id: MarketId,
+	/// Market holding the collateral sold. Differs from `market_id` when a portfolio is
+	/// liquidated.
+	pub collateral_market_id: MarketId,
+	pub borrower: AccountId,
+	/// Debt of the position, in borrow asset, neither covered by proceeds yet nor recorded as
+	/// bad debt.
+	pub debt: Balance,
+}
+
 pub(crate) struct MarketAssets<T: DeFiComposableConfig> {
 	/// The borrow asset for the market.
 	pub(crate) borrow_asset: <T as DeFiComposableConfig>::MayBeAssetId,
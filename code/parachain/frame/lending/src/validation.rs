@@ -11,12 +11,13 @@ use sp_runtime::traits::{One, Zero};
 #[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, Default)]
 pub struct UpdateInputValid;
 
-impl<LiquidationStrategyId, BlockNumber>
-	Validate<UpdateInput<LiquidationStrategyId, BlockNumber>, UpdateInputValid> for UpdateInputValid
+impl<LiquidationStrategyId, Asset, Balance, BlockNumber>
+	Validate<UpdateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, UpdateInputValid>
+	for UpdateInputValid
 {
 	fn validate(
-		update_input: UpdateInput<LiquidationStrategyId, BlockNumber>,
-	) -> Result<UpdateInput<LiquidationStrategyId, BlockNumber>, &'static str> {
+		update_input: UpdateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>,
+	) -> Result<UpdateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, &'static str> {
 		if update_input.collateral_factor < MoreThanOneFixedU128::one() {
 			return Err("Collateral factor must be more than one.")
 		}
@@ -30,13 +31,13 @@ pub struct MarketModelValid;
 #[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo, Default)]
 pub struct CurrencyPairIsNotSame;
 
-impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
-	Validate<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, MarketModelValid>
+impl<LiquidationStrategyId, Asset: Eq, Balance, BlockNumber>
+	Validate<CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, MarketModelValid>
 	for MarketModelValid
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>,
+	) -> Result<CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, &'static str> {
 		let updatable = create_input.updatable.try_into_validated::<UpdateInputValid>()?.value();
 		let interest_rate_model = create_input
 			.interest_rate_model
@@ -47,13 +48,13 @@ impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
 	}
 }
 
-impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
-	Validate<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, CurrencyPairIsNotSame>
+impl<LiquidationStrategyId, Asset: Eq, Balance, BlockNumber>
+	Validate<CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, CurrencyPairIsNotSame>
 	for CurrencyPairIsNotSame
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>,
+	) -> Result<CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, &'static str> {
 		if create_input.currency_pair.base == create_input.currency_pair.quote {
 			Err("Base and quote currencies supposed to be different in currency pair")
 		} else {
@@ -65,15 +66,21 @@ impl<LiquidationStrategyId, Asset: Eq, BlockNumber>
 #[derive(RuntimeDebug, PartialEq, Eq, TypeInfo, Default, Clone, Copy)]
 pub struct AssetIsSupportedByOracle<Oracle: OracleTrait>(PhantomData<Oracle>);
 
-impl<LiquidationStrategyId, Asset: Copy, BlockNumber, Oracle: OracleTrait<AssetId = Asset>>
+impl<
+		LiquidationStrategyId,
+		Asset: Copy,
+		Balance,
+		BlockNumber,
+		Oracle: OracleTrait<AssetId = Asset>,
+	>
 	Validate<
-		CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
+		CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>,
 		AssetIsSupportedByOracle<Oracle>,
 	> for AssetIsSupportedByOracle<Oracle>
 {
 	fn validate(
-		create_input: CreateInput<LiquidationStrategyId, Asset, BlockNumber>,
-	) -> Result<CreateInput<LiquidationStrategyId, Asset, BlockNumber>, &'static str> {
+		create_input: CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>,
+	) -> Result<CreateInput<LiquidationStrategyId, Asset, Balance, BlockNumber>, &'static str> {
 		ensure!(
 			Oracle::is_supported(create_input.borrow_asset())?,
 			"Borrow asset is not supported by oracle"
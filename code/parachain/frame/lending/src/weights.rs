@@ -25,6 +25,10 @@ pub trait WeightInfo {
 	fn handle_withdrawable() -> Weight;
 	fn handle_depositable() -> Weight;
 	fn handle_must_liquidate() -> Weight;
+	fn flash_loan() -> Weight;
+	fn enter_portfolio() -> Weight;
+	fn exit_portfolio() -> Weight;
+	fn cover_bad_debt() -> Weight;
 }
 
 impl WeightInfo for () {
@@ -99,4 +103,24 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	fn flash_loan() -> Weight {
+		Weight::from_ref_time(142_318_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn enter_portfolio() -> Weight {
+		Weight::from_ref_time(61_437_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn exit_portfolio() -> Weight {
+		Weight::from_ref_time(66_012_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn cover_bad_debt() -> Weight {
+		Weight::from_ref_time(41_287_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 }
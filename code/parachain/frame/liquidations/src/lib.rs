@@ -59,19 +59,26 @@ pub mod pallet {
 		math::wrapping_next::WrappingNext,
 	};
 	use composable_traits::{
-		defi::{DeFiComposableConfig, DeFiEngine, Sell, SellEngine},
-		liquidation::Liquidation,
+		defi::{CurrencyPair, DeFiComposableConfig, DeFiEngine, OrderIdLike, Sell, SellEngine},
+		dex::{Amm, AssetAmount},
+		liquidation::{Liquidation, LiquidationCallback},
 		time::{LinearDecrease, StairstepExponentialDecrease, TimeReleaseFunction},
 	};
 	use frame_support::{
 		dispatch::DispatchResultWithPostInfo,
 		pallet_prelude::{OptionQuery, StorageMap, StorageValue},
+		storage::{with_transaction, TransactionOutcome},
 		traits::{EnsureOrigin, Get, IsType, UnixTime},
+		transactional,
+		weights::Weight,
 		BoundedVec, PalletId, Parameter, Twox64Concat,
 	};
 	use frame_system::{ensure_signed, pallet_prelude::OriginFor};
 	use scale_info::TypeInfo;
-	use sp_runtime::{DispatchError, Permill, Perquintill};
+	use sp_runtime::{
+		traits::{Saturating, Zero},
+		DispatchError, PerThing, Permill, Perquintill,
+	};
 	use sp_std::vec::Vec;
 
 	#[cfg(feature = "std")]
@@ -102,7 +109,23 @@ pub mod pallet {
 			+ Copy
 			+ From<u32>;
 
-		type OrderId: Default + FullCodec + MaxEncodedLen + sp_std::fmt::Debug;
+		type OrderId: OrderIdLike;
+
+		/// Sells instantly for `Pablo` strategies, e.g. the dex router.
+		type Dex: Amm<
+			AssetId = <Self as DeFiComposableConfig>::MayBeAssetId,
+			Balance = Self::Balance,
+			AccountId = Self::AccountId,
+			PoolId = CurrencyPair<<Self as DeFiComposableConfig>::MayBeAssetId>,
+		>;
+
+		/// Notified of the part of liquidations sold instantly.
+		type LiquidationCallback: LiquidationCallback<
+			Self::AccountId,
+			<Self as DeFiComposableConfig>::MayBeAssetId,
+			Self::Balance,
+			Self::OrderId,
+		>;
 
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
@@ -117,12 +140,25 @@ pub mod pallet {
 
 		type CanModifyStrategies: EnsureOrigin<Self::RuntimeOrigin>;
 		type MaxLiquidationStrategiesAmount: Get<u32>;
+
+		/// Number of steps of the search of the largest amount a `Pablo` strategy sells instantly
+		/// within slippage, each of them quoting the pool.
+		#[pallet::constant]
+		type InstantSaleSearchSteps: Get<u32>;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (crate) fn deposit_event)]
 	pub enum Event<T: Config> {
 		PositionWasSentToLiquidation {},
+		/// A part of a liquidation was sold instantly, `remaining` is sent to the next strategies.
+		PositionWasSoldInstantly {
+			from_to: T::AccountId,
+			strategy_id: T::LiquidationStrategyId,
+			sold: T::Balance,
+			received: T::Balance,
+			remaining: T::Balance,
+		},
 	}
 
 	#[pallet::error]
@@ -152,7 +188,7 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(T::WeightInfo::sell(configuration.len().max(1) as u32))]
+		#[pallet::weight(Pallet::<T>::sell_weight(configuration.len().max(1) as u32))]
 		pub fn sell(
 			origin: OriginFor<T>,
 			order: Sell<T::MayBeAssetId, T::Balance>,
@@ -250,41 +286,152 @@ pub mod pallet {
 			from_to: &Self::AccountId,
 			order: Sell<Self::MayBeAssetId, Self::Balance>,
 			configuration: Vec<Self::LiquidationStrategyId>,
-		) -> Result<T::OrderId, DispatchError> {
+		) -> Result<Option<T::OrderId>, DispatchError> {
 			let configuration = BoundedVec::try_from(configuration)
 				.map_err(|()| Error::<T>::InvalidLiquidationStrategiesVector)?;
 			Self::do_liquidate(from_to, order, configuration)
 		}
+
+		fn liquidate_weight() -> Weight {
+			Self::sell_weight(T::MaxLiquidationStrategiesAmount::get())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Weight of selling with `strategies` strategies, any of which may sell instantly.
+		pub fn sell_weight(strategies: u32) -> Weight {
+			T::WeightInfo::sell(strategies).saturating_add(
+				T::WeightInfo::sell_instantly(T::InstantSaleSearchSteps::get())
+					.saturating_mul(strategies.into()),
+			)
+		}
+
+		/// Tries the strategies in order, until the order is sold. `Pablo` strategies sell as much
+		/// as they can instantly, and the rest is sent to the next strategies, so that an order is
+		/// auctioned only for the part the pools are too shallow to buy.
+		#[transactional]
 		fn do_liquidate(
 			from_to: &T::AccountId,
 			order: Sell<T::MayBeAssetId, T::Balance>,
 			configuration: BoundedVec<T::LiquidationStrategyId, T::MaxLiquidationStrategiesAmount>,
-		) -> Result<T::OrderId, DispatchError> {
+		) -> Result<Option<T::OrderId>, DispatchError> {
 			let mut configuration = configuration;
 			if configuration.is_empty() {
 				configuration
 					.try_push(DefaultStrategyIndex::<T>::get())
 					.map_err(|()| Error::<T>::InvalidLiquidationStrategiesVector)?;
 			};
+			let mut remaining = order.take.amount;
 			for id in configuration {
 				let configuration = Strategies::<T>::get(id);
 				if let Some(configuration) = configuration {
-					let result = match configuration {
-						LiquidationStrategyConfiguration::DutchAuction(configuration) =>
-							T::DutchAuction::ask(from_to, order.clone(), configuration),
+					match configuration {
+						LiquidationStrategyConfiguration::DutchAuction(configuration) => {
+							let mut order = order.clone();
+							order.take.amount = remaining;
+							if let Ok(order_id) =
+								T::DutchAuction::ask(from_to, order, configuration)
+							{
+								Self::deposit_event(Event::<T>::PositionWasSentToLiquidation {});
+								return Ok(Some(order_id))
+							}
+						},
+						LiquidationStrategyConfiguration::Pablo { slippage } => {
+							// the pool may have moved since it was quoted, in which case the
+							// order is sent to the next strategies as if nothing was sold
+							let sale = with_transaction(|| {
+								match Self::sell_instantly(from_to, &order, remaining, slippage) {
+									Ok(sale) => TransactionOutcome::Commit(Ok(sale)),
+									Err(error) => TransactionOutcome::Rollback(Err(error)),
+								}
+							});
+							let (sold, received) = match sale {
+								Ok(sale) => sale,
+								Err(error) => {
+									log::warn!("instant sale failed: {:?}", error);
+									continue
+								},
+							};
+							if sold.is_zero() {
+								continue
+							}
+							remaining = remaining.saturating_sub(sold);
+							T::LiquidationCallback::on_fill(
+								from_to, None, order.pair, sold, received, remaining,
+							)?;
+							Self::deposit_event(Event::<T>::PositionWasSoldInstantly {
+								from_to: from_to.clone(),
+								strategy_id: id,
+								sold,
+								received,
+								remaining,
+							});
+							if remaining.is_zero() {
+								return Ok(None)
+							}
+						},
 						_ => return Err(Error::<T>::OnlyDutchAuctionStrategyIsImplemented.into()),
-					};
-					if let Ok(order_id) = result {
-						Self::deposit_event(Event::<T>::PositionWasSentToLiquidation {});
-						return Ok(order_id)
 					}
 				}
 			}
 
 			Err(Error::<T>::NoLiquidationEngineFound.into())
 		}
+
+		/// Sells the largest part of `amount` which the pool of the pair of `order` buys for at
+		/// least the limit of `order`, less `slippage`. Returns the amounts sold and received.
+		fn sell_instantly(
+			from_to: &T::AccountId,
+			order: &Sell<T::MayBeAssetId, T::Balance>,
+			amount: T::Balance,
+			slippage: Perquintill,
+		) -> Result<(T::Balance, T::Balance), DispatchError> {
+			let pair = order.pair;
+			if !T::Dex::pool_exists(pair) {
+				return Ok((Zero::zero(), Zero::zero()))
+			}
+			let min_received = |amount: T::Balance| -> Result<T::Balance, DispatchError> {
+				Ok(slippage.left_from_one().mul_floor(order.take.quote_amount(amount)?))
+			};
+			let fillable = |amount: T::Balance| -> bool {
+				let received =
+					T::Dex::spot_price(pair, AssetAmount::new(pair.base, amount), pair.quote, true);
+				matches!(
+					(received, min_received(amount)),
+					(Ok(received), Ok(min_received)) if received.value.amount >= min_received
+				)
+			};
+
+			let mut sold = Zero::zero();
+			if fillable(amount) {
+				sold = amount;
+			} else {
+				let mut unfillable = amount;
+				for _ in 0..T::InstantSaleSearchSteps::get() {
+					let step = unfillable.saturating_sub(sold) / 2_u32.into();
+					if step.is_zero() {
+						break
+					}
+					let middle = sold.saturating_add(step);
+					if fillable(middle) {
+						sold = middle;
+					} else {
+						unfillable = middle;
+					}
+				}
+			}
+			if sold.is_zero() {
+				return Ok((Zero::zero(), Zero::zero()))
+			}
+
+			let received = T::Dex::do_swap(
+				from_to,
+				pair,
+				AssetAmount::new(pair.base, sold),
+				AssetAmount::new(pair.quote, min_received(sold)?),
+				false,
+			)?;
+			Ok((sold, received.value.amount))
+		}
 	}
 }
@@ -0,0 +1,147 @@
+use super::{
+	currency::CurrencyId,
+	runtime::{AccountId, Assets, Balance},
+};
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount, SwapResult},
+};
+use frame_support::{
+	ensure, parameter_types,
+	traits::fungibles::{Inspect, Transfer},
+};
+use hex_literal::hex;
+use sp_core::sr25519::Public;
+use sp_runtime::{DispatchError, Permill};
+use sp_std::collections::btree_map::BTreeMap;
+
+/// Holds the reserves of the pools of [`MockDex`], one for each pair it holds both assets of.
+pub static DEX: Public =
+	Public(hex!("0000000000000000000000000000000000000000000000000000000000000003"));
+
+parameter_types! {
+	/// Makes swaps fail once the seller paid, as if the pool moved in between.
+	pub static SwapFails: bool = false;
+}
+
+/// Constant product pools without fees.
+pub struct MockDex;
+
+impl MockDex {
+	fn reserve(asset_id: CurrencyId) -> Balance {
+		<Assets as Inspect<AccountId>>::balance(asset_id, &DEX)
+	}
+
+	fn out_amount(in_asset: AssetAmount<CurrencyId, Balance>, out_asset_id: CurrencyId) -> Balance {
+		let (in_reserve, out_reserve) =
+			(Self::reserve(in_asset.asset_id), Self::reserve(out_asset_id));
+		out_reserve * in_asset.amount / (in_reserve + in_asset.amount)
+	}
+}
+
+impl Amm for MockDex {
+	type AssetId = CurrencyId;
+	type Balance = Balance;
+	type AccountId = AccountId;
+	type PoolId = CurrencyPair<CurrencyId>;
+
+	fn pool_exists(pool_id: Self::PoolId) -> bool {
+		Self::reserve(pool_id.base) > 0 && Self::reserve(pool_id.quote) > 0
+	}
+
+	fn pools(_limit: u32) -> Vec<Self::PoolId> {
+		todo!()
+	}
+
+	fn assets(_pool_id: Self::PoolId) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
+		todo!()
+	}
+
+	fn lp_token(_pool_id: Self::PoolId) -> Result<Self::AssetId, DispatchError> {
+		todo!()
+	}
+
+	fn redeemable_assets_for_lp_tokens(
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		todo!()
+	}
+
+	fn simulate_add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<Self::Balance, DispatchError> {
+		todo!()
+	}
+
+	fn simulate_remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_amounts: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		todo!()
+	}
+
+	fn spot_price(
+		_pool_id: Self::PoolId,
+		base_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		quote_asset_id: Self::AssetId,
+		_calculate_with_fees: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		let amount = Self::out_amount(base_asset, quote_asset_id);
+		Ok(SwapResult::new(quote_asset_id, amount, quote_asset_id, 0))
+	}
+
+	fn do_buy(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_in_asset_id: Self::AssetId,
+		_out_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		todo!()
+	}
+
+	fn add_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_assets: BTreeMap<Self::AssetId, Self::Balance>,
+		_min_mint_amount: Self::Balance,
+		_keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		todo!()
+	}
+
+	fn remove_liquidity(
+		_who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		_lp_amount: Self::Balance,
+		_min_receive: BTreeMap<Self::AssetId, Self::Balance>,
+	) -> Result<BTreeMap<Self::AssetId, Self::Balance>, DispatchError> {
+		todo!()
+	}
+
+	fn do_swap(
+		who: &Self::AccountId,
+		_pool_id: Self::PoolId,
+		in_asset: AssetAmount<Self::AssetId, Self::Balance>,
+		min_receive: AssetAmount<Self::AssetId, Self::Balance>,
+		_keep_alive: bool,
+	) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
+		let amount = Self::out_amount(in_asset, min_receive.asset_id);
+		ensure!(amount >= min_receive.amount, DispatchError::Other("slippage"));
+		<Assets as Transfer<AccountId>>::transfer(
+			in_asset.asset_id,
+			who,
+			&DEX,
+			in_asset.amount,
+			false,
+		)?;
+		ensure!(!SwapFails::get(), DispatchError::Other("pool moved"));
+		<Assets as Transfer<AccountId>>::transfer(min_receive.asset_id, &DEX, who, amount, false)?;
+		Ok(SwapResult::new(min_receive.asset_id, amount, min_receive.asset_id, 0))
+	}
+}
@@ -27,7 +27,7 @@ use sp_runtime::{
 };
 use xcm::latest::SendXcm;
 
-use super::governance_registry::GovernanceRegistry;
+use super::{dex::MockDex, governance_registry::GovernanceRegistry};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
 pub type Block = frame_system::mocking::MockBlock<Runtime>;
@@ -233,6 +233,7 @@ impl pallet_dutch_auction::Config for Runtime {
 	type PositionExistentialDeposit = NativeExistentialDeposit;
 	type AdminOrigin = EnsureRoot<Self::AccountId>;
 	type XcmSender = XcmFake;
+	type LiquidationCallback = ();
 	type XcmOrigin = XcmFake;
 }
 
@@ -245,6 +246,8 @@ impl pallet_liquidations::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type UnixTime = Timestamp;
 	type OrderId = OrderId;
+	type Dex = MockDex;
+	type LiquidationCallback = ();
 	type WeightInfo = SubstrateWeight<Self>;
 	type DutchAuction = DutchAuction;
 	type LiquidationStrategyId = LiquidationStrategyId;
@@ -252,6 +255,7 @@ impl pallet_liquidations::Config for Runtime {
 	type CanModifyStrategies = EnsureRoot<Self::AccountId>;
 	type XcmSender = XcmFake;
 	type MaxLiquidationStrategiesAmount = ConstU32<3>;
+	type InstantSaleSearchSteps = ConstU32<16>;
 }
 
 #[allow(dead_code)] // not really dead
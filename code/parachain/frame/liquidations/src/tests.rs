@@ -1,6 +1,11 @@
 use crate::{
 	self as pallet_liquidations,
-	mock::{currency::*, runtime::*},
+	mock::{
+		currency::*,
+		dex::{SwapFails, DEX},
+		runtime::*,
+	},
+	LiquidationStrategyConfiguration,
 };
 use codec::Encode;
 use composable_traits::{
@@ -8,10 +13,13 @@ use composable_traits::{
 	liquidation::Liquidation,
 };
 use frame_support::{
-	assert_noop,
-	traits::{fungible::Mutate as NativeMutate, fungibles::Mutate},
+	assert_noop, assert_ok,
+	traits::{
+		fungible::Mutate as NativeMutate,
+		fungibles::{Inspect, Mutate},
+	},
 };
-use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_runtime::{FixedPointNumber, FixedU128, Perquintill};
 
 // ensure that we take extra for sell, at least amount to remove
 #[test]
@@ -29,7 +37,8 @@ fn successful_liquidate() {
 			Sell::new(KUSD, PICA, 100, Ratio::saturating_from_integer(1)),
 			vec![],
 		)
-		.expect("can creator order for existing currencies if enough of amounts");
+		.expect("can creator order for existing currencies if enough of amounts")
+		.expect("order was not sold instantly");
 		let order =
 			pallet_dutch_auction::SellOrders::<Runtime>::get(order).expect("order was placed");
 		assert_eq!(order.from_to, who);
@@ -51,6 +60,118 @@ fn do_not_have_amount_to_liquidate() {
 	});
 }
 
+fn add_pablo_strategy(slippage: Perquintill) -> u32 {
+	assert_ok!(Liquidations::add_liquidation_strategy(
+		RuntimeOrigin::root(),
+		LiquidationStrategyConfiguration::Pablo { slippage },
+	));
+	Liquidations::strategy_index()
+}
+
+fn fund_pool(kusd: Balance, pica: Balance) {
+	Tokens::mint_into(KUSD, &DEX, kusd).unwrap();
+	<Balances as NativeMutate<_>>::mint_into(&DEX, pica).unwrap();
+}
+
+fn fund_seller(kusd: Balance) {
+	<Balances as NativeMutate<_>>::mint_into(&ALICE, NativeExistentialDeposit::get() * 3).unwrap();
+	Tokens::mint_into(KUSD, &ALICE, kusd).unwrap();
+}
+
+#[test]
+fn deep_pool_sells_instantly() {
+	new_test_externalities().execute_with(|| {
+		fund_seller(1_000);
+		fund_pool(1_000_000_000, 1_000_000_000);
+		let pablo = add_pablo_strategy(Perquintill::from_percent(1));
+
+		let order_id = <Liquidations as Liquidation>::liquidate(
+			&ALICE,
+			Sell::new(KUSD, PICA, 1_000, Ratio::saturating_from_integer(1)),
+			vec![pablo, Liquidations::default_strategy_index()],
+		)
+		.expect("pool is deep enough");
+
+		assert_eq!(order_id, None);
+		assert_eq!(<Assets as Inspect<_>>::balance(KUSD, &ALICE), 0);
+		System::assert_has_event(RuntimeEvent::Liquidations(
+			pallet_liquidations::Event::PositionWasSoldInstantly {
+				from_to: ALICE,
+				strategy_id: pablo,
+				sold: 1_000,
+				received: 999,
+				remaining: 0,
+			},
+		));
+	});
+}
+
+#[test]
+fn thin_pool_falls_back_to_auction() {
+	new_test_externalities().execute_with(|| {
+		fund_seller(1_000);
+		fund_pool(1_000, 1_000);
+		let pablo = add_pablo_strategy(Perquintill::from_percent(10));
+
+		let order_id = <Liquidations as Liquidation>::liquidate(
+			&ALICE,
+			Sell::new(KUSD, PICA, 1_000, Ratio::saturating_from_integer(1)),
+			vec![pablo, Liquidations::default_strategy_index()],
+		)
+		.expect("rest of the order is auctioned")
+		.expect("pool is too thin to buy the whole order");
+
+		// the pool buys about a ninth of the order within 10% of the limit price
+		let sold = <Assets as Inspect<_>>::balance(KUSD, &DEX) - 1_000;
+		assert!(sold > 100 && sold < 120);
+		let order =
+			pallet_dutch_auction::SellOrders::<Runtime>::get(order_id).expect("order was placed");
+		assert_eq!(order.order.take.amount, 1_000 - sold);
+	});
+}
+
+#[test]
+fn failed_instant_sale_falls_back_to_auction() {
+	new_test_externalities().execute_with(|| {
+		fund_seller(1_000);
+		fund_pool(1_000_000_000, 1_000_000_000);
+		let pablo = add_pablo_strategy(Perquintill::from_percent(1));
+		SwapFails::set(true);
+
+		let order_id = <Liquidations as Liquidation>::liquidate(
+			&ALICE,
+			Sell::new(KUSD, PICA, 1_000, Ratio::saturating_from_integer(1)),
+			vec![pablo, Liquidations::default_strategy_index()],
+		)
+		.expect("order is auctioned")
+		.expect("pool did not buy");
+
+		// the failed swap was rolled back
+		assert_eq!(<Assets as Inspect<_>>::balance(KUSD, &DEX), 1_000_000_000);
+		let order =
+			pallet_dutch_auction::SellOrders::<Runtime>::get(order_id).expect("order was placed");
+		assert_eq!(order.order.take.amount, 1_000);
+	});
+}
+
+#[test]
+fn thin_pool_without_fallback_does_not_sell() {
+	new_test_externalities().execute_with(|| {
+		fund_seller(1_000);
+		fund_pool(1_000, 1_000);
+		let pablo = add_pablo_strategy(Perquintill::from_percent(10));
+
+		assert_noop!(
+			<Liquidations as Liquidation>::liquidate(
+				&ALICE,
+				Sell::new(KUSD, PICA, 1_000, Ratio::saturating_from_integer(1)),
+				vec![pablo],
+			),
+			pallet_liquidations::Error::<Runtime>::NoLiquidationEngineFound,
+		);
+	});
+}
+
 /// This is used if we will hard code TX for each network.
 #[derive(Encode)]
 pub enum LiquidationsCall {
@@ -9,6 +9,7 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn add_liquidation_strategy() -> Weight;
 	fn sell(vector_length: u32) -> Weight;
+	fn sell_instantly(search_steps: u32) -> Weight;
 }
 
 /// Weight functions for `liquidations`.
@@ -35,4 +36,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(x as u64)))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	fn sell_instantly(s: u32) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(20_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
@@ -6,8 +6,11 @@ use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whiteli
 use frame_support::{
 	assert_ok,
 	traits::{fungibles::Mutate, Get},
+	weights::Weight,
 };
 use frame_system::RawOrigin;
+use sp_runtime::Perquintill;
+use sp_std::prelude::*;
 const MIN_TRANSFER_SIZE: u128 = 1_000_000_000_000;
 const MAX_TRANSFER_SIZE: u128 = 100_000_000_000_000_000;
 const BUDGET_AMOUNT: u128 = 100_000_000_000_000_000_000;
@@ -273,6 +276,106 @@ benchmarks! {
 		assert_ok!(Mosaic::<T>::add_remote_amm_id(RawOrigin::Root.into(), network_id.clone(), amm_id.clone()));
 
 	}: _(RawOrigin::Root, network_id.clone(), amm_id.clone())
+
+	bond_relayer {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), &relayer, T::RelayerBond::get() * 2u32.into()));
+	}: _(RawOrigin::Signed(relayer))
+
+	add_relayer {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), &relayer, T::RelayerBond::get() * 2u32.into()));
+		assert_ok!(Mosaic::<T>::bond_relayer(RawOrigin::Signed(relayer.clone()).into()));
+	}: _(RawOrigin::Root, relayer)
+
+	remove_relayer {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), &relayer, T::RelayerBond::get() * 2u32.into()));
+		assert_ok!(Mosaic::<T>::bond_relayer(RawOrigin::Signed(relayer.clone()).into()));
+		assert_ok!(Mosaic::<T>::add_relayer(RawOrigin::Root.into(), relayer.clone()));
+	}: _(RawOrigin::Root, relayer, false)
+
+	set_attestation_threshold {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), &relayer, T::RelayerBond::get() * 2u32.into()));
+		assert_ok!(Mosaic::<T>::bond_relayer(RawOrigin::Signed(relayer.clone()).into()));
+		assert_ok!(Mosaic::<T>::add_relayer(RawOrigin::Root.into(), relayer));
+	}: _(RawOrigin::Root, 1)
+
+	attest_mint {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(Mosaic::<T>::set_relayer(RawOrigin::Root.into(), relayer.clone()));
+
+		let network_id: T::NetworkId = 1.into();
+		let network_info = NetworkInfo {
+			enabled: true,
+			min_transfer_size: MIN_TRANSFER_SIZE.into(),
+			max_transfer_size: MAX_TRANSFER_SIZE.into(),
+		};
+		assert_ok!(Mosaic::<T>::set_network(RawOrigin::Signed(relayer).into(), network_id.clone(), network_info));
+
+		let asset_id: AssetIdOf<T> = 1.into();
+		let remote_asset_id: RemoteAssetIdOf<T> = [0xFFu8; 20].into();
+		assert_ok!(Mosaic::<T>::update_asset_mapping(RawOrigin::Root.into(), asset_id, network_id.clone(), Some(remote_asset_id.clone())));
+
+		let budget_amount: BalanceOf<T> = BUDGET_AMOUNT.into();
+		let decayer: BudgetPenaltyDecayer<BalanceOf<T>, T::BlockNumber> =
+			BudgetPenaltyDecayer::linear(5.into());
+		assert_ok!(Mosaic::<T>::set_budget(RawOrigin::Root.into(), asset_id, budget_amount, decayer.into()));
+
+		// every relayer attests, the last one minting the transfer
+		let relayers: Vec<T::AccountId> =
+			(0..T::MaxRelayers::get()).map(|i| account("relayer", i, 0)).collect();
+		for relayer in &relayers {
+			assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), relayer, T::RelayerBond::get() * 2u32.into()));
+			assert_ok!(Mosaic::<T>::bond_relayer(RawOrigin::Signed(relayer.clone()).into()));
+			assert_ok!(Mosaic::<T>::add_relayer(RawOrigin::Root.into(), relayer.clone()));
+		}
+		assert_ok!(Mosaic::<T>::set_attestation_threshold(RawOrigin::Root.into(), T::MaxRelayers::get()));
+
+		let alice: T::AccountId = account("alice", 0, 0);
+		let transfer_amount: BalanceOf<T> = TRANSFER_AMOUNT.into();
+		let tx_id = Id::repeat_byte(1);
+		let (last, others) = relayers.split_last().unwrap();
+		for relayer in others {
+			assert_ok!(Mosaic::<T>::attest_mint(RawOrigin::Signed(relayer.clone()).into(), network_id.clone(), remote_asset_id.clone(), alice.clone(), transfer_amount, tx_id));
+		}
+	}: _(RawOrigin::Signed(last.clone()), network_id, remote_asset_id, alice, transfer_amount, tx_id)
+
+	set_rate_limit {
+		let network_id: T::NetworkId = 1.into();
+		let asset_id: AssetIdOf<T> = 1.into();
+		let rate_limit = RateLimit {
+			window: 100.into(),
+			max_outflow: MAX_TRANSFER_SIZE.into(),
+			max_inflow: MAX_TRANSFER_SIZE.into(),
+		};
+	}: _(RawOrigin::Root, network_id, asset_id, Some(rate_limit))
+
+	set_circuit_breaker {
+		let asset_id: AssetIdOf<T> = 1.into();
+		let circuit_breaker = CircuitBreaker { window: 100.into(), max_change: Perquintill::from_percent(10) };
+	}: _(RawOrigin::Root, asset_id, Some(circuit_breaker))
+
+	unbond_relayer {
+		let relayer: T::AccountId = whitelisted_caller();
+		assert_ok!(T::Assets::mint_into(T::BondAssetId::get(), &relayer, T::RelayerBond::get() * 2u32.into()));
+		assert_ok!(Mosaic::<T>::bond_relayer(RawOrigin::Signed(relayer.clone()).into()));
+	}: _(RawOrigin::Signed(relayer))
+
+	expire_attestation {
+		let id = Id::repeat_byte(1);
+		let attestations = (0..T::MaxRelayers::get())
+			.map(|i| (account("relayer", i, 0), Default::default()))
+			.collect::<Vec<_>>();
+		Attestations::<T>::insert(id, (T::BlockNumber::from(0u32), attestations.try_into().unwrap()));
+		frame_system::Pallet::<T>::set_block_number(T::AttestationTTL::get());
+	}: {
+		Mosaic::<T>::expire_attestations(Weight::MAX)
+	}
+	verify {
+		assert!(!Attestations::<T>::contains_key(id));
+	}
 }
 
 impl_benchmark_test_suite!(Mosaic, crate::mock::new_test_ext(), crate::mock::Test,);
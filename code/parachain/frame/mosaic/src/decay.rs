@@ -14,6 +14,9 @@ pub trait Decayer<Balance, BlockNumber> {
 	/// Determine how many blocks are required to pass until the `amount` fully recover from this
 	/// decayer. Returns `None` if the recovery period cannot be computed.
 	fn full_recovery_period(&self, amount: Balance) -> Option<BlockNumber>;
+
+	/// Whether amounts can be decayed at all, `checked_decay` returning `None` otherwise.
+	fn is_valid(&self) -> bool;
 }
 
 /// Recommend type for storing the decay function of a penalty.
@@ -21,6 +24,8 @@ pub trait Decayer<Balance, BlockNumber> {
 pub enum BudgetPenaltyDecayer<Balance, BlockNumber> {
 	/// Linear variant of the decay function, which decreases every block.
 	Linear(LinearDecay<Balance, BlockNumber>),
+	/// Exponential variant of the decay function, which halves every `half_life` blocks.
+	Exponential(ExponentialDecay<Balance, BlockNumber>),
 }
 
 impl<Balance, BlockNumber> BudgetPenaltyDecayer<Balance, BlockNumber> {
@@ -28,13 +33,19 @@ impl<Balance, BlockNumber> BudgetPenaltyDecayer<Balance, BlockNumber> {
 	pub fn linear(n: Balance) -> BudgetPenaltyDecayer<Balance, BlockNumber> {
 		BudgetPenaltyDecayer::Linear(LinearDecay { factor: n, _marker: PhantomData })
 	}
+
+	#[allow(dead_code)]
+	pub fn exponential(half_life: BlockNumber) -> BudgetPenaltyDecayer<Balance, BlockNumber> {
+		BudgetPenaltyDecayer::Exponential(ExponentialDecay { half_life, _marker: PhantomData })
+	}
 }
 
 impl<Balance, BlockNumber> Decayer<Balance, BlockNumber>
 	for BudgetPenaltyDecayer<Balance, BlockNumber>
 where
-	BlockNumber: CheckedSub + Saturating + Into<Balance> + TryFrom<Balance> + One + CheckedAdd,
-	Balance: CheckedMul + CheckedDiv + Saturating + Zero,
+	BlockNumber:
+		CheckedSub + Saturating + Into<Balance> + TryFrom<Balance> + One + CheckedAdd + Clone,
+	Balance: CheckedMul + CheckedDiv + Saturating + Zero + One + Clone,
 {
 	fn checked_decay(
 		&self,
@@ -44,12 +55,21 @@ where
 	) -> Option<Balance> {
 		match self {
 			BudgetPenaltyDecayer::Linear(lin) => lin.checked_decay(amount, current, last),
+			BudgetPenaltyDecayer::Exponential(exp) => exp.checked_decay(amount, current, last),
 		}
 	}
 
 	fn full_recovery_period(&self, amount: Balance) -> Option<BlockNumber> {
 		match self {
 			BudgetPenaltyDecayer::Linear(lin) => lin.full_recovery_period(amount),
+			BudgetPenaltyDecayer::Exponential(exp) => exp.full_recovery_period(amount),
+		}
+	}
+
+	fn is_valid(&self) -> bool {
+		match self {
+			BudgetPenaltyDecayer::Linear(lin) => lin.is_valid(),
+			BudgetPenaltyDecayer::Exponential(exp) => exp.is_valid(),
 		}
 	}
 }
@@ -83,6 +103,69 @@ where
 		let block_full_period_plus_one: BlockNumber = block_full_period.checked_add(&One::one())?;
 		Some(block_full_period_plus_one)
 	}
+
+	fn is_valid(&self) -> bool {
+		true
+	}
+}
+
+#[derive(Decode, Encode, MaxEncodedLen, TypeInfo, Default, Debug, PartialEq, Eq, Clone)]
+pub struct ExponentialDecay<Balance, BlockNumber> {
+	/// Number of blocks in which the amount halves. In between halvings, the amount decays
+	/// linearly towards the next halving.
+	half_life: BlockNumber,
+	_marker: core::marker::PhantomData<Balance>,
+}
+
+impl<Balance, BlockNumber> Decayer<Balance, BlockNumber> for ExponentialDecay<Balance, BlockNumber>
+where
+	BlockNumber: CheckedSub + Saturating + Into<Balance> + TryFrom<Balance> + Clone,
+	Balance: CheckedMul + CheckedDiv + Saturating + Zero + One + Clone,
+{
+	fn checked_decay(
+		&self,
+		amount: Balance,
+		current: BlockNumber,
+		last: BlockNumber,
+	) -> Option<Balance> {
+		let half_life: Balance = self.half_life.clone().into();
+		if half_life.is_zero() {
+			return None
+		}
+		let two = Balance::one().saturating_add(Balance::one());
+		let diff: Balance = current.saturating_sub(last).into();
+		let mut halvings = diff.checked_div(&half_life)?;
+		let remainder = diff.saturating_sub(halvings.checked_mul(&half_life)?);
+
+		// Once the amount reached zero, further halvings are no-ops.
+		let mut amount = amount;
+		while !halvings.is_zero() && !amount.is_zero() {
+			amount = amount.checked_div(&two)?;
+			halvings = halvings.saturating_sub(One::one());
+		}
+
+		let reduction =
+			amount.checked_div(&two)?.checked_mul(&remainder)?.checked_div(&half_life)?;
+		Some(amount.saturating_sub(reduction))
+	}
+
+	fn full_recovery_period(&self, amount: Balance) -> Option<BlockNumber> {
+		let half_life: Balance = self.half_life.clone().into();
+		let two = Balance::one().saturating_add(Balance::one());
+		let mut halvings = Balance::zero();
+		let mut amount = amount;
+		while !amount.is_zero() {
+			amount = amount.checked_div(&two)?;
+			halvings = halvings.saturating_add(One::one());
+		}
+		let full_period = halvings.checked_mul(&half_life)?;
+		TryFrom::<Balance>::try_from(full_period).ok()
+	}
+
+	fn is_valid(&self) -> bool {
+		let half_life: Balance = self.half_life.clone().into();
+		!half_life.is_zero()
+	}
 }
 
 #[cfg(test)]
@@ -100,4 +183,27 @@ mod tests {
 			assert!(prev > penalty);
 		});
 	}
+
+	#[test]
+	fn test_exponential_decrease() {
+		let penalty_decayer = BudgetPenaltyDecayer::<u128, u64>::exponential(10);
+
+		assert_eq!(penalty_decayer.checked_decay(1000, 5, 0), Some(750));
+		assert_eq!(penalty_decayer.checked_decay(1000, 10, 0), Some(500));
+		assert_eq!(penalty_decayer.checked_decay(1000, 15, 0), Some(375));
+		assert_eq!(penalty_decayer.checked_decay(1000, 20, 0), Some(250));
+		assert_eq!(penalty_decayer.full_recovery_period(1000), Some(100));
+		assert_eq!(penalty_decayer.checked_decay(1000, 100, 0), Some(0));
+		assert_eq!(penalty_decayer.checked_decay(1000, 99, 0), Some(1));
+	}
+
+	#[test]
+	fn test_zero_half_life_is_invalid() {
+		let penalty_decayer = BudgetPenaltyDecayer::<u128, u64>::exponential(0);
+
+		assert!(!penalty_decayer.is_valid());
+		assert_eq!(penalty_decayer.checked_decay(1000, 5, 0), None);
+		assert!(BudgetPenaltyDecayer::<u128, u64>::exponential(1).is_valid());
+		assert!(BudgetPenaltyDecayer::<u128, u64>::linear(0).is_valid());
+	}
 }
@@ -48,10 +48,13 @@ pub mod pallet {
 	use scale_info::TypeInfo;
 	use sp_core::H256;
 	use sp_runtime::{
-		traits::{AccountIdConversion, Keccak256, Saturating},
-		DispatchError,
+		traits::{
+			AccountIdConversion, AtLeast32BitUnsigned, Hash, Keccak256, SaturatedConversion,
+			Saturating,
+		},
+		DispatchError, Perquintill,
 	};
-	use sp_std::{fmt::Debug, str};
+	use sp_std::{fmt::Debug, str, vec::Vec};
 
 	pub(crate) type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 	pub(crate) type BalanceOf<T> = <<T as Config>::Assets as Inspect<AccountIdOf<T>>>::Balance;
@@ -110,6 +113,24 @@ pub mod pallet {
 		/// it is also used as the origin capable of stopping attackers.
 		type ControlOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
+		/// The amount an account bonds to become an attesting relayer.
+		#[pallet::constant]
+		type RelayerBond: Get<BalanceOf<Self>>;
+
+		/// The asset in which relayers bond.
+		#[pallet::constant]
+		type BondAssetId: Get<AssetIdOf<Self>>;
+
+		/// The maximum number of attesting relayers.
+		#[pallet::constant]
+		type MaxRelayers: Get<u32>;
+
+		/// The number of blocks an incoming transfer may collect attestations, counted from the
+		/// first one. Attestations to transfers which did not reach the threshold in time are
+		/// dropped.
+		#[pallet::constant]
+		type AttestationTTL: Get<BlockNumberOf<Self>>;
+
 		/// Weight implementation used for extrinsics.
 		type WeightInfo: WeightInfo;
 	}
@@ -126,6 +147,7 @@ pub mod pallet {
 	pub enum TransactionType {
 		Incoming,
 		Outgoing,
+		Bond,
 	}
 
 	#[derive(Clone, Encode, Decode, Debug, MaxEncodedLen, TypeInfo, PartialEq, Eq)]
@@ -158,6 +180,87 @@ pub mod pallet {
 		pub max_transfer_size: Balance,
 	}
 
+	/// Caps on the amounts transferred of an asset over a network within a sliding window of
+	/// `window` blocks.
+	#[derive(Clone, Debug, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq)]
+	pub struct RateLimit<Balance, BlockNumber> {
+		pub window: BlockNumber,
+		pub max_outflow: Balance,
+		pub max_inflow: Balance,
+	}
+
+	/// Trips once the net amount of an asset bridged in or out within a sliding window of
+	/// `window` blocks exceeds `max_change` of its total issuance, disabling the network.
+	#[derive(Clone, Debug, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq)]
+	pub struct CircuitBreaker<BlockNumber> {
+		pub window: BlockNumber,
+		pub max_change: Perquintill,
+	}
+
+	/// The amounts transferred within the current and the previous window. The amount
+	/// transferred within the sliding window is approximated by weighing the previous window by
+	/// its overlap with the sliding window.
+	#[derive(Clone, Debug, Default, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq)]
+	pub struct SlidingWindow<Balance, BlockNumber> {
+		pub started_at: BlockNumber,
+		pub current: Balance,
+		pub previous: Balance,
+	}
+
+	impl<Balance, BlockNumber> SlidingWindow<Balance, BlockNumber>
+	where
+		Balance: AtLeast32BitUnsigned + Copy,
+		BlockNumber: AtLeast32BitUnsigned + Copy,
+	{
+		/// Moves the current window up to `now`, forgetting amounts older than one window.
+		pub fn roll(&mut self, now: BlockNumber, window: BlockNumber) {
+			if window.is_zero() {
+				return
+			}
+			let elapsed = now.saturating_sub(self.started_at);
+			if elapsed >= window.saturating_add(window) {
+				self.previous = Zero::zero();
+				self.current = Zero::zero();
+				self.started_at = now.saturating_sub(elapsed % window);
+			} else if elapsed >= window {
+				self.previous = self.current;
+				self.current = Zero::zero();
+				self.started_at = self.started_at.saturating_add(window);
+			}
+		}
+
+		/// The amount transferred within the last `window` blocks, assuming the window has been
+		/// rolled up to `now`.
+		pub fn total(&self, now: BlockNumber, window: BlockNumber) -> Balance {
+			let window: u128 = window.saturated_into();
+			let elapsed: u128 = now.saturating_sub(self.started_at).saturated_into();
+			let overlap = Perquintill::from_rational(window.saturating_sub(elapsed), window);
+			let previous: u128 = overlap.mul_ceil(self.previous.saturated_into::<u128>());
+			self.current.saturating_add(previous.saturated_into())
+		}
+
+		/// Takes back `amount` transferred earlier, from the current window first.
+		pub fn rescind(&mut self, amount: Balance) {
+			let from_current = amount.min(self.current);
+			self.current -= from_current;
+			self.previous = self.previous.saturating_sub(amount - from_current);
+		}
+	}
+
+	/// The inflow and outflow of an asset.
+	#[derive(Clone, Debug, Default, Encode, Decode, MaxEncodedLen, TypeInfo, PartialEq, Eq)]
+	pub struct Flows<Balance, BlockNumber> {
+		pub inflow: SlidingWindow<Balance, BlockNumber>,
+		pub outflow: SlidingWindow<Balance, BlockNumber>,
+	}
+
+	/// Direction of a transfer, seen from this chain.
+	#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+	pub enum FlowDirection {
+		Inflow,
+		Outflow,
+	}
+
 	/// User incoming/outgoing accounts, that hold the funds for transactions to happen.
 	pub struct SubAccount<T: Config> {
 		transaction_type: TransactionType,
@@ -169,6 +272,7 @@ pub mod pallet {
 			let prefix = match self.transaction_type {
 				TransactionType::Incoming => b"incoming________",
 				TransactionType::Outgoing => b"outgoing________",
+				TransactionType::Bond => b"bond____________",
 			};
 			[prefix.to_vec(), self.account_id.encode()]
 		}
@@ -178,6 +282,9 @@ pub mod pallet {
 		pub fn new_incoming(account_id: AccountIdOf<T>) -> Self {
 			SubAccount { transaction_type: TransactionType::Incoming, account_id }
 		}
+		pub fn new_bond(account_id: AccountIdOf<T>) -> Self {
+			SubAccount { transaction_type: TransactionType::Bond, account_id }
+		}
 	}
 
 	#[pallet::storage]
@@ -185,6 +292,50 @@ pub mod pallet {
 	pub type Relayer<T: Config> =
 		StorageValue<_, StaleRelayer<T::AccountId, T::BlockNumber>, OptionQuery>;
 
+	/// Bonds of the accounts which may be added to the attesting relayers.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_bonds)]
+	pub type RelayerBonds<T: Config> =
+		StorageMap<_, Blake2_128Concat, AccountIdOf<T>, BalanceOf<T>, OptionQuery>;
+
+	/// The attesting relayers, added by governance.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_set)]
+	#[allow(clippy::disallowed_types)]
+	pub type RelayerSet<T: Config> =
+		StorageValue<_, BoundedVec<AccountIdOf<T>, T::MaxRelayers>, ValueQuery>;
+
+	/// The number of distinct relayers which must attest to an incoming transfer before it is
+	/// minted. Zero disables attestation, leaving minting to the single `Relayer`.
+	#[pallet::storage]
+	#[pallet::getter(fn attestation_threshold)]
+	#[allow(clippy::disallowed_types)]
+	pub type AttestationThreshold<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Attestations to incoming transfers which are not yet minted, as the block of the first
+	/// attestation, and the attesting relayers with the digest of the attested
+	/// `(network_id, remote_asset_id, to, amount)`.
+	#[pallet::storage]
+	#[pallet::getter(fn attestations)]
+	#[allow(clippy::disallowed_types)]
+	pub type Attestations<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Id,
+		(BlockNumberOf<T>, BoundedVec<(AccountIdOf<T>, H256), T::MaxRelayers>),
+		ValueQuery,
+	>;
+
+	/// Last transfer checked for expired attestations, the next check starts after it.
+	#[pallet::storage]
+	pub type AttestationsCursor<T: Config> = StorageValue<_, Id, OptionQuery>;
+
+	/// Incoming transfers minted through attestation. They are never forgotten, so that a
+	/// transfer cannot be minted twice.
+	#[pallet::storage]
+	#[pallet::getter(fn minted_transfers)]
+	pub type MintedTransfers<T: Config> = StorageMap<_, Blake2_128Concat, Id, (), OptionQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn asset_infos)]
 	pub type AssetsInfo<T: Config> = StorageMap<
@@ -200,6 +351,70 @@ pub mod pallet {
 	pub type NetworkInfos<T: Config> =
 		StorageMap<_, Blake2_128Concat, NetworkIdOf<T>, NetworkInfo<BalanceOf<T>>, OptionQuery>;
 
+	/// Sliding window caps on the amounts transferred per network and asset.
+	#[pallet::storage]
+	#[pallet::getter(fn rate_limits)]
+	pub type RateLimits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		NetworkIdOf<T>,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		RateLimit<BalanceOf<T>, BlockNumberOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Amounts transferred per network and asset, tracked for networks and assets with a
+	/// `RateLimit`.
+	#[pallet::storage]
+	#[pallet::getter(fn network_flows)]
+	pub type NetworkFlows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		NetworkIdOf<T>,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		Flows<BalanceOf<T>, BlockNumberOf<T>>,
+		ValueQuery,
+	>;
+
+	/// Outflow recorded per network for the outgoing transactions of an account and asset, which
+	/// the Relayer has not yet accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn outgoing_flows)]
+	pub type OutgoingFlows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(AccountIdOf<T>, AssetIdOf<T>),
+		Blake2_128Concat,
+		NetworkIdOf<T>,
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// Circuit breakers on the total issuance of assets changed through the bridge.
+	#[pallet::storage]
+	#[pallet::getter(fn circuit_breakers)]
+	pub type CircuitBreakers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		CircuitBreaker<BlockNumberOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Amounts transferred per asset over all networks, tracked for assets with a
+	/// `CircuitBreaker`.
+	#[pallet::storage]
+	#[pallet::getter(fn asset_flows)]
+	pub type AssetFlows<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		AssetIdOf<T>,
+		Flows<BalanceOf<T>, BlockNumberOf<T>>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn time_lock_period)]
 	#[allow(clippy::disallowed_types)]
@@ -288,6 +503,21 @@ pub mod pallet {
 		RelayerSet { relayer: AccountIdOf<T> },
 		/// The relayer has been rotated to `account_id`.
 		RelayerRotated { ttl: BlockNumberOf<T>, account_id: AccountIdOf<T> },
+		/// An account bonded `amount` to become an attesting relayer.
+		RelayerBonded { relayer: AccountIdOf<T>, amount: BalanceOf<T> },
+		/// A bonded account was added to the attesting relayers.
+		RelayerAdded { relayer: AccountIdOf<T> },
+		/// A relayer was removed, and its bond returned or burned if `slashed`.
+		RelayerRemoved { relayer: AccountIdOf<T>, slashed: bool },
+		/// An account which is not an attesting relayer withdrew its bond.
+		RelayerUnbonded { relayer: AccountIdOf<T>, amount: BalanceOf<T> },
+		/// The number of attestations needed to mint an incoming transfer was set.
+		AttestationThresholdSet { threshold: u32 },
+		/// A relayer attested to the incoming transfer `id`, which now has `attestations`
+		/// matching attestations.
+		MintAttested { id: Id, relayer: AccountIdOf<T>, attestations: u32 },
+		/// The attestations to the incoming transfer `id` expired before reaching the threshold.
+		AttestationsExpired { id: Id },
 		BudgetUpdated {
 			asset_id: AssetIdOf<T>,
 			amount: BalanceOf<T>,
@@ -295,6 +525,24 @@ pub mod pallet {
 		},
 		/// The `NetworkInfos` `network_info` was updated for `network_id`.
 		NetworksUpdated { network_id: NetworkIdOf<T>, network_info: NetworkInfo<BalanceOf<T>> },
+		/// The rate limit of `asset_id` over `network_id` was set, or removed if `None`.
+		RateLimitUpdated {
+			network_id: NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			rate_limit: Option<RateLimit<BalanceOf<T>, BlockNumberOf<T>>>,
+		},
+		/// The circuit breaker of `asset_id` was set, or removed if `None`.
+		CircuitBreakerUpdated {
+			asset_id: AssetIdOf<T>,
+			circuit_breaker: Option<CircuitBreaker<BlockNumberOf<T>>>,
+		},
+		/// The net amount of `asset_id` bridged within the window reached `change`, exceeding
+		/// the circuit breaker. `network_id` has been disabled.
+		CircuitBreakerTripped {
+			network_id: NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			change: BalanceOf<T>,
+		},
 		/// An outgoing tx is created, and locked in the outgoing tx pool.
 		TransferOut {
 			id: Id,
@@ -396,6 +644,27 @@ pub mod pallet {
 		RemoteAmmIdNotFound,
 		RemoteAmmIdAlreadyExists,
 		DestinationAmmIdNotWhitelisted,
+		RelayerAlreadyBonded,
+		RelayerNotBonded,
+		RelayerAlreadyAdded,
+		TooManyRelayers,
+		NotARelayer,
+		InvalidThreshold,
+		AttestationNotEnabled,
+		AttestationRequired,
+		AlreadyAttested,
+		AlreadyMinted,
+		InvalidWindow,
+		OutflowLimitExceeded,
+		InflowLimitExceeded,
+		InvalidDecay,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::expire_attestations(remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -482,6 +751,7 @@ pub mod pallet {
 			// Can also be token governance associated I reckon, as Angular holders should be able
 			// to grant mosaic permission to mint. We'll save that for phase 3.
 			T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(decay.is_valid(), Error::<T>::InvalidDecay);
 
 			<Pallet<T> as RelayerInterface>::set_budget(asset_id, amount, decay.clone());
 
@@ -496,6 +766,7 @@ pub mod pallet {
 		/// - Network must be supported.
 		/// - AssetId must be supported.
 		/// - Amount must be lower than the networks `max_transfer_size`.
+		/// - Amount must not exceed the outflow `RateLimit` of the asset over the network.
 		/// - Origin must have sufficient funds.
 		/// - Transfers near Balance::max may result in overflows, which are caught and returned as
 		///   an error.
@@ -535,6 +806,10 @@ pub mod pallet {
 				keep_alive,
 				now,
 			)?;
+			Self::record_flow(&network_id, asset_id, amount, FlowDirection::Outflow, now)?;
+			OutgoingFlows::<T>::mutate((caller.clone(), asset_id), network_id.clone(), |flow| {
+				*flow = Some(flow.unwrap_or_else(Zero::zero).saturating_add(amount))
+			});
 
 			// Ensure that users can only swap using a whitelisted destination amm id
 			if let Some(swap_info) = &amm_swap_info {
@@ -591,6 +866,14 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			Self::ensure_relayer(origin)?;
 			let asset_id = Self::get_local_mapping(remote_asset_id.clone(), network_id.clone())?;
+			OutgoingFlows::<T>::mutate_exists(
+				(from.clone(), asset_id),
+				network_id.clone(),
+				|flow| {
+					*flow =
+						flow.map(|flow| flow.saturating_sub(amount)).filter(|flow| !flow.is_zero())
+				},
+			);
 
 			<Pallet<T> as RelayerInterface>::accept_transfer(
 				asset_id,
@@ -616,7 +899,11 @@ pub mod pallet {
 
 			let now = <frame_system::Pallet<T>>::block_number();
 
-			<Pallet<T> as TransferTo>::claim_stale_to(caller, asset_id, to, now)?;
+			<Pallet<T> as TransferTo>::claim_stale_to(caller.clone(), asset_id, to, now)?;
+			// the claimed transactions never left, so they no longer count towards the outflow
+			for (network_id, amount) in OutgoingFlows::<T>::drain_prefix((caller, asset_id)) {
+				Self::rescind_flow(&network_id, asset_id, amount, FlowDirection::Outflow, now);
+			}
 
 			Ok(().into())
 		}
@@ -624,7 +911,7 @@ pub mod pallet {
 		/// Mints new tokens into the pallet's wallet, ready for the user to be picked up after
 		/// `lock_time` blocks have expired.
 		///
-		/// Only callable by the current Relayer
+		/// Only callable by the current Relayer, and only while attestation is disabled.
 		#[pallet::weight(T::WeightInfo::timelocked_mint())]
 		#[transactional]
 		pub fn timelocked_mint(
@@ -637,7 +924,9 @@ pub mod pallet {
 			id: Id,
 		) -> DispatchResultWithPostInfo {
 			let (_caller, current_block) = Self::ensure_relayer(origin)?;
+			ensure!(AttestationThreshold::<T>::get().is_zero(), Error::<T>::AttestationRequired);
 			let asset_id = Self::get_local_mapping(remote_asset_id.clone(), network_id.clone())?;
+			Self::ensure_network_enabled(&network_id)?;
 
 			<Pallet<T> as RelayerInterface>::timelocked_mint(
 				asset_id,
@@ -646,6 +935,7 @@ pub mod pallet {
 				amount,
 				lock_time,
 			)?;
+			Self::record_flow(&network_id, asset_id, amount, FlowDirection::Inflow, current_block)?;
 
 			Self::deposit_event(Event::<T>::TransferInto {
 				id,
@@ -696,6 +986,8 @@ pub mod pallet {
 				account.clone(),
 				untrusted_amount,
 			)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			Self::rescind_flow(&network_id, asset_id, untrusted_amount, FlowDirection::Inflow, now);
 
 			Self::deposit_event(Event::<T>::TransferIntoRescined {
 				account,
@@ -835,6 +1127,262 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Bonds [`RelayerBond`](Config::RelayerBond) of the caller, after which the
+		/// [`ControlOrigin`](Config::ControlOrigin) may add it to the attesting relayers.
+		#[pallet::weight(T::WeightInfo::bond_relayer())]
+		#[transactional]
+		pub fn bond_relayer(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			ensure!(!RelayerBonds::<T>::contains_key(&relayer), Error::<T>::RelayerAlreadyBonded);
+
+			let amount = T::RelayerBond::get();
+			T::Assets::transfer(
+				T::BondAssetId::get(),
+				&relayer,
+				&Self::sub_account_id(SubAccount::new_bond(relayer.clone())),
+				amount,
+				false,
+			)?;
+			RelayerBonds::<T>::insert(&relayer, amount);
+
+			Self::deposit_event(Event::RelayerBonded { relayer, amount });
+			Ok(().into())
+		}
+
+		/// Adds a bonded account to the attesting relayers.
+		///
+		/// This can only be called by the [`ControlOrigin`](Config::ControlOrigin)
+		#[pallet::weight(T::WeightInfo::add_relayer())]
+		#[transactional]
+		pub fn add_relayer(
+			origin: OriginFor<T>,
+			relayer: AccountIdOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(RelayerBonds::<T>::contains_key(&relayer), Error::<T>::RelayerNotBonded);
+
+			RelayerSet::<T>::try_mutate(|relayers| {
+				ensure!(!relayers.contains(&relayer), Error::<T>::RelayerAlreadyAdded);
+				relayers.try_push(relayer.clone()).map_err(|_| Error::<T>::TooManyRelayers)
+			})?;
+
+			Self::deposit_event(Event::RelayerAdded { relayer });
+			Ok(().into())
+		}
+
+		/// Removes a relayer from the attesting relayers, returning its bond, or burning it if
+		/// `slash` is set. Attestations of the relayer to transfers which are not yet minted no
+		/// longer count.
+		///
+		/// Note that the threshold is not lowered; if fewer relayers than the threshold remain,
+		/// no transfers can be minted until relayers are added or the threshold is lowered.
+		///
+		/// This can only be called by the [`ControlOrigin`](Config::ControlOrigin)
+		#[pallet::weight(T::WeightInfo::remove_relayer())]
+		#[transactional]
+		pub fn remove_relayer(
+			origin: OriginFor<T>,
+			relayer: AccountIdOf<T>,
+			slash: bool,
+		) -> DispatchResultWithPostInfo {
+			T::ControlOrigin::ensure_origin(origin)?;
+			let bond = RelayerBonds::<T>::take(&relayer).ok_or(Error::<T>::RelayerNotBonded)?;
+			RelayerSet::<T>::mutate(|relayers| relayers.retain(|account| *account != relayer));
+
+			let bond_account = Self::sub_account_id(SubAccount::new_bond(relayer.clone()));
+			if slash {
+				T::Assets::burn_from(T::BondAssetId::get(), &bond_account, bond)?;
+			} else {
+				T::Assets::transfer(T::BondAssetId::get(), &bond_account, &relayer, bond, false)?;
+			}
+
+			Self::deposit_event(Event::RelayerRemoved { relayer, slashed: slash });
+			Ok(().into())
+		}
+
+		/// Returns the bond of the caller, which must not be one of the attesting relayers.
+		/// Attesting relayers get their bond back once removed by the
+		/// [`ControlOrigin`](Config::ControlOrigin).
+		#[pallet::weight(T::WeightInfo::unbond_relayer())]
+		#[transactional]
+		pub fn unbond_relayer(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			ensure!(!RelayerSet::<T>::get().contains(&relayer), Error::<T>::RelayerAlreadyAdded);
+			let amount = RelayerBonds::<T>::take(&relayer).ok_or(Error::<T>::RelayerNotBonded)?;
+
+			T::Assets::transfer(
+				T::BondAssetId::get(),
+				&Self::sub_account_id(SubAccount::new_bond(relayer.clone())),
+				&relayer,
+				amount,
+				false,
+			)?;
+
+			Self::deposit_event(Event::RelayerUnbonded { relayer, amount });
+			Ok(().into())
+		}
+
+		/// Sets the number of distinct relayers which must attest to an incoming transfer before
+		/// it is minted. Setting it to zero disables attestation, leaving minting to the single
+		/// Relayer through `timelocked_mint`.
+		///
+		/// This can only be called by the [`ControlOrigin`](Config::ControlOrigin)
+		#[pallet::weight(T::WeightInfo::set_attestation_threshold())]
+		#[transactional]
+		pub fn set_attestation_threshold(
+			origin: OriginFor<T>,
+			threshold: u32,
+		) -> DispatchResultWithPostInfo {
+			T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(
+				threshold as usize <= RelayerSet::<T>::decode_len().unwrap_or_default(),
+				Error::<T>::InvalidThreshold
+			);
+
+			AttestationThreshold::<T>::put(threshold);
+
+			Self::deposit_event(Event::AttestationThresholdSet { threshold });
+			Ok(().into())
+		}
+
+		/// Attests to the incoming transfer `id`. Once the
+		/// [`AttestationThreshold`](AttestationThreshold) of relayers attested to the same
+		/// `network_id`, `remote_asset_id`, `to` and `amount`, the funds are minted into the
+		/// pallet's wallet, ready for the user to be picked up after the `TimeLockPeriod`.
+		///
+		/// Only callable by the attesting relayers, once per transfer.
+		#[pallet::weight(T::WeightInfo::attest_mint())]
+		#[transactional]
+		pub fn attest_mint(
+			origin: OriginFor<T>,
+			network_id: NetworkIdOf<T>,
+			remote_asset_id: RemoteAssetIdOf<T>,
+			to: AccountIdOf<T>,
+			amount: BalanceOf<T>,
+			id: Id,
+		) -> DispatchResultWithPostInfo {
+			let relayer = ensure_signed(origin)?;
+			let threshold = AttestationThreshold::<T>::get();
+			ensure!(!threshold.is_zero(), Error::<T>::AttestationNotEnabled);
+			let relayers = RelayerSet::<T>::get();
+			ensure!(relayers.contains(&relayer), Error::<T>::NotARelayer);
+			ensure!(!MintedTransfers::<T>::contains_key(id), Error::<T>::AlreadyMinted);
+			let asset_id = Self::get_local_mapping(remote_asset_id.clone(), network_id.clone())?;
+			Self::ensure_network_enabled(&network_id)?;
+
+			let digest = Keccak256::hash_of(&(&network_id, &remote_asset_id, &to, &amount));
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			let attestations = Attestations::<T>::try_mutate(
+				id,
+				|(attested_at, attestations)| -> Result<u32, DispatchError> {
+					// attestations of removed relayers no longer count
+					attestations.retain(|(attester, _)| relayers.contains(attester));
+					if attestations.is_empty() {
+						*attested_at = current_block;
+					}
+					ensure!(
+						!attestations.iter().any(|(attester, _)| *attester == relayer),
+						Error::<T>::AlreadyAttested
+					);
+					attestations
+						.try_push((relayer.clone(), digest))
+						.map_err(|_| Error::<T>::TooManyRelayers)?;
+					Ok(attestations.iter().filter(|(_, attested)| *attested == digest).count()
+						as u32)
+				},
+			)?;
+			Self::deposit_event(Event::MintAttested { id, relayer, attestations });
+
+			if attestations >= threshold {
+				<Pallet<T> as RelayerInterface>::timelocked_mint(
+					asset_id,
+					current_block,
+					to.clone(),
+					amount,
+					TimeLockPeriod::<T>::get(),
+				)?;
+				Self::record_flow(
+					&network_id,
+					asset_id,
+					amount,
+					FlowDirection::Inflow,
+					current_block,
+				)?;
+				Attestations::<T>::remove(id);
+				MintedTransfers::<T>::insert(id, ());
+
+				Self::deposit_event(Event::<T>::TransferInto {
+					id,
+					to,
+					network_id,
+					remote_asset_id,
+					asset_id,
+					amount,
+				});
+			}
+
+			Ok(().into())
+		}
+
+		/// Sets the caps on the amounts of `asset_id` transferred over `network_id` within a
+		/// sliding window, or removes them if `rate_limit` is `None`. Transfers exceeding the
+		/// caps are rejected.
+		///
+		/// This can only be called by the [`ControlOrigin`](Config::ControlOrigin)
+		#[pallet::weight(T::WeightInfo::set_rate_limit())]
+		#[transactional]
+		pub fn set_rate_limit(
+			origin: OriginFor<T>,
+			network_id: NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			rate_limit: Option<RateLimit<BalanceOf<T>, BlockNumberOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			T::ControlOrigin::ensure_origin(origin)?;
+
+			match &rate_limit {
+				Some(limit) => {
+					ensure!(!limit.window.is_zero(), Error::<T>::InvalidWindow);
+					RateLimits::<T>::insert(network_id.clone(), asset_id, limit);
+				},
+				None => {
+					RateLimits::<T>::remove(network_id.clone(), asset_id);
+					NetworkFlows::<T>::remove(network_id.clone(), asset_id);
+				},
+			}
+
+			Self::deposit_event(Event::RateLimitUpdated { network_id, asset_id, rate_limit });
+			Ok(().into())
+		}
+
+		/// Sets the circuit breaker of `asset_id`, or removes it if `circuit_breaker` is
+		/// `None`. Once the net amount bridged within the window exceeds the breaker, the network
+		/// over which the transfer was made is disabled.
+		///
+		/// This can only be called by the [`ControlOrigin`](Config::ControlOrigin)
+		#[pallet::weight(T::WeightInfo::set_circuit_breaker())]
+		#[transactional]
+		pub fn set_circuit_breaker(
+			origin: OriginFor<T>,
+			asset_id: AssetIdOf<T>,
+			circuit_breaker: Option<CircuitBreaker<BlockNumberOf<T>>>,
+		) -> DispatchResultWithPostInfo {
+			T::ControlOrigin::ensure_origin(origin)?;
+
+			match &circuit_breaker {
+				Some(breaker) => {
+					ensure!(!breaker.window.is_zero(), Error::<T>::InvalidWindow);
+					CircuitBreakers::<T>::insert(asset_id, breaker);
+				},
+				None => {
+					CircuitBreakers::<T>::remove(asset_id);
+					AssetFlows::<T>::remove(asset_id);
+				},
+			}
+
+			Self::deposit_event(Event::CircuitBreakerUpdated { asset_id, circuit_breaker });
+			Ok(().into())
+		}
 	}
 
 	#[pallet::extra_constants]
@@ -845,6 +1393,42 @@ pub mod pallet {
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// Drops attestations to transfers first attested more than `AttestationTTL` blocks ago, as
+		/// many as `remaining_weight` allows, continuing from where the previous block stopped.
+		pub(crate) fn expire_attestations(remaining_weight: Weight) -> Weight {
+			let per_transfer = T::WeightInfo::expire_attestation();
+			// reading and updating the cursor
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			let budget =
+				remaining_weight.saturating_sub(weight).ref_time() / per_transfer.ref_time().max(1);
+			if budget.is_zero() {
+				return Weight::from_ref_time(0)
+			}
+
+			let transfers = match AttestationsCursor::<T>::get() {
+				Some(id) => Attestations::<T>::iter_from(Attestations::<T>::hashed_key_for(id)),
+				None => Attestations::<T>::iter(),
+			}
+			.take(budget as usize)
+			.collect::<Vec<_>>();
+			match transfers.last() {
+				Some((id, _)) if transfers.len() as u64 == budget =>
+					AttestationsCursor::<T>::put(id),
+				// the last transfer was reached, start over from the first one
+				_ => AttestationsCursor::<T>::kill(),
+			}
+
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			for (id, (attested_at, _)) in transfers {
+				weight = weight.saturating_add(per_transfer);
+				if attested_at.saturating_add(T::AttestationTTL::get()) <= current_block {
+					Attestations::<T>::remove(id);
+					Self::deposit_event(Event::AttestationsExpired { id });
+				}
+			}
+			weight
+		}
+
 		/// AccountId of the pallet, used to store all funds before actually moving them.
 		pub(crate) fn sub_account_id(sub_account: SubAccount<T>) -> AccountIdOf<T> {
 			T::PalletId::get().into_sub_account_truncating(sub_account.to_id())
@@ -875,6 +1459,115 @@ pub mod pallet {
 			Ok((relayer, current_block))
 		}
 
+		pub(crate) fn ensure_network_enabled(network_id: &NetworkIdOf<T>) -> DispatchResult {
+			let network_info =
+				NetworkInfos::<T>::get(network_id.clone()).ok_or(Error::<T>::UnsupportedNetwork)?;
+			ensure!(network_info.enabled, Error::<T>::NetworkDisabled);
+			Ok(())
+		}
+
+		/// Records a transfer of `amount` of `asset_id` over `network_id`, failing if it exceeds
+		/// the `RateLimit` of the asset over the network.
+		///
+		/// If the transfer changes the total issuance of the asset by more than its
+		/// `CircuitBreaker` allows, the network is disabled. The transfer itself still completes;
+		/// the rate limits bound the amount which can be moved before the breaker trips.
+		pub(crate) fn record_flow(
+			network_id: &NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			direction: FlowDirection,
+			now: BlockNumberOf<T>,
+		) -> DispatchResult {
+			if let Some(limit) = RateLimits::<T>::get(network_id.clone(), asset_id) {
+				NetworkFlows::<T>::try_mutate(network_id.clone(), asset_id, |flows| {
+					let (window, max, error) = match direction {
+						FlowDirection::Inflow =>
+							(&mut flows.inflow, limit.max_inflow, Error::<T>::InflowLimitExceeded),
+						FlowDirection::Outflow => (
+							&mut flows.outflow,
+							limit.max_outflow,
+							Error::<T>::OutflowLimitExceeded,
+						),
+					};
+					window.roll(now, limit.window);
+					ensure!(window.total(now, limit.window).saturating_add(amount) <= max, error);
+					window.current = window.current.saturating_add(amount);
+					Ok::<_, Error<T>>(())
+				})?;
+			}
+
+			if let Some(breaker) = CircuitBreakers::<T>::get(asset_id) {
+				let change = AssetFlows::<T>::mutate(asset_id, |flows| {
+					flows.inflow.roll(now, breaker.window);
+					flows.outflow.roll(now, breaker.window);
+					let window = match direction {
+						FlowDirection::Inflow => &mut flows.inflow,
+						FlowDirection::Outflow => &mut flows.outflow,
+					};
+					window.current = window.current.saturating_add(amount);
+					let inflow = flows.inflow.total(now, breaker.window);
+					let outflow = flows.outflow.total(now, breaker.window);
+					inflow.max(outflow).saturating_sub(inflow.min(outflow))
+				});
+				let total_issuance = T::Assets::total_issuance(asset_id).saturated_into::<u128>();
+				if change.saturated_into::<u128>() > breaker.max_change.mul_floor(total_issuance) {
+					Self::trip_circuit_breaker(network_id, asset_id, change);
+				}
+			}
+
+			Ok(())
+		}
+
+		/// Takes back a flow recorded by [`record_flow`](Self::record_flow) for a transfer which
+		/// was rescinded or claimed back.
+		fn rescind_flow(
+			network_id: &NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			amount: BalanceOf<T>,
+			direction: FlowDirection,
+			now: BlockNumberOf<T>,
+		) {
+			if let Some(limit) = RateLimits::<T>::get(network_id.clone(), asset_id) {
+				NetworkFlows::<T>::mutate(network_id.clone(), asset_id, |flows| {
+					let window = match direction {
+						FlowDirection::Inflow => &mut flows.inflow,
+						FlowDirection::Outflow => &mut flows.outflow,
+					};
+					window.roll(now, limit.window);
+					window.rescind(amount);
+				});
+			}
+			if let Some(breaker) = CircuitBreakers::<T>::get(asset_id) {
+				AssetFlows::<T>::mutate(asset_id, |flows| {
+					let window = match direction {
+						FlowDirection::Inflow => &mut flows.inflow,
+						FlowDirection::Outflow => &mut flows.outflow,
+					};
+					window.roll(now, breaker.window);
+					window.rescind(amount);
+				});
+			}
+		}
+
+		/// Disables `network_id`, alerting that the circuit breaker of `asset_id` tripped.
+		fn trip_circuit_breaker(
+			network_id: &NetworkIdOf<T>,
+			asset_id: AssetIdOf<T>,
+			change: BalanceOf<T>,
+		) {
+			NetworkInfos::<T>::mutate(network_id.clone(), |network_info| {
+				if let Some(network_info) = network_info {
+					network_info.enabled = false;
+				}
+			});
+			Self::deposit_event(Event::CircuitBreakerTripped {
+				network_id: network_id.clone(),
+				asset_id,
+				change,
+			});
+		}
+
 		pub(crate) fn get_local_mapping(
 			remote_asset_id: RemoteAssetIdOf<T>,
 			network_id: NetworkIdOf<T>,
@@ -1214,8 +1907,6 @@ pub mod pallet {
 		amount: &BalanceOf<T>,
 		block_number: &BlockNumberOf<T>,
 	) -> Id {
-		use sp_runtime::traits::Hash;
-
 		let nonce = Nonce::<T>::mutate(|nonce| {
 			// TODO: Use WrappingNext here
 			*nonce = nonce.wrapping_add(1);
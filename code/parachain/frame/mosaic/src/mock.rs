@@ -107,6 +107,10 @@ parameter_types! {
 	pub const MosaicPalletId: PalletId = PalletId(*b"plt_msac");
 	pub const MinimumTTL: BlockNumber = 10;
 	pub const MinimumTimeLockPeriod: BlockNumber = 20;
+	pub const RelayerBond: Balance = 100;
+	pub const BondAssetId: AssetId = 1;
+	pub const MaxRelayers: u32 = 4;
+	pub const AttestationTTL: BlockNumber = 100;
 }
 
 impl pallet_mosaic::Config for Test {
@@ -119,6 +123,10 @@ impl pallet_mosaic::Config for Test {
 	type NetworkId = NetworkId;
 	type RemoteAssetId = RemoteAssetId;
 	type ControlOrigin = EnsureRoot<Self::AccountId>;
+	type RelayerBond = RelayerBond;
+	type BondAssetId = BondAssetId;
+	type MaxRelayers = MaxRelayers;
+	type AttestationTTL = AttestationTTL;
 	type WeightInfo = ();
 	type RemoteAmmId = RemoteAmmId;
 	type AmmMinimumAmountOut = AmmMinimumAmountOut;
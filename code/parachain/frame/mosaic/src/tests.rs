@@ -294,6 +294,21 @@ mod budget {
 			})
 		}
 
+		#[test]
+		fn zero_half_life_is_rejected() {
+			new_test_ext().execute_with(|| {
+				assert_noop!(
+					Mosaic::set_budget(
+						RuntimeOrigin::root(),
+						1,
+						1,
+						BudgetPenaltyDecayer::exponential(0)
+					),
+					Error::<Test>::InvalidDecay
+				);
+			})
+		}
+
 		#[test]
 		fn arbitrary_user_cannot_set_budget() {
 			new_test_ext().execute_with(|| {
@@ -1711,3 +1726,487 @@ mod do_transfer_with_remote_amm_swap {
 		}
 	}
 }
+
+mod attest_mint {
+	use super::*;
+	use frame_support::{
+		dispatch::DispatchResultWithPostInfo,
+		traits::{Get, Hooks},
+		weights::Weight,
+	};
+
+	const RELAYERS: [AccountId; 3] = [ALICE, BOB, CHARLIE];
+	const TO: AccountId = 42;
+
+	fn new_attesters_ext() -> sp_io::TestExternalities {
+		ExtBuilder { balances: RELAYERS.iter().map(|relayer| (*relayer, 1, 1000)).collect() }
+			.build()
+	}
+
+	fn initialize_attesters(threshold: u32) {
+		initialize();
+		for relayer in RELAYERS {
+			assert_ok!(Mosaic::bond_relayer(RuntimeOrigin::signed(relayer)));
+			assert_ok!(Mosaic::add_relayer(RuntimeOrigin::root(), relayer));
+		}
+		assert_ok!(Mosaic::set_attestation_threshold(RuntimeOrigin::root(), threshold));
+	}
+
+	fn attest(relayer: AccountId, amount: Balance, id: Id) -> DispatchResultWithPostInfo {
+		Mosaic::attest_mint(
+			RuntimeOrigin::signed(relayer),
+			NETWORK_ID,
+			REMOTE_ASSET_ID,
+			TO,
+			amount,
+			id,
+		)
+	}
+
+	#[test]
+	fn bond_relayer_locks_bond() {
+		new_attesters_ext().execute_with(|| {
+			assert_ok!(Mosaic::bond_relayer(RuntimeOrigin::signed(ALICE)));
+			assert_eq!(Tokens::balance(1, &ALICE), 1000 - RelayerBond::get());
+			assert_eq!(
+				Tokens::balance(1, &Mosaic::sub_account_id(SubAccount::new_bond(ALICE))),
+				RelayerBond::get()
+			);
+			assert_eq!(Mosaic::relayer_bonds(ALICE), Some(RelayerBond::get()));
+			assert_noop!(
+				Mosaic::bond_relayer(RuntimeOrigin::signed(ALICE)),
+				Error::<Test>::RelayerAlreadyBonded
+			);
+		})
+	}
+
+	#[test]
+	fn only_bonded_accounts_can_be_added_by_governance() {
+		new_attesters_ext().execute_with(|| {
+			assert_noop!(
+				Mosaic::add_relayer(RuntimeOrigin::root(), BOB),
+				Error::<Test>::RelayerNotBonded
+			);
+			assert_ok!(Mosaic::bond_relayer(RuntimeOrigin::signed(BOB)));
+			assert_noop!(
+				Mosaic::add_relayer(RuntimeOrigin::signed(BOB), BOB),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Mosaic::add_relayer(RuntimeOrigin::root(), BOB));
+			assert_noop!(
+				Mosaic::add_relayer(RuntimeOrigin::root(), BOB),
+				Error::<Test>::RelayerAlreadyAdded
+			);
+		})
+	}
+
+	#[test]
+	fn threshold_cannot_exceed_relayers() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(3);
+			assert_noop!(
+				Mosaic::set_attestation_threshold(RuntimeOrigin::root(), 4),
+				Error::<Test>::InvalidThreshold
+			);
+		})
+	}
+
+	#[test]
+	fn transfer_is_minted_once_threshold_is_reached() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+
+			assert_ok!(attest(ALICE, 50, id));
+			assert_eq!(Mosaic::incoming_transactions(TO, ASSET_ID), None);
+
+			assert_ok!(attest(BOB, 50, id));
+			assert_eq!(
+				Mosaic::incoming_transactions(TO, ASSET_ID),
+				Some((50, System::block_number() + Mosaic::time_lock_period()))
+			);
+			assert_eq!(Mosaic::minted_transfers(id), Some(()));
+			assert!(!Attestations::<Test>::contains_key(id));
+
+			assert_noop!(attest(CHARLIE, 50, id), Error::<Test>::AlreadyMinted);
+		})
+	}
+
+	#[test]
+	fn only_matching_attestations_count() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+
+			assert_ok!(attest(ALICE, 50, id));
+			assert_ok!(attest(BOB, 60, id));
+			assert_eq!(Mosaic::incoming_transactions(TO, ASSET_ID), None);
+
+			assert_ok!(attest(CHARLIE, 50, id));
+			assert_eq!(
+				Mosaic::incoming_transactions(TO, ASSET_ID),
+				Some((50, System::block_number() + Mosaic::time_lock_period()))
+			);
+		})
+	}
+
+	#[test]
+	fn relayers_attest_once() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+
+			assert_ok!(attest(ALICE, 50, id));
+			assert_noop!(attest(ALICE, 50, id), Error::<Test>::AlreadyAttested);
+			assert_noop!(attest(RELAYER, 50, id), Error::<Test>::NotARelayer);
+		})
+	}
+
+	#[test]
+	fn single_relayer_cannot_mint_while_attestation_is_enabled() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			assert_noop!(
+				Mosaic::timelocked_mint(
+					RuntimeOrigin::relayer(),
+					NETWORK_ID,
+					REMOTE_ASSET_ID,
+					TO,
+					50,
+					10,
+					Default::default()
+				),
+				Error::<Test>::AttestationRequired
+			);
+		})
+	}
+
+	#[test]
+	fn removed_relayers_no_longer_attest() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+
+			assert_ok!(attest(ALICE, 50, id));
+			assert_ok!(Mosaic::remove_relayer(RuntimeOrigin::root(), ALICE, false));
+			assert_eq!(Tokens::balance(1, &ALICE), 1000);
+			assert_noop!(attest(ALICE, 50, id), Error::<Test>::NotARelayer);
+
+			assert_ok!(attest(BOB, 50, id));
+			assert_eq!(Mosaic::incoming_transactions(TO, ASSET_ID), None);
+			assert_ok!(attest(CHARLIE, 50, id));
+			assert_eq!(
+				Mosaic::incoming_transactions(TO, ASSET_ID).map(|(amount, _)| amount),
+				Some(50)
+			);
+		})
+	}
+
+	#[test]
+	fn slashed_relayers_lose_their_bond() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			assert_ok!(Mosaic::remove_relayer(RuntimeOrigin::root(), ALICE, true));
+			assert_eq!(Tokens::balance(1, &ALICE), 1000 - RelayerBond::get());
+			assert_eq!(Tokens::balance(1, &Mosaic::sub_account_id(SubAccount::new_bond(ALICE))), 0);
+			assert_eq!(Mosaic::relayer_set().into_inner(), vec![BOB, CHARLIE]);
+		})
+	}
+
+	#[test]
+	fn bonded_accounts_which_are_not_relayers_can_unbond() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			assert_noop!(
+				Mosaic::unbond_relayer(RuntimeOrigin::signed(ALICE)),
+				Error::<Test>::RelayerAlreadyAdded
+			);
+			assert_noop!(
+				Mosaic::unbond_relayer(RuntimeOrigin::signed(TO)),
+				Error::<Test>::RelayerNotBonded
+			);
+
+			assert_ok!(Mosaic::remove_relayer(RuntimeOrigin::root(), ALICE, false));
+			assert_ok!(Mosaic::bond_relayer(RuntimeOrigin::signed(ALICE)));
+			assert_ok!(Mosaic::unbond_relayer(RuntimeOrigin::signed(ALICE)));
+			assert_eq!(Tokens::balance(1, &ALICE), 1000);
+			assert_eq!(Mosaic::relayer_bonds(ALICE), None);
+			System::assert_last_event(mock::RuntimeEvent::Mosaic(crate::Event::RelayerUnbonded {
+				relayer: ALICE,
+				amount: RelayerBond::get(),
+			}));
+		})
+	}
+
+	#[test]
+	fn minted_transfers_are_never_minted_again() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+			assert_ok!(attest(ALICE, 50, id));
+			assert_ok!(attest(BOB, 50, id));
+
+			System::set_block_number(System::block_number() + AttestationTTL::get());
+			Mosaic::on_idle(System::block_number(), Weight::MAX);
+			assert_eq!(Mosaic::minted_transfers(id), Some(()));
+			assert_noop!(attest(CHARLIE, 50, id), Error::<Test>::AlreadyMinted);
+		})
+	}
+
+	#[test]
+	fn attestations_below_threshold_expire() {
+		new_attesters_ext().execute_with(|| {
+			initialize_attesters(2);
+			let id = Id::repeat_byte(1);
+			let attested_at = System::block_number();
+			assert_ok!(attest(ALICE, 50, id));
+
+			System::set_block_number(attested_at + AttestationTTL::get() - 1);
+			assert_ok!(attest(BOB, 60, id));
+			Mosaic::on_idle(System::block_number(), Weight::MAX);
+			assert_eq!(Mosaic::attestations(id).0, attested_at);
+
+			System::set_block_number(attested_at + AttestationTTL::get());
+			Mosaic::on_idle(System::block_number(), Weight::MAX);
+			assert!(!Attestations::<Test>::contains_key(id));
+			System::assert_last_event(mock::RuntimeEvent::Mosaic(
+				crate::Event::AttestationsExpired { id },
+			));
+
+			// the transfer collects attestations anew
+			assert_ok!(attest(ALICE, 50, id));
+			assert_eq!(Mosaic::incoming_transactions(TO, ASSET_ID), None);
+			assert_ok!(attest(BOB, 50, id));
+			assert_eq!(
+				Mosaic::incoming_transactions(TO, ASSET_ID).map(|(amount, _)| amount),
+				Some(50)
+			);
+		})
+	}
+}
+
+mod rate_limits {
+	use super::*;
+	use frame_support::dispatch::DispatchResultWithPostInfo;
+	use sp_runtime::Perquintill;
+
+	const WINDOW: u64 = 10;
+
+	fn set_rate_limit(max_outflow: Balance, max_inflow: Balance) {
+		assert_ok!(Mosaic::set_rate_limit(
+			RuntimeOrigin::root(),
+			NETWORK_ID,
+			ASSET_ID,
+			Some(RateLimit { window: WINDOW, max_outflow, max_inflow }),
+		));
+	}
+
+	fn transfer_to(amount: Balance) -> DispatchResultWithPostInfo {
+		Mosaic::transfer_to(
+			RuntimeOrigin::signed(ALICE),
+			NETWORK_ID,
+			ASSET_ID,
+			EthereumAddress([0; 20]),
+			amount,
+			amount,
+			false,
+			ALICE,
+			None,
+			true,
+		)
+	}
+
+	fn timelocked_mint(amount: Balance) -> DispatchResultWithPostInfo {
+		Mosaic::timelocked_mint(
+			RuntimeOrigin::relayer(),
+			NETWORK_ID,
+			REMOTE_ASSET_ID,
+			BOB,
+			amount,
+			10,
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn only_governance_can_set_rate_limits() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			assert_noop!(
+				Mosaic::set_rate_limit(
+					RuntimeOrigin::relayer(),
+					NETWORK_ID,
+					ASSET_ID,
+					Some(RateLimit { window: WINDOW, max_outflow: 100, max_inflow: 100 }),
+				),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Mosaic::set_rate_limit(
+					RuntimeOrigin::root(),
+					NETWORK_ID,
+					ASSET_ID,
+					Some(RateLimit { window: 0, max_outflow: 100, max_inflow: 100 }),
+				),
+				Error::<Test>::InvalidWindow
+			);
+			assert_noop!(
+				Mosaic::set_circuit_breaker(
+					RuntimeOrigin::relayer(),
+					ASSET_ID,
+					Some(CircuitBreaker {
+						window: WINDOW,
+						max_change: Perquintill::from_percent(100)
+					}),
+				),
+				DispatchError::BadOrigin
+			);
+		})
+	}
+
+	#[test]
+	fn outflow_exceeding_rate_limit_is_rejected() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			set_rate_limit(100, 100);
+
+			assert_ok!(transfer_to(60));
+			assert_noop!(transfer_to(50), Error::<Test>::OutflowLimitExceeded);
+			assert_ok!(transfer_to(40));
+
+			// the window slid past the previous transfers
+			System::set_block_number(1 + 2 * WINDOW);
+			assert_ok!(transfer_to(100));
+		})
+	}
+
+	#[test]
+	fn inflow_exceeding_rate_limit_is_rejected() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			set_rate_limit(100, 100);
+
+			assert_ok!(timelocked_mint(100));
+			assert_noop!(timelocked_mint(1), Error::<Test>::InflowLimitExceeded);
+
+			assert_ok!(Mosaic::set_rate_limit(RuntimeOrigin::root(), NETWORK_ID, ASSET_ID, None));
+			assert_ok!(timelocked_mint(1));
+		})
+	}
+
+	#[test]
+	fn previous_window_is_weighed_by_its_overlap() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			set_rate_limit(100, 100);
+			assert_ok!(timelocked_mint(100));
+
+			// halfway through the next window, half of the previous window still counts
+			System::set_block_number(WINDOW + WINDOW / 2);
+			assert_noop!(timelocked_mint(51), Error::<Test>::InflowLimitExceeded);
+			assert_ok!(timelocked_mint(50));
+		})
+	}
+
+	#[test]
+	fn rescinded_mint_frees_inflow() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			set_rate_limit(100, 100);
+
+			assert_ok!(timelocked_mint(100));
+			assert_noop!(timelocked_mint(1), Error::<Test>::InflowLimitExceeded);
+
+			assert_ok!(Mosaic::rescind_timelocked_mint(
+				RuntimeOrigin::relayer(),
+				NETWORK_ID,
+				REMOTE_ASSET_ID,
+				BOB,
+				60
+			));
+			assert_noop!(timelocked_mint(61), Error::<Test>::InflowLimitExceeded);
+			assert_ok!(timelocked_mint(60));
+		})
+	}
+
+	#[test]
+	fn stale_transfer_frees_outflow() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			assert_ok!(Mosaic::set_rate_limit(
+				RuntimeOrigin::root(),
+				NETWORK_ID,
+				ASSET_ID,
+				Some(RateLimit { window: 100, max_outflow: 100, max_inflow: 100 }),
+			));
+
+			assert_ok!(transfer_to(100));
+			assert_ok!(Mosaic::accept_transfer(
+				RuntimeOrigin::relayer(),
+				ALICE,
+				NETWORK_ID,
+				REMOTE_ASSET_ID,
+				40
+			));
+			assert_eq!(Mosaic::outgoing_flows((ALICE, ASSET_ID), NETWORK_ID), Some(60));
+
+			// only the part the Relayer did not pick up is taken back
+			System::set_block_number(System::block_number() + Mosaic::timelock_period() + 1);
+			assert_ok!(Mosaic::claim_stale_to(RuntimeOrigin::signed(ALICE), ASSET_ID, ALICE));
+			assert_eq!(Mosaic::outgoing_flows((ALICE, ASSET_ID), NETWORK_ID), None);
+			assert_noop!(transfer_to(61), Error::<Test>::OutflowLimitExceeded);
+			assert_ok!(transfer_to(60));
+		})
+	}
+
+	#[test]
+	fn circuit_breaker_disables_network() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			assert_ok!(Mosaic::set_circuit_breaker(
+				RuntimeOrigin::root(),
+				ASSET_ID,
+				Some(CircuitBreaker {
+					window: WINDOW,
+					max_change: Perquintill::from_rational(1u64, 1000),
+				}),
+			));
+
+			assert_ok!(timelocked_mint(500));
+			assert!(Mosaic::network_infos(NETWORK_ID).unwrap().enabled);
+
+			// the transfer tripping the breaker completes, later transfers are rejected
+			assert_ok!(timelocked_mint(1000));
+			System::assert_has_event(mock::RuntimeEvent::Mosaic(
+				crate::Event::CircuitBreakerTripped {
+					network_id: NETWORK_ID,
+					asset_id: ASSET_ID,
+					change: 1500,
+				},
+			));
+			assert!(!Mosaic::network_infos(NETWORK_ID).unwrap().enabled);
+			assert_noop!(timelocked_mint(1), Error::<Test>::NetworkDisabled);
+			assert_noop!(transfer_to(100), Error::<Test>::NetworkDisabled);
+		})
+	}
+
+	#[test]
+	fn outflows_offset_inflows_for_circuit_breaker() {
+		new_test_ext().execute_with(|| {
+			initialize();
+			assert_ok!(Mosaic::set_circuit_breaker(
+				RuntimeOrigin::root(),
+				ASSET_ID,
+				Some(CircuitBreaker {
+					window: WINDOW,
+					max_change: Perquintill::from_rational(1u64, 1000),
+				}),
+			));
+
+			assert_ok!(timelocked_mint(800));
+			assert_ok!(transfer_to(800));
+			assert_ok!(timelocked_mint(800));
+			assert!(Mosaic::network_infos(NETWORK_ID).unwrap().enabled);
+		})
+	}
+}
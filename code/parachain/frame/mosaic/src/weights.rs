@@ -21,6 +21,15 @@ pub trait WeightInfo {
   fn update_asset_mapping() -> Weight;
   fn add_remote_amm_id() -> Weight;
   fn remove_remote_amm_id() -> Weight;
+  fn bond_relayer() -> Weight;
+  fn add_relayer() -> Weight;
+  fn remove_relayer() -> Weight;
+  fn set_attestation_threshold() -> Weight;
+  fn attest_mint() -> Weight;
+  fn set_rate_limit() -> Weight;
+  fn set_circuit_breaker() -> Weight;
+  fn unbond_relayer() -> Weight;
+  fn expire_attestation() -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -79,5 +88,40 @@ impl WeightInfo for () {
   fn remove_remote_amm_id() -> Weight {
     Weight::from_ref_time(10_000)
   }
-}
 
+  fn bond_relayer() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn add_relayer() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn remove_relayer() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn set_attestation_threshold() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn attest_mint() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn set_rate_limit() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn set_circuit_breaker() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn unbond_relayer() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+
+  fn expire_attestation() -> Weight {
+    Weight::from_ref_time(10_000)
+  }
+}
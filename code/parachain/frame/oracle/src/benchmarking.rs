@@ -8,13 +8,13 @@ use composable_traits::oracle::Price;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::{
 	assert_ok,
-	traits::{Currency, Get},
+	traits::{Currency, Get, ReservableCurrency},
 	BoundedVec,
 };
 use frame_system::{EventRecord, RawOrigin};
 use sp_runtime::{DispatchResult, Percent};
 
-use sp_std::{prelude::*, vec};
+use sp_std::{collections::btree_set::BTreeSet, prelude::*, vec};
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -56,6 +56,40 @@ benchmarks! {
 		assert_last_event::<T>(Event::AssetInfoChange(asset_id.into(), *threshold, *min_answers, *max_answers, *block_interval, reward, slash).into());
 	}
 
+	add_derived_asset {
+		let asset_id: T::AssetId = 2_u128.into();
+		let derivation = PriceDerivation::CrossRate { base: 0_u128.into(), quote: 1_u128.into() };
+	}: _(RawOrigin::Root, asset_id, derivation.clone())
+	verify {
+		assert_last_event::<T>(Event::DerivedAssetChanged(asset_id, derivation).into());
+	}
+
+	remove_derived_asset {
+		let asset_id: T::AssetId = 2_u128.into();
+		DerivedPrices::<T>::insert(asset_id, PriceDerivation::CrossRate { base: 0_u128.into(), quote: 1_u128.into() });
+	}: _(RawOrigin::Root, asset_id)
+	verify {
+		assert_last_event::<T>(Event::DerivedAssetRemoved(asset_id).into());
+	}
+
+	set_slashing_policy {
+		let asset_id: T::AssetId = 1_u128.into();
+		AssetsInfo::<T>::insert(asset_id, AssetInfo {
+			threshold: Percent::from_percent(80),
+			min_answers: 1,
+			max_answers: T::MaxAnswerBound::get(),
+			block_interval: T::StalePrice::get(),
+			reward_weight: T::Currency::minimum_balance(),
+			slash: T::Currency::minimum_balance(),
+			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 0,
+		});
+	}: _(RawOrigin::Root, asset_id, Percent::from_percent(50), 3)
+	verify {
+		assert_last_event::<T>(Event::SlashingPolicyChanged(asset_id, Percent::from_percent(50), 3).into());
+	}
+
 	adjust_rewards {
 		let annual_cost_per_oracle: T::Balance = 100_000_u128.into();
 		let num_ideal_oracles: u8 = 10;
@@ -123,12 +157,15 @@ benchmarks! {
 			reward_weight: T::Currency::minimum_balance(),
 			slash: T::Currency::minimum_balance(),
 			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 0,
 		});
 		frame_system::Pallet::<T>::set_block_number(6u32.into());
 		PrePrices::<T>::mutate(asset_id, |current_prices| -> DispatchResult {
 			for (i, price_submitter) in price_submitters.iter().enumerate() {
 				let set_price = PrePrice {
 					price: (price + i as u128).into(),
+					confidence: 0u32.into(),
 					block: frame_system::Pallet::<T>::block_number(),
 					who: price_submitter.clone(),
 				};
@@ -136,7 +173,7 @@ benchmarks! {
 			}
 			Ok(())
 		})?;
-	}: _(RawOrigin::Signed(caller.clone()), price.into(), asset_id)
+	}: _(RawOrigin::Signed(caller.clone()), price.into(), 0u32.into(), asset_id)
 	verify {
 		assert_last_event::<T>(Event::PriceSubmitted(caller, asset_id, price.into()).into())
 	}
@@ -154,10 +191,13 @@ benchmarks! {
 			reward_weight: T::Currency::minimum_balance(),
 			slash: T::Currency::minimum_balance(),
 			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 0,
 		};
 		let pre_prices = (0..p).map(|i| {
 			PrePrice {
 				price: (100u128 + i as u128).into(),
+				confidence: 0u32.into(),
 				block: 0u32.into(),
 				who: who.clone()
 			}
@@ -181,10 +221,13 @@ benchmarks! {
 			reward_weight: T::Currency::minimum_balance(),
 			slash: T::Currency::minimum_balance(),
 			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 0,
 		};
 		let pre_prices = (0..p).map(|_| {
 			PrePrice {
 				price: (100u128 + p as u128).into(),
+				confidence: 0u32.into(),
 				block: 0u32.into(),
 				who: who.clone()
 			}
@@ -197,6 +240,34 @@ benchmarks! {
 	}: {
 		Oracle::<T>::update_price(asset_id, asset_info.into(), block, pre_prices)
 	}
+
+	track_missed_rounds {
+		let s in 1 .. T::MaxSigners::get();
+		let asset_id: T::AssetId = T::AssetId::from(1u128);
+		let asset_info = AssetInfo {
+			threshold: Percent::from_percent(80),
+			min_answers: 1,
+			max_answers: T::MaxAnswerBound::get(),
+			block_interval: T::StalePrice::get(),
+			reward_weight: T::Currency::minimum_balance(),
+			slash: T::Currency::minimum_balance(),
+			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 1,
+		};
+		// the worst scenario is when every signer missed the round once too often and is slashed
+		let stake = T::MinStake::get() + T::Currency::minimum_balance();
+		for i in 0..s {
+			let signer: T::AccountId = account("signer", i, SEED);
+			T::Currency::make_free_balance_be(&signer, stake + T::Currency::minimum_balance());
+			assert_ok!(T::Currency::reserve(&signer, stake));
+			SignerToController::<T>::insert(&signer, signer.clone());
+			OracleStake::<T>::insert(&signer, stake);
+			MissedRounds::<T>::insert(&signer, asset_id, 1);
+		}
+	}: {
+		Oracle::<T>::track_missed_rounds(asset_id, &asset_info, BTreeSet::new())
+	}
 }
 
 impl_benchmark_test_suite!(Oracle, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -15,6 +15,8 @@
 #![allow(clippy::too_many_arguments)]
 pub use pallet::*;
 
+pub mod migration;
+pub mod sources;
 mod validation;
 
 #[cfg(test)]
@@ -29,8 +31,11 @@ pub mod weights;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use crate::validation::{ValidBlockInterval, ValidMaxAnswer, ValidMinAnswers, ValidThreshold};
 	pub use crate::weights::WeightInfo;
+	use crate::{
+		sources::{self, PriceSource, PriceSourceKind, SourcePrice},
+		validation::{ValidBlockInterval, ValidMaxAnswer, ValidMinAnswers, ValidThreshold},
+	};
 	use codec::{Codec, FullCodec};
 	use composable_support::{
 		abstractions::{
@@ -44,8 +49,8 @@ pub mod pallet {
 		validation::Validated,
 	};
 	use composable_traits::{
-		currency::{BalanceLike, LocalAssets},
-		oracle::{Oracle, Price, RewardTracker},
+		currency::{BalanceLike, Exponent, LocalAssets},
+		oracle::{LiquidityPools, Oracle, Price, RewardTracker, StakingShares},
 		time::MS_PER_YEAR_NAIVE,
 	};
 	use frame_support::{
@@ -69,7 +74,7 @@ pub mod pallet {
 	use scale_info::TypeInfo;
 	use sp_core::crypto::KeyTypeId;
 	use sp_runtime::{
-		offchain::{http, Duration},
+		offchain::{http, Duration, Timestamp},
 		traits::{
 			AtLeast32Bit, AtLeast32BitUnsigned, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub,
 			Saturating, UniqueSaturatedInto as _, Zero,
@@ -78,7 +83,11 @@ pub mod pallet {
 		PerThing, Percent, RuntimeDebug,
 	};
 	use sp_std::{
-		borrow::ToOwned, collections::btree_set::BTreeSet, fmt::Debug, str, vec, vec::Vec,
+		borrow::ToOwned,
+		collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+		fmt::Debug,
+		str, vec,
+		vec::Vec,
 	};
 
 	// Key Id for location of signer key in keystore
@@ -162,8 +171,16 @@ pub mod pallet {
 		type MaxAnswerBound: Get<u32>;
 		/// Upper bound for total assets available for the oracle
 		type MaxAssetsCount: Get<u32>;
+		/// Upper bound for total signers set, whose missed rounds are tracked each block
+		type MaxSigners: Get<u32>;
 		/// Slashed stakes are transferred to treasury.
 		type TreasuryAccount: Get<Self::AccountId>;
+		/// Share of the stake slashed for each consecutive offence of a signer, on top of the
+		/// `slash` of the asset. The last step applies to any further offence.
+		type SlashLadder: Get<Vec<Percent>>;
+		/// Blocks after setting a signer during which its missed rounds are not counted, and
+		/// without offences after which the offences of a signer are forgiven.
+		type SlashGracePeriod: Get<Self::BlockNumber>;
 
 		#[pallet::constant]
 		type MaxHistory: Get<u32>;
@@ -180,6 +197,12 @@ pub mod pallet {
 		/// The weight information of this pallet.
 		type WeightInfo: WeightInfo;
 		type LocalAssets: LocalAssets<Self::AssetId>;
+		/// The currency prices are quoted in, whose decimals are those of submitted prices.
+		type NormalizedAssetId: Get<Self::AssetId>;
+		/// Pools whose LP tokens may be priced from the assets they are redeemable for.
+		type LiquidityPools: LiquidityPools<Self::AssetId, Self::PriceValue>;
+		/// Staking pools whose shares may be priced from the stake they are redeemable for.
+		type StakingShares: StakingShares<Self::AssetId, Self::PriceValue>;
 
 		/// Type for timestamps
 		type Moment: AtLeast32Bit + Parameter + Default + Copy + MaxEncodedLen + FullCodec;
@@ -201,8 +224,10 @@ pub mod pallet {
 		Encode, Decode, MaxEncodedLen, Clone, Copy, Default, Debug, PartialEq, Eq, TypeInfo,
 	)]
 	pub struct PrePrice<PriceValue, BlockNumber, AccountId> {
-		/// The price of an asset, normalized to 12 decimals.
+		/// The price of an asset, normalized to the decimals of the normalized asset.
 		pub price: PriceValue,
+		/// Half width of the confidence band around `price`.
+		pub confidence: PriceValue,
 		/// The block the price was submitted at.
 		pub block: BlockNumber,
 		/// The account that submitted the price.
@@ -219,12 +244,58 @@ pub mod pallet {
 		pub reward_weight: Balance,
 		pub slash: Balance,
 		pub emit_price_changes: bool,
+		/// Answers deviating more than this from the aggregated price are slashed.
+		pub max_deviation: Percent,
+		/// Consecutive rounds a signer may miss before being slashed, `0` to never slash.
+		pub max_missed_rounds: u32,
+	}
+
+	/// Misbehaviour of a signer which is slashed.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
+	pub enum Offence {
+		/// Answer deviating more than `max_deviation` from the aggregated price.
+		Deviation,
+		/// More than `max_missed_rounds` consecutive rounds without an answer.
+		MissedRounds,
+	}
+
+	/// Offences of a signer since it was last forgiven.
+	#[derive(Encode, Decode, MaxEncodedLen, Default, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub struct OffenceRecord<BlockNumber> {
+		/// Step of the slash ladder of the next offence.
+		pub count: u32,
+		pub last_offence: BlockNumber,
+	}
+
+	/// How the price of a derived asset is computed from the prices of other assets.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Debug, PartialEq, Eq, TypeInfo)]
+	pub enum PriceDerivation<AssetId, PoolId> {
+		/// Price of one unit of `base` in units of `quote`, normalized to the decimals of the
+		/// normalized asset.
+		CrossRate { base: AssetId, quote: AssetId },
+		/// Price of the stake one unit of the share asset is redeemable for.
+		StakingShare,
+		/// Price of the assets backing one unit of the LP token of `pool_id`, were the pool at
+		/// equilibrium with the prices of these assets.
+		LpToken { pool_id: PoolId },
 	}
 
+	/// Maximum number of derived assets a derived price may be computed through.
+	pub const MAX_DERIVATION_DEPTH: u8 = 4;
+
 	type BalanceOf<T> = <T as Config>::Balance;
+	pub type PoolIdOf<T> = <<T as Config>::LiquidityPools as LiquidityPools<
+		<T as Config>::AssetId,
+		<T as Config>::PriceValue,
+	>>::PoolId;
+	pub type PriceDerivationOf<T> = PriceDerivation<<T as Config>::AssetId, PoolIdOf<T>>;
+
+	/// The current storage version, see [`crate::migration`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::storage]
@@ -235,6 +306,12 @@ pub mod pallet {
 	pub type AssetsCount<T: Config> =
 		StorageValue<_, u32, ValueQuery, Nonce<ZeroInit, SafeIncrement>>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn signer_count)]
+	#[allow(clippy::disallowed_types)] // Default signer count of 0 is valid in this context
+	/// Total amount of signers set
+	pub type SignerCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn reward_tracker_store)]
 	#[allow(clippy::disallowed_types)]
@@ -321,6 +398,37 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn offence_record)]
+	/// Offences of signers, which escalate their slashes.
+	pub type OffenceRecords<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, OffenceRecord<T::BlockNumber>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn missed_rounds)]
+	/// Consecutive rounds of an asset a signer did not answer.
+	pub type MissedRounds<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AssetId,
+		u32,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn signer_since)]
+	/// Block a signer was set at.
+	pub type SignerSince<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn derived_price)]
+	/// Derivation of the price of assets which are not fed by oracles, computed on read.
+	pub type DerivedPrices<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, PriceDerivationOf<T>, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -347,6 +455,18 @@ pub mod pallet {
 		AnswerPruned(T::AccountId, T::PriceValue),
 		/// Price changed by oracle \[asset_id, price\]
 		PriceChanged(T::AssetId, T::PriceValue),
+		/// Answer from oracle rejected as its confidence band does not overlap the median.
+		/// \[oracle_address, asset_id, price\]
+		AnswerRejected(T::AccountId, T::AssetId, T::PriceValue),
+		/// Derivation of the price of an asset set. \[asset_id, derivation\]
+		DerivedAssetChanged(T::AssetId, PriceDerivationOf<T>),
+		/// Derivation of the price of an asset removed. \[asset_id\]
+		DerivedAssetRemoved(T::AssetId),
+		/// Slashing policy of an asset changed. \[asset_id, max_deviation, max_missed_rounds\]
+		SlashingPolicyChanged(T::AssetId, Percent, u32),
+		/// Offence committed by a signer, slashed with `UserSlashed` unless in its grace period.
+		/// \[oracle_address, asset_id, offence, offences_count\]
+		OffenceCommitted(T::AccountId, T::AssetId, Offence, u32),
 	}
 
 	#[pallet::error]
@@ -393,6 +513,8 @@ pub mod pallet {
 		ExceedThreshold,
 		/// Asset count exceeded
 		ExceedAssetsCount,
+		/// Signer count exceeded
+		ExceedSignersCount,
 		/// Price not found
 		PriceNotFound,
 		/// Stake exceeded
@@ -412,6 +534,18 @@ pub mod pallet {
 		NoRewardTrackerSet,
 		/// Annual rewarding cost too high
 		AnnualRewardLessThanAlreadyRewarded,
+		/// Derived price computed through too many derived assets
+		DerivationTooDeep,
+		/// Derivation refers to the derived asset itself
+		InvalidDerivation,
+		/// Asset price is not derived
+		AssetNotDerived,
+		/// Asset price is derived, not fed by oracles
+		DerivedAsset,
+		/// Asset price is fed by oracles, not derived
+		OracleAsset,
+		/// Answers rewarded by the threshold would be slashed
+		DeviationBelowThreshold,
 	}
 
 	#[pallet::hooks]
@@ -439,12 +573,7 @@ pub mod pallet {
 			asset_id: Self::AssetId,
 			amount: Self::Balance,
 		) -> Result<Price<Self::Balance, Self::Timestamp>, DispatchError> {
-			let Price { price, block } =
-				Prices::<T>::try_get(asset_id).map_err(|_| Error::<T>::PriceNotFound)?;
-			// dbg!(&price);
-			let price = Self::quote(asset_id, price, amount)?;
-			// dbg!(&price);
-			Ok(Price { price, block })
+			Self::price_of(asset_id, amount, 0)
 		}
 
 		/// Currently using a flat distribution of weights.
@@ -454,7 +583,8 @@ pub mod pallet {
 		) -> Result<Self::Balance, DispatchError> {
 			let prices_length = Self::price_history(asset_id).len();
 			let twap_window: usize = <Self as Oracle>::TwapWindow::get().into();
-			if twap_window > prices_length + 1 {
+			// derived prices have no history
+			if twap_window > prices_length + 1 || DerivedPrices::<T>::contains_key(asset_id) {
 				Self::get_price(asset_id, amount).map(|p| p.price)
 			} else {
 				let price = Self::get_twap(asset_id, twap_window)?;
@@ -475,6 +605,20 @@ pub mod pallet {
 			Ok(base.safe_div(&quote)?)
 		}
 
+		fn get_precise_ratio(
+			pair: composable_traits::defi::CurrencyPair<Self::AssetId>,
+		) -> Result<sp_runtime::FixedU128, DispatchError> {
+			// ratio of the prices of the smallest units, which are `price / unit`
+			let base: u128 = Self::current_price(pair.base)?.into();
+			let quote: u128 = Self::current_price(pair.quote)?.into();
+			let base =
+				base.checked_mul(Self::unit(pair.quote)?).ok_or(ArithmeticError::Overflow)?;
+			let quote =
+				quote.checked_mul(Self::unit(pair.base)?).ok_or(ArithmeticError::Overflow)?;
+			ensure!(!quote.is_zero(), ArithmeticError::DivisionByZero);
+			Ok(FixedU128::checked_from_rational(base, quote).ok_or(ArithmeticError::Overflow)?)
+		}
+
 		fn get_price_inverse(
 			asset_id: Self::AssetId,
 			amount: Self::Balance,
@@ -492,12 +636,11 @@ pub mod pallet {
 			// 10^12 * (10^3 / 4^12) = 2_500
 			//
 			// 2_500 ACENTS are needed to pay for 10 default quote asset.
-			let unit = T::LocalAssets::unit(asset_id)?;
-			let asset_price_per_unit: u128 = Self::get_price(asset_id, unit)?.price.into();
+			let asset_price_per_unit: u128 = Self::current_price(asset_id)?.into();
 
 			let amount: u128 = amount.into();
-			let result = safe_multiply_by_rational(amount, unit.into(), asset_price_per_unit)?;
-			let result: u64 = result.try_into().map_err(|_| ArithmeticError::Overflow)?;
+			let result =
+				safe_multiply_by_rational(amount, Self::unit(asset_id)?, asset_price_per_unit)?;
 
 			Ok(result.into())
 		}
@@ -532,12 +675,14 @@ pub mod pallet {
 			T::AddOracle::ensure_origin(origin)?;
 
 			ensure!(*max_answers >= *min_answers, Error::<T>::MaxAnswersLessThanMinAnswers);
+			ensure!(!DerivedPrices::<T>::contains_key(asset_id), Error::<T>::DerivedAsset);
 
 			ensure!(
 				AssetsCount::<T>::get() < T::MaxAssetsCount::get(),
 				Error::<T>::ExceedAssetsCount
 			);
 
+			let current_asset_info = Self::asset_info(asset_id);
 			let asset_info = AssetInfo {
 				threshold: *threshold,
 				min_answers: *min_answers,
@@ -546,10 +691,18 @@ pub mod pallet {
 				reward_weight,
 				slash,
 				emit_price_changes,
+				// keeps the slashing policy, without slashing rewarded answers
+				max_deviation: current_asset_info
+					.as_ref()
+					.map_or(Percent::zero(), |info| info.max_deviation)
+					.max(threshold.left_from_one()),
+				max_missed_rounds: current_asset_info
+					.as_ref()
+					.map_or(0, |info| info.max_missed_rounds),
 			};
 			// track reward total weight for all assets
 			let mut reward_tracker = RewardTrackerStore::<T>::get().unwrap_or_default();
-			if let Some(current_asset_info) = Self::asset_info(asset_id) {
+			if let Some(current_asset_info) = current_asset_info {
 				reward_tracker.total_reward_weight = reward_tracker.total_reward_weight +
 					reward_weight - current_asset_info
 					.reward_weight;
@@ -588,11 +741,14 @@ pub mod pallet {
 
 			ensure!(current_controller.is_none(), Error::<T>::ControllerUsed);
 			ensure!(current_signer.is_none(), Error::<T>::SignerUsed);
+			ensure!(Self::signer_count() < T::MaxSigners::get(), Error::<T>::ExceedSignersCount);
 
 			Self::do_add_stake(who.clone(), signer.clone(), T::MinStake::get())?;
 
 			ControllerToSigner::<T>::insert(&who, signer.clone());
 			SignerToController::<T>::insert(signer.clone(), who.clone());
+			SignerSince::<T>::insert(&signer, frame_system::Pallet::<T>::block_number());
+			SignerCount::<T>::mutate(|count| *count = count.saturating_add(1));
 
 			Self::deposit_event(Event::SignerSet(signer, who));
 			Ok(().into())
@@ -690,6 +846,10 @@ pub mod pallet {
 
 			ControllerToSigner::<T>::remove(&who);
 			SignerToController::<T>::remove(&signer);
+			SignerSince::<T>::remove(&signer);
+			SignerCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			#[allow(deprecated)]
+			MissedRounds::<T>::remove_prefix(&signer, None);
 
 			Self::deposit_event(Event::StakeReclaimed(signer, withdrawal.stake));
 			Ok(().into())
@@ -699,7 +859,9 @@ pub mod pallet {
 		///
 		/// This is an operational transaction.
 		///
-		/// - `price`: price to submit, normalized to 12 decimals
+		/// - `price`: price to submit, normalized to the decimals of the normalized asset
+		/// - `confidence`: half width of the confidence band around `price`, the answer is rejected
+		///   if the band does not overlap the median of all answers
 		/// - `asset_id`: id for the asset
 		///
 		/// Emits `PriceSubmitted` event when successful.
@@ -707,6 +869,7 @@ pub mod pallet {
 		pub fn submit_price(
 			origin: OriginFor<T>,
 			price: T::PriceValue,
+			confidence: T::PriceValue,
 			asset_id: T::AssetId,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
@@ -734,6 +897,7 @@ pub mod pallet {
 				current_prices
 					.try_push(PrePrice {
 						price,
+						confidence,
 						block: frame_system::Pallet::<T>::block_number(),
 						who: who.clone(),
 					})
@@ -750,6 +914,86 @@ pub mod pallet {
 			Self::deposit_event(Event::PriceSubmitted(who, asset_id, price));
 			Ok(Pays::No.into())
 		}
+
+		/// Permissioned call to derive the price of an asset from the prices of other assets,
+		/// instead of having it fed by oracles. The price is computed on read.
+		///
+		/// - `asset_id`: Id for the derived asset
+		/// - `derivation`: How the price is computed
+		///
+		/// Emits `DerivedAssetChanged` event when successful.
+		#[pallet::weight(T::WeightInfo::add_derived_asset())]
+		pub fn add_derived_asset(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			derivation: PriceDerivationOf<T>,
+		) -> DispatchResultWithPostInfo {
+			T::AddOracle::ensure_origin(origin)?;
+			ensure!(!AssetsInfo::<T>::contains_key(asset_id), Error::<T>::OracleAsset);
+			if let PriceDerivation::CrossRate { base, quote } = &derivation {
+				ensure!(
+					*base != asset_id && *quote != asset_id && base != quote,
+					Error::<T>::InvalidDerivation
+				);
+			}
+
+			DerivedPrices::<T>::insert(asset_id, derivation.clone());
+			Self::deposit_event(Event::DerivedAssetChanged(asset_id, derivation));
+			Ok(().into())
+		}
+
+		/// Permissioned call to remove the derivation of the price of an asset.
+		///
+		/// - `asset_id`: Id for the derived asset
+		///
+		/// Emits `DerivedAssetRemoved` event when successful.
+		#[pallet::weight(T::WeightInfo::remove_derived_asset())]
+		pub fn remove_derived_asset(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+		) -> DispatchResultWithPostInfo {
+			T::AddOracle::ensure_origin(origin)?;
+			DerivedPrices::<T>::take(asset_id).ok_or(Error::<T>::AssetNotDerived)?;
+
+			Self::deposit_event(Event::DerivedAssetRemoved(asset_id));
+			Ok(().into())
+		}
+
+		/// Permissioned call to set when signers answering an asset are slashed.
+		///
+		/// - `asset_id`: Id for the asset
+		/// - `max_deviation`: answers deviating more than this from the aggregated price are
+		///   slashed, at least the deviation rewarded by the `threshold` of the asset
+		/// - `max_missed_rounds`: consecutive rounds a signer may miss before being slashed, `0` to
+		///   never slash
+		///
+		/// Emits `SlashingPolicyChanged` event when successful.
+		#[pallet::weight(T::WeightInfo::set_slashing_policy())]
+		pub fn set_slashing_policy(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			max_deviation: Percent,
+			max_missed_rounds: u32,
+		) -> DispatchResultWithPostInfo {
+			T::AddOracle::ensure_origin(origin)?;
+			AssetsInfo::<T>::try_mutate(asset_id, |asset_info| -> DispatchResult {
+				let asset_info = asset_info.as_mut().ok_or(Error::<T>::InvalidAssetId)?;
+				ensure!(
+					max_deviation >= asset_info.threshold.left_from_one(),
+					Error::<T>::DeviationBelowThreshold
+				);
+				asset_info.max_deviation = max_deviation;
+				asset_info.max_missed_rounds = max_missed_rounds;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::SlashingPolicyChanged(
+				asset_id,
+				max_deviation,
+				max_missed_rounds,
+			));
+			Ok(().into())
+		}
 	}
 
 	/// Payload used by this example crate to hold price
@@ -784,56 +1028,37 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Reward the answers `pre_prices` close enough to `price` and slash those deviating too
+		/// much from it. Answers `rejected` for their confidence band are never rewarded, but are
+		/// slashed all the same.
 		pub fn handle_payout(
 			pre_prices: &[PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>],
+			rejected: &[PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>],
 			price: T::PriceValue,
 			asset_id: T::AssetId,
 			asset_info: &AssetInfo<Percent, T::BlockNumber, BalanceOf<T>>,
 		) -> DispatchResult {
+			for answer in rejected {
+				if Self::accuracy(answer.price, price) < asset_info.max_deviation.left_from_one() {
+					Self::slash(&answer.who, asset_id, asset_info.slash, Offence::Deviation);
+				}
+				Self::remove_price_in_transit(&answer.who, asset_info)
+			}
 			let mut rewarded_oracles = BTreeSet::new();
 			for answer in pre_prices {
-				// TODO vim: duplicated code could be refactored to do these accuracy calculations
-				// once
-				let accuracy: Percent = if answer.price < price {
-					PerThing::from_rational(answer.price, price)
-				} else {
-					let adjusted_number = price.saturating_sub(answer.price - price);
-					PerThing::from_rational(adjusted_number, price)
-				};
-				let min_accuracy = asset_info.threshold;
-				if accuracy < min_accuracy {
-					let slash_amount = asset_info.slash;
-					let new_amount_staked = Self::oracle_stake(answer.who.clone())
-						.unwrap_or_else(|| 0_u32.into())
-						.saturating_sub(slash_amount);
-					OracleStake::<T>::insert(&answer.who, new_amount_staked);
-					let result = T::Currency::repatriate_reserved(
-						&answer.who,
-						&T::TreasuryAccount::get(),
-						slash_amount,
-						BalanceStatus::Free,
-					);
-					match result {
-						Ok(remaining_val) =>
-							if remaining_val > BalanceOf::<T>::zero() {
-								log::warn!("Only slashed {:?}", slash_amount - remaining_val);
-							},
-						Err(e) => {
-							log::warn!("Failed to slash {:?} due to {:?}", answer.who, e);
-						},
-					}
-					Self::deposit_event(Event::UserSlashed(
-						answer.who.clone(),
-						asset_id,
-						slash_amount,
-					));
-				} else {
+				let accuracy = Self::accuracy(answer.price, price);
+				if accuracy < asset_info.max_deviation.left_from_one() {
+					Self::slash(&answer.who, asset_id, asset_info.slash, Offence::Deviation);
+				} else if accuracy >= asset_info.threshold {
 					let controller = SignerToController::<T>::get(&answer.who)
 						.unwrap_or_else(|| answer.who.clone());
 					rewarded_oracles.insert((answer.who.clone(), controller.clone()));
 				}
 				Self::remove_price_in_transit(&answer.who, asset_info)
 			}
+			if rewarded_oracles.is_empty() {
+				return Ok(())
+			}
 			if let Some(mut reward_tracker) = Self::get_reward_tracker_if_enabled() {
 				// divide the per asset reward(by weight) by the number of oracles
 				let reward_amount_per_oracle: T::Balance = safe_multiply_by_rational(
@@ -865,6 +1090,106 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// How close `answer` is to `price`.
+		fn accuracy(answer: T::PriceValue, price: T::PriceValue) -> Percent {
+			if answer < price {
+				PerThing::from_rational(answer, price)
+			} else {
+				let adjusted_number = price.saturating_sub(answer - price);
+				PerThing::from_rational(adjusted_number, price)
+			}
+		}
+
+		/// Slash `who` for an `offence`, by the `slash` of the asset or the current step of the
+		/// slash ladder of the signer, whichever is larger.
+		pub fn slash(
+			who: &T::AccountId,
+			asset_id: T::AssetId,
+			slash: BalanceOf<T>,
+			offence: Offence,
+		) {
+			let now = frame_system::Pallet::<T>::block_number();
+			let mut record = Self::offence_record(who).unwrap_or_default();
+			if record.count > 0 &&
+				now >= record.last_offence.saturating_add(T::SlashGracePeriod::get())
+			{
+				record = OffenceRecord::default();
+			}
+			let stake = Self::oracle_stake(who).unwrap_or_else(Zero::zero);
+			let ladder = T::SlashLadder::get();
+			let step = ladder
+				.get(record.count as usize)
+				.or_else(|| ladder.last())
+				.copied()
+				.unwrap_or_else(Percent::zero);
+			let slash_amount = slash.max(step.mul_floor(stake));
+			record.count = record.count.saturating_add(1);
+			record.last_offence = now;
+			OffenceRecords::<T>::insert(who, record.clone());
+			Self::deposit_event(Event::OffenceCommitted(
+				who.clone(),
+				asset_id,
+				offence,
+				record.count,
+			));
+
+			OracleStake::<T>::insert(who, stake.saturating_sub(slash_amount));
+			let result = T::Currency::repatriate_reserved(
+				who,
+				&T::TreasuryAccount::get(),
+				slash_amount,
+				BalanceStatus::Free,
+			);
+			match result {
+				Ok(remaining_val) =>
+					if remaining_val > BalanceOf::<T>::zero() {
+						log::warn!("Only slashed {:?}", slash_amount - remaining_val);
+					},
+				Err(e) => {
+					log::warn!("Failed to slash {:?} due to {:?}", who, e);
+				},
+			}
+			Self::deposit_event(Event::UserSlashed(who.clone(), asset_id, slash_amount));
+		}
+
+		/// Count the round of `asset_id` as missed for the signers which did not answer it, and
+		/// slash those which missed more than `max_missed_rounds` in a row. Iterates all signers,
+		/// at most `MaxSigners`.
+		///
+		/// A round which got `max_answers` answers is not counted, the signers which did not
+		/// answer it could not have, their answers were rejected with `MaxPrices`.
+		pub fn track_missed_rounds(
+			asset_id: T::AssetId,
+			asset_info: &AssetInfo<Percent, T::BlockNumber, BalanceOf<T>>,
+			answered: BTreeSet<&T::AccountId>,
+		) {
+			if asset_info.max_missed_rounds == 0 || answered.len() as u32 >= asset_info.max_answers
+			{
+				return
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			for signer in SignerToController::<T>::iter_keys() {
+				if answered.contains(&signer) {
+					MissedRounds::<T>::remove(&signer, asset_id);
+					continue
+				}
+				let in_grace_period = Self::signer_since(&signer)
+					.map_or(false, |since| now < since.saturating_add(T::SlashGracePeriod::get()));
+				if in_grace_period ||
+					Self::oracle_stake(&signer).map_or(true, |stake| stake < T::MinStake::get())
+				{
+					continue
+				}
+				let missed_rounds = Self::missed_rounds(&signer, asset_id).saturating_add(1);
+				if missed_rounds > asset_info.max_missed_rounds {
+					MissedRounds::<T>::remove(&signer, asset_id);
+					Self::slash(&signer, asset_id, asset_info.slash, Offence::MissedRounds);
+				} else {
+					MissedRounds::<T>::insert(&signer, asset_id, missed_rounds);
+				}
+			}
+		}
+
 		fn get_reward_tracker_if_enabled(
 		) -> Option<RewardTracker<<T as Config>::Balance, <T as Config>::Moment>> {
 			RewardTrackerStore::<T>::get().and_then(|r| {
@@ -917,6 +1242,11 @@ pub mod pallet {
 					// because we control the length of items of `PriceHistory`.
 					let _ = Self::update_price(asset_id, asset_info.clone(), block, pre_prices);
 					total_weight += T::WeightInfo::update_price(pre_prices_len as u32);
+					if pre_prices_len as u32 >= asset_info.min_answers &&
+						asset_info.max_missed_rounds > 0
+					{
+						total_weight += T::WeightInfo::track_missed_rounds(Self::signer_count());
+					}
 				};
 			}
 			total_weight
@@ -968,12 +1298,29 @@ pub mod pallet {
 			// because pre_prices.len() limited by u32
 			// (type of AssetsInfo::<T>::get(asset_id).max_answers).
 			if pre_prices.len() as u32 >= asset_info.min_answers {
+				let last_price = match pre_prices.last() {
+					Some(pre_price) => pre_price.price,
+					_ => Zero::zero(),
+				};
+				let (pre_prices, rejected) = Self::partition_by_confidence(pre_prices);
+				if (pre_prices.len() as u32) < asset_info.min_answers {
+					// too few answers agree on a price, so the round is dropped
+					PrePrices::<T>::remove(asset_id);
+					for answer in pre_prices {
+						Self::remove_price_in_transit(&answer.who, &asset_info);
+						Self::deposit_event(Event::AnswerPruned(answer.who, answer.price));
+					}
+					for answer in rejected {
+						Self::remove_price_in_transit(&answer.who, &asset_info);
+						Self::deposit_event(Event::AnswerRejected(
+							answer.who,
+							asset_id,
+							answer.price,
+						));
+					}
+					return Ok(())
+				}
 				if let Some(price) = Self::calculate_price(&pre_prices, &asset_info) {
-					let last_price = match pre_prices.last() {
-						Some(pre_price) => pre_price.price,
-						_ => Zero::zero(),
-					};
-
 					Prices::<T>::insert(asset_id, Price { price, block });
 					PriceHistory::<T>::try_mutate(asset_id, |prices| -> DispatchResult {
 						if prices.len() as u32 >= T::MaxHistory::get() {
@@ -987,8 +1334,20 @@ pub mod pallet {
 						Ok(())
 					})?;
 					PrePrices::<T>::remove(asset_id);
+					Self::track_missed_rounds(
+						asset_id,
+						&asset_info,
+						pre_prices.iter().chain(&rejected).map(|answer| &answer.who).collect(),
+					);
 
-					Self::handle_payout(&pre_prices, price, asset_id, &asset_info)?;
+					for answer in &rejected {
+						Self::deposit_event(Event::AnswerRejected(
+							answer.who.clone(),
+							asset_id,
+							answer.price,
+						));
+					}
+					Self::handle_payout(&pre_prices, &rejected, price, asset_id, &asset_info)?;
 
 					// Emit `PriceChanged` event when prices have changed, if required.
 					if price != last_price && asset_info.emit_price_changes {
@@ -1059,6 +1418,24 @@ pub mod pallet {
 			}
 		}
 
+		/// Split answers into those whose confidence band overlaps the median of all answers and
+		/// those which do not.
+		#[allow(clippy::type_complexity)]
+		pub fn partition_by_confidence(
+			prices: Vec<PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>>,
+		) -> (
+			Vec<PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>>,
+			Vec<PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>>,
+		) {
+			match Self::get_median_price(&prices) {
+				Some(median) => prices.into_iter().partition(|answer| {
+					answer.price.saturating_sub(answer.confidence) <= median &&
+						median <= answer.price.saturating_add(answer.confidence)
+				}),
+				None => (prices, Vec::new()),
+			}
+		}
+
 		pub fn calculate_price(
 			prices: &[PrePrice<T::PriceValue, T::BlockNumber, T::AccountId>],
 			asset_info: &AssetInfo<Percent, T::BlockNumber, BalanceOf<T>>,
@@ -1067,12 +1444,7 @@ pub mod pallet {
 			let mut sum_of_price = T::PriceValue::zero();
 			let mut number_of_prices = 0_u32;
 			for answer in prices {
-				let accuracy: Percent = if answer.price < median_price {
-					PerThing::from_rational(answer.price, median_price)
-				} else {
-					let adjusted_number = median_price.saturating_sub(answer.price - median_price);
-					PerThing::from_rational(adjusted_number, median_price)
-				};
+				let accuracy = Self::accuracy(answer.price, median_price);
 				let min_accuracy = asset_info.threshold;
 				// consider all prices which are with in threshold of median_price
 				if accuracy >= min_accuracy {
@@ -1158,7 +1530,7 @@ pub mod pallet {
 						None
 					} else {
 						// otherwise multiply price and weight.
-						Some(price * weight)
+						Some(price.saturating_mul(weight))
 					}
 				})
 				.sum();
@@ -1177,11 +1549,103 @@ pub mod pallet {
 			price: T::PriceValue,
 			amount: T::PriceValue,
 		) -> Result<T::PriceValue, DispatchError> {
-			let unit = <Self as Oracle>::LocalAssets::unit(asset_id)?;
-			let price = safe_multiply_by_rational(price.into(), amount.into(), unit)?;
+			let price =
+				safe_multiply_by_rational(price.into(), amount.into(), Self::unit(asset_id)?)?;
 			Ok(price.into())
 		}
 
+		/// Smallest units of `asset_id` in one unit, as given by its decimals in `LocalAssets`.
+		fn unit(asset_id: T::AssetId) -> Result<u128, DispatchError> {
+			let decimals = T::LocalAssets::decimals(asset_id)?;
+			Ok(10_u128.checked_pow(decimals.into()).ok_or(ArithmeticError::Overflow)?)
+		}
+
+		fn current_price(asset_id: T::AssetId) -> Result<T::PriceValue, DispatchError> {
+			Ok(Self::unit_price(asset_id, 0)?.price)
+		}
+
+		/// Price of `amount` of `asset_id`, at the block of its oldest component price.
+		fn price_of(
+			asset_id: T::AssetId,
+			amount: T::PriceValue,
+			depth: u8,
+		) -> Result<Price<T::PriceValue, T::BlockNumber>, DispatchError> {
+			let Price { price, block } = Self::unit_price(asset_id, depth)?;
+			Ok(Price { price: Self::quote(asset_id, price, amount)?, block })
+		}
+
+		/// Price of one unit of `asset_id`, stored or derived.
+		fn unit_price(
+			asset_id: T::AssetId,
+			depth: u8,
+		) -> Result<Price<T::PriceValue, T::BlockNumber>, DispatchError> {
+			let derivation = match DerivedPrices::<T>::get(asset_id) {
+				Some(derivation) => derivation,
+				None =>
+					return Ok(Prices::<T>::try_get(asset_id).map_err(|_| Error::<T>::PriceNotFound)?),
+			};
+			ensure!(depth < MAX_DERIVATION_DEPTH, Error::<T>::DerivationTooDeep);
+			let depth = depth + 1;
+			match derivation {
+				PriceDerivation::CrossRate { base, quote } => {
+					let base = Self::unit_price(base, depth)?;
+					let quote = Self::unit_price(quote, depth)?;
+					ensure!(!quote.price.is_zero(), ArithmeticError::DivisionByZero);
+					let price = safe_multiply_by_rational(
+						base.price.into(),
+						Self::unit(T::NormalizedAssetId::get())?,
+						quote.price.into(),
+					)?;
+					Ok(Price { price: price.into(), block: base.block.min(quote.block) })
+				},
+				PriceDerivation::StakingShare => {
+					let (staked_asset_id, staked) = T::StakingShares::staked_for_shares(
+						asset_id,
+						Self::unit(asset_id)?.into(),
+					)?;
+					Self::price_of(staked_asset_id, staked, depth)
+				},
+				PriceDerivation::LpToken { pool_id } => {
+					let mut prices = BTreeMap::new();
+					let mut unit_prices = BTreeMap::new();
+					for component in T::LiquidityPools::pool_assets(pool_id.clone())? {
+						let price = Self::unit_price(component, depth)?;
+						let unit_price: u128 = price.price.into();
+						prices.insert(
+							component,
+							FixedU128::checked_from_rational(unit_price, Self::unit(component)?)
+								.ok_or(ArithmeticError::Overflow)?,
+						);
+						unit_prices.insert(component, price);
+					}
+					// valued at the oracle prices of the components rather than the spot
+					// prices of the pool, which trading against it can move
+					let backing = T::LiquidityPools::fair_assets_for_lp_tokens(
+						pool_id,
+						Self::unit(asset_id)?.into(),
+						&prices,
+					)?;
+					let mut total: Option<Price<T::PriceValue, T::BlockNumber>> = None;
+					for (component, amount) in backing {
+						let Price { price, block } =
+							unit_prices.get(&component).ok_or(Error::<T>::PriceNotFound)?;
+						let price = Self::quote(component, *price, amount)?;
+						total = Some(match total {
+							Some(total) => Price {
+								price: total
+									.price
+									.checked_add(&price)
+									.ok_or(ArithmeticError::Overflow)?,
+								block: total.block.min(*block),
+							},
+							None => Price { price, block: *block },
+						});
+					}
+					Ok(total.ok_or(Error::<T>::PriceNotFound)?)
+				},
+			}
+		}
+
 		// REVIEW: indexing
 		pub fn fetch_price_and_send_signed(
 			price_id: &T::AssetId,
@@ -1216,7 +1680,10 @@ pub mod pallet {
 			}
 			// Make an external HTTP request to fetch the current price.
 			// Note this call will block until response is received.
-			let price = Self::fetch_price(price_id).map_err(|_| "Failed to fetch price")?;
+			let decimals = T::LocalAssets::decimals(T::NormalizedAssetId::get())
+				.map_err(|_| "No decimals for the normalized asset")?;
+			let price = Self::fetch_price(price_id, asset_info.threshold.left_from_one(), decimals)
+				.map_err(|_| "Failed to fetch price")?;
 			log::info!("price {:#?}", price);
 
 			// Using `send_signed_transaction` associated type we create and submit a transaction
@@ -1228,12 +1695,21 @@ pub mod pallet {
 				// Received price is wrapped into a call to `submit_price` public function of this
 				// pallet. This means that the transaction, when executed, will simply call that
 				// function passing `price` as an argument.
-				Call::submit_price { price: price.into(), asset_id: *price_id }
+				Call::submit_price {
+					price: price.price.into(),
+					confidence: price.confidence.into(),
+					asset_id: *price_id,
+				}
 			});
 
 			for (acc, res) in &results {
 				match res {
-					Ok(()) => log::info!("[{:?}] Submitted price of {} cents", acc.id, price),
+					Ok(()) => log::info!(
+						"[{:?}] Submitted price of {:?}, confidence of {:?}",
+						acc.id,
+						sources::as_decimal(price.price, decimals),
+						sources::as_decimal(price.confidence, decimals)
+					),
 					Err(e) => log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
 				}
 			}
@@ -1241,22 +1717,71 @@ pub mod pallet {
 			Ok(())
 		}
 
-		pub fn fetch_price(price_id: &T::AssetId) -> Result<u64, http::Error> {
+		/// Query all configured sources and aggregate their answers, weighted by source. Sources
+		/// which fail to answer are skipped.
+		///
+		/// - `min_confidence`: lower bound of the confidence band, relative to the price
+		/// - `decimals`: decimals of the normalized asset, to normalize the answers to
+		pub fn fetch_price(
+			price_id: &T::AssetId,
+			min_confidence: Percent,
+			decimals: Exponent,
+		) -> Result<SourcePrice, http::Error> {
 			// We want to keep the offchain worker execution time reasonable, so we set a hard-coded
-			// deadline to 2s to complete the external call.
+			// deadline to 2s to complete the external calls.
 			// You can also wait indefinitely for the response, however you may still get a timeout
 			// coming from the host machine.
 			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));
-
-			// Check if the node has another endpoint to call if not fall back to localhost:3001
-			// Then build the endpoint
-			let kind = sp_core::offchain::StorageKind::PERSISTENT;
-			let from_local = sp_io::offchain::local_storage_get(kind, b"ocw-url")
-				.unwrap_or_else(|| b"http://localhost:3001/price/".to_vec());
-			let base = str::from_utf8(&from_local).unwrap_or("http://localhost:3001/price/");
 			let string_id =
 				serde_json::to_string(&(*price_id).into()).map_err(|_| http::Error::IoError)?;
-			let url = base.to_owned() + &string_id;
+
+			let answers: Vec<(SourcePrice, u32)> = Self::price_sources()
+				.into_iter()
+				.filter_map(|source| {
+					match Self::fetch_source_price(&source, &string_id, deadline, decimals) {
+						Ok(price) => Some((price, source.weight)),
+						Err(e) => {
+							log::warn!("Failed to fetch price from {:?}: {:?}", source, e);
+							None
+						},
+					}
+				})
+				.collect();
+
+			let price = sources::aggregate(&answers, min_confidence).ok_or(http::Error::Unknown)?;
+
+			log::warn!(
+				"Got price: {:?}, confidence: {:?}",
+				sources::as_decimal(price.price, decimals),
+				sources::as_decimal(price.confidence, decimals)
+			);
+
+			Ok(price)
+		}
+
+		/// Sources set in the `ocw-sources` offchain storage. If there are none, the node queries
+		/// the price-feed service at `ocw-url`, or at localhost:3001 if that is not set either.
+		pub fn price_sources() -> Vec<PriceSource> {
+			let kind = sp_core::offchain::StorageKind::PERSISTENT;
+			if let Some(configured) = sp_io::offchain::local_storage_get(kind, sources::SOURCES_KEY)
+				.and_then(|encoded| Vec::<PriceSource>::decode(&mut &encoded[..]).ok())
+			{
+				return configured
+			}
+			let url = sp_io::offchain::local_storage_get(kind, sources::URL_KEY)
+				.filter(|url| str::from_utf8(url).is_ok())
+				.unwrap_or_else(|| sources::DEFAULT_URL.as_bytes().to_vec());
+			vec![PriceSource { kind: PriceSourceKind::PriceFeed, url, weight: 1 }]
+		}
+
+		fn fetch_source_price(
+			source: &PriceSource,
+			string_id: &str,
+			deadline: Timestamp,
+			decimals: Exponent,
+		) -> Result<SourcePrice, http::Error> {
+			let base = str::from_utf8(&source.url).map_err(|_| http::Error::Unknown)?;
+			let url = base.to_owned() + string_id;
 
 			// Initiate an external HTTP GET request.
 			let request = http::Request::get(&url);
@@ -1282,33 +1807,36 @@ pub mod pallet {
 				http::Error::Unknown
 			})?;
 
-			let price = match Self::parse_price(body_str, &string_id) {
-				Some(price) => Ok(price),
-				None => {
-					log::warn!("Unable to extract price from the response: {:?}", body_str);
-					Err(http::Error::Unknown)
-				},
-			}?;
-
-			log::warn!("Got price: {} cents", price);
-
-			Ok(price)
+			let price = match source.kind {
+				PriceSourceKind::PriceFeed | PriceSourceKind::PabloTwap =>
+					Self::parse_price(body_str, string_id, decimals)
+						.map(|price| SourcePrice { price, confidence: 0 }),
+				PriceSourceKind::Pyth => sources::parse_pyth_price(body_str, decimals),
+			};
+			price.ok_or_else(|| {
+				log::warn!("Unable to extract price from the response: {:?}", body_str);
+				http::Error::Unknown
+			})
 		}
 
-		pub fn parse_price(price_str: &str, asset_id: &str) -> Option<u64> {
+		/// Parse the price of `asset_id` from `{"<asset_id>": <price>}`. The price is either a
+		/// number, already normalized to `decimals`, or `{"price": <mantissa>, "expo":
+		/// <exponent>}` for one unit of the asset.
+		pub fn parse_price(price_str: &str, asset_id: &str, decimals: Exponent) -> Option<u128> {
 			let val = lite_json::parse_json(price_str);
-			let price = match val.ok()? {
+			match val.ok()? {
 				JsonValue::Object(obj) => {
 					let (_, v) =
 						obj.into_iter().find(|(k, _)| k.iter().copied().eq(asset_id.chars()))?;
 					match v {
-						JsonValue::Number(number) => number,
-						_ => return None,
+						JsonValue::Number(number) => sources::json_number(&number),
+						scaled @ JsonValue::Object(_) =>
+							sources::parse_scaled_price(&scaled, decimals),
+						_ => None,
 					}
 				},
-				_ => return None,
-			};
-			Some(price.integer as u64)
+				_ => None,
+			}
 		}
 	}
 }
@@ -0,0 +1,62 @@
+//! Storage migrations of the oracle pallet.
+
+use crate::*;
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use sp_runtime::{PerThing, Percent};
+use sp_std::marker::PhantomData;
+
+pub mod v1 {
+	use super::*;
+
+	/// [`AssetInfo`] before the slashing policy.
+	#[derive(Encode, Decode)]
+	struct OldAssetInfo<Percent, BlockNumber, Balance> {
+		threshold: Percent,
+		min_answers: u32,
+		max_answers: u32,
+		block_interval: BlockNumber,
+		reward_weight: Balance,
+		slash: Balance,
+		emit_price_changes: bool,
+	}
+
+	/// Adds the slashing policy to the info of existing assets. Answers are slashed when they are
+	/// not rewarded, as before, and missed rounds are not slashed until a policy is set. Also
+	/// counts the signers set into [`SignerCount`].
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut assets = 0_u64;
+			AssetsInfo::<T>::translate::<OldAssetInfo<Percent, T::BlockNumber, T::Balance>, _>(
+				|_, old| {
+					assets += 1;
+					Some(AssetInfo {
+						threshold: old.threshold,
+						min_answers: old.min_answers,
+						max_answers: old.max_answers,
+						block_interval: old.block_interval,
+						reward_weight: old.reward_weight,
+						slash: old.slash,
+						emit_price_changes: old.emit_price_changes,
+						max_deviation: old.threshold.left_from_one(),
+						max_missed_rounds: 0,
+					})
+				},
+			);
+
+			let signers = SignerToController::<T>::iter_keys().count() as u32;
+			SignerCount::<T>::put(signers);
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(
+				assets.saturating_add(u64::from(signers)).saturating_add(1),
+				assets.saturating_add(2),
+			)
+		}
+	}
+}
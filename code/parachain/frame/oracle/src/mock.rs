@@ -1,8 +1,10 @@
 use crate as pallet_oracle;
 use crate::*;
 
+use composable_support::math::safe::safe_multiply_by_rational;
+use composable_traits::oracle::{LiquidityPools, StakingShares};
 use frame_support::{
-	ord_parameter_types,
+	ensure, ord_parameter_types,
 	pallet_prelude::ConstU32,
 	parameter_types,
 	traits::{EnsureOneOf, Everything},
@@ -13,8 +15,13 @@ use frame_system::EnsureSignedBy;
 use sp_core::{sr25519, sr25519::Signature, H256};
 use sp_runtime::{
 	testing::{Header, TestXt},
-	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+	traits::{
+		BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, IntegerSquareRoot,
+		Verify,
+	},
+	DispatchError, FixedPointNumber, FixedU128, Percent,
 };
+use sp_std::collections::btree_map::BTreeMap;
 use system::EnsureRoot;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -107,6 +114,7 @@ parameter_types! {
 	pub const StalePrice: u64 = 2;
 	pub const MaxAnswerBound: u32 = 5;
 	pub const MaxAssetsCount: u32 = 2;
+	pub const MaxSigners: u32 = 4;
 	pub const MaxHistory: u32 = 3;
 	pub const MaxPrePrices: u32 = 12;
 	pub const TwapWindow: u16 = 3;
@@ -154,6 +162,65 @@ parameter_types! {
 	// cspell:disable-next
 	pub const OraclePalletId: PalletId = PalletId(*b"plt_orac");
 	pub const MsPerBlock: u64 = MILLISECS_PER_BLOCK;
+	pub SlashLadder: Vec<Percent> =
+		vec![Percent::from_percent(0), Percent::from_percent(10), Percent::from_percent(50)];
+	pub const SlashGracePeriod: u64 = 10;
+	pub const NormalizedAssetId: AssetId = 0xDEADC0DE;
+}
+
+pub const LP_POOL_ID: u128 = 1;
+pub const SHARE_ASSET_ID: AssetId = 10;
+
+/// Unit of the LP token of `LP_POOL_ID`.
+pub const LP_UNIT: u128 = 1_000_000_000_000;
+
+parameter_types! {
+	/// Balances of assets `0` and `1` of the pool `LP_POOL_ID` per unit of its LP token.
+	pub static LpPoolBalances: (u128, u128) = (2 * LP_UNIT, 3 * LP_UNIT);
+}
+
+/// Pool `LP_POOL_ID` is a 50/50 constant product pool of assets `0` and `1`, holding
+/// `LpPoolBalances`.
+pub struct MockPools;
+impl LiquidityPools<AssetId, PriceValue> for MockPools {
+	type PoolId = u128;
+
+	fn pool_assets(pool_id: Self::PoolId) -> Result<Vec<AssetId>, DispatchError> {
+		ensure!(pool_id == LP_POOL_ID, DispatchError::Other("pool not found"));
+		Ok(vec![0, 1])
+	}
+
+	fn fair_assets_for_lp_tokens(
+		pool_id: Self::PoolId,
+		lp_amount: PriceValue,
+		prices: &BTreeMap<AssetId, FixedU128>,
+	) -> Result<BTreeMap<AssetId, PriceValue>, DispatchError> {
+		ensure!(pool_id == LP_POOL_ID, DispatchError::Other("pool not found"));
+		let (b_0, b_1) = LpPoolBalances::get();
+		let price_of =
+			|asset_id| prices.get(&asset_id).copied().ok_or(DispatchError::Other("no price"));
+		let (p_0, p_1) = (price_of(0)?, price_of(1)?);
+		// each asset makes up half of the value `2 * sqrt(v_0 * v_1)` of the pool at equilibrium
+		let half_value = (p_0.saturating_mul_int(b_0) * p_1.saturating_mul_int(b_1)).integer_sqrt();
+		let balance_of = |price: FixedU128| -> Result<PriceValue, DispatchError> {
+			let balance =
+				safe_multiply_by_rational(half_value, FixedU128::DIV, price.into_inner())?;
+			Ok(safe_multiply_by_rational(balance, lp_amount, LP_UNIT)?)
+		};
+		Ok(BTreeMap::from([(0, balance_of(p_0)?), (1, balance_of(p_1)?)]))
+	}
+}
+
+/// Shares of `SHARE_ASSET_ID` are each redeemable for 1.5 of asset `0`.
+pub struct MockStaking;
+impl StakingShares<AssetId, PriceValue> for MockStaking {
+	fn staked_for_shares(
+		share_asset_id: AssetId,
+		amount: PriceValue,
+	) -> Result<(AssetId, PriceValue), DispatchError> {
+		ensure!(share_asset_id == SHARE_ASSET_ID, DispatchError::Other("pool not found"));
+		Ok((0, amount * 3 / 2))
+	}
 }
 
 impl pallet_oracle::Config for Test {
@@ -169,11 +236,17 @@ impl pallet_oracle::Config for Test {
 		EnsureOneOf<EnsureSignedBy<RootAccount, sp_core::sr25519::Public>, EnsureRoot<AccountId>>;
 	type MaxAnswerBound = MaxAnswerBound;
 	type MaxAssetsCount = MaxAssetsCount;
+	type MaxSigners = MaxSigners;
 	type MaxHistory = MaxHistory;
 	type MaxPrePrices = MaxPrePrices;
 	type WeightInfo = ();
 	type LocalAssets = ();
+	type NormalizedAssetId = NormalizedAssetId;
+	type LiquidityPools = MockPools;
+	type StakingShares = MockStaking;
 	type TreasuryAccount = TreasuryAccountId;
+	type SlashLadder = SlashLadder;
+	type SlashGracePeriod = SlashGracePeriod;
 	type Moment = Moment;
 	type Time = Timestamp;
 	type TwapWindow = TwapWindow;
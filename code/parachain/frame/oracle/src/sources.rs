@@ -0,0 +1,156 @@
+//! Price sources queried by the offchain worker and aggregation of their answers.
+use codec::{Decode, Encode};
+use composable_traits::currency::Exponent;
+use lite_json::json::{JsonValue, NumberValue};
+use scale_info::TypeInfo;
+use sp_runtime::{FixedPointNumber, FixedU128, PerThing, Percent, RuntimeDebug};
+use sp_std::vec::Vec;
+
+/// Offchain local storage key of the SCALE encoded `Vec<PriceSource>` queried by the node.
+pub const SOURCES_KEY: &[u8] = b"ocw-sources";
+/// Offchain local storage key of the single price-feed endpoint queried when no sources are set.
+pub const URL_KEY: &[u8] = b"ocw-url";
+/// Endpoint of the price-feed service queried when neither `ocw-sources` nor `ocw-url` are set.
+pub const DEFAULT_URL: &str = "http://localhost:3001/price/";
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PriceSourceKind {
+	/// The `price-feed` service, answering `{"<asset_id>": <price>}`.
+	PriceFeed,
+	/// A Pyth compatible endpoint, answering
+	/// `{"price": {"price": "<mantissa>", "conf": "<mantissa>", "expo": <exponent>}}`.
+	Pyth,
+	/// A Pablo TWAP endpoint, answering like the `price-feed` service.
+	PabloTwap,
+}
+
+/// An endpoint queried for prices. The asset id is appended to `url`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PriceSource {
+	pub kind: PriceSourceKind,
+	pub url: Vec<u8>,
+	/// Weight of the answers of this source in the aggregated price.
+	pub weight: u32,
+}
+
+/// A price, normalized to the decimals of the normalized asset, with the half width of its
+/// confidence band.
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SourcePrice {
+	pub price: u128,
+	pub confidence: u128,
+}
+
+/// Parse the answer of a Pyth compatible endpoint, normalized to `decimals`.
+pub fn parse_pyth_price(price_str: &str, decimals: Exponent) -> Option<SourcePrice> {
+	let answer = match lite_json::parse_json(price_str).ok()? {
+		JsonValue::Object(answer) => answer,
+		_ => return None,
+	};
+	let fields = match field(&answer, "price")? {
+		JsonValue::Object(fields) => fields,
+		_ => return None,
+	};
+	let expo = exponent(field(fields, "expo")?)?;
+	let decimals = i32::from(decimals);
+	Some(SourcePrice {
+		price: scale(mantissa(field(fields, "price")?)?, expo, decimals)?,
+		confidence: scale(mantissa(field(fields, "conf")?)?, expo, decimals)?,
+	})
+}
+
+/// Price of one unit of an asset given as `{"price": <mantissa>, "expo": <exponent>}`, normalized
+/// to `decimals`.
+pub fn parse_scaled_price(value: &JsonValue, decimals: Exponent) -> Option<u128> {
+	match value {
+		JsonValue::Object(fields) => scale(
+			mantissa(field(fields, "price")?)?,
+			exponent(field(fields, "expo")?)?,
+			decimals.into(),
+		),
+		_ => None,
+	}
+}
+
+/// Value of a non negative JSON number, fraction and exponent included, rounded down.
+pub fn json_number(number: &NumberValue) -> Option<u128> {
+	if number.negative {
+		return None
+	}
+	let mantissa = u128::from(number.integer)
+		.checked_mul(10_u128.checked_pow(number.fraction_length)?)?
+		.checked_add(number.fraction.into())?;
+	let expo = number.exponent.checked_sub(number.fraction_length.try_into().ok()?)?;
+	scale(mantissa, expo, 0)
+}
+
+fn field<'a>(object: &'a [(Vec<char>, JsonValue)], name: &str) -> Option<&'a JsonValue> {
+	object.iter().find(|(k, _)| k.iter().copied().eq(name.chars())).map(|(_, v)| v)
+}
+
+fn exponent(value: &JsonValue) -> Option<i32> {
+	match value {
+		JsonValue::Number(NumberValue { integer, negative, .. }) => {
+			let exponent: i32 = (*integer).try_into().ok()?;
+			Some(if *negative { -exponent } else { exponent })
+		},
+		_ => None,
+	}
+}
+
+/// Mantissas may be served as strings, to not lose precision in JSON numbers.
+fn mantissa(value: &JsonValue) -> Option<u128> {
+	match value {
+		JsonValue::String(chars) => chars
+			.iter()
+			.try_fold(0_u128, |acc, c| acc.checked_mul(10)?.checked_add(c.to_digit(10)?.into())),
+		JsonValue::Number(number) => json_number(number),
+		_ => None,
+	}
+}
+
+/// Scale `mantissa * 10^expo` to `decimals`, rounding down.
+fn scale(mantissa: u128, expo: i32, decimals: i32) -> Option<u128> {
+	let shift = decimals.checked_add(expo)?;
+	if shift >= 0 {
+		mantissa.checked_mul(10_u128.checked_pow(shift.unsigned_abs())?)
+	} else {
+		// dividing by more than 10^38 always rounds down to zero
+		Some(
+			10_u128
+				.checked_pow(shift.unsigned_abs())
+				.map_or(0, |divisor| mantissa / divisor),
+		)
+	}
+}
+
+/// A price normalized to `decimals`, for display.
+pub fn as_decimal(price: u128, decimals: Exponent) -> FixedU128 {
+	FixedU128::saturating_from_rational(price, 10_u128.saturating_pow(decimals.into()))
+}
+
+/// Weighted mean of the prices of the sources.
+///
+/// The confidence band covers the weighted mean of the bands of the sources and the weighted
+/// mean deviation of the sources from the aggregated price. It is at least `min_confidence` of
+/// the price, so that an oracle with a single source still has a band around its price.
+pub fn aggregate(answers: &[(SourcePrice, u32)], min_confidence: Percent) -> Option<SourcePrice> {
+	let total_weight: u128 = answers.iter().map(|(_, weight)| u128::from(*weight)).sum();
+	if total_weight == 0 {
+		return None
+	}
+	let weighted_mean = |value: &dyn Fn(&SourcePrice) -> u128| -> Option<u128> {
+		answers
+			.iter()
+			.try_fold(0_u128, |acc, (answer, weight)| {
+				acc.checked_add(value(answer).checked_mul((*weight).into())?)
+			})
+			.map(|sum| sum / total_weight)
+	};
+	let price = weighted_mean(&|answer| answer.price)?;
+	let deviation = weighted_mean(&|answer| answer.price.abs_diff(price))?;
+	let confidence = weighted_mean(&|answer| answer.confidence)?
+		.saturating_add(deviation)
+		.max(min_confidence.mul_ceil(price));
+	Some(SourcePrice { price, confidence })
+}
@@ -1,8 +1,9 @@
 use crate::{
 	mock::{AccountId, Extrinsic, RuntimeCall, RuntimeEvent, *},
+	sources::{self, PriceSource, PriceSourceKind, SourcePrice},
 	AssetInfo, Error, PrePrice, Withdraw, *,
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use composable_traits::{
 	defi::CurrencyPair,
 	oracle::{self, Price},
@@ -36,6 +37,8 @@ use composable_traits::{oracle::RewardTracker, time::MS_PER_YEAR_NAIVE};
 use sp_core::H256;
 
 const UNIT: Balance = 1_000_000_000_000;
+/// Confidence band overlapping any median.
+const WIDE_CONFIDENCE: Balance = Balance::MAX;
 
 prop_compose! {
 	fn asset_info()
@@ -58,6 +61,8 @@ prop_compose! {
 				reward_weight: reward,
 				slash,
 				emit_price_changes: false,
+				max_deviation: threshold.left_from_one(),
+				max_missed_rounds: 0,
 			}
 		}
 }
@@ -697,7 +702,7 @@ mod submit_price {
 		) {
 			new_test_ext().execute_with(|| {
 				prop_assert_noop!(
-					Oracle::submit_price(RuntimeOrigin::signed(account_id), asset_id, price_value),
+					Oracle::submit_price(RuntimeOrigin::signed(account_id), asset_id, WIDE_CONFIDENCE, price_value),
 					Error::<Test>::PriceNotRequested
 				);
 				Ok(())
@@ -734,7 +739,7 @@ mod submit_price {
 				System::set_block_number(last_update + asset_info.block_interval + 1);
 
 				prop_assert_noop!(
-					Oracle::submit_price(RuntimeOrigin::signed(submitter_account), price_value, asset_id),
+					Oracle::submit_price(RuntimeOrigin::signed(submitter_account), price_value, WIDE_CONFIDENCE, asset_id),
 					Error::<Test>::NotEnoughStake
 				);
 
@@ -766,7 +771,12 @@ mod submit_price {
 			assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_2), 50));
 			// fails as asset's slash is high compare to current stake of account_1
 			assert_noop!(
-				Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128),
+				Oracle::submit_price(
+					RuntimeOrigin::signed(account_1),
+					100_u128,
+					WIDE_CONFIDENCE,
+					0_u128
+				),
 				Error::<Test>::NotEnoughStake
 			);
 		});
@@ -796,7 +806,12 @@ fn add_price() {
 		System::set_block_number(6);
 		// fails no stake
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_1),
+				100_u128,
+				WIDE_CONFIDENCE,
+				0_u128
+			),
 			Error::<Test>::NotEnoughStake
 		);
 
@@ -810,28 +825,56 @@ fn add_price() {
 		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_4), 50));
 		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_5), 50));
 
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_2), 100_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_1),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_2),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_2), 100_u128, 0_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_2),
+				100_u128,
+				WIDE_CONFIDENCE,
+				0_u128
+			),
 			Error::<Test>::AlreadySubmitted
 		);
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_4), 100_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_4),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 
 		assert_eq!(Oracle::answer_in_transit(account_1), Some(5));
 		assert_eq!(Oracle::answer_in_transit(account_2), Some(5));
 		assert_eq!(Oracle::answer_in_transit(account_4), Some(5));
 
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_5), 100_u128, 0_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_5),
+				100_u128,
+				WIDE_CONFIDENCE,
+				0_u128
+			),
 			Error::<Test>::MaxPrices
 		);
 
-		let price = PrePrice { price: 100_u128, block: 6, who: account_1 };
+		let price =
+			PrePrice { price: 100_u128, confidence: WIDE_CONFIDENCE, block: 6, who: account_1 };
 
-		let price2 = PrePrice { price: 100_u128, block: 6, who: account_2 };
+		let price2 =
+			PrePrice { price: 100_u128, confidence: WIDE_CONFIDENCE, block: 6, who: account_2 };
 
-		let price4 = PrePrice { price: 100_u128, block: 6, who: account_4 };
+		let price4 =
+			PrePrice { price: 100_u128, confidence: WIDE_CONFIDENCE, block: 6, who: account_4 };
 
 		assert_eq!(Oracle::pre_prices(0), vec![price, price2, price4]);
 		System::set_block_number(2);
@@ -839,13 +882,23 @@ fn add_price() {
 
 		// fails price not requested
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_1),
+				100_u128,
+				WIDE_CONFIDENCE,
+				0_u128
+			),
 			Error::<Test>::PriceNotRequested
 		);
 
 		// non existent asset_id
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 10_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_1),
+				100_u128,
+				WIDE_CONFIDENCE,
+				10_u128
+			),
 			Error::<Test>::PriceNotRequested
 		);
 	});
@@ -874,7 +927,12 @@ fn submit_price_fails_stake_less_than_asset_slash() {
 		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_2), 50));
 		// fails as asset's slash is high compare to current stake of account_1
 		assert_noop!(
-			Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128),
+			Oracle::submit_price(
+				RuntimeOrigin::signed(account_1),
+				100_u128,
+				WIDE_CONFIDENCE,
+				0_u128
+			),
 			Error::<Test>::NotEnoughStake
 		);
 	});
@@ -920,19 +978,59 @@ fn halborn_test_price_manipulation() {
 		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(account_5), 50));
 
 		// Scenario 1: >50% of Oracles are malicious
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_3), 690_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_4), 900_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_5), 900_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_1),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_3),
+			690_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_4),
+			900_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_5),
+			900_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 		System::set_block_number(7);
 		Oracle::on_initialize(7);
 		System::set_block_number(13);
 		// Scenario 2: 50% of Oracles are malicious
 		// These prices prices will not be consider
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_3), 100_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_4), 900_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_5), 900_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_1),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_3),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_4),
+			900_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_5),
+			900_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 		System::set_block_number(14);
 		Oracle::on_initialize(14);
 	});
@@ -1025,12 +1123,12 @@ fn test_payout_slash() {
 		RewardTrackerStore::<Test>::set(Option::from(reward_tracker));
 		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(account_5), account_2));
 
-		let one = PrePrice { price: 79, block: 0, who: account_1 };
-		let two = PrePrice { price: 100, block: 0, who: account_2 };
-		let three = PrePrice { price: 151, block: 0, who: account_3 };
-		let four = PrePrice { price: 400, block: 0, who: account_4 };
+		let one = PrePrice { price: 79, confidence: 0, block: 0, who: account_1 };
+		let two = PrePrice { price: 100, confidence: 0, block: 0, who: account_2 };
+		let three = PrePrice { price: 151, confidence: 0, block: 0, who: account_3 };
+		let four = PrePrice { price: 400, confidence: 0, block: 0, who: account_4 };
 
-		let five = PrePrice { price: 100, block: 0, who: account_5 };
+		let five = PrePrice { price: 100, confidence: 0, block: 0, who: account_5 };
 
 		let asset_info = AssetInfo {
 			threshold: Percent::from_percent(0),
@@ -1040,9 +1138,17 @@ fn test_payout_slash() {
 			reward_weight: 0,
 			slash: 0,
 			emit_price_changes: false,
+			max_deviation: Percent::from_percent(100),
+			max_missed_rounds: 0,
 		};
 		// doesn't panic when percent not set
-		assert_ok!(Oracle::handle_payout(&vec![one, two, three, four, five], 100, 0, &asset_info));
+		assert_ok!(Oracle::handle_payout(
+			&vec![one, two, three, four, five],
+			&[],
+			100,
+			0,
+			&asset_info
+		));
 		assert_eq!(Balances::free_balance(account_1), 100);
 
 		assert_ok!(Oracle::add_asset_and_info(
@@ -1072,6 +1178,7 @@ fn test_payout_slash() {
 
 		assert_ok!(Oracle::handle_payout(
 			&vec![one, two, three, four, five],
+			&[],
 			100,
 			0,
 			&Oracle::asset_info(0).unwrap(),
@@ -1108,6 +1215,7 @@ fn test_payout_slash() {
 		assert_eq!(reward_tracker.total_reward_weight, 100);
 		assert_ok!(Oracle::handle_payout(
 			&vec![one, two, three, four, five],
+			&[],
 			100,
 			0,
 			&Oracle::asset_info(0).unwrap(),
@@ -1126,6 +1234,234 @@ fn test_payout_slash() {
 	});
 }
 
+#[test]
+fn set_slashing_policy() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		assert_noop!(
+			Oracle::set_slashing_policy(root.clone(), 0, Percent::from_percent(50), 3),
+			Error::<Test>::InvalidAssetId
+		);
+		assert_ok!(Oracle::add_asset_and_info(
+			root.clone(),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(3).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		// slashes answers which are not rewarded by default
+		assert_eq!(Oracle::asset_info(0).unwrap().max_deviation, Percent::from_percent(20));
+		assert_eq!(Oracle::asset_info(0).unwrap().max_missed_rounds, 0);
+
+		assert_noop!(
+			Oracle::set_slashing_policy(
+				RuntimeOrigin::signed(get_account_1()),
+				0,
+				Percent::from_percent(50),
+				3
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			Oracle::set_slashing_policy(root.clone(), 0, Percent::from_percent(10), 3),
+			Error::<Test>::DeviationBelowThreshold
+		);
+		assert_ok!(Oracle::set_slashing_policy(root.clone(), 0, Percent::from_percent(50), 3));
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::SlashingPolicyChanged(
+			0,
+			Percent::from_percent(50),
+			3,
+		)));
+
+		// kept when the asset is updated
+		assert_ok!(Oracle::add_asset_and_info(
+			root,
+			0,
+			Validated::new(Percent::from_percent(90)).unwrap(),
+			Validated::new(3).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		let asset_info = Oracle::asset_info(0).unwrap();
+		assert_eq!(asset_info.max_deviation, Percent::from_percent(50));
+		assert_eq!(asset_info.max_missed_rounds, 3);
+
+		// answers between the threshold and the max deviation are neither rewarded nor slashed
+		let account_4 = get_account_4();
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), account_4));
+		let answer = PrePrice { price: 70, confidence: 0, block: 0, who: account_4 };
+		assert_ok!(Oracle::handle_payout(&[answer], &[], 100, 0, &asset_info));
+		assert_eq!(Oracle::oracle_stake(account_4), Some(1));
+		assert_eq!(Oracle::offence_record(account_4), None);
+	});
+}
+
+#[test]
+fn slashes_escalate_until_forgiven() {
+	new_test_ext().execute_with(|| {
+		let account_4 = get_account_4();
+		let treasury_account = get_treasury_account();
+		System::set_block_number(1);
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), account_4));
+		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(get_account_5()), 89));
+		assert_eq!(Oracle::oracle_stake(account_4), Some(90));
+
+		// the asset slash, then 10% and 50% of the stake
+		for (stake, count) in [(89, 1), (81, 2), (41, 3), (21, 4)] {
+			Oracle::slash(&account_4, 0, 1, Offence::Deviation);
+			assert_eq!(Oracle::oracle_stake(account_4), Some(stake));
+			System::assert_has_event(RuntimeEvent::Oracle(crate::Event::OffenceCommitted(
+				account_4,
+				0,
+				Offence::Deviation,
+				count,
+			)));
+		}
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::UserSlashed(
+			account_4, 0, 20,
+		)));
+
+		// forgiven after the grace period
+		System::set_block_number(1 + SlashGracePeriod::get());
+		Oracle::slash(&account_4, 0, 1, Offence::MissedRounds);
+		assert_eq!(Oracle::oracle_stake(account_4), Some(20));
+		assert_eq!(
+			Oracle::offence_record(account_4),
+			Some(OffenceRecord { count: 1, last_offence: 1 + SlashGracePeriod::get() })
+		);
+		assert_eq!(Balances::free_balance(treasury_account), 170);
+		assert_eq!(Balances::reserved_balance(account_4), 20);
+	});
+}
+
+#[test]
+fn missed_rounds_are_slashed_after_grace_period() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		let account_1 = get_account_1();
+		let account_4 = get_account_4();
+		assert_ok!(Oracle::add_asset_and_info(
+			root.clone(),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		assert_ok!(Oracle::set_slashing_policy(root, 0, Percent::from_percent(20), 1));
+		System::set_block_number(1);
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_root_account()), account_1));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), account_4));
+		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(get_account_5()), 50));
+
+		let round = |block| {
+			System::set_block_number(block);
+			let answer =
+				PrePrice { price: 100, confidence: WIDE_CONFIDENCE, block, who: account_1 };
+			assert_ok!(Oracle::update_price(
+				0,
+				Oracle::asset_info(0).unwrap(),
+				block,
+				vec![answer]
+			));
+		};
+
+		round(5);
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 0);
+		round(1 + SlashGracePeriod::get());
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 1);
+		assert_eq!(Oracle::oracle_stake(account_4), Some(51));
+
+		round(2 + SlashGracePeriod::get());
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 0);
+		assert_eq!(Oracle::oracle_stake(account_4), Some(46));
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::OffenceCommitted(
+			account_4,
+			0,
+			Offence::MissedRounds,
+			1,
+		)));
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::UserSlashed(account_4, 0, 5)));
+		assert_eq!(Oracle::missed_rounds(account_1, 0), 0);
+		assert_eq!(Oracle::oracle_stake(account_1), Some(1));
+
+		// missed rounds are forgotten with the signer
+		round(3 + SlashGracePeriod::get());
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 1);
+		assert_ok!(Oracle::remove_stake(RuntimeOrigin::signed(get_account_5())));
+		System::set_block_number(5 + SlashGracePeriod::get());
+		assert_ok!(Oracle::reclaim_stake(RuntimeOrigin::signed(get_account_5())));
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 0);
+		assert_eq!(Oracle::signer_since(account_4), None);
+	});
+}
+
+#[test]
+fn full_rounds_are_not_counted_as_missed() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		let account_1 = get_account_1();
+		let account_4 = get_account_4();
+		assert_ok!(Oracle::add_asset_and_info(
+			root.clone(),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::new(1).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		assert_ok!(Oracle::set_slashing_policy(root, 0, Percent::from_percent(20), 1));
+		System::set_block_number(1);
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_root_account()), account_1));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), account_4));
+
+		// account_4 could not answer, the round was full
+		let block = 1 + SlashGracePeriod::get();
+		System::set_block_number(block);
+		let answer = PrePrice { price: 100, confidence: WIDE_CONFIDENCE, block, who: account_1 };
+		assert_ok!(Oracle::update_price(0, Oracle::asset_info(0).unwrap(), block, vec![answer]));
+		assert_eq!(Oracle::missed_rounds(account_4, 0), 0);
+	});
+}
+
+#[test]
+fn signers_are_bounded() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_root_account()), get_account_1()));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_1()), get_root_account()));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), get_account_4()));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_4()), get_account_5()));
+		assert_eq!(Oracle::signer_count(), MaxSigners::get());
+		assert_noop!(
+			Oracle::set_signer(RuntimeOrigin::signed(get_account_3()), get_treasury_account()),
+			Error::<Test>::ExceedSignersCount
+		);
+
+		assert_ok!(Oracle::remove_stake(RuntimeOrigin::signed(get_account_5())));
+		System::set_block_number(3);
+		assert_ok!(Oracle::reclaim_stake(RuntimeOrigin::signed(get_account_5())));
+		assert_eq!(Oracle::signer_count(), MaxSigners::get() - 1);
+		assert_ok!(Oracle::set_signer(
+			RuntimeOrigin::signed(get_account_3()),
+			get_treasury_account()
+		));
+	});
+}
+
 #[test]
 fn test_reset_reward_tracker_if_expired() {
 	new_test_ext().execute_with(|| {
@@ -1306,10 +1642,25 @@ fn halborn_test_bypass_slashing() {
 		println!("4: {}", balance4);
 		println!("5: {}", balance5);
 
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_1), 100_u128, 0_u128));
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_2), 100_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_1),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_2),
+			100_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 		// Proposing price of 4000 would result in getting stake slashed of controller account_5
-		assert_ok!(Oracle::submit_price(RuntimeOrigin::signed(account_4), 4000_u128, 0_u128));
+		assert_ok!(Oracle::submit_price(
+			RuntimeOrigin::signed(account_4),
+			4000_u128,
+			WIDE_CONFIDENCE,
+			0_u128
+		));
 
 		System::set_block_number(7);
 		Oracle::on_initialize(7);
@@ -1457,6 +1808,118 @@ fn update_price() {
 	});
 }
 
+#[test]
+fn update_price_rejects_answers_outside_of_median_band() {
+	new_test_ext().execute_with(|| {
+		let account_1 = get_account_1();
+		let account_3 = get_account_3();
+		let account_4 = get_account_4();
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(get_root_account()),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(2).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+
+		add_price_with_confidence(100, 10, 0, account_1, 2);
+		add_price_with_confidence(102, 5, 0, account_3, 2);
+		// within the threshold, but too confident to overlap the median of 102
+		add_price_with_confidence(110, 5, 0, account_4, 2);
+
+		System::set_block_number(3);
+		Oracle::on_initialize(3);
+
+		assert_eq!(Oracle::prices(0), Price { price: 101, block: 3 });
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::AnswerRejected(
+			account_4, 0, 110,
+		)));
+		assert_eq!(Oracle::answer_in_transit(account_4), Some(0));
+		assert!(Oracle::pre_prices(0).is_empty());
+	});
+}
+
+#[test]
+fn update_price_slashes_outliers_with_narrow_bands() {
+	new_test_ext().execute_with(|| {
+		let account_1 = get_account_1();
+		let account_3 = get_account_3();
+		let account_4 = get_account_4();
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(get_root_account()),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(2).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+		assert_ok!(Oracle::set_signer(RuntimeOrigin::signed(get_account_5()), account_4));
+		assert_ok!(Oracle::add_stake(RuntimeOrigin::signed(get_account_5()), 9));
+		assert_eq!(Oracle::oracle_stake(account_4), Some(10));
+
+		add_price_with_confidence(100, 10, 0, account_1, 2);
+		add_price_with_confidence(102, 5, 0, account_3, 2);
+		// far off the median of 102, with a band too narrow to overlap it
+		add_price_with_confidence(200, 1, 0, account_4, 2);
+
+		System::set_block_number(3);
+		Oracle::on_initialize(3);
+
+		assert_eq!(Oracle::prices(0), Price { price: 101, block: 3 });
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::AnswerRejected(
+			account_4, 0, 200,
+		)));
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::OffenceCommitted(
+			account_4,
+			0,
+			Offence::Deviation,
+			1,
+		)));
+		assert_eq!(Oracle::oracle_stake(account_4), Some(5));
+		assert_eq!(Oracle::answer_in_transit(account_4), Some(0));
+	});
+}
+
+#[test]
+fn update_price_drops_rounds_without_enough_answers_within_median_band() {
+	new_test_ext().execute_with(|| {
+		let account_1 = get_account_1();
+		let account_3 = get_account_3();
+		assert_ok!(Oracle::add_asset_and_info(
+			RuntimeOrigin::signed(get_root_account()),
+			0,
+			Validated::new(Percent::from_percent(80)).unwrap(),
+			Validated::new(2).unwrap(),
+			Validated::new(5).unwrap(),
+			Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+			5,
+			5,
+			false,
+		));
+
+		add_price_with_confidence(100, 0, 0, account_1, 2);
+		add_price_with_confidence(104, 1, 0, account_3, 2);
+
+		System::set_block_number(3);
+		Oracle::on_initialize(3);
+
+		assert_eq!(Oracle::prices(0), Price::default());
+		assert!(Oracle::pre_prices(0).is_empty());
+		System::assert_has_event(RuntimeEvent::Oracle(crate::Event::AnswerRejected(
+			account_3, 0, 104,
+		)));
+		assert_eq!(Oracle::answer_in_transit(account_1), Some(0));
+		assert_eq!(Oracle::answer_in_transit(account_3), Some(0));
+	});
+}
+
 #[test]
 fn historic_pricing() {
 	new_test_ext().execute_with(|| {
@@ -1570,6 +2033,40 @@ fn inverses() {
 	})
 }
 
+#[test]
+fn inverse_of_amount_above_u64() {
+	new_test_ext().execute_with(|| {
+		Prices::<Test>::insert(
+			BTC::ID,
+			Price { price: NORMALIZED::units(1) / 4, block: System::block_number() },
+		);
+		let inverse =
+			<Oracle as oracle::Oracle>::get_price_inverse(BTC::ID, NORMALIZED::units(10_000_000))
+				.unwrap();
+		assert_eq!(inverse, BTC::units(40_000_000));
+	})
+}
+
+#[test]
+fn ratio_keeps_precision_of_sub_cent_prices() {
+	new_test_ext().execute_with(|| {
+		// 0.000000025 and 3 for one unit
+		Prices::<Test>::insert(BTC::ID, Price { price: 25_000, block: System::block_number() });
+		Prices::<Test>::insert(
+			PICA::ID,
+			Price { price: NORMALIZED::units(3), block: System::block_number() },
+		);
+
+		let pair = CurrencyPair::new(BTC::ID, PICA::ID);
+		let ratio = <Oracle as oracle::Oracle>::get_precise_ratio(pair.clone()).unwrap();
+		assert_eq!(ratio, FixedU128::saturating_from_rational(25_000, NORMALIZED::units(3)));
+		// assets of the same decimals have a ratio of integers already
+		assert_eq!(<Oracle as oracle::Oracle>::get_ratio(pair), Ok(ratio));
+		let price = <Oracle as oracle::Oracle>::get_price(BTC::ID, BTC::units(1_000)).unwrap();
+		assert_eq!(price.price, 25_000_000);
+	})
+}
+
 #[test]
 fn ratio_base_is_way_less_smaller() {
 	new_test_ext().execute_with(|| {
@@ -1585,6 +2082,199 @@ fn ratio_base_is_way_less_smaller() {
 	})
 }
 
+#[test]
+fn add_derived_asset() {
+	new_test_ext().execute_with(|| {
+		let derivation = PriceDerivation::CrossRate { base: BTC::ID, quote: PICA::ID };
+		assert_noop!(
+			Oracle::add_derived_asset(
+				RuntimeOrigin::signed(get_account_1()),
+				7,
+				derivation.clone()
+			),
+			BadOrigin
+		);
+		assert_ok!(Oracle::add_derived_asset(
+			RuntimeOrigin::signed(get_root_account()),
+			7,
+			derivation.clone()
+		));
+		assert_eq!(Oracle::derived_price(7), Some(derivation.clone()));
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::DerivedAssetChanged(
+			7, derivation,
+		)));
+
+		assert_noop!(
+			Oracle::remove_derived_asset(RuntimeOrigin::signed(get_account_1()), 7),
+			BadOrigin
+		);
+		assert_ok!(Oracle::remove_derived_asset(RuntimeOrigin::signed(get_root_account()), 7));
+		assert_eq!(Oracle::derived_price(7), None);
+		System::assert_last_event(RuntimeEvent::Oracle(crate::Event::DerivedAssetRemoved(7)));
+		assert_noop!(
+			Oracle::remove_derived_asset(RuntimeOrigin::signed(get_root_account()), 7),
+			Error::<Test>::AssetNotDerived
+		);
+	});
+}
+
+#[test]
+fn derived_assets_are_not_fed_by_oracles() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		let add_asset = |asset_id| {
+			Oracle::add_asset_and_info(
+				RuntimeOrigin::signed(get_root_account()),
+				asset_id,
+				Validated::new(Percent::from_percent(80)).unwrap(),
+				Validated::new(3).unwrap(),
+				Validated::new(5).unwrap(),
+				Validated::<BlockNumber, ValidBlockInterval<StalePrice>>::new(5).unwrap(),
+				5,
+				5,
+				false,
+			)
+		};
+		let derivation = PriceDerivation::CrossRate { base: BTC::ID, quote: PICA::ID };
+
+		assert_ok!(add_asset(6));
+		assert_noop!(
+			Oracle::add_derived_asset(root.clone(), 6, derivation.clone()),
+			Error::<Test>::OracleAsset
+		);
+
+		assert_ok!(Oracle::add_derived_asset(root.clone(), 7, derivation));
+		assert_noop!(add_asset(7), Error::<Test>::DerivedAsset);
+
+		assert_noop!(
+			Oracle::add_derived_asset(
+				root,
+				8,
+				PriceDerivation::CrossRate { base: 8, quote: PICA::ID }
+			),
+			Error::<Test>::InvalidDerivation
+		);
+	});
+}
+
+#[test]
+fn cross_rate_price() {
+	new_test_ext().execute_with(|| {
+		Prices::<Test>::insert(BTC::ID, Price { price: NORMALIZED::units(30), block: 3 });
+		Prices::<Test>::insert(PICA::ID, Price { price: NORMALIZED::units(2), block: 5 });
+		assert_ok!(Oracle::add_derived_asset(
+			RuntimeOrigin::signed(get_root_account()),
+			7,
+			PriceDerivation::CrossRate { base: BTC::ID, quote: PICA::ID }
+		));
+
+		// as old as its oldest component
+		assert_eq!(
+			<Oracle as oracle::Oracle>::get_price(7, UNIT * 2),
+			Ok(Price { price: NORMALIZED::units(30), block: 3 })
+		);
+		assert_eq!(<Oracle as oracle::Oracle>::get_twap_for_amount(7, UNIT), Ok(UNIT * 15));
+		assert_eq!(<Oracle as oracle::Oracle>::get_price_inverse(7, UNIT * 30), Ok(UNIT * 2));
+
+		Prices::<Test>::remove(PICA::ID);
+		assert_noop!(<Oracle as oracle::Oracle>::get_price(7, UNIT), Error::<Test>::PriceNotFound);
+	});
+}
+
+#[test]
+fn lp_token_and_staking_share_prices() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		Prices::<Test>::insert(0, Price { price: NORMALIZED::units(3), block: 3 });
+		Prices::<Test>::insert(1, Price { price: NORMALIZED::units(2), block: 5 });
+		assert_ok!(Oracle::add_derived_asset(
+			root.clone(),
+			100,
+			PriceDerivation::LpToken { pool_id: LP_POOL_ID }
+		));
+		assert_ok!(Oracle::add_derived_asset(
+			root.clone(),
+			SHARE_ASSET_ID,
+			PriceDerivation::StakingShare
+		));
+
+		// 2 * 3 + 3 * 2
+		assert_eq!(
+			<Oracle as oracle::Oracle>::get_price(100, UNIT),
+			Ok(Price { price: NORMALIZED::units(12), block: 3 })
+		);
+		assert_eq!(<Oracle as oracle::Oracle>::is_supported(100), Ok(true));
+		// 1.5 * 3 * 2
+		assert_eq!(
+			<Oracle as oracle::Oracle>::get_price(SHARE_ASSET_ID, UNIT * 2),
+			Ok(Price { price: NORMALIZED::units(9), block: 3 })
+		);
+		assert_eq!(
+			<Oracle as oracle::Oracle>::get_ratio(CurrencyPair::new(100, SHARE_ASSET_ID)),
+			Ok(FixedU128::saturating_from_rational(12 * 2, 9))
+		);
+
+		// skewing the pool, keeping its invariant, would make its balances worth 8 * 3 + 0.75 * 2
+		LpPoolBalances::set((8 * LP_UNIT, 3 * LP_UNIT / 4));
+		assert_eq!(
+			<Oracle as oracle::Oracle>::get_price(100, UNIT),
+			Ok(Price { price: NORMALIZED::units(12), block: 3 })
+		);
+
+		assert_ok!(Oracle::add_derived_asset(
+			root,
+			101,
+			PriceDerivation::LpToken { pool_id: LP_POOL_ID + 1 }
+		));
+		assert!(<Oracle as oracle::Oracle>::get_price(101, UNIT).is_err());
+	});
+}
+
+#[test]
+fn derivation_depth_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let root = RuntimeOrigin::signed(get_root_account());
+		Prices::<Test>::insert(PICA::ID, Price { price: NORMALIZED::units(1), block: 3 });
+		Prices::<Test>::insert(BTC::ID, Price { price: NORMALIZED::units(2), block: 3 });
+		let mut base = BTC::ID;
+		for asset_id in 20..20 + MAX_DERIVATION_DEPTH as u128 {
+			assert_ok!(Oracle::add_derived_asset(
+				root.clone(),
+				asset_id,
+				PriceDerivation::CrossRate { base, quote: PICA::ID }
+			));
+			base = asset_id;
+		}
+		assert_ok!(<Oracle as oracle::Oracle>::get_price(base, UNIT));
+
+		assert_ok!(Oracle::add_derived_asset(
+			root.clone(),
+			30,
+			PriceDerivation::CrossRate { base, quote: PICA::ID }
+		));
+		assert_noop!(
+			<Oracle as oracle::Oracle>::get_price(30, UNIT),
+			Error::<Test>::DerivationTooDeep
+		);
+
+		// cycles
+		assert_ok!(Oracle::add_derived_asset(
+			root.clone(),
+			40,
+			PriceDerivation::CrossRate { base: 41, quote: PICA::ID }
+		));
+		assert_ok!(Oracle::add_derived_asset(
+			root,
+			41,
+			PriceDerivation::CrossRate { base: 40, quote: PICA::ID }
+		));
+		assert_noop!(
+			<Oracle as oracle::Oracle>::get_price(40, UNIT),
+			Error::<Test>::DerivationTooDeep
+		);
+	});
+}
+
 #[test]
 fn get_twap() {
 	new_test_ext().execute_with(|| {
@@ -1769,6 +2459,8 @@ fn prune_old_pre_prices_edgecase() {
 			reward_weight: 5,
 			slash: 5,
 			emit_price_changes: false,
+			max_deviation: Percent::from_percent(20),
+			max_missed_rounds: 0,
 		};
 		Oracle::prune_old_pre_prices(&asset_info, vec![], 0);
 	});
@@ -1780,9 +2472,9 @@ fn should_make_http_call_and_parse_result() {
 
 	t.execute_with(|| {
 		// when
-		let price = Oracle::fetch_price(&0).unwrap();
+		let price = Oracle::fetch_price(&0, Percent::zero(), 12).unwrap();
 		// then
-		assert_eq!(price, 15523);
+		assert_eq!(price, SourcePrice { price: 15523, confidence: 0 });
 	});
 }
 
@@ -1815,9 +2507,9 @@ fn knows_how_to_mock_several_http_calls() {
 	});
 
 	t.execute_with(|| {
-		let price1 = Oracle::fetch_price(&0).unwrap();
-		let price2 = Oracle::fetch_price(&0).unwrap();
-		let price3 = Oracle::fetch_price(&0).unwrap();
+		let price1 = Oracle::fetch_price(&0, Percent::zero(), 12).unwrap().price;
+		let price2 = Oracle::fetch_price(&0, Percent::zero(), 12).unwrap().price;
+		let price3 = Oracle::fetch_price(&0, Percent::zero(), 12).unwrap().price;
 
 		assert_eq!(price1, 100);
 		assert_eq!(price2, 200);
@@ -1852,7 +2544,12 @@ fn should_submit_signed_transaction_on_chain() {
 		assert_eq!(tx.signature.unwrap().0, 0);
 		assert_eq!(
 			tx.call,
-			RuntimeCall::Oracle(crate::Call::submit_price { price: 15523, asset_id: 0 })
+			// the band of a single source is the 20% tolerated by the threshold of the asset
+			RuntimeCall::Oracle(crate::Call::submit_price {
+				price: 15523,
+				confidence: 3105,
+				asset_id: 0
+			})
 		);
 	});
 }
@@ -1896,6 +2593,8 @@ fn should_check_oracles_max_answer() {
 		reward_weight: 0,
 		slash: 0,
 		emit_price_changes: false,
+		max_deviation: Percent::from_percent(100),
+		max_missed_rounds: 0,
 	};
 	t.execute_with(|| {
 		Oracle::fetch_price_and_send_signed(&0, asset_info).unwrap();
@@ -1907,17 +2606,139 @@ fn parse_price_works() {
 	let test_data = vec![
 		("{\"1\":6536.92}", Some(6536)),
 		("{\"1\":650000000}", Some(650000000)),
+		("{\"1\":6.5e8}", Some(650000000)),
+		("{\"1\":-6536}", None),
 		("{\"2\":6536}", None),
 		("{\"0\":\"6432\"}", None),
+		// 0.000000025 for one unit
+		(r#"{"1": {"price": "25", "expo": -9}}"#, Some(25_000)),
+		// more than fits into u64 once normalized
+		(r#"{"1": {"price": 65000000, "expo": 0}}"#, Some(65_000_000 * UNIT)),
+		(r#"{"1": {"price": "25"}}"#, None),
+	];
+
+	for (json, expected) in test_data {
+		assert_eq!(expected, Oracle::parse_price(json, "1", 12));
+	}
+
+	// to the decimals of a normalized asset with 6 decimals
+	assert_eq!(Oracle::parse_price(r#"{"1": {"price": "25", "expo": -3}}"#, "1", 6), Some(25_000));
+	assert_eq!(
+		sources::parse_pyth_price(r#"{"price": {"price": "25", "conf": "1", "expo": -3}}"#, 6),
+		Some(SourcePrice { price: 25_000, confidence: 1_000 })
+	);
+}
+
+#[test]
+fn fetch_price_aggregates_weighted_sources() {
+	let (mut t, _, _) = offchain_worker_env(|state| {
+		state.expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: "http://localhost:3001/price/0".into(),
+			response: Some(br#"{"0": 8000000000000}"#.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+		state.expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: "http://localhost:3002/pyth/0".into(),
+			response: Some(
+				br#"{"price": {"price": "1200000000", "conf": "1000000", "expo": -8}}"#.to_vec(),
+			),
+			sent: true,
+			..Default::default()
+		});
+		state.expect_request(testing::PendingRequest {
+			method: "GET".into(),
+			uri: "http://localhost:3003/twap/0".into(),
+			response: Some(br#"{"1": 9000000000000}"#.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+	});
+
+	t.execute_with(|| {
+		let sources = vec![
+			PriceSource {
+				kind: PriceSourceKind::PriceFeed,
+				url: b"http://localhost:3001/price/".to_vec(),
+				weight: 1,
+			},
+			PriceSource {
+				kind: PriceSourceKind::Pyth,
+				url: b"http://localhost:3002/pyth/".to_vec(),
+				weight: 3,
+			},
+			// answers for another asset, so it is skipped
+			PriceSource {
+				kind: PriceSourceKind::PabloTwap,
+				url: b"http://localhost:3003/twap/".to_vec(),
+				weight: 10,
+			},
+		];
+		sp_io::offchain::local_storage_set(
+			sp_core::offchain::StorageKind::PERSISTENT,
+			sources::SOURCES_KEY,
+			&sources.encode(),
+		);
+
+		// (8 * 1 + 12 * 3) / 4 = 11, deviating by (3 * 1 + 1 * 3) / 4 = 1.5 from the sources,
+		// plus the 0.01 band of Pyth weighted by 3 / 4
+		assert_eq!(
+			Oracle::fetch_price(&0, Percent::zero(), 12),
+			Ok(SourcePrice { price: 11_000_000_000_000, confidence: 1_507_500_000_000 })
+		);
+	});
+}
+
+#[test]
+fn parse_pyth_price_works() {
+	let test_data = vec![
+		(
+			r#"{"price": {"price": "650012345", "conf": "12345", "expo": -5}}"#,
+			Some(SourcePrice { price: 6_500_123_450_000_000, confidence: 123_450_000_000 }),
+		),
+		(
+			r#"{"price": {"price": 65, "conf": 1, "expo": 2}}"#,
+			Some(SourcePrice { price: 6_500 * UNIT, confidence: 100 * UNIT }),
+		),
+		(
+			r#"{"price": {"price": "1234567", "conf": "1", "expo": -15}}"#,
+			Some(SourcePrice { price: 1234, confidence: 0 }),
+		),
+		(r#"{"price": {"price": "-65", "conf": "1", "expo": -2}}"#, None),
+		(r#"{"price": {"price": "65", "expo": -2}}"#, None),
+		(r#"{"price": 6536}"#, None),
 	];
 
 	for (json, expected) in test_data {
-		assert_eq!(expected, Oracle::parse_price(json, "1"));
+		assert_eq!(expected, sources::parse_pyth_price(json, 12));
 	}
 }
 
+#[test]
+fn aggregate_keeps_a_minimal_band() {
+	let answer = SourcePrice { price: 1_000, confidence: 1 };
+	assert_eq!(
+		sources::aggregate(&[(answer, 1)], Percent::from_percent(20)),
+		Some(SourcePrice { price: 1_000, confidence: 200 })
+	);
+	assert_eq!(sources::aggregate(&[(answer, 0)], Percent::zero()), None);
+	assert_eq!(sources::aggregate(&[], Percent::zero()), None);
+}
+
 fn add_price_storage(price: u128, asset_id: u128, who: AccountId, block: u64) {
-	let price = PrePrice { price, block, who };
+	add_price_with_confidence(price, WIDE_CONFIDENCE, asset_id, who, block);
+}
+
+fn add_price_with_confidence(
+	price: u128,
+	confidence: u128,
+	asset_id: u128,
+	who: AccountId,
+	block: u64,
+) {
+	let price = PrePrice { price, confidence, block, who };
 	PrePrices::<Test>::mutate(asset_id, |current_prices| current_prices.try_push(price).unwrap());
 	AnswerInTransit::<Test>::mutate(who, |transit| {
 		*transit = Some(transit.unwrap_or_else(Zero::zero) + 5)
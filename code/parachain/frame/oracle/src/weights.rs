@@ -16,6 +16,10 @@ pub trait WeightInfo {
 	fn submit_price(p: u32) -> Weight;
 	fn update_pre_prices(p: u32) -> Weight;
 	fn update_price(p: u32) -> Weight;
+	fn add_derived_asset() -> Weight;
+	fn remove_derived_asset() -> Weight;
+	fn set_slashing_policy() -> Weight;
+	fn track_missed_rounds(s: u32) -> Weight;
 }
 
 /// Weights for pallet_oracle using the Substrate node and recommended hardware.
@@ -72,6 +76,27 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	fn add_derived_asset() -> Weight {
+		Weight::from_ref_time(24_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn remove_derived_asset() -> Weight {
+		Weight::from_ref_time(22_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn set_slashing_policy() -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn track_missed_rounds(s: u32) -> Weight {
+		Weight::from_ref_time(5_000_000_u64)
+			.saturating_add(Weight::from_ref_time(30_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(s as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -129,4 +154,25 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
+	fn add_derived_asset() -> Weight {
+		Weight::from_ref_time(24_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn remove_derived_asset() -> Weight {
+		Weight::from_ref_time(22_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn set_slashing_policy() -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn track_missed_rounds(s: u32) -> Weight {
+		Weight::from_ref_time(5_000_000_u64)
+			.saturating_add(Weight::from_ref_time(30_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(s as u64)))
+	}
 }
@@ -1,17 +1,22 @@
 use super::*;
-use crate::{Pallet as Pablo, PoolConfiguration::DualAssetConstantProduct};
+use crate::Pallet as Pablo;
 use composable_traits::{
 	defi::CurrencyPair,
-	dex::{Amm, AssetAmount},
+	dex::{Amm, AssetAmount, Sale},
 };
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::{
 	assert_ok,
-	traits::fungibles::{Inspect, Mutate},
+	storage::with_transaction,
+	traits::{
+		fungibles::{Inspect, Mutate},
+		Time,
+	},
+	weights::Weight,
 };
 use frame_system::RawOrigin;
 use sp_arithmetic::{PerThing, Permill};
-use sp_runtime::BoundedBTreeMap;
+use sp_runtime::{BoundedBTreeMap, TransactionOutcome};
 use sp_std::collections::btree_map::BTreeMap;
 
 fn amm_init_config<T: Config>(
@@ -28,6 +33,38 @@ fn amm_init_config<T: Config>(
 	PoolInitConfiguration::DualAssetConstantProduct { owner, fee, assets_weights }
 }
 
+/// A balanced dual asset pool of 100_000_000 units each and a user owning 1000 units of `usdt`.
+fn create_limit_order_pool<T: Config>(
+	usdc: T::AssetId,
+	usdt: T::AssetId,
+	unit: u128,
+) -> (T::PoolId, T::AccountId) {
+	let owner: T::AccountId = whitelisted_caller();
+	let pool_id = create_amm_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+	let initial_amount: T::Balance = (100_000_000_u128 * unit).into();
+	assert_ok!(T::Assets::mint_into(usdc, &owner, initial_amount));
+	assert_ok!(T::Assets::mint_into(usdt, &owner, initial_amount));
+	assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+		&owner,
+		pool_id,
+		BTreeMap::from([(usdc, initial_amount), (usdt, initial_amount)]),
+		0.into(),
+		false
+	));
+	let user = account("user", 0, 0);
+	assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+	(pool_id, user)
+}
+
+fn stable_swap_init_config<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	amplification_coefficient: u16,
+	fee: Permill,
+) -> PoolInitConfigurationOf<T> {
+	PoolInitConfiguration::StableSwap { owner, pair, amplification_coefficient, fee }
+}
+
 fn create_amm_pool<T: Config>(
 	owner: T::AccountId,
 	pair: CurrencyPair<T::AssetId>,
@@ -38,13 +75,176 @@ fn create_amm_pool<T: Config>(
 	Pablo::<T>::do_create_pool(swap_pool_init, Some(lp_token_id)).expect("impossible; qed;")
 }
 
-fn get_lp_token<T: Config>(pool_id: T::PoolId) -> T::AssetId {
-	let pool_info = Pablo::<T>::get_pool(pool_id).expect("impossible; qed;");
-	match pool_info {
-		DualAssetConstantProduct(pool) => pool.lp_token,
+fn create_stable_swap_pool<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	lp_token_id: T::AssetId,
+) -> T::PoolId {
+	let stable_swap_pool_init =
+		stable_swap_init_config::<T>(owner, pair, 100, Permill::from_percent(1));
+	Pablo::<T>::do_create_pool(stable_swap_pool_init, Some(lp_token_id)).expect("impossible; qed;")
+}
+
+/// Assets `100..100 + number_of_assets`, weighted equally. Any rounding remainder of the weights
+/// is given to the first asset.
+fn weighted_product_init_config<T: Config>(
+	owner: T::AccountId,
+	number_of_assets: u32,
+	fee: Permill,
+) -> PoolInitConfigurationOf<T> {
+	let weight = Permill::from_rational(1, number_of_assets);
+	let first_weight =
+		Permill::from_parts(Permill::ACCURACY - weight.deconstruct() * (number_of_assets - 1));
+	let assets_weights = (0..number_of_assets)
+		.map(|i| {
+			let asset_id = T::AssetId::from(100 + u128::from(i));
+			(asset_id, if i == 0 { first_weight } else { weight })
+		})
+		.collect::<BTreeMap<_, _>>()
+		.try_into()
+		.expect("Should work");
+	PoolInitConfiguration::WeightedProduct { owner, assets_weights, fee }
+}
+
+fn create_weighted_product_pool<T: Config>(
+	owner: T::AccountId,
+	number_of_assets: u32,
+	lp_token_id: T::AssetId,
+) -> T::PoolId {
+	let weighted_pool_init =
+		weighted_product_init_config::<T>(owner, number_of_assets, Permill::from_percent(1));
+	Pablo::<T>::do_create_pool(weighted_pool_init, Some(lp_token_id)).expect("impossible; qed;")
+}
+
+/// Mints `amount` of every asset of the pool to `who` and returns the deposit for all of them.
+fn mint_pool_assets<T: Config>(
+	pool_id: T::PoolId,
+	who: &T::AccountId,
+	amount: T::Balance,
+) -> BTreeMap<T::AssetId, T::Balance> {
+	<Pablo<T> as Amm>::assets(pool_id)
+		.expect("impossible; qed;")
+		.into_keys()
+		.map(|asset_id| {
+			assert_ok!(T::Assets::mint_into(asset_id, who, amount));
+			(asset_id, amount)
+		})
+		.collect()
+}
+
+fn lbp_init_config<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	fee: Permill,
+) -> PoolInitConfigurationOf<T> {
+	let start = T::Time::now();
+	let sale = Sale {
+		start,
+		end: start + T::MinSaleDuration::get(),
+		initial_weight: Permill::from_percent(90),
+		final_weight: Permill::from_percent(50),
+	};
+	PoolInitConfiguration::LiquidityBootstrapping { owner, pair, sale, fee }
+}
+
+fn create_lbp_pool<T: Config>(owner: T::AccountId, pair: CurrencyPair<T::AssetId>) -> T::PoolId {
+	let lbp_init = lbp_init_config::<T>(owner, pair, Permill::from_percent(1));
+	Pablo::<T>::do_create_pool(lbp_init, None).expect("impossible; qed;")
+}
+
+/// Moves the sale of a liquidity bootstrapping pool to its end, as time can't be advanced here.
+fn end_sale<T: Config>(pool_id: T::PoolId) {
+	Pools::<T>::mutate(pool_id, |pool| {
+		if let Some(PoolConfiguration::LiquidityBootstrapping(info)) = pool {
+			let now = T::Time::now();
+			info.sale.start = now;
+			info.sale.end = now;
+		}
+	});
+}
+
+fn concentrated_liquidity_init_config<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	fee: Permill,
+) -> PoolInitConfigurationOf<T> {
+	PoolInitConfiguration::ConcentratedLiquidity {
+		owner,
+		pair,
+		tick_spacing: 10,
+		initial_tick: 0,
+		fee,
 	}
 }
 
+fn create_concentrated_liquidity_pool<T: Config>(
+	owner: T::AccountId,
+	pair: CurrencyPair<T::AssetId>,
+	position_collection_id: T::AssetId,
+) -> T::PoolId {
+	let init = concentrated_liquidity_init_config::<T>(owner, pair, Permill::from_percent(1));
+	Pablo::<T>::do_create_pool(init, Some(position_collection_id)).expect("impossible; qed;")
+}
+
+/// Opens a position around the initial price of the pool, with all of the minted assets.
+fn open_position<T: Config>(
+	pool_id: T::PoolId,
+	who: &T::AccountId,
+	amount: T::Balance,
+) -> T::FinancialNftInstanceId {
+	let assets = mint_pool_assets::<T>(pool_id, who, amount);
+	let (position_id, _) =
+		Pablo::<T>::do_open_position(who, pool_id, -6_000, 6_000, assets, 0, false)
+			.expect("impossible; qed;");
+	position_id
+}
+
+/// Opens `ticks` adjacent usdc only positions above the initial price of the pool, returning the
+/// amount of usdc to buy for the price to cross all of their lower ticks.
+fn open_adjacent_positions<T: Config>(
+	pool_id: T::PoolId,
+	who: &T::AccountId,
+	usdc: T::AssetId,
+	ticks: u32,
+	unit: u128,
+) -> T::Balance {
+	let deposit = 1_000_u128 * unit;
+	assert_ok!(T::Assets::mint_into(usdc, who, (deposit * ticks as u128).into()));
+	for tick in 1..=ticks as i32 {
+		assert_ok!(Pablo::<T>::do_open_position(
+			who,
+			pool_id,
+			tick * 10,
+			(tick + 1) * 10,
+			BTreeMap::from([(usdc, deposit.into())]),
+			0,
+			false,
+		));
+	}
+	// stop halfway through the last position
+	(deposit * ticks as u128 - deposit / 2).into()
+}
+
+/// Amount of `in_asset_id` spent by buying `out_asset`, leaving the pool untouched.
+fn buy_cost<T: Config>(
+	who: &T::AccountId,
+	pool_id: T::PoolId,
+	in_asset_id: T::AssetId,
+	out_asset: AssetAmount<T::AssetId, T::Balance>,
+) -> T::Balance {
+	let balance = T::Assets::balance(in_asset_id, who);
+	with_transaction(|| {
+		let cost = <Pablo<T> as Amm>::do_buy(who, pool_id, in_asset_id, out_asset, false)
+			.map(|_| balance - T::Assets::balance(in_asset_id, who));
+		TransactionOutcome::Rollback(cost)
+	})
+	.expect("impossible; qed;")
+}
+
+fn get_lp_token<T: Config>(pool_id: T::PoolId) -> T::AssetId {
+	<Pablo<T> as Amm>::lp_token(pool_id).expect("impossible; qed;")
+}
+
 benchmarks! {
   where_clause { where T::BlockNumber: From<u32>, T::Balance: From<u128>, T::AssetId: From<u128> }
 	create {
@@ -120,7 +320,7 @@ benchmarks! {
 		let user = account("user", 0, 0);
 		assert_ok!(T::Assets::mint_into(usdt, &user, (1020_u128 * unit).into()));
 		// buy 1000 USDC
-	 }: _(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(usdc, (1000_u128 * unit).into()), false)
+	 }: _(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(usdc, (1000_u128 * unit).into()), false, None)
 
 	 swap {
 		let usdc = 100.into();
@@ -147,7 +347,367 @@ benchmarks! {
 		let user = account("user", 0, 0);
 		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
 		// swap 1000 USDC
-	 }: _(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false)
+	 }: _(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false, None)
+
+	create_stable_swap {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pair = CurrencyPair::new(usdc, usdt);
+		let fee = Permill::from_percent(1);
+		let stable_swap_pool_init = stable_swap_init_config::<T>(owner, pair, 100, fee);
+	  }: create(RawOrigin::Root, stable_swap_pool_init)
+
+	add_liquidity_stable_swap {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_stable_swap_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the initial liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		let user = account("user", 0, 0);
+		let usdc_deposit: T::Balance = (1_000_000_u128 * unit).into();
+		let usdt_deposit: T::Balance = (500_000_u128 * unit).into();
+		assert_ok!(T::Assets::mint_into(usdc, &user, usdc_deposit));
+		assert_ok!(T::Assets::mint_into(usdt, &user, usdt_deposit));
+		// imbalanced deposit, as it requires computing the invariant three times
+	  }: add_liquidity(RawOrigin::Signed(user), pool_id, BTreeMap::from([(usdc, usdc_deposit), (usdt, usdt_deposit)]), 0.into(), false)
+
+	remove_liquidity_stable_swap {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_stable_swap_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		let lp_amount = T::Assets::balance(get_lp_token::<T>(pool_id), &owner);
+	  }: remove_liquidity(RawOrigin::Signed(owner), pool_id, lp_amount, BTreeMap::from([(usdc, 0.into()), (usdt, 0.into())]))
+
+	buy_stable_swap {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_stable_swap_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1020_u128 * unit).into()));
+		// buy 1000 USDC
+	 }: buy(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(usdc, (1000_u128 * unit).into()), false, None)
+
+	swap_stable_swap {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_stable_swap_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_usdc: T::Balance = (100_000_000_u128 * unit).into();
+		let initial_usdt: T::Balance = (100_000_000_u128 * unit).into();
+		// Mint the tokens
+		assert_ok!(T::Assets::mint_into(usdc, &owner, initial_usdc));
+		assert_ok!(T::Assets::mint_into(usdt, &owner, initial_usdt));
+		// Add the liquidity
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(
+			&owner,
+			pool_id,
+			BTreeMap::from([(usdc, initial_usdc), (usdt, initial_usdt)]),
+			0.into(),
+			false
+		));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// swap 1000 USDT
+	 }: swap(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false, None)
+
+	create_weighted_product {
+		let a in 2 .. T::MaxAssetsPerPool::get();
+		let owner: T::AccountId = whitelisted_caller();
+		let weighted_pool_init = weighted_product_init_config::<T>(owner, a, Permill::from_percent(1));
+	  }: create(RawOrigin::Root, weighted_pool_init)
+
+	add_liquidity_weighted_product {
+		let a in 2 .. T::MaxAssetsPerPool::get();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_weighted_product_pool::<T>(owner.clone(), a, lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 of every asset
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let user = account("user", 0, 0);
+		let deposit = mint_pool_assets::<T>(pool_id, &user, (1_000_000_u128 * unit).into());
+	  }: add_liquidity(RawOrigin::Signed(user), pool_id, deposit, 0.into(), false)
+
+	remove_liquidity_weighted_product {
+		let a in 2 .. T::MaxAssetsPerPool::get();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_weighted_product_pool::<T>(owner.clone(), a, lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 of every asset
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let lp_amount = T::Assets::balance(get_lp_token::<T>(pool_id), &owner);
+	  }: remove_liquidity(RawOrigin::Signed(owner), pool_id, lp_amount, BTreeMap::new())
+
+	remove_liquidity_single_asset {
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_weighted_product_pool::<T>(owner.clone(), T::MaxAssetsPerPool::get(), lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 of every asset
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		let first_asset = *initial_liquidity.keys().next().expect("impossible; qed;");
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		// redeem 1% of the lp tokens
+		let lp_amount = T::Assets::balance(get_lp_token::<T>(pool_id), &owner) / 100_u128.into();
+	  }: _(RawOrigin::Signed(owner), pool_id, lp_amount, AssetAmount::new(first_asset, 0.into()))
+
+	buy_weighted_product {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_weighted_product_pool::<T>(owner.clone(), 2, lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1020_u128 * unit).into()));
+		// buy 1000 USDC
+	 }: buy(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(usdc, (1000_u128 * unit).into()), false, None)
+
+	swap_weighted_product {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let lp_token_id = 1000.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_weighted_product_pool::<T>(owner.clone(), 2, lp_token_id);
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// swap 1000 USDT
+	 }: swap(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false, None)
+
+	create_lbp {
+		let project = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let lbp_init = lbp_init_config::<T>(owner, CurrencyPair::new(project, usdt), Permill::from_percent(1));
+	  }: create(RawOrigin::Root, lbp_init)
+
+	add_liquidity_lbp {
+		let project = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_lbp_pool::<T>(owner.clone(), CurrencyPair::new(project, usdt));
+		let unit = 1_000_000_000_000;
+		// 100_000_000 PROJECT , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+	  }: add_liquidity(RawOrigin::Signed(owner), pool_id, initial_liquidity, 0.into(), false)
+
+	remove_liquidity_lbp {
+		let project = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_lbp_pool::<T>(owner.clone(), CurrencyPair::new(project, usdt));
+		let unit = 1_000_000_000_000;
+		// 100_000_000 PROJECT , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		end_sale::<T>(pool_id);
+	  }: remove_liquidity(RawOrigin::Signed(owner), pool_id, 0.into(), BTreeMap::from([(project, 0.into()), (usdt, 0.into())]))
+
+	buy_lbp {
+		let project = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_lbp_pool::<T>(owner.clone(), CurrencyPair::new(project, usdt));
+		let unit = 1_000_000_000_000;
+		// 100_000_000 PROJECT , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// buy 100 PROJECT, priced ~9 USDT
+	 }: buy(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(project, (100_u128 * unit).into()), false, None)
+
+	swap_lbp {
+		let project = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_lbp_pool::<T>(owner.clone(), CurrencyPair::new(project, usdt));
+		let unit = 1_000_000_000_000;
+		// 100_000_000 PROJECT , 100_000_000 USDT
+		let initial_liquidity = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		assert_ok!(<Pablo<T> as Amm>::add_liquidity(&owner, pool_id, initial_liquidity, 0.into(), false));
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// swap 1000 USDT
+	 }: swap(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(project, 0.into()), false, None)
+
+	create_concentrated_liquidity {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let init = concentrated_liquidity_init_config::<T>(owner, CurrencyPair::new(usdc, usdt), Permill::from_percent(1));
+	  }: create(RawOrigin::Root, init)
+
+	open_position {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let assets = mint_pool_assets::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+	  }: _(RawOrigin::Signed(owner), pool_id, -6_000, 6_000, assets, 0, false)
+
+	close_position {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let position_id = open_position::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// swap 1000 USDT, so that the position earns fees in USDT
+		assert_ok!(<Pablo<T> as Amm>::do_swap(&user, pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false));
+	  }: _(RawOrigin::Signed(owner), pool_id, position_id, BTreeMap::new())
+
+	collect_position_fees {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+		let unit = 1_000_000_000_000;
+		// 100_000_000 USDC , 100_000_000 USDT
+		let position_id = open_position::<T>(pool_id, &owner, (100_000_000_u128 * unit).into());
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (1000_u128 * unit).into()));
+		// swap 1000 USDT, so that the position earns fees in USDT
+		assert_ok!(<Pablo<T> as Amm>::do_swap(&user, pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, 0.into()), false));
+	  }: _(RawOrigin::Signed(owner), pool_id, position_id)
+
+	buy_concentrated_liquidity {
+		// steps of the swap, the last one doesn't cross any tick
+		let s in 1 .. T::MaxSwapSteps::get() - 1;
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+		let unit = 1_000_000_000_000;
+		let amount = open_adjacent_positions::<T>(pool_id, &owner, usdc, s, unit);
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (2_000_u128 * s as u128 * unit).into()));
+	 }: buy(RawOrigin::Signed(user), pool_id, usdt, AssetAmount::new(usdc, amount), false, None)
+
+	swap_concentrated_liquidity {
+		// steps of the swap, the last one doesn't cross any tick
+		let s in 1 .. T::MaxSwapSteps::get() - 1;
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let owner: T::AccountId = whitelisted_caller();
+		let pool_id = create_concentrated_liquidity_pool::<T>(owner.clone(), CurrencyPair::new(usdc, usdt), 1000.into());
+		let unit = 1_000_000_000_000;
+		let amount = open_adjacent_positions::<T>(pool_id, &owner, usdc, s, unit);
+		let user = account("user", 0, 0);
+		assert_ok!(T::Assets::mint_into(usdt, &user, (2_000_u128 * s as u128 * unit).into()));
+		let cost = buy_cost::<T>(&user, pool_id, usdt, AssetAmount::new(usdc, amount));
+	 }: swap(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, cost), AssetAmount::new(usdc, 0.into()), false, None)
+
+	place_limit_order {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let unit = 1_000_000_000_000;
+		let (pool_id, user) = create_limit_order_pool::<T>(usdc, usdt, unit);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10_u32.into();
+	 }: _(RawOrigin::Signed(user), pool_id, AssetAmount::new(usdt, (1000_u128 * unit).into()), AssetAmount::new(usdc, (900_u128 * unit).into()), expires_at)
+
+	cancel_limit_order {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let unit = 1_000_000_000_000;
+		let (pool_id, user) = create_limit_order_pool::<T>(usdc, usdt, unit);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10_u32.into();
+		let order_id = Pablo::<T>::do_place_limit_order(
+			&user,
+			pool_id,
+			AssetAmount::new(usdt, (1000_u128 * unit).into()),
+			AssetAmount::new(usdc, (900_u128 * unit).into()),
+			expires_at,
+		).expect("impossible; qed;");
+	 }: _(RawOrigin::Signed(user), pool_id, order_id)
+
+	fill_limit_order {
+		let usdc = 100.into();
+		let usdt = 101.into();
+		let unit = 1_000_000_000_000;
+		let (pool_id, user) = create_limit_order_pool::<T>(usdc, usdt, unit);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10_u32.into();
+		let order_id = Pablo::<T>::do_place_limit_order(
+			&user,
+			pool_id,
+			AssetAmount::new(usdt, (1000_u128 * unit).into()),
+			AssetAmount::new(usdc, (900_u128 * unit).into()),
+			expires_at,
+		).expect("impossible; qed;");
+	}: {
+		Pablo::<T>::fill_limit_orders(Weight::from_ref_time(u64::MAX));
+	}
+	verify {
+		assert!(LimitOrders::<T>::get(pool_id, order_id).is_none());
+	}
 
 	do_create_pool {
 		let usdc = 100.into();
@@ -0,0 +1,719 @@
+use crate::{
+	types::{ConcentratedLiquidityState, FeeGrowth, Position, TickInfo},
+	AssetIdOf, ConcentratedLiquidityStates, ConcentratedLiquidityTickBitmap,
+	ConcentratedLiquidityTicks, Config, Error, Pallet, PoolConfiguration, PoolCount, Pools,
+	Positions,
+};
+use composable_maths::dex::concentrated_liquidity::{
+	amounts_for_liquidity, compute_swap_step, fee_growth, fees_for_liquidity,
+	liquidity_for_amounts, price_from_sqrt_price, sqrt_price_at_tick, tick_at_sqrt_price, MAX_TICK,
+	MIN_TICK,
+};
+use composable_support::math::safe::{SafeAdd, SafeSub};
+use composable_traits::{
+	currency::{CurrencyFactory, RangeId},
+	defi::{CurrencyPair, Rate},
+	dex::{AssetAmount, ConcentratedLiquidityPoolInfo, Fee, FeeConfig},
+	fnft::FinancialNft,
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungibles::{Inspect, Transfer},
+		tokens::nonfungibles::{
+			Create, Inspect as NonFungiblesInspect, Mutate as NonFungiblesMutate,
+		},
+	},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	ArithmeticError, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+pub(crate) type ConcentratedLiquidityPoolInfoOf<T> =
+	ConcentratedLiquidityPoolInfo<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
+
+/// Number of ticks tracked by a single word of the tick bitmap.
+const TICKS_PER_WORD: i32 = 128;
+
+// Concentrated Liquidity Pool, in which liquidity is provided within a price range
+pub(crate) struct ConcentratedLiquidity<T>(PhantomData<T>);
+
+impl<T: Config> ConcentratedLiquidity<T> {
+	/// Positions are not fungible, both assets are reported as being weighted equally.
+	pub(crate) fn assets_weights(pair: &CurrencyPair<T::AssetId>) -> BTreeMap<T::AssetId, Permill> {
+		BTreeMap::from([
+			(pair.base, Permill::from_percent(50)),
+			(pair.quote, Permill::from_percent(50)),
+		])
+	}
+
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		pair: CurrencyPair<T::AssetId>,
+		fee_config: FeeConfig,
+		tick_spacing: u32,
+		initial_tick: i32,
+		position_collection_id: Option<AssetIdOf<T>>,
+	) -> Result<T::PoolId, DispatchError> {
+		ensure!(pair.base != pair.quote, Error::<T>::InvalidPair);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+		ensure!(
+			tick_spacing > 0 && tick_spacing <= MAX_TICK.unsigned_abs(),
+			Error::<T>::InvalidTickRange
+		);
+		ensure!((MIN_TICK..=MAX_TICK).contains(&initial_tick), Error::<T>::InvalidTickRange);
+
+		let position_collection =
+			position_collection_id.unwrap_or(T::CurrencyFactory::create(RangeId::FNFT_ASSETS)?);
+
+		let pool_id =
+			PoolCount::<T>::try_mutate(|pool_count| -> Result<T::PoolId, DispatchError> {
+				let pool_id = *pool_count;
+				Pools::<T>::insert(
+					pool_id,
+					PoolConfiguration::ConcentratedLiquidity(ConcentratedLiquidityPoolInfo {
+						owner: who.clone(),
+						pair,
+						tick_spacing,
+						position_collection,
+						fee_config,
+					}),
+				);
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok(pool_id)
+			})?;
+
+		ConcentratedLiquidityStates::<T>::insert(
+			pool_id,
+			ConcentratedLiquidityState {
+				sqrt_price: sqrt_price_at_tick(initial_tick)?,
+				tick: initial_tick,
+				liquidity: 0,
+				fee_growth_global: FeeGrowth::default(),
+			},
+		);
+
+		// the pool account administers the positions
+		let pool_account = Pallet::<T>::account_id(&pool_id);
+		T::FinancialNft::create_collection(&position_collection, &pool_account, &pool_account)?;
+
+		Ok(pool_id)
+	}
+
+	fn get_state(pool_id: &T::PoolId) -> Result<ConcentratedLiquidityState, DispatchError> {
+		ConcentratedLiquidityStates::<T>::get(pool_id)
+			.ok_or_else(|| Error::<T>::PoolNotFound.into())
+	}
+
+	fn tick_spacing(pool: &ConcentratedLiquidityPoolInfoOf<T>) -> Result<i32, DispatchError> {
+		Ok(i32::try_from(pool.tick_spacing).map_err(|_| Error::<T>::InvalidTickRange)?)
+	}
+
+	fn ensure_valid_range(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		lower_tick: i32,
+		upper_tick: i32,
+	) -> Result<(), DispatchError> {
+		let tick_spacing = Self::tick_spacing(pool)?;
+		ensure!(
+			lower_tick < upper_tick &&
+				lower_tick >= MIN_TICK &&
+				upper_tick <= MAX_TICK &&
+				lower_tick % tick_spacing == 0 &&
+				upper_tick % tick_spacing == 0,
+			Error::<T>::InvalidTickRange
+		);
+		Ok(())
+	}
+
+	/// Ensures that `who` owns the financial NFT of the position.
+	fn get_position(
+		who: &T::AccountId,
+		pool_id: &T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		position_id: &T::FinancialNftInstanceId,
+	) -> Result<Position, DispatchError> {
+		let position =
+			Positions::<T>::get(pool_id, position_id).ok_or(Error::<T>::PositionNotFound)?;
+		ensure!(
+			T::FinancialNft::owner(&pool.position_collection, position_id).as_ref() == Some(who),
+			Error::<T>::MustBeOwner
+		);
+		Ok(position)
+	}
+
+	/// Fees earned between `lower_tick` and `upper_tick`.
+	fn fee_growth_inside(
+		pool_id: &T::PoolId,
+		state: &ConcentratedLiquidityState,
+		lower_tick: i32,
+		upper_tick: i32,
+	) -> FeeGrowth {
+		let outside = |tick| {
+			ConcentratedLiquidityTicks::<T>::get(pool_id, tick)
+				.map(|info| info.fee_growth_outside)
+				.unwrap_or_default()
+		};
+		let global = state.fee_growth_global;
+		let below = if state.tick >= lower_tick {
+			outside(lower_tick)
+		} else {
+			global.wrapping_sub(&outside(lower_tick))
+		};
+		let above = if state.tick < upper_tick {
+			outside(upper_tick)
+		} else {
+			global.wrapping_sub(&outside(upper_tick))
+		};
+		global.wrapping_sub(&below).wrapping_sub(&above)
+	}
+
+	/// Word of the tick bitmap holding the tick `compressed` times the tick spacing, and the bit
+	/// of the tick within the word.
+	fn bitmap_position(compressed: i32) -> (i32, i32) {
+		(compressed.div_euclid(TICKS_PER_WORD), compressed.rem_euclid(TICKS_PER_WORD))
+	}
+
+	/// Marks `tick` as initialized in the tick bitmap of the pool if it wasn't, and conversely.
+	fn flip_tick(pool_id: &T::PoolId, tick_spacing: i32, tick: i32) {
+		let (word, bit) = Self::bitmap_position(tick.div_euclid(tick_spacing));
+		ConcentratedLiquidityTickBitmap::<T>::mutate_exists(pool_id, word, |bits| {
+			let flipped = bits.unwrap_or_default() ^ (1_u128 << bit);
+			*bits = (flipped != 0).then_some(flipped);
+		});
+	}
+
+	/// Closest initialized tick to `tick` within the same word of the tick bitmap, at or below
+	/// `tick` when `lte`, above it otherwise. If there is none, the last tick of the word in
+	/// the direction of the search is returned, as uninitialized.
+	fn next_initialized_tick_within_word(
+		pool_id: &T::PoolId,
+		tick_spacing: i32,
+		tick: i32,
+		lte: bool,
+	) -> (i32, bool) {
+		let compressed = tick.div_euclid(tick_spacing);
+		let bitmap =
+			|word| ConcentratedLiquidityTickBitmap::<T>::get(pool_id, word).unwrap_or_default();
+		let (next, initialized) = if lte {
+			let (word, bit) = Self::bitmap_position(compressed);
+			let masked = bitmap(word) & (u128::MAX >> (TICKS_PER_WORD - 1 - bit));
+			if masked != 0 {
+				let most_significant_bit = TICKS_PER_WORD - 1 - masked.leading_zeros() as i32;
+				(compressed - (bit - most_significant_bit), true)
+			} else {
+				(compressed - bit, false)
+			}
+		} else {
+			let (word, bit) = Self::bitmap_position(compressed + 1);
+			let masked = bitmap(word) & (u128::MAX << bit);
+			if masked != 0 {
+				(compressed + 1 + (masked.trailing_zeros() as i32 - bit), true)
+			} else {
+				(compressed + 1 + (TICKS_PER_WORD - 1 - bit), false)
+			}
+		};
+		(next * tick_spacing, initialized)
+	}
+
+	fn update_tick(
+		pool_id: &T::PoolId,
+		tick_spacing: i32,
+		state: &ConcentratedLiquidityState,
+		tick: i32,
+		liquidity_delta: i128,
+		is_upper: bool,
+	) -> Result<(), DispatchError> {
+		let existing = ConcentratedLiquidityTicks::<T>::get(pool_id, tick);
+		if existing.is_none() {
+			Self::flip_tick(pool_id, tick_spacing, tick);
+		}
+		let mut info = existing.unwrap_or_else(|| TickInfo {
+			// by convention, all of the fees earned so far were earned below the tick
+			fee_growth_outside: if tick <= state.tick {
+				state.fee_growth_global
+			} else {
+				FeeGrowth::default()
+			},
+			..Default::default()
+		});
+		info.liquidity_gross = if liquidity_delta >= 0 {
+			info.liquidity_gross.checked_add(liquidity_delta.unsigned_abs())
+		} else {
+			info.liquidity_gross.checked_sub(liquidity_delta.unsigned_abs())
+		}
+		.ok_or(ArithmeticError::Overflow)?;
+		// liquidity is removed when crossing an upper tick upwards
+		info.liquidity_net = if is_upper {
+			info.liquidity_net.checked_sub(liquidity_delta)
+		} else {
+			info.liquidity_net.checked_add(liquidity_delta)
+		}
+		.ok_or(ArithmeticError::Overflow)?;
+
+		ConcentratedLiquidityTicks::<T>::insert(pool_id, tick, info);
+		Ok(())
+	}
+
+	/// Adds `liquidity_delta` to the range of a position, returning the fees earned within the
+	/// range. Ticks that no longer bound any position are dropped.
+	fn modify_position(
+		pool_id: &T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		state: &mut ConcentratedLiquidityState,
+		lower_tick: i32,
+		upper_tick: i32,
+		liquidity_delta: i128,
+	) -> Result<FeeGrowth, DispatchError> {
+		let tick_spacing = Self::tick_spacing(pool)?;
+		Self::update_tick(pool_id, tick_spacing, state, lower_tick, liquidity_delta, false)?;
+		Self::update_tick(pool_id, tick_spacing, state, upper_tick, liquidity_delta, true)?;
+		let fee_growth_inside = Self::fee_growth_inside(pool_id, state, lower_tick, upper_tick);
+
+		for tick in [lower_tick, upper_tick] {
+			if ConcentratedLiquidityTicks::<T>::get(pool_id, tick)
+				.map_or(false, |info| info.liquidity_gross == 0)
+			{
+				ConcentratedLiquidityTicks::<T>::remove(pool_id, tick);
+				Self::flip_tick(pool_id, tick_spacing, tick);
+			}
+		}
+
+		if lower_tick <= state.tick && state.tick < upper_tick {
+			state.liquidity = if liquidity_delta >= 0 {
+				state.liquidity.checked_add(liquidity_delta.unsigned_abs())
+			} else {
+				state.liquidity.checked_sub(liquidity_delta.unsigned_abs())
+			}
+			.ok_or(ArithmeticError::Overflow)?;
+		}
+
+		Ok(fee_growth_inside)
+	}
+
+	/// Moves the fees earned by a position since they were last credited from the pool into the
+	/// asset account of the position's financial NFT.
+	fn credit_fees(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		position_id: &T::FinancialNftInstanceId,
+		position: &mut Position,
+		fee_growth_inside: FeeGrowth,
+	) -> Result<(), DispatchError> {
+		let earned = fee_growth_inside.wrapping_sub(&position.fee_growth_inside_last);
+		let position_account =
+			T::FinancialNft::asset_account(&pool.position_collection, position_id);
+		for (asset_id, growth) in [(pool.pair.base, earned.base), (pool.pair.quote, earned.quote)] {
+			let fees = fees_for_liquidity(position.liquidity, growth)?;
+			if fees != 0 {
+				T::Assets::transfer(
+					asset_id,
+					pool_account,
+					&position_account,
+					T::Convert::convert(fees),
+					false, // pool account doesn't need to be kept alive
+				)?;
+			}
+		}
+		position.fee_growth_inside_last = fee_growth_inside;
+		Ok(())
+	}
+
+	/// Transfers all of the fees held by the asset account of a position to `who`.
+	fn withdraw_fees(
+		who: &T::AccountId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		position_id: &T::FinancialNftInstanceId,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let position_account =
+			T::FinancialNft::asset_account(&pool.position_collection, position_id);
+		[pool.pair.base, pool.pair.quote]
+			.into_iter()
+			.map(|asset_id| {
+				let amount = T::Assets::balance(asset_id, &position_account);
+				if !amount.is_zero() {
+					T::Assets::transfer(asset_id, &position_account, who, amount, false)?;
+				}
+				Ok((asset_id, amount))
+			})
+			.collect()
+	}
+
+	/// Provides liquidity between `lower_tick` and `upper_tick` with at most the given amounts,
+	/// minting a financial NFT representing the position to `who`.
+	#[allow(clippy::too_many_arguments)]
+	pub(crate) fn open_position(
+		who: &T::AccountId,
+		pool_id: T::PoolId,
+		pool: ConcentratedLiquidityPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		lower_tick: i32,
+		upper_tick: i32,
+		assets: BTreeMap<T::AssetId, T::Balance>,
+		min_liquidity: u128,
+		keep_alive: bool,
+	) -> Result<(T::FinancialNftInstanceId, u128, BTreeMap<T::AssetId, T::Balance>), DispatchError>
+	{
+		Self::ensure_valid_range(&pool, lower_tick, upper_tick)?;
+		ensure!(!assets.is_empty(), Error::<T>::MustDepositMinimumOneAsset);
+		ensure!(
+			assets
+				.keys()
+				.all(|asset_id| *asset_id == pool.pair.base || *asset_id == pool.pair.quote),
+			Error::<T>::AssetNotFound
+		);
+		let amount_of = |asset_id| {
+			T::Convert::convert(assets.get(&asset_id).copied().unwrap_or_else(Zero::zero))
+		};
+		let (base_amount, quote_amount): (u128, u128) =
+			(amount_of(pool.pair.base), amount_of(pool.pair.quote));
+
+		let mut state = Self::get_state(&pool_id)?;
+		let (sqrt_price_lower, sqrt_price_upper) =
+			(sqrt_price_at_tick(lower_tick)?, sqrt_price_at_tick(upper_tick)?);
+
+		let liquidity = liquidity_for_amounts(
+			state.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			base_amount,
+			quote_amount,
+		)?;
+		ensure!(liquidity > 0, Error::<T>::InvalidAmount);
+		ensure!(liquidity >= min_liquidity, Error::<T>::CannotRespectMinimumRequested);
+		let (base_deposit, quote_deposit) = amounts_for_liquidity(
+			state.sqrt_price,
+			sqrt_price_lower,
+			sqrt_price_upper,
+			liquidity,
+			true,
+		)?;
+		ensure!(
+			base_deposit <= base_amount && quote_deposit <= quote_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		let fee_growth_inside = Self::modify_position(
+			&pool_id,
+			&pool,
+			&mut state,
+			lower_tick,
+			upper_tick,
+			i128::try_from(liquidity).map_err(|_| ArithmeticError::Overflow)?,
+		)?;
+
+		let deposited = BTreeMap::from([
+			(pool.pair.base, T::Convert::convert(base_deposit)),
+			(pool.pair.quote, T::Convert::convert(quote_deposit)),
+		]);
+		for (asset_id, amount) in &deposited {
+			if !amount.is_zero() {
+				T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+			}
+		}
+
+		let position_id = T::FinancialNft::get_next_nft_id(&pool.position_collection)?;
+		T::FinancialNft::mint_into(&pool.position_collection, &position_id, who)?;
+		Positions::<T>::insert(
+			pool_id,
+			position_id,
+			Position {
+				lower_tick,
+				upper_tick,
+				liquidity,
+				fee_growth_inside_last: fee_growth_inside,
+			},
+		);
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+
+		Ok((position_id, liquidity, deposited))
+	}
+
+	/// Withdraws all of the liquidity and fees of a position to `who`, and burns its financial
+	/// NFT. Returns the withdrawn liquidity and fees.
+	#[allow(clippy::type_complexity)]
+	pub(crate) fn close_position(
+		who: &T::AccountId,
+		pool_id: T::PoolId,
+		pool: ConcentratedLiquidityPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		position_id: T::FinancialNftInstanceId,
+		mut min_receive: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<(BTreeMap<T::AssetId, T::Balance>, BTreeMap<T::AssetId, T::Balance>), DispatchError>
+	{
+		let mut position = Self::get_position(who, &pool_id, &pool, &position_id)?;
+		let mut state = Self::get_state(&pool_id)?;
+
+		let fee_growth_inside = Self::modify_position(
+			&pool_id,
+			&pool,
+			&mut state,
+			position.lower_tick,
+			position.upper_tick,
+			-i128::try_from(position.liquidity).map_err(|_| ArithmeticError::Overflow)?,
+		)?;
+		Self::credit_fees(&pool, &pool_account, &position_id, &mut position, fee_growth_inside)?;
+
+		let (base_amount, quote_amount) = amounts_for_liquidity(
+			state.sqrt_price,
+			sqrt_price_at_tick(position.lower_tick)?,
+			sqrt_price_at_tick(position.upper_tick)?,
+			position.liquidity,
+			false,
+		)?;
+		let withdrawn = [(pool.pair.base, base_amount), (pool.pair.quote, quote_amount)]
+			.into_iter()
+			.map(|(asset_id, amount)| {
+				let amount = T::Convert::convert(amount);
+				if let Some(min_amount) = min_receive.remove(&asset_id) {
+					ensure!(amount >= min_amount, Error::<T>::CannotRespectMinimumRequested);
+				}
+				Ok((asset_id, amount))
+			})
+			.collect::<Result<BTreeMap<_, _>, DispatchError>>()?;
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (asset_id, amount) in &withdrawn {
+			if !amount.is_zero() {
+				T::Assets::transfer(
+					*asset_id,
+					&pool_account,
+					who,
+					*amount,
+					false, // pool account doesn't need to be kept alive
+				)?;
+			}
+		}
+		let fees = Self::withdraw_fees(who, &pool, &position_id)?;
+
+		T::FinancialNft::burn(&pool.position_collection, &position_id, Some(who))?;
+		Positions::<T>::remove(pool_id, position_id);
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+
+		Ok((withdrawn, fees))
+	}
+
+	/// Withdraws the fees earned by a position to `who`, leaving its liquidity untouched.
+	pub(crate) fn collect_fees(
+		who: &T::AccountId,
+		pool_id: T::PoolId,
+		pool: ConcentratedLiquidityPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		position_id: T::FinancialNftInstanceId,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let mut position = Self::get_position(who, &pool_id, &pool, &position_id)?;
+		let state = Self::get_state(&pool_id)?;
+
+		let fee_growth_inside =
+			Self::fee_growth_inside(&pool_id, &state, position.lower_tick, position.upper_tick);
+		Self::credit_fees(&pool, &pool_account, &position_id, &mut position, fee_growth_inside)?;
+		let fees = Self::withdraw_fees(who, &pool, &position_id)?;
+		Positions::<T>::insert(pool_id, position_id, position);
+
+		Ok(fees)
+	}
+
+	/// Price of the base asset, denominated in the quote asset.
+	pub(crate) fn price(pool_id: &T::PoolId) -> Result<Rate, DispatchError> {
+		Ok(price_from_sqrt_price(Self::get_state(pool_id)?.sqrt_price)?)
+	}
+
+	/// Swaps through the initialized ticks of the pool until `amount` is exhausted, returning the
+	/// total amount in (fee included), amount out and fee along with the state of the pool and
+	/// the ticks crossed by the swap.
+	///
+	/// With `exact_in`, `amount` is denominated in the input asset, otherwise in the output asset.
+	/// Each step of the swap stops at the next initialized tick or at the end of the word of the
+	/// tick bitmap, the swap fails if it doesn't complete within `T::MaxSwapSteps` steps.
+	#[allow(clippy::type_complexity)]
+	fn compute_swap(
+		pool_id: &T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		base_for_quote: bool,
+		amount: u128,
+		exact_in: bool,
+		apply_fees: bool,
+	) -> Result<(u128, u128, u128, ConcentratedLiquidityState, Vec<(i32, TickInfo)>), DispatchError>
+	{
+		let mut state = Self::get_state(pool_id)?;
+		let tick_spacing = Self::tick_spacing(pool)?;
+		let mut crossed_ticks = Vec::new();
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (mut remaining, mut total_in, mut total_out, mut total_fee) =
+			(amount, 0_u128, 0_u128, 0_u128);
+
+		let mut steps = 0;
+		while remaining > 0 {
+			ensure!(steps < T::MaxSwapSteps::get(), Error::<T>::SwapTooLarge);
+			steps += 1;
+
+			let (next_tick, initialized) = Self::next_initialized_tick_within_word(
+				pool_id,
+				tick_spacing,
+				state.tick,
+				base_for_quote,
+			);
+			let next_tick = next_tick.clamp(MIN_TICK, MAX_TICK);
+			let sqrt_price_target = sqrt_price_at_tick(next_tick)?;
+
+			let step = compute_swap_step(
+				state.sqrt_price,
+				sqrt_price_target,
+				state.liquidity,
+				remaining,
+				exact_in,
+				fee,
+			)?;
+			remaining = if exact_in {
+				remaining.safe_sub(&step.amount_in.safe_add(&step.fee)?)?
+			} else if step.sqrt_price == sqrt_price_target {
+				remaining.safe_sub(&step.amount_out)?
+			} else {
+				// the step was sized for the whole remaining amount
+				0
+			};
+			total_in = total_in.safe_add(&step.amount_in)?.safe_add(&step.fee)?;
+			total_out = total_out.safe_add(&step.amount_out)?;
+			total_fee = total_fee.safe_add(&step.fee)?;
+
+			// owner fees are not disbursed yet, the whole fee goes to the liquidity providers
+			if state.liquidity > 0 && step.fee > 0 {
+				let growth = fee_growth(step.fee, state.liquidity)?;
+				if base_for_quote {
+					state.fee_growth_global.base =
+						state.fee_growth_global.base.wrapping_add(growth);
+				} else {
+					state.fee_growth_global.quote =
+						state.fee_growth_global.quote.wrapping_add(growth);
+				}
+			}
+
+			state.sqrt_price = step.sqrt_price;
+			let reached_target = step.sqrt_price == sqrt_price_target;
+			match next_tick {
+				tick if reached_target && initialized => {
+					let mut info = ConcentratedLiquidityTicks::<T>::get(pool_id, tick)
+						.ok_or(Error::<T>::NotEnoughLiquidity)?;
+					info.fee_growth_outside =
+						state.fee_growth_global.wrapping_sub(&info.fee_growth_outside);
+					let liquidity_net =
+						if base_for_quote { -info.liquidity_net } else { info.liquidity_net };
+					state.liquidity = if liquidity_net >= 0 {
+						state.liquidity.checked_add(liquidity_net.unsigned_abs())
+					} else {
+						state.liquidity.checked_sub(liquidity_net.unsigned_abs())
+					}
+					.ok_or(ArithmeticError::Overflow)?;
+					crossed_ticks.push((tick, info));
+					state.tick = if base_for_quote { tick - 1 } else { tick };
+				},
+				MIN_TICK | MAX_TICK if reached_target => {
+					// the price reached the edge of the ticks, there is no liquidity beyond it
+					ensure!(remaining == 0, Error::<T>::NotEnoughLiquidity);
+					state.tick = tick_at_sqrt_price(state.sqrt_price)?;
+				},
+				// end of the word of the tick bitmap, no liquidity changes
+				tick if reached_target => state.tick = if base_for_quote { tick - 1 } else { tick },
+				_ => state.tick = tick_at_sqrt_price(state.sqrt_price)?,
+			}
+		}
+
+		Ok((total_in, total_out, total_fee, state, crossed_ticks))
+	}
+
+	fn commit_swap(
+		pool_id: &T::PoolId,
+		state: ConcentratedLiquidityState,
+		crossed_ticks: Vec<(i32, TickInfo)>,
+	) {
+		ConcentratedLiquidityStates::<T>::insert(pool_id, state);
+		for (tick, info) in crossed_ticks {
+			ConcentratedLiquidityTicks::<T>::insert(pool_id, tick, info);
+		}
+	}
+
+	fn swap_direction(
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		in_asset_id: T::AssetId,
+		out_asset_id: T::AssetId,
+	) -> Result<bool, DispatchError> {
+		let pair = CurrencyPair::new(in_asset_id, out_asset_id);
+		if pair == pool.pair {
+			Ok(true)
+		} else if pair.swap() == pool.pair {
+			Ok(false)
+		} else {
+			Err(Error::<T>::AssetNotFound.into())
+		}
+	}
+
+	/// Computes the outcome of swapping `in_asset`, without updating the pool. Pass
+	/// `commit = true` to update the pool with the outcome of the swap.
+	pub(crate) fn get_exchange_value(
+		pool_id: &T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+		commit: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let base_for_quote = Self::swap_direction(pool, in_asset.asset_id, out_asset_id)?;
+		let (_, amount_out, fee, state, crossed_ticks) = Self::compute_swap(
+			pool_id,
+			pool,
+			base_for_quote,
+			T::Convert::convert(in_asset.amount),
+			true,
+			apply_fees,
+		)?;
+		if commit {
+			Self::commit_swap(pool_id, state, crossed_ticks);
+		}
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amount_out));
+		let fee = pool.fee_config.calculate_fees(in_asset.asset_id, T::Convert::convert(fee));
+
+		Ok((a_out, in_asset, fee))
+	}
+
+	/// Buys `out_asset`, updating the pool with the outcome of the swap.
+	pub(crate) fn do_buy(
+		pool_id: &T::PoolId,
+		pool: &ConcentratedLiquidityPoolInfoOf<T>,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let base_for_quote = Self::swap_direction(pool, in_asset_id, out_asset.asset_id)?;
+		let (amount_in, _, fee, state, crossed_ticks) = Self::compute_swap(
+			pool_id,
+			pool,
+			base_for_quote,
+			T::Convert::convert(out_asset.amount),
+			false,
+			apply_fees,
+		)?;
+		Self::commit_swap(pool_id, state, crossed_ticks);
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amount_in));
+		let fee = pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
@@ -43,29 +43,49 @@ pub mod weights;
 #[cfg(any(feature = "runtime-benchmarks", test))]
 mod benchmarking;
 
+mod concentrated_liquidity;
 mod dual_asset_constant_product;
+mod liquidity_bootstrapping;
+mod stable_swap;
 mod twap;
 mod types;
+mod weighted_product;
 
 pub use crate::weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use crate::{
+		concentrated_liquidity::ConcentratedLiquidity,
 		dual_asset_constant_product::DualAssetConstantProduct,
-		twap::{update_price_cumulative_state, update_twap_state},
-		types::{PriceCumulative, TimeWeightedAveragePrice},
+		liquidity_bootstrapping::LiquidityBootstrapping,
+		stable_swap::StableSwap,
+		twap::{
+			update_multi_asset_price_cumulative_state, update_multi_asset_twap_state,
+			update_price_cumulative_state, update_twap_state,
+		},
+		types::{
+			ConcentratedLiquidityState, LimitOrder, MultiAssetPriceCumulative,
+			MultiAssetTimeWeightedAveragePrice, Position, PriceCumulative, SwapDeadline, TickInfo,
+			TimeWeightedAveragePrice,
+		},
+		weighted_product::WeightedProduct,
 		WeightInfo,
 	};
 	use codec::FullCodec;
 	use composable_support::{
 		collections::vec::bounded::{bi_bounded_vec::BiBoundedVecOutOfBounds, BiBoundedVec},
-		math::safe::{SafeArithmetic, SafeSub},
+		math::safe::{safe_multiply_by_rational, SafeArithmetic, SafeSub},
 	};
 	use composable_traits::{
 		currency::{CurrencyFactory, LocalAssets},
-		defi::{CurrencyPair, Rate},
-		dex::{Amm, BasicPoolInfo, Fee, PriceAggregate},
+		defi::{CurrencyPair, Rate, Ratio},
+		dex::{
+			Amm, BasicPoolInfo, ConcentratedLiquidityPoolInfo, Fee, LiquidityBootstrappingPoolInfo,
+			PriceAggregate, Sale, SaleState, StableSwapPoolInfo,
+		},
+		fnft::FinancialNft,
+		oracle::LiquidityPools,
 	};
 	use core::fmt::Debug;
 	use frame_support::{
@@ -73,14 +93,17 @@ pub mod pallet {
 		storage::with_transaction,
 		traits::{
 			fungibles::{Inspect, Mutate, Transfer},
+			tokens::nonfungibles::{Create, Mutate as NonFungiblesMutate},
 			Time,
 		},
-		transactional, BoundedBTreeMap, PalletId, RuntimeDebug,
+		transactional, BoundedBTreeMap, EqNoBound, PalletId, RuntimeDebugNoBound,
 	};
 	use sp_arithmetic::FixedPointOperand;
 
 	use composable_maths::dex::{
-		constant_product::compute_redeemed_for_lp, price::compute_initial_price_cumulative,
+		constant_product::{compute_equilibrium_balances, compute_redeemed_for_lp},
+		price::compute_initial_price_cumulative,
+		stable_swap::{compute_d, compute_spot_price},
 	};
 	use composable_traits::{
 		currency::BalanceLike,
@@ -94,35 +117,124 @@ pub mod pallet {
 	use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 	#[derive(
-		RuntimeDebug, Encode, Decode, MaxEncodedLen, CloneNoBound, PartialEq, Eq, TypeInfo,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		MaxEncodedLen,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		TypeInfo,
 	)]
-	pub enum PoolInitConfiguration<AccountId: Clone, AssetId: Clone> {
+	#[scale_info(skip_type_params(MaxAssets))]
+	pub enum PoolInitConfiguration<
+		AccountId: Clone + PartialEq + Debug,
+		AssetId: Clone + Ord + Debug,
+		MaxAssets: Get<u32>,
+		Moment: Clone + PartialEq + Debug,
+	> {
 		DualAssetConstantProduct {
 			owner: AccountId,
 			assets_weights: BoundedBTreeMap<AssetId, Permill, ConstU32<2>>,
 			// trading fee
 			fee: Permill,
 		},
+		StableSwap {
+			owner: AccountId,
+			pair: CurrencyPair<AssetId>,
+			/// Amplification coefficient, must be greater than zero.
+			amplification_coefficient: u16,
+			// trading fee
+			fee: Permill,
+		},
+		WeightedProduct {
+			owner: AccountId,
+			/// Between 2 and `MaxAssets` assets, with normalized weights.
+			assets_weights: BoundedBTreeMap<AssetId, Permill, MaxAssets>,
+			// trading fee
+			fee: Permill,
+		},
+		LiquidityBootstrapping {
+			owner: AccountId,
+			/// `base` is the project asset being sold, `quote` the asset it is sold for.
+			pair: CurrencyPair<AssetId>,
+			/// Sale window, with the weights of the project asset at its start and end.
+			sale: Sale<Moment>,
+			// trading fee
+			fee: Permill,
+		},
+		ConcentratedLiquidity {
+			owner: AccountId,
+			pair: CurrencyPair<AssetId>,
+			/// Positions can only be bounded by multiples of the tick spacing.
+			tick_spacing: u32,
+			/// Tick of the initial price of `base`, denominated in `quote`.
+			initial_tick: i32,
+			// trading fee
+			fee: Permill,
+		},
 	}
 
 	#[derive(
-		RuntimeDebug, Encode, Decode, MaxEncodedLen, CloneNoBound, PartialEqNoBound, Eq, TypeInfo,
+		RuntimeDebugNoBound,
+		Encode,
+		Decode,
+		MaxEncodedLen,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		TypeInfo,
 	)]
-	pub enum PoolConfiguration<AccountId: Clone + PartialEq + Debug, AssetId: Clone + Ord + Debug> {
+	#[scale_info(skip_type_params(MaxAssets))]
+	pub enum PoolConfiguration<
+		AccountId: Clone + PartialEq + Debug,
+		AssetId: Clone + Ord + Debug,
+		MaxAssets: Get<u32>,
+		Moment: Clone + PartialEq + Debug,
+	> {
 		DualAssetConstantProduct(BasicPoolInfo<AccountId, AssetId, ConstU32<2>>),
+		StableSwap(StableSwapPoolInfo<AccountId, AssetId, ConstU32<2>>),
+		WeightedProduct(BasicPoolInfo<AccountId, AssetId, MaxAssets>),
+		LiquidityBootstrapping(LiquidityBootstrappingPoolInfo<AccountId, AssetId, Moment>),
+		ConcentratedLiquidity(ConcentratedLiquidityPoolInfo<AccountId, AssetId>),
 	}
 
 	pub(crate) type AssetIdOf<T> = <T as Config>::AssetId;
 	pub(crate) type BalanceOf<T> = <T as Config>::Balance;
 	pub(crate) type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-	type PoolConfigurationOf<T> =
-		PoolConfiguration<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
-	pub(crate) type PoolInitConfigurationOf<T> =
-		PoolInitConfiguration<<T as frame_system::Config>::AccountId, <T as Config>::AssetId>;
+	type PoolConfigurationOf<T> = PoolConfiguration<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::AssetId,
+		<T as Config>::MaxAssetsPerPool,
+		MomentOf<T>,
+	>;
+	pub(crate) type PoolInitConfigurationOf<T> = PoolInitConfiguration<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::AssetId,
+		<T as Config>::MaxAssetsPerPool,
+		MomentOf<T>,
+	>;
 	pub(crate) type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+	pub(crate) type SwapDeadlineOf<T> =
+		SwapDeadline<<T as frame_system::Config>::BlockNumber, MomentOf<T>>;
+	pub(crate) type LimitOrderOf<T> = LimitOrder<
+		AccountIdOf<T>,
+		<T as Config>::AssetId,
+		<T as Config>::Balance,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+	/// Identifier of a limit order, unique across pools.
+	pub type LimitOrderId = u64;
 	pub(crate) type TWAPStateOf<T> = TimeWeightedAveragePrice<MomentOf<T>, <T as Config>::Balance>;
 	pub(crate) type PriceCumulativeStateOf<T> =
 		PriceCumulative<MomentOf<T>, <T as Config>::Balance>;
+	pub(crate) type MultiAssetTWAPStateOf<T> = MultiAssetTimeWeightedAveragePrice<
+		MomentOf<T>,
+		<T as Config>::AssetId,
+		<T as Config>::Balance,
+	>;
+	pub(crate) type MultiAssetPriceCumulativeStateOf<T> =
+		MultiAssetPriceCumulative<MomentOf<T>, <T as Config>::AssetId, <T as Config>::Balance>;
 
 	// TODO (vim): Introduce a  new event for "buy" operation as swap is different.
 	#[pallet::event]
@@ -183,6 +295,93 @@ pub mod pallet {
 			/// Map of asset_id -> twap
 			twaps: BTreeMap<T::AssetId, Rate>,
 		},
+		/// Liquidity provided within a price range of a concentrated liquidity pool.
+		PositionOpened {
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Owner of the position.
+			owner: T::AccountId,
+			/// Financial NFT instance representing the position.
+			position_id: T::FinancialNftInstanceId,
+			/// Lower bound of the price range.
+			lower_tick: i32,
+			/// Upper bound of the price range.
+			upper_tick: i32,
+			/// Liquidity of the position.
+			liquidity: u128,
+			/// Amount(s) of asset(s) deposited.
+			asset_amounts: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Position closed and its financial NFT burned.
+		PositionClosed {
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Owner of the position.
+			owner: T::AccountId,
+			/// Financial NFT instance representing the position.
+			position_id: T::FinancialNftInstanceId,
+			/// Amount(s) of asset(s) withdrawn.
+			asset_amounts: BTreeMap<T::AssetId, T::Balance>,
+			/// Fees earned by the position and not yet collected.
+			fees: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Fees earned by a position collected.
+		PositionFeesCollected {
+			/// Pool id of the position.
+			pool_id: T::PoolId,
+			/// Owner of the position.
+			owner: T::AccountId,
+			/// Financial NFT instance representing the position.
+			position_id: T::FinancialNftInstanceId,
+			/// Amount(s) of fees collected.
+			fees: BTreeMap<T::AssetId, T::Balance>,
+		},
+		/// Limit order placed, its input held in escrow.
+		LimitOrderPlaced {
+			/// Pool id the order swaps on.
+			pool_id: T::PoolId,
+			/// Id of the order.
+			order_id: LimitOrderId,
+			/// Owner of the order.
+			owner: T::AccountId,
+			/// Amount to swap.
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			/// Least amount to receive.
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			/// Last block the order can be filled in.
+			expires_at: T::BlockNumber,
+		},
+		/// Limit order filled and its output paid to the owner.
+		LimitOrderFilled {
+			/// Pool id the order swapped on.
+			pool_id: T::PoolId,
+			/// Id of the order.
+			order_id: LimitOrderId,
+			/// Owner of the order.
+			owner: T::AccountId,
+			/// Amount swapped.
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			/// Amount received.
+			out_asset: AssetAmount<T::AssetId, T::Balance>,
+		},
+		/// Limit order cancelled by its owner and its input refunded.
+		LimitOrderCancelled {
+			/// Pool id the order was placed on.
+			pool_id: T::PoolId,
+			/// Id of the order.
+			order_id: LimitOrderId,
+			/// Owner of the order.
+			owner: T::AccountId,
+		},
+		/// Limit order expired unfilled and its input refunded.
+		LimitOrderExpired {
+			/// Pool id the order was placed on.
+			pool_id: T::PoolId,
+			/// Id of the order.
+			order_id: LimitOrderId,
+			/// Owner of the order.
+			owner: T::AccountId,
+		},
 	}
 
 	#[pallet::error]
@@ -221,6 +420,23 @@ pub mod pallet {
 		CannotSwapSameAsset,
 		/// Cannot buy an asset with itself.
 		CannotBuyAssetWithItself,
+		/// Multi-asset pools must be created with at least two assets.
+		PoolMustContainAtLeastTwoAssets,
+		/// Liquidity of concentrated liquidity pools is held in positions, not lp tokens.
+		NoLpTokenForConcentratedLiquidity,
+		/// Ticks of a position must be ordered, within bounds and multiples of the tick spacing.
+		InvalidTickRange,
+		PositionNotFound,
+		/// The swap crosses more ticks of the pool than can be processed at once.
+		SwapTooLarge,
+		/// The deadline of the swap has passed.
+		DeadlinePassed,
+		/// No limit order with this id waits on the pool.
+		LimitOrderNotFound,
+		/// The pool cannot hold any more limit orders.
+		TooManyLimitOrders,
+		/// Limit orders must expire after the current block.
+		LimitOrderExpiryInThePast,
 	}
 
 	#[pallet::config]
@@ -293,6 +509,57 @@ pub mod pallet {
 		#[pallet::constant]
 		type TWAPInterval: Get<MomentOf<Self>>;
 
+		/// The maximum number of assets a weighted product pool can hold.
+		#[pallet::constant]
+		type MaxAssetsPerPool: Get<u32>;
+
+		/// Minimum duration of the sale of a liquidity bootstrapping pool.
+		#[pallet::constant]
+		type MinSaleDuration: Get<MomentOf<Self>>;
+
+		/// Maximum duration of the sale of a liquidity bootstrapping pool.
+		#[pallet::constant]
+		type MaxSaleDuration: Get<MomentOf<Self>>;
+
+		/// Maximum weight of the project asset at the start of a liquidity bootstrapping sale.
+		#[pallet::constant]
+		type MaxInitialWeight: Get<Permill>;
+
+		/// Minimum weight of the project asset at the end of a liquidity bootstrapping sale.
+		#[pallet::constant]
+		type MinFinalWeight: Get<Permill>;
+
+		/// Financial NFTs representing the positions of concentrated liquidity pools.
+		type FinancialNft: NonFungiblesMutate<AccountIdOf<Self>>
+			+ Create<
+				AccountIdOf<Self>,
+				CollectionId = Self::AssetId,
+				ItemId = Self::FinancialNftInstanceId,
+			> + FinancialNft<
+				AccountIdOf<Self>,
+				CollectionId = Self::AssetId,
+				ItemId = Self::FinancialNftInstanceId,
+			>;
+
+		/// Type representing the unique ID of a position of a concentrated liquidity pool.
+		type FinancialNftInstanceId: Parameter
+			+ Member
+			+ MaxEncodedLen
+			+ Copy
+			+ PartialOrd
+			+ Ord
+			+ From<u64>
+			+ Into<u64>;
+
+		/// The maximum number of steps of a swap through a concentrated liquidity pool, each step
+		/// crossing at most one initialized tick or one word of the tick bitmap.
+		#[pallet::constant]
+		type MaxSwapSteps: Get<u32>;
+
+		/// The maximum number of limit orders resting on a single pool.
+		#[pallet::constant]
+		type MaxLimitOrdersPerPool: Get<u32>;
+
 		type WeightInfo: WeightInfo;
 	}
 
@@ -326,6 +593,85 @@ pub mod pallet {
 	pub type PriceCumulativeState<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::PoolId, PriceCumulativeStateOf<T>, OptionQuery>;
 
+	/// TWAP of pools holding an arbitrary number of assets, keyed by asset.
+	#[pallet::storage]
+	#[pallet::getter(fn multi_asset_twap)]
+	#[pallet::unbounded]
+	pub type MultiAssetTWAPState<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, MultiAssetTWAPStateOf<T>, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn multi_asset_price_cumulative)]
+	#[pallet::unbounded]
+	pub type MultiAssetPriceCumulativeState<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		MultiAssetPriceCumulativeStateOf<T>,
+		OptionQuery,
+	>;
+
+	/// Current price, tick and liquidity of concentrated liquidity pools.
+	#[pallet::storage]
+	pub type ConcentratedLiquidityStates<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, ConcentratedLiquidityState, OptionQuery>;
+
+	/// Ticks bounding at least one position of a concentrated liquidity pool.
+	#[pallet::storage]
+	pub type ConcentratedLiquidityTicks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		Blake2_128Concat,
+		i32,
+		TickInfo,
+		OptionQuery,
+	>;
+
+	/// Bitmap of the initialized ticks of a concentrated liquidity pool, in words of 128 ticks
+	/// spaced by the tick spacing of the pool.
+	#[pallet::storage]
+	pub type ConcentratedLiquidityTickBitmap<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::PoolId, Blake2_128Concat, i32, u128, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn positions)]
+	pub type Positions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		Blake2_128Concat,
+		T::FinancialNftInstanceId,
+		Position,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	pub type LimitOrderCount<T: Config> = StorageValue<_, LimitOrderId, ValueQuery>;
+
+	/// Limit orders waiting to be filled, keyed by the pool they swap on.
+	#[pallet::storage]
+	#[pallet::getter(fn limit_orders)]
+	pub type LimitOrders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::PoolId,
+		Twox64Concat,
+		LimitOrderId,
+		LimitOrderOf<T>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	pub type LimitOrdersPerPool<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::PoolId, u32, ValueQuery>;
+
+	/// Pool and id of the last limit order looked at by the keeper, which resumes after it.
+	#[pallet::storage]
+	pub type LimitOrderCursor<T: Config> = StorageValue<_, (T::PoolId, LimitOrderId), OptionQuery>;
+
 	pub(crate) enum PriceRatio {
 		Swapped,
 		NotSwapped,
@@ -337,43 +683,65 @@ pub mod pallet {
 		/// assets already exists in the runtime.
 		///
 		/// Emits `PoolCreated` event when successful.
-		#[pallet::weight(T::WeightInfo::create())]
+		#[pallet::weight(
+			T::WeightInfo::create()
+				.max(T::WeightInfo::create_stable_swap())
+				.max(T::WeightInfo::create_weighted_product(T::MaxAssetsPerPool::get()))
+				.max(T::WeightInfo::create_lbp())
+				.max(T::WeightInfo::create_concentrated_liquidity())
+		)]
 		pub fn create(origin: OriginFor<T>, pool: PoolInitConfigurationOf<T>) -> DispatchResult {
 			T::PoolCreationOrigin::ensure_origin(origin)?;
 			let _ = Self::do_create_pool(pool, None)?;
 			Ok(())
 		}
 
-		/// Execute a buy order on pool.
+		/// Execute a buy order on pool, unless `deadline` has passed.
 		///
 		/// Emits `Swapped` event when successful.
-		#[pallet::weight(T::WeightInfo::buy())]
+		#[pallet::weight(
+			T::WeightInfo::buy()
+				.max(T::WeightInfo::buy_stable_swap())
+				.max(T::WeightInfo::buy_weighted_product())
+				.max(T::WeightInfo::buy_lbp())
+				.max(T::WeightInfo::buy_concentrated_liquidity(T::MaxSwapSteps::get()))
+		)]
 		pub fn buy(
 			origin: OriginFor<T>,
 			pool_id: T::PoolId,
 			in_asset_id: T::AssetId,
 			out_asset: AssetAmount<T::AssetId, T::Balance>,
 			keep_alive: bool,
+			deadline: Option<SwapDeadlineOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
 			let _ = <Self as Amm>::do_buy(&who, pool_id, in_asset_id, out_asset, keep_alive)?;
 			Ok(())
 		}
 
-		/// Execute a specific swap operation.
+		/// Execute a specific swap operation, unless `deadline` has passed.
 		///
 		/// The `quote_amount` is always the quote asset amount (A/B => B), (B/A => A).
 		///
 		/// Emits `Swapped` event when successful.
-		#[pallet::weight(T::WeightInfo::swap())]
+		#[pallet::weight(
+			T::WeightInfo::swap()
+				.max(T::WeightInfo::swap_stable_swap())
+				.max(T::WeightInfo::swap_weighted_product())
+				.max(T::WeightInfo::swap_lbp())
+				.max(T::WeightInfo::swap_concentrated_liquidity(T::MaxSwapSteps::get()))
+		)]
 		pub fn swap(
 			origin: OriginFor<T>,
 			pool_id: T::PoolId,
 			in_asset: AssetAmount<T::AssetId, T::Balance>,
 			min_receive: AssetAmount<T::AssetId, T::Balance>,
 			keep_alive: bool,
+			deadline: Option<SwapDeadlineOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_deadline(deadline)?;
 			let _ = <Self as Amm>::do_swap(&who, pool_id, in_asset, min_receive, keep_alive)?;
 			Ok(())
 		}
@@ -381,7 +749,12 @@ pub mod pallet {
 		/// Add liquidity to the given pool.
 		///
 		/// Emits `LiquidityAdded` event when successful.
-		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		#[pallet::weight(
+			T::WeightInfo::add_liquidity()
+				.max(T::WeightInfo::add_liquidity_stable_swap())
+				.max(T::WeightInfo::add_liquidity_weighted_product(T::MaxAssetsPerPool::get()))
+				.max(T::WeightInfo::add_liquidity_lbp())
+		)]
 		pub fn add_liquidity(
 			origin: OriginFor<T>,
 			pool_id: T::PoolId,
@@ -397,7 +770,12 @@ pub mod pallet {
 		/// Remove liquidity from the given pool.
 		///
 		/// Emits `LiquidityRemoved` event when successful.
-		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		#[pallet::weight(
+			T::WeightInfo::remove_liquidity()
+				.max(T::WeightInfo::remove_liquidity_stable_swap())
+				.max(T::WeightInfo::remove_liquidity_weighted_product(T::MaxAssetsPerPool::get()))
+				.max(T::WeightInfo::remove_liquidity_lbp())
+		)]
 		pub fn remove_liquidity(
 			origin: OriginFor<T>,
 			pool_id: T::PoolId,
@@ -413,11 +791,35 @@ pub mod pallet {
 		#[transactional]
 		pub fn enable_twap(origin: OriginFor<T>, pool_id: T::PoolId) -> DispatchResult {
 			T::EnableTwapOrigin::ensure_origin(origin)?;
-			if TWAPState::<T>::contains_key(pool_id) {
+			if TWAPState::<T>::contains_key(pool_id) ||
+				MultiAssetTWAPState::<T>::contains_key(pool_id)
+			{
 				// pool_id is already enabled for TWAP
 				return Ok(())
 			}
 			let current_timestamp = T::Time::now();
+			if let PoolConfiguration::WeightedProduct(_) = Self::get_pool(pool_id)? {
+				let twaps = Self::do_get_exchange_rates(pool_id)?;
+				let price_cumulatives = twaps
+					.iter()
+					.map(|(asset_id, rate)| {
+						Ok((*asset_id, compute_initial_price_cumulative::<T::Convert, _>(*rate)?))
+					})
+					.collect::<Result<BTreeMap<_, _>, DispatchError>>()?;
+				MultiAssetTWAPState::<T>::insert(
+					pool_id,
+					MultiAssetTimeWeightedAveragePrice {
+						timestamp: current_timestamp,
+						price_cumulatives: price_cumulatives.clone(),
+						twaps,
+					},
+				);
+				MultiAssetPriceCumulativeState::<T>::insert(
+					pool_id,
+					MultiAssetPriceCumulative { timestamp: current_timestamp, price_cumulatives },
+				);
+				return Ok(())
+			}
 			let rate_base = Self::do_get_exchange_rate(pool_id, PriceRatio::NotSwapped)?;
 			let rate_quote = Self::do_get_exchange_rate(pool_id, PriceRatio::Swapped)?;
 			let base_price_cumulative =
@@ -444,6 +846,109 @@ pub mod pallet {
 			);
 			Ok(())
 		}
+
+		/// Remove liquidity from a weighted product pool, receiving a single asset of the pool.
+		///
+		/// Emits `LiquidityRemoved` event when successful.
+		#[pallet::weight(T::WeightInfo::remove_liquidity_single_asset())]
+		pub fn remove_liquidity_single_asset(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			lp_amount: T::Balance,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let _ = Self::do_remove_liquidity_single_asset(&who, pool_id, lp_amount, min_receive)?;
+			Ok(())
+		}
+
+		/// Provide liquidity to a concentrated liquidity pool between `lower_tick` and
+		/// `upper_tick`, depositing at most `assets`. The position is minted as a financial NFT.
+		///
+		/// Emits `PositionOpened` event when successful.
+		#[pallet::weight(T::WeightInfo::open_position())]
+		pub fn open_position(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			lower_tick: i32,
+			upper_tick: i32,
+			assets: BTreeMap<T::AssetId, T::Balance>,
+			min_liquidity: u128,
+			keep_alive: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let _ = Self::do_open_position(
+				&who,
+				pool_id,
+				lower_tick,
+				upper_tick,
+				assets,
+				min_liquidity,
+				keep_alive,
+			)?;
+			Ok(())
+		}
+
+		/// Withdraw the liquidity and fees of a position of a concentrated liquidity pool, burning
+		/// its financial NFT.
+		///
+		/// Emits `PositionClosed` event when successful.
+		#[pallet::weight(T::WeightInfo::close_position())]
+		pub fn close_position(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			position_id: T::FinancialNftInstanceId,
+			min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let _ = Self::do_close_position(&who, pool_id, position_id, min_receive)?;
+			Ok(())
+		}
+
+		/// Withdraw the fees earned by a position of a concentrated liquidity pool.
+		///
+		/// Emits `PositionFeesCollected` event when successful.
+		#[pallet::weight(T::WeightInfo::collect_position_fees())]
+		pub fn collect_position_fees(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			position_id: T::FinancialNftInstanceId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let _ = Self::do_collect_position_fees(&who, pool_id, position_id)?;
+			Ok(())
+		}
+
+		/// Escrow `in_asset` in a limit order swapping it on `pool_id` once the pool pays at least
+		/// `min_receive` for it, no later than block `expires_at`. Orders are filled by the keeper
+		/// running when blocks have weight left.
+		///
+		/// Emits `LimitOrderPlaced` event when successful.
+		#[pallet::weight(T::WeightInfo::place_limit_order())]
+		pub fn place_limit_order(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let _ = Self::do_place_limit_order(&who, pool_id, in_asset, min_receive, expires_at)?;
+			Ok(())
+		}
+
+		/// Cancel a limit order and refund its escrowed input. Only the owner can cancel.
+		///
+		/// Emits `LimitOrderCancelled` event when successful.
+		#[pallet::weight(T::WeightInfo::cancel_limit_order())]
+		pub fn cancel_limit_order(
+			origin: OriginFor<T>,
+			pool_id: T::PoolId,
+			order_id: LimitOrderId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_cancel_limit_order(&who, pool_id, order_id)
+		}
 	}
 
 	#[pallet::hooks]
@@ -489,8 +994,45 @@ pub mod pallet {
 					}
 				}
 			}
+			let multi_asset_twap_enabled_pools: Vec<T::PoolId> =
+				MultiAssetPriceCumulativeState::<T>::iter_keys().collect();
+			for pool_id in multi_asset_twap_enabled_pools {
+				let result = MultiAssetPriceCumulativeState::<T>::try_mutate(
+					pool_id,
+					|prev_price_cumulative| -> Result<(), DispatchError> {
+						let price_cumulatives = update_multi_asset_price_cumulative_state::<T>(
+							pool_id,
+							prev_price_cumulative,
+						)?
+						.ok_or(DispatchError::Other("Stale TWAP Request Found"))?;
+						MultiAssetTWAPState::<T>::try_mutate(
+							pool_id,
+							|prev_twap_state| -> Result<(), DispatchError> {
+								update_multi_asset_twap_state::<T>(
+									price_cumulatives,
+									prev_twap_state,
+								)
+							},
+						)
+					},
+				);
+				if result.is_ok() {
+					weight = weight.saturating_add(Weight::from_ref_time(1));
+					if let Some(updated_twap) = MultiAssetTWAPState::<T>::get(pool_id) {
+						Self::deposit_event(Event::<T>::TwapUpdated {
+							pool_id,
+							timestamp: updated_twap.timestamp,
+							twaps: updated_twap.twaps,
+						});
+					}
+				}
+			}
 			weight
 		}
+
+		fn on_idle(_block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::fill_limit_orders(remaining_weight)
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -510,13 +1052,71 @@ pub mod pallet {
 						assets_weights.clone(),
 						lp_token_id,
 					)?;
+					(owner, pool_id, assets_weights.into_inner())
+				},
+				PoolInitConfiguration::StableSwap {
+					owner,
+					pair,
+					amplification_coefficient,
+					fee,
+				} => {
+					let assets_weights = StableSwap::<T>::assets_weights(pair)?;
+					let pool_id = StableSwap::<T>::do_create_pool(
+						&owner,
+						FeeConfig::default_from(fee),
+						assets_weights.clone(),
+						amplification_coefficient,
+						lp_token_id,
+					)?;
+					(owner, pool_id, assets_weights.into_inner())
+				},
+				PoolInitConfiguration::WeightedProduct { owner, assets_weights, fee } => {
+					let pool_id = WeightedProduct::<T>::do_create_pool(
+						&owner,
+						FeeConfig::default_from(fee),
+						assets_weights.clone(),
+						lp_token_id,
+					)?;
+					(owner, pool_id, assets_weights.into_inner())
+				},
+				PoolInitConfiguration::LiquidityBootstrapping { owner, pair, sale, fee } => {
+					ensure!(lp_token_id.is_none(), Error::<T>::NoLpTokenForLbp);
+					let (base_weight, quote_weight) = sale.current_weights(sale.start);
+					let assets_weights =
+						BTreeMap::from([(pair.base, base_weight), (pair.quote, quote_weight)]);
+					let pool = LiquidityBootstrappingPoolInfo {
+						owner: owner.clone(),
+						pair,
+						sale,
+						fee_config: FeeConfig::default_from(fee),
+					};
+					let pool_id = LiquidityBootstrapping::<T>::do_create_pool(pool)?;
+					(owner, pool_id, assets_weights)
+				},
+				PoolInitConfiguration::ConcentratedLiquidity {
+					owner,
+					pair,
+					tick_spacing,
+					initial_tick,
+					fee,
+				} => {
+					let assets_weights = ConcentratedLiquidity::<T>::assets_weights(&pair);
+					// positions are financial NFTs, `lp_token_id` is used as their collection
+					let pool_id = ConcentratedLiquidity::<T>::do_create_pool(
+						&owner,
+						pair,
+						FeeConfig::default_from(fee),
+						tick_spacing,
+						initial_tick,
+						lp_token_id,
+					)?;
 					(owner, pool_id, assets_weights)
 				},
 			};
 			Self::deposit_event(Event::<T>::PoolCreated {
 				owner,
 				pool_id,
-				asset_weights: assets_weights.into_inner(),
+				asset_weights: assets_weights,
 			});
 			Ok(pool_id)
 		}
@@ -537,6 +1137,7 @@ pub mod pallet {
 		) -> Result<Rate, DispatchError> {
 			#[allow(deprecated)]
 			let pair = Self::pool_ordered_pair(pool_id)?;
+			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
 			let pair = match price_ratio {
 				PriceRatio::NotSwapped => pair,
@@ -554,14 +1155,104 @@ pub mod pallet {
 				Error::<T>::NotEnoughLiquidity
 			);
 
-			Ok(Rate::checked_from_rational(
-				pool_base_asset_under_management,
-				pool_quote_asset_under_management,
-			)
-			.ok_or(ArithmeticError::Overflow)?)
+			match pool {
+				PoolConfiguration::DualAssetConstantProduct(_) => Ok(Rate::checked_from_rational(
+					pool_base_asset_under_management,
+					pool_quote_asset_under_management,
+				)
+				.ok_or(ArithmeticError::Overflow)?),
+				PoolConfiguration::StableSwap(info) => Ok(compute_spot_price(
+					u128::from(info.amplification_coefficient),
+					T::Convert::convert(pool_base_asset_under_management),
+					T::Convert::convert(pool_quote_asset_under_management),
+				)?),
+				PoolConfiguration::WeightedProduct(info) => {
+					let weight_of = |asset_id| {
+						info.assets_weights.get(&asset_id).copied().ok_or(Error::<T>::AssetNotFound)
+					};
+					WeightedProduct::<T>::exchange_rate(
+						(
+							weight_of(pair.base)?,
+							T::Convert::convert(pool_base_asset_under_management),
+						),
+						(
+							weight_of(pair.quote)?,
+							T::Convert::convert(pool_quote_asset_under_management),
+						),
+					)
+				},
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					let weights = LiquidityBootstrapping::<T>::current_weights(&info);
+					let weight_of =
+						|asset_id| weights.get(&asset_id).copied().ok_or(Error::<T>::AssetNotFound);
+					WeightedProduct::<T>::exchange_rate(
+						(
+							weight_of(pair.base)?,
+							T::Convert::convert(pool_base_asset_under_management),
+						),
+						(
+							weight_of(pair.quote)?,
+							T::Convert::convert(pool_quote_asset_under_management),
+						),
+					)
+				},
+				PoolConfiguration::ConcentratedLiquidity(info) => {
+					// the price of the pool is denominated in the quote asset of the pool
+					let price = ConcentratedLiquidity::<T>::price(&pool_id)?;
+					if pair.base == info.pair.base {
+						Ok(price.reciprocal().ok_or(ArithmeticError::DivisionByZero)?)
+					} else {
+						Ok(price)
+					}
+				},
+			}
+		}
+
+		/// Prices of all of the assets of a weighted product pool, denominated in the first asset
+		/// of the pool.
+		pub(crate) fn do_get_exchange_rates(
+			pool_id: T::PoolId,
+		) -> Result<BTreeMap<T::AssetId, Rate>, DispatchError> {
+			match Self::get_pool(pool_id)? {
+				PoolConfiguration::WeightedProduct(info) =>
+					WeightedProduct::<T>::get_exchange_rates(&info, &Self::account_id(&pool_id)),
+				PoolConfiguration::DualAssetConstantProduct(_) |
+				PoolConfiguration::StableSwap(_) |
+				PoolConfiguration::LiquidityBootstrapping(_) |
+				PoolConfiguration::ConcentratedLiquidity(_) => Err(Error::<T>::UnsupportedOperation.into()),
+			}
+		}
+
+		fn update_multi_asset_twap(pool_id: T::PoolId) -> Result<(), DispatchError> {
+			let price_cumulatives = MultiAssetPriceCumulativeState::<T>::try_mutate(
+				pool_id,
+				|prev_price_cumulative| {
+					update_multi_asset_price_cumulative_state::<T>(pool_id, prev_price_cumulative)
+				},
+			)?;
+			if let Some(price_cumulatives) = price_cumulatives {
+				let updated_twap = MultiAssetTWAPState::<T>::try_mutate(
+					pool_id,
+					|prev_twap_state| -> Result<Option<MultiAssetTWAPStateOf<T>>, DispatchError> {
+						update_multi_asset_twap_state::<T>(price_cumulatives, prev_twap_state)
+							.map_or_else(|_| Ok(None), |_| Ok(prev_twap_state.clone()))
+					},
+				)?;
+				if let Some(updated_twap) = updated_twap {
+					Self::deposit_event(Event::<T>::TwapUpdated {
+						pool_id,
+						timestamp: updated_twap.timestamp,
+						twaps: updated_twap.twaps,
+					});
+				}
+			}
+			Ok(())
 		}
 
 		fn update_twap(pool_id: T::PoolId) -> Result<(), DispatchError> {
+			if let PoolConfiguration::WeightedProduct(_) = Self::get_pool(pool_id)? {
+				return Self::update_multi_asset_twap(pool_id)
+			}
 			#[allow(deprecated)]
 			let currency_pair = Self::pool_ordered_pair(pool_id)?; // update price cumulatives
 			let (base_price_cumulative, quote_price_cumulative) =
@@ -632,15 +1323,404 @@ pub mod pallet {
 			pool_id: T::PoolId,
 		) -> Result<CurrencyPair<T::AssetId>, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
-			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let assets = info.assets_weights.keys().copied().collect::<Vec<_>>();
-					ensure!(assets.len() == 2, Error::<T>::PairMismatch);
-					let base_asset = assets.get(0).ok_or(Error::<T>::PairMismatch)?;
-					let quote_asset = assets.get(1).ok_or(Error::<T>::PairMismatch)?;
-					Ok(CurrencyPair::new(*base_asset, *quote_asset))
+			let assets = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo { pool_info: info, .. }) =>
+					info.assets_weights.keys().copied().collect::<Vec<_>>(),
+				PoolConfiguration::WeightedProduct(info) =>
+					info.assets_weights.keys().copied().collect::<Vec<_>>(),
+				PoolConfiguration::LiquidityBootstrapping(LiquidityBootstrappingPoolInfo {
+					pair,
+					..
+				}) |
+				PoolConfiguration::ConcentratedLiquidity(ConcentratedLiquidityPoolInfo {
+					pair,
+					..
+				}) => {
+					let mut assets = Vec::from([pair.base, pair.quote]);
+					assets.sort();
+					assets
 				},
+			};
+			ensure!(assets.len() == 2, Error::<T>::PairMismatch);
+			let base_asset = assets.get(0).ok_or(Error::<T>::PairMismatch)?;
+			let quote_asset = assets.get(1).ok_or(Error::<T>::PairMismatch)?;
+			Ok(CurrencyPair::new(*base_asset, *quote_asset))
+		}
+
+		fn bi_bounded_assets<const U: usize>(
+			assets: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, U>, Error<T>> {
+			BiBoundedVec::from_vec(
+				assets
+					.into_iter()
+					.map(|(asset_id, amount)| AssetAmount { asset_id, amount })
+					.collect(),
+			)
+			.map_err(|err| match err {
+				BiBoundedVecOutOfBounds::LowerBoundError { .. } =>
+					Error::<T>::MustDepositMinimumOneAsset,
+				BiBoundedVecOutOfBounds::UpperBoundError { .. } => Error::<T>::UnsupportedOperation,
+			})
+		}
+
+		/// Redeems `lp_amount` for the single asset `min_receive.asset_id` of a weighted product
+		/// pool.
+		#[transactional]
+		pub fn do_remove_liquidity_single_asset(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			lp_amount: T::Balance,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+		) -> Result<AssetAmount<T::AssetId, T::Balance>, DispatchError> {
+			let pool_account = Self::account_id(&pool_id);
+			let redeemed = match Self::get_pool(pool_id)? {
+				PoolConfiguration::WeightedProduct(info) =>
+					WeightedProduct::<T>::remove_liquidity_single_asset(
+						who,
+						info,
+						pool_account,
+						lp_amount,
+						min_receive,
+					)?,
+				PoolConfiguration::DualAssetConstantProduct(_) |
+				PoolConfiguration::StableSwap(_) |
+				PoolConfiguration::LiquidityBootstrapping(_) |
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::UnsupportedOperation.into()),
+			};
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::LiquidityRemoved {
+				pool_id,
+				who: who.clone(),
+				asset_amounts: BTreeMap::from([(redeemed.asset_id, redeemed.amount)]),
+			});
+
+			Ok(redeemed)
+		}
+
+		fn get_concentrated_liquidity_pool(
+			pool_id: T::PoolId,
+		) -> Result<ConcentratedLiquidityPoolInfo<T::AccountId, T::AssetId>, DispatchError> {
+			match Self::get_pool(pool_id)? {
+				PoolConfiguration::ConcentratedLiquidity(info) => Ok(info),
+				PoolConfiguration::DualAssetConstantProduct(_) |
+				PoolConfiguration::StableSwap(_) |
+				PoolConfiguration::WeightedProduct(_) |
+				PoolConfiguration::LiquidityBootstrapping(_) => Err(Error::<T>::UnsupportedOperation.into()),
+			}
+		}
+
+		/// Opens a position of a concentrated liquidity pool, returning the id of its financial
+		/// NFT and its liquidity.
+		#[transactional]
+		pub fn do_open_position(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			lower_tick: i32,
+			upper_tick: i32,
+			assets: BTreeMap<T::AssetId, T::Balance>,
+			min_liquidity: u128,
+			keep_alive: bool,
+		) -> Result<(T::FinancialNftInstanceId, u128), DispatchError> {
+			let pool = Self::get_concentrated_liquidity_pool(pool_id)?;
+			let (position_id, liquidity, asset_amounts) =
+				ConcentratedLiquidity::<T>::open_position(
+					who,
+					pool_id,
+					pool,
+					Self::account_id(&pool_id),
+					lower_tick,
+					upper_tick,
+					assets,
+					min_liquidity,
+					keep_alive,
+				)?;
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::PositionOpened {
+				pool_id,
+				owner: who.clone(),
+				position_id,
+				lower_tick,
+				upper_tick,
+				liquidity,
+				asset_amounts,
+			});
+			Ok((position_id, liquidity))
+		}
+
+		/// Closes a position of a concentrated liquidity pool, returning the withdrawn amounts.
+		#[transactional]
+		pub fn do_close_position(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			position_id: T::FinancialNftInstanceId,
+			min_receive: BTreeMap<T::AssetId, T::Balance>,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let pool = Self::get_concentrated_liquidity_pool(pool_id)?;
+			let (asset_amounts, fees) = ConcentratedLiquidity::<T>::close_position(
+				who,
+				pool_id,
+				pool,
+				Self::account_id(&pool_id),
+				position_id,
+				min_receive,
+			)?;
+
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::PositionClosed {
+				pool_id,
+				owner: who.clone(),
+				position_id,
+				asset_amounts: asset_amounts.clone(),
+				fees,
+			});
+			Ok(asset_amounts)
+		}
+
+		/// Collects the fees earned by a position of a concentrated liquidity pool.
+		#[transactional]
+		pub fn do_collect_position_fees(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			position_id: T::FinancialNftInstanceId,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			let pool = Self::get_concentrated_liquidity_pool(pool_id)?;
+			let fees = ConcentratedLiquidity::<T>::collect_fees(
+				who,
+				pool_id,
+				pool,
+				Self::account_id(&pool_id),
+				position_id,
+			)?;
+
+			Self::deposit_event(Event::<T>::PositionFeesCollected {
+				pool_id,
+				owner: who.clone(),
+				position_id,
+				fees: fees.clone(),
+			});
+			Ok(fees)
+		}
+
+		pub(crate) fn ensure_deadline(deadline: Option<SwapDeadlineOf<T>>) -> DispatchResult {
+			let within_deadline = match deadline {
+				None => true,
+				Some(SwapDeadline::Block(block)) =>
+					frame_system::Pallet::<T>::block_number() <= block,
+				Some(SwapDeadline::Moment(moment)) => T::Time::now() <= moment,
+			};
+			ensure!(within_deadline, Error::<T>::DeadlinePassed);
+			Ok(())
+		}
+
+		/// Account holding the input of limit orders until they are filled, cancelled or expired.
+		pub(crate) fn limit_order_escrow_account() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		#[transactional]
+		pub fn do_place_limit_order(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			in_asset: AssetAmount<T::AssetId, T::Balance>,
+			min_receive: AssetAmount<T::AssetId, T::Balance>,
+			expires_at: T::BlockNumber,
+		) -> Result<LimitOrderId, DispatchError> {
+			ensure!(in_asset.asset_id != min_receive.asset_id, Error::<T>::CannotSwapSameAsset);
+			ensure!(
+				!in_asset.amount.is_zero() && !min_receive.amount.is_zero(),
+				Error::<T>::AssetAmountMustBePositiveNumber
+			);
+			let assets = <Self as Amm>::assets(pool_id)?;
+			ensure!(
+				assets.contains_key(&in_asset.asset_id) &&
+					assets.contains_key(&min_receive.asset_id),
+				Error::<T>::AssetNotFound
+			);
+			ensure!(
+				expires_at > frame_system::Pallet::<T>::block_number(),
+				Error::<T>::LimitOrderExpiryInThePast
+			);
+			LimitOrdersPerPool::<T>::try_mutate(pool_id, |count| -> DispatchResult {
+				ensure!(*count < T::MaxLimitOrdersPerPool::get(), Error::<T>::TooManyLimitOrders);
+				*count = count.saturating_add(1);
+				Ok(())
+			})?;
+			let order_id = LimitOrderCount::<T>::mutate(|count| {
+				let order_id = *count;
+				*count = count.saturating_add(1);
+				order_id
+			});
+
+			T::Assets::transfer(
+				in_asset.asset_id,
+				who,
+				&Self::limit_order_escrow_account(),
+				in_asset.amount,
+				false,
+			)?;
+			LimitOrders::<T>::insert(
+				pool_id,
+				order_id,
+				LimitOrder { owner: who.clone(), in_asset, min_receive, expires_at },
+			);
+
+			Self::deposit_event(Event::<T>::LimitOrderPlaced {
+				pool_id,
+				order_id,
+				owner: who.clone(),
+				in_asset,
+				min_receive,
+				expires_at,
+			});
+			Ok(order_id)
+		}
+
+		#[transactional]
+		pub fn do_cancel_limit_order(
+			who: &T::AccountId,
+			pool_id: T::PoolId,
+			order_id: LimitOrderId,
+		) -> DispatchResult {
+			let order =
+				LimitOrders::<T>::get(pool_id, order_id).ok_or(Error::<T>::LimitOrderNotFound)?;
+			ensure!(order.owner == *who, Error::<T>::MustBeOwner);
+			Self::remove_limit_order(pool_id, order_id);
+			Self::refund_limit_order(&order)?;
+
+			Self::deposit_event(Event::<T>::LimitOrderCancelled {
+				pool_id,
+				order_id,
+				owner: order.owner,
+			});
+			Ok(())
+		}
+
+		/// Fills the limit orders their pool pays enough for and refunds the expired ones, as
+		/// many as `remaining_weight` allows. Each call resumes after the last order looked at by
+		/// the previous one, so that every order is eventually looked at.
+		pub(crate) fn fill_limit_orders(remaining_weight: Weight) -> Weight {
+			// reading and updating the cursor
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			// no order is cheaper to fill than one on a constant product pool
+			let budget = remaining_weight.saturating_sub(weight).ref_time() /
+				T::WeightInfo::fill_limit_order().ref_time().max(1);
+			if budget.is_zero() {
+				return Weight::from_ref_time(0)
+			}
+
+			let orders = match LimitOrderCursor::<T>::get() {
+				Some((pool_id, order_id)) =>
+					LimitOrders::<T>::iter_from(LimitOrders::<T>::hashed_key_for(pool_id, order_id)),
+				None => LimitOrders::<T>::iter(),
 			}
+			.take(budget as usize)
+			.collect::<Vec<_>>();
+			let fetched = orders.len() as u64;
+			let mut looked_at = None;
+			let mut out_of_weight = false;
+			let now = frame_system::Pallet::<T>::block_number();
+			for (pool_id, order_id, order) in orders {
+				let per_order = Self::fill_limit_order_weight(pool_id);
+				if weight.saturating_add(per_order).ref_time() > remaining_weight.ref_time() {
+					out_of_weight = true;
+					break
+				}
+				weight = weight.saturating_add(per_order);
+				// Orders the pool does not pay enough for yet are left untouched.
+				let _ = Self::process_limit_order(pool_id, order_id, order, now);
+				looked_at = Some((pool_id, order_id));
+			}
+			match looked_at {
+				Some(last) if out_of_weight || fetched == budget =>
+					LimitOrderCursor::<T>::put(last),
+				// no order fit, look at the same ones next time
+				None if out_of_weight => (),
+				// the last order was reached, start over from the first one
+				_ => LimitOrderCursor::<T>::kill(),
+			}
+			weight
+		}
+
+		/// Weight of filling a limit order on `pool_id`. `fill_limit_order` is benchmarked on a
+		/// constant product pool, so its swap is replaced by the one of the pool's type.
+		pub(crate) fn fill_limit_order_weight(pool_id: T::PoolId) -> Weight {
+			let swap = match Pools::<T>::get(pool_id) {
+				Some(PoolConfiguration::StableSwap(_)) => T::WeightInfo::swap_stable_swap(),
+				Some(PoolConfiguration::WeightedProduct(_)) =>
+					T::WeightInfo::swap_weighted_product(),
+				Some(PoolConfiguration::LiquidityBootstrapping(_)) => T::WeightInfo::swap_lbp(),
+				Some(PoolConfiguration::ConcentratedLiquidity(_)) =>
+					T::WeightInfo::swap_concentrated_liquidity(T::MaxSwapSteps::get()),
+				Some(PoolConfiguration::DualAssetConstantProduct(_)) | None =>
+					T::WeightInfo::swap(),
+			};
+			T::WeightInfo::fill_limit_order()
+				.saturating_sub(T::WeightInfo::swap())
+				.saturating_add(swap)
+				// reading the pool to weigh the order
+				.saturating_add(T::DbWeight::get().reads(1))
+		}
+
+		#[transactional]
+		fn process_limit_order(
+			pool_id: T::PoolId,
+			order_id: LimitOrderId,
+			order: LimitOrderOf<T>,
+			now: T::BlockNumber,
+		) -> DispatchResult {
+			Self::remove_limit_order(pool_id, order_id);
+			if now > order.expires_at {
+				Self::refund_limit_order(&order)?;
+				Self::deposit_event(Event::<T>::LimitOrderExpired {
+					pool_id,
+					order_id,
+					owner: order.owner,
+				});
+				return Ok(())
+			}
+
+			let escrow = Self::limit_order_escrow_account();
+			let out_asset =
+				<Self as Amm>::do_swap(&escrow, pool_id, order.in_asset, order.min_receive, false)?
+					.value;
+			T::Assets::transfer(
+				out_asset.asset_id,
+				&escrow,
+				&order.owner,
+				out_asset.amount,
+				false,
+			)?;
+
+			Self::deposit_event(Event::<T>::LimitOrderFilled {
+				pool_id,
+				order_id,
+				owner: order.owner,
+				in_asset: order.in_asset,
+				out_asset,
+			});
+			Ok(())
+		}
+
+		fn remove_limit_order(pool_id: T::PoolId, order_id: LimitOrderId) {
+			LimitOrders::<T>::remove(pool_id, order_id);
+			LimitOrdersPerPool::<T>::mutate_exists(pool_id, |count| {
+				*count = count.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0);
+			});
+		}
+
+		fn refund_limit_order(order: &LimitOrderOf<T>) -> DispatchResult {
+			T::Assets::transfer(
+				order.in_asset.asset_id,
+				&Self::limit_order_escrow_account(),
+				&order.owner,
+				order.in_asset.amount,
+				false,
+			)?;
+			Ok(())
 		}
 	}
 
@@ -654,20 +1734,39 @@ pub mod pallet {
 			Pools::<T>::contains_key(pool_id)
 		}
 
+		fn pools(limit: u32) -> Vec<Self::PoolId> {
+			Pools::<T>::iter_keys().take(limit as usize).collect()
+		}
+
 		fn assets(
 			pool_id: Self::PoolId,
 		) -> Result<BTreeMap<Self::AssetId, Permill>, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
 			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) =>
+				PoolConfiguration::DualAssetConstantProduct(info) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo { pool_info: info, .. }) =>
 					Ok(info.assets_weights.into_inner()),
+				PoolConfiguration::WeightedProduct(info) => Ok(info.assets_weights.into_inner()),
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					Ok(LiquidityBootstrapping::<T>::current_weights(&info)),
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					Ok(ConcentratedLiquidity::<T>::assets_weights(&info.pair)),
 			}
 		}
 
 		fn lp_token(pool_id: Self::PoolId) -> Result<Self::AssetId, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
 			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => Ok(info.lp_token),
+				PoolConfiguration::DualAssetConstantProduct(BasicPoolInfo { lp_token, .. }) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo {
+					pool_info: BasicPoolInfo { lp_token, .. },
+					..
+				}) |
+				PoolConfiguration::WeightedProduct(BasicPoolInfo { lp_token, .. }) => Ok(lp_token),
+				PoolConfiguration::LiquidityBootstrapping(_) =>
+					Err(Error::<T>::NoLpTokenForLbp.into()),
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					Err(Error::<T>::NoLpTokenForConcentratedLiquidity.into()),
 			}
 		}
 
@@ -701,6 +1800,10 @@ pub mod pallet {
 					lp_token,
 					assets_weights,
 					..
+				}) |
+				PoolConfiguration::StableSwap(StableSwapPoolInfo {
+					pool_info: BasicPoolInfo { lp_token, assets_weights, .. },
+					..
 				}) => {
 					let assets = assets_weights
 						.into_iter()
@@ -717,6 +1820,33 @@ pub mod pallet {
 
 					Ok(assets)
 				},
+				// liquidity is redeemed in a balanced way, see `WeightedProduct::remove_liquidity`
+				PoolConfiguration::WeightedProduct(BasicPoolInfo {
+					lp_token,
+					assets_weights,
+					..
+				}) => {
+					let lp_total_issuance =
+						T::Convert::convert(T::Assets::total_issuance(lp_token));
+					let assets = assets_weights
+						.into_iter()
+						.map(|(id, _)| {
+							compute_redeemed_for_lp(
+								lp_total_issuance,
+								T::Convert::convert(lp_amount),
+								T::Convert::convert(T::Assets::balance(id, &pool_account)),
+								Permill::one(),
+							)
+							.map(|res| (id, T::Convert::convert(res)))
+						})
+						.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+					Ok(assets)
+				},
+				PoolConfiguration::LiquidityBootstrapping(_) =>
+					Err(Error::<T>::NoLpTokenForLbp.into()),
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					Err(Error::<T>::NoLpTokenForConcentratedLiquidity.into()),
 			}
 		}
 
@@ -746,24 +1876,54 @@ pub mod pallet {
 		) -> Result<SwapResult<Self::AssetId, Self::Balance>, DispatchError> {
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let (amount_out, amount_in, fee) =
-						DualAssetConstantProduct::<T>::get_exchange_value(
-							&info,
-							&pool_account,
-							base_asset,
-							quote_asset_id,
-							calculate_with_fees,
-						)?;
-
-					Ok(SwapResult {
-						value: amount_out,
-						// fee = initial_amount - post_fee_amount
-						fee: AssetAmount::new(amount_in.asset_id, fee.fee),
-					})
-				},
-			}
+			let (amount_out, amount_in, fee) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						base_asset,
+						quote_asset_id,
+						calculate_with_fees,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::get_exchange_value(
+					&info,
+					&pool_account,
+					base_asset,
+					quote_asset_id,
+					calculate_with_fees,
+				)?,
+				PoolConfiguration::WeightedProduct(info) =>
+					WeightedProduct::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						base_asset,
+						quote_asset_id,
+						calculate_with_fees,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						base_asset,
+						quote_asset_id,
+						calculate_with_fees,
+					)?,
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::get_exchange_value(
+						&pool_id,
+						&info,
+						base_asset,
+						quote_asset_id,
+						calculate_with_fees,
+						false,
+					)?,
+			};
+
+			Ok(SwapResult {
+				value: amount_out,
+				// fee = initial_amount - post_fee_amount
+				fee: AssetAmount::new(amount_in.asset_id, fee.fee),
+			})
 		}
 
 		#[transactional]
@@ -782,22 +1942,37 @@ pub mod pallet {
 						who,
 						info,
 						pool_account,
-						BiBoundedVec::from_vec(
-							assets
-								.clone()
-								.into_iter()
-								.map(|(asset_id, amount)| AssetAmount { asset_id, amount })
-								.collect(),
-						)
-						.map_err(|err| match err {
-							BiBoundedVecOutOfBounds::LowerBoundError { .. } =>
-								Error::<T>::MustDepositMinimumOneAsset,
-							BiBoundedVecOutOfBounds::UpperBoundError { .. } =>
-								Error::<T>::UnsupportedOperation,
-						})?,
+						Self::bi_bounded_assets(assets)?,
+						min_mint_amount,
+						keep_alive,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::add_liquidity(
+					who,
+					info,
+					pool_account,
+					Self::bi_bounded_assets(assets)?,
+					min_mint_amount,
+					keep_alive,
+				)?,
+				PoolConfiguration::WeightedProduct(info) => WeightedProduct::<T>::add_liquidity(
+					who,
+					info,
+					pool_account,
+					assets,
+					min_mint_amount,
+					keep_alive,
+				)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::add_liquidity(
+						who,
+						info,
+						pool_account,
+						assets,
 						min_mint_amount,
 						keep_alive,
 					)?,
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::NoLpTokenForConcentratedLiquidity.into()),
 			};
 
 			Self::update_twap(pool_id)?;
@@ -820,26 +1995,46 @@ pub mod pallet {
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
 			let res = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let res = DualAssetConstantProduct::<T>::remove_liquidity(
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::remove_liquidity(
 						who,
 						info,
 						pool_account,
 						lp_amount,
 						min_receive.try_into().map_err(|_| Error::<T>::UnsupportedOperation)?,
-					)?;
-
-					Self::update_twap(pool_id)?;
-					Self::deposit_event(Event::<T>::LiquidityRemoved {
-						pool_id,
-						who: who.clone(),
-						asset_amounts: res.clone(),
-					});
-
-					res
-				},
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::remove_liquidity(
+					who,
+					info,
+					pool_account,
+					lp_amount,
+					min_receive.try_into().map_err(|_| Error::<T>::UnsupportedOperation)?,
+				)?,
+				PoolConfiguration::WeightedProduct(info) => WeightedProduct::<T>::remove_liquidity(
+					who,
+					info,
+					pool_account,
+					lp_amount,
+					min_receive,
+				)?,
+				PoolConfiguration::LiquidityBootstrapping(info) =>
+					LiquidityBootstrapping::<T>::remove_liquidity(
+						who,
+						info,
+						pool_account,
+						min_receive,
+					)?,
+				PoolConfiguration::ConcentratedLiquidity(_) =>
+					return Err(Error::<T>::NoLpTokenForConcentratedLiquidity.into()),
 			};
 
+			Self::update_twap(pool_id)?;
+			Self::deposit_event(Event::<T>::LiquidityRemoved {
+				pool_id,
+				who: who.clone(),
+				asset_amounts: res.clone(),
+			});
+
 			Ok(res)
 		}
 
@@ -855,46 +2050,73 @@ pub mod pallet {
 
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			let (amount_out, amount_in, fee, _owner) = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					let (amount_out, amount_in, fee) =
-						DualAssetConstantProduct::<T>::get_exchange_value(
-							&info,
-							&pool_account,
-							in_asset,
-							min_receive.asset_id,
-							true,
-						)?;
-
-					ensure!(
-						amount_out.amount >= min_receive.amount,
-						Error::<T>::CannotRespectMinimumRequested
-					);
-					ensure!(
-						T::Assets::balance(amount_out.asset_id, &pool_account) > amount_out.amount,
-						Error::<T>::NotEnoughLiquidity
-					);
-
-					// Transfer the in asset amount to the pool
-					T::Assets::transfer(
-						amount_in.asset_id,
-						who,
+			let (amount_out, amount_in, fee) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::get_exchange_value(
+						&info,
 						&pool_account,
-						amount_in.amount,
-						keep_alive,
-					)?;
-					// Transfer swapped value to user
-					T::Assets::transfer(
-						amount_out.asset_id,
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?,
+				PoolConfiguration::StableSwap(info) => StableSwap::<T>::get_exchange_value(
+					&info,
+					&pool_account,
+					in_asset,
+					min_receive.asset_id,
+					true,
+				)?,
+				PoolConfiguration::WeightedProduct(info) =>
+					WeightedProduct::<T>::get_exchange_value(
+						&info,
 						&pool_account,
-						who,
-						amount_out.amount,
-						false,
-					)?;
-
-					(amount_out, amount_in, fee, info.owner)
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?,
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					LiquidityBootstrapping::<T>::ensure_sale_state(&info, |state| {
+						state == SaleState::Ongoing
+					})?;
+					LiquidityBootstrapping::<T>::get_exchange_value(
+						&info,
+						&pool_account,
+						in_asset,
+						min_receive.asset_id,
+						true,
+					)?
 				},
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::get_exchange_value(
+						&pool_id,
+						&info,
+						in_asset,
+						min_receive.asset_id,
+						true,
+						true,
+					)?,
 			};
+
+			ensure!(
+				amount_out.amount >= min_receive.amount,
+				Error::<T>::CannotRespectMinimumRequested
+			);
+			ensure!(
+				T::Assets::balance(amount_out.asset_id, &pool_account) > amount_out.amount,
+				Error::<T>::NotEnoughLiquidity
+			);
+
+			// Transfer the in asset amount to the pool
+			T::Assets::transfer(
+				amount_in.asset_id,
+				who,
+				&pool_account,
+				amount_in.amount,
+				keep_alive,
+			)?;
+			// Transfer swapped value to user
+			T::Assets::transfer(amount_out.asset_id, &pool_account, who, amount_out.amount, false)?;
+
 			Self::update_twap(pool_id)?;
 			Self::deposit_event(Event::<T>::Swapped {
 				pool_id,
@@ -925,34 +2147,55 @@ pub mod pallet {
 
 			let pool = Self::get_pool(pool_id)?;
 			let pool_account = Self::account_id(&pool_id);
-			let (amount_sent, _owner, fees) = match pool {
-				PoolConfiguration::DualAssetConstantProduct(info) => {
-					// NOTE: lp_fees includes owner_fees.
-					let (amount_out, amount_sent, fees) = DualAssetConstantProduct::<T>::do_buy(
+			// NOTE: lp_fees includes owner_fees.
+			let (amount_out, amount_sent, fees) = match pool {
+				PoolConfiguration::DualAssetConstantProduct(info) =>
+					DualAssetConstantProduct::<T>::do_buy(
 						&info,
 						&pool_account,
 						out_asset,
 						in_asset_id,
 						true,
-					)?;
-
-					T::Assets::transfer(
-						amount_sent.asset_id,
-						who,
-						&pool_account,
-						amount_sent.amount,
-						keep_alive,
-					)?;
-					T::Assets::transfer(
-						amount_out.asset_id,
+					)?,
+				PoolConfiguration::StableSwap(info) =>
+					StableSwap::<T>::do_buy(&info, &pool_account, out_asset, in_asset_id, true)?,
+				PoolConfiguration::WeightedProduct(info) => WeightedProduct::<T>::do_buy(
+					&info,
+					&pool_account,
+					out_asset,
+					in_asset_id,
+					true,
+				)?,
+				PoolConfiguration::LiquidityBootstrapping(info) => {
+					LiquidityBootstrapping::<T>::ensure_sale_state(&info, |state| {
+						state == SaleState::Ongoing
+					})?;
+					LiquidityBootstrapping::<T>::do_buy(
+						&info,
 						&pool_account,
-						who,
-						amount_out.amount,
-						false,
-					)?;
-					(amount_sent, info.owner, fees)
+						out_asset,
+						in_asset_id,
+						true,
+					)?
 				},
+				PoolConfiguration::ConcentratedLiquidity(info) =>
+					ConcentratedLiquidity::<T>::do_buy(
+						&pool_id,
+						&info,
+						out_asset,
+						in_asset_id,
+						true,
+					)?,
 			};
+
+			T::Assets::transfer(
+				amount_sent.asset_id,
+				who,
+				&pool_account,
+				amount_sent.amount,
+				keep_alive,
+			)?;
+			T::Assets::transfer(amount_out.asset_id, &pool_account, who, amount_out.amount, false)?;
 			Self::update_twap(pool_id)?;
 			// TODO (vim): Emit a Buy event: Release 3
 			Self::deposit_event(Event::<T>::Swapped {
@@ -969,6 +2212,80 @@ pub mod pallet {
 		}
 	}
 
+	impl<T: Config> LiquidityPools<T::AssetId, T::Balance> for Pallet<T> {
+		type PoolId = T::PoolId;
+
+		fn pool_assets(pool_id: Self::PoolId) -> Result<Vec<T::AssetId>, DispatchError> {
+			Ok(<Self as Amm>::assets(pool_id)?.into_keys().collect())
+		}
+
+		/// Weighted pools are valued from their invariant `prod(b_i^w_i)`. Stable swap pools are
+		/// valued from their invariant `D`, the balance they hold when balanced, priced as their
+		/// cheapest asset.
+		fn fair_assets_for_lp_tokens(
+			pool_id: Self::PoolId,
+			lp_amount: T::Balance,
+			prices: &BTreeMap<T::AssetId, Ratio>,
+		) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+			// pools without LP token have no value to derive
+			let lp_token = <Self as Amm>::lp_token(pool_id)?;
+			let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(lp_token));
+			let pool_account = Self::account_id(&pool_id);
+			let balance_of = |asset_id: &T::AssetId| {
+				T::Convert::convert(T::Assets::balance(*asset_id, &pool_account))
+			};
+			let price_of = |asset_id: &T::AssetId| {
+				prices.get(asset_id).copied().ok_or(Error::<T>::AssetNotFound)
+			};
+
+			let fair_balances: Vec<(T::AssetId, u128)> = match Self::get_pool(pool_id)? {
+				PoolConfiguration::StableSwap(StableSwapPoolInfo {
+					pool_info,
+					amplification_coefficient,
+				}) => {
+					let balances =
+						pool_info.assets_weights.keys().map(balance_of).collect::<Vec<_>>();
+					let d = compute_d(&balances, u128::from(amplification_coefficient))?;
+					let mut cheapest: Option<(T::AssetId, Ratio)> = None;
+					for asset_id in pool_info.assets_weights.keys() {
+						let price = price_of(asset_id)?;
+						if cheapest.map_or(true, |(_, cheapest_price)| price < cheapest_price) {
+							cheapest = Some((*asset_id, price));
+						}
+					}
+					let (asset_id, _) = cheapest.ok_or(Error::<T>::AssetNotFound)?;
+					Vec::from([(asset_id, d)])
+				},
+				_ => {
+					let assets = <Self as Amm>::assets(pool_id)?;
+					let pool_assets = assets
+						.iter()
+						.map(|(asset_id, weight)| {
+							Ok::<_, DispatchError>((
+								balance_of(asset_id),
+								price_of(asset_id)?,
+								*weight,
+							))
+						})
+						.collect::<Result<Vec<_>, _>>()?;
+					assets.into_keys().zip(compute_equilibrium_balances(&pool_assets)?).collect()
+				},
+			};
+
+			fair_balances
+				.into_iter()
+				.map(|(asset_id, balance)| {
+					let backing = safe_multiply_by_rational(
+						balance,
+						T::Convert::convert(lp_amount),
+						lp_total_issuance,
+					)?;
+					Ok::<_, DispatchError>((asset_id, T::Convert::convert(backing)))
+				})
+				.collect()
+		}
+	}
+
 	/// Retrieve the price(s) from the given pool calculated for the given `base_asset_id`
 	/// and `quote_asset_id` pair.
 	pub fn prices_for<T: Config>(
@@ -0,0 +1,230 @@
+use crate::{Config, Error, MomentOf, PoolConfiguration, PoolCount, Pools};
+use composable_maths::dex::constant_product::{compute_in_given_out, compute_out_given_in};
+use composable_support::math::safe::SafeAdd;
+use composable_traits::dex::{AssetAmount, Fee, LiquidityBootstrappingPoolInfo, SaleState};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{
+		fungibles::{Inspect, Transfer},
+		Time,
+	},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	Permill,
+};
+use sp_std::collections::btree_map::BTreeMap;
+
+pub(crate) type LiquidityBootstrappingPoolInfoOf<T> = LiquidityBootstrappingPoolInfo<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::AssetId,
+	MomentOf<T>,
+>;
+
+// Liquidity Bootstrapping Pool, a dual asset weighted pool whose weights shift over a sale window
+pub(crate) struct LiquidityBootstrapping<T>(PhantomData<T>);
+
+impl<T: Config> LiquidityBootstrapping<T> {
+	pub(crate) fn do_create_pool(
+		pool: LiquidityBootstrappingPoolInfoOf<T>,
+	) -> Result<T::PoolId, DispatchError> {
+		ensure!(pool.pair.base != pool.pair.quote, Error::<T>::InvalidPair);
+		ensure!(pool.fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let sale = pool.sale;
+		ensure!(sale.start >= T::Time::now(), Error::<T>::InvalidSaleState);
+		ensure!(
+			sale.end > sale.start &&
+				sale.duration() >= T::MinSaleDuration::get() &&
+				sale.duration() <= T::MaxSaleDuration::get(),
+			Error::<T>::InvalidSaleState
+		);
+		// the weight of the project asset is expected to decrease over the sale, driving its price
+		// down until demand meets it
+		ensure!(
+			sale.initial_weight <= T::MaxInitialWeight::get() &&
+				sale.final_weight >= T::MinFinalWeight::get() &&
+				sale.final_weight <= sale.initial_weight,
+			Error::<T>::InvalidSaleState
+		);
+		ensure!(
+			!sale.final_weight.is_zero() && sale.initial_weight < Permill::one(),
+			Error::<T>::WeightsMustBeNonZero
+		);
+
+		let pool_id =
+			PoolCount::<T>::try_mutate(|pool_count| -> Result<T::PoolId, DispatchError> {
+				let pool_id = *pool_count;
+				Pools::<T>::insert(pool_id, PoolConfiguration::LiquidityBootstrapping(pool));
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok(pool_id)
+			})?;
+
+		Ok(pool_id)
+	}
+
+	/// Weights of the pool's assets at the current time.
+	pub(crate) fn current_weights(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+	) -> BTreeMap<T::AssetId, Permill> {
+		let (base_weight, quote_weight) = pool.sale.current_weights(T::Time::now());
+		BTreeMap::from([(pool.pair.base, base_weight), (pool.pair.quote, quote_weight)])
+	}
+
+	pub(crate) fn ensure_sale_state(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		valid_state: impl Fn(SaleState) -> bool,
+	) -> Result<(), DispatchError> {
+		ensure!(valid_state(pool.sale.state(T::Time::now())), Error::<T>::InvalidSaleState);
+		Ok(())
+	}
+
+	fn get_pool_balances(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, (Permill, u128)> {
+		Self::current_weights(pool)
+			.into_iter()
+			.map(|(asset_id, weight)| {
+				(
+					asset_id,
+					(weight, T::Convert::convert(T::Assets::balance(asset_id, pool_account))),
+				)
+			})
+			.collect()
+	}
+
+	/// Only the owner can provide liquidity, up until the end of the sale. As the pool has no LP
+	/// token, `min_mint_amount` must be zero.
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		assets: BTreeMap<T::AssetId, T::Balance>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<(T::Balance, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
+		ensure!(*who == pool.owner, Error::<T>::MustBeOwner);
+		Self::ensure_sale_state(&pool, |state| state != SaleState::Ended)?;
+		ensure!(min_mint_amount.is_zero(), Error::<T>::NoLpTokenForLbp);
+		ensure!(!assets.is_empty(), Error::<T>::MustDepositMinimumOneAsset);
+
+		for (asset_id, amount) in &assets {
+			ensure!(
+				*asset_id == pool.pair.base || *asset_id == pool.pair.quote,
+				Error::<T>::AssetNotFound
+			);
+			ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		Ok((Zero::zero(), assets))
+	}
+
+	/// Once the sale has ended, the owner withdraws all of the pool's liquidity. As the pool has
+	/// no LP token, `lp_amount` is ignored.
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		mut min_receive: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		ensure!(*who == pool.owner, Error::<T>::MustBeOwner);
+		Self::ensure_sale_state(&pool, |state| state == SaleState::Ended)?;
+
+		let redeemed_assets = [pool.pair.base, pool.pair.quote]
+			.into_iter()
+			.map(|asset_id| {
+				let amount = T::Assets::balance(asset_id, &pool_account);
+				if let Some(min_amount) = min_receive.remove(&asset_id) {
+					ensure!(amount >= min_amount, Error::<T>::CannotRespectMinimumRequested);
+				}
+				Ok((asset_id, amount))
+			})
+			.collect::<Result<BTreeMap<_, _>, DispatchError>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (asset_id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*asset_id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		Ok(redeemed_assets)
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_i, b_i) = pool_assets.get(&in_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_o, b_o) = pool_assets.get(&out_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_out_given_in(
+			*w_i,
+			*w_o,
+			*b_i,
+			*b_o,
+			T::Convert::convert(in_asset.amount),
+			fee,
+		)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((a_out, in_asset, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &LiquidityBootstrappingPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_o, b_o) = pool_assets.get(&out_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let (w_i, b_i) = pool_assets.get(&in_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let amm_pair = compute_in_given_out(
+			*w_i,
+			*w_o,
+			*b_i,
+			*b_o,
+			T::Convert::convert(out_asset.amount),
+			fee,
+		)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
@@ -1,28 +1,42 @@
+use codec::Encode;
 use composable_traits::fnft::FinancialNft;
 use frame_support::{
 	dispatch::DispatchResult,
+	ensure,
+	storage::unhashed,
 	traits::tokens::nonfungibles::{Create, Inspect, Mutate},
 };
 use sp_runtime::DispatchError;
 
+/// Owners and instance counters are kept in unhashed storage, scoped to the test externalities.
 pub struct MockFnft;
 
+fn owner_key(collection: &u128, item: &u64) -> Vec<u8> {
+	(b"mockfnft:owner", collection, item).encode()
+}
+
+fn next_id_key(collection: &u128) -> Vec<u8> {
+	(b"mockfnft:next_id", collection).encode()
+}
+
 impl Inspect<u128> for MockFnft {
 	type ItemId = u64;
 	type CollectionId = u128;
 
-	fn owner(_collection: &Self::CollectionId, _item: &Self::ItemId) -> Option<u128> {
-		todo!()
+	fn owner(collection: &Self::CollectionId, item: &Self::ItemId) -> Option<u128> {
+		unhashed::get(&owner_key(collection, item))
 	}
 }
 
 impl FinancialNft<u128> for MockFnft {
-	fn asset_account(_collection: &Self::CollectionId, _instance: &Self::ItemId) -> u128 {
-		todo!()
+	fn asset_account(collection: &Self::CollectionId, instance: &Self::ItemId) -> u128 {
+		u128::from_le_bytes(sp_io::hashing::blake2_128(
+			&(b"mockfnft:account", collection, instance).encode(),
+		))
 	}
 
-	fn get_next_nft_id(_collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
-		todo!()
+	fn get_next_nft_id(collection: &Self::CollectionId) -> Result<Self::ItemId, DispatchError> {
+		Ok(unhashed::get_or_default(&next_id_key(collection)))
 	}
 }
 
@@ -36,4 +50,28 @@ impl Create<u128> for MockFnft {
 	}
 }
 
-impl Mutate<u128> for MockFnft {}
+impl Mutate<u128> for MockFnft {
+	fn mint_into(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		who: &u128,
+	) -> DispatchResult {
+		ensure!(Self::owner(collection, item).is_none(), "item already minted");
+		unhashed::put(&owner_key(collection, item), who);
+		unhashed::put(&next_id_key(collection), &item.saturating_add(1));
+		Ok(())
+	}
+
+	fn burn(
+		collection: &Self::CollectionId,
+		item: &Self::ItemId,
+		maybe_check_owner: Option<&u128>,
+	) -> DispatchResult {
+		let owner = Self::owner(collection, item).ok_or("item not found")?;
+		if let Some(check_owner) = maybe_check_owner {
+			ensure!(owner == *check_owner, "not the owner of the item");
+		}
+		unhashed::kill(&owner_key(collection, item));
+		Ok(())
+	}
+}
@@ -0,0 +1,281 @@
+use crate::{AssetIdOf, Config, Error, PoolConfiguration, PoolCount, Pools};
+use composable_maths::dex::{
+	constant_product::compute_redeemed_for_lp,
+	stable_swap::{
+		compute_deposit_lp, compute_first_deposit_lp, compute_in_given_out, compute_out_given_in,
+	},
+};
+use composable_support::{collections::vec::bounded::BiBoundedVec, math::safe::SafeAdd};
+use composable_traits::{
+	currency::{CurrencyFactory, RangeId},
+	defi::CurrencyPair,
+	dex::{AssetAmount, BasicPoolInfo, Fee, FeeConfig, StableSwapPoolInfo},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Mutate, Transfer},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	BoundedBTreeMap, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+// Curve StableSwap Pool
+pub(crate) struct StableSwap<T>(PhantomData<T>);
+
+impl<T: Config> StableSwap<T> {
+	/// StableSwap pools do not use weights in their invariant; both assets are reported as being
+	/// weighted equally.
+	pub(crate) fn assets_weights(
+		pair: CurrencyPair<T::AssetId>,
+	) -> Result<BoundedBTreeMap<T::AssetId, Permill, ConstU32<2>>, DispatchError> {
+		ensure!(pair.base != pair.quote, Error::<T>::InvalidPair);
+		let mut assets_weights = BoundedBTreeMap::new();
+		assets_weights
+			.try_insert(pair.base, Permill::from_percent(50))
+			.map_err(|_| Error::<T>::InvalidPair)?;
+		assets_weights
+			.try_insert(pair.quote, Permill::from_percent(50))
+			.map_err(|_| Error::<T>::InvalidPair)?;
+		Ok(assets_weights)
+	}
+
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		fee_config: FeeConfig,
+		assets_weights: BoundedBTreeMap<T::AssetId, Permill, ConstU32<2>>,
+		amplification_coefficient: u16,
+		lp_token_id: Option<AssetIdOf<T>>,
+	) -> Result<T::PoolId, DispatchError> {
+		ensure!(assets_weights.len() == 2, Error::<T>::InvalidPair);
+		ensure!(amplification_coefficient > 0, Error::<T>::AmpFactorMustBeGreaterThanZero);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let lp_token = lp_token_id.unwrap_or(T::CurrencyFactory::create(RangeId::LP_TOKENS)?);
+
+		// Add new pool
+		let pool_id =
+			PoolCount::<T>::try_mutate(|pool_count| -> Result<T::PoolId, DispatchError> {
+				let pool_id = *pool_count;
+				Pools::<T>::insert(
+					pool_id,
+					PoolConfiguration::StableSwap(StableSwapPoolInfo {
+						pool_info: BasicPoolInfo {
+							owner: who.clone(),
+							assets_weights,
+							lp_token,
+							fee_config,
+						},
+						amplification_coefficient,
+					}),
+				);
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok(pool_id)
+			})?;
+
+		Ok(pool_id)
+	}
+
+	/// Returns the balances of the pool's assets, in the order of the assets in the pool.
+	///
+	/// WARNING! This does one storage read per asset in the pool!
+	fn get_pool_balances(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, u128> {
+		pool.pool_info
+			.assets_weights
+			.keys()
+			.map(|asset_id| {
+				(*asset_id, T::Convert::convert(T::Assets::balance(*asset_id, pool_account)))
+			})
+			.collect()
+	}
+
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: T::AccountId,
+		assets: BiBoundedVec<AssetAmount<T::AssetId, T::Balance>, 1, 2>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<(T::Balance, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
+		let pool_balances = Self::get_pool_balances(&pool, &pool_account);
+
+		let mut deposits = pool_balances
+			.keys()
+			.map(|asset_id| (*asset_id, 0_u128))
+			.collect::<BTreeMap<_, _>>();
+		for asset_amount in assets {
+			ensure!(!asset_amount.amount.is_zero(), Error::<T>::InvalidAmount);
+			let deposit =
+				deposits.get_mut(&asset_amount.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+			*deposit = T::Convert::convert(asset_amount.amount);
+		}
+
+		let balances = pool_balances.values().copied().collect::<Vec<_>>();
+		let deposit_amounts = deposits.values().copied().collect::<Vec<_>>();
+		let amp_coeff = u128::from(pool.amplification_coefficient);
+		let lp_total_issuance =
+			T::Convert::convert(T::Assets::total_issuance(pool.pool_info.lp_token));
+
+		let amount_of_lp_token_to_mint = if lp_total_issuance.is_zero() {
+			ensure!(
+				deposit_amounts.iter().all(|amount| !amount.is_zero()),
+				Error::<T>::InitialDepositMustContainAllAssets
+			);
+			compute_first_deposit_lp(amp_coeff, &deposit_amounts)?
+		} else {
+			compute_deposit_lp(
+				lp_total_issuance,
+				amp_coeff,
+				&balances,
+				&deposit_amounts,
+				pool.pool_info.fee_config.fee_rate,
+			)?
+			.value
+		};
+		let amount_of_lp_token_to_mint = T::Convert::convert(amount_of_lp_token_to_mint);
+
+		ensure!(
+			amount_of_lp_token_to_mint >= min_mint_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		let actual_amounts_deposited = deposits
+			.into_iter()
+			.filter(|(_, amount)| !amount.is_zero())
+			.map(|(asset_id, amount)| (asset_id, T::Convert::convert(amount)))
+			.collect::<BTreeMap<_, _>>();
+
+		for (asset_id, amount) in &actual_amounts_deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		T::Assets::mint_into(pool.pool_info.lp_token, who, amount_of_lp_token_to_mint)?;
+
+		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited))
+	}
+
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		mut min_receive: BoundedBTreeMap<T::AssetId, T::Balance, ConstU32<2>>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let lp_total_issuance =
+			T::Convert::convert(T::Assets::total_issuance(pool.pool_info.lp_token));
+
+		// liquidity is always removed in a balanced way, so the invariant is not needed here
+		let redeemed_assets = Self::get_pool_balances(&pool, &pool_account)
+			.into_iter()
+			.map(|(id, balance)| {
+				let redeemed_amount = compute_redeemed_for_lp(
+					lp_total_issuance,
+					T::Convert::convert(lp_amount),
+					balance,
+					Permill::one(),
+				)?;
+
+				if let Some(min_amount) = min_receive.remove(&id) {
+					ensure!(
+						redeemed_amount >= T::Convert::convert(min_amount),
+						Error::<T>::CannotRespectMinimumRequested
+					);
+				}
+
+				Ok::<_, DispatchError>((id, T::Convert::convert(redeemed_amount)))
+			})
+			.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		T::Assets::burn_from(pool.pool_info.lp_token, who, lp_amount)?;
+
+		Ok(redeemed_assets)
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_balances = Self::get_pool_balances(pool, pool_account);
+		let fee = if apply_fees { pool.pool_info.fee_config.fee_rate } else { Permill::zero() };
+		let b_i = pool_balances.get(&in_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let b_o = pool_balances.get(&out_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let value_fee_pair = compute_out_given_in(
+			u128::from(pool.amplification_coefficient),
+			*b_i,
+			*b_o,
+			T::Convert::convert(in_asset.amount),
+			fee,
+		)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(value_fee_pair.value));
+		let fee = pool
+			.pool_info
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(value_fee_pair.fee));
+
+		Ok((a_out, in_asset, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &StableSwapPoolInfo<T::AccountId, T::AssetId, ConstU32<2>>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let pool_balances = Self::get_pool_balances(pool, pool_account);
+		let fee = if apply_fees { pool.pool_info.fee_config.fee_rate } else { Permill::zero() };
+		let b_o = pool_balances.get(&out_asset.asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		let b_i = pool_balances.get(&in_asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+		let value_fee_pair = compute_in_given_out(
+			u128::from(pool.amplification_coefficient),
+			*b_i,
+			*b_o,
+			T::Convert::convert(out_asset.amount),
+			fee,
+		)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(value_fee_pair.value));
+		let fee = pool
+			.pool_info
+			.fee_config
+			.calculate_fees(in_asset_id, T::Convert::convert(value_fee_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+}
@@ -2,7 +2,10 @@ use crate::{
 	mock,
 	mock::{Pablo, *},
 	Config,
-	PoolConfiguration::DualAssetConstantProduct,
+	PoolConfiguration::{
+		ConcentratedLiquidity, DualAssetConstantProduct, LiquidityBootstrapping, StableSwap,
+		WeightedProduct,
+	},
 	PoolInitConfiguration,
 };
 use composable_traits::dex::AssetAmount;
@@ -33,7 +36,7 @@ pub fn dual_asset_pool_weights(
 /// `expected_lp_check` takes base_amount, quote_amount and lp_tokens in order and returns
 /// true if lp_tokens are expected for given base_amount, quote_amount.
 pub fn common_add_remove_lp(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment>,
 	first_asset_amount: Balance,
 	second_asset_amount: Balance,
 	next_first_asset_amount: Balance,
@@ -74,6 +77,10 @@ pub fn common_add_remove_lp(
 	let pool = Pablo::pools(actual_pool_id).expect("pool not found");
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		WeightedProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => panic!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => panic!("concentrated liquidity pools have no lp token"),
 	};
 	// Mint the tokens
 	assert_ok!(Tokens::mint_into(pair[0], &BOB, next_first_asset_amount));
@@ -113,7 +120,9 @@ pub fn common_add_remove_lp(
 	assert_eq!(lp, 0_u128);
 }
 
-pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [AssetId; 2] {
+pub fn get_pair(
+	init_config: PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment>,
+) -> [AssetId; 2] {
 	match init_config {
 		PoolInitConfiguration::DualAssetConstantProduct { assets_weights, .. } => assets_weights
 			.keys()
@@ -121,6 +130,15 @@ pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [Asse
 			.collect::<Vec<_>>()
 			.try_into()
 			.expect("pool should have exactly 2 assets; qed;"),
+		PoolInitConfiguration::StableSwap { pair, .. } => [pair.base, pair.quote],
+		PoolInitConfiguration::WeightedProduct { assets_weights, .. } => assets_weights
+			.keys()
+			.copied()
+			.collect::<Vec<_>>()
+			.try_into()
+			.expect("pool should have exactly 2 assets; qed;"),
+		PoolInitConfiguration::LiquidityBootstrapping { pair, .. } |
+		PoolInitConfiguration::ConcentratedLiquidity { pair, .. } => [pair.base, pair.quote],
 	}
 }
 
@@ -128,7 +146,7 @@ pub fn get_pair(init_config: PoolInitConfiguration<AccountId, AssetId>) -> [Asse
 /// `pool_base_amount` and `pool_quote_amount` parameters and returns amount of expected new
 /// lp_tokens.
 pub fn common_add_lp_with_min_mint_amount(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment>,
 	init_first_asset_amount: Balance,
 	init_second_asset_amount: Balance,
 	first_asset_amount: Balance,
@@ -149,6 +167,10 @@ pub fn common_add_lp_with_min_mint_amount(
 
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		WeightedProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => panic!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => panic!("concentrated liquidity pools have no lp token"),
 	};
 
 	let [first_asset, second_asset] = get_pair(init_config);
@@ -211,7 +233,7 @@ pub fn common_add_lp_with_min_mint_amount(
 }
 
 pub fn common_remove_lp_failure(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment>,
 	init_base_amount: Balance,
 	init_quote_amount: Balance,
 	base_amount: Balance,
@@ -237,6 +259,10 @@ pub fn common_remove_lp_failure(
 	let pool = Pablo::pools(pool_id).expect("pool not found");
 	let lp_token = match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		WeightedProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => panic!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => panic!("concentrated liquidity pools have no lp token"),
 	};
 	// Mint the tokens
 	assert_ok!(Tokens::mint_into(pair[0], &BOB, base_amount));
@@ -281,7 +307,7 @@ pub fn common_remove_lp_failure(
 }
 
 pub fn common_exchange_failure(
-	init_config: PoolInitConfiguration<AccountId, AssetId>,
+	init_config: PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment>,
 	init_first_amount: AssetAmount<AssetId, Balance>,
 	init_second_amount: AssetAmount<AssetId, Balance>,
 	exchange_first_amount: AssetAmount<AssetId, Balance>,
@@ -317,7 +343,8 @@ pub fn common_exchange_failure(
 			pool_id,
 			AssetAmount::new(exchange_first_amount.asset_id, exchange_first_amount.amount + 1),
 			AssetAmount::new(init_second_amount.asset_id, 0),
-			false
+			false,
+			None
 		),
 		orml_tokens::Error::<Test>::BalanceTooLow
 	);
@@ -329,7 +356,8 @@ pub fn common_exchange_failure(
 			pool_id,
 			AssetAmount::new(exchange_first_amount.asset_id, exchange_first_amount.amount),
 			AssetAmount::new(init_second_amount.asset_id, dbg!(init_second_amount.amount + 1)),
-			false
+			false,
+			None
 		),
 		crate::Error::<Test>::CannotRespectMinimumRequested
 	);
@@ -0,0 +1,449 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	mock_fnft::MockFnft,
+	ConcentratedLiquidityStates, ConcentratedLiquidityTickBitmap, ConcentratedLiquidityTicks,
+	Error, Event, PoolConfiguration, PoolInitConfiguration, PoolInitConfigurationOf,
+};
+use composable_maths::dex::concentrated_liquidity::MAX_TICK;
+use composable_tests_helpers::test::helper::RuntimeTrait;
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount},
+	fnft::FinancialNft,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungibles::{Inspect, Mutate},
+		tokens::nonfungibles::Inspect as NonFungiblesInspect,
+	},
+};
+use sp_runtime::Permill;
+use sp_std::collections::btree_map::BTreeMap;
+
+const UNIT: u128 = 1_000_000_000_000;
+
+fn cl_init_config(tick_spacing: u32, initial_tick: i32) -> PoolInitConfigurationOf<Test> {
+	PoolInitConfiguration::ConcentratedLiquidity {
+		owner: ALICE,
+		pair: CurrencyPair::new(BTC, USDT),
+		tick_spacing,
+		initial_tick,
+		fee: Permill::from_percent(1),
+	}
+}
+
+/// Creates a BTC/USDT pool with a tick spacing of 10, where 1 BTC is worth 1 USDT.
+fn create_pool() -> u128 {
+	create_pool_with_tick_spacing(10)
+}
+
+fn create_pool_with_tick_spacing(tick_spacing: u32) -> u128 {
+	Test::assert_extrinsic_event_with(
+		Pablo::create(RuntimeOrigin::root(), cl_init_config(tick_spacing, 0)),
+		|e| match e {
+			Event::PoolCreated { pool_id, .. } => Some(pool_id),
+			_ => None,
+		},
+	)
+}
+
+/// Mints the given amounts to `who` and opens a position with them, returning its id.
+fn open_position(
+	pool_id: u128,
+	who: AccountId,
+	lower_tick: i32,
+	upper_tick: i32,
+	btc_amount: u128,
+	usdt_amount: u128,
+) -> u64 {
+	let mut assets = BTreeMap::new();
+	for (asset_id, amount) in [(BTC, btc_amount), (USDT, usdt_amount)] {
+		if amount != 0 {
+			Tokens::mint_into(asset_id, &who, amount).unwrap();
+			assets.insert(asset_id, amount);
+		}
+	}
+	Test::assert_extrinsic_event_with(
+		Pablo::open_position(
+			RuntimeOrigin::signed(who),
+			pool_id,
+			lower_tick,
+			upper_tick,
+			assets,
+			0,
+			false,
+		),
+		|e| match e {
+			Event::PositionOpened { position_id, .. } => Some(position_id),
+			_ => None,
+		},
+	)
+}
+
+fn position_collection(pool_id: u128) -> u128 {
+	match Pablo::pools(pool_id).unwrap() {
+		PoolConfiguration::ConcentratedLiquidity(pool) => pool.position_collection,
+		_ => panic!("expected a concentrated liquidity pool"),
+	}
+}
+
+fn swap_usdt_for_btc(pool_id: u128, who: AccountId, amount: u128) -> u128 {
+	Tokens::mint_into(USDT, &who, amount).unwrap();
+	<Pablo as Amm>::do_swap(
+		&who,
+		pool_id,
+		AssetAmount::new(USDT, amount),
+		AssetAmount::new(BTC, 0),
+		false,
+	)
+	.unwrap()
+	.value
+	.amount
+}
+
+fn collect_fees(pool_id: u128, who: AccountId, position_id: u64) -> BTreeMap<CurrencyId, u128> {
+	Test::assert_extrinsic_event_with(
+		Pablo::collect_position_fees(RuntimeOrigin::signed(who), pool_id, position_id),
+		|e| match e {
+			Event::PositionFeesCollected { fees, .. } => Some(fees),
+			_ => None,
+		},
+	)
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_successfully_create_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::ConcentratedLiquidity(pool) => {
+					assert_eq!(pool.owner, ALICE);
+					assert_eq!(pool.pair, CurrencyPair::new(BTC, USDT));
+					assert_eq!(pool.tick_spacing, 10);
+				},
+				_ => panic!("expected a concentrated liquidity pool"),
+			}
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert_eq!(state.tick, 0);
+			assert_eq!(state.liquidity, 0);
+			assert_eq!(
+				<Pablo as Amm>::assets(pool_id).unwrap(),
+				BTreeMap::from([
+					(BTC, Permill::from_percent(50)),
+					(USDT, Permill::from_percent(50))
+				])
+			);
+			assert_noop!(
+				<Pablo as Amm>::lp_token(pool_id),
+				Error::<Test>::NoLpTokenForConcentratedLiquidity
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_with_invalid_ticks() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(cl_init_config(0, 0), None),
+				Error::<Test>::InvalidTickRange
+			);
+			assert_noop!(
+				Pablo::do_create_pool(cl_init_config(10, MAX_TICK + 1), None),
+				Error::<Test>::InvalidTickRange
+			);
+		});
+	}
+}
+
+mod open_position {
+	use super::*;
+
+	#[test]
+	fn should_deposit_both_assets_within_range() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let position_id = open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+
+			let pool_account = Pablo::account_id(&pool_id);
+			assert_eq!(Tokens::balance(BTC, &pool_account), 1_000 * UNIT);
+			assert_eq!(Tokens::balance(USDT, &pool_account), 1_000 * UNIT);
+			assert_eq!(MockFnft::owner(&position_collection(pool_id), &position_id), Some(ALICE));
+
+			let position = Pablo::positions(pool_id, position_id).unwrap();
+			assert_eq!((position.lower_tick, position.upper_tick), (-100, 100));
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert_eq!(state.liquidity, position.liquidity);
+		});
+	}
+
+	#[test]
+	fn should_deposit_a_single_asset_out_of_range() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			// the range is above the current price, only the base asset is needed
+			open_position(pool_id, ALICE, 100, 200, 1_000 * UNIT, 0);
+
+			let pool_account = Pablo::account_id(&pool_id);
+			assert_eq!(Tokens::balance(BTC, &pool_account), 1_000 * UNIT);
+			assert_eq!(Tokens::balance(USDT, &pool_account), 0);
+			// liquidity is not active at the current price
+			assert_eq!(ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap().liquidity, 0);
+		});
+	}
+
+	#[test]
+	fn should_fail_with_invalid_range() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			Tokens::mint_into(BTC, &ALICE, UNIT).unwrap();
+			let assets = BTreeMap::from([(BTC, UNIT)]);
+			for (lower_tick, upper_tick) in [(100, 100), (200, 100), (100, 205)] {
+				assert_noop!(
+					Pablo::open_position(
+						RuntimeOrigin::signed(ALICE),
+						pool_id,
+						lower_tick,
+						upper_tick,
+						assets.clone(),
+						0,
+						false,
+					),
+					Error::<Test>::InvalidTickRange
+				);
+			}
+		});
+	}
+
+	#[test]
+	fn should_fail_when_min_liquidity_is_not_respected() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			Tokens::mint_into(BTC, &ALICE, UNIT).unwrap();
+			assert_noop!(
+				Pablo::open_position(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					100,
+					200,
+					BTreeMap::from([(BTC, UNIT)]),
+					u128::MAX,
+					false,
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+		});
+	}
+
+	#[test]
+	fn should_not_support_lp_tokens() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			Tokens::mint_into(BTC, &ALICE, UNIT).unwrap();
+			Tokens::mint_into(USDT, &ALICE, UNIT).unwrap();
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					BTreeMap::from([(BTC, UNIT), (USDT, UNIT)]),
+					0,
+					false,
+				),
+				Error::<Test>::NoLpTokenForConcentratedLiquidity
+			);
+		});
+	}
+}
+
+mod swap {
+	use super::*;
+
+	#[test]
+	fn should_swap_and_accrue_fees_to_position() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let position_id = open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+
+			let expected =
+				<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(USDT, 10 * UNIT), BTC, true)
+					.unwrap();
+			let received = swap_usdt_for_btc(pool_id, BOB, 10 * UNIT);
+			assert_eq!(received, expected.value.amount);
+			assert_eq!(expected.fee, AssetAmount::new(USDT, UNIT / 10));
+			// the price of BTC went up while swapping
+			assert!(received < 9_900_000_000_000 && received > 9_800_000_000_000);
+			assert_eq!(Tokens::balance(BTC, &BOB), received);
+
+			// all of the fee goes to the only position, minus rounding
+			let fees = collect_fees(pool_id, ALICE, position_id);
+			assert_eq!(fees.get(&BTC), Some(&0));
+			assert!(UNIT / 10 - fees[&USDT] <= 1);
+			assert_eq!(Tokens::balance(USDT, &ALICE), fees[&USDT]);
+			// fees are only collected once
+			assert_eq!(collect_fees(pool_id, ALICE, position_id)[&USDT], 0);
+		});
+	}
+
+	#[test]
+	fn should_cross_initialized_ticks() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let alice_position =
+				open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+			let charlie_position = open_position(pool_id, CHARLIE, 100, 200, 1_000 * UNIT, 0);
+
+			// more than the BTC available within ALICE's range
+			swap_usdt_for_btc(pool_id, BOB, 1_500 * UNIT);
+
+			let state = ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap();
+			assert!(state.tick >= 100 && state.tick < 200);
+			assert_eq!(
+				state.liquidity,
+				Pablo::positions(pool_id, charlie_position).unwrap().liquidity
+			);
+
+			// both positions earned fees, together the whole fee minus rounding
+			let alice_fees = collect_fees(pool_id, ALICE, alice_position)[&USDT];
+			let charlie_fees = collect_fees(pool_id, CHARLIE, charlie_position)[&USDT];
+			assert!(alice_fees > 0 && charlie_fees > 0);
+			assert!(15 * UNIT - (alice_fees + charlie_fees) <= 2);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_not_enough_liquidity() {
+		new_test_ext().execute_with(|| {
+			// wide enough tick spacing for the price to reach the edge within a few steps
+			let pool_id = create_pool_with_tick_spacing(1_000);
+			open_position(pool_id, ALICE, -1_000, 1_000, 1_000 * UNIT, 1_000 * UNIT);
+
+			Tokens::mint_into(USDT, &BOB, 10_000 * UNIT).unwrap();
+			assert_noop!(
+				<Pablo as Amm>::do_swap(
+					&BOB,
+					pool_id,
+					AssetAmount::new(USDT, 10_000 * UNIT),
+					AssetAmount::new(BTC, 0),
+					false,
+				),
+				Error::<Test>::NotEnoughLiquidity
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_crossing_too_many_ticks() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+
+			// the price runs past the last position one word of the tick bitmap at a time
+			Tokens::mint_into(USDT, &BOB, 10_000 * UNIT).unwrap();
+			assert_noop!(
+				<Pablo as Amm>::do_swap(
+					&BOB,
+					pool_id,
+					AssetAmount::new(USDT, 10_000 * UNIT),
+					AssetAmount::new(BTC, 0),
+					false,
+				),
+				Error::<Test>::SwapTooLarge
+			);
+		});
+	}
+
+	#[test]
+	fn should_buy_exact_amount() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+
+			Tokens::mint_into(USDT, &BOB, 11 * UNIT).unwrap();
+			assert_ok!(Pablo::buy(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				USDT,
+				AssetAmount::new(BTC, 10 * UNIT),
+				false,
+				None,
+			));
+			assert_eq!(Tokens::balance(BTC, &BOB), 10 * UNIT);
+			// 10 USDT, the slippage and a 1% fee
+			let paid = 11 * UNIT - Tokens::balance(USDT, &BOB);
+			assert!(paid > 10_100 * UNIT / 1_000 && paid < 10_200 * UNIT / 1_000);
+		});
+	}
+}
+
+mod close_position {
+	use super::*;
+
+	#[test]
+	fn should_withdraw_liquidity_and_fees() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let position_id = open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+			let received = swap_usdt_for_btc(pool_id, BOB, 10 * UNIT);
+
+			let (asset_amounts, fees) = Test::assert_extrinsic_event_with(
+				Pablo::close_position(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					position_id,
+					BTreeMap::new(),
+				),
+				|e| match e {
+					Event::PositionClosed { asset_amounts, fees, .. } =>
+						Some((asset_amounts, fees)),
+					_ => None,
+				},
+			);
+
+			// BOB's swap is reflected in the withdrawn amounts, up to rounding in favour of the
+			// pool
+			assert!(1_000 * UNIT - received - asset_amounts[&BTC] <= 1);
+			assert!(1_010 * UNIT - UNIT / 10 - asset_amounts[&USDT] <= 1);
+			assert!(UNIT / 10 - fees[&USDT] <= 1);
+			assert_eq!(Tokens::balance(BTC, &ALICE), asset_amounts[&BTC]);
+			assert_eq!(Tokens::balance(USDT, &ALICE), asset_amounts[&USDT] + fees[&USDT]);
+
+			assert_eq!(Pablo::positions(pool_id, position_id), None);
+			assert_eq!(MockFnft::owner(&position_collection(pool_id), &position_id), None);
+			assert_eq!(ConcentratedLiquidityStates::<Test>::get(pool_id).unwrap().liquidity, 0);
+			assert_eq!(ConcentratedLiquidityTicks::<Test>::iter_prefix(pool_id).count(), 0);
+			assert_eq!(ConcentratedLiquidityTickBitmap::<Test>::iter_prefix(pool_id).count(), 0);
+			// nothing is left behind in the position's account
+			let position_account =
+				MockFnft::asset_account(&position_collection(pool_id), &position_id);
+			assert_eq!(Tokens::balance(USDT, &position_account), 0);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_not_owner() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool();
+			let position_id = open_position(pool_id, ALICE, -100, 100, 1_000 * UNIT, 1_000 * UNIT);
+
+			assert_noop!(
+				Pablo::close_position(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					position_id,
+					BTreeMap::new()
+				),
+				Error::<Test>::MustBeOwner
+			);
+			assert_noop!(
+				Pablo::collect_position_fees(RuntimeOrigin::signed(BOB), pool_id, position_id),
+				Error::<Test>::MustBeOwner
+			);
+		});
+	}
+}
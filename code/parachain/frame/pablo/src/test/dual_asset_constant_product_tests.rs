@@ -9,7 +9,10 @@ use crate::{
 	pallet,
 	test::common_test_functions::*,
 	Error,
-	PoolConfiguration::DualAssetConstantProduct,
+	PoolConfiguration::{
+		ConcentratedLiquidity, DualAssetConstantProduct, LiquidityBootstrapping, StableSwap,
+		WeightedProduct,
+	},
 	PoolInitConfiguration,
 };
 use composable_maths::dex::constant_product::{compute_deposit_lp, compute_first_deposit_lp};
@@ -24,7 +27,10 @@ use composable_tests_helpers::{
 		},
 	},
 };
-use composable_traits::dex::{Amm, AssetAmount, BasicPoolInfo, FeeConfig};
+use composable_traits::{
+	dex::{Amm, AssetAmount, BasicPoolInfo, FeeConfig},
+	oracle::LiquidityPools,
+};
 use frame_support::{
 	assert_noop, assert_ok,
 	traits::fungibles::{Inspect, Mutate},
@@ -32,7 +38,7 @@ use frame_support::{
 use proptest::prelude::*;
 use sp_runtime::{
 	traits::{ConstU32, IntegerSquareRoot},
-	BoundedBTreeMap, DispatchError, Perbill, Permill, TokenError,
+	BoundedBTreeMap, DispatchError, FixedPointNumber, FixedU128, Perbill, Permill, TokenError,
 };
 use sp_std::collections::btree_map::BTreeMap;
 
@@ -83,6 +89,10 @@ fn create_pool(
 fn get_pool(pool_id: PoolId) -> BasicPoolInfo<AccountId, AssetId, ConstU32<2>> {
 	match Pablo::pools(pool_id).expect("pool not found") {
 		DualAssetConstantProduct(pool) => pool,
+		StableSwap(_) |
+		WeightedProduct(_) |
+		LiquidityBootstrapping(_) |
+		ConcentratedLiquidity(_) => panic!("expected a dual asset constant product pool"),
 	}
 }
 
@@ -202,7 +212,7 @@ pub fn valid_pool_init_config(
 	first_asset_weight: Permill,
 	second_asset: AssetId,
 	fee: Permill,
-) -> PoolInitConfiguration<AccountId, AssetId> {
+) -> PoolInitConfiguration<AccountId, AssetId, MaxAssetsPerPool, Moment> {
 	PoolInitConfiguration::DualAssetConstantProduct {
 		owner: *owner,
 		assets_weights: dual_asset_pool_weights(first_asset, first_asset_weight, second_asset),
@@ -257,7 +267,64 @@ fn test_redeemable_assets() {
 	});
 }
 
-pub fn create_pool_from_config(init_config: PoolInitConfiguration<u128, u128>) -> u128 {
+#[test]
+fn fair_assets_for_lp_tokens_are_not_moved_by_swaps() {
+	new_test_ext().execute_with(|| {
+		// 100 BTC/4.5M USDT
+		let initial_btc = currency::BTC::units(100);
+		let initial_usdt = currency::USDT::units(4_500_000);
+		let pool_id = create_pool(
+			BTC,
+			USDT,
+			initial_btc,
+			initial_usdt,
+			LP_TOKEN_ID,
+			Permill::zero(),
+			Permill::zero(),
+		);
+		let lp = Tokens::balance(LP_TOKEN_ID, &ALICE);
+		let prices = BTreeMap::from([
+			(BTC, FixedU128::saturating_from_rational(initial_usdt, initial_btc)),
+			(USDT, FixedU128::one()),
+		]);
+		let fair_assets = || {
+			<Pablo as LiquidityPools<_, _>>::fair_assets_for_lp_tokens(pool_id, lp, &prices)
+				.expect("fair_assets_for_lp_tokens failed")
+		};
+
+		// the pool is at equilibrium with the prices
+		let fair = fair_assets();
+		assert_ok!(default_acceptable_computation_error(fair[&BTC], initial_btc));
+		assert_ok!(default_acceptable_computation_error(fair[&USDT], initial_usdt));
+
+		// buying most of the BTC of the pool more than doubles the value of its balances at these
+		// prices
+		let usdt_sent = currency::USDT::units(13_500_000);
+		assert_ok!(Tokens::mint_into(USDT, &BOB, usdt_sent));
+		assert_ok!(Pablo::swap(
+			RuntimeOrigin::signed(BOB),
+			pool_id,
+			AssetAmount::new(USDT, usdt_sent),
+			AssetAmount::new(BTC, 0),
+			false,
+			None
+		));
+		let redeemable = <Pablo as Amm>::redeemable_assets_for_lp_tokens(pool_id, lp)
+			.expect("redeemable_assets failed");
+		assert_ok!(default_acceptable_computation_error(
+			redeemable[&USDT],
+			initial_usdt + usdt_sent
+		));
+
+		let fair = fair_assets();
+		assert_ok!(default_acceptable_computation_error(fair[&BTC], initial_btc));
+		assert_ok!(default_acceptable_computation_error(fair[&USDT], initial_usdt));
+	});
+}
+
+pub fn create_pool_from_config(
+	init_config: PoolInitConfiguration<u128, u128, MaxAssetsPerPool, Moment>,
+) -> u128 {
 	Test::assert_extrinsic_event_with(Pablo::create(RuntimeOrigin::root(), init_config), |event| {
 		match event {
 			crate::Event::PoolCreated { pool_id, .. } => Some(pool_id),
@@ -414,6 +481,10 @@ pub fn lp_token_of_pool(pool_id: u128) -> u128 {
 
 	match pool {
 		DualAssetConstantProduct(pool) => pool.lp_token,
+		StableSwap(pool) => pool.pool_info.lp_token,
+		WeightedProduct(pool) => pool.lp_token,
+		LiquidityBootstrapping(_) => panic!("liquidity bootstrapping pools have no lp token"),
+		ConcentratedLiquidity(_) => panic!("concentrated liquidity pools have no lp token"),
 	}
 }
 
@@ -735,7 +806,8 @@ fn cannot_swap_between_wrong_pairs() {
 				pool_id,
 				AssetAmount::new(USDC, usdc_amount),
 				AssetAmount::new(BTC, 0_u128),
-				false
+				false,
+				None
 			),
 			Error::<Test>::AssetNotFound
 		);
@@ -745,7 +817,8 @@ fn cannot_swap_between_wrong_pairs() {
 				pool_id,
 				AssetAmount::new(BTC, usdc_amount),
 				AssetAmount::new(USDC, 0_u128),
-				false
+				false,
+				None
 			),
 			Error::<Test>::AssetNotFound
 		);
@@ -844,7 +917,8 @@ proptest! {
 					pool_id,
 					AssetAmount::new(USDT, usdt_value),
 					AssetAmount::new(BTC, 0_u128),
-					false
+					false,
+					None
 				)
 			);
 			let bob_btc = Tokens::balance(BTC, &BOB);
@@ -859,7 +933,8 @@ proptest! {
 					pool_id,
 					BTC,
 					AssetAmount::new(USDT, usdt_value),
-					false
+					false,
+					None
 				)
 			);
 			let bob_usdt = Tokens::balance(USDT, &BOB);
@@ -935,7 +1010,7 @@ proptest! {
 		  );
 		  let pool = get_pool(pool_id);
 		  prop_assert_ok!(Tokens::mint_into(USDT, &BOB, usdt_value));
-		  prop_assert_ok!(Pablo::swap(RuntimeOrigin::signed(BOB), pool_id, AssetAmount::new(USDT, usdt_value), AssetAmount::new(BTC, 0), false));
+		  prop_assert_ok!(Pablo::swap(RuntimeOrigin::signed(BOB), pool_id, AssetAmount::new(USDT, usdt_value), AssetAmount::new(BTC, 0), false, None));
 		  let usdt_value_after_fee = usdt_value - pool.fee_config.fee_rate.mul_floor(usdt_value);
 		  let ratio = initial_btc as f64 / initial_usdt as f64;
 		  let expected_btc_value = ratio * usdt_value_after_fee as f64;
@@ -1008,7 +1083,8 @@ mod twap {
 				pool_id,
 				AssetAmount::new(USDT, usdt_value),
 				AssetAmount::new(BTC, 0),
-				false
+				false,
+				None
 			));
 
 			let price_cumulative =
@@ -1106,7 +1182,8 @@ mod twap {
 					pool_identifier,
 					AssetAmount::new(USDT, usdt_value),
 					AssetAmount::new(BTC, 0),
-					false
+					false,
+					None
 				));
 			};
 			run_to_block_and_swap(5);
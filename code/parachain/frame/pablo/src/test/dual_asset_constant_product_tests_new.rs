@@ -204,7 +204,8 @@ mod do_buy {
 					pool_id,
 					BTC,
 					AssetAmount::new(BTC, 0),
-					false
+					false,
+					None
 				),
 				crate::Error::<Test>::CannotBuyAssetWithItself,
 			);
@@ -301,7 +302,8 @@ mod do_swap {
 					pool_id,
 					AssetAmount::new(BTC, 128_000),
 					AssetAmount::new(BTC, 0),
-					false
+					false,
+					None
 				),
 				crate::Error::<Test>::CannotSwapSameAsset
 			);
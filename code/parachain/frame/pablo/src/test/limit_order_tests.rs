@@ -0,0 +1,314 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	test::{
+		common_test_functions::dual_asset_pool_weights,
+		dual_asset_constant_product_tests::create_pool_from_config,
+	},
+	types::SwapDeadline,
+	Error, Event, LimitOrders, LimitOrdersPerPool, PoolInitConfiguration,
+};
+use composable_tests_helpers::test::{block::next_block, helper::RuntimeTrait};
+use composable_traits::dex::AssetAmount;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{
+		fungibles::{Inspect, Mutate},
+		Get, Hooks,
+	},
+	weights::Weight,
+};
+use sp_runtime::Permill;
+use sp_std::collections::btree_map::BTreeMap;
+
+const UNIT: u128 = 1_000_000_000_000;
+
+/// Creates a fee-less BTC/USDT pool holding 1000 of each.
+fn create_pool() -> u128 {
+	let pool_id = create_pool_from_config(PoolInitConfiguration::DualAssetConstantProduct {
+		owner: ALICE,
+		assets_weights: dual_asset_pool_weights(BTC, Permill::from_percent(50), USDT),
+		fee: Permill::zero(),
+	});
+	Tokens::mint_into(BTC, &ALICE, 1_000 * UNIT).unwrap();
+	Tokens::mint_into(USDT, &ALICE, 1_000 * UNIT).unwrap();
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		BTreeMap::from([(BTC, 1_000 * UNIT), (USDT, 1_000 * UNIT)]),
+		0,
+		false,
+	));
+	pool_id
+}
+
+/// Mints 10 USDT to BOB and places an order swapping them for at least `min_btc` BTC.
+fn place_order(pool_id: u128, min_btc: u128, expires_at: u64) -> u64 {
+	Tokens::mint_into(USDT, &BOB, 10 * UNIT).unwrap();
+	Test::assert_extrinsic_event_with(
+		Pablo::place_limit_order(
+			RuntimeOrigin::signed(BOB),
+			pool_id,
+			AssetAmount::new(USDT, 10 * UNIT),
+			AssetAmount::new(BTC, min_btc),
+			expires_at,
+		),
+		|e| match e {
+			Event::LimitOrderPlaced { order_id, .. } => Some(order_id),
+			_ => None,
+		},
+	)
+}
+
+fn run_keeper() {
+	Pablo::on_idle(System::block_number(), Weight::from_ref_time(u64::MAX));
+}
+
+mod deadline {
+	use super::*;
+
+	#[test]
+	fn swap_reverts_after_block_deadline() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			next_block::<Pablo, Test>();
+			Tokens::mint_into(USDT, &BOB, 20 * UNIT).unwrap();
+
+			let swap = |deadline| {
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, 10 * UNIT),
+					AssetAmount::new(BTC, 0),
+					false,
+					Some(deadline),
+				)
+			};
+			let now = System::block_number();
+			assert_noop!(swap(SwapDeadline::Block(now - 1)), Error::<Test>::DeadlinePassed);
+			assert_ok!(swap(SwapDeadline::Block(now)));
+		});
+	}
+
+	#[test]
+	fn buy_reverts_after_moment_deadline() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			next_block::<Pablo, Test>();
+			Tokens::mint_into(USDT, &BOB, 100 * UNIT).unwrap();
+
+			let buy = |deadline| {
+				Pablo::buy(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					USDT,
+					AssetAmount::new(BTC, 10 * UNIT),
+					false,
+					Some(deadline),
+				)
+			};
+			let now = Timestamp::now();
+			assert_noop!(buy(SwapDeadline::Moment(now - 1)), Error::<Test>::DeadlinePassed);
+			assert_ok!(buy(SwapDeadline::Moment(now + MILLISECS_PER_BLOCK)));
+			assert_eq!(Tokens::balance(BTC, &BOB), 10 * UNIT);
+		});
+	}
+}
+
+mod place_limit_order {
+	use super::*;
+
+	#[test]
+	fn escrows_the_input() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			let order_id = place_order(pool_id, 11 * UNIT, 10);
+
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+			assert_eq!(Tokens::balance(USDT, &Pablo::limit_order_escrow_account()), 10 * UNIT);
+			assert_eq!(Pablo::limit_orders(pool_id, order_id).unwrap().owner, BOB);
+			assert_eq!(LimitOrdersPerPool::<Test>::get(pool_id), 1);
+		});
+	}
+
+	#[test]
+	fn rejects_invalid_orders() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			Tokens::mint_into(USDT, &BOB, 10 * UNIT).unwrap();
+
+			let place = |min_receive, expires_at| {
+				Pablo::place_limit_order(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, 10 * UNIT),
+					min_receive,
+					expires_at,
+				)
+			};
+			assert_noop!(
+				place(AssetAmount::new(USDT, UNIT), 10),
+				Error::<Test>::CannotSwapSameAsset
+			);
+			assert_noop!(place(AssetAmount::new(USDC, UNIT), 10), Error::<Test>::AssetNotFound);
+			assert_noop!(
+				place(AssetAmount::new(BTC, 0), 10),
+				Error::<Test>::AssetAmountMustBePositiveNumber
+			);
+			assert_noop!(
+				place(AssetAmount::new(BTC, UNIT), System::block_number()),
+				Error::<Test>::LimitOrderExpiryInThePast
+			);
+		});
+	}
+
+	#[test]
+	fn is_capped_per_pool() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			for _ in 0..MaxLimitOrdersPerPool::get() {
+				place_order(pool_id, 11 * UNIT, 10);
+			}
+
+			Tokens::mint_into(USDT, &BOB, 10 * UNIT).unwrap();
+			assert_noop!(
+				Pablo::place_limit_order(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, 10 * UNIT),
+					AssetAmount::new(BTC, 11 * UNIT),
+					10,
+				),
+				Error::<Test>::TooManyLimitOrders
+			);
+		});
+	}
+}
+
+mod cancel_limit_order {
+	use super::*;
+
+	#[test]
+	fn refunds_the_owner() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			let order_id = place_order(pool_id, 11 * UNIT, 10);
+
+			assert_noop!(
+				Pablo::cancel_limit_order(RuntimeOrigin::signed(CHARLIE), pool_id, order_id),
+				Error::<Test>::MustBeOwner
+			);
+			assert_ok!(Pablo::cancel_limit_order(RuntimeOrigin::signed(BOB), pool_id, order_id));
+			Test::assert_last_event(Event::<Test>::LimitOrderCancelled {
+				pool_id,
+				order_id,
+				owner: BOB,
+			});
+
+			assert_eq!(Tokens::balance(USDT, &BOB), 10 * UNIT);
+			assert!(LimitOrders::<Test>::get(pool_id, order_id).is_none());
+			assert_eq!(LimitOrdersPerPool::<Test>::get(pool_id), 0);
+			assert_noop!(
+				Pablo::cancel_limit_order(RuntimeOrigin::signed(BOB), pool_id, order_id),
+				Error::<Test>::LimitOrderNotFound
+			);
+		});
+	}
+}
+
+mod keeper {
+	use super::*;
+
+	#[test]
+	fn fills_orders_once_the_price_crosses_the_limit() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			let order_id = place_order(pool_id, 11 * UNIT, 10);
+
+			// 10 USDT only buy about 9.9 BTC
+			run_keeper();
+			assert!(LimitOrders::<Test>::get(pool_id, order_id).is_some());
+			assert_eq!(Tokens::balance(BTC, &BOB), 0);
+
+			// selling BTC makes it cheap enough
+			Tokens::mint_into(BTC, &CHARLIE, 200 * UNIT).unwrap();
+			assert_ok!(Pablo::swap(
+				RuntimeOrigin::signed(CHARLIE),
+				pool_id,
+				AssetAmount::new(BTC, 200 * UNIT),
+				AssetAmount::new(USDT, 0),
+				false,
+				None,
+			));
+			run_keeper();
+
+			assert!(LimitOrders::<Test>::get(pool_id, order_id).is_none());
+			assert_eq!(LimitOrdersPerPool::<Test>::get(pool_id), 0);
+			let bob_btc = Tokens::balance(BTC, &BOB);
+			assert!(bob_btc >= 11 * UNIT);
+			assert_eq!(Tokens::balance(USDT, &Pablo::limit_order_escrow_account()), 0);
+			Test::assert_last_event(Event::<Test>::LimitOrderFilled {
+				pool_id,
+				order_id,
+				owner: BOB,
+				in_asset: AssetAmount::new(USDT, 10 * UNIT),
+				out_asset: AssetAmount::new(BTC, bob_btc),
+			});
+		});
+	}
+
+	#[test]
+	fn refunds_expired_orders() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			let order_id = place_order(pool_id, 11 * UNIT, System::block_number() + 1);
+
+			next_block::<Pablo, Test>();
+			run_keeper();
+			assert!(LimitOrders::<Test>::get(pool_id, order_id).is_some());
+
+			next_block::<Pablo, Test>();
+			run_keeper();
+			Test::assert_last_event(Event::<Test>::LimitOrderExpired {
+				pool_id,
+				order_id,
+				owner: BOB,
+			});
+			assert!(LimitOrders::<Test>::get(pool_id, order_id).is_none());
+			assert_eq!(Tokens::balance(USDT, &BOB), 10 * UNIT);
+		});
+	}
+
+	#[test]
+	fn resumes_where_the_weight_ran_out() {
+		new_test_ext().execute_with(|| {
+			next_block::<Pablo, Test>();
+			let pool_id = create_pool();
+			let expires_at = System::block_number() + 1;
+			let first = place_order(pool_id, 11 * UNIT, expires_at);
+			let second = place_order(pool_id, 11 * UNIT, expires_at);
+			next_block::<Pablo, Test>();
+			next_block::<Pablo, Test>();
+
+			// enough for the cursor and a single order
+			let one_order = Pablo::fill_limit_order_weight(pool_id)
+				.saturating_add(<Test as frame_system::Config>::DbWeight::get().reads_writes(1, 1));
+			Pablo::on_idle(System::block_number(), one_order);
+			assert_eq!(LimitOrdersPerPool::<Test>::get(pool_id), 1);
+
+			Pablo::on_idle(System::block_number(), one_order);
+			assert!(LimitOrders::<Test>::get(pool_id, first).is_none());
+			assert!(LimitOrders::<Test>::get(pool_id, second).is_none());
+			assert_eq!(Tokens::balance(USDT, &BOB), 20 * UNIT);
+		});
+	}
+}
@@ -0,0 +1,327 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	Error, Event, PoolConfiguration, PoolInitConfiguration, PoolInitConfigurationOf,
+};
+use composable_tests_helpers::test::helper::RuntimeTrait;
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount, Sale},
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::Permill;
+
+const UNIT: u128 = 1_000_000_000_000;
+const HOUR: Moment = 60 * 60 * 1000;
+/// The project asset being sold.
+const PROJECT: CurrencyId = BTC;
+
+fn sale() -> Sale<Moment> {
+	Sale {
+		start: HOUR,
+		end: 3 * HOUR,
+		initial_weight: Permill::from_percent(90),
+		final_weight: Permill::from_percent(50),
+	}
+}
+
+fn lbp_init_config(sale: Sale<Moment>) -> PoolInitConfigurationOf<Test> {
+	PoolInitConfiguration::LiquidityBootstrapping {
+		owner: ALICE,
+		pair: CurrencyPair::new(PROJECT, USDT),
+		sale,
+		fee: Permill::from_percent(1),
+	}
+}
+
+/// Creates a PROJECT/USDT liquidity bootstrapping pool and seeds it with `initial_liquidity` of
+/// each asset, before the sale starts.
+fn create_pool_with_liquidity(initial_liquidity: u128) -> u128 {
+	let pool_id = Test::assert_extrinsic_event_with(
+		Pablo::create(RuntimeOrigin::root(), lbp_init_config(sale())),
+		|e| match e {
+			Event::PoolCreated { pool_id, .. } => Some(pool_id),
+			_ => None,
+		},
+	);
+
+	Tokens::mint_into(PROJECT, &ALICE, initial_liquidity).unwrap();
+	Tokens::mint_into(USDT, &ALICE, initial_liquidity).unwrap();
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		[(PROJECT, initial_liquidity), (USDT, initial_liquidity)].into_iter().collect(),
+		0,
+		false,
+	));
+
+	pool_id
+}
+
+fn price_of_project_unit(pool_id: u128) -> u128 {
+	<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(PROJECT, UNIT), USDT, false)
+		.unwrap()
+		.value
+		.amount
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_successfully_create_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(lbp_init_config(sale()), None).unwrap();
+
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::LiquidityBootstrapping(pool) => {
+					assert_eq!(pool.owner, ALICE);
+					assert_eq!(pool.sale, sale());
+				},
+				_ => panic!("expected a liquidity bootstrapping pool"),
+			}
+			assert_noop!(<Pablo as Amm>::lp_token(pool_id), Error::<Test>::NoLpTokenForLbp);
+		});
+	}
+
+	#[test]
+	fn should_fail_with_lp_token() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(lbp_init_config(sale()), Some(LP_TOKEN_ID)),
+				Error::<Test>::NoLpTokenForLbp
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_sale_already_started() {
+		new_test_ext().execute_with(|| {
+			Timestamp::set_timestamp(2 * HOUR);
+			assert_noop!(
+				Pablo::do_create_pool(lbp_init_config(sale()), None),
+				Error::<Test>::InvalidSaleState
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_sale_is_too_short() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(lbp_init_config(Sale { end: HOUR + 1, ..sale() }), None),
+				Error::<Test>::InvalidSaleState
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_weights_are_out_of_bounds() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(Sale { initial_weight: Permill::from_percent(99), ..sale() }),
+					None
+				),
+				Error::<Test>::InvalidSaleState
+			);
+			assert_noop!(
+				Pablo::do_create_pool(
+					lbp_init_config(Sale { final_weight: Permill::from_percent(1), ..sale() }),
+					None
+				),
+				Error::<Test>::InvalidSaleState
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn only_owner_can_add_liquidity() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(lbp_init_config(sale()), None).unwrap();
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					[(USDT, 1_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				Error::<Test>::MustBeOwner
+			);
+		});
+	}
+
+	#[test]
+	fn cannot_expect_lp_tokens() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(lbp_init_config(sale()), None).unwrap();
+			Tokens::mint_into(PROJECT, &ALICE, 1_000 * UNIT).unwrap();
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					[(PROJECT, 1_000 * UNIT)].into_iter().collect(),
+					1,
+					false,
+				),
+				Error::<Test>::NoLpTokenForLbp
+			);
+		});
+	}
+
+	#[test]
+	fn liquidity_can_only_be_removed_after_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(1_000_000 * UNIT);
+
+			Timestamp::set_timestamp(2 * HOUR);
+			assert_noop!(
+				Pablo::remove_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					0,
+					[(PROJECT, 0), (USDT, 0)].into_iter().collect(),
+				),
+				Error::<Test>::InvalidSaleState
+			);
+
+			Timestamp::set_timestamp(3 * HOUR);
+			Tokens::mint_into(USDT, &ALICE, 1_000 * UNIT).unwrap();
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					[(USDT, 1_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				Error::<Test>::InvalidSaleState
+			);
+			assert_noop!(
+				Pablo::remove_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					0,
+					[(PROJECT, 0), (USDT, 0)].into_iter().collect(),
+				),
+				Error::<Test>::MustBeOwner
+			);
+			assert_ok!(Pablo::remove_liquidity(
+				RuntimeOrigin::signed(ALICE),
+				pool_id,
+				0,
+				[(PROJECT, 0), (USDT, 0)].into_iter().collect(),
+			));
+
+			assert_eq!(Tokens::balance(PROJECT, &ALICE), 1_000_000 * UNIT);
+			assert_eq!(Tokens::balance(USDT, &ALICE), 1_001_000 * UNIT);
+		});
+	}
+}
+
+mod sale {
+	use super::*;
+
+	#[test]
+	fn cannot_swap_outside_of_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(1_000_000 * UNIT);
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+
+			assert_noop!(
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, 1_000 * UNIT),
+					AssetAmount::new(PROJECT, 0),
+					false,
+					None,
+				),
+				Error::<Test>::InvalidSaleState
+			);
+
+			Timestamp::set_timestamp(3 * HOUR);
+			assert_noop!(
+				Pablo::buy(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					USDT,
+					AssetAmount::new(PROJECT, UNIT),
+					false,
+					None,
+				),
+				Error::<Test>::InvalidSaleState
+			);
+		});
+	}
+
+	#[test]
+	fn swap_during_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(1_000_000 * UNIT);
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+
+			Timestamp::set_timestamp(HOUR);
+			assert_ok!(Pablo::swap(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				AssetAmount::new(USDT, 1_000 * UNIT),
+				AssetAmount::new(PROJECT, 0),
+				false,
+				None,
+			));
+
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+			// a 90/10 weighted pool prices the project asset at 9 USDT, minus the 1% fee
+			let received = Tokens::balance(PROJECT, &BOB);
+			assert!(received > 109 * UNIT);
+			assert!(received < 111 * UNIT);
+		});
+	}
+
+	#[test]
+	fn price_decreases_over_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = create_pool_with_liquidity(1_000_000 * UNIT);
+
+			Timestamp::set_timestamp(HOUR);
+			let price_at_start = price_of_project_unit(pool_id);
+			Timestamp::set_timestamp(2 * HOUR);
+			let price_halfway = price_of_project_unit(pool_id);
+			Timestamp::set_timestamp(3 * HOUR);
+			let price_at_end = price_of_project_unit(pool_id);
+
+			// (1 / 0.1) / (1 / 0.9), (1 / 0.3) / (1 / 0.7) and (1 / 0.5) / (1 / 0.5)
+			assert!(price_at_start > 8_999 * UNIT / 1_000 && price_at_start <= 9 * UNIT);
+			assert!(price_halfway > 2_333 * UNIT / 1_000 && price_halfway <= 2_334 * UNIT / 1_000);
+			assert!(price_at_end > 999 * UNIT / 1_000 && price_at_end <= UNIT);
+		});
+	}
+
+	#[test]
+	fn weights_follow_the_sale() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(lbp_init_config(sale()), None).unwrap();
+
+			Timestamp::set_timestamp(2 * HOUR);
+			assert_eq!(
+				<Pablo as Amm>::assets(pool_id).unwrap(),
+				[(PROJECT, Permill::from_percent(70)), (USDT, Permill::from_percent(30))]
+					.into_iter()
+					.collect()
+			);
+		});
+	}
+}
@@ -1,4 +1,9 @@
 mod common_test_functions;
+mod concentrated_liquidity_tests;
 mod dual_asset_constant_product_tests;
 mod dual_asset_constant_product_tests_new;
+mod limit_order_tests;
+mod liquidity_bootstrapping_tests;
 mod pablo_tests;
+mod stable_swap_tests;
+mod weighted_product_tests;
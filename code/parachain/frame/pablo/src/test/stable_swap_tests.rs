@@ -0,0 +1,311 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	Error, Event, PoolConfiguration, PoolInitConfiguration, PoolInitConfigurationOf,
+};
+use composable_tests_helpers::test::helper::{default_acceptable_computation_error, RuntimeTrait};
+use composable_traits::{
+	defi::CurrencyPair,
+	dex::{Amm, AssetAmount},
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::Permill;
+
+const UNIT: u128 = 1_000_000_000_000;
+
+fn stable_swap_init_config(
+	amplification_coefficient: u16,
+	fee: Permill,
+) -> PoolInitConfigurationOf<Test> {
+	PoolInitConfiguration::StableSwap {
+		owner: ALICE,
+		pair: CurrencyPair::new(USDC, USDT),
+		amplification_coefficient,
+		fee,
+	}
+}
+
+/// Creates a USDC/USDT stable swap pool and seeds it with `initial_liquidity` of each asset.
+fn create_pool_with_liquidity(
+	amplification_coefficient: u16,
+	fee: Permill,
+	initial_liquidity: u128,
+) -> u128 {
+	let pool_id = Test::assert_extrinsic_event_with(
+		Pablo::create(
+			RuntimeOrigin::root(),
+			stable_swap_init_config(amplification_coefficient, fee),
+		),
+		|e| match e {
+			Event::PoolCreated { pool_id, .. } => Some(pool_id),
+			_ => None,
+		},
+	);
+
+	Tokens::mint_into(USDC, &ALICE, initial_liquidity).unwrap();
+	Tokens::mint_into(USDT, &ALICE, initial_liquidity).unwrap();
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		[(USDC, initial_liquidity), (USDT, initial_liquidity)].into_iter().collect(),
+		0,
+		false,
+	));
+
+	pool_id
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_successfully_create_pool() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(
+				stable_swap_init_config(100, Permill::from_percent(1)),
+				Some(LP_TOKEN_ID),
+			)
+			.unwrap();
+
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::StableSwap(pool) => {
+					assert_eq!(pool.amplification_coefficient, 100);
+					assert_eq!(pool.pool_info.lp_token, LP_TOKEN_ID);
+					assert_eq!(
+						pool.pool_info.assets_weights.into_inner(),
+						[(USDC, Permill::from_percent(50)), (USDT, Permill::from_percent(50))]
+							.into_iter()
+							.collect()
+					);
+				},
+				_ => panic!("expected a stable swap pool"),
+			}
+		});
+	}
+
+	#[test]
+	fn should_fail_with_zero_amplification_coefficient() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(stable_swap_init_config(0, Permill::from_percent(1)), None),
+				Error::<Test>::AmpFactorMustBeGreaterThanZero
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_with_identical_assets() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					PoolInitConfiguration::StableSwap {
+						owner: ALICE,
+						pair: CurrencyPair::new(USDC, USDC),
+						amplification_coefficient: 100,
+						fee: Permill::from_percent(1),
+					},
+					None
+				),
+				Error::<Test>::InvalidPair
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn initial_deposit_must_contain_all_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id =
+				Pablo::do_create_pool(stable_swap_init_config(100, Permill::from_percent(1)), None)
+					.unwrap();
+			Tokens::mint_into(USDC, &ALICE, 1_000 * UNIT).unwrap();
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					[(USDC, 1_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				Error::<Test>::InitialDepositMustContainAllAssets
+			);
+		});
+	}
+
+	#[test]
+	fn add_and_remove_liquidity() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDC, &BOB, 1_000 * UNIT).unwrap();
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+
+			let minted_lp = Test::assert_extrinsic_event_with(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					[(USDC, 1_000 * UNIT), (USDT, 1_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				|e| match e {
+					Event::LiquidityAdded { minted_lp, .. } => Some(minted_lp),
+					_ => None,
+				},
+			);
+			assert_eq!(Tokens::balance(USDC, &BOB), 0);
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+
+			assert_ok!(Pablo::remove_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				minted_lp,
+				[(USDC, 0), (USDT, 0)].into_iter().collect(),
+			));
+			// a balanced deposit pays no imbalance fee and is fully redeemable
+			assert_ok!(default_acceptable_computation_error(
+				Tokens::balance(USDC, &BOB),
+				1_000 * UNIT
+			));
+			assert_ok!(default_acceptable_computation_error(
+				Tokens::balance(USDT, &BOB),
+				1_000 * UNIT
+			));
+		});
+	}
+
+	#[test]
+	fn single_sided_deposit_mints_less_than_balanced_deposit() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			let balanced = <Pablo as Amm>::simulate_add_liquidity(
+				&BOB,
+				pool_id,
+				[(USDC, 1_000 * UNIT), (USDT, 1_000 * UNIT)].into_iter().collect(),
+			)
+			.unwrap();
+			let single_sided = <Pablo as Amm>::simulate_add_liquidity(
+				&BOB,
+				pool_id,
+				[(USDC, 2_000 * UNIT)].into_iter().collect(),
+			)
+			.unwrap();
+
+			assert!(single_sided < balanced);
+		});
+	}
+}
+
+mod swap {
+	use super::*;
+
+	#[test]
+	fn balanced_pool_swaps_close_to_parity() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(100, Permill::zero(), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDC, &BOB, 1_000 * UNIT).unwrap();
+			assert_ok!(Pablo::swap(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				AssetAmount::new(USDC, 1_000 * UNIT),
+				AssetAmount::new(USDT, 0),
+				false,
+				None,
+			));
+
+			// a constant product pool of the same depth would only return ~999 USDT
+			let received = Tokens::balance(USDT, &BOB);
+			assert!(received > 999_990_000_000_000);
+			assert!(received < 1_000 * UNIT);
+			assert_eq!(Tokens::balance(USDC, &BOB), 0);
+		});
+	}
+
+	#[test]
+	fn swap_respects_min_receive() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDC, &BOB, 1_000 * UNIT).unwrap();
+			assert_noop!(
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDC, 1_000 * UNIT),
+					AssetAmount::new(USDT, 1_000 * UNIT),
+					false,
+					None,
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+		});
+	}
+
+	#[test]
+	fn buy_transfers_requested_amount() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDC, &BOB, 2_000 * UNIT).unwrap();
+			assert_ok!(Pablo::buy(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				USDC,
+				AssetAmount::new(USDT, 1_000 * UNIT),
+				false,
+				None,
+			));
+
+			assert_eq!(Tokens::balance(USDT, &BOB), 1_000 * UNIT);
+			// ~1% fee on top of an almost 1:1 rate
+			let spent = 2_000 * UNIT - Tokens::balance(USDC, &BOB);
+			assert!(spent > 1_010 * UNIT);
+			assert!(spent < 1_011 * UNIT);
+		});
+	}
+
+	#[test]
+	fn spot_price_of_balanced_pool_is_parity() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			let spot_price =
+				<Pablo as Amm>::spot_price(pool_id, AssetAmount::new(USDC, UNIT), USDT, false)
+					.unwrap();
+			assert_ok!(default_acceptable_computation_error(spot_price.value.amount, UNIT));
+		});
+	}
+
+	#[test]
+	fn twap_can_be_enabled() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id =
+				create_pool_with_liquidity(100, Permill::from_percent(1), 1_000_000 * UNIT);
+
+			assert_ok!(Pablo::enable_twap(RuntimeOrigin::root(), pool_id));
+			assert!(Pablo::twap(pool_id).is_some());
+		});
+	}
+}
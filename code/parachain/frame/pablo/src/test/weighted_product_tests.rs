@@ -0,0 +1,411 @@
+#![allow(clippy::disallowed_methods, clippy::unwrap_used)]
+
+use crate::{
+	mock::{Pablo, *},
+	Error, Event, PoolConfiguration, PoolInitConfiguration, PoolInitConfigurationOf,
+};
+use composable_tests_helpers::test::helper::{default_acceptable_computation_error, RuntimeTrait};
+use composable_traits::dex::{Amm, AssetAmount};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::fungibles::{Inspect, Mutate},
+};
+use sp_runtime::{traits::One, FixedU128, Permill};
+use sp_std::collections::btree_map::BTreeMap;
+
+const UNIT: u128 = 1_000_000_000_000;
+
+fn weighted_product_init_config(
+	assets_weights: &[(CurrencyId, Permill)],
+	fee: Permill,
+) -> PoolInitConfigurationOf<Test> {
+	PoolInitConfiguration::WeightedProduct {
+		owner: ALICE,
+		assets_weights: assets_weights
+			.iter()
+			.copied()
+			.collect::<BTreeMap<_, _>>()
+			.try_into()
+			.unwrap(),
+		fee,
+	}
+}
+
+/// USDC/USDT/BTC weighted equally.
+fn equal_weights() -> [(CurrencyId, Permill); 3] {
+	[
+		(USDC, Permill::from_parts(333_334)),
+		(USDT, Permill::from_parts(333_333)),
+		(BTC, Permill::from_parts(333_333)),
+	]
+}
+
+/// Creates an equally weighted USDC/USDT/BTC pool and seeds it with `initial_liquidity` of each
+/// asset.
+fn create_pool_with_liquidity(fee: Permill, initial_liquidity: u128) -> u128 {
+	let pool_id = Test::assert_extrinsic_event_with(
+		Pablo::create(RuntimeOrigin::root(), weighted_product_init_config(&equal_weights(), fee)),
+		|e| match e {
+			Event::PoolCreated { pool_id, .. } => Some(pool_id),
+			_ => None,
+		},
+	);
+
+	for (asset_id, _) in equal_weights() {
+		Tokens::mint_into(asset_id, &ALICE, initial_liquidity).unwrap();
+	}
+	assert_ok!(Pablo::add_liquidity(
+		RuntimeOrigin::signed(ALICE),
+		pool_id,
+		equal_weights()
+			.into_iter()
+			.map(|(asset_id, _)| (asset_id, initial_liquidity))
+			.collect(),
+		0,
+		false,
+	));
+
+	pool_id
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn should_successfully_create_pool_with_three_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(
+				weighted_product_init_config(&equal_weights(), Permill::from_percent(1)),
+				Some(LP_TOKEN_ID),
+			)
+			.unwrap();
+
+			match Pablo::pools(pool_id).unwrap() {
+				PoolConfiguration::WeightedProduct(pool) => {
+					assert_eq!(pool.lp_token, LP_TOKEN_ID);
+					assert_eq!(
+						pool.assets_weights.into_inner(),
+						equal_weights().into_iter().collect()
+					);
+				},
+				_ => panic!("expected a weighted product pool"),
+			}
+		});
+	}
+
+	#[test]
+	fn should_fail_with_a_single_asset() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					weighted_product_init_config(
+						&[(USDC, Permill::from_percent(100))],
+						Permill::from_percent(1)
+					),
+					None
+				),
+				Error::<Test>::PoolMustContainAtLeastTwoAssets
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_when_weights_do_not_sum_to_one() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				Pablo::do_create_pool(
+					weighted_product_init_config(
+						&[
+							(USDC, Permill::from_percent(30)),
+							(USDT, Permill::from_percent(30)),
+							(BTC, Permill::from_percent(30)),
+						],
+						Permill::from_percent(1)
+					),
+					None
+				),
+				Error::<Test>::WeightsMustSumToOne
+			);
+		});
+	}
+}
+
+mod liquidity {
+	use super::*;
+
+	#[test]
+	fn initial_deposit_must_contain_all_assets() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(
+				weighted_product_init_config(&equal_weights(), Permill::from_percent(1)),
+				None,
+			)
+			.unwrap();
+			Tokens::mint_into(USDC, &ALICE, 1_000 * UNIT).unwrap();
+			Tokens::mint_into(USDT, &ALICE, 1_000 * UNIT).unwrap();
+
+			assert_noop!(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(ALICE),
+					pool_id,
+					[(USDC, 1_000 * UNIT), (USDT, 1_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				Error::<Test>::InitialDepositMustContainAllAssets
+			);
+		});
+	}
+
+	#[test]
+	fn add_and_remove_liquidity() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			for (asset_id, _) in equal_weights() {
+				Tokens::mint_into(asset_id, &BOB, 1_000 * UNIT).unwrap();
+			}
+			let minted_lp = Test::assert_extrinsic_event_with(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					equal_weights()
+						.into_iter()
+						.map(|(asset_id, _)| (asset_id, 1_000 * UNIT))
+						.collect(),
+					0,
+					false,
+				),
+				|e| match e {
+					Event::LiquidityAdded { minted_lp, .. } => Some(minted_lp),
+					_ => None,
+				},
+			);
+
+			assert_ok!(Pablo::remove_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				minted_lp,
+				[(USDC, 0), (USDT, 0), (BTC, 0)].into_iter().collect(),
+			));
+			for (asset_id, _) in equal_weights() {
+				assert_ok!(default_acceptable_computation_error(
+					Tokens::balance(asset_id, &BOB),
+					1_000 * UNIT
+				));
+			}
+		});
+	}
+
+	#[test]
+	fn single_sided_deposit() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			let balanced = <Pablo as Amm>::simulate_add_liquidity(
+				&BOB,
+				pool_id,
+				equal_weights()
+					.into_iter()
+					.map(|(asset_id, _)| (asset_id, 1_000 * UNIT))
+					.collect(),
+			)
+			.unwrap();
+
+			Tokens::mint_into(USDC, &BOB, 3_000 * UNIT).unwrap();
+			let minted_lp = Test::assert_extrinsic_event_with(
+				Pablo::add_liquidity(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					[(USDC, 3_000 * UNIT)].into_iter().collect(),
+					0,
+					false,
+				),
+				|e| match e {
+					Event::LiquidityAdded { minted_lp, .. } => Some(minted_lp),
+					_ => None,
+				},
+			);
+
+			assert_eq!(Tokens::balance(USDC, &BOB), 0);
+			// the same value deposited into a single asset pays the swap fee and slippage
+			assert!(minted_lp > 0);
+			assert!(minted_lp < balanced);
+		});
+	}
+
+	#[test]
+	fn remove_liquidity_single_asset() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			for (asset_id, _) in equal_weights() {
+				Tokens::mint_into(asset_id, &BOB, 1_000 * UNIT).unwrap();
+			}
+			assert_ok!(Pablo::add_liquidity(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				equal_weights()
+					.into_iter()
+					.map(|(asset_id, _)| (asset_id, 1_000 * UNIT))
+					.collect(),
+				0,
+				false,
+			));
+			let lp_token = <Pablo as Amm>::lp_token(pool_id).unwrap();
+			let lp_amount = Tokens::balance(lp_token, &BOB);
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					lp_amount,
+					AssetAmount::new(USDC, 3_000 * UNIT),
+				),
+				Error::<Test>::CannotRespectMinimumRequested
+			);
+			assert_ok!(Pablo::remove_liquidity_single_asset(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				lp_amount,
+				AssetAmount::new(USDC, 0),
+			));
+
+			assert_eq!(Tokens::balance(lp_token, &BOB), 0);
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+			assert_eq!(Tokens::balance(BTC, &BOB), 0);
+			// the value of all three deposits, minus the fee on the part swapped into USDC
+			let received = Tokens::balance(USDC, &BOB);
+			assert!(received > 2_900 * UNIT);
+			assert!(received < 3_000 * UNIT);
+		});
+	}
+
+	#[test]
+	fn remove_liquidity_single_asset_is_not_supported_by_dual_asset_pools() {
+		new_test_ext().execute_with(|| {
+			let pool_id = Pablo::do_create_pool(
+				PoolInitConfiguration::DualAssetConstantProduct {
+					owner: ALICE,
+					assets_weights: [
+						(USDC, Permill::from_percent(50)),
+						(USDT, Permill::from_percent(50)),
+					]
+					.into_iter()
+					.collect::<BTreeMap<_, _>>()
+					.try_into()
+					.unwrap(),
+					fee: Permill::from_percent(1),
+				},
+				None,
+			)
+			.unwrap();
+
+			assert_noop!(
+				Pablo::remove_liquidity_single_asset(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					UNIT,
+					AssetAmount::new(USDC, 0),
+				),
+				Error::<Test>::UnsupportedOperation
+			);
+		});
+	}
+}
+
+mod swap {
+	use super::*;
+
+	#[test]
+	fn swap_between_any_two_assets_of_the_pool() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+			assert_ok!(Pablo::swap(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				AssetAmount::new(USDT, 1_000 * UNIT),
+				AssetAmount::new(BTC, 0),
+				false,
+				None,
+			));
+
+			assert_eq!(Tokens::balance(USDT, &BOB), 0);
+			// 1% fee plus the slippage of a 0.1% trade
+			let received = Tokens::balance(BTC, &BOB);
+			assert!(received > 988 * UNIT);
+			assert!(received < 990 * UNIT);
+		});
+	}
+
+	#[test]
+	fn buy_transfers_requested_amount() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(BTC, &BOB, 2_000 * UNIT).unwrap();
+			assert_ok!(Pablo::buy(
+				RuntimeOrigin::signed(BOB),
+				pool_id,
+				BTC,
+				AssetAmount::new(USDC, 1_000 * UNIT),
+				false,
+				None,
+			));
+
+			assert_eq!(Tokens::balance(USDC, &BOB), 1_000 * UNIT);
+			let spent = 2_000 * UNIT - Tokens::balance(BTC, &BOB);
+			assert!(spent > 1_010 * UNIT);
+			assert!(spent < 1_012 * UNIT);
+		});
+	}
+
+	#[test]
+	fn swap_fails_for_assets_outside_of_the_pool() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			Tokens::mint_into(USDT, &BOB, 1_000 * UNIT).unwrap();
+			assert_noop!(
+				Pablo::swap(
+					RuntimeOrigin::signed(BOB),
+					pool_id,
+					AssetAmount::new(USDT, 1_000 * UNIT),
+					AssetAmount::new(LP_TOKEN_ID, 0),
+					false,
+					None,
+				),
+				Error::<Test>::AssetNotFound
+			);
+		});
+	}
+}
+
+mod twap {
+	use super::*;
+
+	#[test]
+	fn twap_is_tracked_for_every_asset() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let pool_id = create_pool_with_liquidity(Permill::from_percent(1), 1_000_000 * UNIT);
+
+			assert_ok!(Pablo::enable_twap(RuntimeOrigin::root(), pool_id));
+			assert!(Pablo::twap(pool_id).is_none());
+
+			let twap = Pablo::multi_asset_twap(pool_id).unwrap();
+			assert_eq!(twap.twaps.len(), 3);
+			// every asset is priced in terms of the first asset of the pool
+			assert_eq!(twap.twaps.get(&USDC), Some(&FixedU128::one()));
+			assert!(Pablo::multi_asset_price_cumulative(pool_id).is_some());
+		});
+	}
+}
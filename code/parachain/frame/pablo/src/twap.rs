@@ -1,6 +1,10 @@
 use crate::{
-	types::{PriceCumulative, TimeWeightedAveragePrice},
-	Config, MomentOf, PriceCumulativeStateOf, PriceRatio, TWAPStateOf,
+	types::{
+		MultiAssetPriceCumulative, MultiAssetTimeWeightedAveragePrice, PriceCumulative,
+		TimeWeightedAveragePrice,
+	},
+	Config, MomentOf, MultiAssetPriceCumulativeStateOf, MultiAssetTWAPStateOf,
+	PriceCumulativeStateOf, PriceRatio, TWAPStateOf,
 };
 use composable_maths::dex::price::{compute_next_price_cumulative, compute_twap};
 use composable_traits::defi::Rate;
@@ -9,6 +13,7 @@ use sp_runtime::{
 	traits::{Saturating, Zero},
 	DispatchError,
 };
+use sp_std::collections::btree_map::BTreeMap;
 
 pub(crate) fn get_next_price_cumulative<T: Config>(
 	pool_id: T::PoolId,
@@ -106,3 +111,81 @@ pub(crate) fn update_twap_state<T: Config>(
 	}
 	Ok(())
 }
+
+/// Updates the price cumulative of every asset of a multi-asset pool.
+///
+/// Returns `None` if TWAP is not enabled for the pool or if the price cumulatives were already
+/// updated at the current timestamp (i.e. earlier in the same block).
+pub(crate) fn update_multi_asset_price_cumulative_state<T: Config>(
+	pool_id: T::PoolId,
+	prev_price_cumulative: &mut Option<MultiAssetPriceCumulativeStateOf<T>>,
+) -> Result<Option<BTreeMap<T::AssetId, T::Balance>>, DispatchError> {
+	match prev_price_cumulative {
+		Some(previous_price_cumulative) => {
+			let current_timestamp = T::Time::now();
+			if current_timestamp <= previous_price_cumulative.timestamp {
+				return Ok(None)
+			}
+			let price_cumulatives = crate::Pallet::<T>::do_get_exchange_rates(pool_id)?
+				.into_iter()
+				.map(|(asset_id, rate)| {
+					let previous = previous_price_cumulative
+						.price_cumulatives
+						.get(&asset_id)
+						.copied()
+						.unwrap_or_else(T::Balance::zero);
+					let (_, price_cumulative) = compute_next_price_cumulative::<T::Convert, _, _>(
+						previous_price_cumulative.timestamp,
+						previous,
+						current_timestamp,
+						rate,
+					)?;
+					Ok((asset_id, price_cumulative))
+				})
+				.collect::<Result<BTreeMap<_, _>, DispatchError>>()?;
+			*prev_price_cumulative = Some(MultiAssetPriceCumulative {
+				timestamp: current_timestamp,
+				price_cumulatives: price_cumulatives.clone(),
+			});
+			Ok(Some(price_cumulatives))
+		},
+		None => Ok(None),
+	}
+}
+
+pub(crate) fn update_multi_asset_twap_state<T: Config>(
+	price_cumulatives: BTreeMap<T::AssetId, T::Balance>,
+	prev_twap_state: &mut Option<MultiAssetTWAPStateOf<T>>,
+) -> Result<(), DispatchError> {
+	if let Some(previous_twap_state) = prev_twap_state {
+		let current_timestamp = T::Time::now();
+		ensure!(
+			current_timestamp > previous_twap_state.timestamp,
+			DispatchError::Other("Stale TWAP Request Found")
+		);
+		let elapsed = current_timestamp.saturating_sub(previous_twap_state.timestamp);
+		if elapsed >= T::TWAPInterval::get() {
+			let twaps = price_cumulatives
+				.iter()
+				.map(|(asset_id, price_cumulative)| {
+					let previous = previous_twap_state
+						.price_cumulatives
+						.get(asset_id)
+						.copied()
+						.unwrap_or_else(T::Balance::zero);
+					let twap =
+						compute_twap::<T::Convert, _, _>(*price_cumulative, previous, elapsed)?;
+					Ok((*asset_id, twap))
+				})
+				.collect::<Result<BTreeMap<_, _>, DispatchError>>()?;
+			*prev_twap_state = Some(MultiAssetTimeWeightedAveragePrice {
+				timestamp: current_timestamp,
+				price_cumulatives,
+				twaps,
+			});
+		} else {
+			return DispatchError::Other("Elapsed time < TWAPInterval").into()
+		}
+	}
+	Ok(())
+}
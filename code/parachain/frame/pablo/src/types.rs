@@ -1,7 +1,8 @@
-use codec::{Decode, Encode};
-use composable_traits::defi::Rate;
+use codec::{Decode, Encode, MaxEncodedLen};
+use composable_traits::{defi::Rate, dex::AssetAmount};
 use scale_info::TypeInfo;
 use sp_runtime::RuntimeDebug;
+use sp_std::collections::btree_map::BTreeMap;
 
 #[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
 pub struct TimeWeightedAveragePrice<Timestamp, Balance> {
@@ -17,3 +18,93 @@ pub struct PriceCumulative<Timestamp, Balance> {
 	pub base_price_cumulative: Balance,
 	pub quote_price_cumulative: Balance,
 }
+
+/// TWAP state of a pool holding more than two assets. Every asset is priced in terms of the
+/// pool's first asset, so that the rate between any two assets of the pool can be derived.
+#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct MultiAssetTimeWeightedAveragePrice<Timestamp, AssetId: Ord, Balance> {
+	pub timestamp: Timestamp,
+	pub price_cumulatives: BTreeMap<AssetId, Balance>,
+	pub twaps: BTreeMap<AssetId, Rate>,
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct MultiAssetPriceCumulative<Timestamp, AssetId: Ord, Balance> {
+	pub timestamp: Timestamp,
+	pub price_cumulatives: BTreeMap<AssetId, Balance>,
+}
+
+/// Fees earned per unit of liquidity, in each asset of a concentrated liquidity pool, as Q64.64
+/// numbers. These are expected to overflow, only their differences are meaningful.
+#[derive(
+	Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug,
+)]
+pub struct FeeGrowth {
+	pub base: u128,
+	pub quote: u128,
+}
+
+impl FeeGrowth {
+	pub fn wrapping_sub(&self, other: &Self) -> Self {
+		FeeGrowth {
+			base: self.base.wrapping_sub(other.base),
+			quote: self.quote.wrapping_sub(other.quote),
+		}
+	}
+}
+
+/// Price and liquidity of a concentrated liquidity pool.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ConcentratedLiquidityState {
+	/// Square root of the price, as a Q64.64 number.
+	pub sqrt_price: u128,
+	/// Greatest tick whose price is lower than or equal to the current price.
+	pub tick: i32,
+	/// Liquidity of the positions whose range contains the current price.
+	pub liquidity: u128,
+	/// Fees earned over the lifetime of the pool.
+	pub fee_growth_global: FeeGrowth,
+}
+
+/// A tick bounding at least one position of a concentrated liquidity pool.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct TickInfo {
+	/// Total liquidity of the positions bounded by the tick.
+	pub liquidity_gross: u128,
+	/// Liquidity added to the pool when the price crosses the tick upwards.
+	pub liquidity_net: i128,
+	/// Fees earned on the other side of the tick, relative to the current price.
+	pub fee_growth_outside: FeeGrowth,
+}
+
+/// Liquidity provided to a concentrated liquidity pool between two ticks.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Position {
+	pub lower_tick: i32,
+	pub upper_tick: i32,
+	pub liquidity: u128,
+	/// Fees earned within the range of the position when they were last credited to it.
+	pub fee_growth_inside_last: FeeGrowth,
+}
+
+/// Point in time after which a swap is no longer executed.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SwapDeadline<BlockNumber, Moment> {
+	/// Last block the swap can be included in.
+	Block(BlockNumber),
+	/// Latest timestamp the swap can be executed at.
+	Moment(Moment),
+}
+
+/// Resting order swapping its escrowed input as soon as the pool pays at least `min_receive`
+/// for it.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LimitOrder<AccountId, AssetId, Balance, BlockNumber> {
+	pub owner: AccountId,
+	/// Amount held in escrow until the order is filled, cancelled or expired.
+	pub in_asset: AssetAmount<AssetId, Balance>,
+	/// Asset to receive and the least amount of it to be paid for the whole `in_asset`.
+	pub min_receive: AssetAmount<AssetId, Balance>,
+	/// Last block the order can be filled in.
+	pub expires_at: BlockNumber,
+}
@@ -0,0 +1,389 @@
+use crate::{AssetIdOf, Config, Error, PoolConfiguration, PoolCount, Pools};
+use composable_maths::dex::{
+	constant_product::{
+		compute_deposit_lp, compute_first_deposit_lp, compute_in_given_out, compute_out_given_in,
+		compute_redeemed_for_lp, compute_single_asset_redeemed_for_lp,
+	},
+	PoolWeightMathExt,
+};
+use composable_support::math::safe::SafeAdd;
+use composable_traits::{
+	currency::{CurrencyFactory, RangeId},
+	defi::Rate,
+	dex::{
+		normalize_asset_deposit_infos_to_min_ratio, AssetAmount, AssetDepositInfo,
+		AssetDepositNormalizationError, BasicPoolInfo, Fee, FeeConfig,
+	},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::fungibles::{Inspect, Mutate, Transfer},
+};
+use sp_runtime::{
+	traits::{Convert, One, Zero},
+	ArithmeticError, BoundedBTreeMap, FixedPointNumber, PerThing, Permill,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+pub(crate) type WeightedProductPoolInfoOf<T> = BasicPoolInfo<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::AssetId,
+	<T as Config>::MaxAssetsPerPool,
+>;
+
+// Balancer V1 Weighted Product Pool, holding up to `T::MaxAssetsPerPool` assets
+pub(crate) struct WeightedProduct<T>(PhantomData<T>);
+
+impl<T: Config> WeightedProduct<T> {
+	pub(crate) fn do_create_pool(
+		who: &T::AccountId,
+		fee_config: FeeConfig,
+		assets_weights: BoundedBTreeMap<T::AssetId, Permill, T::MaxAssetsPerPool>,
+		lp_token_id: Option<AssetIdOf<T>>,
+	) -> Result<T::PoolId, DispatchError> {
+		ensure!(assets_weights.len() >= 2, Error::<T>::PoolMustContainAtLeastTwoAssets);
+		ensure!(assets_weights.values().non_zero_weights(), Error::<T>::WeightsMustBeNonZero);
+		ensure!(
+			assets_weights
+				.values()
+				.sum_weights()
+				.map(|total_weight| total_weight.is_one())
+				// If `None`, `sum_weights` overflowed - weights are not normalized
+				.unwrap_or(false),
+			Error::<T>::WeightsMustSumToOne
+		);
+		ensure!(fee_config.fee_rate < Permill::one(), Error::<T>::InvalidFees);
+
+		let lp_token = lp_token_id.unwrap_or(T::CurrencyFactory::create(RangeId::LP_TOKENS)?);
+
+		// Add new pool
+		let pool_id =
+			PoolCount::<T>::try_mutate(|pool_count| -> Result<T::PoolId, DispatchError> {
+				let pool_id = *pool_count;
+				Pools::<T>::insert(
+					pool_id,
+					PoolConfiguration::WeightedProduct(BasicPoolInfo {
+						owner: who.clone(),
+						assets_weights,
+						lp_token,
+						fee_config,
+					}),
+				);
+				*pool_count = pool_id.safe_add(&T::PoolId::one())?;
+				Ok(pool_id)
+			})?;
+
+		Ok(pool_id)
+	}
+
+	/// WARNING! This does one storage read per asset in the pool!
+	fn get_pool_balances(
+		pool: &WeightedProductPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+	) -> BTreeMap<T::AssetId, (Permill, u128)> {
+		pool.assets_weights
+			.iter()
+			.map(|(asset_id, weight)| {
+				(
+					*asset_id,
+					(*weight, T::Convert::convert(T::Assets::balance(*asset_id, pool_account))),
+				)
+			})
+			.collect()
+	}
+
+	/// Returns the weight and balance of a single asset of the pool.
+	fn get_pool_balance(
+		pool: &WeightedProductPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		asset_id: T::AssetId,
+	) -> Result<(Permill, u128), DispatchError> {
+		let weight = pool.assets_weights.get(&asset_id).ok_or(Error::<T>::AssetNotFound)?;
+		Ok((*weight, T::Convert::convert(T::Assets::balance(asset_id, pool_account))))
+	}
+
+	pub(crate) fn add_liquidity(
+		who: &T::AccountId,
+		pool: WeightedProductPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		assets: BTreeMap<T::AssetId, T::Balance>,
+		min_mint_amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<(T::Balance, BTreeMap<T::AssetId, T::Balance>), DispatchError> {
+		ensure!(!assets.is_empty(), Error::<T>::MustDepositMinimumOneAsset);
+
+		let mut pool_assets = Self::get_pool_balances(&pool, &pool_account);
+
+		let assets_with_balances = assets
+			.into_iter()
+			.map(|(asset_id, amount)| {
+				ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+				let (weight, balance) =
+					pool_assets.remove(&asset_id).ok_or(Error::<T>::AssetNotFound)?;
+
+				Ok(AssetDepositInfo {
+					asset_id,
+					deposit_amount: T::Convert::convert(amount),
+					existing_balance: balance,
+					asset_weight: weight,
+				})
+			})
+			.collect::<Result<Vec<_>, Error<T>>>()?;
+
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let (amount_of_lp_token_to_mint, deposits) = if let [single] =
+			assets_with_balances.as_slice()
+		{
+			if lp_total_issuance.is_zero() {
+				return Err(Error::<T>::InitialDepositMustContainAllAssets.into())
+			}
+
+			let lp_to_mint = compute_deposit_lp(
+				lp_total_issuance,
+				single.deposit_amount,
+				single.existing_balance,
+				single.asset_weight,
+				pool.fee_config.fee_rate,
+			)?
+			.value;
+
+			(lp_to_mint, assets_with_balances)
+		} else if lp_total_issuance.is_zero() {
+			ensure!(pool_assets.is_empty(), Error::<T>::InitialDepositMustContainAllAssets);
+
+			let lp_to_mint = compute_first_deposit_lp(
+				assets_with_balances
+					.iter()
+					.map(|adi| (adi.asset_id, adi.deposit_amount, adi.asset_weight)),
+				Permill::zero(),
+			)?
+			.value;
+
+			(lp_to_mint, assets_with_balances)
+		} else {
+			// only single sided or fully balanced deposits are supported
+			ensure!(pool_assets.is_empty(), Error::<T>::UnsupportedOperation);
+
+			let normalized_deposits = normalize_asset_deposit_infos_to_min_ratio(
+				assets_with_balances,
+			)
+			.map_err(|err| match err {
+				AssetDepositNormalizationError::ArithmeticOverflow =>
+					DispatchError::Arithmetic(ArithmeticError::Overflow),
+				AssetDepositNormalizationError::NotEnoughAssets =>
+					Error::<T>::PoolMustContainAtLeastTwoAssets.into(),
+			})?;
+
+			// since the asset deposits were normalized, the lp_to_mint will be the same for all
+			// asset deposits
+			let asset_to_calculate_with =
+				normalized_deposits.first().ok_or(Error::<T>::PoolMustContainAtLeastTwoAssets)?;
+
+			// pass 1 as weight since adding liquidity for all assets with normalized deposits
+			let lp_to_mint = compute_deposit_lp(
+				lp_total_issuance,
+				asset_to_calculate_with.deposit_amount,
+				asset_to_calculate_with.existing_balance,
+				Permill::one(),
+				Zero::zero(),
+			)?
+			.value;
+
+			(lp_to_mint, normalized_deposits)
+		};
+
+		let amount_of_lp_token_to_mint = T::Convert::convert(amount_of_lp_token_to_mint);
+
+		ensure!(
+			amount_of_lp_token_to_mint >= min_mint_amount,
+			Error::<T>::CannotRespectMinimumRequested
+		);
+
+		let actual_amounts_deposited = deposits
+			.into_iter()
+			.map(|adi| (adi.asset_id, T::Convert::convert(adi.deposit_amount)))
+			.collect::<BTreeMap<_, _>>();
+
+		for (asset_id, amount) in &actual_amounts_deposited {
+			T::Assets::transfer(*asset_id, who, &pool_account, *amount, keep_alive)?;
+		}
+
+		T::Assets::mint_into(pool.lp_token, who, amount_of_lp_token_to_mint)?;
+
+		Ok((amount_of_lp_token_to_mint, actual_amounts_deposited))
+	}
+
+	pub(crate) fn remove_liquidity(
+		who: &T::AccountId,
+		pool: WeightedProductPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		mut min_receive: BTreeMap<T::AssetId, T::Balance>,
+	) -> Result<BTreeMap<T::AssetId, T::Balance>, DispatchError> {
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let redeemed_assets = Self::get_pool_balances(&pool, &pool_account)
+			.into_iter()
+			.map(|(id, (_, balance))| {
+				let redeemed_amount = compute_redeemed_for_lp(
+					lp_total_issuance,
+					T::Convert::convert(lp_amount),
+					balance,
+					Permill::one(),
+				)?;
+
+				if let Some(min_amount) = min_receive.remove(&id) {
+					ensure!(
+						redeemed_amount >= T::Convert::convert(min_amount),
+						Error::<T>::CannotRespectMinimumRequested
+					);
+				}
+
+				Ok::<_, DispatchError>((id, T::Convert::convert(redeemed_amount)))
+			})
+			.collect::<Result<BTreeMap<_, _>, _>>()?;
+
+		ensure!(min_receive.is_empty(), Error::<T>::AssetNotFound);
+
+		for (id, amount) in &redeemed_assets {
+			T::Assets::transfer(
+				*id,
+				&pool_account,
+				who,
+				*amount,
+				false, // pool account doesn't need to be kept alive
+			)?;
+		}
+
+		T::Assets::burn_from(pool.lp_token, who, lp_amount)?;
+
+		Ok(redeemed_assets)
+	}
+
+	/// Redeems `lp_amount` for a single asset of the pool. The swap fee is charged on the part of
+	/// the withdrawal that is implicitly swapped into `min_receive.asset_id`, and stays in the
+	/// pool.
+	pub(crate) fn remove_liquidity_single_asset(
+		who: &T::AccountId,
+		pool: WeightedProductPoolInfoOf<T>,
+		pool_account: T::AccountId,
+		lp_amount: T::Balance,
+		min_receive: AssetAmount<T::AssetId, T::Balance>,
+	) -> Result<AssetAmount<T::AssetId, T::Balance>, DispatchError> {
+		let (weight, balance) = Self::get_pool_balance(&pool, &pool_account, min_receive.asset_id)?;
+		let lp_total_issuance = T::Convert::convert(T::Assets::total_issuance(pool.lp_token));
+
+		let redeemed = compute_single_asset_redeemed_for_lp(
+			lp_total_issuance,
+			T::Convert::convert(lp_amount),
+			balance,
+			weight,
+			pool.fee_config.fee_rate,
+		)?;
+
+		// the pool must keep a non-zero balance of every asset for its prices to remain defined
+		ensure!(redeemed.value < balance, Error::<T>::NotEnoughLiquidity);
+
+		let redeemed = AssetAmount::new(min_receive.asset_id, T::Convert::convert(redeemed.value));
+		ensure!(redeemed.amount >= min_receive.amount, Error::<T>::CannotRespectMinimumRequested);
+
+		T::Assets::transfer(
+			redeemed.asset_id,
+			&pool_account,
+			who,
+			redeemed.amount,
+			false, // pool account doesn't need to be kept alive
+		)?;
+
+		T::Assets::burn_from(pool.lp_token, who, lp_amount)?;
+
+		Ok(redeemed)
+	}
+
+	pub(crate) fn get_exchange_value(
+		pool: &WeightedProductPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		in_asset: AssetAmount<T::AssetId, T::Balance>,
+		out_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_i, b_i) = Self::get_pool_balance(pool, pool_account, in_asset.asset_id)?;
+		let (w_o, b_o) = Self::get_pool_balance(pool, pool_account, out_asset_id)?;
+
+		let amm_pair =
+			compute_out_given_in(w_i, w_o, b_i, b_o, T::Convert::convert(in_asset.amount), fee)?;
+
+		let a_out = AssetAmount::new(out_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool
+			.fee_config
+			.calculate_fees(in_asset.asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((a_out, in_asset, fee))
+	}
+
+	pub(crate) fn do_buy(
+		pool: &WeightedProductPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+		out_asset: AssetAmount<T::AssetId, T::Balance>,
+		in_asset_id: T::AssetId,
+		apply_fees: bool,
+	) -> Result<
+		(
+			AssetAmount<T::AssetId, T::Balance>,
+			AssetAmount<T::AssetId, T::Balance>,
+			Fee<T::AssetId, T::Balance>,
+		),
+		DispatchError,
+	> {
+		let fee = if apply_fees { pool.fee_config.fee_rate } else { Permill::zero() };
+		let (w_o, b_o) = Self::get_pool_balance(pool, pool_account, out_asset.asset_id)?;
+		let (w_i, b_i) = Self::get_pool_balance(pool, pool_account, in_asset_id)?;
+
+		let amm_pair =
+			compute_in_given_out(w_i, w_o, b_i, b_o, T::Convert::convert(out_asset.amount), fee)?;
+
+		let a_sent = AssetAmount::new(in_asset_id, T::Convert::convert(amm_pair.value));
+		let fee = pool.fee_config.calculate_fees(in_asset_id, T::Convert::convert(amm_pair.fee));
+
+		Ok((out_asset, a_sent, fee))
+	}
+
+	/// Price of `quote` denominated in `base`, i.e. `(b_base / w_base) / (b_quote / w_quote)`.
+	pub(crate) fn exchange_rate(
+		(w_base, b_base): (Permill, u128),
+		(w_quote, b_quote): (Permill, u128),
+	) -> Result<Rate, DispatchError> {
+		ensure!(!b_base.is_zero() && !b_quote.is_zero(), Error::<T>::NotEnoughLiquidity);
+		let balance_ratio =
+			Rate::checked_from_rational(b_base, b_quote).ok_or(ArithmeticError::Overflow)?;
+		let weight_ratio = Rate::checked_from_rational(w_quote.deconstruct(), w_base.deconstruct())
+			.ok_or(ArithmeticError::DivisionByZero)?;
+		Ok(balance_ratio.checked_mul(&weight_ratio).ok_or(ArithmeticError::Overflow)?)
+	}
+
+	/// Prices of all of the pool's assets, denominated in the first asset of the pool.
+	pub(crate) fn get_exchange_rates(
+		pool: &WeightedProductPoolInfoOf<T>,
+		pool_account: &T::AccountId,
+	) -> Result<BTreeMap<T::AssetId, Rate>, DispatchError> {
+		let pool_assets = Self::get_pool_balances(pool, pool_account);
+		let reference =
+			*pool_assets.values().next().ok_or(Error::<T>::PoolMustContainAtLeastTwoAssets)?;
+		pool_assets
+			.into_iter()
+			.map(|(asset_id, weight_and_balance)| {
+				Ok((asset_id, Self::exchange_rate(reference, weight_and_balance)?))
+			})
+			.collect()
+	}
+}
@@ -13,6 +13,31 @@ pub trait WeightInfo {
 	fn buy() -> Weight;
 	fn swap() -> Weight;
 	fn do_create_pool() -> Weight;
+	fn create_stable_swap() -> Weight;
+	fn add_liquidity_stable_swap() -> Weight;
+	fn remove_liquidity_stable_swap() -> Weight;
+	fn buy_stable_swap() -> Weight;
+	fn swap_stable_swap() -> Weight;
+	fn create_weighted_product(a: u32, ) -> Weight;
+	fn add_liquidity_weighted_product(a: u32, ) -> Weight;
+	fn remove_liquidity_weighted_product(a: u32, ) -> Weight;
+	fn remove_liquidity_single_asset() -> Weight;
+	fn buy_weighted_product() -> Weight;
+	fn swap_weighted_product() -> Weight;
+	fn create_lbp() -> Weight;
+	fn add_liquidity_lbp() -> Weight;
+	fn remove_liquidity_lbp() -> Weight;
+	fn buy_lbp() -> Weight;
+	fn swap_lbp() -> Weight;
+	fn create_concentrated_liquidity() -> Weight;
+	fn open_position() -> Weight;
+	fn close_position() -> Weight;
+	fn collect_position_fees() -> Weight;
+	fn buy_concentrated_liquidity(s: u32, ) -> Weight;
+	fn swap_concentrated_liquidity(s: u32, ) -> Weight;
+	fn place_limit_order() -> Weight;
+	fn cancel_limit_order() -> Weight;
+	fn fill_limit_order() -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -34,5 +59,80 @@ impl WeightInfo for () {
   }
 	fn do_create_pool() -> Weight {
     Weight::from_ref_time(10_000 )
+  }
+	fn create_stable_swap() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn add_liquidity_stable_swap() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn remove_liquidity_stable_swap() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn buy_stable_swap() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn swap_stable_swap() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn create_weighted_product(_a: u32, ) -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn add_liquidity_weighted_product(_a: u32, ) -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn remove_liquidity_weighted_product(_a: u32, ) -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn remove_liquidity_single_asset() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn buy_weighted_product() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn swap_weighted_product() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn create_lbp() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn add_liquidity_lbp() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn remove_liquidity_lbp() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn buy_lbp() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn swap_lbp() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn create_concentrated_liquidity() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn open_position() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn close_position() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn collect_position_fees() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn buy_concentrated_liquidity(_s: u32, ) -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn swap_concentrated_liquidity(_s: u32, ) -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn place_limit_order() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn cancel_limit_order() -> Weight {
+    Weight::from_ref_time(10_000 )
+  }
+	fn fill_limit_order() -> Weight {
+    Weight::from_ref_time(10_000 )
   }
 }
@@ -0,0 +1,212 @@
+//! Adapts the voting power of staking positions to the currency traits `pallet_democracy` takes
+//! votes in.
+
+use crate::{Config, Pallet};
+use composable_traits::staking::VotingPower;
+use frame_support::traits::{
+	BalanceStatus, Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency,
+	ReservableCurrency, SignedImbalance, WithdrawReasons,
+};
+use sp_runtime::{traits::Zero, DispatchError, DispatchResult};
+use sp_std::marker::PhantomData;
+
+/// A currency whose free and total balance are the voting power of an account in the pool
+/// `PoolId`, and whose total issuance is the total voting power of the pool, for votes to be
+/// weighed by staking.
+///
+/// A lock of any amount locks the positions of the account in the pool to its votes, see
+/// [`VotingPower::set_voting_lock`]. Everything else, such as the deposits of proposals, is done in
+/// `NativeCurrency`.
+pub struct VotingPowerCurrency<T, NativeCurrency, PoolId>(PhantomData<(T, NativeCurrency, PoolId)>);
+
+impl<T, NativeCurrency, PoolId> Currency<T::AccountId>
+	for VotingPowerCurrency<T, NativeCurrency, PoolId>
+where
+	T: Config,
+	NativeCurrency: Currency<T::AccountId, Balance = T::Balance>,
+	PoolId: Get<T::AssetId>,
+{
+	type Balance = NativeCurrency::Balance;
+	type PositiveImbalance = NativeCurrency::PositiveImbalance;
+	type NegativeImbalance = NativeCurrency::NegativeImbalance;
+
+	fn total_balance(who: &T::AccountId) -> Self::Balance {
+		Self::free_balance(who)
+	}
+
+	fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
+		NativeCurrency::can_slash(who, value)
+	}
+
+	fn total_issuance() -> Self::Balance {
+		Pallet::<T>::total_voting_power(&PoolId::get())
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		NativeCurrency::minimum_balance()
+	}
+
+	fn burn(amount: Self::Balance) -> Self::PositiveImbalance {
+		NativeCurrency::burn(amount)
+	}
+
+	fn issue(amount: Self::Balance) -> Self::NegativeImbalance {
+		NativeCurrency::issue(amount)
+	}
+
+	fn pair(amount: Self::Balance) -> (Self::PositiveImbalance, Self::NegativeImbalance) {
+		NativeCurrency::pair(amount)
+	}
+
+	fn free_balance(who: &T::AccountId) -> Self::Balance {
+		Pallet::<T>::voting_power(who, &PoolId::get())
+	}
+
+	fn ensure_can_withdraw(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+		new_balance: Self::Balance,
+	) -> DispatchResult {
+		NativeCurrency::ensure_can_withdraw(who, amount, reasons, new_balance)
+	}
+
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: Self::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		NativeCurrency::transfer(source, dest, value, existence_requirement)
+	}
+
+	fn slash(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		NativeCurrency::slash(who, value)
+	}
+
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> Result<Self::PositiveImbalance, DispatchError> {
+		NativeCurrency::deposit_into_existing(who, value)
+	}
+
+	fn resolve_into_existing(
+		who: &T::AccountId,
+		value: Self::NegativeImbalance,
+	) -> Result<(), Self::NegativeImbalance> {
+		NativeCurrency::resolve_into_existing(who, value)
+	}
+
+	fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
+		NativeCurrency::deposit_creating(who, value)
+	}
+
+	fn resolve_creating(who: &T::AccountId, value: Self::NegativeImbalance) {
+		NativeCurrency::resolve_creating(who, value)
+	}
+
+	fn withdraw(
+		who: &T::AccountId,
+		value: Self::Balance,
+		reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> Result<Self::NegativeImbalance, DispatchError> {
+		NativeCurrency::withdraw(who, value, reasons, liveness)
+	}
+
+	fn settle(
+		who: &T::AccountId,
+		value: Self::PositiveImbalance,
+		reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> Result<(), Self::PositiveImbalance> {
+		NativeCurrency::settle(who, value, reasons, liveness)
+	}
+
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: Self::Balance,
+	) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
+		NativeCurrency::make_free_balance_be(who, balance)
+	}
+}
+
+impl<T, NativeCurrency, PoolId> ReservableCurrency<T::AccountId>
+	for VotingPowerCurrency<T, NativeCurrency, PoolId>
+where
+	T: Config,
+	NativeCurrency: ReservableCurrency<T::AccountId, Balance = T::Balance>,
+	PoolId: Get<T::AssetId>,
+{
+	fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+		NativeCurrency::can_reserve(who, value)
+	}
+
+	fn slash_reserved(
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> (Self::NegativeImbalance, Self::Balance) {
+		NativeCurrency::slash_reserved(who, value)
+	}
+
+	fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+		NativeCurrency::reserved_balance(who)
+	}
+
+	fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		NativeCurrency::reserve(who, value)
+	}
+
+	fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		NativeCurrency::unreserve(who, value)
+	}
+
+	fn repatriate_reserved(
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: BalanceStatus,
+	) -> Result<Self::Balance, DispatchError> {
+		NativeCurrency::repatriate_reserved(slashed, beneficiary, value, status)
+	}
+}
+
+impl<T, NativeCurrency, PoolId> LockableCurrency<T::AccountId>
+	for VotingPowerCurrency<T, NativeCurrency, PoolId>
+where
+	T: Config,
+	NativeCurrency: LockableCurrency<T::AccountId, Balance = T::Balance>,
+	PoolId: Get<T::AssetId>,
+{
+	type Moment = NativeCurrency::Moment;
+	type MaxLocks = NativeCurrency::MaxLocks;
+
+	fn set_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		_reasons: WithdrawReasons,
+	) {
+		if amount.is_zero() {
+			Self::remove_lock(id, who);
+		} else {
+			Pallet::<T>::set_voting_lock(id, who, &PoolId::get());
+		}
+	}
+
+	fn extend_lock(
+		id: LockIdentifier,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		_reasons: WithdrawReasons,
+	) {
+		if !amount.is_zero() {
+			Pallet::<T>::set_voting_lock(id, who, &PoolId::get());
+		}
+	}
+
+	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		Pallet::<T>::remove_voting_lock(id, who, &PoolId::get());
+	}
+}
@@ -46,6 +46,8 @@ pub(crate) mod test_helpers;
 
 mod validation;
 
+pub mod governance;
+pub mod migration;
 pub mod prelude;
 pub mod weights;
 
@@ -79,7 +81,8 @@ pub mod pallet {
 	};
 	use composable_traits::{
 		currency::{BalanceLike, CurrencyFactory},
-		fnft::{FinancialNft, FinancialNftProtocol},
+		fnft::{FinancialNft, FinancialNftProtocol, FinancialNftTransferHook},
+		oracle::StakingShares,
 		staking::{RewardPoolConfiguration::RewardRateBasedIncentive, RewardRatePeriod},
 		time::DurationSeconds,
 	};
@@ -94,6 +97,7 @@ pub mod pallet {
 			tokens::{
 				nonfungibles::{
 					Create as NonFungiblesCreate, Inspect as NonFungiblesInspect,
+					InspectEnumerable as NonFungiblesInspectEnumerable,
 					Mutate as NonFungiblesMutate,
 				},
 				WithdrawConsequence,
@@ -109,7 +113,7 @@ pub mod pallet {
 		Permill,
 	};
 	use sp_runtime::{
-		traits::{AccountIdConversion, BlockNumberProvider, One},
+		traits::{AccountIdConversion, BlockNumberProvider, CheckedDiv, One, Saturating},
 		ArithmeticError, PerThing,
 	};
 	use sp_std::{fmt::Debug, ops::Mul, vec, vec::Vec};
@@ -227,6 +231,20 @@ pub mod pallet {
 		Overflow,
 	}
 
+	/// Voting power of the positions of a pool, or of those of an account in the pool, as of
+	/// `timestamp`, in stake-seconds of remaining lock. Divided by the longest lock of the pool,
+	/// `bias` is the voting power.
+	#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct VotingPowerPoint<Balance> {
+		/// Sum of the stakes of the positions times their remaining lock.
+		pub bias: Balance,
+		/// Sum of the stakes of the positions still conferring voting power, by which `bias`
+		/// decreases every second.
+		pub slope: Balance,
+		/// Unix timestamp of the point, in seconds.
+		pub timestamp: u64,
+	}
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Error when creating reward configs.
@@ -279,6 +297,10 @@ pub mod pallet {
 		StakedAmountTooLowAfterSplit,
 		/// Some operation resulted in an arithmetic overflow.
 		ArithmeticError,
+		/// The share asset is already issued by another reward pool.
+		ShareAssetAlreadyInUse,
+		/// The position is locked to the votes of an account.
+		PositionLockedToVotes,
 	}
 
 	pub(crate) type AssetIdOf<T> = <T as Config>::AssetId;
@@ -311,6 +333,7 @@ pub mod pallet {
 
 		// REVIEW(benluelo): Mutate::CollectionId type?
 		type FinancialNft: NonFungiblesMutate<AccountIdOf<Self>>
+			+ NonFungiblesInspectEnumerable<AccountIdOf<Self>>
 			+ NonFungiblesCreate<
 				AccountIdOf<Self>,
 				CollectionId = Self::AssetId,
@@ -387,6 +410,15 @@ pub mod pallet {
 		type TreasuryAccount: Get<Self::AccountId>;
 
 		type ExistentialDeposits: GetByKey<Self::AssetId, Self::Balance>;
+
+		/// Granularity of the ends of voting power, in seconds. A position confers voting power
+		/// until the start of the epoch its lock ends in. Should be longer than a block.
+		#[pallet::constant]
+		type VotingPowerEpoch: Get<DurationSeconds>;
+
+		/// Maximum number of voting locks an account can hold in a pool.
+		#[pallet::constant]
+		type MaxVotingLocks: Get<u32>;
 	}
 
 	/// Abstraction over RewardPoolConfiguration type
@@ -417,8 +449,12 @@ pub mod pallet {
 		<T as Config>::MaxRewardConfigsPerPool,
 	>;
 
+	/// The current storage version, see [`crate::migration`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -438,11 +474,96 @@ pub mod pallet {
 		StakeOf<T>,
 	>;
 
+	/// The reward pool issuing each share asset.
+	///
+	/// Share AssetId -> Pool AssetId
+	#[pallet::storage]
+	pub type SharePools<T: Config> = StorageMap<_, Blake2_128Concat, T::AssetId, T::AssetId>;
+
+	/// The amount staked in each reward pool, the sum of the `stake` of its positions.
+	///
+	/// Pool AssetId -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // A pool without stakes has zero staked.
+	pub type TotalStaked<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, T::Balance, ValueQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	pub(super) type RewardsPotIsEmpty<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, T::AssetId, Blake2_128Concat, T::AssetId, ()>;
 
+	/// Voting power of the positions of each pool, brought forward every block.
+	///
+	/// Pool AssetId -> VotingPowerPoint
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // A pool without stakes has no voting power.
+	pub type TotalVotingPower<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, VotingPowerPoint<T::Balance>, ValueQuery>;
+
+	/// Stakes of the positions whose voting power ends at the start of an epoch, by which the
+	/// slope of the [`TotalVotingPower`] of their pool decreases then.
+	///
+	/// Pool AssetId -> Unix timestamp -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No voting power ends at most epochs.
+	pub type VotingPowerSlopeChanges<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Twox64Concat,
+		u64,
+		T::Balance,
+		ValueQuery,
+	>;
+
+	/// Voting power of the positions owned by each account in each pool, brought forward when
+	/// they change.
+	///
+	/// AccountId -> Pool AssetId -> VotingPowerPoint
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // An account without positions has no voting power.
+	pub type AccountVotingPower<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AssetId,
+		VotingPowerPoint<T::Balance>,
+		ValueQuery,
+	>;
+
+	/// Stakes of the positions of an account whose voting power ends at the start of an epoch, by
+	/// which the slope of the [`AccountVotingPower`] of the account decreases then.
+	///
+	/// AccountId -> Pool AssetId -> Unix timestamp -> Balance
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)] // No voting power ends at most epochs.
+	pub type AccountVotingPowerSlopeChanges<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, T::AssetId>,
+			NMapKey<Twox64Concat, u64>,
+		),
+		T::Balance,
+		ValueQuery,
+	>;
+
+	/// Identifiers of the voting locks of each account in each pool, see
+	/// [`VotingPower::set_voting_lock`].
+	///
+	/// AccountId -> Pool AssetId -> LockIdentifiers
+	#[pallet::storage]
+	pub type VotingLocks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AssetId,
+		BoundedVec<LockIdentifier, T::MaxVotingLocks>,
+	>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		/// Weight: see `begin_block`
@@ -647,6 +768,10 @@ pub mod pallet {
 						!RewardPools::<T>::contains_key(pool_asset),
 						Error::<T>::RewardsPoolAlreadyExists
 					);
+					ensure!(
+						!SharePools::<T>::contains_key(share_asset_id),
+						Error::<T>::ShareAssetAlreadyInUse
+					);
 
 					ensure!(lock.duration_presets.len() > 0, Error::<T>::NoDurationPresetsProvided);
 
@@ -687,6 +812,7 @@ pub mod pallet {
 						.try_collect()
 						.expect("No items were added; qed;");
 
+					SharePools::<T>::insert(share_asset_id, pool_asset);
 					RewardPools::<T>::insert(
 						pool_asset,
 						RewardPool {
@@ -813,7 +939,12 @@ pub mod pallet {
 			// Mint the fNFT
 			T::FinancialNft::mint_into(&fnft_collection_id, &fnft_instance_id, who)?;
 
+			TotalStaked::<T>::try_mutate(pool_id, |total| {
+				*total = total.safe_add(&amount)?;
+				Ok::<_, DispatchError>(())
+			})?;
 			RewardPools::<T>::insert(pool_id, rewards_pool);
+			Self::add_voting_power(who, &new_position);
 			Stakes::<T>::insert(fnft_collection_id, fnft_instance_id, new_position);
 
 			Self::deposit_event(Event::<T>::Staked {
@@ -839,6 +970,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			Stakes::<T>::try_mutate(fnft_collection_id, fnft_instance_id, |maybe_stake| {
 				let stake = maybe_stake.as_mut().ok_or(Error::<T>::StakeNotFound)?;
+				Self::remove_voting_power(who, stake);
 
 				RewardPools::<T>::try_mutate(stake.reward_pool_id, |maybe_rewards_pool| {
 					let rewards_pool =
@@ -919,8 +1051,13 @@ pub mod pallet {
 					});
 
 					stake.stake = stake.stake.safe_add(&amount)?;
+					TotalStaked::<T>::try_mutate(stake.reward_pool_id, |total| {
+						*total = total.safe_add(&amount)?;
+						Ok::<_, DispatchError>(())
+					})?;
 					stake.share = stake.share.safe_add(&new_shares)?;
 					stake.lock.started_at = T::UnixTime::now().as_secs();
+					Self::add_voting_power(who, stake);
 
 					Ok(())
 				})
@@ -935,6 +1072,10 @@ pub mod pallet {
 			// TODO(benluelo): Use ::take here instead of try_get and then remove
 			let mut stake = Stakes::<T>::try_get(fnft_collection_id, fnft_instance_id)
 				.map_err(|_| Error::<T>::StakeNotFound)?;
+			ensure!(
+				!VotingLocks::<T>::contains_key(who, stake.reward_pool_id),
+				Error::<T>::PositionLockedToVotes
+			);
 
 			let is_early_unlock = stake.lock.started_at.safe_add(&stake.lock.duration)? >=
 				T::UnixTime::now().as_secs();
@@ -979,6 +1120,10 @@ pub mod pallet {
 			)?;
 
 			Stakes::<T>::remove(fnft_collection_id, fnft_instance_id);
+			TotalStaked::<T>::mutate(stake.reward_pool_id, |total| {
+				*total = total.defensive_saturating_sub(stake.stake)
+			});
+			Self::remove_voting_power(who, &stake);
 
 			// transfer slashed stake to the treasury
 			if is_early_unlock {
@@ -1025,6 +1170,7 @@ pub mod pallet {
 				|maybe_existing_position| {
 					let existing_position =
 						maybe_existing_position.as_mut().ok_or(Error::<T>::StakeNotFound)?;
+					Self::remove_voting_power(who, existing_position);
 
 					let left_from_one_ratio = ratio.left_from_one();
 
@@ -1061,6 +1207,7 @@ pub mod pallet {
 					for (_, reduction) in &mut existing_position.reductions {
 						*reduction = ratio.mul_floor(*reduction);
 					}
+					Self::add_voting_power(who, existing_position);
 
 					let new_fnft_instance_id =
 						T::FinancialNft::get_next_nft_id(fnft_collection_id)?;
@@ -1119,6 +1266,7 @@ pub mod pallet {
 				},
 			)?;
 
+			Self::add_voting_power(who, &new_position);
 			Stakes::<T>::insert(fnft_collection_id, new_fnft_instance_id, new_position);
 
 			Ok((*fnft_collection_id, new_fnft_instance_id))
@@ -1181,6 +1329,137 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// End of the voting power of a position: the start of the epoch its lock ends in.
+		pub(crate) fn voting_power_end(stake: &StakeOf<T>) -> u64 {
+			let end = stake.lock.started_at.saturating_add(stake.lock.duration);
+			end.saturating_sub(end % T::VotingPowerEpoch::get().max(1))
+		}
+
+		/// Stake-seconds of remaining lock of a position at `now_seconds`.
+		pub(crate) fn voting_power_bias(stake: &StakeOf<T>, now_seconds: u64) -> T::Balance {
+			let remaining = Self::voting_power_end(stake).saturating_sub(now_seconds);
+			stake.stake.saturating_mul(T::Balance::from(u128::from(remaining)))
+		}
+
+		/// Voting power of `bias` stake-seconds of remaining lock: the stake, weighed by its
+		/// remaining lock relative to the longest lock of the pool.
+		pub(crate) fn voting_power_of(pool: &RewardPoolOf<T>, bias: T::Balance) -> T::Balance {
+			let max_duration =
+				pool.lock.duration_presets.keys().next_back().copied().unwrap_or_default();
+			bias.checked_div(&T::Balance::from(u128::from(max_duration)))
+				.unwrap_or_else(T::Balance::zero)
+		}
+
+		/// Brings a [`VotingPowerPoint`] forward to `now_seconds`, dropping from its slope the
+		/// stakes `slope_change` returns for each epoch started in between.
+		pub(crate) fn voting_power_point_at(
+			mut point: VotingPowerPoint<T::Balance>,
+			now_seconds: u64,
+			mut slope_change: impl FnMut(u64) -> T::Balance,
+		) -> VotingPowerPoint<T::Balance> {
+			let epoch = T::VotingPowerEpoch::get().max(1);
+			// without a slope, there is no voting power left to end
+			while point.timestamp < now_seconds && !point.slope.is_zero() {
+				let next = point
+					.timestamp
+					.saturating_sub(point.timestamp % epoch)
+					.saturating_add(epoch)
+					.min(now_seconds);
+				let elapsed = T::Balance::from(u128::from(next.saturating_sub(point.timestamp)));
+				point.bias = point.bias.saturating_sub(point.slope.saturating_mul(elapsed));
+				if next % epoch == 0 {
+					point.slope = point.slope.saturating_sub(slope_change(next));
+				}
+				point.timestamp = next;
+			}
+			point.timestamp = point.timestamp.max(now_seconds);
+			point
+		}
+
+		/// Brings the [`TotalVotingPower`] of a pool forward to `now_seconds`, pruning the slope
+		/// changes it went past.
+		pub(crate) fn checkpoint_voting_power(pool_id: &T::AssetId, now_seconds: u64) {
+			TotalVotingPower::<T>::mutate(pool_id, |point| {
+				*point = Self::voting_power_point_at(point.clone(), now_seconds, |at| {
+					VotingPowerSlopeChanges::<T>::take(pool_id, at)
+				});
+			});
+		}
+
+		/// Brings the [`AccountVotingPower`] of an account in a pool forward to `now_seconds`,
+		/// pruning the slope changes it went past, and applies `f` to it. The point is removed once
+		/// the account has no voting power left.
+		pub(crate) fn checkpoint_account_voting_power(
+			who: &T::AccountId,
+			pool_id: &T::AssetId,
+			now_seconds: u64,
+			f: impl FnOnce(&mut VotingPowerPoint<T::Balance>),
+		) {
+			AccountVotingPower::<T>::mutate_exists(who, pool_id, |maybe_point| {
+				let mut point = Self::voting_power_point_at(
+					maybe_point.take().unwrap_or_default(),
+					now_seconds,
+					|at| AccountVotingPowerSlopeChanges::<T>::take((who, pool_id, at)),
+				);
+				f(&mut point);
+				*maybe_point = (!point.slope.is_zero()).then_some(point);
+			});
+		}
+
+		/// Adds the voting power of a position to the total of its pool and to that of the
+		/// account owning it.
+		pub(crate) fn add_voting_power(who: &T::AccountId, stake: &StakeOf<T>) {
+			let pool_id = stake.reward_pool_id;
+			let end = Self::voting_power_end(stake);
+			let now_seconds = T::UnixTime::now().as_secs();
+			Self::checkpoint_voting_power(&pool_id, now_seconds);
+			if end <= now_seconds {
+				return
+			}
+			let bias = Self::voting_power_bias(stake, now_seconds);
+			let add = |point: &mut VotingPowerPoint<T::Balance>| {
+				point.bias = point.bias.saturating_add(bias);
+				point.slope = point.slope.saturating_add(stake.stake);
+			};
+			TotalVotingPower::<T>::mutate(pool_id, add);
+			VotingPowerSlopeChanges::<T>::mutate(pool_id, end, |slope| {
+				*slope = slope.saturating_add(stake.stake)
+			});
+			Self::checkpoint_account_voting_power(who, &pool_id, now_seconds, add);
+			AccountVotingPowerSlopeChanges::<T>::mutate((who, pool_id, end), |slope| {
+				*slope = slope.saturating_add(stake.stake)
+			});
+		}
+
+		/// Takes the voting power of a position back from the total of its pool and from that of
+		/// the account owning it. The voting power of a position whose lock ended was already
+		/// dropped from both.
+		pub(crate) fn remove_voting_power(who: &T::AccountId, stake: &StakeOf<T>) {
+			let pool_id = stake.reward_pool_id;
+			let end = Self::voting_power_end(stake);
+			let now_seconds = T::UnixTime::now().as_secs();
+			Self::checkpoint_voting_power(&pool_id, now_seconds);
+			if end <= now_seconds {
+				return
+			}
+			let bias = Self::voting_power_bias(stake, now_seconds);
+			let remove = |point: &mut VotingPowerPoint<T::Balance>| {
+				point.bias = point.bias.defensive_saturating_sub(bias);
+				point.slope = point.slope.defensive_saturating_sub(stake.stake);
+			};
+			let remove_slope_change = |slope: &mut Option<T::Balance>| {
+				let left = slope.unwrap_or_default().defensive_saturating_sub(stake.stake);
+				*slope = (!left.is_zero()).then_some(left);
+			};
+			TotalVotingPower::<T>::mutate(pool_id, remove);
+			VotingPowerSlopeChanges::<T>::mutate_exists(pool_id, end, remove_slope_change);
+			Self::checkpoint_account_voting_power(who, &pool_id, now_seconds, remove);
+			AccountVotingPowerSlopeChanges::<T>::mutate_exists(
+				(who, pool_id, end),
+				remove_slope_change,
+			);
+		}
+
 		/// Ensure `who` is the owner of the fNFT associated with a stake
 		///
 		/// # Errors
@@ -1443,6 +1722,97 @@ pub mod pallet {
 			})
 		}
 	}
+
+	impl<T: Config> StakingShares<T::AssetId, T::Balance> for Pallet<T> {
+		/// Shares are redeemable for the stakes of the pool, pro rata.
+		fn staked_for_shares(
+			share_asset_id: T::AssetId,
+			amount: T::Balance,
+		) -> Result<(T::AssetId, T::Balance), DispatchError> {
+			let staked_asset_id =
+				SharePools::<T>::get(share_asset_id).ok_or(Error::<T>::RewardsPoolNotFound)?;
+			let total_shares = T::Assets::total_issuance(share_asset_id);
+			if total_shares.is_zero() {
+				return Ok((staked_asset_id, amount))
+			}
+			let total_staked = TotalStaked::<T>::get(staked_asset_id);
+			Ok((staked_asset_id, amount.safe_mul(&total_staked)?.safe_div(&total_shares)?))
+		}
+	}
+
+	impl<T: Config> VotingPower<T::AccountId, T::AssetId, T::Balance> for Pallet<T> {
+		/// Brings the running total of `who` forward from the last change to its positions.
+		fn voting_power(who: &T::AccountId, pool_id: &T::AssetId) -> T::Balance {
+			let pool = match RewardPools::<T>::get(pool_id) {
+				Some(pool) => pool,
+				None => return T::Balance::zero(),
+			};
+			let point = Self::voting_power_point_at(
+				AccountVotingPower::<T>::get(who, pool_id),
+				T::UnixTime::now().as_secs(),
+				|at| AccountVotingPowerSlopeChanges::<T>::get((who, pool_id, at)),
+			);
+			Self::voting_power_of(&pool, point.bias)
+		}
+
+		/// Brings the running total of the pool forward from the start of the block.
+		fn total_voting_power(pool_id: &T::AssetId) -> T::Balance {
+			let pool = match RewardPools::<T>::get(pool_id) {
+				Some(pool) => pool,
+				None => return T::Balance::zero(),
+			};
+			let point = Self::voting_power_point_at(
+				TotalVotingPower::<T>::get(pool_id),
+				T::UnixTime::now().as_secs(),
+				|at| VotingPowerSlopeChanges::<T>::get(pool_id, at),
+			);
+			Self::voting_power_of(&pool, point.bias)
+		}
+
+		fn set_voting_lock(id: LockIdentifier, who: &T::AccountId, pool_id: &T::AssetId) {
+			VotingLocks::<T>::mutate(who, pool_id, |maybe_ids| {
+				let ids = maybe_ids.get_or_insert_with(Default::default);
+				// the positions stay locked as long as any lock is held
+				if !ids.contains(&id) && ids.try_push(id).is_err() {
+					defensive!("more voting locks than MaxVotingLocks");
+				}
+			});
+		}
+
+		fn remove_voting_lock(id: LockIdentifier, who: &T::AccountId, pool_id: &T::AssetId) {
+			VotingLocks::<T>::mutate_exists(who, pool_id, |maybe_ids| {
+				if let Some(ids) = maybe_ids {
+					ids.retain(|lock_id| *lock_id != id);
+					if ids.is_empty() {
+						*maybe_ids = None;
+					}
+				}
+			});
+		}
+	}
+
+	impl<T: Config> FinancialNftTransferHook<T::AccountId, T::AssetId, FinancialNftInstanceIdOf<T>>
+		for Pallet<T>
+	{
+		/// Moves the voting power of a position to its new owner. The positions of an account
+		/// holding voting locks in their pool cannot be transferred.
+		fn on_transfer(
+			collection: &T::AssetId,
+			instance: &FinancialNftInstanceIdOf<T>,
+			from: &T::AccountId,
+			to: &T::AccountId,
+		) -> DispatchResult {
+			if let Some(stake) = Stakes::<T>::get(collection, instance) {
+				ensure!(
+					!VotingLocks::<T>::contains_key(from, stake.reward_pool_id),
+					Error::<T>::PositionLockedToVotes
+				);
+				Self::remove_voting_power(from, &stake);
+				Self::add_voting_power(to, &stake);
+			}
+			Ok(())
+		}
+	}
 }
 /// Accumulates the rewards in a pool, if the pot isn't empty. Emits the relevant events
 /// after accumulation. See [`accumulate_reward`] for more information about how the
@@ -1521,6 +1891,10 @@ pub(crate) fn accumulate_rewards_hook<T: Config>() -> Weight {
 		total_weight +=
 			accumulate_pool_rewards::<T>(pool_id, &mut reward_pool, current_block, now_seconds);
 
+		// the voting power crosses at most one epoch per block
+		Pallet::<T>::checkpoint_voting_power(&pool_id, now_seconds);
+		total_weight += T::DbWeight::get().reads_writes(2, 2);
+
 		Some(reward_pool)
 	});
 
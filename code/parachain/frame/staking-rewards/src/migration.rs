@@ -0,0 +1,77 @@
+//! Storage migrations of the staking rewards pallet.
+
+use crate::*;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{tokens::nonfungibles::Inspect, OnRuntimeUpgrade},
+};
+use sp_runtime::traits::Saturating;
+use sp_std::marker::PhantomData;
+
+pub mod v1 {
+	use super::*;
+
+	/// Fills [`SharePools`] from the existing reward pools and sums the stakes of each pool into
+	/// [`TotalStaked`].
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut pools = 0_u64;
+			for (pool_id, pool) in RewardPools::<T>::iter() {
+				pools += 1;
+				SharePools::<T>::insert(pool.share_asset_id, pool_id);
+			}
+
+			let mut stakes = 0_u64;
+			for (_, _, stake) in Stakes::<T>::iter() {
+				stakes += 1;
+				TotalStaked::<T>::mutate(stake.reward_pool_id, |total| {
+					*total = total.saturating_add(stake.stake)
+				});
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(
+				pools.saturating_add(stakes.saturating_mul(2)).saturating_add(1),
+				pools.saturating_add(stakes).saturating_add(1),
+			)
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Sums the voting power of the existing positions into [`TotalVotingPower`] and the
+	/// [`AccountVotingPower`] of their owners, scheduling the end of each into
+	/// [`VotingPowerSlopeChanges`] and [`AccountVotingPowerSlopeChanges`].
+	pub struct MigrateToV2<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 2 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut stakes = 0_u64;
+			for (collection, instance, stake) in Stakes::<T>::iter() {
+				stakes += 1;
+				match T::FinancialNft::owner(&collection, &instance) {
+					Some(owner) => Pallet::<T>::add_voting_power(&owner, &stake),
+					None => log::warn!("no owner for position {collection:?}/{instance:?}"),
+				}
+			}
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(
+				stakes.saturating_mul(7).saturating_add(1),
+				stakes.saturating_mul(5).saturating_add(1),
+			)
+		}
+	}
+}
@@ -2,6 +2,7 @@ use composable_tests_helpers::test::currency::PICA;
 use composable_traits::{
 	account_proxy::ProxyType,
 	governance::{GovernanceRegistry, SignedRawOrigin},
+	time::{DurationSeconds, ONE_MINUTE},
 };
 use frame_support::pallet_prelude::*;
 use sp_core::{
@@ -214,6 +215,7 @@ impl pallet_fnft::Config for Test {
 	type ProxyType = ProxyType;
 	type AccountProxy = AccountProxyWrapperInstance;
 	type ProxyTypeSelector = FnftAccountProxyType;
+	type OnTransfer = StakingRewards;
 	type PalletId = FnftPalletId;
 	type WeightInfo = ();
 }
@@ -245,6 +247,7 @@ parameter_types! {
 	pub const MaxStakingDurationPresets : u32 = 10;
 	pub const MaxRewardConfigsPerPool : u32 = 10;
 	pub const StakingRewardsLockId: LockIdentifier = *b"stk_lock";
+	pub const VotingPowerEpoch: DurationSeconds = ONE_MINUTE;
 	// REVIEW(benluelo): Use a better value for this?
 	pub const TreasuryAccountId: AccountId = sr25519::Public([10_u8; 32]);
 }
@@ -269,6 +272,8 @@ impl crate::Config for Test {
 
 	type LockId = StakingRewardsLockId;
 	type TreasuryAccount = TreasuryAccountId;
+	type VotingPowerEpoch = VotingPowerEpoch;
+	type MaxVotingLocks = ConstU32<8>;
 }
 
 impl InstanceFilter<RuntimeCall> for ProxyType {
@@ -4,14 +4,15 @@
 pub(crate) use crate::runtime::{new_test_ext, Test};
 
 use crate::{
-	claim_of_stake,
+	claim_of_stake, governance,
 	runtime::*,
 	test::prelude::{MINIMUM_STAKING_AMOUNT, STAKING_FNFT_COLLECTION_ID},
 	test_helpers::{
 		add_to_rewards_pot_and_assert, create_rewards_pool_and_assert, split_and_assert,
 		stake_and_assert, unstake_and_assert,
 	},
-	FinancialNftInstanceIdOf, Pallet, RewardPoolConfigurationOf, RewardPools, Stakes,
+	AccountVotingPower, FinancialNftInstanceIdOf, Pallet, RewardPoolConfigurationOf, RewardPools,
+	Stakes, TotalStaked,
 };
 
 use composable_support::validation::TryIntoValidated;
@@ -23,11 +24,12 @@ use composable_tests_helpers::test::{
 
 use composable_traits::{
 	fnft::{FinancialNft as FinancialNftT, FinancialNftProtocol},
+	oracle::StakingShares,
 	staking::{
 		lock::{Lock, LockConfig},
 		ProtocolStaking, RewardConfig,
 		RewardPoolConfiguration::RewardRateBasedIncentive,
-		RewardRate, Stake,
+		RewardRate, Stake, VotingPower,
 	},
 	time::{DurationSeconds, ONE_HOUR, ONE_MINUTE},
 };
@@ -36,7 +38,7 @@ use frame_support::{
 	traits::{
 		fungibles::{Inspect, Mutate},
 		tokens::nonfungibles::InspectEnumerable,
-		TryCollect,
+		Currency, LockIdentifier, LockableCurrency, TryCollect, WithdrawReasons,
 	},
 	BoundedBTreeMap,
 };
@@ -1550,6 +1552,281 @@ fn unstake_should_work() {
 		unstake_and_assert::<Test>(BOB, STAKING_FNFT_COLLECTION_ID, fnft_instance_id, true);
 	})
 }
+
+#[test]
+fn voting_power_decays_with_remaining_lock() {
+	new_test_ext().execute_with(|| {
+		next_block::<crate::Pallet<Test>, Test>();
+
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			end_block: 100_000,
+			reward_configs: default_reward_config(),
+			lock: default_lock_config(),
+			share_asset_id: XPICA::ID,
+			financial_nft_asset_id: STAKING_FNFT_COLLECTION_ID,
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		// stake at the start of a voting power epoch, so that locks aren't rounded down
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(9);
+
+		mint_assets([BOB, CHARLIE], [PICA::ID], PICA::units(200));
+		stake_and_assert::<Test>(BOB, PICA::ID, PICA::units(100), ONE_HOUR);
+		stake_and_assert::<Test>(CHARLIE, PICA::ID, PICA::units(100), ONE_MINUTE);
+
+		// the longest lock of the pool confers full voting power
+		let charlie_voting_power = PICA::units(100) * u128::from(ONE_MINUTE) / u128::from(ONE_HOUR);
+		assert_eq!(StakingRewards::voting_power(&BOB, &PICA::ID), PICA::units(100));
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), charlie_voting_power);
+		assert_eq!(
+			StakingRewards::total_voting_power(&PICA::ID),
+			PICA::units(100) + charlie_voting_power
+		);
+		assert_eq!(StakingRewards::voting_power(&BOB, &BTC::ID), 0);
+
+		// 300 blocks * 6 seconds per block is half an hour
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(300);
+
+		assert_eq!(StakingRewards::voting_power(&BOB, &PICA::ID), PICA::units(50));
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), 0);
+		assert_eq!(StakingRewards::total_voting_power(&PICA::ID), PICA::units(50));
+	})
+}
+
+#[test]
+fn total_voting_power_is_the_sum_of_positions() {
+	new_test_ext().execute_with(|| {
+		next_block::<crate::Pallet<Test>, Test>();
+
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			end_block: 100_000,
+			reward_configs: default_reward_config(),
+			lock: default_lock_config(),
+			share_asset_id: XPICA::ID,
+			financial_nft_asset_id: STAKING_FNFT_COLLECTION_ID,
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(9);
+
+		// stakes divisible by the longest lock, so that voting powers aren't rounded down
+		let assert_total_is_sum = || {
+			assert_eq!(
+				StakingRewards::total_voting_power(&PICA::ID),
+				StakingRewards::voting_power(&BOB, &PICA::ID) +
+					StakingRewards::voting_power(&CHARLIE, &PICA::ID)
+			);
+		};
+
+		mint_assets([BOB, CHARLIE], [PICA::ID], PICA::units(200));
+		let bob_instance_id = stake_and_assert::<Test>(BOB, PICA::ID, PICA::units(36), ONE_HOUR);
+		let charlie_instance_id =
+			stake_and_assert::<Test>(CHARLIE, PICA::ID, PICA::units(36), ONE_MINUTE);
+		assert_total_is_sum();
+
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(5);
+		assert_ok!(StakingRewards::extend(
+			RuntimeOrigin::signed(BOB),
+			STAKING_FNFT_COLLECTION_ID,
+			bob_instance_id,
+			PICA::units(36),
+		));
+		assert_total_is_sum();
+
+		let bob_split_instance_id = split_and_assert::<Test>(
+			BOB,
+			STAKING_FNFT_COLLECTION_ID,
+			bob_instance_id,
+			Permill::from_percent(50).try_into_validated().unwrap(),
+		);
+		assert_total_is_sum();
+
+		// past the lock of CHARLIE
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(10);
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), 0);
+		assert_total_is_sum();
+
+		unstake_and_assert::<Test>(CHARLIE, STAKING_FNFT_COLLECTION_ID, charlie_instance_id, false);
+		unstake_and_assert::<Test>(BOB, STAKING_FNFT_COLLECTION_ID, bob_instance_id, true);
+		assert_total_is_sum();
+
+		// past the lock of BOB
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(600);
+		assert_eq!(StakingRewards::total_voting_power(&PICA::ID), 0);
+
+		unstake_and_assert::<Test>(BOB, STAKING_FNFT_COLLECTION_ID, bob_split_instance_id, false);
+		assert_eq!(StakingRewards::total_voting_power(&PICA::ID), 0);
+	})
+}
+
+#[test]
+fn transfers_move_voting_power() {
+	new_test_ext().execute_with(|| {
+		next_block::<crate::Pallet<Test>, Test>();
+
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			end_block: 100_000,
+			reward_configs: default_reward_config(),
+			lock: default_lock_config(),
+			share_asset_id: XPICA::ID,
+			financial_nft_asset_id: STAKING_FNFT_COLLECTION_ID,
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(9);
+
+		mint_assets([BOB], [PICA::ID], PICA::units(200));
+		let instance_id = stake_and_assert::<Test>(BOB, PICA::ID, PICA::units(100), ONE_HOUR);
+		assert_ok!(FinancialNft::transfer(
+			RuntimeOrigin::signed(BOB),
+			STAKING_FNFT_COLLECTION_ID,
+			instance_id,
+			CHARLIE,
+		));
+
+		assert_eq!(StakingRewards::voting_power(&BOB, &PICA::ID), 0);
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), PICA::units(100));
+		assert_eq!(StakingRewards::total_voting_power(&PICA::ID), PICA::units(100));
+
+		// 300 blocks * 6 seconds per block is half an hour
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(300);
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), PICA::units(50));
+
+		unstake_and_assert::<Test>(CHARLIE, STAKING_FNFT_COLLECTION_ID, instance_id, true);
+		assert_eq!(StakingRewards::voting_power(&CHARLIE, &PICA::ID), 0);
+		assert_eq!(StakingRewards::total_voting_power(&PICA::ID), 0);
+		assert!(!AccountVotingPower::<Test>::contains_key(CHARLIE, PICA::ID));
+	})
+}
+
+#[test]
+fn voted_positions_cannot_be_transferred_nor_unstaked() {
+	type StakedVotingPower = governance::VotingPowerCurrency<
+		Test,
+		Balances,
+		frame_support::traits::ConstU128<{ PICA::ID }>,
+	>;
+	const VOTE_LOCK_ID: LockIdentifier = *b"democrac";
+
+	new_test_ext().execute_with(|| {
+		next_block::<crate::Pallet<Test>, Test>();
+
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			end_block: 100_000,
+			reward_configs: default_reward_config(),
+			lock: default_lock_config(),
+			share_asset_id: XPICA::ID,
+			financial_nft_asset_id: STAKING_FNFT_COLLECTION_ID,
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		process_and_progress_blocks::<crate::Pallet<Test>, Test>(9);
+
+		mint_assets([BOB], [PICA::ID], PICA::units(200));
+		let instance_id = stake_and_assert::<Test>(BOB, PICA::ID, PICA::units(100), ONE_HOUR);
+
+		assert_eq!(StakedVotingPower::free_balance(&BOB), PICA::units(100));
+		assert_eq!(StakedVotingPower::total_issuance(), PICA::units(100));
+
+		StakedVotingPower::set_lock(VOTE_LOCK_ID, &BOB, PICA::units(100), WithdrawReasons::all());
+		assert_noop!(
+			FinancialNft::transfer(
+				RuntimeOrigin::signed(BOB),
+				STAKING_FNFT_COLLECTION_ID,
+				instance_id,
+				CHARLIE,
+			),
+			crate::Error::<Test>::PositionLockedToVotes
+		);
+		assert_noop!(
+			StakingRewards::unstake(
+				RuntimeOrigin::signed(BOB),
+				STAKING_FNFT_COLLECTION_ID,
+				instance_id
+			),
+			crate::Error::<Test>::PositionLockedToVotes
+		);
+		assert_eq!(StakedVotingPower::free_balance(&BOB), PICA::units(100));
+
+		StakedVotingPower::remove_lock(VOTE_LOCK_ID, &BOB);
+
+		assert_ok!(FinancialNft::transfer(
+			RuntimeOrigin::signed(BOB),
+			STAKING_FNFT_COLLECTION_ID,
+			instance_id,
+			CHARLIE,
+		));
+		assert_eq!(StakedVotingPower::free_balance(&BOB), 0);
+		assert_eq!(StakedVotingPower::free_balance(&CHARLIE), PICA::units(100));
+		assert_eq!(StakedVotingPower::total_issuance(), PICA::units(100));
+	})
+}
+
+#[test]
+fn shares_are_redeemable_for_the_total_staked() {
+	new_test_ext().execute_with(|| {
+		next_block::<crate::Pallet<Test>, Test>();
+
+		create_rewards_pool_and_assert::<Test>(RewardRateBasedIncentive {
+			owner: ALICE,
+			asset_id: PICA::ID,
+			start_block: 2,
+			end_block: 100_000,
+			reward_configs: default_reward_config(),
+			lock: default_lock_config(),
+			share_asset_id: XPICA::ID,
+			financial_nft_asset_id: STAKING_FNFT_COLLECTION_ID,
+			minimum_staking_amount: MINIMUM_STAKING_AMOUNT,
+		});
+
+		next_block::<crate::Pallet<Test>, Test>();
+
+		mint_assets([BOB, CHARLIE], [PICA::ID], PICA::units(200));
+		let bob_instance_id = stake_and_assert::<Test>(BOB, PICA::ID, PICA::units(100), ONE_HOUR);
+		let charlie_instance_id =
+			stake_and_assert::<Test>(CHARLIE, PICA::ID, PICA::units(100), ONE_MINUTE);
+		assert_ok!(StakingRewards::extend(
+			RuntimeOrigin::signed(BOB),
+			STAKING_FNFT_COLLECTION_ID,
+			bob_instance_id,
+			PICA::units(50),
+		));
+		assert_eq!(TotalStaked::<Test>::get(PICA::ID), PICA::units(250));
+
+		let total_shares = <Tokens as Inspect<_>>::total_issuance(XPICA::ID);
+		assert_eq!(
+			StakingRewards::staked_for_shares(XPICA::ID, total_shares),
+			Ok((PICA::ID, PICA::units(250)))
+		);
+
+		unstake_and_assert::<Test>(CHARLIE, STAKING_FNFT_COLLECTION_ID, charlie_instance_id, true);
+		assert_eq!(TotalStaked::<Test>::get(PICA::ID), PICA::units(150));
+		assert_eq!(
+			StakingRewards::staked_for_shares(
+				XPICA::ID,
+				<Tokens as Inspect<_>>::total_issuance(XPICA::ID)
+			),
+			Ok((PICA::ID, PICA::units(150)))
+		);
+		assert_eq!(
+			StakingRewards::staked_for_shares(PICA::ID, 1),
+			Err(crate::Error::<Test>::RewardsPoolNotFound.into())
+		);
+	})
+}
+
 mod claim {
 	use crate::test::prelude::init_logger;
 
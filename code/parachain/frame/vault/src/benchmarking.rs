@@ -1,16 +1,18 @@
 use super::*;
 
-use crate::Pallet as Vault;
+use crate::{models::FeeConfig, Pallet as Vault};
 use codec::{Decode, Encode, MaxEncodedLen};
 use composable_support::validation::Validated;
-use composable_traits::vault::{CapabilityVault, Deposit, Vault as VaultTrait, VaultConfig};
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use composable_traits::vault::{
+	CapabilityVault, Deposit, StrategicVault, Vault as VaultTrait, VaultConfig,
+};
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
 use frame_support::{
 	assert_ok,
 	traits::{fungible::Mutate as FungibleMutate, fungibles::Mutate as FungiblesMutate, Get},
 };
 use frame_system::{EventRecord, Pallet as System, RawOrigin};
-use sp_runtime::Perquintill;
+use sp_runtime::{PerThing, Perquintill};
 use sp_std::prelude::*;
 
 fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
@@ -198,6 +200,92 @@ benchmarks! {
 		// Wait until the vault is deletable.
 		System::<T>::set_block_number(System::<T>::block_number() + T::TombstoneDuration::get());
 	}: _(RawOrigin::Signed(caller), vault, None)
+
+	rebalance {
+		let s in 1 .. T::MaxStrategies::get() as u32;
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::CreationDeposit::get() * 10u32.into();
+		let share = Perquintill::from_rational(1, u64::from(s) + 1);
+		let strategies = (0..s).map(|i| (account("strategy", i, 0), share)).collect();
+		let reserved = Perquintill::from_parts(
+			Perquintill::one().deconstruct() - share.deconstruct() * u64::from(s),
+		);
+		let config = VaultConfig { asset_id: recode_unwrap_u128(A), manager: caller.clone(), reserved, strategies };
+		let (vault, _) = Vault::<T>::do_create_vault(Deposit::Existential, Validated::new(config).unwrap()).unwrap();
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		<Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+	}: _(RawOrigin::Signed(caller), vault)
+
+	request_redemption {
+		let s in 1 .. T::MaxStrategies::get() as u32;
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::CreationDeposit::get() * 10u32.into();
+		let share = Perquintill::from_rational(1, u64::from(s) + 1);
+		let strategies: Vec<T::AccountId> = (0..s).map(|i| account("strategy", i, 0)).collect();
+		let reserved = Perquintill::from_parts(
+			Perquintill::one().deconstruct() - share.deconstruct() * u64::from(s),
+		);
+		let config = VaultConfig {
+			asset_id: recode_unwrap_u128(A),
+			manager: caller.clone(),
+			reserved,
+			strategies: strategies.iter().map(|strategy| (strategy.clone(), share)).collect(),
+		};
+		let (vault, _) = Vault::<T>::do_create_vault(Deposit::Existential, Validated::new(config).unwrap()).unwrap();
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		let lp_amount = <Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+		// deploy all funds, so that every strategy is asked to liquidate
+		let deployed = amount / (s + 1).into();
+		for strategy in &strategies {
+			<Vault<T> as StrategicVault>::withdraw(&vault, strategy, deployed)?;
+		}
+	}: _(RawOrigin::Signed(caller), vault, lp_amount)
+
+	claim_redemption {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::CreationDeposit::get() * 10u32.into();
+		let (vault, _) = create_vault::<T>(A, account("strategy", 0, 0));
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		let lp_amount = <Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+		Vault::<T>::request_redemption(RawOrigin::Signed(caller.clone()).into(), vault, lp_amount)?;
+	}: _(RawOrigin::Signed(caller), vault, 0)
+
+	set_fees {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::CreationDeposit::get() * 10u32.into();
+		let (vault, _) = create_vault::<T>(A, account("strategy", 0, 0));
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		<Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+		let fees = FeeConfig {
+			recipient: account("recipient", 0, 0),
+			management: Perquintill::from_percent(2),
+			performance: Perquintill::from_percent(20),
+		};
+		Vault::<T>::set_fees(RawOrigin::Signed(caller.clone()).into(), vault, Some(fees.clone()))?;
+		System::<T>::set_block_number(T::BlocksPerYear::get());
+	}: _(RawOrigin::Signed(caller), vault, Some(fees))
+
+	fulfil_redemptions {
+		let r in 1 .. T::MaxRedemptionsPerCall::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let strategy: T::AccountId = account("strategy", 0, 0);
+		let amount = T::CreationDeposit::get() * 10u32.into();
+		let (vault, vault_info) = create_vault_extended::<T>(A, strategy.clone(), Perquintill::one(), Perquintill::zero(), Deposit::Existential);
+		T::Currency::mint_into(recode_unwrap_u128(A), &caller, amount * 2u32.into())?;
+		let lp_amount = <Vault<T> as VaultTrait>::deposit(&vault, &caller, amount)?;
+		// deploy all funds, so that none of the redemptions is fulfilled when requested
+		<Vault<T> as StrategicVault>::withdraw(&vault, &strategy, amount)?;
+		for _ in 0..r {
+			Vault::<T>::request_redemption(RawOrigin::Signed(caller.clone()).into(), vault, lp_amount / r.into())?;
+		}
+		let account = <Vault<T> as VaultTrait>::account_id(&vault);
+		T::Currency::mint_into(recode_unwrap_u128(A), &account, amount)?;
+	}: {
+		Vault::<T>::fulfil_redemptions(&vault, &vault_info)
+	}
+	verify {
+		assert!(Vault::<T>::redemptions(vault, u64::from(r) - 1).unwrap().fulfilled);
+	}
 }
 
 impl_benchmark_test_suite!(
@@ -37,6 +37,7 @@
 #![doc = include_str!("../README.md")]
 
 mod capabilities;
+pub mod migration;
 pub mod models;
 mod rent;
 mod traits;
@@ -60,7 +61,10 @@ pub mod pallet {
 	use core::ops::AddAssign;
 
 	use crate::{
-		models::StrategyOverview,
+		models::{
+			Allocation, FeeConfig, Redemption, RedemptionQueue, ScheduledFees, StrategyOverview,
+			VaultFees,
+		},
 		rent::{self, Verdict},
 		traits::{CurrencyFactory, StrategicVault},
 		validation::{ValidateCreationDeposit, ValidateMaxStrategies},
@@ -90,7 +94,9 @@ pub mod pallet {
 		transactional, PalletId,
 	};
 	use frame_system::{
-		ensure_root, ensure_signed, pallet_prelude::OriginFor, Config as SystemConfig,
+		ensure_root, ensure_signed,
+		pallet_prelude::{BlockNumberFor, OriginFor},
+		Config as SystemConfig,
 	};
 	use num_traits::{One, SaturatingSub};
 	use scale_info::TypeInfo;
@@ -99,11 +105,11 @@ pub mod pallet {
 		helpers_128bit::multiply_by_rational_with_rounding,
 		traits::{
 			AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, CheckedSub, Convert,
-			Zero,
+			UniqueSaturatedInto, Zero,
 		},
 		ArithmeticError, DispatchError, FixedPointNumber, Perquintill,
 	};
-	use sp_std::{cmp::Ordering, fmt::Debug};
+	use sp_std::{cmp::Ordering, fmt::Debug, vec::Vec};
 
 	#[allow(missing_docs)]
 	pub type AssetIdOf<T> =
@@ -214,14 +220,45 @@ pub mod pallet {
 		#[pallet::constant]
 		type RentPerBlock: Get<Self::Balance>;
 
+		/// The share of the assets under management a strategy may hold above its allocation
+		/// before rebalancing asks it to liquidate.
+		#[pallet::constant]
+		type RebalanceThreshold: Get<Perquintill>;
+
+		/// The number of blocks in a year, over which the management fee of a vault is charged.
+		#[pallet::constant]
+		type BlocksPerYear: Get<Self::BlockNumber>;
+
+		/// The highest management fee a vault may charge per year.
+		#[pallet::constant]
+		type MaxManagementFee: Get<Perquintill>;
+
+		/// The highest share of the profit a vault may charge as performance fee.
+		#[pallet::constant]
+		type MaxPerformanceFee: Get<Perquintill>;
+
+		/// The number of blocks between the manager setting new fees and the vault charging them,
+		/// giving depositors time to withdraw.
+		#[pallet::constant]
+		type FeeNoticePeriod: Get<Self::BlockNumber>;
+
+		/// The maximum number of redemptions fulfilled by a single call. The rest are fulfilled by
+		/// later calls, or in `on_idle`.
+		#[pallet::constant]
+		type MaxRedemptionsPerCall: Get<u32>;
+
 		/// The id used as the `AccountId` of the vault. This should be unique across all pallets to
 		/// avoid name collisions with other pallets and vaults.
 		#[pallet::constant]
 		type PalletId: Get<PalletId>;
 	}
 
+	/// The current storage version, see [`crate::migration`].
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The number of vaults, also used to generate the next vault identifier.
@@ -263,6 +300,52 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// Last vault rebalanced in `on_idle`, the next rebalance starts after it.
+	#[pallet::storage]
+	pub type RebalanceCursor<T: Config> = StorageValue<_, T::VaultId, OptionQuery>;
+
+	/// Fees charged by each vault, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn fees)]
+	pub type Fees<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::VaultId,
+		VaultFees<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Fees set by the manager of each vault, which are not yet charged.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_fees)]
+	pub type PendingFees<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::VaultId,
+		ScheduledFees<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Queue of the redemptions of each vault, fulfilled in the order they were requested.
+	#[pallet::storage]
+	#[pallet::getter(fn redemption_queue)]
+	#[allow(clippy::disallowed_types)]
+	pub type RedemptionQueues<T: Config> =
+		StorageMap<_, Twox64Concat, T::VaultId, RedemptionQueue<T::Balance>, ValueQuery>;
+
+	/// Redemptions which are not yet claimed, by vault and identifier.
+	#[pallet::storage]
+	#[pallet::getter(fn redemptions)]
+	pub type Redemptions<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::VaultId,
+		Twox64Concat,
+		u64,
+		Redemption<T::AccountId, T::Balance>,
+		OptionQuery,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -303,6 +386,107 @@ pub mod pallet {
 			/// The ID of the vault.
 			vault: T::VaultId,
 		},
+		/// Emitted after rebalancing sent idle funds of the vault to an under-allocated strategy.
+		StrategyFunded {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account of the strategy.
+			strategy: T::AccountId,
+			/// The amount of assets sent to the strategy.
+			amount: T::Balance,
+		},
+		/// Emitted after a strategy was asked to return its funds, either because it is
+		/// over-allocated or to fulfil redemptions.
+		StrategyMustLiquidate {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account of the strategy.
+			strategy: T::AccountId,
+			/// The balance the strategy should return.
+			excess: T::Balance,
+		},
+		/// Emitted after a strategy reported its balance.
+		StrategyReported {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account of the strategy.
+			strategy: T::AccountId,
+			/// The reported balance.
+			balance: T::Balance,
+			/// The increase of the balance since the previous report.
+			profit: T::Balance,
+			/// The decrease of the balance since the previous report.
+			loss: T::Balance,
+		},
+		/// Emitted after a user burned LP tokens to redeem them once the vault holds the assets.
+		RedemptionRequested {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The ID of the redemption.
+			redemption: u64,
+			/// The account requesting the redemption.
+			account: T::AccountId,
+			/// The number of LP tokens burned.
+			lp_amount: T::Balance,
+			/// The value of the LP tokens, owed to the account.
+			asset_amount: T::Balance,
+		},
+		/// Emitted after the vault set aside the assets of a redemption, which can now be claimed.
+		RedemptionFulfilled {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The ID of the redemption.
+			redemption: u64,
+			/// The account which requested the redemption.
+			account: T::AccountId,
+			/// The amount of assets set aside.
+			asset_amount: T::Balance,
+		},
+		/// Emitted after the manager set new fees, which the vault charges from `effective_at`.
+		FeesScheduled {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The new fees, or `None` if the vault stops charging fees.
+			fees: Option<FeeConfig<T::AccountId>>,
+			/// The block from which the new fees apply.
+			effective_at: T::BlockNumber,
+		},
+		/// Emitted after the fees set by the manager started to apply.
+		FeesUpdated {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The new fees, or `None` if the vault no longer charges fees.
+			fees: Option<FeeConfig<T::AccountId>>,
+		},
+		/// Emitted after the management fee accrued since the previous charge was minted.
+		ManagementFeeCharged {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account receiving the fee.
+			recipient: T::AccountId,
+			/// The number of LP tokens minted.
+			lp_amount: T::Balance,
+		},
+		/// Emitted after the performance fee on profit above the high-water mark was minted.
+		PerformanceFeeCharged {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The account receiving the fee.
+			recipient: T::AccountId,
+			/// The number of LP tokens minted.
+			lp_amount: T::Balance,
+		},
+		/// Emitted after a user claimed the assets of a fulfilled redemption.
+		RedemptionClaimed {
+			/// The ID of the vault.
+			vault: T::VaultId,
+			/// The ID of the redemption.
+			redemption: u64,
+			/// The account claiming the redemption.
+			account: T::AccountId,
+			/// The amount of assets transferred to the account.
+			asset_amount: T::Balance,
+		},
 	}
 
 	#[allow(missing_docs)]
@@ -364,6 +548,22 @@ pub mod pallet {
 		TombstoneDurationNotExceeded,
 		/// Existentially funded vaults do not require extra funds.
 		InvalidAddSurcharge,
+		/// Querying/operating on invalid redemption id's result in `RedemptionDoesNotExist`.
+		RedemptionDoesNotExist,
+		/// Only the account which requested a redemption may claim it.
+		NotRedemptionOwner,
+		/// The vault does not yet hold the assets of the redemption.
+		RedemptionNotFulfilled,
+		/// Fees may not exceed [`MaxManagementFee`](Config::MaxManagementFee) and
+		/// [`MaxPerformanceFee`](Config::MaxPerformanceFee).
+		FeeTooHigh,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::rebalance_vaults(remaining_weight)
+		}
 	}
 
 	#[pallet::call]
@@ -636,6 +836,120 @@ pub mod pallet {
 			});
 			Ok(())
 		}
+
+		/// Moves the strategies of the vault towards their allocations. Idle funds above the
+		/// reserve are sent to under-allocated strategies, and strategies holding more than
+		/// their allocation by [`RebalanceThreshold`](Config::RebalanceThreshold) are asked to
+		/// return their funds through [`FundsAvailability::MustLiquidate`].
+		///
+		/// Vaults are also rebalanced in `on_idle`. Only the vault's manager will be able to call
+		/// this method.
+		///
+		/// # Emits
+		///  - Event::StrategyFunded
+		///  - Event::StrategyMustLiquidate
+		#[pallet::weight(Pallet::<T>::rebalance_weight())]
+		pub fn rebalance(origin: OriginFor<T>, vault: T::VaultId) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			ensure!(Self::vault_info(&vault)?.manager == from, Error::<T>::AccountIsNotManager);
+			Self::do_rebalance(&vault)
+		}
+
+		/// Burns LP tokens in exchange for their current value, paid out once the vault holds
+		/// enough assets. Strategies are asked to return the missing funds through
+		/// [`FundsAvailability::MustLiquidate`], and redemptions are fulfilled in the order they
+		/// were requested as the funds return.
+		///
+		/// Should be used when `withdraw` fails with `NotEnoughLiquidity`.
+		///
+		/// # Emits
+		///  - Event::RedemptionRequested
+		///  - Event::RedemptionFulfilled
+		///  - Event::StrategyMustLiquidate
+		///
+		/// # Errors
+		///  - When the origin is not signed.
+		///  - When `lp_amount < MinimumWithdrawal`.
+		///  - When the origin has insufficient LP tokens.
+		#[transactional]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::request_redemption(T::MaxStrategies::get() as u32)
+				.saturating_add(Pallet::<T>::fulfil_redemptions_weight())
+		)]
+		pub fn request_redemption(
+			origin: OriginFor<T>,
+			vault: T::VaultId,
+			lp_amount: T::Balance,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			ensure!(
+				lp_amount > T::MinimumWithdrawal::get(),
+				Error::<T>::AmountMustGteMinimumWithdrawal
+			);
+			Self::do_request_redemption(&vault, &from, lp_amount)?;
+			Ok(())
+		}
+
+		/// Transfers the assets of a fulfilled redemption to the account which requested it. The
+		/// assets are set aside once fulfilled, so they can be claimed even if withdrawals are
+		/// stopped.
+		///
+		/// # Emits
+		///  - Event::RedemptionClaimed
+		///
+		/// # Errors
+		///  - When the origin did not request the redemption.
+		///  - When the redemption is not yet fulfilled.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_redemption())]
+		pub fn claim_redemption(
+			origin: OriginFor<T>,
+			vault: T::VaultId,
+			redemption: u64,
+		) -> DispatchResult {
+			let to = ensure_signed(origin)?;
+			Self::do_claim_redemption(&vault, &to, redemption)
+		}
+
+		/// Sets the fees charged by the vault, or stops charging fees if `fees` is `None`. The new
+		/// fees apply after [`FeeNoticePeriod`](Config::FeeNoticePeriod) blocks, replacing any
+		/// fees set before which do not apply yet. Fees accrued under the previous configuration
+		/// are charged first.
+		///
+		/// Only the vault's manager will be able to call this method.
+		///
+		/// # Emits
+		///  - Event::FeesScheduled
+		///  - Event::ManagementFeeCharged
+		///
+		/// # Errors
+		///  - When the origin is not the manager of the vault.
+		///  - When the fees exceed [`MaxManagementFee`](Config::MaxManagementFee) or
+		///    [`MaxPerformanceFee`](Config::MaxPerformanceFee).
+		#[transactional]
+		#[pallet::weight(<T as Config>::WeightInfo::set_fees())]
+		pub fn set_fees(
+			origin: OriginFor<T>,
+			vault: T::VaultId,
+			fees: Option<FeeConfig<T::AccountId>>,
+		) -> DispatchResult {
+			let from = ensure_signed(origin)?;
+			let vault_info = Self::vault_info(&vault)?;
+			ensure!(vault_info.manager == from, Error::<T>::AccountIsNotManager);
+			if let Some(config) = &fees {
+				ensure!(
+					config.management <= T::MaxManagementFee::get() &&
+						config.performance <= T::MaxPerformanceFee::get(),
+					Error::<T>::FeeTooHigh
+				);
+			}
+			// applies fees which are already due, instead of replacing them
+			Self::accrue_management_fee(&vault, &vault_info)?;
+			let effective_at =
+				<frame_system::Pallet<T>>::block_number().saturating_add(T::FeeNoticePeriod::get());
+			PendingFees::<T>::insert(vault, ScheduledFees { fees: fees.clone(), effective_at });
+			Self::deposit_event(Event::FeesScheduled { vault, fees, effective_at });
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -687,6 +1001,7 @@ pub mod pallet {
 							balance: T::Balance::zero(),
 							lifetime_withdrawn: T::Balance::zero(),
 							lifetime_deposited: T::Balance::zero(),
+							must_liquidate: false,
 						},
 					);
 				});
@@ -733,11 +1048,11 @@ pub mod pallet {
 			let vault = Self::vault_info(vault_id)?;
 
 			ensure!(vault.capabilities.withdrawals_allowed(), Error::<T>::WithdrawalsHalted);
+			Self::accrue_management_fee(vault_id, &vault)?;
 
 			let lp_shares_value_amount = Self::do_lp_share_value(vault_id, &vault, lp_amount)?;
 
-			let vault_owned_amount =
-				T::Currency::balance(vault.asset_id, &Self::account_id(vault_id));
+			let vault_owned_amount = Self::free_balance(vault_id, &vault);
 
 			// TODO(hussein-aitlahcen): should we provide what we can to reduce the available
 			// liquidity in order to force strategies to rebalance?
@@ -780,6 +1095,7 @@ pub mod pallet {
 
 			let to = Self::account_id(vault_id);
 
+			Self::accrue_management_fee(vault_id, &vault)?;
 			let lp = Self::do_calculate_lp_tokens_to_mint(vault_id, &vault, amount)?;
 
 			T::Currency::transfer(vault.asset_id, from, &to, amount, true)
@@ -877,7 +1193,403 @@ pub mod pallet {
 			let owned = T::Currency::balance(vault.asset_id, &Self::account_id(vault_id));
 			let outstanding = CapitalStructure::<T>::iter_prefix_values(vault_id)
 				.fold(T::Balance::zero(), |sum, item| sum + item.balance);
-			Ok(owned + outstanding)
+			// assets owed to redemptions are no longer backing the LP tokens
+			Ok((owned + outstanding).saturating_sub(&RedemptionQueues::<T>::get(vault_id).owed))
+		}
+
+		/// The assets held by the vault which are not owed to redemptions.
+		fn free_balance(vault_id: &T::VaultId, vault: &VaultInfo<T>) -> T::Balance {
+			T::Currency::balance(vault.asset_id, &Self::account_id(vault_id))
+				.saturating_sub(&RedemptionQueues::<T>::get(vault_id).owed)
+		}
+
+		/// Target and actual balance of each strategy of the vault.
+		pub fn strategy_allocations(
+			vault_id: &T::VaultId,
+		) -> Result<Vec<(T::AccountId, Allocation<T::Balance>)>, DispatchError> {
+			let aum = Self::assets_under_management(vault_id)?;
+			Ok(CapitalStructure::<T>::iter_prefix(vault_id)
+				.map(|(strategy, overview)| {
+					let target = Self::share_of(overview.allocation, aum);
+					(strategy, Allocation { target, actual: overview.balance })
+				})
+				.collect())
+		}
+
+		#[transactional]
+		fn do_rebalance(vault_id: &T::VaultId) -> DispatchResult {
+			let vault = Self::vault_info(vault_id)?;
+			// strategies of stopped and tombstoned vaults are already asked to liquidate
+			if vault.capabilities.is_stopped() || vault.capabilities.is_tombstoned() {
+				Self::fulfil_redemptions(vault_id, &vault);
+				return Ok(())
+			}
+			Self::accrue_management_fee(vault_id, &vault)?;
+			let allocations = Self::strategy_allocations(vault_id)?;
+			let aum = Self::do_assets_under_management(vault_id, &vault)?;
+			let threshold = Self::share_of(T::RebalanceThreshold::get(), aum);
+			let reserve = allocations
+				.iter()
+				.fold(aum, |reserve, (_, allocation)| reserve.saturating_sub(&allocation.target));
+			let mut idle = Self::free_balance(vault_id, &vault).saturating_sub(&reserve);
+
+			for (strategy, Allocation { target, actual }) in allocations {
+				let must_liquidate =
+					target.checked_add(&threshold).map_or(false, |limit| actual > limit);
+				CapitalStructure::<T>::mutate(vault_id, &strategy, |state| {
+					state.must_liquidate = must_liquidate
+				});
+				if must_liquidate {
+					Self::deposit_event(Event::StrategyMustLiquidate {
+						vault: *vault_id,
+						strategy,
+						excess: actual - target,
+					});
+				} else if actual < target && !idle.is_zero() {
+					let amount = (target - actual).min(idle);
+					<Self as StrategicVault>::withdraw(vault_id, &strategy, amount)?;
+					idle -= amount;
+					Self::deposit_event(Event::StrategyFunded {
+						vault: *vault_id,
+						strategy,
+						amount,
+					});
+				}
+			}
+			Self::fulfil_redemptions(vault_id, &vault);
+			Self::signal_redemption_shortfall(vault_id, &vault);
+			Ok(())
+		}
+
+		fn do_request_redemption(
+			vault_id: &T::VaultId,
+			from: &T::AccountId,
+			lp_amount: T::Balance,
+		) -> Result<u64, DispatchError> {
+			let vault = Self::vault_info(vault_id)?;
+			ensure!(vault.capabilities.withdrawals_allowed(), Error::<T>::WithdrawalsHalted);
+			ensure!(
+				T::Currency::can_withdraw(vault.lp_token_id, from, lp_amount)
+					.into_result()
+					.is_ok(),
+				Error::<T>::InsufficientLpTokens
+			);
+
+			Self::accrue_management_fee(vault_id, &vault)?;
+			let asset_amount = Self::do_lp_share_value(vault_id, &vault, lp_amount)?;
+			T::Currency::burn_from(vault.lp_token_id, from, lp_amount)
+				.map_err(|_| Error::<T>::InsufficientLpTokens)?;
+			let redemption = RedemptionQueues::<T>::try_mutate(
+				vault_id,
+				|queue| -> Result<u64, DispatchError> {
+					queue.owed =
+						queue.owed.checked_add(&asset_amount).ok_or(ArithmeticError::Overflow)?;
+					let id = queue.next;
+					queue.next = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+					Ok(id)
+				},
+			)?;
+			Redemptions::<T>::insert(
+				vault_id,
+				redemption,
+				Redemption { account: from.clone(), amount: asset_amount, fulfilled: false },
+			);
+			Self::deposit_event(Event::RedemptionRequested {
+				vault: *vault_id,
+				redemption,
+				account: from.clone(),
+				lp_amount,
+				asset_amount,
+			});
+
+			Self::fulfil_redemptions(vault_id, &vault);
+			Self::signal_redemption_shortfall(vault_id, &vault);
+			Ok(redemption)
+		}
+
+		fn do_claim_redemption(
+			vault_id: &T::VaultId,
+			to: &T::AccountId,
+			redemption_id: u64,
+		) -> DispatchResult {
+			let vault = Self::vault_info(vault_id)?;
+			let redemption = Redemptions::<T>::get(vault_id, redemption_id)
+				.ok_or(Error::<T>::RedemptionDoesNotExist)?;
+			ensure!(redemption.account == *to, Error::<T>::NotRedemptionOwner);
+			ensure!(redemption.fulfilled, Error::<T>::RedemptionNotFulfilled);
+
+			T::Currency::transfer(
+				vault.asset_id,
+				&Self::account_id(vault_id),
+				to,
+				redemption.amount,
+				true,
+			)
+			.map_err(|_| Error::<T>::TransferFromFailed)?;
+			RedemptionQueues::<T>::mutate(vault_id, |queue| {
+				queue.owed = queue.owed.saturating_sub(&redemption.amount);
+				queue.fulfilled = queue.fulfilled.saturating_sub(&redemption.amount);
+			});
+			Redemptions::<T>::remove(vault_id, redemption_id);
+			Self::deposit_event(Event::RedemptionClaimed {
+				vault: *vault_id,
+				redemption: redemption_id,
+				account: redemption.account,
+				asset_amount: redemption.amount,
+			});
+			Ok(())
+		}
+
+		/// Sets aside the assets of the oldest redemptions, for as long as the vault holds enough
+		/// assets which are not yet set aside, and at most `MaxRedemptionsPerCall` of them.
+		pub(crate) fn fulfil_redemptions(vault_id: &T::VaultId, vault: &VaultInfo<T>) {
+			let owned = T::Currency::balance(vault.asset_id, &Self::account_id(vault_id));
+			RedemptionQueues::<T>::mutate(vault_id, |queue| {
+				let end = queue
+					.next
+					.min(queue.head.saturating_add(u64::from(T::MaxRedemptionsPerCall::get())));
+				while queue.head < end {
+					let available = owned.saturating_sub(&queue.fulfilled);
+					let redemption = match Redemptions::<T>::get(vault_id, queue.head) {
+						Some(redemption) if redemption.amount <= available => redemption,
+						Some(_) => break,
+						// only fulfilled redemptions can be claimed, which are behind the head
+						None => {
+							queue.head += 1;
+							continue
+						},
+					};
+					queue.fulfilled += redemption.amount;
+					Redemptions::<T>::insert(
+						vault_id,
+						queue.head,
+						Redemption { fulfilled: true, ..redemption.clone() },
+					);
+					Self::deposit_event(Event::RedemptionFulfilled {
+						vault: *vault_id,
+						redemption: queue.head,
+						account: redemption.account,
+						asset_amount: redemption.amount,
+					});
+					queue.head += 1;
+				}
+			});
+		}
+
+		/// Asks strategies to return their funds until they cover the assets owed to redemptions
+		/// which the vault does not hold.
+		fn signal_redemption_shortfall(vault_id: &T::VaultId, vault: &VaultInfo<T>) {
+			let owned = T::Currency::balance(vault.asset_id, &Self::account_id(vault_id));
+			let mut shortfall = RedemptionQueues::<T>::get(vault_id).owed.saturating_sub(&owned);
+			if shortfall.is_zero() {
+				return
+			}
+			let (liquidating, remaining): (Vec<_>, Vec<_>) =
+				CapitalStructure::<T>::iter_prefix(vault_id)
+					.partition(|(_, overview)| overview.must_liquidate);
+			for (_, overview) in liquidating {
+				shortfall = shortfall.saturating_sub(&overview.balance);
+			}
+			for (strategy, mut overview) in remaining {
+				if shortfall.is_zero() {
+					break
+				}
+				if overview.balance.is_zero() {
+					continue
+				}
+				shortfall = shortfall.saturating_sub(&overview.balance);
+				overview.must_liquidate = true;
+				CapitalStructure::<T>::insert(vault_id, &strategy, overview);
+				Self::deposit_event(Event::StrategyMustLiquidate {
+					vault: *vault_id,
+					strategy,
+					excess: overview.balance,
+				});
+			}
+		}
+
+		/// Rebalances as many vaults as `remaining_weight` allows, continuing from where the
+		/// previous block stopped.
+		pub(crate) fn rebalance_vaults(remaining_weight: Weight) -> Weight {
+			let per_vault = Self::rebalance_weight();
+			// reading and updating the cursor
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			let budget =
+				remaining_weight.saturating_sub(weight).ref_time() / per_vault.ref_time().max(1);
+			if budget.is_zero() {
+				return Weight::from_ref_time(0)
+			}
+
+			let vaults = match RebalanceCursor::<T>::get() {
+				Some(vault_id) =>
+					Vaults::<T>::iter_keys_from(Vaults::<T>::hashed_key_for(vault_id)),
+				None => Vaults::<T>::iter_keys(),
+			}
+			.take(budget as usize)
+			.collect::<Vec<_>>();
+			match vaults.last() {
+				Some(vault_id) if vaults.len() as u64 == budget =>
+					RebalanceCursor::<T>::put(vault_id),
+				// the last vault was reached, start over from the first one
+				_ => RebalanceCursor::<T>::kill(),
+			}
+
+			for vault_id in vaults {
+				weight = weight.saturating_add(per_vault);
+				if let Err(err) = Self::do_rebalance(&vault_id) {
+					log::error!("failed to rebalance vault {:?} with {:?}", vault_id, err);
+				}
+			}
+			weight
+		}
+
+		/// Weight of fulfilling as many redemptions as a single call may.
+		pub(crate) fn fulfil_redemptions_weight() -> Weight {
+			T::WeightInfo::fulfil_redemptions(T::MaxRedemptionsPerCall::get())
+		}
+
+		/// Weight of rebalancing a vault, including the redemptions it fulfils.
+		pub(crate) fn rebalance_weight() -> Weight {
+			T::WeightInfo::rebalance(T::MaxStrategies::get() as u32)
+				.saturating_add(Self::fulfil_redemptions_weight())
+		}
+
+		/// Charges the management fee accrued since it was last charged, switching to the fees set
+		/// by the manager once their notice period ended.
+		fn accrue_management_fee(vault_id: &T::VaultId, vault: &VaultInfo<T>) -> DispatchResult {
+			let now = <frame_system::Pallet<T>>::block_number();
+			if let Some(pending) = PendingFees::<T>::get(vault_id) {
+				if pending.effective_at <= now {
+					// fees accrued under the previous configuration are charged first
+					Self::charge_management_fee(vault_id, vault, pending.effective_at)?;
+					Self::apply_fees(vault_id, pending)?;
+				}
+			}
+			Self::charge_management_fee(vault_id, vault, now)
+		}
+
+		/// Replaces the fees charged by the vault with the fees set by the manager.
+		fn apply_fees(
+			vault_id: &T::VaultId,
+			pending: ScheduledFees<T::AccountId, T::BlockNumber>,
+		) -> DispatchResult {
+			PendingFees::<T>::remove(vault_id);
+			match pending.fees.clone() {
+				Some(config) => {
+					// keep the high-water mark, so that changing the fees does not charge the
+					// performance fee twice on the same profit
+					let high_water_mark = match Fees::<T>::get(vault_id) {
+						Some(fees) => fees.high_water_mark,
+						None => <Self as Vault>::stock_dilution_rate(vault_id)?,
+					};
+					Fees::<T>::insert(
+						vault_id,
+						VaultFees { config, accrued_at: pending.effective_at, high_water_mark },
+					);
+				},
+				None => Fees::<T>::remove(vault_id),
+			}
+			Self::deposit_event(Event::FeesUpdated { vault: *vault_id, fees: pending.fees });
+			Ok(())
+		}
+
+		/// Charges the management fee accrued from when it was last charged until `until`.
+		fn charge_management_fee(
+			vault_id: &T::VaultId,
+			vault: &VaultInfo<T>,
+			until: T::BlockNumber,
+		) -> DispatchResult {
+			let mut fees = match Fees::<T>::get(vault_id) {
+				Some(fees) => fees,
+				None => return Ok(()),
+			};
+			let elapsed: u128 = until.saturating_sub(fees.accrued_at).unique_saturated_into();
+			if elapsed.is_zero() {
+				return Ok(())
+			}
+			fees.accrued_at = until;
+
+			let aum = Self::do_assets_under_management(vault_id, vault)?;
+			let blocks_per_year: u128 = T::BlocksPerYear::get().unique_saturated_into();
+			let accrued = multiply_by_rational_with_rounding(
+				<T::Convert as Convert<T::Balance, u128>>::convert(aum),
+				elapsed,
+				blocks_per_year.max(1),
+				Rounding::Down,
+			)
+			.ok_or(ArithmeticError::Overflow)?;
+			let fee = Self::share_of(
+				fees.config.management,
+				<T::Convert as Convert<u128, T::Balance>>::convert(accrued).min(aum),
+			);
+			let lp_amount = Self::mint_fee(vault, &fees.config.recipient, fee, aum)?;
+			if !lp_amount.is_zero() {
+				Self::deposit_event(Event::ManagementFeeCharged {
+					vault: *vault_id,
+					recipient: fees.config.recipient.clone(),
+					lp_amount,
+				});
+			}
+			Fees::<T>::insert(vault_id, fees);
+			Ok(())
+		}
+
+		/// Charges the performance fee on the increase of the value of LP tokens above the
+		/// high-water mark.
+		fn charge_performance_fee(vault_id: &T::VaultId, vault: &VaultInfo<T>) -> DispatchResult {
+			let mut fees = match Fees::<T>::get(vault_id) {
+				Some(fees) => fees,
+				None => return Ok(()),
+			};
+			let value = <Self as Vault>::stock_dilution_rate(vault_id)?;
+			if value <= fees.high_water_mark {
+				return Ok(())
+			}
+
+			let aum = Self::do_assets_under_management(vault_id, vault)?;
+			let issuance = T::Currency::total_issuance(vault.lp_token_id);
+			let profit = (value - fees.high_water_mark)
+				.saturating_mul_int(<T::Convert as Convert<T::Balance, u128>>::convert(issuance));
+			let fee = Self::share_of(
+				fees.config.performance,
+				<T::Convert as Convert<u128, T::Balance>>::convert(profit),
+			);
+			let lp_amount = Self::mint_fee(vault, &fees.config.recipient, fee, aum)?;
+			if !lp_amount.is_zero() {
+				Self::deposit_event(Event::PerformanceFeeCharged {
+					vault: *vault_id,
+					recipient: fees.config.recipient.clone(),
+					lp_amount,
+				});
+			}
+			fees.high_water_mark = <Self as Vault>::stock_dilution_rate(vault_id)?;
+			Fees::<T>::insert(vault_id, fees);
+			Ok(())
+		}
+
+		/// Mints LP tokens worth `fee` of the `aum` to `recipient`, diluting the other holders.
+		fn mint_fee(
+			vault: &VaultInfo<T>,
+			recipient: &T::AccountId,
+			fee: T::Balance,
+			aum: T::Balance,
+		) -> Result<T::Balance, DispatchError> {
+			let issuance = T::Currency::total_issuance(vault.lp_token_id);
+			if fee.is_zero() || issuance.is_zero() || fee >= aum {
+				return Ok(T::Balance::zero())
+			}
+			// fee / aum = lp / (issuance + lp)
+			let lp = Self::convert_and_multiply_by_rational(fee, issuance, aum - fee)?;
+			T::Currency::mint_into(vault.lp_token_id, recipient, lp)
+				.map_err(|_| Error::<T>::MintFailed)?;
+			Ok(lp)
+		}
+
+		/// `share` of `amount`, rounded down.
+		fn share_of(share: Perquintill, amount: T::Balance) -> T::Balance {
+			<T::Convert as Convert<u128, T::Balance>>::convert(
+				share.mul_floor(<T::Convert as Convert<T::Balance, u128>>::convert(amount)),
+			)
 		}
 
 		/// Tries to fetch a stored [VaultInfo] through its index.
@@ -1004,19 +1716,23 @@ pub mod pallet {
 				Vaults::<T>::try_get(vault_id),
 				CapitalStructure::<T>::try_get(vault_id, account),
 			) {
-				(Ok(vault), Ok(StrategyOverview { allocation, balance, .. }))
-					if !vault.capabilities.is_stopped() && !vault.capabilities.is_tombstoned() =>
-				{
+				(
+					Ok(vault),
+					Ok(StrategyOverview { allocation, balance, must_liquidate: false, .. }),
+				) if !vault.capabilities.is_stopped() && !vault.capabilities.is_tombstoned() => {
 					let aum = Self::assets_under_management(vault_id)?;
-					let max_allowed = <T::Convert as Convert<u128, T::Balance>>::convert(
-						allocation
-							.mul_floor(<T::Convert as Convert<T::Balance, u128>>::convert(aum)),
-					);
+					let max_allowed = Self::share_of(allocation, aum);
 					match balance.cmp(&max_allowed) {
 						Ordering::Greater =>
 							Ok(FundsAvailability::Depositable(balance - max_allowed)),
-						Ordering::Less =>
-							Ok(FundsAvailability::Withdrawable(max_allowed - balance)),
+						Ordering::Less => {
+							// assets owed to redemptions are paid out before funding strategies
+							let free = Self::free_balance(vault_id, &vault);
+							match (max_allowed - balance).min(free) {
+								amount if amount.is_zero() => Ok(FundsAvailability::None),
+								amount => Ok(FundsAvailability::Withdrawable(amount)),
+							}
+						},
 						Ordering::Equal => Ok(FundsAvailability::None),
 					}
 				},
@@ -1061,9 +1777,12 @@ pub mod pallet {
 			amount: Self::Balance,
 		) -> Result<(), DispatchError> {
 			let vault = Self::vault_info(vault_id)?;
-			CapitalStructure::<T>::try_mutate(vault_id, from, |state| {
+			CapitalStructure::<T>::try_mutate(vault_id, from, |state| -> DispatchResult {
 				// A strategy can return more than it has withdrawn through profits.
 				state.balance = state.balance.saturating_sub(&amount);
+				if state.balance.is_zero() {
+					state.must_liquidate = false;
+				}
 				// This can definitely overflow. Perhaps it should be a BigUint?
 				state.lifetime_deposited = state
 					.lifetime_deposited
@@ -1078,7 +1797,9 @@ pub mod pallet {
 				)
 				.map_err(|_| Error::<T>::InsufficientFunds)?;
 				Ok(())
-			})
+			})?;
+			Self::fulfil_redemptions(vault_id, &vault);
+			Ok(())
 		}
 	}
 
@@ -1090,8 +1811,20 @@ pub mod pallet {
 			strategy: &Self::AccountId,
 			report: &Self::Report,
 		) -> Result<(), DispatchError> {
-			CapitalStructure::<T>::mutate(vault, strategy, |state| state.balance = *report);
-			Ok(())
+			let vault_info = Self::vault_info(vault)?;
+			// the management fee is charged on the assets before the report
+			Self::accrue_management_fee(vault, &vault_info)?;
+			let previous = CapitalStructure::<T>::mutate(vault, strategy, |state| {
+				sp_std::mem::replace(&mut state.balance, *report)
+			});
+			Self::deposit_event(Event::StrategyReported {
+				vault: *vault,
+				strategy: strategy.clone(),
+				balance: *report,
+				profit: report.saturating_sub(&previous),
+				loss: previous.saturating_sub(report),
+			});
+			Self::charge_performance_fee(vault, &vault_info)
 		}
 	}
 
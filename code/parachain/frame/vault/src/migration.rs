@@ -0,0 +1,47 @@
+//! Storage migrations of the vault pallet.
+
+use crate::{models::StrategyOverview, *};
+use frame_support::{pallet_prelude::*, traits::OnRuntimeUpgrade};
+use sp_runtime::Perquintill;
+use sp_std::marker::PhantomData;
+
+pub mod v1 {
+	use super::*;
+
+	/// [`StrategyOverview`] before rebalancing.
+	#[derive(Encode, Decode)]
+	struct OldStrategyOverview<Balance> {
+		allocation: Perquintill,
+		balance: Balance,
+		lifetime_withdrawn: Balance,
+		lifetime_deposited: Balance,
+	}
+
+	/// Adds the liquidation flag to the overview of existing strategies, which the next rebalance
+	/// sets for over-allocated ones.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() >= 1 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut strategies = 0_u64;
+			CapitalStructure::<T>::translate::<OldStrategyOverview<T::Balance>, _>(|_, _, old| {
+				strategies += 1;
+				Some(StrategyOverview {
+					allocation: old.allocation,
+					balance: old.balance,
+					lifetime_withdrawn: old.lifetime_withdrawn,
+					lifetime_deposited: old.lifetime_deposited,
+					must_liquidate: false,
+				})
+			});
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get()
+				.reads_writes(strategies.saturating_add(1), strategies.saturating_add(1))
+		}
+	}
+}
@@ -12,6 +12,7 @@ use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{ConvertInto, IdentityLookup},
+	Perquintill,
 };
 
 pub type BlockNumber = u64;
@@ -88,6 +89,12 @@ parameter_types! {
 	pub const MinimumDeposit: Balance = 0;
 	pub const MinimumWithdrawal: Balance = 0;
 	pub const TombstoneDuration: BlockNumber = 10;
+	pub const RebalanceThreshold: Perquintill = Perquintill::from_percent(5);
+	pub const BlocksPerYear: BlockNumber = 1000;
+	pub const MaxManagementFee: Perquintill = Perquintill::from_percent(10);
+	pub const MaxPerformanceFee: Perquintill = Perquintill::from_percent(50);
+	pub const FeeNoticePeriod: BlockNumber = 10;
+	pub const MaxRedemptionsPerCall: u32 = 2;
 }
 
 impl pallet_vault::Config for Test {
@@ -102,6 +109,12 @@ impl pallet_vault::Config for Test {
 	type CreationDeposit = CreationDeposit;
 	type ExistentialDeposit = ExistentialDeposit;
 	type RentPerBlock = RentPerBlock;
+	type RebalanceThreshold = RebalanceThreshold;
+	type BlocksPerYear = BlocksPerYear;
+	type MaxManagementFee = MaxManagementFee;
+	type MaxPerformanceFee = MaxPerformanceFee;
+	type FeeNoticePeriod = FeeNoticePeriod;
+	type MaxRedemptionsPerCall = MaxRedemptionsPerCall;
 	type NativeCurrency = Balances;
 	type MinimumDeposit = MinimumDeposit;
 	type MinimumWithdrawal = MinimumWithdrawal;
@@ -1,5 +1,5 @@
 use crate::Capabilities;
-use composable_traits::vault::Deposit;
+use composable_traits::{defi::Rate, vault::Deposit};
 use frame_support::pallet_prelude::*;
 use scale_info::TypeInfo;
 use sp_runtime::Perquintill;
@@ -23,4 +23,70 @@ pub struct StrategyOverview<Balance> {
 	pub lifetime_withdrawn: Balance,
 	/// Sum of all deposited funds.
 	pub lifetime_deposited: Balance,
+	/// Set when rebalancing found the strategy over-allocated, until it returns its funds.
+	pub must_liquidate: bool,
+}
+
+/// Target and actual balance of a strategy, as computed from its allocation.
+#[derive(Copy, Clone, Encode, Decode, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct Allocation<Balance> {
+	/// The share of the assets under management the strategy is allocated.
+	pub target: Balance,
+	/// The reported balance of the strategy.
+	pub actual: Balance,
+}
+
+/// Redemptions waiting for, or holding, the assets of a vault.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct RedemptionQueue<Balance> {
+	/// The identifier of the next requested redemption.
+	pub next: u64,
+	/// The identifier of the oldest redemption which is not yet fulfilled.
+	pub head: u64,
+	/// The assets owed to all unclaimed redemptions.
+	pub owed: Balance,
+	/// The assets set aside for fulfilled, but unclaimed, redemptions.
+	pub fulfilled: Balance,
+}
+
+/// A request to redeem LP tokens for assets which are not yet held by the vault.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct Redemption<AccountId, Balance> {
+	/// The account which requested the redemption.
+	pub account: AccountId,
+	/// The value of the burned LP tokens at the time of the request.
+	pub amount: Balance,
+	/// Set once the vault holds the assets, after which they can be claimed.
+	pub fulfilled: bool,
+}
+
+/// Fees charged by a vault, paid by minting LP tokens to the recipient.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct FeeConfig<AccountId> {
+	/// The account receiving the minted LP tokens.
+	pub recipient: AccountId,
+	/// The share of the assets under management charged per year.
+	pub management: Perquintill,
+	/// The share of the profit above the high-water mark charged when strategies report.
+	pub performance: Perquintill,
+}
+
+/// Fees currently charged by a vault, and the state needed to charge them.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct VaultFees<AccountId, BlockNumber> {
+	/// The fees charged by the vault.
+	pub config: FeeConfig<AccountId>,
+	/// The block up to which the management fee has been charged.
+	pub accrued_at: BlockNumber,
+	/// The highest value of an LP token on which the performance fee has been charged.
+	pub high_water_mark: Rate,
+}
+
+/// Fees set by the manager, which the vault starts charging once the notice period ended.
+#[derive(Copy, Clone, Encode, Decode, MaxEncodedLen, Default, Debug, PartialEq, Eq, TypeInfo)]
+pub struct ScheduledFees<AccountId, BlockNumber> {
+	/// The new fees, or `None` if the vault stops charging fees.
+	pub fees: Option<FeeConfig<AccountId>>,
+	/// The block from which the new fees apply.
+	pub effective_at: BlockNumber,
 }
@@ -2,12 +2,13 @@ use crate::{
 	mocks::{
 		currency_factory::MockCurrencyId,
 		tests::{
-			AccountId, Balance, Balances, BlockNumber, CreationDeposit, ExistentialDeposit,
-			ExtBuilder, RuntimeEvent, RuntimeOrigin, System, Test, Tokens, TombstoneDuration,
-			Vaults, ACCOUNT_FREE_START, ALICE, BOB, CHARLIE, MINIMUM_BALANCE,
+			AccountId, Balance, Balances, BlockNumber, BlocksPerYear, CreationDeposit,
+			ExistentialDeposit, ExtBuilder, FeeNoticePeriod, MaxRedemptionsPerCall, RuntimeEvent,
+			RuntimeOrigin, System, Test, Tokens, TombstoneDuration, Vaults, ACCOUNT_FREE_START,
+			ALICE, BOB, CHARLIE, MINIMUM_BALANCE,
 		},
 	},
-	models::VaultInfo,
+	models::{Allocation, FeeConfig, VaultInfo},
 	*,
 };
 use composable_support::{math::safe::safe_multiply_by_rational, validation::Validated};
@@ -23,7 +24,11 @@ use composable_traits::{
 };
 use frame_support::{
 	assert_noop, assert_ok,
-	traits::fungibles::{Inspect, Mutate},
+	traits::{
+		fungibles::{Inspect, Mutate},
+		Get, Hooks,
+	},
+	weights::Weight,
 };
 use proptest::prelude::*;
 use sp_runtime::{ArithmeticError, FixedPointNumber, Perbill, Perquintill};
@@ -981,3 +986,432 @@ fn do_liquidate_strategy_successfully_liquidates_a_strategy_account(
 		assert_eq!(Tokens::balance(currency_id, &strategy_account_id), 0);
 	});
 }
+
+fn create_vault_with_strategies(
+	strategies: &[(AccountId, Perquintill)],
+	reserved: Perquintill,
+) -> u64 {
+	let config = VaultConfig {
+		asset_id: MockCurrencyId::A,
+		manager: ALICE,
+		reserved,
+		strategies: strategies.iter().cloned().collect(),
+	};
+	let (id, _) = Vaults::do_create_vault(Deposit::Existential, Validated::new(config).unwrap())
+		.expect("creating vault should succeed");
+	id
+}
+
+#[test]
+fn rebalance_sends_idle_funds_to_strategies() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50)), (CHARLIE, Perquintill::from_percent(30))],
+			Perquintill::from_percent(20),
+		);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &BOB), 500);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &CHARLIE), 300);
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 200);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyFunded {
+			vault: id,
+			strategy: BOB,
+			amount: 500,
+		}));
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyFunded {
+			vault: id,
+			strategy: CHARLIE,
+			amount: 300,
+		}));
+
+		let mut allocations = Vaults::strategy_allocations(&id).unwrap();
+		allocations.sort_by_key(|(strategy, _)| *strategy);
+		assert_eq!(
+			allocations,
+			vec![
+				(BOB, Allocation { target: 500, actual: 500 }),
+				(CHARLIE, Allocation { target: 300, actual: 300 }),
+			]
+		);
+	});
+}
+
+#[test]
+fn rebalance_asks_over_allocated_strategy_to_liquidate() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50)), (CHARLIE, Perquintill::from_percent(30))],
+			Perquintill::from_percent(20),
+		);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+
+		// BOB made a profit of 300, bringing the assets under management to 1300, of which BOB
+		// should only hold 650 plus the threshold.
+		assert_ok!(<Vaults as ReportableStrategicVault>::update_strategy_report(&id, &BOB, &800));
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyReported {
+			vault: id,
+			strategy: BOB,
+			balance: 800,
+			profit: 300,
+			loss: 0,
+		}));
+
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyMustLiquidate {
+			vault: id,
+			strategy: BOB,
+			excess: 150,
+		}));
+		assert_eq!(
+			<Vaults as StrategicVault>::available_funds(&id, &BOB),
+			Ok(FundsAvailability::MustLiquidate)
+		);
+
+		Tokens::mint_into(MockCurrencyId::A, &BOB, 300).unwrap();
+		assert_ok!(<Vaults as StrategicVault>::deposit(&id, &BOB, 800));
+		assert_ne!(
+			<Vaults as StrategicVault>::available_funds(&id, &BOB),
+			Ok(FundsAvailability::MustLiquidate)
+		);
+	});
+}
+
+#[test]
+fn rebalance_can_not_be_executed_by_non_manager_accounts() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50))],
+			Perquintill::from_percent(50),
+		);
+		assert_noop!(
+			Vaults::rebalance(RuntimeOrigin::signed(BOB), id),
+			Error::<Test>::AccountIsNotManager
+		);
+	});
+}
+
+#[test]
+fn on_idle_rebalances_vaults() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50))],
+			Perquintill::from_percent(50),
+		);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+
+		Vaults::on_idle(1, Weight::MAX);
+
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &BOB), 500);
+		assert!(RebalanceCursor::<Test>::get().is_none());
+	});
+}
+
+#[test]
+fn request_redemption_is_fulfilled_once_funds_return() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(80))],
+			Perquintill::from_percent(20),
+		);
+		let lp_id = <Vaults as Vault>::lp_asset_id(&id).unwrap();
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		assert_noop!(
+			Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 500),
+			Error::<Test>::NotEnoughLiquidity
+		);
+
+		assert_ok!(Vaults::request_redemption(RuntimeOrigin::signed(ALICE), id, 500));
+		assert_eq!(Tokens::balance(lp_id, &ALICE), 500);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::RedemptionRequested {
+			vault: id,
+			redemption: 0,
+			account: ALICE,
+			lp_amount: 500,
+			asset_amount: 500,
+		}));
+		// the remaining LP tokens keep their value
+		assert_eq!(<Vaults as Vault>::lp_share_value(&id, 500), Ok(500));
+		// the vault only holds 200, so BOB has to return its funds
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::StrategyMustLiquidate {
+			vault: id,
+			strategy: BOB,
+			excess: 800,
+		}));
+		assert_eq!(
+			<Vaults as StrategicVault>::available_funds(&id, &BOB),
+			Ok(FundsAvailability::MustLiquidate)
+		);
+		assert_noop!(
+			Vaults::claim_redemption(RuntimeOrigin::signed(ALICE), id, 0),
+			Error::<Test>::RedemptionNotFulfilled
+		);
+
+		assert_ok!(<Vaults as StrategicVault>::deposit(&id, &BOB, 800));
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::RedemptionFulfilled {
+			vault: id,
+			redemption: 0,
+			account: ALICE,
+			asset_amount: 500,
+		}));
+		assert_noop!(
+			Vaults::claim_redemption(RuntimeOrigin::signed(BOB), id, 0),
+			Error::<Test>::NotRedemptionOwner
+		);
+		assert_ok!(Vaults::claim_redemption(RuntimeOrigin::signed(ALICE), id, 0));
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &ALICE), 500);
+		System::assert_last_event(RuntimeEvent::Vaults(crate::Event::RedemptionClaimed {
+			vault: id,
+			redemption: 0,
+			account: ALICE,
+			asset_amount: 500,
+		}));
+		assert_eq!(Vaults::redemptions(id, 0), None);
+		assert_eq!(Vaults::redemption_queue(id).owed, 0);
+		assert_noop!(
+			Vaults::claim_redemption(RuntimeOrigin::signed(ALICE), id, 0),
+			Error::<Test>::RedemptionDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn redemptions_are_fulfilled_in_order() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(80))],
+			Perquintill::from_percent(20),
+		);
+		for account in [ALICE, CHARLIE] {
+			Tokens::mint_into(MockCurrencyId::A, &account, 1000).unwrap();
+			assert_ok!(Vaults::deposit(RuntimeOrigin::signed(account), id, 1000));
+		}
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 400);
+
+		assert_ok!(Vaults::request_redemption(RuntimeOrigin::signed(ALICE), id, 600));
+		// the vault could pay CHARLIE, but ALICE requested first
+		assert_ok!(Vaults::request_redemption(RuntimeOrigin::signed(CHARLIE), id, 300));
+		assert!(!Vaults::redemptions(id, 0).unwrap().fulfilled);
+		assert!(!Vaults::redemptions(id, 1).unwrap().fulfilled);
+
+		assert_ok!(<Vaults as StrategicVault>::deposit(&id, &BOB, 200));
+		assert!(Vaults::redemptions(id, 0).unwrap().fulfilled);
+		assert!(!Vaults::redemptions(id, 1).unwrap().fulfilled);
+
+		assert_ok!(<Vaults as StrategicVault>::deposit(&id, &BOB, 300));
+		assert!(Vaults::redemptions(id, 1).unwrap().fulfilled);
+
+		// funds set aside for redemptions can not be withdrawn
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &Vaults::account_id(&id)), 900);
+		assert_noop!(
+			Vaults::withdraw(RuntimeOrigin::signed(ALICE), id, 100),
+			Error::<Test>::NotEnoughLiquidity
+		);
+	});
+}
+
+#[test]
+fn redemptions_over_the_limit_are_fulfilled_on_idle() {
+	ExtBuilder::default().build().execute_with(|| {
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(80))],
+			Perquintill::from_percent(20),
+		);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		for _ in 0..3 {
+			assert_ok!(Vaults::request_redemption(RuntimeOrigin::signed(ALICE), id, 300));
+		}
+
+		assert_ok!(<Vaults as StrategicVault>::deposit(&id, &BOB, 800));
+		assert_eq!(Vaults::redemption_queue(id).head, u64::from(MaxRedemptionsPerCall::get()));
+		assert!(Vaults::redemptions(id, 1).unwrap().fulfilled);
+		assert!(!Vaults::redemptions(id, 2).unwrap().fulfilled);
+
+		Vaults::on_idle(1, Weight::MAX);
+		assert!(Vaults::redemptions(id, 2).unwrap().fulfilled);
+	});
+}
+
+#[test]
+fn fulfilled_redemptions_can_be_claimed_from_stopped_vaults() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (id, _) = create_vault(ALICE, MockCurrencyId::A);
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_ok!(Vaults::request_redemption(RuntimeOrigin::signed(ALICE), id, 500));
+		assert!(Vaults::redemptions(id, 0).unwrap().fulfilled);
+
+		assert_ok!(Vaults::emergency_shutdown(RuntimeOrigin::root(), id));
+		assert_ok!(Vaults::claim_redemption(RuntimeOrigin::signed(ALICE), id, 0));
+		assert_eq!(Tokens::balance(MockCurrencyId::A, &ALICE), 500);
+	});
+}
+
+#[test]
+fn management_fee_accrues_over_time() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50))],
+			Perquintill::from_percent(50),
+		);
+		let lp_id = <Vaults as Vault>::lp_asset_id(&id).unwrap();
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		let fees = FeeConfig {
+			recipient: CHARLIE,
+			management: Perquintill::from_percent(10),
+			performance: Perquintill::from_percent(0),
+		};
+		assert_ok!(Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, Some(fees)));
+
+		// a year after the fees applied, 10% of the assets are owed to the recipient
+		let charged_at = 1 + FeeNoticePeriod::get() + BlocksPerYear::get();
+		System::set_block_number(charged_at);
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+
+		assert_eq!(Tokens::balance(lp_id, &CHARLIE), 111);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::ManagementFeeCharged {
+			vault: id,
+			recipient: CHARLIE,
+			lp_amount: 111,
+		}));
+		assert_eq!(<Vaults as Vault>::lp_share_value(&id, 111), Ok(99));
+		assert_eq!(Vaults::fees(id).unwrap().accrued_at, charged_at);
+	});
+}
+
+#[test]
+fn performance_fee_is_charged_above_high_water_mark() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let id = create_vault_with_strategies(
+			&[(BOB, Perquintill::from_percent(50))],
+			Perquintill::from_percent(50),
+		);
+		let lp_id = <Vaults as Vault>::lp_asset_id(&id).unwrap();
+		let fees = FeeConfig {
+			recipient: CHARLIE,
+			management: Perquintill::from_percent(0),
+			performance: Perquintill::from_percent(20),
+		};
+		assert_ok!(Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, Some(fees)));
+		System::set_block_number(1 + FeeNoticePeriod::get());
+		Tokens::mint_into(MockCurrencyId::A, &ALICE, 1000).unwrap();
+		assert_ok!(Vaults::deposit(RuntimeOrigin::signed(ALICE), id, 1000));
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+
+		// a profit of 500, of which 100 is charged
+		assert_ok!(<Vaults as ReportableStrategicVault>::update_strategy_report(&id, &BOB, &1000));
+		assert_eq!(Tokens::balance(lp_id, &CHARLIE), 71);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::PerformanceFeeCharged {
+			vault: id,
+			recipient: CHARLIE,
+			lp_amount: 71,
+		}));
+
+		// recovering from a loss does not charge the fee again
+		assert_ok!(<Vaults as ReportableStrategicVault>::update_strategy_report(&id, &BOB, &800));
+		assert_ok!(<Vaults as ReportableStrategicVault>::update_strategy_report(&id, &BOB, &1000));
+		assert_eq!(Tokens::balance(lp_id, &CHARLIE), 71);
+	});
+}
+
+#[test]
+fn fees_apply_after_notice_period() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let (id, _) = create_vault(ALICE, MockCurrencyId::A);
+		let fees = FeeConfig {
+			recipient: CHARLIE,
+			management: Perquintill::from_percent(2),
+			performance: Perquintill::from_percent(20),
+		};
+		assert_ok!(Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, Some(fees)));
+		let effective_at = 1 + FeeNoticePeriod::get();
+		System::assert_last_event(RuntimeEvent::Vaults(crate::Event::FeesScheduled {
+			vault: id,
+			fees: Some(fees),
+			effective_at,
+		}));
+
+		// depositors can still leave before the fees are charged
+		System::set_block_number(effective_at - 1);
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		assert!(Vaults::fees(id).is_none());
+
+		System::set_block_number(effective_at + 5);
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		assert!(Vaults::pending_fees(id).is_none());
+		let applied = Vaults::fees(id).unwrap();
+		assert_eq!(applied.config, fees);
+		assert_eq!(applied.accrued_at, effective_at + 5);
+		System::assert_has_event(RuntimeEvent::Vaults(crate::Event::FeesUpdated {
+			vault: id,
+			fees: Some(fees),
+		}));
+
+		// stopping the fees also waits for the notice period
+		assert_ok!(Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, None));
+		assert!(Vaults::fees(id).is_some());
+		System::set_block_number(effective_at + 5 + FeeNoticePeriod::get());
+		assert_ok!(Vaults::rebalance(RuntimeOrigin::signed(ALICE), id));
+		assert!(Vaults::fees(id).is_none());
+	});
+}
+
+#[test]
+fn fees_above_maximum_are_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (id, _) = create_vault(ALICE, MockCurrencyId::A);
+		let fees = FeeConfig {
+			recipient: BOB,
+			management: Perquintill::from_percent(11),
+			performance: Perquintill::from_percent(20),
+		};
+		assert_noop!(
+			Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, Some(fees)),
+			Error::<Test>::FeeTooHigh
+		);
+		let fees = FeeConfig {
+			recipient: BOB,
+			management: Perquintill::from_percent(2),
+			performance: Perquintill::from_percent(51),
+		};
+		assert_noop!(
+			Vaults::set_fees(RuntimeOrigin::signed(ALICE), id, Some(fees)),
+			Error::<Test>::FeeTooHigh
+		);
+	});
+}
+
+#[test]
+fn set_fees_can_not_be_executed_by_non_manager_accounts() {
+	ExtBuilder::default().build().execute_with(|| {
+		let (id, _) = create_vault(ALICE, MockCurrencyId::A);
+		let fees = FeeConfig {
+			recipient: BOB,
+			management: Perquintill::from_percent(2),
+			performance: Perquintill::from_percent(20),
+		};
+		assert_noop!(
+			Vaults::set_fees(RuntimeOrigin::signed(BOB), id, Some(fees)),
+			Error::<Test>::AccountIsNotManager
+		);
+	});
+}
@@ -18,6 +18,11 @@ pub trait WeightInfo {
 	fn add_surcharge() -> Weight;
 	fn claim_surcharge() -> Weight;
 	fn delete_tombstoned() -> Weight;
+	fn rebalance(s: u32) -> Weight;
+	fn request_redemption(s: u32) -> Weight;
+	fn claim_redemption() -> Weight;
+	fn set_fees() -> Weight;
+	fn fulfil_redemptions(r: u32) -> Weight;
 }
 
 pub struct SubstrateWeight<T>(PhantomData<T>);
@@ -86,6 +91,62 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault CapitalStructure (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	fn rebalance(s: u32) -> Weight {
+		Weight::from_ref_time(48_316_000_u64)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_ref_time(61_474_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Tokens Accounts (r:2 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: Vault CapitalStructure (r:1 w:1)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	// Storage: Vault Redemptions (r:1 w:1)
+	fn request_redemption(s: u32) -> Weight {
+		Weight::from_ref_time(65_604_000_u64)
+			// Standard Error: 11_000
+			.saturating_add(Weight::from_ref_time(18_932_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault Redemptions (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	fn claim_redemption() -> Weight {
+		Weight::from_ref_time(27_744_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault Fees (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	fn set_fees() -> Weight {
+		Weight::from_ref_time(31_562_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	// Storage: Vault Redemptions (r:1 w:1)
+	fn fulfil_redemptions(r: u32) -> Weight {
+		Weight::from_ref_time(9_814_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(11_207_000_u64).saturating_mul(r as u64))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r as u64)))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(r as u64)))
+	}
 }
 
 impl WeightInfo for () {
@@ -153,4 +214,60 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault CapitalStructure (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	fn rebalance(s: u32) -> Weight {
+		Weight::from_ref_time(48_316_000_u64)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_ref_time(61_474_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Tokens Accounts (r:2 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:1)
+	// Storage: Vault CapitalStructure (r:1 w:1)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	// Storage: Vault Redemptions (r:1 w:1)
+	fn request_redemption(s: u32) -> Weight {
+		Weight::from_ref_time(65_604_000_u64)
+			// Standard Error: 11_000
+			.saturating_add(Weight::from_ref_time(18_932_000_u64).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s as u64)))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault Redemptions (r:1 w:1)
+	// Storage: Tokens Accounts (r:2 w:2)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	fn claim_redemption() -> Weight {
+		Weight::from_ref_time(27_744_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	// Storage: Vault Vaults (r:1 w:0)
+	// Storage: Vault Fees (r:1 w:1)
+	// Storage: Tokens TotalIssuance (r:1 w:0)
+	// Storage: Tokens Accounts (r:1 w:0)
+	fn set_fees() -> Weight {
+		Weight::from_ref_time(31_562_000_u64)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Storage: Tokens Accounts (r:1 w:0)
+	// Storage: Vault RedemptionQueues (r:1 w:1)
+	// Storage: Vault Redemptions (r:1 w:1)
+	fn fulfil_redemptions(r: u32) -> Weight {
+		Weight::from_ref_time(9_814_000_u64)
+			// Standard Error: 4_000
+			.saturating_add(Weight::from_ref_time(11_207_000_u64).saturating_mul(r as u64))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(r as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(r as u64)))
+	}
 }
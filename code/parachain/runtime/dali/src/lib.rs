@@ -60,7 +60,9 @@ use composable_support::rpc_helpers::SafeRpcWrapper;
 use composable_traits::{
 	assets::Asset,
 	defi::Rate,
-	dex::{Amm, PriceAggregate},
+	dex::{Amm, AssetAmount, PriceAggregate, RouteQuote},
+	lending::{AccountPosition, MarketSolvency, MarketStats},
+	time::ONE_DAY,
 	xcm::assets::RemoteAssetRegistryInspect,
 };
 use cosmwasm::instrument::CostRules;
@@ -70,8 +72,8 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	generic, impl_opaque_keys,
 	traits::{
-		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Bounded, Convert,
-		ConvertInto, Zero,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Convert, ConvertInto,
+		Zero,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, DispatchError, Either,
@@ -489,12 +491,19 @@ parameter_types! {
 	pub MinStake: Balance = 1000 * CurrencyId::unit::<Balance>();
 	pub const MaxAnswerBound: u32 = 25;
 	pub const MaxAssetsCount: u32 = 100_000;
+	pub const OracleMaxSigners: u32 = 100;
 	pub const MaxHistory: u32 = 20;
 	pub const MaxPrePrices: u32 = 40;
 	pub const TwapWindow: u16 = 3;
 	// cspell:disable-next
 	pub const OraclePalletId: PalletId = PalletId(*b"plt_orac");
 	pub const MsPerBlock: u64 = MILLISECS_PER_BLOCK as u64;
+	pub OracleSlashLadder: Vec<sp_runtime::Percent> = [0, 5, 20, 50]
+		.into_iter()
+		.map(sp_runtime::Percent::from_percent)
+		.collect();
+	pub const OracleSlashGracePeriod: BlockNumber = DAYS;
+	pub const OracleNormalizedAssetId: CurrencyId = CurrencyId::USDT;
 }
 
 impl oracle::Config for Runtime {
@@ -511,13 +520,19 @@ impl oracle::Config for Runtime {
 	type RewardOrigin = EnsureRootOrHalfNativeCouncil;
 	type MaxAnswerBound = MaxAnswerBound;
 	type MaxAssetsCount = MaxAssetsCount;
+	type MaxSigners = OracleMaxSigners;
 	type TreasuryAccount = TreasuryAccount;
+	type SlashLadder = OracleSlashLadder;
+	type SlashGracePeriod = OracleSlashGracePeriod;
 	type MaxHistory = MaxHistory;
 	type TwapWindow = TwapWindow;
 	type MaxPrePrices = MaxPrePrices;
 	type MsPerBlock = MsPerBlock;
 	type WeightInfo = weights::oracle::WeightInfo<Runtime>;
 	type LocalAssets = CurrencyFactory;
+	type NormalizedAssetId = OracleNormalizedAssetId;
+	type LiquidityPools = Pablo;
+	type StakingShares = StakingRewards;
 	type Moment = Moment;
 	type Time = Timestamp;
 	type PalletId = OraclePalletId;
@@ -747,6 +762,7 @@ impl pallet_fnft::Config for Runtime {
 	type ProxyType = ProxyType;
 	type AccountProxy = AccountProxyWrapperInstance;
 	type ProxyTypeSelector = FnftAccountProxyType;
+	type OnTransfer = StakingRewards;
 	type PalletId = FnftPalletId;
 	type WeightInfo = weights::fnft::WeightInfo<Runtime>;
 }
@@ -776,6 +792,12 @@ parameter_types! {
 	pub const VaultMinimumWithdrawal: Balance = 10_000;
 	pub const VaultPalletId: PalletId = PalletId(*b"cubic___");
 	pub const TombstoneDuration: BlockNumber = DAYS * 7;
+	pub const VaultRebalanceThreshold: Perquintill = Perquintill::from_percent(10);
+	pub const VaultBlocksPerYear: BlockNumber = DAYS * 365;
+	pub const VaultMaxManagementFee: Perquintill = Perquintill::from_percent(5);
+	pub const VaultMaxPerformanceFee: Perquintill = Perquintill::from_percent(30);
+	pub const VaultFeeNoticePeriod: BlockNumber = DAYS * 7;
+	pub const VaultMaxRedemptionsPerCall: u32 = 16;
 }
 
 impl vault::Config for Runtime {
@@ -790,6 +812,12 @@ impl vault::Config for Runtime {
 	type CreationDeposit = CreationDeposit;
 	type ExistentialDeposit = VaultExistentialDeposit;
 	type RentPerBlock = RentPerBlock;
+	type RebalanceThreshold = VaultRebalanceThreshold;
+	type BlocksPerYear = VaultBlocksPerYear;
+	type MaxManagementFee = VaultMaxManagementFee;
+	type MaxPerformanceFee = VaultMaxPerformanceFee;
+	type FeeNoticePeriod = VaultFeeNoticePeriod;
+	type MaxRedemptionsPerCall = VaultMaxRedemptionsPerCall;
 	type NativeCurrency = Balances;
 	type MinimumDeposit = VaultMinimumDeposit;
 	type MinimumWithdrawal = VaultMinimumWithdrawal;
@@ -864,6 +892,7 @@ parameter_types! {
 	pub const MaxStakingDurationPresets : u32 = 10;
 	pub const MaxRewardConfigsPerPool : u32 = 10;
 	pub const StakingRewardsLockId: LockIdentifier = *b"stk_lock";
+	pub const StakingRewardsVotingPowerEpoch: u64 = ONE_DAY;
 }
 
 impl pallet_staking_rewards::Config for Runtime {
@@ -885,6 +914,8 @@ impl pallet_staking_rewards::Config for Runtime {
 	type LockId = StakingRewardsLockId;
 	type TreasuryAccount = TreasuryAccount;
 	type ExistentialDeposits = MultiExistentialDeposits;
+	type VotingPowerEpoch = StakingRewardsVotingPowerEpoch;
+	type MaxVotingLocks = ConstU32<8>;
 }
 
 /// The calls we permit to be executed by extrinsics
@@ -969,12 +1000,17 @@ impl dutch_auction::Config for Runtime {
 	type XcmOrigin = RuntimeOrigin;
 	type AdminOrigin = EnsureRootOrHalfNativeCouncil;
 	type XcmSender = XcmRouter;
+	type LiquidationCallback = Lending;
 }
 
 parameter_types! {
 	pub const MosaicId: PalletId = PalletId(*b"plmosaic");
 	pub const MinimumTTL: BlockNumber = 10;
 	pub const MinimumTimeLockPeriod: BlockNumber = 20;
+	pub MosaicRelayerBond: Balance = 10_000 * CurrencyId::unit::<Balance>();
+	pub MosaicBondAssetId: CurrencyId = CurrencyId::PICA;
+	pub const MosaicMaxRelayers: u32 = 16;
+	pub const MosaicAttestationTTL: BlockNumber = DAYS * 7;
 }
 
 impl mosaic::Config for Runtime {
@@ -987,6 +1023,10 @@ impl mosaic::Config for Runtime {
 	type NetworkId = u32;
 	type RemoteAssetId = MosaicRemoteAssetId;
 	type ControlOrigin = EnsureRootOrHalfNativeCouncil;
+	type RelayerBond = MosaicRelayerBond;
+	type BondAssetId = MosaicBondAssetId;
+	type MaxRelayers = MosaicMaxRelayers;
+	type AttestationTTL = MosaicAttestationTTL;
 	type WeightInfo = weights::mosaic::WeightInfo<Runtime>;
 	type RemoteAmmId = u128; // TODO: Swap to U256?
 	type AmmMinimumAmountOut = u128;
@@ -1005,11 +1045,14 @@ impl liquidations::Config for Runtime {
 	type DutchAuction = DutchAuction;
 	type LiquidationStrategyId = LiquidationStrategyId;
 	type OrderId = OrderId;
+	type Dex = DexRouter;
+	type LiquidationCallback = Lending;
 	type WeightInfo = weights::liquidations::WeightInfo<Runtime>;
 	type PalletId = LiquidationsPalletId;
 	type CanModifyStrategies = EnsureRootOrHalfNativeCouncil;
 	type XcmSender = XcmRouter;
 	type MaxLiquidationStrategiesAmount = ConstU32<10>;
+	type InstantSaleSearchSteps = ConstU32<12>;
 }
 
 parameter_types! {
@@ -1017,6 +1060,8 @@ parameter_types! {
 	pub LendingPalletId: PalletId = PalletId(*b"liqiudat");
 	pub OracleMarketCreationStake: Balance = 300;
 	pub const MaxLiquidationBatchSize: u32 = 1000;
+	/// 0.09% of the amount borrowed.
+	pub LendingFlashLoanFee: Permill = Permill::from_parts(900);
 }
 
 impl lending::Config for Runtime {
@@ -1038,11 +1083,21 @@ impl lending::Config for Runtime {
 	type NativeCurrency = Balances;
 	type MaxLiquidationBatchSize = MaxLiquidationBatchSize;
 	type WeightToFee = WeightToFeeConverter;
+	type RuntimeCall = RuntimeCall;
+	type FlashLoanFee = LendingFlashLoanFee;
+	type MaxPortfolioMarkets = MaxLendingCount;
 }
 
 parameter_types! {
   pub PabloId: PalletId = PalletId(*b"pall_pab");
   pub TWAPInterval: u64 = (MILLISECS_PER_BLOCK as u64) * 10;
+  pub const PabloMaxAssetsPerPool: u32 = 5;
+  pub const PabloMinSaleDuration: Moment = 60 * 60 * 1000;
+  pub const PabloMaxSaleDuration: Moment = 30 * 24 * 60 * 60 * 1000;
+  pub PabloMaxInitialWeight: Permill = Permill::from_percent(95);
+  pub PabloMinFinalWeight: Permill = Permill::from_percent(5);
+  pub const PabloMaxSwapSteps: u32 = 64;
+  pub const PabloMaxLimitOrdersPerPool: u32 = 256;
 }
 
 impl pablo::Config for Runtime {
@@ -1060,12 +1115,24 @@ impl pablo::Config for Runtime {
 	type EnableTwapOrigin = EnsureRootOrHalfNativeCouncil;
 	type TWAPInterval = TWAPInterval;
 	type Time = Timestamp;
+	type MaxAssetsPerPool = PabloMaxAssetsPerPool;
+	type MinSaleDuration = PabloMinSaleDuration;
+	type MaxSaleDuration = PabloMaxSaleDuration;
+	type MaxInitialWeight = PabloMaxInitialWeight;
+	type MinFinalWeight = PabloMinFinalWeight;
+	type FinancialNft = Fnft;
+	type FinancialNftInstanceId = FinancialNftInstanceId;
+	type MaxSwapSteps = PabloMaxSwapSteps;
+	type MaxLimitOrdersPerPool = PabloMaxLimitOrdersPerPool;
 	type WeightInfo = weights::pablo::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	#[derive(TypeInfo, codec::MaxEncodedLen, codec::Encode)]
 	pub const MaxHopsCount: u32 = 4;
+	#[derive(TypeInfo, codec::MaxEncodedLen, codec::Encode)]
+	pub const MaxSplitsCount: u32 = 4;
+	pub const DexRouterMaxPoolsInGraph: u32 = 32;
 	pub DexRouterPalletID: PalletId = PalletId(*b"dex_rout");
 }
 
@@ -1074,6 +1141,8 @@ impl dex_router::Config for Runtime {
 	type AssetId = CurrencyId;
 	type Balance = Balance;
 	type MaxHopsInRoute = MaxHopsCount;
+	type MaxPoolsInGraph = DexRouterMaxPoolsInGraph;
+	type MaxSplitsInRoute = MaxSplitsCount;
 	type PoolId = PoolId;
 	type Pablo = Pablo;
 	type PalletId = DexRouterPalletID;
@@ -1475,14 +1544,44 @@ mod benches {
 }
 
 impl_runtime_apis! {
-	impl lending_runtime_api::LendingRuntimeApi<Block, MarketId> for Runtime {
-		fn current_interest_rate(_market_id: MarketId) -> SafeRpcWrapper<Rate> {
-			SafeRpcWrapper(
-				// TODO: Actually implement this
-				Rate::max_value()
-				// lending::BorrowIndex::<Runtime>::get(market_id)
-				// 	.unwrap_or_else(Rate::zero)
-			)
+	impl lending_runtime_api::LendingRuntimeApi<Block, AccountId, MarketId, Balance> for Runtime {
+		fn current_interest_rate(market_id: MarketId) -> SafeRpcWrapper<Rate> {
+			SafeRpcWrapper(Lending::borrow_rate(&market_id).unwrap_or_else(|_| Rate::zero()))
+		}
+
+		fn market_solvency(market_id: MarketId) -> MarketSolvency<SafeRpcWrapper<Balance>> {
+			let MarketSolvency { bad_debt, reserves } = Lending::market_solvency(&market_id);
+			MarketSolvency { bad_debt: SafeRpcWrapper(bad_debt), reserves: SafeRpcWrapper(reserves) }
+		}
+
+		fn account_position(
+			market_id: MarketId,
+			account: SafeRpcWrapper<AccountId>,
+		) -> Option<AccountPosition<SafeRpcWrapper<Balance>>> {
+			let AccountPosition { collateral, debt_with_interest, borrow_limit } =
+				Lending::account_position(&market_id, &account.0).ok()?;
+			Some(AccountPosition {
+				collateral: SafeRpcWrapper(collateral),
+				debt_with_interest: SafeRpcWrapper(debt_with_interest),
+				borrow_limit: SafeRpcWrapper(borrow_limit),
+			})
+		}
+
+		fn market_stats(
+			market_id: MarketId,
+		) -> Option<MarketStats<SafeRpcWrapper<Balance>, SafeRpcWrapper<Rate>>> {
+			let MarketStats { utilization, borrow_apy, supply_apy, total_reserves } =
+				Lending::market_stats(&market_id).ok()?;
+			Some(MarketStats {
+				utilization,
+				borrow_apy: SafeRpcWrapper(borrow_apy),
+				supply_apy: SafeRpcWrapper(supply_apy),
+				total_reserves: SafeRpcWrapper(total_reserves),
+			})
+		}
+
+		fn accounts_to_warn(market_id: MarketId) -> Vec<SafeRpcWrapper<AccountId>> {
+			Lending::accounts_to_warn(&market_id).into_iter().map(SafeRpcWrapper).collect()
 		}
 	}
 
@@ -1598,6 +1697,25 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl dex_router_runtime_api::DexRouterRuntimeApi<Block, PoolId, CurrencyId, Balance> for Runtime {
+		fn swap_route(
+			in_asset_id: CurrencyId,
+			amount: Balance,
+			out_asset_id: CurrencyId,
+		) -> Option<RouteQuote<SafeRpcWrapper<PoolId>, SafeRpcWrapper<CurrencyId>, SafeRpcWrapper<Balance>>> {
+			DexRouter::swap_route(AssetAmount::new(in_asset_id, amount), out_asset_id)
+				.map(|quote| RouteQuote {
+					hops: quote
+						.hops
+						.into_iter()
+						.map(|(pool_id, asset_id)| (SafeRpcWrapper(pool_id), SafeRpcWrapper(asset_id)))
+						.collect(),
+					amount: SafeRpcWrapper(quote.amount),
+				})
+				.ok()
+		}
+	}
+
 	impl cosmwasm_runtime_api::CosmwasmRuntimeApi<Block, AccountId, CurrencyId, Balance, Vec<u8>> for Runtime {
 		fn query(
 			contract: AccountId,
@@ -2,7 +2,16 @@ use crate::{prelude::*, *};
 
 use pablo_picasso_init_pools::PabloPicassoInitialPoolsMigration;
 
-pub type Migrations = (PabloPicassoInitialPoolsMigration, SchedulerMigrationV3);
+pub type Migrations = (
+	PabloPicassoInitialPoolsMigration,
+	SchedulerMigrationV3,
+	lending::migration::v1::MigrateToV1<Runtime>,
+	pallet_staking_rewards::migration::v1::MigrateToV1<Runtime>,
+	pallet_staking_rewards::migration::v2::MigrateToV2<Runtime>,
+	oracle::migration::v1::MigrateToV1<Runtime>,
+	dutch_auction::migration::v1::MigrateToV1<Runtime>,
+	vault::migration::v1::MigrateToV1<Runtime>,
+);
 
 // Migration for scheduler pallet to move from a plain Call to a CallOrHash.
 pub struct SchedulerMigrationV3;
@@ -25,7 +34,7 @@ pub mod pablo_picasso_init_pools {
 	#[derive(Clone)]
 	struct PoolCreationInput {
 		/// Initial Configuration for the Pool
-		init_config: PoolInitConfiguration<AccountId, CurrencyId>,
+		init_config: PoolInitConfiguration<AccountId, CurrencyId, PabloMaxAssetsPerPool, Moment>,
 		/// LP Token for pool to mint
 		lp_token: CurrencyId,
 	}
@@ -74,7 +83,7 @@ pub mod pablo_picasso_init_pools {
 		second_asset_id: CurrencyId,
 		first_asset_weight: Permill,
 		fee: Permill,
-	) -> PoolInitConfiguration<AccountId, CurrencyId> {
+	) -> PoolInitConfiguration<AccountId, CurrencyId, PabloMaxAssetsPerPool, Moment> {
 		let owner = AccountId::from([0; 32]);
 
 		let mut assets_weights = BoundedBTreeMap::new();
@@ -86,11 +95,7 @@ pub mod pablo_picasso_init_pools {
 			.try_insert(second_asset_id, first_asset_weight.left_from_one())
 			.expect("Map is within bounds; QED");
 
-		PoolInitConfiguration::<AccountId, CurrencyId>::DualAssetConstantProduct {
-			owner,
-			assets_weights,
-			fee,
-		}
+		PoolInitConfiguration::DualAssetConstantProduct { owner, assets_weights, fee }
 	}
 
 	impl OnRuntimeUpgrade for PabloPicassoInitialPoolsMigration {
@@ -204,6 +209,11 @@ pub mod pablo_picasso_init_pools {
 								Permill::from_rational::<u32>(3, 1000)
 							);
 						},
+						PoolConfiguration::StableSwap(_) |
+						PoolConfiguration::WeightedProduct(_) |
+						PoolConfiguration::LiquidityBootstrapping(_) |
+						PoolConfiguration::ConcentratedLiquidity(_) =>
+							panic!("expected a dual asset constant product pool"),
 					}
 
 					match pica_usdt_pool {
@@ -214,6 +224,11 @@ pub mod pablo_picasso_init_pools {
 								Permill::from_rational::<u32>(3, 1000)
 							);
 						},
+						PoolConfiguration::StableSwap(_) |
+						PoolConfiguration::WeightedProduct(_) |
+						PoolConfiguration::LiquidityBootstrapping(_) |
+						PoolConfiguration::ConcentratedLiquidity(_) =>
+							panic!("expected a dual asset constant product pool"),
 					}
 				})
 			}
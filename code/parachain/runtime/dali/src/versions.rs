@@ -19,7 +19,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_version: 10_005,
 	impl_version: 3,
 	apis: crate::RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 0,
 };
 
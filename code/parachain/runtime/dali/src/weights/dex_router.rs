@@ -78,4 +78,35 @@ impl<T: frame_system::Config> dex_router::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(7_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn swap_best_route(p: u32, ) -> Weight {
+		Weight::from_ref_time(702_415_000_u64)
+			.saturating_add(Weight::from_ref_time(95_372_000_u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(p as u64)))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn buy_best_route(p: u32, ) -> Weight {
+		Weight::from_ref_time(711_904_000_u64)
+			.saturating_add(Weight::from_ref_time(94_880_000_u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(31_u64))
+			.saturating_add(T::DbWeight::get().reads((3_u64).saturating_mul(p as u64)))
+			.saturating_add(T::DbWeight::get().writes(17_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn swap_split(s: u32, ) -> Weight {
+		Weight::from_ref_time(41_306_000_u64)
+			.saturating_add(Weight::from_ref_time(688_540_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads((25_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((17_u64).saturating_mul(s as u64)))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn buy_split(s: u32, ) -> Weight {
+		Weight::from_ref_time(44_870_000_u64)
+			.saturating_add(Weight::from_ref_time(702_116_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((25_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((17_u64).saturating_mul(s as u64)))
+	}
 }
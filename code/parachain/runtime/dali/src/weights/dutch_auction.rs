@@ -83,4 +83,10 @@ impl<T: frame_system::Config> dutch_auction::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(6_u64))
 			.saturating_add(T::DbWeight::get().writes(5_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn check_floor() -> Weight {
+		Weight::from_ref_time(140_523_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
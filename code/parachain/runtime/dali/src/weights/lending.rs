@@ -187,4 +187,28 @@ impl<T: frame_system::Config> lending::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(5_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn flash_loan() -> Weight {
+		Weight::from_ref_time(151_902_000_u64)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn enter_portfolio() -> Weight {
+		Weight::from_ref_time(64_918_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn exit_portfolio() -> Weight {
+		Weight::from_ref_time(69_743_000_u64)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn cover_bad_debt() -> Weight {
+		Weight::from_ref_time(43_512_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 }
@@ -52,4 +52,12 @@ impl<T: frame_system::Config> liquidations::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(x as u64)))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn sell_instantly(s: u32, ) -> Weight {
+		Weight::from_ref_time(60_000_000_u64)
+			.saturating_add(Weight::from_ref_time(20_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
@@ -133,4 +133,56 @@ impl<T: frame_system::Config> mosaic::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn bond_relayer() -> Weight {
+		Weight::from_ref_time(73_418_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn add_relayer() -> Weight {
+		Weight::from_ref_time(27_904_000_u64)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn remove_relayer() -> Weight {
+		Weight::from_ref_time(69_351_000_u64)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn set_attestation_threshold() -> Weight {
+		Weight::from_ref_time(19_210_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn attest_mint() -> Weight {
+		Weight::from_ref_time(121_903_000_u64)
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn set_rate_limit() -> Weight {
+		Weight::from_ref_time(24_117_000_u64)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn set_circuit_breaker() -> Weight {
+		Weight::from_ref_time(22_683_000_u64)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn unbond_relayer() -> Weight {
+		Weight::from_ref_time(66_205_000_u64)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn expire_attestation() -> Weight {
+		Weight::from_ref_time(18_734_000_u64)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
 }
@@ -115,4 +115,29 @@ impl<T: frame_system::Config> oracle::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn add_derived_asset() -> Weight {
+		Weight::from_ref_time(24_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn remove_derived_asset() -> Weight {
+		Weight::from_ref_time(22_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn set_slashing_policy() -> Weight {
+		Weight::from_ref_time(25_000_000_u64)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// PLACEHOLDER: written by hand, not generated by the benchmark CLI. Rerun the benchmarks to replace it.
+	fn track_missed_rounds(s: u32, ) -> Weight {
+		Weight::from_ref_time(5_000_000_u64)
+			.saturating_add(Weight::from_ref_time(30_000_000_u64).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(s as u64)))
+	}
 }
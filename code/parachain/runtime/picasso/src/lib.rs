@@ -40,9 +40,9 @@ use common::{
 	governance::native::*,
 	rewards::StakingPot,
 	AccountId, AccountIndex, Address, Amount, AuraId, Balance, BlockNumber, BondOfferId,
-	ForeignAssetId, Hash, MaxStringSize, Moment, PoolId, ReservedDmpWeight, ReservedXcmpWeight,
-	Signature, AVERAGE_ON_INITIALIZE_RATIO, DAYS, HOURS, MAXIMUM_BLOCK_WEIGHT, MILLISECS_PER_BLOCK,
-	NORMAL_DISPATCH_RATIO, SLOT_DURATION,
+	FinancialNftInstanceId, ForeignAssetId, Hash, MaxStringSize, Moment, PoolId, ReservedDmpWeight,
+	ReservedXcmpWeight, Signature, AVERAGE_ON_INITIALIZE_RATIO, DAYS, HOURS, MAXIMUM_BLOCK_WEIGHT,
+	MILLISECS_PER_BLOCK, NORMAL_DISPATCH_RATIO, SLOT_DURATION,
 };
 use composable_support::rpc_helpers::SafeRpcWrapper;
 use composable_traits::{
@@ -130,7 +130,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_version: 10_005,
 	impl_version: 2,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 0,
 };
 
@@ -140,7 +140,10 @@ pub fn native_version() -> NativeVersion {
 	NativeVersion { runtime_version: VERSION, can_author_with: Default::default() }
 }
 
-use composable_traits::account_proxy::ProxyType;
+use composable_traits::{
+	account_proxy::{AccountProxyWrapper, ProxyType},
+	fnft::FnftAccountProxyType,
+};
 use orml_traits::{parameter_type_with_key, LockIdentifier};
 parameter_type_with_key! {
 	// Minimum amount an account has to hold to stay in state
@@ -253,6 +256,13 @@ impl assets_registry::Config for Runtime {
 parameter_types! {
 	pub PabloPalletId: PalletId = PalletId(*b"pal_pblo");
 	pub TWAPInterval: u64 = (MILLISECS_PER_BLOCK as u64) * 10;
+	pub const PabloMaxAssetsPerPool: u32 = 5;
+	pub const PabloMinSaleDuration: Moment = 60 * 60 * 1000;
+	pub const PabloMaxSaleDuration: Moment = 30 * 24 * 60 * 60 * 1000;
+	pub PabloMaxInitialWeight: Permill = Permill::from_percent(95);
+	pub PabloMinFinalWeight: Permill = Permill::from_percent(5);
+	pub const PabloMaxSwapSteps: u32 = 64;
+	pub const PabloMaxLimitOrdersPerPool: u32 = 256;
 }
 
 impl pablo::Config for Runtime {
@@ -269,6 +279,15 @@ impl pablo::Config for Runtime {
 	type EnableTwapOrigin = EnsureRootOrTwoThirdNativeCouncil;
 	type Time = Timestamp;
 	type TWAPInterval = TWAPInterval;
+	type MaxAssetsPerPool = PabloMaxAssetsPerPool;
+	type MinSaleDuration = PabloMinSaleDuration;
+	type MaxSaleDuration = PabloMaxSaleDuration;
+	type MaxInitialWeight = PabloMaxInitialWeight;
+	type MinFinalWeight = PabloMinFinalWeight;
+	type FinancialNft = Fnft;
+	type FinancialNftInstanceId = FinancialNftInstanceId;
+	type MaxSwapSteps = PabloMaxSwapSteps;
+	type MaxLimitOrdersPerPool = PabloMaxLimitOrdersPerPool;
 	type WeightInfo = weights::pablo::WeightInfo<Runtime>;
 }
 
@@ -728,6 +747,24 @@ impl proxy::Config for Runtime {
 	type AnnouncementDepositFactor = ProxyPrice;
 }
 
+type AccountProxyWrapperInstance = AccountProxyWrapper<Runtime>;
+parameter_types! {
+	pub const FnftPalletId: PalletId = PalletId(*b"pal_fnft");
+}
+
+impl pallet_fnft::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxProperties = ConstU32<16>;
+	type FinancialNftCollectionId = CurrencyId;
+	type FinancialNftInstanceId = FinancialNftInstanceId;
+	type ProxyType = ProxyType;
+	type AccountProxy = AccountProxyWrapperInstance;
+	type ProxyTypeSelector = FnftAccountProxyType;
+	type OnTransfer = ();
+	type PalletId = FnftPalletId;
+	type WeightInfo = weights::fnft::WeightInfo<Runtime>;
+}
+
 impl crowdloan_rewards::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Balance = Balance;
@@ -794,10 +831,25 @@ impl Contains<RuntimeCall> for BaseCallFilter {
 			matches!(
 				call,
 				RuntimeCall::Tokens(_) | RuntimeCall::Indices(_) | RuntimeCall::Treasury(_)
-			))
+			) || is_concentrated_liquidity_call(call))
 	}
 }
 
+/// Concentrated liquidity pools and their financial NFT positions are not benchmarked on Picasso
+/// yet, and are kept off until they are.
+fn is_concentrated_liquidity_call(call: &RuntimeCall) -> bool {
+	matches!(
+		call,
+		RuntimeCall::Pablo(
+			pablo::Call::create {
+				pool: pablo::PoolInitConfiguration::ConcentratedLiquidity { .. }
+			} | pablo::Call::open_position { .. } |
+				pablo::Call::close_position { .. } |
+				pablo::Call::collect_position_fees { .. }
+		) | RuntimeCall::Fnft(_)
+	)
+}
+
 impl call_filter::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type UpdateOrigin = EnsureRootOrHalfNativeTechnical;
@@ -866,6 +918,7 @@ construct_runtime!(
 		BondedFinance: bonded_finance = 58,
 		AssetsRegistry: assets_registry = 59,
 		Pablo: pablo = 60,
+		Fnft: pallet_fnft = 67,
 
 		CallFilter: call_filter = 100,
 	}
@@ -62,7 +62,7 @@ pub mod pablo_picasso_init_pools {
 	#[derive(Clone)]
 	pub struct PoolCreationInput {
 		/// Initial Configuration for the Pool
-		init_config: PoolInitConfiguration<AccountId, CurrencyId>,
+		init_config: PoolInitConfiguration<AccountId, CurrencyId, PabloMaxAssetsPerPool, Moment>,
 		/// LP Token for pool to mint
 		lp_token: CurrencyId,
 	}
@@ -111,7 +111,7 @@ pub mod pablo_picasso_init_pools {
 		second_asset_id: CurrencyId,
 		first_asset_weight: Permill,
 		fee: Permill,
-	) -> PoolInitConfiguration<AccountId, CurrencyId> {
+	) -> PoolInitConfiguration<AccountId, CurrencyId, PabloMaxAssetsPerPool, Moment> {
 		let owner = PabloPalletId::get().into_account_truncating();
 
 		let mut assets_weights = BoundedBTreeMap::new();
@@ -123,11 +123,7 @@ pub mod pablo_picasso_init_pools {
 			.try_insert(second_asset_id, first_asset_weight.left_from_one())
 			.expect("Map is within bounds; QED");
 
-		PoolInitConfiguration::<AccountId, CurrencyId>::DualAssetConstantProduct {
-			owner,
-			assets_weights,
-			fee,
-		}
+		PoolInitConfiguration::DualAssetConstantProduct { owner, assets_weights, fee }
 	}
 
 	impl OnRuntimeUpgrade for PabloPicassoInitialPoolsMigration {
@@ -273,6 +269,11 @@ mod tests {
 							Permill::from_rational::<u32>(3, 1000)
 						);
 					},
+					PoolConfiguration::StableSwap(_) |
+					PoolConfiguration::WeightedProduct(_) |
+					PoolConfiguration::LiquidityBootstrapping(_) |
+					PoolConfiguration::ConcentratedLiquidity(_) =>
+						panic!("expected a dual asset constant product pool"),
 				}
 
 				match pica_usdt_pool {
@@ -283,6 +284,11 @@ mod tests {
 							Permill::from_rational::<u32>(3, 1000)
 						);
 					},
+					PoolConfiguration::StableSwap(_) |
+					PoolConfiguration::WeightedProduct(_) |
+					PoolConfiguration::LiquidityBootstrapping(_) |
+					PoolConfiguration::ConcentratedLiquidity(_) =>
+						panic!("expected a dual asset constant product pool"),
 				}
 			})
 		}